@@ -1,4 +1,4 @@
-use std::{ops::Deref, sync::Arc};
+use std::{ops::Deref, sync::Arc, time::Instant};
 
 use anyhow::Result;
 use napi::{bindgen_prelude::External, JsFunction};
@@ -47,6 +47,10 @@ pub struct NapiWrittenEndpoint {
     pub client_paths: Vec<String>,
     pub server_paths: Vec<NapiServerPath>,
     pub config: NapiEndpointConfig,
+    /// The time to compile and write the endpoint in this call, in
+    /// milliseconds. Work that is shared with other endpoints counts for the
+    /// endpoint that computed it first.
+    pub duration_ms: u32,
 }
 
 impl From<Option<WrittenEndpoint>> for NapiWrittenEndpoint {
@@ -147,6 +151,7 @@ pub async fn endpoint_write_to_disk(
 ) -> napi::Result<TurbopackResult<NapiWrittenEndpoint>> {
     let turbo_tasks = endpoint.turbo_tasks().clone();
     let endpoint = ***endpoint;
+    let start = Instant::now();
     let (written, issues, diags) = turbo_tasks
         .run_once(async move {
             let WrittenEndpointWithIssues {
@@ -161,7 +166,10 @@ pub async fn endpoint_write_to_disk(
         .await
         .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
     Ok(TurbopackResult {
-        result: NapiWrittenEndpoint::from(written.map(|v| v.clone_value())),
+        result: NapiWrittenEndpoint {
+            duration_ms: start.elapsed().as_millis() as u32,
+            ..NapiWrittenEndpoint::from(written.map(|v| v.clone_value()))
+        },
         issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
         diagnostics: diags.iter().map(|d| NapiDiagnostic::from(d)).collect(),
     })
@@ -177,6 +185,7 @@ pub fn endpoint_server_changed_subscribe(
     let endpoint = ***endpoint;
     subscribe(
         turbo_tasks,
+        "endpoints".into(),
         func,
         move || {
             async move {
@@ -261,6 +270,7 @@ pub fn endpoint_client_changed_subscribe(
     let endpoint = ***endpoint;
     subscribe(
         turbo_tasks,
+        "endpoints".into(),
         func,
         move || {
             async move {
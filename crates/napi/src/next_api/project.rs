@@ -1,4 +1,10 @@
-use std::{path::PathBuf, sync::Arc, thread, time::Duration};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
 
 use anyhow::{anyhow, bail, Context, Result};
 use napi::{
@@ -23,16 +29,22 @@ use rand::Rng;
 use tokio::{io::AsyncWriteExt, time::Instant};
 use tracing::Instrument;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Registry};
-use turbo_tasks::{Completion, RcStr, ReadRef, TransientInstance, UpdateInfo, Vc};
+use turbo_tasks::{
+    Completion, IntoTraitRef, RcStr, ReadRef, RootTaskAdmissionMetrics, TaskPriority, TraitRef,
+    TransientInstance, UpdateInfo, Vc,
+};
 use turbo_tasks_fs::{
     util::uri_from_file, DiskFileSystem, FileContent, FileSystem, FileSystemPath,
 };
 use turbopack_core::{
+    asset::AssetContent,
     diagnostics::PlainDiagnostic,
     error::PrettyPrintError,
     issue::PlainIssue,
     source_map::{SourceMap, Token},
-    version::{PartialUpdate, TotalUpdate, Update, VersionState},
+    version::{
+        ResumedVersion, TotalUpdate, Update, VersionHistory, VersionState, VersionedContent,
+    },
     SOURCE_MAP_PREFIX,
 };
 use turbopack_ecmascript_hmr_protocol::{ClientUpdateInstruction, ResourceIdentifier};
@@ -197,6 +209,19 @@ pub struct NapiTurboEngineOptions {
     pub persistent_caching: Option<bool>,
     /// An upper bound of memory that turbopack will attempt to stay under.
     pub memory_limit: Option<f64>,
+    /// The maximum number of root tasks, e.g. HMR subscriptions, that are
+    /// recomputed concurrently. Unlimited by default.
+    pub root_task_concurrency: Option<u32>,
+    /// The maximum number of tasks that are computed concurrently. Waiting
+    /// tasks are admitted by priority, and HMR updates come first. Unlimited by
+    /// default.
+    pub task_concurrency: Option<u32>,
+    /// A cache archive, written by `projectExportCache`, that is imported when
+    /// the persistent cache doesn't exist yet.
+    pub import_cache: Option<String>,
+    /// Serve the outputs of the persistent cache immediately after a restart
+    /// while the files they depend on are checked in the background.
+    pub background_revalidation: Option<bool>,
 }
 
 impl From<NapiWatchOptions> for WatchOptions {
@@ -282,6 +307,8 @@ pub struct ProjectInstance {
     turbo_tasks: NextTurboTasks,
     container: Vc<ProjectContainer>,
     exit_receiver: tokio::sync::Mutex<Option<ExitReceiver>>,
+    /// Versions sent to HMR clients, to bring reconnecting clients up to date.
+    hmr_version_history: Arc<VersionHistory>,
 }
 
 #[napi(ts_return_type = "Promise<{ __napiType: \"Project\" }>")]
@@ -330,6 +357,19 @@ pub async fn project_new(
             tokio::task::spawn_blocking(move || drop(trace_writer_guard));
         });
 
+        #[cfg(feature = "otlp")]
+        let subscriber = {
+            use turbopack_trace_utils::otlp::{OtlpConfig, OtlpLayer};
+
+            let (otlp_layer, otlp_guard) = OtlpConfig::from_env("next-turbopack")
+                .map(OtlpLayer::new)
+                .unzip();
+            exit.on_exit(async move {
+                tokio::task::spawn_blocking(move || drop(otlp_guard));
+            });
+            subscriber.with(otlp_layer)
+        };
+
         let trace_server = std::env::var("NEXT_TURBOPACK_TRACE_SERVER").ok();
         if trace_server.is_some() {
             thread::spawn(move || {
@@ -346,11 +386,34 @@ pub async fn project_new(
         .map(|m| m as usize)
         .unwrap_or(usize::MAX);
     let persistent_caching = turbo_engine_options.persistent_caching.unwrap_or_default();
+    // With persistent caching, the content is the same after a restart, so clients can resume
+    // by replaying the updates they missed.
+    let hmr_version_history = if persistent_caching {
+        VersionHistory::persistent(PathBuf::from(&options.dist_dir).join("cache/hmr-updates"))
+    } else {
+        VersionHistory::default()
+    };
     let turbo_tasks = create_turbo_tasks(
         PathBuf::from(&options.dist_dir),
         persistent_caching,
         memory_limit,
+        turbo_engine_options.import_cache.map(PathBuf::from),
     )?;
+    turbo_tasks.set_root_task_concurrency(
+        turbo_engine_options
+            .root_task_concurrency
+            .map(|limit| limit as usize),
+    );
+    turbo_tasks.set_task_concurrency(
+        turbo_engine_options
+            .task_concurrency
+            .map(|limit| limit as usize),
+    );
+    turbo_tasks.set_background_revalidation(
+        turbo_engine_options
+            .background_revalidation
+            .unwrap_or_default(),
+    );
     if !persistent_caching {
         use std::io::Write;
         let stats_path = std::env::var_os("NEXT_TURBOPACK_TASK_STATISTICS");
@@ -394,6 +457,7 @@ pub async fn project_new(
             turbo_tasks,
             container,
             exit_receiver: tokio::sync::Mutex::new(Some(exit_receiver)),
+            hmr_version_history: Arc::new(hmr_version_history),
         },
         100,
     ))
@@ -474,6 +538,84 @@ pub async fn project_update(
     Ok(())
 }
 
+/// Invalidates all computations that depend on the invalidation key, e.g.
+/// after a CMS reports a content update. See [`turbo_tasks::depend_on_key`].
+#[napi]
+pub async fn project_invalidate_key(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    key: String,
+) {
+    project.turbo_tasks.invalidate_key(&key);
+}
+
+/// Sets (strings) and removes (`null`) env variables for the computations,
+/// e.g. after `.env` files changed, and invalidates the computations that read
+/// the changed variables. The env of the process itself is not modified.
+#[napi]
+pub fn update_process_env(env: HashMap<String, Option<String>>) {
+    turbo_tasks_env::update_process_env(
+        env.into_iter()
+            .map(|(name, value)| (name.into(), value.map(RcStr::from))),
+    );
+}
+
+#[napi(object)]
+pub struct NapiRootTaskQueue {
+    pub requester: String,
+    pub queued: u32,
+}
+
+#[napi(object)]
+pub struct NapiRootTaskAdmissionMetrics {
+    pub limit: Option<u32>,
+    pub running: u32,
+    pub queues: Vec<NapiRootTaskQueue>,
+    pub total_queued: f64,
+    pub total_wait_time_ms: f64,
+}
+
+impl From<RootTaskAdmissionMetrics> for NapiRootTaskAdmissionMetrics {
+    fn from(metrics: RootTaskAdmissionMetrics) -> Self {
+        Self {
+            limit: metrics.limit.map(|limit| limit as u32),
+            running: metrics.running as u32,
+            queues: metrics
+                .queued
+                .into_iter()
+                .map(|(requester, queued)| NapiRootTaskQueue {
+                    requester: requester.to_string(),
+                    queued: queued as u32,
+                })
+                .collect(),
+            total_queued: metrics.total_queued as f64,
+            total_wait_time_ms: metrics.total_wait_time.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// Returns the number of running root tasks and the number of root tasks that
+/// wait for admission by requester, see `rootTaskConcurrency`.
+#[napi]
+pub fn project_root_task_admission_metrics(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> NapiRootTaskAdmissionMetrics {
+    project.turbo_tasks.root_task_admission_metrics().into()
+}
+
+/// Writes the persistent cache to a portable archive at `path`, which can be
+/// imported on another machine with the `importCache` option.
+#[napi]
+pub async fn project_export_cache(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    path: String,
+) -> napi::Result<()> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    tokio::task::spawn_blocking(move || turbo_tasks.export_cache(Path::new(&path)))
+        .await
+        .context("exporting the cache panicked")?
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))
+}
+
 #[napi]
 pub async fn project_shutdown(
     #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
@@ -649,6 +791,7 @@ pub fn project_entrypoints_subscribe(
     let container = project.container;
     subscribe(
         turbo_tasks.clone(),
+        "entrypoints".into(),
         func,
         move || {
             async move {
@@ -741,13 +884,69 @@ async fn hmr_update(
 pub fn project_hmr_events(
     #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
     identifier: String,
+    // The id of the last version applied by a client that is resubscribing.
+    version: Option<String>,
+    // The client the events are for, e.g. `hmr-client-1`. The root tasks of
+    // different clients are admitted fairly when the root task concurrency is
+    // limited.
+    requester: Option<String>,
+    func: JsFunction,
+) -> napi::Result<External<RootTask>> {
+    hmr_subscription(project, identifier, version, requester, false, func)
+}
+
+/// Like [project_hmr_events], but for clients that don't load the chunk group
+/// from the dev server, e.g. React Native clients or test harnesses. Instead
+/// of asking them to restart, the current content is sent as a `sync`
+/// instruction: initially when the client doesn't know its version, and
+/// whenever the content can't be updated partially.
+#[napi(ts_return_type = "{ __napiType: \"RootTask\" }")]
+pub fn project_hmr_subscribe(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    identifier: String,
+    version: Option<String>,
+    requester: Option<String>,
+    func: JsFunction,
+) -> napi::Result<External<RootTask>> {
+    hmr_subscription(project, identifier, version, requester, true, func)
+}
+
+/// Reads the current content of the chunk group `identifier` as a string.
+async fn hmr_sync_content(project: Vc<Project>, identifier: RcStr) -> Result<RcStr> {
+    let Some(content) = *project.hmr_content(identifier.clone()).await? else {
+        bail!("HMR resource {identifier} not found");
+    };
+    let content = content.content().await?;
+    let AssetContent::File(file) = &*content else {
+        bail!("HMR resource {identifier} is not a file");
+    };
+    let file = file.await?;
+    let FileContent::Content(file) = &*file else {
+        bail!("HMR resource {identifier} not found");
+    };
+    Ok(file.content().to_str()?.into_owned().into())
+}
+
+fn hmr_subscription(
+    project: External<ProjectInstance>,
+    identifier: String,
+    version: Option<String>,
+    requester: Option<String>,
+    sync: bool,
     func: JsFunction,
 ) -> napi::Result<External<RootTask>> {
     let turbo_tasks = project.turbo_tasks.clone();
+    let requester: RcStr = requester.unwrap_or_else(|| "hmr".to_string()).into();
+    let history = project.hmr_version_history.clone();
     let project = project.container;
     let session = TransientInstance::new(());
-    subscribe(
+    // The client version is only relevant for the initial computation.
+    let client_version = Arc::new(Mutex::new(Some(version)));
+    // The id of the version the client is at, which the updates sent to it are recorded from.
+    let last_version = Arc::new(Mutex::new(RcStr::default()));
+    let root_task = subscribe(
         turbo_tasks.clone(),
+        requester,
         func,
         {
             let outer_identifier = identifier.clone();
@@ -755,10 +954,38 @@ pub fn project_hmr_events(
             move || {
                 let identifier: RcStr = outer_identifier.clone().into();
                 let session = session.clone();
+                let history = history.clone();
+                let client_version = client_version.lock().unwrap().take();
+                let last_version = last_version.clone();
                 async move {
                     let project = project.project().resolve().await?;
                     let state = project.hmr_version_state(identifier.clone(), session);
 
+                    // On the initial computation, start from the version the client is at and
+                    // let it know about its version.
+                    let mut version = None;
+                    // The updates the client missed, when its version isn't known anymore.
+                    let mut replayed = Vec::new();
+                    // Clients that sync don't have any content before the first sync.
+                    let mut needs_sync = sync && matches!(client_version, Some(None));
+                    if let Some(client_version) = client_version {
+                        let current = state.get().into_trait_ref().await?;
+                        let from = match history
+                            .resume(&identifier, current.clone(), client_version.as_deref())
+                            .await?
+                        {
+                            ResumedVersion::Version(from) => from,
+                            ResumedVersion::Replay(instructions) => {
+                                replayed = instructions;
+                                current.clone()
+                            }
+                        };
+                        let from_version = TraitRef::cell(from.clone()).id().await?.clone_value();
+                        *last_version.lock().unwrap() = from_version;
+                        state.set(from).await?;
+                        version = Some(history.insert(identifier.clone(), current).await?);
+                    }
+
                     let update = hmr_update(project, identifier.clone(), state)
                         .strongly_consistent()
                         .await?;
@@ -771,12 +998,39 @@ pub fn project_hmr_events(
                         Update::Missing | Update::None => {}
                         Update::Total(TotalUpdate { to }) => {
                             state.set(to.clone()).await?;
+                            version = Some(history.insert(identifier.clone(), to.clone()).await?);
+                            needs_sync |= sync;
                         }
-                        Update::Partial(PartialUpdate { to, .. }) => {
-                            state.set(to.clone()).await?;
+                        Update::Partial(partial) => {
+                            state.set(partial.to.clone()).await?;
+                            let from = last_version.lock().unwrap().clone();
+                            version = Some(
+                                history
+                                    .insert_update(identifier.clone(), from, partial)
+                                    .await?,
+                            );
+                            if !replayed.is_empty() {
+                                // The content changed since it was resumed.
+                                replayed.push(partial.instruction.clone());
+                            }
                         }
                     }
-                    Ok((Some(update.clone()), issues.clone(), diagnostics.clone()))
+                    if let Some(version) = &version {
+                        *last_version.lock().unwrap() = version.clone();
+                    }
+                    let content = if needs_sync {
+                        Some(hmr_sync_content(project, identifier.clone()).await?)
+                    } else {
+                        None
+                    };
+                    Ok((
+                        Some(update.clone()),
+                        issues.clone(),
+                        diagnostics.clone(),
+                        version,
+                        content,
+                        replayed,
+                    ))
                 }
                 .instrument(tracing::info_span!(
                     "HMR subscription",
@@ -785,7 +1039,7 @@ pub fn project_hmr_events(
             }
         },
         move |ctx| {
-            let (update, issues, diags) = ctx.value;
+            let (update, issues, diags, version, content, replayed) = ctx.value;
 
             let napi_issues = issues
                 .iter()
@@ -800,17 +1054,30 @@ pub fn project_hmr_events(
                 path: identifier.clone(),
                 headers: None,
             };
-            let update = match update.as_deref() {
-                None | Some(Update::Missing) | Some(Update::Total(_)) => {
+            let update = match (update.as_deref(), content.as_deref()) {
+                (_, Some(content)) => {
+                    ClientUpdateInstruction::sync(&identifier, content, &update_issues)
+                }
+                (Some(Update::None | Update::Partial(_)), None) if !replayed.is_empty() => {
+                    ClientUpdateInstruction::replay(
+                        &identifier,
+                        replayed.iter().map(|i| &**i).collect(),
+                        &update_issues,
+                    )
+                }
+                (None | Some(Update::Missing) | Some(Update::Total(_)), None) => {
                     ClientUpdateInstruction::restart(&identifier, &update_issues)
                 }
-                Some(Update::Partial(update)) => ClientUpdateInstruction::partial(
+                (Some(Update::Partial(update)), None) => ClientUpdateInstruction::partial(
                     &identifier,
                     &update.instruction,
                     &update_issues,
                 ),
-                Some(Update::None) => ClientUpdateInstruction::issues(&identifier, &update_issues),
-            };
+                (Some(Update::None), None) => {
+                    ClientUpdateInstruction::issues(&identifier, &update_issues)
+                }
+            }
+            .with_version(version.as_deref());
 
             Ok(vec![TurbopackResult {
                 result: ctx.env.to_js_value(&update)?,
@@ -818,7 +1085,11 @@ pub fn project_hmr_events(
                 diagnostics: diags.iter().map(|d| NapiDiagnostic::from(d)).collect(),
             }])
         },
-    )
+    )?;
+    // The user waits for the updates, so they are admitted before other work
+    // when the task concurrency is limited.
+    root_task.set_priority(TaskPriority::Interactive);
+    Ok(root_task)
 }
 
 #[napi(object)]
@@ -858,6 +1129,7 @@ pub fn project_hmr_identifiers_subscribe(
     let container = project.container;
     subscribe(
         turbo_tasks.clone(),
+        "hmr-identifiers".into(),
         func,
         move || async move {
             let HmrIdentifiersWithIssues {
@@ -1189,6 +1461,128 @@ pub async fn project_get_source_map(
     Ok(source_map)
 }
 
+#[napi(object)]
+pub struct NapiFeatureUsage {
+    pub app_router: bool,
+    pub server_actions: u32,
+    pub image_component: u32,
+    pub mdx: bool,
+    /// The invocation count of every feature by name, as reported in
+    /// `EVENT_BUILD_FEATURE_USAGE` telemetry events.
+    pub features: HashMap<String, u32>,
+}
+
+/// Returns the usage of Next.js features by the project, which is computed
+/// while building its endpoints.
+#[napi]
+pub async fn project_feature_usage(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> napi::Result<NapiFeatureUsage> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    let feature_usage = turbo_tasks
+        .run_once(async move {
+            let feature_usage = container.feature_usage().strongly_consistent().await?;
+            Ok(NapiFeatureUsage {
+                app_router: feature_usage.app_router,
+                server_actions: feature_usage.server_actions as u32,
+                image_component: feature_usage.image_component as u32,
+                mdx: feature_usage.mdx,
+                features: feature_usage
+                    .features
+                    .iter()
+                    .map(|(name, count)| (name.to_string(), *count as u32))
+                    .collect(),
+            })
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(feature_usage)
+}
+
+#[turbo_tasks::value(serialization = "none")]
+struct StaticExportIssues {
+    issues: Arc<Vec<ReadRef<PlainIssue>>>,
+    diagnostics: Arc<Vec<ReadRef<PlainDiagnostic>>>,
+}
+
+#[turbo_tasks::function]
+async fn export_static_site_with_issues(
+    container: Vc<ProjectContainer>,
+    out_dir: RcStr,
+) -> Result<Vc<StaticExportIssues>> {
+    let export_operation = container.export_static_site(out_dir);
+    export_operation.strongly_consistent().await?;
+    let issues = get_issues(export_operation).await?;
+    let diagnostics = get_diagnostics(export_operation).await?;
+    Ok(StaticExportIssues {
+        issues,
+        diagnostics,
+    }
+    .cell())
+}
+
+/// Writes the `out_dir` of a static export (`output: "export"`), relative to
+/// the project directory, from the pages prerendered by `next build`. Files
+/// that were not exported are deleted from it. Routes that can't be exported
+/// are reported as issues.
+#[napi]
+pub async fn project_export_static_site(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    out_dir: String,
+) -> napi::Result<TurbopackResult<()>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    let (issues, diagnostics) = turbo_tasks
+        .run_once(async move {
+            let result = export_static_site_with_issues(container, out_dir.into())
+                .strongly_consistent()
+                .await?;
+            Ok((result.issues.clone(), result.diagnostics.clone()))
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(TurbopackResult {
+        result: (),
+        issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
+        diagnostics: diagnostics
+            .iter()
+            .map(|d| NapiDiagnostic::from(d))
+            .collect(),
+    })
+}
+
+/// Whether the `images.remotePatterns` config allows optimizing the remote
+/// image at `url`. This is used by the image optimization endpoint.
+#[napi]
+pub async fn project_match_image_remote_pattern(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    url: String,
+) -> napi::Result<bool> {
+    let url = Url::parse(&url).map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    turbo_tasks
+        .run_once(async move {
+            let matches = container.image_remote_pattern_matches(
+                url.scheme().into(),
+                url.host_str().unwrap_or_default().into(),
+                url.port()
+                    .map(|port| port.to_string())
+                    .unwrap_or_default()
+                    .into(),
+                url.path().into(),
+                url.query()
+                    .map(|query| format!("?{query}"))
+                    .unwrap_or_default()
+                    .into(),
+            );
+            Ok(*matches.strongly_consistent().await?)
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))
+}
+
 /// Runs exit handlers for the project registered using the [`ExitHandler`] API.
 #[napi]
 pub async fn project_on_exit(
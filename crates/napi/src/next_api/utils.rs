@@ -1,8 +1,15 @@
 use std::{
-    collections::HashMap, env, future::Future, ops::Deref, path::PathBuf, sync::Arc, time::Duration,
+    collections::HashMap,
+    env,
+    future::Future,
+    net::ToSocketAddrs,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use napi::{
     bindgen_prelude::{External, ToNapiValue},
     threadsafe_function::{ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode},
@@ -10,7 +17,8 @@ use napi::{
 };
 use serde::Serialize;
 use turbo_tasks::{
-    trace::TraceRawVcs, ReadRef, TaskId, TryJoinIterExt, TurboTasks, UpdateInfo, Vc,
+    trace::TraceRawVcs, RcStr, ReadRef, RootTaskAdmissionMetrics, StronglyConsistentReadWatchdog,
+    TaskId, TaskPriority, TryJoinIterExt, TurboTasks, UpdateInfo, Vc,
 };
 use turbo_tasks_backend::{default_backing_storage, DefaultBackingStorage};
 use turbo_tasks_fs::FileContent;
@@ -39,15 +47,19 @@ impl NextTurboTasks {
         }
     }
 
-    pub fn spawn_root_task<T, F, Fut>(&self, functor: F) -> TaskId
+    pub fn spawn_root_task_with_requester<T, F, Fut>(&self, requester: RcStr, functor: F) -> TaskId
     where
         T: Send,
         F: Fn() -> Fut + Send + Sync + Clone + 'static,
         Fut: Future<Output = Result<Vc<T>>> + Send,
     {
         match self {
-            NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.spawn_root_task(functor),
-            NextTurboTasks::PersistentCaching(turbo_tasks) => turbo_tasks.spawn_root_task(functor),
+            NextTurboTasks::Memory(turbo_tasks) => {
+                turbo_tasks.spawn_root_task_with_requester(requester, functor)
+            }
+            NextTurboTasks::PersistentCaching(turbo_tasks) => {
+                turbo_tasks.spawn_root_task_with_requester(requester, functor)
+            }
         }
     }
 
@@ -106,6 +118,57 @@ impl NextTurboTasks {
         }
     }
 
+    pub fn invalidate_key(&self, key: &str) {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.invalidate_key(key),
+            NextTurboTasks::PersistentCaching(turbo_tasks) => turbo_tasks.invalidate_key(key),
+        }
+    }
+
+    pub fn set_root_task_concurrency(&self, limit: Option<usize>) {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.set_root_task_concurrency(limit),
+            NextTurboTasks::PersistentCaching(turbo_tasks) => {
+                turbo_tasks.set_root_task_concurrency(limit)
+            }
+        }
+    }
+
+    pub fn set_task_concurrency(&self, limit: Option<usize>) {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.set_task_concurrency(limit),
+            NextTurboTasks::PersistentCaching(turbo_tasks) => {
+                turbo_tasks.set_task_concurrency(limit)
+            }
+        }
+    }
+
+    pub fn set_task_priority(&self, task: TaskId, priority: TaskPriority) {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.set_task_priority(task, priority),
+            NextTurboTasks::PersistentCaching(turbo_tasks) => {
+                turbo_tasks.set_task_priority(task, priority)
+            }
+        }
+    }
+
+    /// See [turbo_tasks_backend::TurboTasksBackend::set_background_revalidation]. Nothing is
+    /// persisted without persistent caching, so there is nothing to revalidate then.
+    pub fn set_background_revalidation(&self, enabled: bool) {
+        if let NextTurboTasks::PersistentCaching(turbo_tasks) = self {
+            turbo_tasks.backend().set_background_revalidation(enabled);
+        }
+    }
+
+    pub fn root_task_admission_metrics(&self) -> RootTaskAdmissionMetrics {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.root_task_admission_metrics(),
+            NextTurboTasks::PersistentCaching(turbo_tasks) => {
+                turbo_tasks.root_task_admission_metrics()
+            }
+        }
+    }
+
     pub fn memory_backend(&self) -> Option<&turbo_tasks_memory::MemoryBackend> {
         match self {
             NextTurboTasks::Memory(turbo_tasks) => Some(turbo_tasks.backend()),
@@ -113,6 +176,15 @@ impl NextTurboTasks {
         }
     }
 
+    /// Writes the persistent cache to a portable archive, see
+    /// [TurboTasks::export_cache].
+    pub fn export_cache(&self, path: &Path) -> Result<()> {
+        match self {
+            NextTurboTasks::Memory(_) => bail!("exporting the cache requires persistent caching"),
+            NextTurboTasks::PersistentCaching(turbo_tasks) => turbo_tasks.export_cache(path),
+        }
+    }
+
     pub async fn stop_and_wait(&self) {
         match self {
             NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.stop_and_wait().await,
@@ -121,23 +193,42 @@ impl NextTurboTasks {
     }
 }
 
+/// Creates the turbo tasks of a project. With persistent caching, the cache
+/// archive `import_cache` is imported when the persistent cache doesn't exist
+/// yet.
 pub fn create_turbo_tasks(
     output_path: PathBuf,
     persistent_caching: bool,
     memory_limit: usize,
+    import_cache: Option<PathBuf>,
 ) -> Result<NextTurboTasks> {
+    // Reports the tasks a strongly consistent read is waiting on, when it's pending for longer
+    // than the given number of seconds.
+    let read_watchdog = env::var("NEXT_TURBOPACK_READ_WATCHDOG_SECONDS")
+        .ok()
+        .and_then(|seconds| seconds.parse().ok())
+        .map(|seconds| StronglyConsistentReadWatchdog {
+            timeout: Duration::from_secs(seconds),
+            repeat: true,
+        });
     Ok(if persistent_caching {
-        NextTurboTasks::PersistentCaching(TurboTasks::new(
-            turbo_tasks_backend::TurboTasksBackend::new(default_backing_storage(
-                &output_path.join("cache/turbopack"),
-            )?),
-        ))
+        let cache_path = output_path.join("cache/turbopack");
+        if let Some(archive) = import_cache {
+            turbo_tasks_backend::import_cache(&cache_path, &archive)?;
+        }
+        let turbo_tasks = TurboTasks::new(turbo_tasks_backend::TurboTasksBackend::new(
+            default_backing_storage(&cache_path)?,
+        ));
+        turbo_tasks.set_strongly_consistent_read_watchdog(read_watchdog);
+        NextTurboTasks::PersistentCaching(turbo_tasks)
     } else {
         let mut backend = turbo_tasks_memory::MemoryBackend::new(memory_limit);
         if env::var_os("NEXT_TURBOPACK_PRINT_TASK_INVALIDATION").is_some() {
             backend.print_task_invalidation(true);
         }
-        NextTurboTasks::Memory(TurboTasks::new(backend))
+        let turbo_tasks = TurboTasks::new(backend);
+        turbo_tasks.set_strongly_consistent_read_watchdog(read_watchdog);
+        NextTurboTasks::Memory(turbo_tasks)
     })
 }
 
@@ -184,6 +275,15 @@ pub struct RootTask {
     task_id: Option<TaskId>,
 }
 
+impl RootTask {
+    /// See [TurboTasks::set_task_priority].
+    pub fn set_priority(&self, priority: TaskPriority) {
+        if let Some(task) = self.task_id {
+            self.turbo_tasks.set_task_priority(task, priority);
+        }
+    }
+}
+
 impl Drop for RootTask {
     fn drop(&mut self) {
         // TODO stop the root task
@@ -200,6 +300,25 @@ pub fn root_task_dispose(
     Ok(())
 }
 
+/// Returns the first free port of `port..port + max_attempts` on `hostname`,
+/// so tools that start the dev servers of several projects at the same time
+/// don't need to probe ports themselves.
+#[napi]
+pub fn negotiate_dev_server_port(
+    hostname: String,
+    port: u16,
+    max_attempts: u16,
+) -> napi::Result<u16> {
+    let host = (hostname.as_str(), port)
+        .to_socket_addrs()
+        .map_err(|e| napi::Error::from_reason(format!("Invalid hostname {hostname}: {e}")))?
+        .next()
+        .ok_or_else(|| napi::Error::from_reason(format!("Invalid hostname {hostname}")))?
+        .ip();
+    turbopack_dev_server::port::negotiate_port(host, port, max_attempts)
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))
+}
+
 pub async fn get_issues<T: Send>(source: Vc<T>) -> Result<Arc<Vec<ReadRef<PlainIssue>>>> {
     let issues = source.peek_issues_with_path().await?;
     Ok(Arc::new(issues.get_plain_issues().await?))
@@ -422,14 +541,18 @@ impl<T: ToNapiValue> ToNapiValue for TurbopackResult<T> {
     }
 }
 
+/// Spawns a root task that calls `func` with the result of `handler` whenever
+/// it changes. `requester` identifies the consumer of the updates, e.g. an HMR
+/// client, for the fair admission of root tasks.
 pub fn subscribe<T: 'static + Send + Sync, F: Future<Output = Result<T>> + Send, V: ToNapiValue>(
     turbo_tasks: NextTurboTasks,
+    requester: RcStr,
     func: JsFunction,
     handler: impl 'static + Sync + Send + Clone + Fn() -> F,
     mapper: impl 'static + Sync + Send + FnMut(ThreadSafeCallContext<T>) -> napi::Result<Vec<V>>,
 ) -> napi::Result<External<RootTask>> {
     let func: ThreadsafeFunction<T> = func.create_threadsafe_function(0, mapper)?;
-    let task_id = turbo_tasks.spawn_root_task(move || {
+    let task_id = turbo_tasks.spawn_root_task_with_requester(requester, move || {
         let handler = handler.clone();
         let func = func.clone();
         Box::pin(async move {
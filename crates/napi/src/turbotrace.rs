@@ -17,7 +17,7 @@ pub fn create_turbo_tasks(
 ) -> External<NextTurboTasks> {
     let limit = memory_limit.map(|u| u as usize).unwrap_or(usize::MAX);
     let turbo_tasks =
-        utils::create_turbo_tasks(PathBuf::from(&output_path), persistent_caching, limit)
+        utils::create_turbo_tasks(PathBuf::from(&output_path), persistent_caching, limit, None)
             .expect("Failed to create TurboTasks");
     External::new_with_size_hint(turbo_tasks, limit)
 }
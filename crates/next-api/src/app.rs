@@ -1,4 +1,4 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use next_core::{
     all_assets_from_entries,
     app_segment_config::NextSegmentConfig,
@@ -19,7 +19,8 @@ use next_core::{
     },
     next_client_reference::{
         client_reference_graph, find_server_entries, ClientReferenceGraphResult,
-        NextEcmascriptClientReferenceTransition, ServerEntries, VisitedClientReferenceGraphNodes,
+        ClientReferenceType, NextEcmascriptClientReferenceTransition, ServerEntries,
+        VisitedClientReferenceGraphNodes,
     },
     next_config::NextConfig,
     next_dynamic::NextDynamicTransition,
@@ -52,7 +53,7 @@ use turbopack::{
 use turbopack_core::{
     asset::AssetContent,
     chunk::{
-        availability_info::AvailabilityInfo, ChunkingContext, ChunkingContextExt,
+        availability_info::AvailabilityInfo, ChunkableModule, ChunkingContext, ChunkingContextExt,
         EntryChunkGroupResult, EvaluatableAsset, EvaluatableAssets,
     },
     file_source::FileSource,
@@ -64,7 +65,11 @@ use turbopack_core::{
     source::Source,
     virtual_output::VirtualOutputAsset,
 };
-use turbopack_ecmascript::resolve::cjs_resolve;
+use turbopack_ecmascript::{
+    duplicate_packages::{find_duplicate_packages, DuplicatePackages},
+    polyfills::{polyfills_entry_module, required_polyfills, PolyfillableApis},
+    resolve::cjs_resolve,
+};
 
 use crate::{
     dynamic_imports::{
@@ -73,12 +78,13 @@ use crate::{
     },
     font::create_font_manifest,
     loadable_manifest::create_react_loadable_manifest,
+    nft_json::with_nft_json,
     paths::{
         all_paths_in_root, all_server_paths, get_js_paths_from_root, get_paths_from_root,
         get_wasm_paths_from_root, paths_to_bindings, wasm_paths_to_bindings,
     },
     project::Project,
-    route::{AppPageRoute, Endpoint, Route, Routes, WrittenEndpoint},
+    route::{AppPageRoute, Endpoint, Route, RouteFiles, Routes, WrittenEndpoint},
     server_actions::create_server_actions_manifest,
     webpack_stats::generate_webpack_stats,
 };
@@ -583,6 +589,64 @@ impl AppProject {
         .resolve_entries(Vc::upcast(self.client_module_context())))
     }
 
+    /// The APIs that the client code of a route uses, but that not all
+    /// targeted browsers support, when `usageBasedPolyfills` is enabled.
+    #[turbo_tasks::function]
+    async fn client_polyfills(
+        self: Vc<Self>,
+        client_references: Vc<ClientReferenceGraphResult>,
+    ) -> Result<Vc<PolyfillableApis>> {
+        let project = self.project();
+        if !*project.next_config().usage_based_polyfills().await? {
+            return Ok(Vc::cell(vec![]));
+        }
+        let mut entries = self
+            .client_runtime_entries()
+            .await?
+            .iter()
+            .map(|entry| async move { Vc::upcast::<Box<dyn Module>>(*entry).to_resolved().await })
+            .try_join()
+            .await?;
+        for client_reference in client_references.await?.client_references.iter() {
+            if let ClientReferenceType::EcmascriptClientReference { module, .. } =
+                client_reference.ty()
+            {
+                entries.push(ResolvedVc::upcast(module.await?.client_module));
+            }
+        }
+        Ok(required_polyfills(
+            Vc::cell(entries),
+            project.client_chunking_context().environment(),
+        ))
+    }
+
+    /// The packages that are included in more than one version in the client
+    /// code of a route, when `reportDuplicatePackages` is enabled. Emits a
+    /// warning for each of them.
+    #[turbo_tasks::function]
+    async fn client_duplicate_packages(
+        self: Vc<Self>,
+        client_references: Vc<ClientReferenceGraphResult>,
+    ) -> Result<Vc<DuplicatePackages>> {
+        if !*self
+            .project()
+            .next_config()
+            .report_duplicate_packages()
+            .await?
+        {
+            return Ok(Vc::cell(vec![]));
+        }
+        let mut entries = vec![];
+        for client_reference in client_references.await?.client_references.iter() {
+            if let ClientReferenceType::EcmascriptClientReference { module, .. } =
+                client_reference.ty()
+            {
+                entries.push(ResolvedVc::upcast(module.await?.client_module));
+            }
+        }
+        Ok(find_duplicate_packages(Vc::cell(entries)))
+    }
+
     #[turbo_tasks::function]
     pub async fn routes(self: Vc<Self>) -> Result<Vc<Routes>> {
         let app_entrypoints = self.app_entrypoints();
@@ -605,6 +669,26 @@ impl AppProject {
         ))
     }
 
+    /// See [RouteFiles].
+    #[turbo_tasks::function]
+    pub async fn route_files(self: Vc<Self>) -> Result<Vc<RouteFiles>> {
+        let mut files = FxIndexMap::default();
+        for (pathname, entrypoint) in self.app_entrypoints().await?.iter() {
+            let file = match entrypoint {
+                AppEntrypoint::AppPage { loader_tree, .. } => page_file(&*loader_tree.await?),
+                AppEntrypoint::AppRoute { path, .. } => Some(**path),
+                AppEntrypoint::AppMetadata {
+                    metadata: MetadataItem::Static { path } | MetadataItem::Dynamic { path },
+                    ..
+                } => Some(*path),
+            };
+            if let Some(file) = file {
+                files.insert(pathname.to_string().into(), file.to_resolved().await?);
+            }
+        }
+        Ok(Vc::cell(files))
+    }
+
     #[turbo_tasks::function]
     pub async fn client_main_module(self: Vc<Self>) -> Result<Vc<Box<dyn Module>>> {
         let client_module_context = Vc::upcast(self.client_module_context());
@@ -630,6 +714,16 @@ impl AppProject {
     }
 }
 
+/// The page file of a loader tree, which is in the `children` slots.
+fn page_file(loader_tree: &AppPageLoaderTree) -> Option<Vc<FileSystemPath>> {
+    loader_tree.modules.page.or_else(|| {
+        loader_tree
+            .parallel_routes
+            .get("children")
+            .and_then(page_file)
+    })
+}
+
 #[turbo_tasks::function]
 pub fn app_entry_point_to_route(
     app_project: Vc<AppProject>,
@@ -879,27 +973,6 @@ impl AppEndpoint {
             client_references,
             client_references_chunks,
         ) = if process_client_components {
-            let client_shared_chunk_group = get_app_client_shared_chunk_group(
-                AssetIdent::from_path(this.app_project.project().project_path())
-                    .with_modifier(client_shared_chunks()),
-                this.app_project.client_runtime_entries(),
-                client_chunking_context,
-            )
-            .await?;
-
-            let mut client_shared_chunks_paths = vec![];
-            for chunk in client_shared_chunk_group.assets.await?.iter().copied() {
-                client_assets.insert(chunk);
-
-                let chunk_path = chunk.ident().path().await?;
-                if chunk_path.extension_ref() == Some("js") {
-                    if let Some(chunk_path) = client_relative_path_ref.get_path_to(&chunk_path) {
-                        client_shared_chunks_paths.push(chunk_path.into());
-                    }
-                }
-            }
-            let client_shared_availability_info = client_shared_chunk_group.availability_info;
-
             let client_references = {
                 let ServerEntries {
                     server_component_entries,
@@ -928,6 +1001,53 @@ impl AppEndpoint {
             };
             let client_references_cell = client_references.clone().cell();
 
+            let mut client_shared_ident =
+                AssetIdent::from_path(this.app_project.project().project_path())
+                    .with_modifier(client_shared_chunks());
+            let mut client_runtime_entries = this.app_project.client_runtime_entries();
+            this.app_project
+                .client_duplicate_packages(client_references_cell)
+                .await?;
+            let polyfills = this.app_project.client_polyfills(client_references_cell);
+            if !polyfills.await?.is_empty() {
+                // Polyfills need to be evaluated before the code that uses them, so
+                // they are added to the shared chunks, which are specific to the
+                // route then.
+                let route_path = rsc_entry.ident().path();
+                let polyfills_module = polyfills_entry_module(
+                    Vc::upcast(this.app_project.client_module_context()),
+                    route_path.append(".polyfills.js".into()),
+                    polyfills,
+                );
+                let Some(polyfills_module) =
+                    Vc::try_resolve_sidecast::<Box<dyn EvaluatableAsset>>(polyfills_module).await?
+                else {
+                    bail!("expected an evaluateable asset");
+                };
+                client_runtime_entries = client_runtime_entries.with_entry(polyfills_module);
+                client_shared_ident =
+                    AssetIdent::from_path(route_path).with_modifier(client_shared_chunks());
+            }
+            let client_shared_chunk_group = get_app_client_shared_chunk_group(
+                client_shared_ident,
+                client_runtime_entries,
+                client_chunking_context,
+            )
+            .await?;
+
+            let mut client_shared_chunks_paths = vec![];
+            for chunk in client_shared_chunk_group.assets.await?.iter().copied() {
+                client_assets.insert(chunk);
+
+                let chunk_path = chunk.ident().path().await?;
+                if chunk_path.extension_ref() == Some("js") {
+                    if let Some(chunk_path) = client_relative_path_ref.get_path_to(&chunk_path) {
+                        client_shared_chunks_paths.push(chunk_path.into());
+                    }
+                }
+            }
+            let client_shared_availability_info = client_shared_chunk_group.availability_info;
+
             let client_dynamic_imports = {
                 let mut client_dynamic_imports = FxIndexMap::default();
                 let mut visited_modules = VisitedDynamicImportModules::empty();
@@ -1064,6 +1184,13 @@ impl AppEndpoint {
             let build_manifest = BuildManifest {
                 root_main_files: client_shared_chunks_paths,
                 polyfill_files: polyfill_client_paths,
+                injected_polyfills: [(
+                    app_entry.original_name.clone(),
+                    polyfills.await?.clone_value(),
+                )]
+                .into_iter()
+                .filter(|(_, polyfills)| !polyfills.is_empty())
+                .collect(),
                 ..Default::default()
             };
             let build_manifest_output = VirtualOutputAsset::new(
@@ -1347,10 +1474,17 @@ impl AppEndpoint {
                     ),
                 );
                 server_assets.extend(loadable_manifest_output.await?.iter().copied());
+                let server_assets = with_nft_json(
+                    this.app_project.project(),
+                    app_entry.pathname.clone(),
+                    rsc_chunk,
+                    server_assets.into_iter().collect(),
+                )
+                .await?;
 
                 AppEndpointOutput::NodeJs {
                     rsc_chunk,
-                    server_assets: Vc::cell(server_assets.iter().cloned().collect::<Vec<_>>()),
+                    server_assets: Vc::cell(server_assets),
                     client_assets,
                 }
             }
@@ -1421,6 +1555,19 @@ impl AppEndpoint {
                     let mut current_availability_info = AvailabilityInfo::Root;
                     if let Some(client_references) = client_references {
                         let client_references = client_references.await?;
+                        // The layouts come first, so their chunks only depend on the layouts
+                        // and sibling routes share them. The order of the chunks doesn't
+                        // matter, as all of them are loaded before the entry is evaluated.
+                        let layouts = &client_references.server_component_entries;
+                        (current_chunks, current_availability_info) = *layout_segments_chunk_group(
+                            chunking_context,
+                            layouts[..layouts.len().saturating_sub(1)]
+                                .iter()
+                                .map(|&layout| Vc::upcast(layout))
+                                .collect(),
+                        )
+                        .await?;
+
                         let span = tracing::trace_span!("server utils");
                         async {
                             let utils_module = IncludeModulesModule::new(
@@ -1447,41 +1594,6 @@ impl AppEndpoint {
                         }
                         .instrument(span)
                         .await?;
-                        for server_component in client_references
-                            .server_component_entries
-                            .iter()
-                            .copied()
-                            .take(
-                                client_references
-                                    .server_component_entries
-                                    .len()
-                                    .saturating_sub(1),
-                            )
-                        {
-                            let span = tracing::trace_span!(
-                                "layout segment",
-                                name = server_component.ident().to_string().await?.as_str()
-                            );
-                            async {
-                                let chunk_group = chunking_context
-                                    .chunk_group(
-                                        server_component.ident(),
-                                        Vc::upcast(server_component),
-                                        Value::new(current_availability_info),
-                                    )
-                                    .await?;
-
-                                current_chunks = current_chunks
-                                    .concatenate(chunk_group.assets)
-                                    .resolve()
-                                    .await?;
-                                current_availability_info = chunk_group.availability_info;
-
-                                anyhow::Ok(())
-                            }
-                            .instrument(span)
-                            .await?;
-                        }
                     }
                     chunking_context
                         .entry_chunk_group(
@@ -1507,6 +1619,39 @@ impl AppEndpoint {
     }
 }
 
+/// Chunks the server components of the layout segments of a route one after
+/// another, with the modules of the parent layouts available to each. The
+/// result only depends on the layouts, so sibling routes share the chunks of
+/// their common parent layouts instead of chunking them again each. A changed
+/// layout invalidates the chunks of its child layouts.
+#[turbo_tasks::function]
+async fn layout_segments_chunk_group(
+    chunking_context: Vc<Box<dyn ChunkingContext>>,
+    layouts: Vec<Vc<Box<dyn ChunkableModule>>>,
+) -> Result<Vc<OutputAssetsWithAvailability>> {
+    let Some((&layout, parents)) = layouts.split_last() else {
+        return Ok(Vc::cell((OutputAssets::empty(), AvailabilityInfo::Root)));
+    };
+    let (parent_chunks, parent_availability_info) =
+        *layout_segments_chunk_group(chunking_context, parents.to_vec()).await?;
+
+    let span = tracing::trace_span!(
+        "layout segment",
+        name = layout.ident().to_string().await?.as_str()
+    );
+    async move {
+        let chunk_group = chunking_context
+            .chunk_group(layout.ident(), layout, Value::new(parent_availability_info))
+            .await?;
+        Ok(Vc::cell((
+            parent_chunks.concatenate(chunk_group.assets),
+            chunk_group.availability_info,
+        )))
+    }
+    .instrument(span)
+    .await
+}
+
 async fn create_app_paths_manifest(
     node_root: Vc<FileSystemPath>,
     original_name: &str,
@@ -1626,7 +1771,7 @@ impl Endpoint for AppEndpoint {
     #[turbo_tasks::function]
     async fn root_modules(self: Vc<Self>) -> Result<Vc<Modules>> {
         let rsc_entry = self.app_endpoint_entry().await?.rsc_entry;
-        Ok(Vc::cell(vec![rsc_entry]))
+        Ok(Vc::cell(vec![rsc_entry].into()))
     }
 }
 
@@ -1675,3 +1820,176 @@ impl AppEndpointOutput {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, sync::Once};
+
+    use anyhow::Result;
+    use turbo_tasks::{TurboTasks, Value, Vc};
+    use turbo_tasks_fs::{DiskFileSystem, FileContent, FileSystem, FileSystemPath};
+    use turbo_tasks_memory::MemoryBackend;
+    use turbopack::{
+        module_options::ModuleOptionsContext, resolve_options_context::ResolveOptionsContext,
+        ModuleAssetContext,
+    };
+    use turbopack_core::{
+        asset::Asset,
+        chunk::{ChunkableModule, ChunkingContext},
+        compile_time_info::CompileTimeInfo,
+        context::AssetContext,
+        environment::{Environment, ExecutionEnvironment, NodeJsEnvironment},
+        file_source::FileSource,
+        output::OutputAssets,
+        reference_type::ReferenceType,
+    };
+    use turbopack_ecmascript_runtime::RuntimeType;
+    use turbopack_nodejs::NodeJsChunkingContext;
+
+    use super::layout_segments_chunk_group;
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    fn write_layout(root: &Path, dir: &str, content: &str) {
+        fs::create_dir_all(root.join(dir)).unwrap();
+        fs::write(root.join(dir).join("layout.js"), content).unwrap();
+    }
+
+    fn chunking_context(root: Vc<FileSystemPath>) -> Vc<Box<dyn ChunkingContext>> {
+        Vc::upcast(
+            NodeJsChunkingContext::builder(
+                root,
+                root.join(".next/server".into()),
+                root.join(".next".into()),
+                root.join(".next/server/chunks".into()),
+                root.join(".next/static/media".into()),
+                environment(),
+                RuntimeType::Development,
+            )
+            .build(),
+        )
+    }
+
+    fn environment() -> Vc<Environment> {
+        Environment::new(Value::new(ExecutionEnvironment::NodeJsLambda(
+            NodeJsEnvironment::default().into(),
+        )))
+    }
+
+    async fn layout(root: Vc<FileSystemPath>, dir: &str) -> Result<Vc<Box<dyn ChunkableModule>>> {
+        let asset_context = ModuleAssetContext::new(
+            Default::default(),
+            CompileTimeInfo::new(environment()),
+            ModuleOptionsContext::default().cell(),
+            ResolveOptionsContext::default().cell(),
+            Vc::cell("test".into()),
+        );
+        let module = asset_context
+            .process(
+                Vc::upcast(FileSource::new(
+                    root.join(format!("{dir}/layout.js").into()),
+                )),
+                Value::new(ReferenceType::Undefined),
+            )
+            .module();
+        Ok(Vc::try_resolve_sidecast::<Box<dyn ChunkableModule>>(module)
+            .await?
+            .unwrap())
+    }
+
+    /// Returns the chunks of the layouts in `dirs`, from the root layout down.
+    async fn chunks(fs: Vc<DiskFileSystem>, dirs: &[&str]) -> Result<Vc<OutputAssets>> {
+        let root = fs.root();
+        let mut layouts = Vec::new();
+        for dir in dirs {
+            layouts.push(layout(root, dir).await?);
+        }
+        let chunk_group = layout_segments_chunk_group(chunking_context(root), layouts);
+        Ok(chunk_group.strongly_consistent().await?.0)
+    }
+
+    async fn code(chunks: Vc<OutputAssets>) -> Result<String> {
+        let mut code = String::new();
+        for chunk in chunks.await?.iter() {
+            if let FileContent::Content(file) = &*chunk.content().file_content().await? {
+                code += &file.content().to_str()?;
+            }
+        }
+        Ok(code)
+    }
+
+    #[tokio::test]
+    async fn shares_the_chunks_of_parent_layouts() {
+        register();
+        let root = tempfile::tempdir().unwrap();
+        write_layout(root.path(), "app", "export default 'root layout'");
+        write_layout(
+            root.path(),
+            "app/dashboard",
+            "export default 'dashboard layout'",
+        );
+        write_layout(
+            root.path(),
+            "app/settings",
+            "export default 'settings layout'",
+        );
+        let root_path = root.path().to_string_lossy().to_string();
+
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            let fs = DiskFileSystem::new("project".into(), root_path.into(), vec![]);
+            let root_chunks = chunks(fs, &["app"]).await?.await?;
+            let dashboard_chunks = chunks(fs, &["app", "app/dashboard"]).await?.await?;
+            let settings_chunks = chunks(fs, &["app", "app/settings"]).await?.await?;
+
+            // Sibling routes reuse the chunks of the root layout
+            assert!(!root_chunks.is_empty());
+            assert_eq!(dashboard_chunks[..root_chunks.len()], root_chunks[..]);
+            assert_eq!(settings_chunks[..root_chunks.len()], root_chunks[..]);
+
+            // and only add the chunks of their own layout
+            let dashboard_code =
+                code(Vc::cell(dashboard_chunks[root_chunks.len()..].to_vec())).await?;
+            assert!(dashboard_code.contains("dashboard layout"));
+            assert!(!dashboard_code.contains("root layout"));
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn invalidates_the_chunks_of_child_layouts() {
+        register();
+        let root = tempfile::tempdir().unwrap();
+        let root_path = root.path().to_path_buf();
+        write_layout(&root_path, "app", "export default 'root layout'");
+        write_layout(
+            &root_path,
+            "app/dashboard",
+            "export default 'dashboard layout'",
+        );
+
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            let fs =
+                DiskFileSystem::new("project".into(), root_path.to_string_lossy().into(), vec![]);
+            let dashboard = ["app", "app/dashboard"];
+            assert!(code(chunks(fs, &dashboard).await?)
+                .await?
+                .contains("root layout"));
+
+            write_layout(&root_path, "app", "export default 'changed root layout'");
+            fs.await?.invalidate();
+            let dashboard_code = code(chunks(fs, &dashboard).await?).await?;
+            assert!(dashboard_code.contains("changed root layout"));
+            assert!(dashboard_code.contains("dashboard layout"));
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
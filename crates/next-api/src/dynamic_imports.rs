@@ -16,7 +16,7 @@ use turbo_tasks::{
 use turbopack_core::{
     chunk::{
         availability_info::AvailabilityInfo, ChunkableModule, ChunkingContext, ChunkingContextExt,
-        EvaluatableAsset,
+        EvaluatableAsset, ModuleId,
     },
     context::AssetContext,
     module::Module,
@@ -28,6 +28,7 @@ use turbopack_core::{
 use turbopack_ecmascript::{parse::ParseResult, resolve::esm_resolve, EcmascriptParsable};
 
 async fn collect_chunk_group_inner<F, Fu>(
+    chunking_context: Vc<Box<dyn ChunkingContext>>,
     dynamic_import_entries: FxIndexMap<ResolvedVc<Box<dyn Module>>, DynamicImportedModules>,
     mut build_chunk: F,
 ) -> Result<Vc<DynamicImportedChunks>>
@@ -35,15 +36,15 @@ where
     F: FnMut(Vc<Box<dyn ChunkableModule>>) -> Fu,
     Fu: Future<Output = Result<Vc<OutputAssets>>> + Send,
 {
-    let mut chunks_hash: HashMap<RcStr, Vc<OutputAssets>> = HashMap::new();
+    let mut chunks_hash: HashMap<RcStr, (Vc<ModuleId>, Vc<OutputAssets>)> = HashMap::new();
     let mut dynamic_import_chunks = FxIndexMap::default();
 
     // Iterate over the collected import mappings, and create a chunk for each
     // dynamic import.
     for (origin_module, dynamic_imports) in dynamic_import_entries {
         for (imported_raw_str, imported_module) in dynamic_imports {
-            let chunk = if let Some(chunk) = chunks_hash.get(&imported_raw_str) {
-                *chunk
+            let (module_id, chunk) = if let Some(entry) = chunks_hash.get(&imported_raw_str) {
+                *entry
             } else {
                 let Some(module) =
                     ResolvedVc::try_sidecast::<Box<dyn ChunkableModule>>(imported_module).await?
@@ -58,14 +59,15 @@ where
                 // chunks in case if there are same modules being imported in different
                 // origins.
                 let chunk_group = build_chunk(*module).await?;
-                chunks_hash.insert(imported_raw_str.clone(), chunk_group);
-                chunk_group
+                let module_id = chunking_context.chunk_item_id_from_ident(module.ident());
+                chunks_hash.insert(imported_raw_str.clone(), (module_id, chunk_group));
+                (module_id, chunk_group)
             };
 
             dynamic_import_chunks
                 .entry(origin_module)
                 .or_insert_with(Vec::new)
-                .push((imported_raw_str.clone(), chunk));
+                .push((imported_raw_str.clone(), module_id, chunk));
         }
     }
 
@@ -77,9 +79,11 @@ pub(crate) async fn collect_chunk_group(
     dynamic_import_entries: FxIndexMap<ResolvedVc<Box<dyn Module>>, DynamicImportedModules>,
     availability_info: Value<AvailabilityInfo>,
 ) -> Result<Vc<DynamicImportedChunks>> {
-    collect_chunk_group_inner(dynamic_import_entries, |module| async move {
-        Ok(chunking_context.chunk_group_assets(module, availability_info))
-    })
+    collect_chunk_group_inner(
+        chunking_context,
+        dynamic_import_entries,
+        |module| async move { Ok(chunking_context.chunk_group_assets(module, availability_info)) },
+    )
     .await
 }
 
@@ -87,17 +91,23 @@ pub(crate) async fn collect_evaluated_chunk_group(
     chunking_context: Vc<Box<dyn ChunkingContext>>,
     dynamic_import_entries: FxIndexMap<ResolvedVc<Box<dyn Module>>, DynamicImportedModules>,
 ) -> Result<Vc<DynamicImportedChunks>> {
-    collect_chunk_group_inner(dynamic_import_entries, |module| async move {
-        if let Some(module) = Vc::try_resolve_downcast::<Box<dyn EvaluatableAsset>>(module).await? {
-            Ok(chunking_context.evaluated_chunk_group_assets(
-                module.ident(),
-                Vc::cell(vec![Vc::upcast(module)]),
-                Value::new(AvailabilityInfo::Root),
-            ))
-        } else {
-            Ok(chunking_context.chunk_group_assets(module, Value::new(AvailabilityInfo::Root)))
-        }
-    })
+    collect_chunk_group_inner(
+        chunking_context,
+        dynamic_import_entries,
+        |module| async move {
+            if let Some(module) =
+                Vc::try_resolve_downcast::<Box<dyn EvaluatableAsset>>(module).await?
+            {
+                Ok(chunking_context.evaluated_chunk_group_assets(
+                    module.ident(),
+                    Vc::cell(vec![Vc::upcast(module)]),
+                    Value::new(AvailabilityInfo::Root),
+                ))
+            } else {
+                Ok(chunking_context.chunk_group_assets(module, Value::new(AvailabilityInfo::Root)))
+            }
+        },
+    )
     .await
 }
 
@@ -410,7 +420,9 @@ impl Visit for CollectImportSourceVisitor {
 }
 
 pub type DynamicImportedModules = Vec<(RcStr, ResolvedVc<Box<dyn Module>>)>;
-pub type DynamicImportedOutputAssets = Vec<(RcStr, Vc<OutputAssets>)>;
+/// The chunks of each dynamic import: (ImportSourceString, ModuleId of the
+/// imported module, OutputAssets).
+pub type DynamicImportedOutputAssets = Vec<(RcStr, Vc<ModuleId>, Vc<OutputAssets>)>;
 
 /// A struct contains mapping for the dynamic imports to construct chunk per
 /// each individual module (Origin Module, Vec<(ImportSourceString, Module)>)
@@ -34,6 +34,6 @@ impl Endpoint for EmptyEndpoint {
 
     #[turbo_tasks::function]
     fn root_modules(self: Vc<Self>) -> Vc<Modules> {
-        Vc::cell(vec![])
+        Modules::empty()
     }
 }
@@ -262,9 +262,8 @@ impl Endpoint for InstrumentationEndpoint {
     #[turbo_tasks::function]
     async fn root_modules(self: Vc<Self>) -> Result<Vc<Modules>> {
         let core_modules = self.core_modules().await?;
-        Ok(Vc::cell(vec![
-            core_modules.userland_module,
-            core_modules.edge_entry_module,
-        ]))
+        Ok(Vc::cell(
+            vec![core_modules.userland_module, core_modules.edge_entry_module].into(),
+        ))
     }
 }
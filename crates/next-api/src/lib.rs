@@ -12,11 +12,13 @@ pub mod global_module_id_strategy;
 mod instrumentation;
 mod loadable_manifest;
 mod middleware;
+mod nft_json;
 mod pages;
 pub mod paths;
 pub mod project;
 pub mod route;
 mod server_actions;
+mod static_export;
 mod versioned_content_map;
 mod webpack_stats;
 
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use anyhow::Result;
 use next_core::next_manifests::LoadableManifest;
-use turbo_tasks::{RcStr, ResolvedVc, TryFlatJoinIterExt, Vc};
+use turbo_tasks::{RcStr, ResolvedVc, TryFlatJoinIterExt, ValueToString, Vc};
 use turbo_tasks_fs::{File, FileContent, FileSystemPath};
 use turbopack_core::{
     asset::AssetContent,
@@ -27,12 +27,10 @@ pub async fn create_react_loadable_manifest(
     for (origin, dynamic_imports) in dynamic_import_entries.into_iter() {
         let origin_path = &*origin.ident().path().await?;
 
-        for (import, chunk_output) in dynamic_imports {
+        for (import, module_id, chunk_output) in dynamic_imports {
             let chunk_output = chunk_output.await?;
             output.extend(chunk_output.iter().copied());
 
-            let id: RcStr = format!("{} -> {}", origin_path, import).into();
-
             let client_relative_path_value = client_relative_path.await?;
             let files = chunk_output
                 .iter()
@@ -47,12 +45,13 @@ pub async fn create_react_loadable_manifest(
                 .try_flat_join()
                 .await?;
 
-            let manifest_item = LoadableManifest {
-                id: id.clone(),
+            insert_manifest_entries(
+                &mut loadable_manifest,
+                &origin_path.to_string(),
+                import,
+                module_id.to_string().await?.clone_value(),
                 files,
-            };
-
-            loadable_manifest.insert(id, manifest_item);
+            );
         }
     }
 
@@ -71,3 +70,80 @@ pub async fn create_react_loadable_manifest(
     output.push(ResolvedVc::upcast(loadable_manifest));
     Ok(Vc::cell(output))
 }
+
+/// Adds the entries of a dynamic import of `import` from `origin_path` to the
+/// manifest.
+fn insert_manifest_entries(
+    loadable_manifest: &mut HashMap<RcStr, LoadableManifest>,
+    origin_path: &str,
+    import: &str,
+    module_id: RcStr,
+    files: Vec<RcStr>,
+) {
+    // The entry keyed by the stable module ID of the imported module
+    // shares the chunks of the `${origin} -> ${imported}` entry, which
+    // is what `loadableGenerated.modules` refers to.
+    loadable_manifest.insert(
+        module_id.clone(),
+        LoadableManifest {
+            id: module_id,
+            files: files.clone(),
+        },
+    );
+
+    let id: RcStr = format!("{} -> {}", origin_path, import).into();
+    let manifest_item = LoadableManifest {
+        id: id.clone(),
+        files,
+    };
+
+    loadable_manifest.insert(id, manifest_item);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+    use turbo_tasks::RcStr;
+
+    use super::insert_manifest_entries;
+
+    #[test]
+    fn lists_dynamic_imports_by_module_id() {
+        let mut manifest = HashMap::new();
+        let files = vec![RcStr::from("static/chunks/hello.js")];
+        insert_manifest_entries(
+            &mut manifest,
+            "[project]/pages/index.js",
+            "../components/hello",
+            "[project]/components/hello.js [client] (ecmascript)".into(),
+            files.clone(),
+        );
+        insert_manifest_entries(
+            &mut manifest,
+            "[project]/pages/about.js",
+            "../components/hello",
+            "[project]/components/hello.js [client] (ecmascript)".into(),
+            files,
+        );
+
+        assert_eq!(
+            serde_json::to_value(&manifest).unwrap(),
+            json!({
+                "[project]/pages/index.js -> ../components/hello": {
+                    "id": "[project]/pages/index.js -> ../components/hello",
+                    "files": ["static/chunks/hello.js"],
+                },
+                "[project]/pages/about.js -> ../components/hello": {
+                    "id": "[project]/pages/about.js -> ../components/hello",
+                    "files": ["static/chunks/hello.js"],
+                },
+                "[project]/components/hello.js [client] (ecmascript)": {
+                    "id": "[project]/components/hello.js [client] (ecmascript)",
+                    "files": ["static/chunks/hello.js"],
+                },
+            })
+        );
+    }
+}
@@ -312,6 +312,8 @@ impl Endpoint for MiddlewareEndpoint {
 
     #[turbo_tasks::function]
     async fn root_modules(self: Vc<Self>) -> Result<Vc<Modules>> {
-        Ok(Vc::cell(vec![self.userland_module().to_resolved().await?]))
+        Ok(Vc::cell(
+            vec![self.userland_module().to_resolved().await?].into(),
+        ))
     }
 }
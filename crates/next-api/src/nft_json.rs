@@ -0,0 +1,96 @@
+use anyhow::{Context, Result};
+use next_core::{
+    all_assets_from_entries,
+    output_file_tracing::{apply_output_file_tracing_config, TracedFiles},
+};
+use serde_json::json;
+use turbo_tasks::{RcStr, ResolvedVc, TryFlatJoinIterExt, TryJoinIterExt, Vc};
+use turbo_tasks_fs::{to_sys_path, File};
+use turbopack_core::{
+    asset::AssetContent,
+    output::{OutputAsset, OutputAssets},
+    virtual_output::VirtualOutputAsset,
+};
+
+use crate::project::Project;
+
+/// Creates the `.nft.json` file next to the server `entry` of `route`, which
+/// lists the files that are needed to run the route, in the format of
+/// `@vercel/nft`. These are the `server_assets` of the route, with the
+/// `outputFileTracingIncludes` and `outputFileTracingExcludes` config applied.
+#[turbo_tasks::function]
+pub async fn create_nft_json(
+    project: Vc<Project>,
+    route: RcStr,
+    entry: Vc<Box<dyn OutputAsset>>,
+    server_assets: Vc<OutputAssets>,
+) -> Result<Vc<Box<dyn OutputAsset>>> {
+    let entry_path = entry.ident().path();
+    let entry_path_ref = &*entry_path.await?;
+    let traced_files = all_assets_from_entries(server_assets)
+        .await?
+        .iter()
+        .map(|asset| async move {
+            let path = asset.ident().path();
+            Ok((*path.await? != *entry_path_ref).then_some(path.to_resolved().await?))
+        })
+        .try_flat_join()
+        .await?;
+    let traced_files = apply_output_file_tracing_config(
+        project.next_config(),
+        project.project_path(),
+        route,
+        Vc::<TracedFiles>::cell(traced_files),
+    );
+
+    let dir = to_sys_path(entry_path.parent())
+        .await?
+        .context("the server entry needs to be on disk")?;
+    let mut files = traced_files
+        .await?
+        .iter()
+        .map(|&path| async move { to_sys_path(*path).await })
+        .try_join()
+        .await?
+        .into_iter()
+        .flatten()
+        .filter_map(|path| pathdiff::diff_paths(path, &dir))
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let nft_json = json!({
+        "version": 1,
+        "files": files,
+    });
+    Ok(Vc::upcast(VirtualOutputAsset::new(
+        entry_path.append(".nft.json".into()),
+        AssetContent::file(File::from(serde_json::to_string_pretty(&nft_json)?).into()),
+    )))
+}
+
+/// The server assets of a route, plus its `.nft.json` file in production
+/// builds.
+pub(crate) async fn with_nft_json(
+    project: Vc<Project>,
+    route: RcStr,
+    entry: ResolvedVc<Box<dyn OutputAsset>>,
+    mut server_assets: Vec<ResolvedVc<Box<dyn OutputAsset>>>,
+) -> Result<Vec<ResolvedVc<Box<dyn OutputAsset>>>> {
+    if project.next_mode().await?.is_production() {
+        let nft_json = create_nft_json(
+            project,
+            route,
+            *entry,
+            Vc::cell(
+                std::iter::once(*entry)
+                    .chain(server_assets.iter().map(|asset| **asset))
+                    .collect(),
+            ),
+        )
+        .to_resolved()
+        .await?;
+        server_assets.push(nft_json);
+    }
+    Ok(server_assets)
+}
@@ -56,7 +56,11 @@ use turbopack_core::{
     source::Source,
     virtual_output::VirtualOutputAsset,
 };
-use turbopack_ecmascript::resolve::esm_resolve;
+use turbopack_ecmascript::{
+    duplicate_packages::{find_duplicate_packages, DuplicatePackages},
+    polyfills::{polyfills_entry_module, required_polyfills, PolyfillableApis},
+    resolve::esm_resolve,
+};
 use turbopack_nodejs::NodeJsChunkingContext;
 
 use crate::{
@@ -66,12 +70,13 @@ use crate::{
     },
     font::create_font_manifest,
     loadable_manifest::create_react_loadable_manifest,
+    nft_json::with_nft_json,
     paths::{
         all_paths_in_root, all_server_paths, get_js_paths_from_root, get_paths_from_root,
         get_wasm_paths_from_root, paths_to_bindings, wasm_paths_to_bindings,
     },
     project::Project,
-    route::{Endpoint, Route, Routes, WrittenEndpoint},
+    route::{Endpoint, Route, RouteFiles, Routes, WrittenEndpoint},
     webpack_stats::generate_webpack_stats,
 };
 
@@ -234,6 +239,27 @@ impl PagesProject {
         self.project
     }
 
+    /// See [RouteFiles].
+    #[turbo_tasks::function]
+    pub async fn route_files(self: Vc<Self>) -> Result<Vc<RouteFiles>> {
+        let PagesStructure { api, pages, .. } = &*self.pages_structure().await?;
+        let mut files = FxIndexMap::default();
+        let mut queue = api
+            .iter()
+            .chain(pages.iter())
+            .map(|dir| **dir)
+            .collect::<Vec<_>>();
+        while let Some(dir) = queue.pop() {
+            let dir = dir.await?;
+            for &item in dir.items.iter() {
+                let pathname = format!("/{}", item.await?.next_router_path.await?.path).into();
+                files.insert(pathname, item.project_path().to_resolved().await?);
+            }
+            queue.extend(dir.children.iter().copied());
+        }
+        Ok(Vc::cell(files))
+    }
+
     #[turbo_tasks::function]
     fn pages_structure(&self) -> Vc<PagesStructure> {
         let next_router_fs = Vc::upcast::<Box<dyn FileSystem>>(VirtualFileSystem::new());
@@ -690,10 +716,28 @@ impl PageEndpoint {
 
             let client_chunking_context = this.pages_project.project().client_chunking_context();
 
+            self.client_duplicate_packages().await?;
+
+            let mut runtime_entries = this.pages_project.client_runtime_entries();
+            let polyfills = self.client_polyfills();
+            if !polyfills.await?.is_empty() {
+                // Polyfills need to be evaluated before the code that uses them.
+                let polyfills_module = polyfills_entry_module(
+                    this.pages_project.client_module_context(),
+                    this.page.project_path().append(".polyfills.js".into()),
+                    polyfills,
+                );
+                let Some(polyfills_module) =
+                    Vc::try_resolve_sidecast::<Box<dyn EvaluatableAsset>>(polyfills_module).await?
+                else {
+                    bail!("expected an evaluateable asset");
+                };
+                runtime_entries = runtime_entries.with_entry(polyfills_module);
+            }
+
             let client_chunks = client_chunking_context.evaluated_chunk_group_assets(
                 AssetIdent::from_path(this.page.await?.base_path),
-                this.pages_project
-                    .client_runtime_entries()
+                runtime_entries
                     .with_entry(client_main_module)
                     .with_entry(client_module),
                 Value::new(AvailabilityInfo::Root),
@@ -705,6 +749,50 @@ impl PageEndpoint {
         .await
     }
 
+    /// The packages that are included in more than one version in the client
+    /// code of the page, when `reportDuplicatePackages` is enabled. Emits a
+    /// warning for each of them.
+    #[turbo_tasks::function]
+    async fn client_duplicate_packages(self: Vc<Self>) -> Result<Vc<DuplicatePackages>> {
+        let this = self.await?;
+        let project = this.pages_project.project();
+        if !*project.next_config().report_duplicate_packages().await? {
+            return Ok(Vc::cell(vec![]));
+        }
+        let entries = [
+            self.client_module(),
+            this.pages_project.client_main_module(),
+        ]
+        .into_iter()
+        .map(|module| module.to_resolved())
+        .try_join()
+        .await?;
+        Ok(find_duplicate_packages(Vc::cell(entries.into())))
+    }
+
+    /// The APIs that the client code of the page uses, but that not all
+    /// targeted browsers support, when `usageBasedPolyfills` is enabled.
+    #[turbo_tasks::function]
+    async fn client_polyfills(self: Vc<Self>) -> Result<Vc<PolyfillableApis>> {
+        let this = self.await?;
+        let project = this.pages_project.project();
+        if !*project.next_config().usage_based_polyfills().await? {
+            return Ok(Vc::cell(vec![]));
+        }
+        let entries = [
+            self.client_module(),
+            this.pages_project.client_main_module(),
+        ]
+        .into_iter()
+        .map(|module| module.to_resolved())
+        .try_join()
+        .await?;
+        Ok(required_polyfills(
+            Vc::cell(entries.into()),
+            project.client_chunking_context().environment(),
+        ))
+    }
+
     #[turbo_tasks::function]
     async fn page_loader(
         self: Vc<Self>,
@@ -990,6 +1078,7 @@ impl PageEndpoint {
     async fn build_manifest(
         &self,
         client_chunks: Vc<OutputAssets>,
+        polyfills: Vc<PolyfillableApis>,
     ) -> Result<Vc<Box<dyn OutputAsset>>> {
         let node_root = self.pages_project.project().node_root();
         let client_relative_path = self.pages_project.project().client_relative_path();
@@ -1016,6 +1105,13 @@ impl PageEndpoint {
             )]
             .into_iter()
             .collect(),
+            injected_polyfills: [(
+                self.pathname.await?.clone_value(),
+                polyfills.await?.clone_value(),
+            )]
+            .into_iter()
+            .filter(|(_, polyfills)| !polyfills.is_empty())
+            .collect(),
             ..Default::default()
         };
         let manifest_path_prefix = get_asset_prefix_from_pathname(&self.pathname.await?);
@@ -1042,7 +1138,10 @@ impl PageEndpoint {
             PageEndpointType::Html => {
                 let client_chunks = self.client_chunks();
                 client_assets.extend(client_chunks.await?.iter().map(|asset| **asset));
-                let build_manifest = self.build_manifest(client_chunks).to_resolved().await?;
+                let build_manifest = self
+                    .build_manifest(client_chunks, self.client_polyfills())
+                    .to_resolved()
+                    .await?;
                 let page_loader = self.page_loader(client_chunks);
                 client_assets.push(page_loader);
                 server_assets.push(build_manifest);
@@ -1104,6 +1203,13 @@ impl PageEndpoint {
 
                 let loadable_manifest_output = self.react_loadable_manifest(dynamic_import_entries);
                 server_assets.extend(loadable_manifest_output.await?.iter().copied());
+                let server_assets = with_nft_json(
+                    this.pages_project.project(),
+                    get_asset_prefix_from_pathname(&this.pathname.await?).into(),
+                    entry,
+                    server_assets,
+                )
+                .await?;
 
                 PageEndpointOutput::NodeJs {
                     entry_chunk: entry,
@@ -1318,7 +1424,7 @@ impl Endpoint for PageEndpoint {
             modules.push(self.client_module().to_resolved().await?);
         }
 
-        Ok(Vc::cell(modules))
+        Ok(Vc::cell(modules.into()))
     }
 }
 
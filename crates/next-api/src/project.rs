@@ -5,33 +5,36 @@ use indexmap::map::Entry;
 use next_core::{
     all_assets_from_entries,
     app_structure::find_app_dir,
-    emit_assets, get_edge_chunking_context, get_edge_chunking_context_with_client_assets,
-    get_edge_compile_time_info, get_edge_resolve_options_context,
+    emit_assets, emitted_paths, get_edge_chunking_context,
+    get_edge_chunking_context_with_client_assets, get_edge_compile_time_info,
+    get_edge_resolve_options_context, image_remote_pattern_matches,
     instrumentation::instrumentation_files,
     middleware::middleware_files,
     mode::NextMode,
     next_client::{get_client_chunking_context, get_client_compile_time_info},
     next_config::{JsConfig, ModuleIdStrategy as ModuleIdStrategyConfig, NextConfig},
+    next_public::{get_public_files, public_file_conflicts, PublicFileConflict},
     next_server::{
         get_server_chunking_context, get_server_chunking_context_with_client_assets,
         get_server_compile_time_info, get_server_module_options_context,
         get_server_resolve_options_context, ServerContextType,
     },
-    next_telemetry::NextFeatureTelemetry,
+    next_telemetry::{FeatureUsage, NextFeatureTelemetry},
     util::NextRuntime,
+    validate_image_config,
 };
 use serde::{Deserialize, Serialize};
 use tracing::Instrument;
 use turbo_tasks::{
     debug::ValueDebugFormat,
     fxindexmap,
-    graph::{AdjacencyMap, GraphTraversal},
+    graph::{AdjacencyMap, GraphTraversal, NonDeterministic},
     trace::TraceRawVcs,
     Completion, Completions, FxIndexMap, IntoTraitRef, RcStr, ReadRef, ResolvedVc, State,
-    TaskInput, TransientInstance, TryFlatJoinIterExt, Value, Vc,
+    TaskInput, TransientInstance, TryFlatJoinIterExt, TryJoinIterExt, Value, Vc,
 };
 use turbo_tasks_env::{EnvMap, ProcessEnv};
-use turbo_tasks_fs::{DiskFileSystem, FileSystem, FileSystemPath, VirtualFileSystem};
+use turbo_tasks_fs::{glob::Glob, DiskFileSystem, FileSystem, FileSystemPath, VirtualFileSystem};
 use turbopack::{
     evaluate_context::node_build_environment, transition::TransitionOptions, ModuleAssetContext,
 };
@@ -43,11 +46,13 @@ use turbopack_core::{
     },
     compile_time_info::CompileTimeInfo,
     context::AssetContext,
-    diagnostics::DiagnosticExt,
+    diagnostics::{Diagnostic, DiagnosticContextExt, DiagnosticExt},
     file_source::FileSource,
     issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
-    module::Modules,
+    module::{Module, Modules},
     output::{OutputAsset, OutputAssets},
+    output_pruning::OutputPruning,
+    reference::primary_referenced_modules,
     resolve::{find_context_file, FindContextFileResult},
     source_map::OptionSourceMap,
     version::{
@@ -68,6 +73,7 @@ use crate::{
     middleware::MiddlewareEndpoint,
     pages::PagesProject,
     route::{Endpoint, Route},
+    static_export::export_static_site,
     versioned_content_map::{OutputAssetsOperation, VersionedContentMap},
 };
 
@@ -197,6 +203,8 @@ pub struct ProjectContainer {
     name: RcStr,
     options_state: State<Option<ProjectOptions>>,
     versioned_content_map: Option<Vc<VersionedContentMap>>,
+    /// Deletes the files of previous emits that are no longer emitted.
+    output_pruning: Vc<OutputPruning>,
 }
 
 #[turbo_tasks::value_impl]
@@ -208,12 +216,23 @@ impl ProjectContainer {
             // we only need to enable versioning in dev mode, since build
             // is assumed to be operating over a static snapshot
             versioned_content_map: dev.then(VersionedContentMap::new),
+            output_pruning: OutputPruning::new(),
             options_state: State::new(None),
         }
         .cell()
     }
 }
 
+/// Reads `package.json` and `index.*` files of the project ahead when the
+/// watcher polls. Polling is used for filesystems without native file events,
+/// e.g. network filesystems and mounts in containers, which are slow to read
+/// from as well.
+fn enable_read_ahead(project_fs: &DiskFileSystem, watch: WatchOptions) {
+    if watch.poll_interval.is_some() {
+        project_fs.enable_read_ahead();
+    }
+}
+
 impl ProjectContainer {
     #[tracing::instrument(level = "info", name = "initialize project", skip_all)]
     pub async fn initialize(self: Vc<Self>, options: ProjectOptions) -> Result<()> {
@@ -223,6 +242,7 @@ impl ProjectContainer {
 
         let project = self.project();
         let project_fs = project.project_fs().strongly_consistent().await?;
+        enable_read_ahead(&project_fs, watch);
         if watch.enable {
             project_fs
                 .start_watching_with_invalidation_reason(watch.poll_interval)
@@ -305,6 +325,7 @@ impl ProjectContainer {
         let output_fs = project.output_fs().strongly_consistent().await?;
 
         if !ReadRef::ptr_eq(&prev_project_fs, &project_fs) {
+            enable_read_ahead(&project_fs, watch);
             if watch.enable {
                 // TODO stop watching: prev_project_fs.stop_watching()?;
                 project_fs
@@ -384,6 +405,7 @@ impl ProjectContainer {
                 NextMode::Build.cell()
             },
             versioned_content_map: self.versioned_content_map,
+            output_pruning: self.output_pruning,
             build_id,
             encryption_key,
             preview_props,
@@ -403,6 +425,32 @@ impl ProjectContainer {
         self.project().hmr_identifiers()
     }
 
+    /// See [Project::feature_usage].
+    #[turbo_tasks::function]
+    pub fn feature_usage(self: Vc<Self>) -> Vc<FeatureUsage> {
+        self.project().feature_usage()
+    }
+
+    /// See [Project::export_static_site].
+    #[turbo_tasks::function]
+    pub fn export_static_site(self: Vc<Self>, out_dir: RcStr) -> Vc<()> {
+        self.project().export_static_site(out_dir)
+    }
+
+    /// See [Project::image_remote_pattern_matches].
+    #[turbo_tasks::function]
+    pub fn image_remote_pattern_matches(
+        self: Vc<Self>,
+        protocol: RcStr,
+        hostname: RcStr,
+        port: RcStr,
+        pathname: RcStr,
+        search: RcStr,
+    ) -> Vc<bool> {
+        self.project()
+            .image_remote_pattern_matches(protocol, hostname, port, pathname, search)
+    }
+
     /// Gets a source map for a particular `file_path`. If `dev` mode is
     /// disabled, this will always return [`OptionSourceMap::none`].
     #[turbo_tasks::function]
@@ -454,6 +502,8 @@ pub struct Project {
 
     versioned_content_map: Option<Vc<VersionedContentMap>>,
 
+    output_pruning: Vc<OutputPruning>,
+
     build_id: RcStr,
 
     encryption_key: RcStr,
@@ -522,6 +572,81 @@ impl Issue for ConflictIssue {
     }
 }
 
+/// Emits issues for files in the `public` directory that conflict with
+/// routes, see [public_file_conflicts]. Public files take precedence over
+/// routes, so a route is never served for the pathname of a public file.
+async fn emit_public_file_conflicts(
+    project_path: Vc<FileSystemPath>,
+    routes: &FxIndexMap<RcStr, Route>,
+) -> Result<()> {
+    let files = get_public_files(project_path).await?;
+    let conflicts = public_file_conflicts(
+        files.keys().map(|pathname| pathname.as_str()),
+        routes.keys().map(|pathname| pathname.as_str()),
+    );
+    for (pathname, conflict) in conflicts {
+        let file = files[pathname];
+        let (title, description, severity) = match conflict {
+            PublicFileConflict::ShadowedByNext => (
+                format!("Public file is shadowed by Next.js: {}", pathname),
+                format!(
+                    "public{} is never served, because paths starting with /_next/ are reserved \
+                     for Next.js. Please move the file to a different directory.",
+                    pathname
+                ),
+                IssueSeverity::Warning,
+            ),
+            PublicFileConflict::ShadowsRoute(route_pathname) => {
+                let Some(route_name) = route_name(route_pathname, &routes[route_pathname]) else {
+                    continue;
+                };
+                (
+                    format!("Public file and route both match path: {}", pathname),
+                    format!(
+                        "public{} conflicts with {}. Public files take precedence over routes, so \
+                         the route is never served. Please remove one of them.",
+                        pathname, route_name
+                    ),
+                    IssueSeverity::Error,
+                )
+            }
+            PublicFileConflict::ShadowsDynamicRoute(route_pathname) => {
+                let Some(route_name) = route_name(route_pathname, &routes[route_pathname]) else {
+                    continue;
+                };
+                (
+                    format!("Public file shadows a dynamic route: {}", pathname),
+                    format!(
+                        "public{} is served instead of {} for this path, because public files \
+                         take precedence over routes.",
+                        pathname, route_name
+                    ),
+                    IssueSeverity::Warning,
+                )
+            }
+        };
+        ConflictIssue {
+            path: *file,
+            title: StyledString::Text(title.into()).cell(),
+            description: StyledString::Text(description.into()).cell(),
+            severity: severity.cell(),
+        }
+        .cell()
+        .emit();
+    }
+    Ok(())
+}
+
+/// Describes the route for issues, with the path of its file when it's known.
+fn route_name(pathname: &str, route: &Route) -> Option<String> {
+    Some(match route {
+        Route::Page { .. } | Route::PageApi { .. } => format!("the pages route {}", pathname),
+        Route::AppPage(routes) => format!("app{}", routes.first()?.original_name),
+        Route::AppRoute { original_name, .. } => format!("app{}", original_name),
+        Route::Conflict => return None,
+    })
+}
+
 #[turbo_tasks::value_impl]
 impl Project {
     #[turbo_tasks::function]
@@ -656,8 +781,13 @@ impl Project {
     }
 
     #[turbo_tasks::function]
-    pub(super) fn client_compile_time_info(&self) -> Vc<CompileTimeInfo> {
-        get_client_compile_time_info(self.browserslist_query.clone(), self.define_env.client())
+    pub(super) async fn client_compile_time_info(self: Vc<Self>) -> Result<Vc<CompileTimeInfo>> {
+        let this = self.await?;
+        Ok(get_client_compile_time_info(
+            this.browserslist_query.clone(),
+            this.define_env.client(),
+            self.next_config().strict_csp(),
+        ))
     }
 
     #[turbo_tasks::function]
@@ -699,6 +829,10 @@ impl Project {
             self.client_compile_time_info().environment(),
             self.next_mode(),
             self.module_id_strategy(),
+            self.next_config().external_source_map_threshold(),
+            self.next_config().chunk_load_retries(),
+            self.next_config().source_map_source_template(),
+            self.next_config().chunk_code_wrapping(),
         )
     }
 
@@ -716,6 +850,8 @@ impl Project {
                 self.next_config().computed_asset_prefix(),
                 self.server_compile_time_info().environment(),
                 self.module_id_strategy(),
+                self.next_config().source_map_source_template(),
+                self.next_config().chunk_code_wrapping(),
             )
         } else {
             get_server_chunking_context(
@@ -724,6 +860,8 @@ impl Project {
                 self.node_root(),
                 self.server_compile_time_info().environment(),
                 self.module_id_strategy(),
+                self.next_config().source_map_source_template(),
+                self.next_config().chunk_code_wrapping(),
             )
         }
     }
@@ -742,6 +880,8 @@ impl Project {
                 self.next_config().computed_asset_prefix(),
                 self.edge_compile_time_info().environment(),
                 self.module_id_strategy(),
+                self.next_config().source_map_source_template(),
+                self.next_config().chunk_code_wrapping(),
             )
         } else {
             get_edge_chunking_context(
@@ -750,6 +890,8 @@ impl Project {
                 self.node_root(),
                 self.edge_compile_time_info().environment(),
                 self.module_id_strategy(),
+                self.next_config().source_map_source_template(),
+                self.next_config().chunk_code_wrapping(),
             )
         }
     }
@@ -839,6 +981,14 @@ impl Project {
         emit_event("swcRemoveConsole", remove_console_enabled);
         emit_event("swcEmotion", emotion_enabled);
 
+        // Project structure
+        emit_event("appRouter", self.app_project().await?.is_some());
+        emit_event(
+            "mdx",
+            config.page_extensions.iter().any(|ext| ext == "mdx")
+                || self.next_config().mdx_rs().await?.is_some(),
+        );
+
         Ok(Default::default())
     }
 
@@ -847,6 +997,12 @@ impl Project {
     #[turbo_tasks::function]
     pub async fn entrypoints(self: Vc<Self>) -> Result<Vc<Entrypoints>> {
         self.collect_project_feature_telemetry().await?;
+        validate_image_config(
+            self.next_config(),
+            self.project_path(),
+            self.pages_project().client_module_context(),
+        )
+        .await?;
 
         let mut routes = FxIndexMap::default();
         let app_project = self.app_project();
@@ -891,6 +1047,8 @@ impl Project {
             }
         }
 
+        emit_public_file_conflicts(self.project_path(), &routes).await?;
+
         let pages_document_endpoint = self.pages_project().document_endpoint();
         let pages_app_endpoint = self.pages_project().app_endpoint();
         let pages_error_endpoint = self.pages_project().error_endpoint();
@@ -925,6 +1083,103 @@ impl Project {
         .cell())
     }
 
+    /// Visits the module graph of the endpoints that are built by `next build`
+    /// without generating code or writing any output, so the diagnostics
+    /// emitted while resolving and transforming the modules are collected by
+    /// this task.
+    #[turbo_tasks::function]
+    async fn visit_all_modules(self: Vc<Self>) -> Result<Vc<()>> {
+        let entrypoints = self.entrypoints().await?;
+
+        let mut endpoints = vec![
+            entrypoints.pages_document_endpoint,
+            entrypoints.pages_app_endpoint,
+            entrypoints.pages_error_endpoint,
+        ];
+        for route in entrypoints.routes.values() {
+            match route {
+                Route::Page { html_endpoint, .. } => endpoints.push(*html_endpoint),
+                Route::PageApi { endpoint } | Route::AppRoute { endpoint, .. } => {
+                    endpoints.push(*endpoint)
+                }
+                Route::AppPage(page_routes) => {
+                    endpoints.extend(page_routes.iter().map(|route| route.html_endpoint))
+                }
+                Route::Conflict => {}
+            }
+        }
+        if let Some(middleware) = &entrypoints.middleware {
+            endpoints.push(middleware.endpoint);
+        }
+        if let Some(instrumentation) = &entrypoints.instrumentation {
+            endpoints.push(instrumentation.node_js);
+            endpoints.push(instrumentation.edge);
+        }
+
+        let root_modules = endpoints
+            .into_iter()
+            .map(|endpoint| async move { Ok(endpoint.root_modules().await?.to_vec()) })
+            .try_join()
+            .await?;
+        NonDeterministic::new()
+            .skip_duplicates()
+            .visit(root_modules.into_iter().flatten(), get_referenced_modules)
+            .await
+            .completed()?;
+
+        Ok(Vc::cell(()))
+    }
+
+    /// Aggregates the feature telemetry of the project and of all modules
+    /// that are built, which is computed from the module graph instead of
+    /// scanning the source files.
+    #[turbo_tasks::function]
+    pub async fn feature_usage(self: Vc<Self>) -> Result<Vc<FeatureUsage>> {
+        let visit_all_modules = self.visit_all_modules();
+        visit_all_modules.await?;
+        let diagnostics = visit_all_modules
+            .peek_diagnostics()
+            .await?
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.into_plain())
+            .try_join()
+            .await?;
+
+        Ok(FeatureUsage::from_diagnostics(diagnostics.iter().map(|d| &**d)).cell())
+    }
+
+    /// Writes the `out_dir` of a static export (`output: "export"`),
+    /// relative to the project directory, from the pages prerendered by
+    /// `next build`.
+    #[turbo_tasks::function]
+    pub fn export_static_site(self: Vc<Self>, out_dir: RcStr) -> Vc<()> {
+        export_static_site(self, out_dir)
+    }
+
+    /// Whether the `images.remotePatterns` config allows optimizing the remote
+    /// image at the URL with the given parts, which are the properties of a
+    /// WHATWG `URL`.
+    #[turbo_tasks::function]
+    pub fn image_remote_pattern_matches(
+        self: Vc<Self>,
+        protocol: RcStr,
+        hostname: RcStr,
+        port: RcStr,
+        pathname: RcStr,
+        search: RcStr,
+    ) -> Vc<bool> {
+        image_remote_pattern_matches(
+            self.next_config(),
+            self.project_path(),
+            protocol,
+            hostname,
+            port,
+            pathname,
+            search,
+        )
+    }
+
     #[turbo_tasks::function]
     async fn middleware_context(self: Vc<Self>) -> Result<Vc<Box<dyn AssetContext>>> {
         let mut transitions = vec![];
@@ -1156,37 +1411,58 @@ impl Project {
 
             let client_relative_path = self.client_relative_path();
             let node_root = self.node_root();
+            let this = self.await?;
+
+            // The files need to be recorded before they are written, so they are not pruned by
+            // concurrent emits
+            let assets = *all_output_assets.await?;
+            this.output_pruning
+                .record(
+                    assets,
+                    emitted_paths(assets, node_root, client_relative_path, node_root),
+                    node_root,
+                )
+                .await?;
 
-            if let Some(map) = self.await?.versioned_content_map {
-                let _ = map
-                    .insert_output_assets(
-                        all_output_assets,
-                        node_root,
-                        client_relative_path,
-                        node_root,
-                    )
-                    .resolve()
-                    .await?;
-
-                Ok(Vc::cell(()))
-            } else {
-                let _ = emit_assets(
-                    *all_output_assets.await?,
+            let emit = if let Some(map) = this.versioned_content_map {
+                map.insert_output_assets(
+                    all_output_assets,
                     node_root,
                     client_relative_path,
                     node_root,
                 )
+            } else {
+                emit_assets(assets, node_root, client_relative_path, node_root)
+            };
+            // Delete the files that are no longer emitted, e.g. of removed routes
+            let _ = this
+                .output_pruning
+                .prune(
+                    emit,
+                    node_root,
+                    // Files in the dist directory that are not emitted by turbopack
+                    Glob::new(
+                        "{cache/**,types/**,trace,package.json,BUILD_ID,*.json,server/*.json,\
+                         server/*.js,static/development/**,static/*/_buildManifest.js,static/*/\
+                         _ssgManifest.js}"
+                            .into(),
+                    )?,
+                )
                 .resolve()
                 .await?;
-                Ok(Vc::cell(()))
-            }
+            Ok(Vc::cell(()))
         }
         .instrument(span)
         .await
     }
 
+    /// The content of the chunk group internally known as `identifier`, for
+    /// clients that don't load it from the dev server.
     #[turbo_tasks::function]
-    async fn hmr_content(self: Vc<Self>, identifier: RcStr) -> Result<Vc<OptionVersionedContent>> {
+    pub async fn hmr_content(
+        self: Vc<Self>,
+        identifier: RcStr,
+    ) -> Result<Vc<OptionVersionedContent>> {
         if let Some(map) = self.await?.versioned_content_map {
             let content = map.get(self.client_relative_path().join(identifier.clone()));
             Ok(content)
@@ -1207,7 +1483,7 @@ impl Project {
 
     /// Get the version state for a session. Initialized with the first seen
     /// version in that session.
-    #[turbo_tasks::function]
+    #[turbo_tasks::function(transient)]
     pub async fn hmr_version_state(
         self: Vc<Self>,
         identifier: RcStr,
@@ -1284,7 +1560,7 @@ impl Project {
             modules.push(app_project.client_main_module().to_resolved().await?);
         }
 
-        Ok(Vc::cell(modules))
+        Ok(Vc::cell(modules.into()))
     }
 
     /// Gets the module id strategy for the project.
@@ -1335,6 +1611,17 @@ async fn any_output_changed(
     Ok(Vc::<Completions>::cell(completions).completed())
 }
 
+async fn get_referenced_modules(
+    parent: ResolvedVc<Box<dyn Module>>,
+) -> Result<impl Iterator<Item = ResolvedVc<Box<dyn Module>>> + Send> {
+    Ok(primary_referenced_modules(*parent)
+        .await?
+        .iter()
+        .copied()
+        .collect::<Vec<_>>()
+        .into_iter())
+}
+
 async fn get_referenced_output_assets(
     parent: ResolvedVc<Box<dyn OutputAsset>>,
 ) -> Result<impl Iterator<Item = ResolvedVc<Box<dyn OutputAsset>>> + Send> {
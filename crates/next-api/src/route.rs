@@ -1,6 +1,9 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use turbo_tasks::{debug::ValueDebugFormat, trace::TraceRawVcs, Completion, FxIndexMap, RcStr, Vc};
+use turbo_tasks::{
+    debug::ValueDebugFormat, trace::TraceRawVcs, Completion, FxIndexMap, RcStr, ResolvedVc, Vc,
+};
+use turbo_tasks_fs::FileSystemPath;
 use turbopack_core::module::Modules;
 
 use crate::paths::ServerPath;
@@ -97,3 +100,8 @@ pub enum WrittenEndpoint {
 /// slash)
 #[turbo_tasks::value(transparent)]
 pub struct Routes(FxIndexMap<RcStr, Route>);
+
+/// The file that defines each route, by pathname, e.g. the page of an app
+/// route. Issues about a route are reported at its file.
+#[turbo_tasks::value(transparent)]
+pub struct RouteFiles(FxIndexMap<RcStr, ResolvedVc<FileSystemPath>>);
@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, io::Write, iter::once};
+use std::{collections::HashSet, hash::Hash, io::Write, iter::once};
 
 use anyhow::{bail, Context, Result};
 use indexmap::map::Entry;
@@ -7,10 +7,10 @@ use next_core::{
         ActionLayer, ActionManifestModuleId, ActionManifestWorkerEntry, ServerReferenceManifest,
     },
     util::NextRuntime,
+    SERVER_ACTIONS, SERVER_ACTIONS_FILE,
 };
 use swc_core::{
     atoms::Atom,
-    common::comments::Comments,
     ecma::{
         ast::{Decl, ExportSpecifier, Id, ModuleDecl, ModuleItem, Program},
         utils::find_pat_ids,
@@ -19,7 +19,7 @@ use swc_core::{
 use tracing::Instrument;
 use turbo_tasks::{
     graph::{GraphTraversal, NonDeterministic},
-    FxIndexMap, RcStr, ResolvedVc, TryFlatJoinIterExt, Value, ValueToString, Vc,
+    FxIndexMap, RcStr, ResolvedVc, TryFlatJoinIterExt, TryJoinIterExt, Value, ValueToString, Vc,
 };
 use turbo_tasks_fs::{self, rope::RopeBuilder, File, FileSystemPath};
 use turbopack_core::{
@@ -180,9 +180,12 @@ async fn build_manifest(
     ))
 }
 
-/// Traverses the entire module graph starting from [Module], looking for magic
-/// comment which identifies server actions. Every found server action will be
+/// Traverses the entire module graph starting from [Module], looking for the
+/// server actions that the modules define. Every found server action will be
 /// returned along with the module which exports that action.
+///
+/// Actions of "use server" files whose ids can't reach the client are dropped,
+/// see [get_live_server_actions_files].
 #[turbo_tasks::function]
 async fn get_actions(
     rsc_entry: ResolvedVc<Box<dyn Module>>,
@@ -190,7 +193,7 @@ async fn get_actions(
     asset_context: Vc<Box<dyn AssetContext>>,
 ) -> Result<Vc<AllActions>> {
     async move {
-        let actions = NonDeterministic::new()
+        let modules: Vec<_> = NonDeterministic::new()
             .skip_duplicates()
             .visit(
                 once((ActionLayer::Rsc, rsc_entry)).chain(
@@ -205,15 +208,35 @@ async fn get_actions(
             .completed()?
             .into_inner()
             .into_iter()
+            .collect();
+        let actions = modules
+            .iter()
+            .copied()
             .map(parse_actions_filter_map)
             .try_flat_join()
             .await?;
 
+        let mut client_action_ids = HashSet::new();
+        for ((layer, _), actions_map) in actions.iter() {
+            if *layer == ActionLayer::ActionBrowser {
+                client_action_ids.extend(actions_map.await?.keys().cloned());
+            }
+        }
+        let live_files =
+            get_live_server_actions_files(rsc_entry, &modules, &actions, &client_action_ids)
+                .await?;
+
         // Actions can be imported by both Client and RSC layers, in which case we need
         // to use the RSC layer's module. We do that by merging the hashes (which match
         // in both layers) and preferring the RSC layer's action.
         let mut all_actions: HashToLayerNameModule = FxIndexMap::default();
         for ((layer, module), actions_map) in actions.iter() {
+            if *layer == ActionLayer::Rsc
+                && !live_files.contains(module)
+                && is_server_actions_file(**module).await?
+            {
+                continue;
+            }
             let module = if *layer == ActionLayer::Rsc {
                 *module
             } else {
@@ -261,6 +284,98 @@ async fn to_rsc_context(
     Ok(module)
 }
 
+/// Returns whether the module is a "use server" file, which only exports
+/// Server Actions.
+async fn is_server_actions_file(module: Vc<Box<dyn Module>>) -> Result<bool> {
+    Ok(module
+        .metadata()
+        .await?
+        .get(&SERVER_ACTIONS_FILE)?
+        .unwrap_or_default())
+}
+
+/// Returns the "use server" files of the RSC layer whose action ids can reach
+/// the client.
+///
+/// The ids reach the client when a module that isn't a "use server" file
+/// references the file, e.g. to pass an action as a prop to a client
+/// component, or when the client graph references one of its actions. From
+/// there, they propagate to the "use server" files that a reachable one
+/// references, as an action can return or re-export the actions of another
+/// file.
+async fn get_live_server_actions_files(
+    rsc_entry: ResolvedVc<Box<dyn Module>>,
+    modules: &[(ActionLayer, ResolvedVc<Box<dyn Module>>)],
+    actions: &[((ActionLayer, ResolvedVc<Box<dyn Module>>), Vc<ActionMap>)],
+    client_action_ids: &HashSet<String>,
+) -> Result<HashSet<ResolvedVc<Box<dyn Module>>>> {
+    let rsc_modules = modules
+        .iter()
+        .filter(|(layer, _)| *layer == ActionLayer::Rsc)
+        .map(|&(_, module)| async move {
+            anyhow::Ok((
+                module,
+                is_server_actions_file(*module).await?,
+                primary_referenced_modules(*module).await?,
+            ))
+        })
+        .try_join()
+        .await?;
+    let server_actions_files: HashSet<_> = rsc_modules
+        .iter()
+        .filter(|(_, is_file, _)| *is_file)
+        .map(|(module, ..)| *module)
+        .collect();
+
+    // The entry is exposed as it's rendered.
+    let mut queue = vec![rsc_entry];
+    for (_, is_file, references) in &rsc_modules {
+        if !is_file {
+            queue.extend(references.iter().copied());
+        }
+    }
+    for ((layer, module), actions_map) in actions {
+        if *layer == ActionLayer::Rsc
+            && actions_map
+                .await?
+                .keys()
+                .any(|id| client_action_ids.contains(id))
+        {
+            queue.push(*module);
+        }
+    }
+
+    let references: FxIndexMap<_, _> = rsc_modules
+        .iter()
+        .map(|(module, _, references)| (*module, references.as_slice()))
+        .collect();
+    Ok(reachable_server_actions_files(
+        queue,
+        &server_actions_files,
+        &references,
+    ))
+}
+
+/// Returns the "use server" files reachable from the exposed modules, only
+/// following the references of the reached "use server" files.
+fn reachable_server_actions_files<K: Copy + Eq + Hash>(
+    exposed: Vec<K>,
+    server_actions_files: &HashSet<K>,
+    references: &FxIndexMap<K, &[K]>,
+) -> HashSet<K> {
+    let mut queue = exposed;
+    let mut live = HashSet::new();
+    while let Some(module) = queue.pop() {
+        if !server_actions_files.contains(&module) || !live.insert(module) {
+            continue;
+        }
+        if let Some(references) = references.get(&module) {
+            queue.extend(references.iter().copied());
+        }
+    }
+    live
+}
+
 /// Our graph traversal visitor, which finds the primary modules directly
 /// referenced by parent.
 async fn get_referenced_modules(
@@ -271,32 +386,9 @@ async fn get_referenced_modules(
         .map(|modules| modules.into_iter().map(move |&m| (layer, m)))
 }
 
-/// Parses the Server Actions comment for all exported action function names.
-///
-/// Action names are stored in a leading BlockComment prefixed by
-/// `__next_internal_action_entry_do_not_use__`.
-pub fn parse_server_actions(
-    program: &Program,
-    comments: &dyn Comments,
-) -> Option<BTreeMap<String, String>> {
-    let byte_pos = match program {
-        Program::Module(m) => m.span.lo,
-        Program::Script(s) => s.span.lo,
-    };
-    comments.get_leading(byte_pos).and_then(|comments| {
-        comments.iter().find_map(|c| {
-            c.text
-                .split_once("__next_internal_action_entry_do_not_use__")
-                .and_then(|(_, actions)| match serde_json::from_str(actions) {
-                    Ok(v) => Some(v),
-                    Err(_) => None,
-                })
-        })
-    })
-}
-/// Inspects the comments inside [Module] looking for the magic actions comment.
-/// If found, we return the mapping of every action's hashed id to the name of
-/// the exported action function. If not, we return a None.
+/// Reads the actions that the Server Actions transform found in [Module]. If
+/// found, we return the mapping of every action's hashed id to the name of the
+/// exported action function. If not, we return a None.
 #[turbo_tasks::function]
 async fn parse_actions(module: Vc<Box<dyn Module>>) -> Result<Vc<OptionActionMap>> {
     let Some(ecmascript_asset) =
@@ -318,22 +410,11 @@ async fn parse_actions(module: Vc<Box<dyn Module>>) -> Result<Vc<OptionActionMap
         }
     }
 
-    let original_parsed = ecmascript_asset.parse_original().resolve().await?;
-
-    let ParseResult::Ok {
-        program: original,
-        comments,
-        ..
-    } = &*original_parsed.await?
-    else {
-        // The file might be parse-able, but this is reported separately.
-        return Ok(OptionActionMap::none());
-    };
-
-    let Some(mut actions) = parse_server_actions(original, comments) else {
+    let Some(mut actions) = module.metadata().await?.get(&SERVER_ACTIONS)? else {
         return Ok(OptionActionMap::none());
     };
 
+    let original_parsed = ecmascript_asset.parse_original().resolve().await?;
     let fragment = ecmascript_asset.failsafe_parse().resolve().await?;
 
     if fragment != original_parsed {
@@ -454,3 +535,40 @@ impl OptionActionMap {
         Vc::cell(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use turbo_tasks::FxIndexMap;
+
+    use super::reachable_server_actions_files;
+
+    #[test]
+    fn follows_references_between_server_actions_files() {
+        // The page exposes "returning", whose action returns an action of
+        // "inner". "client" is referenced by the client graph, and "dead" only
+        // by itself.
+        let files = HashSet::from(["returning", "inner", "client", "dead"]);
+        let references = FxIndexMap::from_iter([
+            ("page", &["returning", "util"][..]),
+            ("returning", &["inner"][..]),
+            ("inner", &["util"][..]),
+            ("dead", &["dead", "inner"][..]),
+        ]);
+        let live = reachable_server_actions_files(
+            vec!["returning", "util", "client"],
+            &files,
+            &references,
+        );
+        assert_eq!(live, HashSet::from(["returning", "inner", "client"]));
+    }
+
+    #[test]
+    fn does_not_follow_references_of_other_modules() {
+        let files = HashSet::from(["actions"]);
+        let references = FxIndexMap::from_iter([("util", &["actions"][..])]);
+        let live = reachable_server_actions_files(vec!["util"], &files, &references);
+        assert!(live.is_empty());
+    }
+}
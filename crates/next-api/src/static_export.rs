@@ -0,0 +1,484 @@
+use anyhow::Result;
+use next_core::next_public::{get_public_files, is_dynamic_route};
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{
+    debug::ValueDebugFormat, trace::TraceRawVcs, Completion, Completions, FxIndexMap, FxIndexSet,
+    RcStr, TryJoinIterExt, Vc,
+};
+use turbo_tasks_fs::{
+    to_sys_path, DirectoryContent, DirectoryEntry, FileContent, FileJsonContent,
+    FileSystemEntryType, FileSystemPath,
+};
+use turbopack_core::{
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    output_pruning::files_on_disk,
+};
+
+use crate::{
+    project::Project,
+    route::{Route, RouteFiles},
+};
+
+/// A page that `next build` prerendered, as listed in the
+/// `prerender-manifest.json`.
+#[derive(Serialize, Deserialize, TraceRawVcs, PartialEq, Eq, ValueDebugFormat, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PrerenderedRoute {
+    /// The dynamic route the page was generated from, if any.
+    pub src_route: Option<RcStr>,
+    /// The pathname of the data of the page, e.g. its RSC payload.
+    pub data_route: Option<RcStr>,
+}
+
+/// The prerendered pages by pathname.
+#[turbo_tasks::value(transparent)]
+pub struct PrerenderedRoutes(FxIndexMap<RcStr, PrerenderedRoute>);
+
+/// The files written to the `out` directory, relative to it.
+#[turbo_tasks::value(transparent)]
+struct ExportedFiles(Vec<RcStr>);
+
+#[derive(Deserialize)]
+struct PrerenderManifest {
+    routes: FxIndexMap<RcStr, PrerenderedRoute>,
+}
+
+#[turbo_tasks::function]
+async fn prerendered_routes(node_root: Vc<FileSystemPath>) -> Result<Vc<PrerenderedRoutes>> {
+    let manifest = node_root
+        .join("prerender-manifest.json".into())
+        .read_json()
+        .await?;
+    let FileJsonContent::Content(manifest) = &*manifest else {
+        return Ok(Vc::cell(FxIndexMap::default()));
+    };
+    let manifest: PrerenderManifest = serde_json::from_value(manifest.clone())?;
+    Ok(Vc::cell(manifest.routes))
+}
+
+/// The files of the routes of the project, see [RouteFiles].
+#[turbo_tasks::function]
+async fn route_files(project: Vc<Project>) -> Result<Vc<RouteFiles>> {
+    let mut files = project.pages_project().route_files().await?.clone_value();
+    if let Some(app_project) = &*project.app_project().await? {
+        files.extend(
+            app_project
+                .route_files()
+                .await?
+                .iter()
+                .map(|(k, v)| (k.clone(), *v)),
+        );
+    }
+    Ok(Vc::cell(files))
+}
+
+/// Writes the `out_dir` of a static export (`output: "export"`), relative to
+/// the project directory, from the pages that `next build` prerendered into
+/// the dist directory, the client assets and the `public` directory. Files
+/// in `out_dir` that were not exported, e.g. of removed pages, are deleted.
+///
+/// Every route needs to be statically generatable. An issue is emitted at the
+/// file of each route that isn't, like API routes and dynamic routes without
+/// generated params.
+///
+/// Pages and files are exported by their own tasks, so exporting again after
+/// a change only rewrites the files of the affected pages.
+#[turbo_tasks::function]
+pub async fn export_static_site(project: Vc<Project>, out_dir: RcStr) -> Result<Vc<()>> {
+    let entrypoints = project.entrypoints().await?;
+    let trailing_slash = project.next_config().await?.trailing_slash.unwrap_or(false);
+    let project_path = project.project_path();
+    let node_root = project.node_root();
+    let out_dir = project.output_fs().root().join(out_dir);
+    let prerendered_routes = prerendered_routes(node_root).await?;
+    let route_files = route_files(project).await?;
+    let route_file = |pathname: &RcStr| {
+        route_files
+            .get(pathname)
+            .map_or(project_path, |file| **file)
+    };
+
+    if entrypoints.middleware.is_some() {
+        StaticExportIssue {
+            path: project_path,
+            title: "Middleware cannot be used with \"output: export\"".into(),
+            description: "Middleware runs on the server for every request, which a static export \
+                          doesn't have. Remove the middleware file or the \"output: export\" \
+                          config."
+                .into(),
+        }
+        .cell()
+        .emit();
+    }
+
+    let mut pages = Vec::new();
+    for (pathname, route) in entrypoints.routes.iter() {
+        match route {
+            Route::PageApi { .. } => {
+                StaticExportIssue {
+                    path: route_file(pathname),
+                    title: format!("API route {pathname} cannot be used with \"output: export\"")
+                        .into(),
+                    description: "API routes respond to requests on the server, which a static \
+                                  export doesn't have. Use a route handler of the app directory \
+                                  that is statically generated instead."
+                        .into(),
+                }
+                .cell()
+                .emit();
+                continue;
+            }
+            Route::Conflict => continue,
+            Route::Page { .. } | Route::AppPage(_) | Route::AppRoute { .. } => {}
+        }
+        // `_app`, `_document`, `_error` and `_not-found` are not routes of their own.
+        if pathname.starts_with("/_") {
+            continue;
+        }
+        if is_dynamic_route(pathname) {
+            let generated = prerendered_routes
+                .iter()
+                .filter(|(_, page)| page.src_route.as_ref() == Some(pathname))
+                .map(|(page, prerendered)| (page.clone(), prerendered.data_route.clone()))
+                .collect::<Vec<_>>();
+            if generated.is_empty() {
+                StaticExportIssue {
+                    path: route_file(pathname),
+                    title: format!(
+                        "Dynamic route {pathname} has no generated pages for \"output: export\""
+                    )
+                    .into(),
+                    description: "The pages of dynamic routes need to be known at build time. \
+                                  Export \"generateStaticParams()\" from the page in the app \
+                                  directory, or \"getStaticPaths()\" from the page in the pages \
+                                  directory."
+                        .into(),
+                }
+                .cell()
+                .emit();
+            }
+            pages.extend(generated);
+        } else {
+            let data_route = prerendered_routes
+                .get(pathname)
+                .and_then(|page| page.data_route.clone());
+            pages.push((pathname.clone(), data_route));
+        }
+    }
+
+    let exported = pages
+        .iter()
+        .map(|(page, data_route)| {
+            export_page(
+                node_root,
+                out_dir,
+                page.clone(),
+                data_route.clone(),
+                trailing_slash,
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut exported_files = FxIndexSet::default();
+    for ((page, _), exported) in pages.iter().zip(exported.iter()) {
+        let exported = exported.await?;
+        exported_files.extend(exported.iter().cloned());
+        if exported.is_empty() {
+            // Pages of dynamic routes are reported at the file of the route.
+            let route = prerendered_routes
+                .get(page)
+                .and_then(|page| page.src_route.as_ref())
+                .unwrap_or(page);
+            StaticExportIssue {
+                path: route_file(route),
+                title: format!("Page {page} couldn't be exported with \"output: export\"").into(),
+                description: "The page was not statically generated, because it uses features \
+                              that need a server, like dynamic APIs, \"dynamic = \
+                              'force-dynamic'\" or \"getServerSideProps()\"."
+                    .into(),
+            }
+            .cell()
+            .emit();
+        }
+    }
+
+    if !pages.iter().any(|(page, _)| page == "/404") {
+        let exported = export_page(
+            node_root,
+            out_dir,
+            "/_not-found".into(),
+            None,
+            trailing_slash,
+        )
+        .await?;
+        exported_files.extend(exported.iter().cloned());
+    }
+
+    exported_files.extend(
+        export_directory(
+            node_root.join("static".into()),
+            out_dir,
+            "_next/static".into(),
+        )
+        .await?
+        .iter()
+        .cloned(),
+    );
+    let public_files = get_public_files(project_path)
+        .await?
+        .iter()
+        .map(|(pathname, file)| {
+            let target: RcStr = pathname.trim_start_matches('/').into();
+            async move {
+                export_file(**file, out_dir.join(target.clone())).await?;
+                Ok(target)
+            }
+        })
+        .try_join()
+        .await?;
+    exported_files.extend(public_files);
+
+    delete_stale_files(out_dir, &exported_files).await?;
+
+    Ok(Vc::cell(()))
+}
+
+/// Deletes the files in `out_dir` that were not exported. The directory is
+/// read without tracking it, as the export writes to it.
+async fn delete_stale_files(
+    out_dir: Vc<FileSystemPath>,
+    exported_files: &FxIndexSet<RcStr>,
+) -> Result<()> {
+    let Some(root) = to_sys_path(out_dir).await? else {
+        return Ok(());
+    };
+    let stale = stale_files(&files_on_disk(&root).await?, exported_files);
+    Vc::<Completions>::cell(
+        stale
+            .into_iter()
+            .map(|path| out_dir.join(path).write(FileContent::NotFound.cell()))
+            .collect(),
+    )
+    .completed()
+    .await?;
+    Ok(())
+}
+
+fn stale_files(on_disk: &FxIndexSet<RcStr>, exported: &FxIndexSet<RcStr>) -> Vec<RcStr> {
+    on_disk
+        .iter()
+        .filter(|path| !exported.contains(*path))
+        .cloned()
+        .collect()
+}
+
+/// Exports the files of a prerendered page. Returns no files when the page
+/// was not prerendered.
+#[turbo_tasks::function]
+async fn export_page(
+    node_root: Vc<FileSystemPath>,
+    out_dir: Vc<FileSystemPath>,
+    page: RcStr,
+    data_route: Option<RcStr>,
+    trailing_slash: bool,
+) -> Result<Vc<ExportedFiles>> {
+    let stem = page_file_stem(&page);
+    let mut files = Vec::new();
+    for dir in ["server/app", "server/pages"] {
+        let dir = node_root.join(dir.into());
+
+        let html = dir.join(format!("{stem}.html").into());
+        if is_file(html).await? {
+            files.push((html, exported_path(&page, "html", trailing_slash)));
+        }
+        let rsc = dir.join(format!("{stem}.rsc").into());
+        if is_file(rsc).await? {
+            files.push((rsc, exported_path(&page, "txt", trailing_slash)));
+        }
+        // Route handlers are exported as the body of their response, at their
+        // pathname.
+        let body = dir.join(format!("{stem}.body").into());
+        if page != "/" && is_file(body).await? {
+            files.push((body, page.trim_start_matches('/').to_string()));
+        }
+        if let Some(data_route) = data_route
+            .as_deref()
+            .filter(|data_route| data_route.starts_with("/_next/data/"))
+        {
+            let json = dir.join(format!("{stem}.json").into());
+            if is_file(json).await? {
+                files.push((json, data_route.trim_start_matches('/').to_string()));
+            }
+        }
+
+        if !files.is_empty() {
+            break;
+        }
+    }
+
+    let exported = files
+        .iter()
+        .map(|(_, target)| RcStr::from(target.as_str()))
+        .collect();
+    Vc::<Completions>::cell(
+        files
+            .into_iter()
+            .map(|(source, target)| export_file(source, out_dir.join(target.into())))
+            .collect(),
+    )
+    .completed()
+    .await?;
+    Ok(Vc::cell(exported))
+}
+
+/// Exports the files of the `source` directory into `target` inside
+/// `out_dir`.
+#[turbo_tasks::function]
+async fn export_directory(
+    source: Vc<FileSystemPath>,
+    out_dir: Vc<FileSystemPath>,
+    target: RcStr,
+) -> Result<Vc<ExportedFiles>> {
+    let DirectoryContent::Entries(entries) = &*source.read_dir().await? else {
+        return Ok(Vc::cell(vec![]));
+    };
+    let exported = entries
+        .iter()
+        .map(|(name, entry)| {
+            let target: RcStr = format!("{target}/{name}").into();
+            async move {
+                Ok(match entry {
+                    DirectoryEntry::File(path) | DirectoryEntry::Symlink(path) => {
+                        export_file(**path, out_dir.join(target.clone())).await?;
+                        vec![target]
+                    }
+                    DirectoryEntry::Directory(path) => export_directory(**path, out_dir, target)
+                        .await?
+                        .clone_value(),
+                    DirectoryEntry::Other(_) | DirectoryEntry::Error => vec![],
+                })
+            }
+        })
+        .try_join()
+        .await?
+        .into_iter()
+        .flatten()
+        .collect();
+    Ok(Vc::cell(exported))
+}
+
+/// Copies `source` to `target`. Unchanged files are not written again.
+#[turbo_tasks::function]
+fn export_file(source: Vc<FileSystemPath>, target: Vc<FileSystemPath>) -> Vc<Completion> {
+    target.write(source.read())
+}
+
+async fn is_file(path: Vc<FileSystemPath>) -> Result<bool> {
+    Ok(matches!(
+        &*path.get_type().await?,
+        FileSystemEntryType::File
+    ))
+}
+
+/// The name of the prerendered files of a page in the dist directory without
+/// extension, e.g. `blog/post` for `server/app/blog/post.html`.
+fn page_file_stem(page: &str) -> &str {
+    match page.trim_start_matches('/') {
+        "" => "index",
+        stem => stem,
+    }
+}
+
+/// The path of a file of a page in the `out` directory, e.g. `about.html`, or
+/// `about/index.html` with `trailingSlash`. The not found page is always
+/// exported as `404.html`, which static hosts serve for unknown paths.
+fn exported_path(page: &str, extension: &str, trailing_slash: bool) -> String {
+    match page.trim_start_matches('/') {
+        "" => format!("index.{extension}"),
+        "404" | "_not-found" => format!("404.{extension}"),
+        page if trailing_slash => format!("{page}/index.{extension}"),
+        page => format!("{page}.{extension}"),
+    }
+}
+
+#[turbo_tasks::value(shared)]
+struct StaticExportIssue {
+    path: Vc<FileSystemPath>,
+    title: RcStr,
+    description: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for StaticExportIssue {
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Unsupported.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(self.title.clone()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(StyledString::Text(self.description.clone()).cell()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use turbo_tasks::{FxIndexSet, RcStr};
+
+    use super::{exported_path, page_file_stem, stale_files};
+
+    #[test]
+    fn exports_pages_with_and_without_trailing_slash() {
+        assert_eq!(exported_path("/", "html", false), "index.html");
+        assert_eq!(exported_path("/", "html", true), "index.html");
+        assert_eq!(exported_path("/about", "html", false), "about.html");
+        assert_eq!(exported_path("/about", "txt", true), "about/index.txt");
+        assert_eq!(
+            exported_path("/blog/post", "html", true),
+            "blog/post/index.html"
+        );
+    }
+
+    #[test]
+    fn exports_the_not_found_page_as_404() {
+        assert_eq!(exported_path("/404", "html", true), "404.html");
+        assert_eq!(exported_path("/_not-found", "html", false), "404.html");
+        assert_eq!(exported_path("/_not-found", "txt", false), "404.txt");
+    }
+
+    #[test]
+    fn finds_the_prerendered_files_of_a_page() {
+        assert_eq!(page_file_stem("/"), "index");
+        assert_eq!(page_file_stem("/blog/post"), "blog/post");
+    }
+
+    #[test]
+    fn deletes_files_that_were_not_exported() {
+        let set = |paths: &[&str]| {
+            paths
+                .iter()
+                .map(|path| RcStr::from(*path))
+                .collect::<FxIndexSet<_>>()
+        };
+        let on_disk = set(&["index.html", "removed.html", "_next/static/old.js"]);
+        let exported = set(&["index.html", "_next/static/new.js"]);
+        assert_eq!(
+            stale_files(&on_disk, &exported),
+            vec![RcStr::from("removed.html"), "_next/static/old.js".into()]
+        );
+    }
+}
@@ -10,7 +10,7 @@ use turbo_tasks::{
 use turbo_tasks_fs::FileSystemPath;
 use turbopack_core::{
     asset::Asset,
-    output::{OptionOutputAsset, OutputAsset, OutputAssets},
+    output::{OutputAsset, OutputAssets},
     source_map::{GenerateSourceMap, OptionSourceMap},
     version::OptionVersionedContent,
 };
@@ -190,7 +190,7 @@ impl VersionedContentMap {
     pub async fn get_asset(
         self: Vc<Self>,
         path: ResolvedVc<FileSystemPath>,
-    ) -> Result<Vc<OptionOutputAsset>> {
+    ) -> Result<Vc<Option<ResolvedVc<Box<dyn OutputAsset>>>>> {
         let result = self.raw_get(*path).await?;
         if let Some(MapEntry {
             assets_operation: _,
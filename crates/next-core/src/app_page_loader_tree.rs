@@ -23,6 +23,24 @@ use crate::{
     next_image::module::{BlurPlaceholderMode, StructuredImageModuleType},
 };
 
+/// The modules of a segment in the order of next-app-loader, as it determines
+/// the evaluation order of the modules (and therefore the order of their CSS).
+fn segment_modules(modules: &AppDirModules) -> [(AppDirModuleType, Option<Vc<FileSystemPath>>); 8] {
+    [
+        (AppDirModuleType::Layout, modules.layout),
+        (AppDirModuleType::Template, modules.template),
+        (AppDirModuleType::Error, modules.error),
+        (AppDirModuleType::Loading, modules.loading),
+        (AppDirModuleType::NotFound, modules.not_found),
+        (
+            AppDirModuleType::GlobalError,
+            modules.global_error.map(|global_error| *global_error),
+        ),
+        (AppDirModuleType::Page, modules.page),
+        (AppDirModuleType::DefaultPage, modules.default),
+    ]
+}
+
 pub struct AppPageLoaderTreeBuilder {
     base: BaseLoaderTreeBuilder,
     loader_tree_code: String,
@@ -309,45 +327,19 @@ impl AppPageLoaderTreeBuilder {
 
         let temp_loader_tree_code = take(&mut self.loader_tree_code);
 
-        let AppDirModules {
-            page,
-            default,
-            error,
-            global_error,
-            layout,
-            loading,
-            template,
-            not_found,
-            metadata,
-            route: _,
-        } = &modules;
-
         // Ensure global metadata being written only once at the root level
         // Otherwise child pages will have redundant metadata
         let global_metadata = &*global_metadata.await?;
         self.write_metadata(
             app_page,
-            metadata,
+            &modules.metadata,
             if root { Some(global_metadata) } else { None },
         )
         .await?;
 
-        self.write_modules_entry(AppDirModuleType::Layout, *layout)
-            .await?;
-        self.write_modules_entry(AppDirModuleType::Error, *error)
-            .await?;
-        self.write_modules_entry(AppDirModuleType::Loading, *loading)
-            .await?;
-        self.write_modules_entry(AppDirModuleType::Template, *template)
-            .await?;
-        self.write_modules_entry(AppDirModuleType::NotFound, *not_found)
-            .await?;
-        self.write_modules_entry(AppDirModuleType::Page, *page)
-            .await?;
-        self.write_modules_entry(AppDirModuleType::DefaultPage, *default)
-            .await?;
-        self.write_modules_entry(AppDirModuleType::GlobalError, global_error.map(|err| *err))
-            .await?;
+        for (module_type, module) in segment_modules(modules) {
+            self.write_modules_entry(module_type, module).await?;
+        }
 
         let modules_code = replace(&mut self.loader_tree_code, temp_loader_tree_code);
 
@@ -412,3 +404,32 @@ impl AppPageLoaderTreeModule {
 }
 
 pub const GLOBAL_ERROR: &str = "GLOBAL_ERROR_MODULE";
+
+#[cfg(test)]
+mod tests {
+    use super::segment_modules;
+    use crate::{app_structure::AppDirModules, base_loader_tree::AppDirModuleType};
+
+    #[test]
+    fn orders_segment_modules_like_next_app_loader() {
+        let order = segment_modules(&AppDirModules::default())
+            .map(|(module_type, module)| {
+                assert!(module.is_none());
+                module_type
+            })
+            .to_vec();
+        assert_eq!(
+            order,
+            vec![
+                AppDirModuleType::Layout,
+                AppDirModuleType::Template,
+                AppDirModuleType::Error,
+                AppDirModuleType::Loading,
+                AppDirModuleType::NotFound,
+                AppDirModuleType::GlobalError,
+                AppDirModuleType::Page,
+                AppDirModuleType::DefaultPage,
+            ]
+        );
+    }
+}
@@ -757,6 +757,80 @@ impl Issue for DuplicateParallelRouteIssue {
     }
 }
 
+/// Two pages in different route groups that resolve to the same URL path, as
+/// route groups don't affect the path.
+#[turbo_tasks::value]
+struct RouteGroupConflictIssue {
+    app_dir: Vc<FileSystemPath>,
+    page: AppPage,
+    previous_page: AppPage,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for RouteGroupConflictIssue {
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.app_dir.join(self.page.to_string().into())
+    }
+
+    #[turbo_tasks::function]
+    fn stage(self: Vc<Self>) -> Vc<IssueStage> {
+        IssueStage::ProcessModule.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(self: Vc<Self>) -> Vc<StyledString> {
+        StyledString::Text(
+            "You cannot have two pages in different route groups that resolve to the same path."
+                .into(),
+        )
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            route_group_conflict_description(&self.page, &self.previous_page).cell(),
+        ))
+    }
+}
+
+fn route_group_conflict_description(page: &AppPage, previous_page: &AppPage) -> StyledString {
+    StyledString::Stack(vec![
+        StyledString::Line(vec![
+            StyledString::Text("Both ".into()),
+            StyledString::Code(page_file(previous_page)),
+            StyledString::Text(" and ".into()),
+            StyledString::Code(page_file(page)),
+            StyledString::Text(" resolve to ".into()),
+            StyledString::Code(AppPath::from(page.clone()).to_string().into()),
+            StyledString::Text(".".into()),
+        ]),
+        StyledString::Text(
+            "Route groups like `(group)` don't affect the URL path, so the pages of different \
+             groups need to resolve to different paths."
+                .into(),
+        ),
+    ])
+}
+
+/// The page file of `page`, without extension, e.g. `/(shop)/cart/page`.
+fn page_file(page: &AppPage) -> RcStr {
+    if page.is_root() {
+        "/page".into()
+    } else {
+        format!("{page}/page").into()
+    }
+}
+
+/// Whether two pages that resolve to the same path differ in their route
+/// groups, instead of being two parallel pages.
+fn is_route_group_conflict(page: &AppPage, previous_page: &AppPage) -> bool {
+    page.iter()
+        .chain(previous_page.iter())
+        .any(|segment| matches!(segment, PageSegment::Group(..)))
+}
+
 fn page_path_except_parallel(loader_tree: &AppPageLoaderTree) -> Option<AppPage> {
     if loader_tree.page.iter().any(|v| {
         matches!(
@@ -790,12 +864,22 @@ fn check_duplicate(
     if let Some(page_path) = page_path {
         if let Some(prev) = duplicate.insert(AppPath::from(page_path.clone()), page_path.clone()) {
             if prev != page_path {
-                DuplicateParallelRouteIssue {
-                    app_dir,
-                    page: loader_tree.page.clone(),
+                if is_route_group_conflict(&page_path, &prev) {
+                    RouteGroupConflictIssue {
+                        app_dir,
+                        page: page_path,
+                        previous_page: prev,
+                    }
+                    .cell()
+                    .emit();
+                } else {
+                    DuplicateParallelRouteIssue {
+                        app_dir,
+                        page: loader_tree.page.clone(),
+                    }
+                    .cell()
+                    .emit();
                 }
-                .cell()
-                .emit();
             }
         }
     }
@@ -824,6 +908,7 @@ async fn directory_tree_to_loader_tree(
         plain_tree,
         app_page,
         for_app_path,
+        false,
     )?;
 
     Ok(Vc::cell(tree.map(AppPageLoaderTree::resolved_cell)))
@@ -837,6 +922,8 @@ fn directory_tree_to_loader_tree_internal(
     app_page: AppPage,
     // the page this loader tree is constructed for
     for_app_path: AppPath,
+    // whether the root directory of the app dir has its own `not-found` file
+    has_root_not_found: bool,
 ) -> Result<Option<AppPageLoaderTree>> {
     let app_path = AppPath::from(app_page.clone());
 
@@ -856,12 +943,27 @@ fn directory_tree_to_loader_tree_internal(
     // the path).
     let is_root_layout = app_path.is_root() && modules.layout.is_some();
 
-    if (is_root_directory || is_root_layout) && modules.not_found.is_none() {
+    let has_root_not_found = if is_root_directory {
+        modules.not_found.is_some()
+    } else {
+        has_root_not_found
+    };
+
+    // An alternative root layout doesn't get the default not-found boundary when
+    // the app already has a root not-found, the root one is used instead.
+    if (is_root_directory || (is_root_layout && !has_root_not_found)) && modules.not_found.is_none()
+    {
         modules.not_found = Some(
             get_next_package(app_dir).join("dist/client/components/not-found-error.js".into()),
         );
     }
 
+    // `global-error` is only a convention in the root of the app dir, see
+    // `directory_tree_to_entrypoints_internal_untraced` for the warning.
+    if !is_root_directory {
+        modules.global_error = None;
+    }
+
     let mut tree = AppPageLoaderTree {
         page: app_page.clone(),
         segment: directory_name.clone(),
@@ -902,6 +1004,11 @@ fn directory_tree_to_loader_tree_internal(
 
     let mut duplicate = FxHashMap::default();
 
+    // A page in a route group conflicts with the page of this directory too.
+    if let Some(children) = tree.parallel_routes.get("children") {
+        check_duplicate(&mut duplicate, children, app_dir);
+    }
+
     for (subdir_name, subdirectory) in &directory_tree.subdirectories {
         let parallel_route_key = match_parallel_route(subdir_name);
 
@@ -922,6 +1029,7 @@ fn directory_tree_to_loader_tree_internal(
             subdirectory,
             child_app_page.clone(),
             for_app_path.clone(),
+            has_root_not_found,
         )?;
 
         if let Some(illegal_path) = subtree.as_ref().and(illegal_path_error) {
@@ -1086,6 +1194,26 @@ async fn directory_tree_to_entrypoints_internal_untraced(
 
     let subdirectories = &directory_tree.subdirectories;
     let modules = &directory_tree.modules;
+
+    if let Some(global_error) = modules.global_error {
+        if !app_page.is_root() {
+            DirectoryTreeIssue {
+                severity: IssueSeverity::Warning.cell(),
+                app_dir,
+                message: StyledString::Text(
+                    format!(
+                        "{} is ignored. `global-error` is only supported in the root of the app \
+                         directory.",
+                        global_error.to_string().await?
+                    )
+                    .into(),
+                )
+                .cell(),
+            }
+            .cell()
+            .emit();
+        }
+    }
     // Route can have its own segment config, also can inherit from the layout root
     // segment config. https://nextjs.org/docs/app/building-your-application/rendering/edge-and-nodejs-runtimes#segment-runtime-option
     // Pass down layouts from each tree to apply segment config when adding route.
@@ -1389,3 +1517,320 @@ impl Issue for DirectoryTreeIssue {
         Vc::cell(Some(self.message))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, sync::Once};
+
+    use anyhow::{bail, Result};
+    use turbo_tasks::{RcStr, ReadRef, TurboTasks, ValueToString, Vc};
+    use turbo_tasks_fs::{DiskFileSystem, FileSystem, FileSystemPath};
+    use turbo_tasks_memory::MemoryBackend;
+    use turbopack_core::issue::{IssueDescriptionExt, IssueSeverity, StyledString};
+
+    use super::{
+        directory_tree_to_loader_tree, get_directory_tree, get_entrypoints, get_global_metadata,
+        is_route_group_conflict, page_file, route_group_conflict_description, AppPageLoaderTree,
+        Entrypoint,
+    };
+    use crate::next_app::{AppPage, AppPath};
+
+    const DEFAULT_NOT_FOUND: &str = "node_modules/next/dist/client/components/not-found-error.js";
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    /// Writes `files` to `root`, or removes the files without content.
+    fn write_files(root: &Path, files: &[(&str, Option<&str>)]) {
+        for (path, content) in files {
+            let path = root.join(path);
+            match content {
+                Some(content) => {
+                    fs::create_dir_all(path.parent().unwrap()).unwrap();
+                    fs::write(path, content).unwrap();
+                }
+                None if path.exists() => fs::remove_file(path).unwrap(),
+                None => {}
+            }
+        }
+    }
+
+    /// Creates a project with the `next` package and `files`.
+    fn project(files: &[(&str, Option<&str>)]) -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        write_files(
+            root.path(),
+            &[("node_modules/next/package.json", Some(r#"{"name":"next"}"#))],
+        );
+        write_files(root.path(), files);
+        root
+    }
+
+    fn project_fs(root: &Path) -> Vc<DiskFileSystem> {
+        DiskFileSystem::new("project".into(), root.to_string_lossy().into(), vec![])
+    }
+
+    fn page_extensions() -> Vc<Vec<RcStr>> {
+        Vc::cell(vec!["tsx".into()])
+    }
+
+    async fn loader_tree(
+        app_dir: Vc<FileSystemPath>,
+        page: &str,
+    ) -> Result<ReadRef<AppPageLoaderTree>> {
+        let entrypoints = get_entrypoints(app_dir, page_extensions())
+            .strongly_consistent()
+            .await?;
+        let Some(Entrypoint::AppPage { loader_tree, .. }) =
+            entrypoints.get(&AppPath::from(AppPage::parse(page)?))
+        else {
+            bail!("{page} is not a page");
+        };
+        Ok(loader_tree.await?)
+    }
+
+    async fn path(path: Option<Vc<FileSystemPath>>) -> Result<Option<RcStr>> {
+        Ok(match path {
+            Some(path) => Some(path.await?.path.clone()),
+            None => None,
+        })
+    }
+
+    /// The child of `tree` in the `children` slot.
+    fn children(tree: &AppPageLoaderTree) -> &AppPageLoaderTree {
+        &tree.parallel_routes["children"]
+    }
+
+    #[test]
+    fn detects_route_group_conflicts() {
+        let shop = AppPage::parse("/(shop)/cart").unwrap();
+        let marketing = AppPage::parse("/(marketing)/cart").unwrap();
+        let plain = AppPage::parse("/cart").unwrap();
+        assert!(is_route_group_conflict(&shop, &marketing));
+        assert!(is_route_group_conflict(&shop, &plain));
+        assert!(!is_route_group_conflict(&plain, &plain));
+    }
+
+    #[test]
+    fn describes_both_pages() {
+        assert_eq!(page_file(&AppPage::new()), "/page");
+        let shop = AppPage::parse("/(shop)/cart").unwrap();
+        let marketing = AppPage::parse("/(marketing)/cart").unwrap();
+        assert_eq!(page_file(&shop), "/(shop)/cart/page");
+
+        assert_eq!(
+            route_group_conflict_description(&shop, &marketing),
+            StyledString::Stack(vec![
+                StyledString::Line(vec![
+                    StyledString::Text("Both ".into()),
+                    StyledString::Code("/(marketing)/cart/page".into()),
+                    StyledString::Text(" and ".into()),
+                    StyledString::Code("/(shop)/cart/page".into()),
+                    StyledString::Text(" resolve to ".into()),
+                    StyledString::Code("/cart".into()),
+                    StyledString::Text(".".into()),
+                ]),
+                StyledString::Text(
+                    "Route groups like `(group)` don't affect the URL path, so the pages of \
+                     different groups need to resolve to different paths."
+                        .into(),
+                ),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_route_groups_that_resolve_to_the_same_path() {
+        register();
+        let root = project(&[
+            ("app/layout.tsx", Some("")),
+            ("app/(shop)/cart/page.tsx", Some("")),
+            ("app/(marketing)/cart/page.tsx", Some("")),
+        ]);
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            let app_dir = project_fs(root.path()).root().join("app".into());
+            let tree = directory_tree_to_loader_tree(
+                app_dir,
+                get_global_metadata(app_dir, page_extensions()),
+                "".into(),
+                get_directory_tree(app_dir, page_extensions()),
+                AppPage::new(),
+                AppPath::from(AppPage::parse("/cart")?),
+            );
+            tree.strongly_consistent().await?;
+            let issues = tree
+                .peek_issues_with_path()
+                .await?
+                .get_plain_issues()
+                .await?;
+            let [issue] = &issues[..] else {
+                panic!("expected one issue, got {issues:?}");
+            };
+            assert_eq!(
+                issue.title,
+                StyledString::Text(
+                    "You cannot have two pages in different route groups that resolve to the same \
+                     path."
+                        .into()
+                )
+            );
+            assert_eq!(
+                issue.description,
+                Some(route_group_conflict_description(
+                    &AppPage::parse("/(shop)/cart")?,
+                    &AppPage::parse("/(marketing)/cart")?,
+                ))
+            );
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn injects_the_default_not_found_into_root_layouts() {
+        register();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+
+        let root = project(&[("app/layout.tsx", Some("")), ("app/page.tsx", Some(""))]);
+        tt.run_once(async move {
+            let app_dir = project_fs(root.path()).root().join("app".into());
+            let tree = loader_tree(app_dir, "/").await?;
+            assert_eq!(
+                path(tree.modules.not_found).await?.as_deref(),
+                Some(DEFAULT_NOT_FOUND)
+            );
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+
+        // An alternative root layout in a route group uses the not-found of the root
+        // directory when there is one.
+        for (root_not_found, group_not_found) in [
+            (Some("app/not-found.tsx"), None),
+            (None, Some(DEFAULT_NOT_FOUND)),
+        ] {
+            let root = project(&[
+                ("app/(group)/layout.tsx", Some("")),
+                ("app/(group)/page.tsx", Some("")),
+                ("app/not-found.tsx", root_not_found.map(|_| "")),
+            ]);
+            tt.run_once(async move {
+                let app_dir = project_fs(root.path()).root().join("app".into());
+                let tree = loader_tree(app_dir, "/").await?;
+                assert_eq!(
+                    path(tree.modules.not_found).await?.as_deref(),
+                    Some(root_not_found.unwrap_or(DEFAULT_NOT_FOUND))
+                );
+                let group = children(&tree);
+                assert_eq!(&*group.segment, "(group)");
+                assert_eq!(
+                    path(group.modules.not_found).await?.as_deref(),
+                    group_not_found
+                );
+                anyhow::Ok(())
+            })
+            .await
+            .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn only_uses_global_error_in_the_root_directory() {
+        register();
+        let root = project(&[
+            ("app/layout.tsx", Some("")),
+            ("app/global-error.tsx", Some("")),
+            ("app/dashboard/page.tsx", Some("")),
+            ("app/dashboard/global-error.tsx", Some("")),
+        ]);
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            let app_dir = project_fs(root.path()).root().join("app".into());
+            let tree = loader_tree(app_dir, "/dashboard").await?;
+            assert_eq!(
+                path(tree.modules.global_error.map(|global_error| *global_error))
+                    .await?
+                    .as_deref(),
+                Some("app/global-error.tsx")
+            );
+            let dashboard = children(&tree);
+            assert_eq!(&*dashboard.segment, "dashboard");
+            assert!(dashboard.modules.global_error.is_none());
+
+            let entrypoints = get_entrypoints(app_dir, page_extensions());
+            entrypoints.strongly_consistent().await?;
+            let issues = entrypoints
+                .peek_issues_with_path()
+                .await?
+                .get_plain_issues()
+                .await?;
+            let [issue] = &issues[..] else {
+                panic!("expected one issue, got {issues:?}");
+            };
+            assert_eq!(issue.severity, IssueSeverity::Warning);
+            assert_eq!(
+                issue.description,
+                Some(StyledString::Text(
+                    format!(
+                        "{} is ignored. `global-error` is only supported in the root of the app \
+                         directory.",
+                        app_dir
+                            .join("dashboard/global-error.tsx".into())
+                            .to_string()
+                            .await?
+                    )
+                    .into()
+                ))
+            );
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn updates_the_loader_tree_when_boundaries_are_added_or_removed() {
+        register();
+        let root = project(&[("app/layout.tsx", Some("")), ("app/page.tsx", Some(""))]);
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+
+        for (boundary, not_found, global_error) in [
+            (None, DEFAULT_NOT_FOUND, None),
+            (Some(""), "app/not-found.tsx", Some("app/global-error.tsx")),
+            (None, DEFAULT_NOT_FOUND, None),
+        ] {
+            write_files(
+                root.path(),
+                &[
+                    ("app/not-found.tsx", boundary),
+                    ("app/global-error.tsx", boundary),
+                ],
+            );
+            let root = root.path().to_path_buf();
+            tt.run_once(async move {
+                let fs = project_fs(&root);
+                // Like the watcher does after the files changed.
+                fs.await?.invalidate();
+                let tree = loader_tree(fs.root().join("app".into()), "/").await?;
+                assert_eq!(
+                    path(tree.modules.not_found).await?.as_deref(),
+                    Some(not_found)
+                );
+                assert_eq!(
+                    path(tree.modules.global_error.map(|global_error| *global_error))
+                        .await?
+                        .as_deref(),
+                    global_error
+                );
+                anyhow::Ok(())
+            })
+            .await
+            .unwrap();
+        }
+    }
+}
@@ -8,6 +8,7 @@ use turbo_tasks_fs::{rebase, FileSystemPath};
 use turbopack_core::{
     asset::Asset,
     output::{OutputAsset, OutputAssets},
+    output_pruning::EmittedPaths,
 };
 
 /// Emits all assets transitively reachable from the given chunks, that are
@@ -74,6 +75,38 @@ pub async fn emit_assets(
     .completed())
 }
 
+/// The paths that [emit_assets] writes the given assets to.
+#[turbo_tasks::function]
+pub async fn emitted_paths(
+    assets: Vc<OutputAssets>,
+    node_root: Vc<FileSystemPath>,
+    client_relative_path: Vc<FileSystemPath>,
+    client_output_path: Vc<FileSystemPath>,
+) -> Result<Vc<EmittedPaths>> {
+    Ok(Vc::cell(
+        assets
+            .await?
+            .iter()
+            .map(|asset| async move {
+                let path = asset.ident().path();
+                let path_ref = path.await?;
+                Ok(if path_ref.is_inside_ref(&*node_root.await?) {
+                    Some(path.to_resolved().await?)
+                } else if path_ref.is_inside_ref(&*client_relative_path.await?) {
+                    Some(
+                        rebase(path, client_relative_path, client_output_path)
+                            .to_resolved()
+                            .await?,
+                    )
+                } else {
+                    None
+                })
+            })
+            .try_flat_join()
+            .await?,
+    ))
+}
+
 #[turbo_tasks::function]
 fn emit(asset: Vc<Box<dyn OutputAsset>>) -> Vc<Completion> {
     asset.content().write(asset.ident().path())
@@ -28,11 +28,13 @@ mod next_image;
 mod next_import_map;
 pub mod next_manifests;
 pub mod next_pages;
+pub mod next_public;
 mod next_route_matcher;
 pub mod next_server;
 mod next_server_component;
 mod next_shared;
 pub mod next_telemetry;
+pub mod output_file_tracing;
 mod page_loader;
 pub mod pages_structure;
 pub mod tracing_presets;
@@ -43,12 +45,17 @@ pub mod util;
 pub use app_segment_config::{
     parse_segment_config_from_loader_tree, parse_segment_config_from_source,
 };
-pub use emit::{all_assets_from_entries, emit_all_assets, emit_assets};
+pub use emit::{all_assets_from_entries, emit_all_assets, emit_assets, emitted_paths};
 pub use next_edge::context::{
     get_edge_chunking_context, get_edge_chunking_context_with_client_assets,
     get_edge_compile_time_info, get_edge_resolve_options_context,
 };
+pub use next_image::{
+    image_remote_pattern_matchers, image_remote_pattern_matches, validate_image_config,
+    ImageRemotePatternMatcher, ImageRemotePatternMatchers,
+};
 pub use next_import_map::get_next_package;
+pub use next_shared::transforms::server_actions::{SERVER_ACTIONS, SERVER_ACTIONS_FILE};
 pub use page_loader::{create_page_loader_entry_module, PageLoaderAsset};
 pub use util::{get_asset_path_from_pathname, pathname_for_path, PathType};
 
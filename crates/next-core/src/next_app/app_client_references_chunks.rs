@@ -344,6 +344,7 @@ pub async fn get_app_server_reference_modules(
                 })
             })
             .try_flat_join()
-            .await?,
+            .await?
+            .into(),
     ))
 }
@@ -13,7 +13,7 @@ use turbopack::{
 };
 use turbopack_browser::{react_refresh::assert_can_resolve_react_refresh, BrowserChunkingContext};
 use turbopack_core::{
-    chunk::{module_id_strategies::ModuleIdStrategy, ChunkingContext},
+    chunk::{module_id_strategies::ModuleIdStrategy, ChunkCodeWrapping, ChunkingContext},
     compile_time_info::{
         CompileTimeDefineValue, CompileTimeDefines, CompileTimeInfo, DefineableNameSegment,
         FreeVarReference, FreeVarReferences,
@@ -23,6 +23,7 @@ use turbopack_core::{
     free_var_references,
     resolve::{parse::Request, pattern::Pattern},
 };
+use turbopack_ecmascript_runtime::ChunkLoadRetry;
 use turbopack_node::{
     execution_context::ExecutionContext,
     transforms::postcss::{PostCssConfigLocation, PostCssTransformOptions},
@@ -42,8 +43,8 @@ use crate::{
     },
     next_shared::{
         resolve::{
-            get_invalid_server_only_resolve_plugin, ModuleFeatureReportResolvePlugin,
-            NextSharedRuntimeResolvePlugin,
+            get_invalid_server_only_resolve_plugin, get_remote_url_resolve_plugins,
+            ModuleFeatureReportResolvePlugin, NextSharedRuntimeResolvePlugin,
         },
         transforms::{
             emotion::get_emotion_transform_rule,
@@ -109,22 +110,26 @@ async fn next_client_free_vars(define_env: Vc<EnvMap>) -> Result<Vc<FreeVarRefer
 }
 
 #[turbo_tasks::function]
-pub fn get_client_compile_time_info(
+pub async fn get_client_compile_time_info(
     browserslist_query: RcStr,
     define_env: Vc<EnvMap>,
-) -> Vc<CompileTimeInfo> {
-    CompileTimeInfo::builder(Environment::new(Value::new(ExecutionEnvironment::Browser(
-        BrowserEnvironment {
-            dom: true,
-            web_worker: false,
-            service_worker: false,
-            browserslist_query: browserslist_query.to_owned(),
-        }
-        .into(),
-    ))))
-    .defines(next_client_defines(define_env))
-    .free_var_references(next_client_free_vars(define_env))
-    .cell()
+    strict_csp: Vc<bool>,
+) -> Result<Vc<CompileTimeInfo>> {
+    Ok(
+        CompileTimeInfo::builder(Environment::new(Value::new(ExecutionEnvironment::Browser(
+            BrowserEnvironment {
+                dom: true,
+                web_worker: false,
+                service_worker: false,
+                browserslist_query: browserslist_query.to_owned(),
+                strict_csp: *strict_csp.await?,
+            }
+            .into(),
+        ))))
+        .defines(next_client_defines(define_env))
+        .free_var_references(next_client_free_vars(define_env))
+        .cell(),
+    )
 }
 
 #[turbo_tasks::value(shared, serialization = "auto_for_input")]
@@ -160,6 +165,24 @@ pub async fn get_client_resolve_options_context(
             .to_resolved()
             .await?;
     let custom_conditions = vec![mode.await?.condition().into()];
+    let mut before_resolve_plugins = vec![
+        ResolvedVc::upcast(
+            get_invalid_server_only_resolve_plugin(project_path)
+                .to_resolved()
+                .await?,
+        ),
+        ResolvedVc::upcast(
+            ModuleFeatureReportResolvePlugin::new(project_path)
+                .to_resolved()
+                .await?,
+        ),
+        ResolvedVc::upcast(
+            NextFontLocalResolvePlugin::new(project_path)
+                .to_resolved()
+                .await?,
+        ),
+    ];
+    before_resolve_plugins.extend(get_remote_url_resolve_plugins(project_path, next_config).await?);
     let module_options_context = ResolveOptionsContext {
         enable_node_modules: Some(project_path.root().to_resolved().await?),
         custom_conditions,
@@ -168,23 +191,7 @@ pub async fn get_client_resolve_options_context(
         resolved_map: Some(next_client_resolved_map),
         browser: true,
         module: true,
-        before_resolve_plugins: vec![
-            ResolvedVc::upcast(
-                get_invalid_server_only_resolve_plugin(project_path)
-                    .to_resolved()
-                    .await?,
-            ),
-            ResolvedVc::upcast(
-                ModuleFeatureReportResolvePlugin::new(project_path)
-                    .to_resolved()
-                    .await?,
-            ),
-            ResolvedVc::upcast(
-                NextFontLocalResolvePlugin::new(project_path)
-                    .to_resolved()
-                    .await?,
-            ),
-        ],
+        before_resolve_plugins,
         after_resolve_plugins: vec![ResolvedVc::upcast(
             NextSharedRuntimeResolvePlugin::new(project_path)
                 .to_resolved()
@@ -309,6 +316,15 @@ pub async fn get_client_module_options_context(
     let module_options_context = ModuleOptionsContext {
         ecmascript: EcmascriptOptionsContext {
             enable_typeof_window_inlining: Some(TypeofWindow::Object),
+            enable_module_federation: *next_config.module_federation().await?,
+            extract_styled_jsx: *next_config.extract_styled_jsx().await?,
+            report_slow_modules: next_mode.is_development(),
+            exclude_optional_requires: Some(
+                next_config
+                    .exclude_optional_requires()
+                    .to_resolved()
+                    .await?,
+            ),
             ..Default::default()
         },
         preset_env_versions: Some(env),
@@ -386,6 +402,10 @@ pub async fn get_client_chunking_context(
     environment: Vc<Environment>,
     mode: Vc<NextMode>,
     module_id_strategy: Vc<Box<dyn ModuleIdStrategy>>,
+    external_source_map_threshold: Vc<Option<u64>>,
+    chunk_load_retries: Vc<u32>,
+    source_map_source_template: Vc<Option<RcStr>>,
+    chunk_code_wrapping: Vc<ChunkCodeWrapping>,
 ) -> Result<Vc<Box<dyn ChunkingContext>>> {
     let next_mode = mode.await?;
     let mut builder = BrowserChunkingContext::builder(
@@ -400,10 +420,21 @@ pub async fn get_client_chunking_context(
     .chunk_base_path(asset_prefix)
     .minify_type(next_mode.minify_type())
     .asset_base_path(asset_prefix)
-    .module_id_strategy(module_id_strategy);
+    // Like webpack, refer to assets in CSS by their url with the asset prefix, so
+    // they work no matter where the CSS chunk is served from.
+    .asset_urls_in_css()
+    .module_id_strategy(module_id_strategy)
+    .chunk_load_retry(ChunkLoadRetry::with_max_retries(*chunk_load_retries.await?))
+    .chunk_code_wrapping((*chunk_code_wrapping.await?).clone());
 
     if next_mode.is_development() {
         builder = builder.hot_module_replacement().use_file_source_map_uris();
+        if let Some(threshold) = *external_source_map_threshold.await? {
+            builder = builder.external_source_map_threshold(threshold);
+        }
+    }
+    if let Some(template) = &*source_map_source_template.await? {
+        builder = builder.source_map_source_template(template.clone());
     }
 
     Ok(Vc::upcast(builder.build()))
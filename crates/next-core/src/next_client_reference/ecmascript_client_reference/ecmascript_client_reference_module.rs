@@ -60,7 +60,7 @@ impl Module for EcmascriptClientReferenceModule {
     fn additional_layers_modules(&self) -> Vc<Modules> {
         let client_module = ResolvedVc::upcast(self.client_module);
         let ssr_module = ResolvedVc::upcast(self.ssr_module);
-        Vc::cell(vec![client_module, ssr_module])
+        Vc::cell(vec![client_module, ssr_module].into())
     }
 }
 
@@ -7,13 +7,20 @@ use turbo_tasks::{trace::TraceRawVcs, FxIndexMap, RcStr, ResolvedVc, TaskInput,
 use turbo_tasks_env::EnvMap;
 use turbo_tasks_fs::FileSystemPath;
 use turbopack::module_options::{
-    module_options_context::MdxTransformOptions, LoaderRuleItem, OptionWebpackRules,
+    module_options_context::MdxTransformOptions, LoaderEnforce, LoaderRuleItem, OptionWebpackRules,
 };
 use turbopack_core::{
+    chunk::ChunkCodeWrapping,
     issue::{Issue, IssueSeverity, IssueStage, OptionStyledString, StyledString},
     resolve::ResolveAliasMap,
 };
-use turbopack_ecmascript::{OptionTreeShaking, TreeShakingMode};
+use turbopack_ecmascript::{
+    references::module_federation::{
+        ModuleFederationOptions, ModuleFederationRemote, ModuleFederationShared,
+        OptionModuleFederationOptions,
+    },
+    OptionTreeShaking, TreeShakingMode,
+};
 use turbopack_ecmascript_plugins::transform::{
     emotion::EmotionTransformConfig, relay::RelayConfig,
     styled_components::StyledComponentsTransformConfig,
@@ -72,6 +79,15 @@ pub struct NextConfig {
     pub dev_indicators: Option<DevIndicatorsConfig>,
     pub output: Option<OutputType>,
 
+    /// Globs of files to add to the traced files of routes, keyed by globs
+    /// matching the routes.
+    ///
+    /// [API Reference](https://nextjs.org/docs/app/api-reference/next-config-js/output#caveats)
+    pub output_file_tracing_includes: Option<FxIndexMap<RcStr, Vec<RcStr>>>,
+    /// Globs of files to remove from the traced files of routes, keyed by globs
+    /// matching the routes.
+    pub output_file_tracing_excludes: Option<FxIndexMap<RcStr, Vec<RcStr>>>,
+
     /// Enables the bundling of node_modules packages (externals) for pages
     /// server-side bundles.
     ///
@@ -383,6 +399,8 @@ pub struct RemotePattern {
     pub port: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pathname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TraceRawVcs)]
@@ -403,6 +421,45 @@ pub struct ExperimentalTurboConfig {
     pub use_swc_css: Option<bool>,
     pub tree_shaking: Option<bool>,
     pub module_id_strategy: Option<ModuleIdStrategy>,
+    pub module_federation: Option<ModuleFederationConfig>,
+    pub extract_styled_jsx: Option<bool>,
+    pub usage_based_polyfills: Option<bool>,
+    pub report_duplicate_packages: Option<bool>,
+    pub exclude_optional_requires: Option<Vec<RcStr>>,
+    pub external_source_map_threshold: Option<u64>,
+    pub chunk_load_retries: Option<u32>,
+    pub source_map_source_template: Option<RcStr>,
+    pub strict_csp: Option<bool>,
+    pub chunk_code_wrapping: Option<ChunkCodeWrappingConfig>,
+}
+
+/// Code to add to every emitted chunk, see [ChunkCodeWrapping].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkCodeWrappingConfig {
+    pub banner: Option<RcStr>,
+    pub footer: Option<RcStr>,
+    pub runtime_injection: Option<RcStr>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleFederationConfig {
+    pub share_scope: Option<RcStr>,
+    /// Maps the name of a remote to its `remoteEntry`. `name@url` loads a
+    /// script that assigns the container to the global `name`, a plain url is
+    /// imported as an ES module.
+    #[serde(default)]
+    pub remotes: FxIndexMap<RcStr, RcStr>,
+    #[serde(default)]
+    pub shared: FxIndexMap<RcStr, ModuleFederationSharedConfig>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleFederationSharedConfig {
+    pub version: Option<RcStr>,
+    pub required_version: Option<RcStr>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
@@ -411,12 +468,20 @@ pub struct RuleConfigItemOptions {
     pub loaders: Vec<LoaderItem>,
     #[serde(default, alias = "as")]
     pub rename_as: Option<RcStr>,
+    #[serde(default)]
+    pub enforce: LoaderEnforce,
+    #[serde(default)]
+    pub exclude: Option<RcStr>,
+    #[serde(default)]
+    pub issuer: Option<RcStr>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum RuleConfigItemOrShortcut {
     Loaders(Vec<LoaderItem>),
+    /// Rules that run one after another.
+    Pipeline(Vec<RuleConfigItem>),
     Advanced(RuleConfigItem),
 }
 
@@ -964,29 +1029,35 @@ impl NextConfig {
                     RuleConfigItem::Boolean(_) => FindRuleResult::Break,
                 }
             }
-            match rule {
+            let find_rule_item = |rule: &RuleConfigItem| match find_rule(rule, &active_conditions) {
+                FindRuleResult::Found(RuleConfigItemOptions {
+                    loaders,
+                    rename_as,
+                    enforce,
+                    exclude,
+                    issuer,
+                }) => Some(LoaderRuleItem {
+                    loaders: transform_loaders(loaders),
+                    rename_as: rename_as.clone(),
+                    enforce: *enforce,
+                    exclude: exclude.clone(),
+                    issuer: issuer.clone(),
+                }),
+                FindRuleResult::NotFound | FindRuleResult::Break => None,
+            };
+            let pipeline = match rule {
                 RuleConfigItemOrShortcut::Loaders(loaders) => {
-                    rules.insert(
-                        ext.clone(),
-                        LoaderRuleItem {
-                            loaders: transform_loaders(loaders),
-                            rename_as: None,
-                        },
-                    );
+                    vec![LoaderRuleItem::new(transform_loaders(loaders), None)]
+                }
+                RuleConfigItemOrShortcut::Pipeline(pipeline) => {
+                    pipeline.iter().filter_map(find_rule_item).collect()
                 }
                 RuleConfigItemOrShortcut::Advanced(rule) => {
-                    if let FindRuleResult::Found(RuleConfigItemOptions { loaders, rename_as }) =
-                        find_rule(rule, &active_conditions)
-                    {
-                        rules.insert(
-                            ext.clone(),
-                            LoaderRuleItem {
-                                loaders: transform_loaders(loaders),
-                                rename_as: rename_as.clone(),
-                            },
-                        );
-                    }
+                    find_rule_item(rule).into_iter().collect()
                 }
+            };
+            if !pipeline.is_empty() {
+                rules.insert(ext.clone(), pipeline);
             }
         }
         Vc::cell(Some(Vc::cell(rules)))
@@ -1165,6 +1236,136 @@ impl NextConfig {
         )
     }
 
+    #[turbo_tasks::function]
+    pub fn extract_styled_jsx(&self) -> Vc<bool> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|turbo| turbo.extract_styled_jsx)
+                .unwrap_or(false),
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub fn usage_based_polyfills(&self) -> Vc<bool> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|turbo| turbo.usage_based_polyfills)
+                .unwrap_or(false),
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub fn report_duplicate_packages(&self) -> Vc<bool> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|turbo| turbo.report_duplicate_packages)
+                .unwrap_or(false),
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub fn exclude_optional_requires(&self) -> Vc<Vec<RcStr>> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|turbo| turbo.exclude_optional_requires.clone())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// The size in bytes above which the source maps of modules in HMR updates
+    /// are emitted as separate files instead of being inlined.
+    #[turbo_tasks::function]
+    pub fn external_source_map_threshold(&self) -> Vc<Option<u64>> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|turbo| turbo.external_source_map_threshold),
+        )
+    }
+
+    /// How often the browser runtime retries loading a chunk that failed to
+    /// load. Retries are disabled by default.
+    #[turbo_tasks::function]
+    pub fn chunk_load_retries(&self) -> Vc<u32> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|turbo| turbo.chunk_load_retries)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// The template to rewrite the `turbopack://[namespace]/path` sources of
+    /// emitted source maps with, e.g. `webpack://[namespace]/[resource-path]`.
+    #[turbo_tasks::function]
+    pub fn source_map_source_template(&self) -> Vc<Option<RcStr>> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|turbo| turbo.source_map_source_template.clone()),
+        )
+    }
+
+    /// Whether the pages enforce a strict Content Security Policy that forbids
+    /// `eval`, so the development runtime needs to apply HMR updates with
+    /// `<script>` tags.
+    #[turbo_tasks::function]
+    pub fn strict_csp(&self) -> Vc<bool> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|turbo| turbo.strict_csp)
+                .unwrap_or_default(),
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub fn chunk_code_wrapping(&self) -> Vc<ChunkCodeWrapping> {
+        let config = self
+            .experimental
+            .turbo
+            .as_ref()
+            .and_then(|turbo| turbo.chunk_code_wrapping.clone())
+            .unwrap_or_default();
+        ChunkCodeWrapping {
+            banner: config.banner,
+            footer: config.footer,
+            runtime_injection: config.runtime_injection,
+        }
+        .cell()
+    }
+
+    /// The origins of remote URLs that can be imported, from
+    /// `experimental.urlImports`. It's either a list of allowed URLs, or an
+    /// object with `allowedUris`.
+    #[turbo_tasks::function]
+    pub fn url_imports(&self) -> Vc<Vec<RcStr>> {
+        let allowed_uris = match &self.experimental.url_imports {
+            Some(serde_json::Value::Object(options)) => options.get("allowedUris"),
+            url_imports => url_imports.as_ref(),
+        };
+        Vc::cell(
+            allowed_uris
+                .and_then(|allowed_uris| allowed_uris.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|uri| uri.as_str().map(RcStr::from))
+                .collect(),
+        )
+    }
+
     #[turbo_tasks::function]
     pub fn optimize_package_imports(&self) -> Vc<Vec<RcStr>> {
         Vc::cell(
@@ -1222,6 +1423,44 @@ impl NextConfig {
         .cell()
     }
 
+    #[turbo_tasks::function]
+    pub fn module_federation(&self) -> Vc<OptionModuleFederationOptions> {
+        let Some(config) = self
+            .experimental
+            .turbo
+            .as_ref()
+            .and_then(|t| t.module_federation.as_ref())
+        else {
+            return Vc::cell(None);
+        };
+        let remotes = config
+            .remotes
+            .iter()
+            .map(|(name, entry)| (name.clone(), ModuleFederationRemote::parse(entry)))
+            .collect();
+        let shared = config
+            .shared
+            .iter()
+            .map(|(name, shared)| {
+                (
+                    name.clone(),
+                    ModuleFederationShared {
+                        version: shared.version.clone(),
+                        required_version: shared.required_version.clone(),
+                    },
+                )
+            })
+            .collect();
+        Vc::cell(Some(
+            ModuleFederationOptions {
+                share_scope: config.share_scope.clone(),
+                remotes,
+                shared,
+            }
+            .cell(),
+        ))
+    }
+
     #[turbo_tasks::function]
     pub fn module_id_strategy_config(&self) -> Vc<OptionModuleIdStrategy> {
         let Some(module_id_strategy) = self
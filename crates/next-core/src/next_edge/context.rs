@@ -5,7 +5,7 @@ use turbo_tasks_fs::FileSystemPath;
 use turbopack::resolve_options_context::ResolveOptionsContext;
 use turbopack_browser::BrowserChunkingContext;
 use turbopack_core::{
-    chunk::{module_id_strategies::ModuleIdStrategy, ChunkingContext},
+    chunk::{module_id_strategies::ModuleIdStrategy, ChunkCodeWrapping, ChunkingContext},
     compile_time_info::{
         CompileTimeDefineValue, CompileTimeDefines, CompileTimeInfo, DefineableNameSegment,
         FreeVarReference, FreeVarReferences,
@@ -23,7 +23,8 @@ use crate::{
     next_server::context::ServerContextType,
     next_shared::resolve::{
         get_invalid_client_only_resolve_plugin, get_invalid_styled_jsx_resolve_plugin,
-        ModuleFeatureReportResolvePlugin, NextSharedRuntimeResolvePlugin,
+        get_remote_url_resolve_plugins, ModuleFeatureReportResolvePlugin,
+        NextSharedRuntimeResolvePlugin,
     },
     util::{foreign_code_context_condition, NextRuntime},
 };
@@ -139,6 +140,7 @@ pub async fn get_edge_resolve_options_context(
                 .await?,
         ));
     }
+    before_resolve_plugins.extend(get_remote_url_resolve_plugins(project_path, next_config).await?);
 
     let after_resolve_plugins = vec![ResolvedVc::upcast(
         NextSharedRuntimeResolvePlugin::new(project_path)
@@ -196,24 +198,29 @@ pub async fn get_edge_chunking_context_with_client_assets(
     asset_prefix: Vc<Option<RcStr>>,
     environment: Vc<Environment>,
     module_id_strategy: Vc<Box<dyn ModuleIdStrategy>>,
+    source_map_source_template: Vc<Option<RcStr>>,
+    chunk_code_wrapping: Vc<ChunkCodeWrapping>,
 ) -> Result<Vc<Box<dyn ChunkingContext>>> {
     let output_root = node_root.join("server/edge".into());
     let next_mode = mode.await?;
-    Ok(Vc::upcast(
-        BrowserChunkingContext::builder(
-            project_path,
-            output_root,
-            client_root,
-            output_root.join("chunks/ssr".into()),
-            client_root.join("static/media".into()),
-            environment,
-            next_mode.runtime_type(),
-        )
-        .asset_base_path(asset_prefix)
-        .minify_type(next_mode.minify_type())
-        .module_id_strategy(module_id_strategy)
-        .build(),
-    ))
+    let mut builder = BrowserChunkingContext::builder(
+        project_path,
+        output_root,
+        client_root,
+        output_root.join("chunks/ssr".into()),
+        client_root.join("static/media".into()),
+        environment,
+        next_mode.runtime_type(),
+    )
+    .asset_base_path(asset_prefix)
+    .asset_urls_in_css()
+    .minify_type(next_mode.minify_type())
+    .module_id_strategy(module_id_strategy)
+    .chunk_code_wrapping((*chunk_code_wrapping.await?).clone());
+    if let Some(template) = &*source_map_source_template.await? {
+        builder = builder.source_map_source_template(template.clone());
+    }
+    Ok(Vc::upcast(builder.build()))
 }
 
 #[turbo_tasks::function]
@@ -223,26 +230,30 @@ pub async fn get_edge_chunking_context(
     node_root: Vc<FileSystemPath>,
     environment: Vc<Environment>,
     module_id_strategy: Vc<Box<dyn ModuleIdStrategy>>,
+    source_map_source_template: Vc<Option<RcStr>>,
+    chunk_code_wrapping: Vc<ChunkCodeWrapping>,
 ) -> Result<Vc<Box<dyn ChunkingContext>>> {
     let output_root = node_root.join("server/edge".into());
     let next_mode = mode.await?;
-    Ok(Vc::upcast(
-        BrowserChunkingContext::builder(
-            project_path,
-            output_root,
-            output_root,
-            output_root.join("chunks".into()),
-            output_root.join("assets".into()),
-            environment,
-            next_mode.runtime_type(),
-        )
-        // Since one can't read files in edge directly, any asset need to be fetched
-        // instead. This special blob url is handled by the custom fetch
-        // implementation in the edge sandbox. It will respond with the
-        // asset from the output directory.
-        .asset_base_path(Vc::cell(Some("blob:server/edge/".into())))
-        .minify_type(next_mode.minify_type())
-        .module_id_strategy(module_id_strategy)
-        .build(),
-    ))
+    let mut builder = BrowserChunkingContext::builder(
+        project_path,
+        output_root,
+        output_root,
+        output_root.join("chunks".into()),
+        output_root.join("assets".into()),
+        environment,
+        next_mode.runtime_type(),
+    )
+    // Since one can't read files in edge directly, any asset need to be fetched
+    // instead. This special blob url is handled by the custom fetch
+    // implementation in the edge sandbox. It will respond with the
+    // asset from the output directory.
+    .asset_base_path(Vc::cell(Some("blob:server/edge/".into())))
+    .minify_type(next_mode.minify_type())
+    .module_id_strategy(module_id_strategy)
+    .chunk_code_wrapping((*chunk_code_wrapping.await?).clone());
+    if let Some(template) = &*source_map_source_template.await? {
+        builder = builder.source_map_source_template(template.clone());
+    }
+    Ok(Vc::upcast(builder.build()))
 }
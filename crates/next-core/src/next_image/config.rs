@@ -0,0 +1,370 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{trace::TraceRawVcs, RcStr, Value, Vc};
+use turbo_tasks_fs::{glob::Glob, FileSystemPath};
+use turbopack_core::{
+    context::AssetContext,
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    module::Module,
+    reference_type::{EcmaScriptModulesReferenceSubType, ReferenceType},
+    resolve::{parse::Request, pattern::Pattern},
+};
+
+use crate::next_config::{ImageLoader, NextConfig, RemotePattern, RemotePatternProtocal};
+
+/// A `images.remotePatterns` entry, compiled into matchers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TraceRawVcs)]
+pub struct ImageRemotePatternMatcher {
+    protocol: Option<RemotePatternProtocal>,
+    /// Matches the hostname with its labels separated by `/` instead of `.`,
+    /// so `*` matches a single label and `**` any number of labels.
+    hostname: Glob,
+    port: Option<RcStr>,
+    pathname: Option<Glob>,
+    search: Option<RcStr>,
+}
+
+impl ImageRemotePatternMatcher {
+    /// Whether the pattern allows optimizing the remote image at the URL with
+    /// the given parts, which are the properties of a WHATWG `URL`: `port` is
+    /// empty for the default port of the protocol and `search` is empty or
+    /// starts with `?`.
+    pub fn matches(
+        &self,
+        protocol: &str,
+        hostname: &str,
+        port: &str,
+        pathname: &str,
+        search: &str,
+    ) -> bool {
+        if let Some(expected) = &self.protocol {
+            let expected = match expected {
+                RemotePatternProtocal::Http => "http",
+                RemotePatternProtocal::Https => "https",
+            };
+            if protocol.trim_end_matches(':') != expected {
+                return false;
+            }
+        }
+        if self.port.as_ref().is_some_and(|expected| expected != port) {
+            return false;
+        }
+        if self
+            .search
+            .as_ref()
+            .is_some_and(|expected| expected != search)
+        {
+            return false;
+        }
+        if !self.hostname.execute(&hostname.replace('.', "/")) {
+            return false;
+        }
+        match &self.pathname {
+            Some(pathname_glob) => pathname_glob.execute(pathname),
+            None => true,
+        }
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct ImageRemotePatternMatchers(Vec<ImageRemotePatternMatcher>);
+
+/// Compiles the `images.remotePatterns` config into matchers. An issue is
+/// emitted for each invalid pattern, which is left out.
+#[turbo_tasks::function]
+pub async fn image_remote_pattern_matchers(
+    next_config: Vc<NextConfig>,
+    project_path: Vc<FileSystemPath>,
+) -> Result<Vc<ImageRemotePatternMatchers>> {
+    let image_config = next_config.image_config().await?;
+    let mut matchers = Vec::new();
+    for pattern in &image_config.remote_patterns {
+        match compile_remote_pattern(pattern) {
+            Ok(matcher) => matchers.push(matcher),
+            Err(error) => {
+                ImageConfigIssue {
+                    path: project_path,
+                    title: format!(
+                        "Invalid images.remotePatterns entry for hostname \"{}\"",
+                        pattern.hostname
+                    )
+                    .into(),
+                    description: format!("{error}").into(),
+                }
+                .cell()
+                .emit();
+            }
+        }
+    }
+    Ok(Vc::cell(matchers))
+}
+
+fn compile_remote_pattern(pattern: &RemotePattern) -> Result<ImageRemotePatternMatcher> {
+    let hostname = &pattern.hostname;
+    if hostname.is_empty() {
+        bail!("The hostname must not be empty.");
+    }
+    if let Some(invalid) = hostname
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '*')))
+    {
+        bail!(
+            "The hostname contains the invalid character '{invalid}'. Hostnames can only contain \
+             letters, digits, '-', '.' and the wildcards '*' and '**'."
+        );
+    }
+    let port = match pattern.port.as_deref() {
+        Some(port) if !port.chars().all(|c| c.is_ascii_digit()) => {
+            bail!("The port \"{port}\" is not a number.");
+        }
+        port => port.map(RcStr::from),
+    };
+    let pathname = match pattern.pathname.as_deref() {
+        Some(pathname) if !pathname.starts_with('/') => {
+            bail!("The pathname \"{pathname}\" must start with '/'.");
+        }
+        Some(pathname) => Some(Glob::parse(pathname)?),
+        None => None,
+    };
+    let search = match pattern.search.as_deref() {
+        Some(search) if !search.is_empty() && !search.starts_with('?') => {
+            bail!("The search \"{search}\" must be empty or start with '?'.");
+        }
+        search => search.map(RcStr::from),
+    };
+    Ok(ImageRemotePatternMatcher {
+        protocol: pattern.protocol.clone(),
+        hostname: Glob::parse(&hostname.replace('.', "/"))?,
+        port,
+        pathname,
+        search,
+    })
+}
+
+/// Whether the `images.remotePatterns` config allows optimizing the remote
+/// image at the URL with the given parts, see
+/// [ImageRemotePatternMatcher::matches]. This is used by the image
+/// optimization endpoint of `next dev --turbopack`.
+#[turbo_tasks::function]
+pub async fn image_remote_pattern_matches(
+    next_config: Vc<NextConfig>,
+    project_path: Vc<FileSystemPath>,
+    protocol: RcStr,
+    hostname: RcStr,
+    port: RcStr,
+    pathname: RcStr,
+    search: RcStr,
+) -> Result<Vc<bool>> {
+    let matchers = image_remote_pattern_matchers(next_config, project_path).await?;
+    Ok(Vc::cell(matchers.iter().any(|matcher| {
+        matcher.matches(&protocol, &hostname, &port, &pathname, &search)
+    })))
+}
+
+/// Validates the `images` config, emitting issues instead of failing when
+/// images are optimized at runtime: the `remotePatterns` need to be valid and
+/// the `loaderFile` needs to resolve to a module that compiles in the
+/// `client_asset_context`, as it's bundled in place of the default image
+/// loader.
+#[turbo_tasks::function]
+pub async fn validate_image_config(
+    next_config: Vc<NextConfig>,
+    project_path: Vc<FileSystemPath>,
+    client_asset_context: Vc<Box<dyn AssetContext>>,
+) -> Result<Vc<()>> {
+    image_remote_pattern_matchers(next_config, project_path).await?;
+
+    let image_config = next_config.image_config().await?;
+    let Some(loader_file) = image_config.loader_file.as_deref() else {
+        return Ok(Vc::cell(()));
+    };
+    if image_config.loader != ImageLoader::Custom {
+        ImageConfigIssue {
+            path: project_path,
+            title: "images.loaderFile requires images.loader to be \"custom\"".into(),
+            description: "The loader of images.loaderFile is only used with the custom loader. \
+                          Set images.loader to \"custom\"."
+                .into(),
+        }
+        .cell()
+        .emit();
+    }
+    // Resolved like the alias of the default image loader, see `next_import_map`.
+    let reference_type = Value::new(ReferenceType::EcmaScriptModules(
+        EcmaScriptModulesReferenceSubType::Undefined,
+    ));
+    let origin_path = project_path.join("_".into());
+    let result = client_asset_context.resolve_asset(
+        origin_path,
+        Request::parse(Value::new(Pattern::Constant(loader_file.into()))),
+        client_asset_context.resolve_options(origin_path, reference_type.clone()),
+        reference_type,
+    );
+    if let Some(module) = *result.first_module().await? {
+        // Analyzing the module compiles it, which reports its syntax errors and
+        // unresolvable imports as issues.
+        module.references().await?;
+    } else {
+        ImageConfigIssue {
+            path: project_path,
+            title: format!("images.loaderFile \"{loader_file}\" could not be resolved").into(),
+            description: "The loaderFile needs to be a path to a module that default exports the \
+                          image loader function, relative to the project directory."
+                .into(),
+        }
+        .cell()
+        .emit();
+    }
+    Ok(Vc::cell(()))
+}
+
+#[turbo_tasks::value(shared)]
+struct ImageConfigIssue {
+    path: Vc<FileSystemPath>,
+    title: RcStr,
+    description: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for ImageConfigIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.into()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Config.into()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(self.title.clone()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(StyledString::Text(self.description.clone()).cell()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compile_remote_pattern;
+    use crate::next_config::{RemotePattern, RemotePatternProtocal};
+
+    fn pattern(hostname: &str) -> RemotePattern {
+        RemotePattern {
+            hostname: hostname.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Splits `url` into the parts of a WHATWG `URL` for a match.
+    fn matches(pattern: &RemotePattern, url: &str) -> bool {
+        let (protocol, rest) = url.split_once("://").unwrap();
+        let (host, path) = rest.split_at(rest.find('/').unwrap());
+        let (hostname, port) = host.split_once(':').unwrap_or((host, ""));
+        let (pathname, search) = path.split_at(path.find('?').unwrap_or(path.len()));
+        compile_remote_pattern(pattern)
+            .unwrap()
+            .matches(protocol, hostname, port, pathname, search)
+    }
+
+    #[test]
+    fn matches_exact_hostnames() {
+        let pattern = pattern("example.com");
+        assert!(matches(&pattern, "https://example.com/a.png"));
+        assert!(matches(&pattern, "http://example.com/a.png"));
+        assert!(!matches(&pattern, "https://cdn.example.com/a.png"));
+        assert!(!matches(&pattern, "https://example.org/a.png"));
+    }
+
+    #[test]
+    fn matches_wildcard_hostnames() {
+        let single = pattern("*.example.com");
+        assert!(matches(&single, "https://cdn.example.com/a.png"));
+        assert!(!matches(&single, "https://a.cdn.example.com/a.png"));
+        assert!(!matches(&single, "https://example.com/a.png"));
+
+        let any = pattern("**.example.com");
+        assert!(matches(&any, "https://cdn.example.com/a.png"));
+        assert!(matches(&any, "https://a.cdn.example.com/a.png"));
+        assert!(!matches(&any, "https://cdn.example.org/a.png"));
+    }
+
+    #[test]
+    fn matches_protocols_and_ports() {
+        let pattern = RemotePattern {
+            protocol: Some(RemotePatternProtocal::Https),
+            port: Some("8443".to_string()),
+            ..pattern("example.com")
+        };
+        assert!(matches(&pattern, "https://example.com:8443/a.png"));
+        assert!(!matches(&pattern, "http://example.com:8443/a.png"));
+        assert!(!matches(&pattern, "https://example.com/a.png"));
+
+        let default_port = RemotePattern {
+            port: Some(String::new()),
+            ..pattern("example.com")
+        };
+        assert!(matches(&default_port, "https://example.com/a.png"));
+        assert!(!matches(&default_port, "https://example.com:8443/a.png"));
+    }
+
+    #[test]
+    fn matches_pathname_globs() {
+        let pattern = RemotePattern {
+            pathname: Some("/images/**".to_string()),
+            ..pattern("example.com")
+        };
+        assert!(matches(&pattern, "https://example.com/images/a.png"));
+        assert!(matches(&pattern, "https://example.com/images/nested/a.png"));
+        assert!(!matches(&pattern, "https://example.com/other/a.png"));
+
+        let single = RemotePattern {
+            pathname: Some("/images/*.png".to_string()),
+            ..pattern("example.com")
+        };
+        assert!(matches(&single, "https://example.com/images/a.png"));
+        assert!(!matches(&single, "https://example.com/images/nested/a.png"));
+    }
+
+    #[test]
+    fn matches_search() {
+        let pattern = RemotePattern {
+            search: Some("?v=1".to_string()),
+            ..pattern("example.com")
+        };
+        assert!(matches(&pattern, "https://example.com/a.png?v=1"));
+        assert!(!matches(&pattern, "https://example.com/a.png?v=2"));
+        assert!(!matches(&pattern, "https://example.com/a.png"));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(compile_remote_pattern(&pattern("")).is_err());
+        assert!(compile_remote_pattern(&pattern("example.com/path")).is_err());
+        for invalid in [
+            RemotePattern {
+                port: Some("http".to_string()),
+                ..pattern("example.com")
+            },
+            RemotePattern {
+                pathname: Some("images/**".to_string()),
+                ..pattern("example.com")
+            },
+            RemotePattern {
+                search: Some("v=1".to_string()),
+                ..pattern("example.com")
+            },
+        ] {
+            assert!(compile_remote_pattern(&invalid).is_err());
+        }
+    }
+}
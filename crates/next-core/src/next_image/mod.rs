@@ -1,4 +1,9 @@
+pub(crate) mod config;
 pub(crate) mod module;
 pub(crate) mod source_asset;
 
+pub use config::{
+    image_remote_pattern_matchers, image_remote_pattern_matches, validate_image_config,
+    ImageRemotePatternMatcher, ImageRemotePatternMatchers,
+};
 pub use module::StructuredImageModuleType;
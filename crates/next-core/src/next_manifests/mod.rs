@@ -25,6 +25,10 @@ pub struct BuildManifest {
     pub root_main_files: Vec<RcStr>,
     pub pages: HashMap<RcStr, Vec<RcStr>>,
     pub amp_first_pages: Vec<RcStr>,
+    /// The APIs that are polyfilled for each page, because they are used by
+    /// the page but not supported by all targeted browsers.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub injected_polyfills: HashMap<RcStr, Vec<RcStr>>,
 }
 
 #[derive(Serialize, Debug)]
@@ -167,6 +171,8 @@ pub struct AppPathsManifest {
 // The manifest is in a format of:
 // { [`${origin} -> ${imported}`]: { id: `${origin} -> ${imported}`, files:
 // string[] } }
+// Each imported module is also listed by its module id:
+// { [moduleId]: { id: moduleId, files: string[] } }
 #[derive(Serialize, Default, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct LoadableManifest {
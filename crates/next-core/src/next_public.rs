@@ -0,0 +1,286 @@
+use anyhow::Result;
+use rustc_hash::{FxHashMap, FxHashSet};
+use turbo_tasks::{FxIndexMap, RcStr, ResolvedVc, Vc};
+use turbo_tasks_fs::{DirectoryContent, DirectoryEntry, FileSystemPath};
+use turbopack_dev_server::source::{
+    combined::CombinedContentSource, static_assets::StaticAssetsContentSource, ContentSource,
+};
+
+/// The files of the `public` directory, by the pathname they are served at,
+/// e.g. `/images/logo.png`.
+#[turbo_tasks::value(transparent)]
+pub struct PublicFiles(FxIndexMap<RcStr, ResolvedVc<FileSystemPath>>);
+
+#[turbo_tasks::function]
+pub fn get_public_dir(project_path: Vc<FileSystemPath>) -> Vc<FileSystemPath> {
+    project_path.join("public".into())
+}
+
+/// Lists the files of the `public` directory of the project, sorted by
+/// pathname.
+#[turbo_tasks::function]
+pub async fn get_public_files(project_path: Vc<FileSystemPath>) -> Result<Vc<PublicFiles>> {
+    let mut files = FxIndexMap::default();
+    let mut queue = vec![(get_public_dir(project_path), RcStr::default())];
+    while let Some((dir, prefix)) = queue.pop() {
+        let DirectoryContent::Entries(entries) = &*dir.read_dir().await? else {
+            continue;
+        };
+        for (name, entry) in entries.iter() {
+            let pathname: RcStr = format!("{prefix}/{name}").into();
+            match entry {
+                DirectoryEntry::File(path) | DirectoryEntry::Symlink(path) => {
+                    files.insert(pathname, *path);
+                }
+                DirectoryEntry::Directory(path) => queue.push((**path, pathname)),
+                DirectoryEntry::Other(_) | DirectoryEntry::Error => {}
+            }
+        }
+    }
+    files.sort_keys();
+    Ok(Vc::cell(files))
+}
+
+/// Serves the files of the `public` directory in front of `routes`. Like with
+/// `next start`, a public file takes precedence over a route that matches the
+/// same pathname.
+#[turbo_tasks::function]
+pub fn get_public_content_source(
+    project_path: Vc<FileSystemPath>,
+    routes: Vc<Box<dyn ContentSource>>,
+) -> Vc<Box<dyn ContentSource>> {
+    let public_source = Vc::upcast(StaticAssetsContentSource::new(
+        RcStr::default(),
+        get_public_dir(project_path),
+    ));
+    Vc::upcast(CombinedContentSource::new(vec![public_source, routes]))
+}
+
+/// How a file of the `public` directory conflicts with routes.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PublicFileConflict<'a> {
+    /// The file is never served, because Next.js serves its own files at
+    /// pathnames starting with `/_next/`.
+    ShadowedByNext,
+    /// The file has the pathname of a static route, which is never served.
+    ShadowsRoute(&'a str),
+    /// The file has a pathname that a dynamic route matches, which isn't served
+    /// for that pathname.
+    ShadowsDynamicRoute(&'a str),
+}
+
+/// Returns the conflicts of the public files with the pathnames `files` with
+/// the routes with the pathnames `routes`, by file.
+///
+/// Catch-all routes are expected to handle all pathnames that aren't served
+/// otherwise, so public files within them aren't reported. A file is only
+/// compared with the dynamic routes that have as many segments as its
+/// pathname.
+pub fn public_file_conflicts<'a>(
+    files: impl IntoIterator<Item = &'a str>,
+    routes: impl IntoIterator<Item = &'a str>,
+) -> Vec<(&'a str, PublicFileConflict<'a>)> {
+    let mut static_routes = FxHashSet::default();
+    let mut dynamic_routes: FxHashMap<usize, Vec<&str>> = FxHashMap::default();
+    for route in routes {
+        if !is_dynamic_route(route) {
+            static_routes.insert(route);
+        } else if !route.contains("[...") && !route.contains("[[...") {
+            dynamic_routes
+                .entry(segments(route).count())
+                .or_default()
+                .push(route);
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    for file in files {
+        if file.starts_with("/_next/") {
+            conflicts.push((file, PublicFileConflict::ShadowedByNext));
+            continue;
+        }
+        if static_routes.contains(file) {
+            conflicts.push((file, PublicFileConflict::ShadowsRoute(file)));
+        }
+        for route in dynamic_routes
+            .get(&segments(file).count())
+            .into_iter()
+            .flatten()
+        {
+            if route_matches_pathname(route, file) {
+                conflicts.push((file, PublicFileConflict::ShadowsDynamicRoute(route)));
+            }
+        }
+    }
+    conflicts
+}
+
+fn segments(pathname: &str) -> impl Iterator<Item = &str> {
+    pathname.split('/').filter(|s| !s.is_empty())
+}
+
+/// Returns whether the route with the pathname `route`, e.g. `/blog/[slug]`,
+/// matches `pathname`, e.g. `/blog/hello`.
+pub fn route_matches_pathname(route: &str, pathname: &str) -> bool {
+    fn matches(route: &[&str], pathname: &[&str]) -> bool {
+        match route.split_first() {
+            None => pathname.is_empty(),
+            Some((segment, _)) if segment.starts_with("[[...") => true,
+            Some((segment, _)) if segment.starts_with("[...") => !pathname.is_empty(),
+            Some((segment, route)) => match pathname.split_first() {
+                Some((pathname_segment, pathname)) => {
+                    (segment.starts_with('[') || segment == pathname_segment)
+                        && matches(route, pathname)
+                }
+                None => false,
+            },
+        }
+    }
+
+    let route = segments(route).collect::<Vec<_>>();
+    let pathname = segments(pathname).collect::<Vec<_>>();
+    matches(&route, &pathname)
+}
+
+/// Returns whether the route with the pathname `route` has dynamic segments.
+pub fn is_dynamic_route(route: &str) -> bool {
+    route.split('/').any(|segment| segment.starts_with('['))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::Once};
+
+    use anyhow::{bail, Result};
+    use turbo_tasks::{TurboTasks, Value, Vc};
+    use turbo_tasks_fs::{DiskFileSystem, FileContent, FileSystem};
+    use turbo_tasks_memory::MemoryBackend;
+    use turbopack_core::asset::AssetContent;
+    use turbopack_dev_server::source::{
+        static_assets::StaticAssetsContentSource, ContentSource, ContentSourceContent,
+        ContentSourceData,
+    };
+
+    use super::{
+        get_public_content_source, is_dynamic_route, public_file_conflicts, route_matches_pathname,
+        PublicFileConflict,
+    };
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    /// Returns the content of the file `source` serves at `path`.
+    async fn served(source: Vc<Box<dyn ContentSource>>, path: &str) -> Result<Option<String>> {
+        for get_content in source.get_routes().get(path.into()).await?.iter() {
+            let content = get_content.get(path.into(), Value::new(ContentSourceData::default()));
+            let ContentSourceContent::Static(content) = &*content.await? else {
+                continue;
+            };
+            let AssetContent::File(file) = &*content.await?.content.content().await? else {
+                bail!("expected a file");
+            };
+            let FileContent::Content(file) = &*file.await? else {
+                bail!("expected file content");
+            };
+            return Ok(Some(file.content().to_str()?.to_string()));
+        }
+        Ok(None)
+    }
+
+    #[tokio::test]
+    async fn serves_public_files_in_front_of_routes() {
+        register();
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("public")).unwrap();
+        fs::create_dir_all(dir.path().join("routes")).unwrap();
+        fs::write(dir.path().join("public/robots.txt"), "public").unwrap();
+        fs::write(dir.path().join("routes/robots.txt"), "route").unwrap();
+        fs::write(dir.path().join("routes/about"), "about").unwrap();
+        let root = dir.path().to_str().unwrap().to_string();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            let project_path = DiskFileSystem::new("project".into(), root.into(), vec![]).root();
+            let routes = Vc::upcast(StaticAssetsContentSource::new(
+                "".into(),
+                project_path.join("routes".into()),
+            ));
+            let source = get_public_content_source(project_path, routes);
+            assert_eq!(
+                served(source, "robots.txt").await?.as_deref(),
+                Some("public")
+            );
+            assert_eq!(served(source, "about").await?.as_deref(), Some("about"));
+            assert_eq!(served(source, "missing").await?, None);
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn reports_files_that_shadow_static_routes() {
+        assert_eq!(
+            public_file_conflicts(["/robots.txt", "/about/team"], ["/robots.txt", "/about"]),
+            vec![(
+                "/robots.txt",
+                PublicFileConflict::ShadowsRoute("/robots.txt")
+            )]
+        );
+        // Routes can't be served at pathnames reserved for Next.js either.
+        assert_eq!(
+            public_file_conflicts(["/_next/data.json"], []),
+            vec![("/_next/data.json", PublicFileConflict::ShadowedByNext)]
+        );
+    }
+
+    #[test]
+    fn reports_files_that_shadow_dynamic_routes() {
+        assert_eq!(
+            public_file_conflicts(
+                ["/blog/hello.txt", "/blog", "/docs/a/b", "/favicon.ico"],
+                ["/blog/[slug]", "/docs/[...slug]", "/[[...slug]]", "/[id]"]
+            ),
+            vec![
+                (
+                    "/blog/hello.txt",
+                    PublicFileConflict::ShadowsDynamicRoute("/blog/[slug]")
+                ),
+                ("/blog", PublicFileConflict::ShadowsDynamicRoute("/[id]")),
+                (
+                    "/favicon.ico",
+                    PublicFileConflict::ShadowsDynamicRoute("/[id]")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn matches_static_routes() {
+        assert!(route_matches_pathname("/", "/"));
+        assert!(route_matches_pathname("/robots.txt", "/robots.txt"));
+        assert!(!route_matches_pathname("/robots.txt", "/robots"));
+        assert!(!route_matches_pathname("/blog", "/blog/post"));
+    }
+
+    #[test]
+    fn matches_dynamic_routes() {
+        assert!(route_matches_pathname("/blog/[slug]", "/blog/hello.txt"));
+        assert!(!route_matches_pathname("/blog/[slug]", "/blog"));
+        assert!(!route_matches_pathname("/blog/[slug]", "/blog/a/b"));
+        assert!(route_matches_pathname("/docs/[...slug]", "/docs/a/b"));
+        assert!(!route_matches_pathname("/docs/[...slug]", "/docs"));
+        assert!(route_matches_pathname("/docs/[[...slug]]", "/docs"));
+        assert!(route_matches_pathname("/[[...slug]]", "/favicon.ico"));
+    }
+
+    #[test]
+    fn detects_dynamic_routes() {
+        assert!(!is_dynamic_route("/"));
+        assert!(!is_dynamic_route("/robots.txt"));
+        assert!(is_dynamic_route("/blog/[slug]"));
+        assert!(is_dynamic_route("/docs/[...slug]"));
+        assert!(is_dynamic_route("/[[...slug]]"));
+    }
+}
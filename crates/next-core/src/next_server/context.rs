@@ -13,7 +13,7 @@ use turbopack::{
     transition::Transition,
 };
 use turbopack_core::{
-    chunk::module_id_strategies::ModuleIdStrategy,
+    chunk::{module_id_strategies::ModuleIdStrategy, ChunkCodeWrapping},
     compile_time_info::{
         CompileTimeDefineValue, CompileTimeDefines, CompileTimeInfo, DefineableNameSegment,
         FreeVarReferences,
@@ -48,8 +48,8 @@ use crate::{
     next_shared::{
         resolve::{
             get_invalid_client_only_resolve_plugin, get_invalid_styled_jsx_resolve_plugin,
-            ModuleFeatureReportResolvePlugin, NextExternalResolvePlugin,
-            NextNodeSharedRuntimeResolvePlugin,
+            get_remote_url_resolve_plugins, ModuleFeatureReportResolvePlugin,
+            NextExternalResolvePlugin, NextNodeSharedRuntimeResolvePlugin,
         },
         transforms::{
             emotion::get_emotion_transform_rule, get_ecma_transform_rule,
@@ -307,6 +307,8 @@ pub async fn get_server_resolve_options_context(
         }
     }
 
+    before_resolve_plugins.extend(get_remote_url_resolve_plugins(project_path, next_config).await?);
+
     let resolve_options_context = ResolveOptionsContext {
         enable_node_modules: Some(root_dir),
         enable_node_externals: true,
@@ -515,6 +517,14 @@ pub async fn get_server_module_options_context(
             enable_typeof_window_inlining: Some(TypeofWindow::Undefined),
             import_externals: *next_config.import_externals().await?,
             ignore_dynamic_requests: true,
+            report_slow_modules: next_mode.is_development(),
+            exclude_optional_requires: Some(
+                next_config
+                    .exclude_optional_requires()
+                    .to_resolved()
+                    .await?,
+            ),
+            extract_styled_jsx: *next_config.extract_styled_jsx().await?,
             ..Default::default()
         },
         execution_context: Some(execution_context),
@@ -944,6 +954,8 @@ pub async fn get_server_chunking_context_with_client_assets(
     asset_prefix: Vc<Option<RcStr>>,
     environment: Vc<Environment>,
     module_id_strategy: Vc<Box<dyn ModuleIdStrategy>>,
+    source_map_source_template: Vc<Option<RcStr>>,
+    chunk_code_wrapping: Vc<ChunkCodeWrapping>,
 ) -> Result<Vc<NodeJsChunkingContext>> {
     let next_mode = mode.await?;
     // TODO(alexkirsz) This should return a trait that can be implemented by the
@@ -959,12 +971,17 @@ pub async fn get_server_chunking_context_with_client_assets(
         next_mode.runtime_type(),
     )
     .asset_prefix(asset_prefix)
+    .asset_urls_in_css()
     .minify_type(next_mode.minify_type())
-    .module_id_strategy(module_id_strategy);
+    .module_id_strategy(module_id_strategy)
+    .chunk_code_wrapping((*chunk_code_wrapping.await?).clone());
 
     if next_mode.is_development() {
         builder = builder.use_file_source_map_uris();
     }
+    if let Some(template) = &*source_map_source_template.await? {
+        builder = builder.source_map_source_template(template.clone());
+    }
     Ok(builder.build())
 }
 
@@ -975,6 +992,8 @@ pub async fn get_server_chunking_context(
     node_root: Vc<FileSystemPath>,
     environment: Vc<Environment>,
     module_id_strategy: Vc<Box<dyn ModuleIdStrategy>>,
+    source_map_source_template: Vc<Option<RcStr>>,
+    chunk_code_wrapping: Vc<ChunkCodeWrapping>,
 ) -> Result<Vc<NodeJsChunkingContext>> {
     let next_mode = mode.await?;
     // TODO(alexkirsz) This should return a trait that can be implemented by the
@@ -990,11 +1009,15 @@ pub async fn get_server_chunking_context(
         next_mode.runtime_type(),
     )
     .minify_type(next_mode.minify_type())
-    .module_id_strategy(module_id_strategy);
+    .module_id_strategy(module_id_strategy)
+    .chunk_code_wrapping((*chunk_code_wrapping.await?).clone());
 
     if next_mode.is_development() {
         builder = builder.use_file_source_map_uris()
     }
+    if let Some(template) = &*source_map_source_template.await? {
+        builder = builder.source_map_source_template(template.clone());
+    }
 
     Ok(builder.build())
 }
@@ -78,7 +78,7 @@ impl Module for NextServerComponentModule {
                 .to_resolved()
                 .await?,
         );
-        Ok(Vc::cell(vec![ssr_entry_module, client_entry_module]))
+        Ok(Vc::cell(vec![ssr_entry_module, client_entry_module].into()))
     }
 }
 
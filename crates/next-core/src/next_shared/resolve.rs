@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use anyhow::Result;
 use lazy_static::lazy_static;
 use turbo_tasks::{RcStr, ResolvedVc, Value, Vc};
+use turbo_tasks_fetch::RemoteUrlResolvePlugin;
 use turbo_tasks_fs::{glob::Glob, FileSystemPath};
 use turbopack_core::{
     diagnostics::DiagnosticExt,
@@ -19,7 +20,9 @@ use turbopack_core::{
     },
 };
 
-use crate::{next_server::ServerContextType, next_telemetry::ModuleFeatureTelemetry};
+use crate::{
+    next_config::NextConfig, next_server::ServerContextType, next_telemetry::ModuleFeatureTelemetry,
+};
 
 lazy_static! {
     // Set of the features we want to track, following existing references in webpack/plugins/telemetry-plugin.
@@ -149,6 +152,28 @@ impl BeforeResolvePlugin for InvalidImportResolvePlugin {
     }
 }
 
+/// Resolves imports of the remote URLs allowed by `experimental.urlImports`.
+/// Their integrity is pinned in `next.lock/turbopack.json`, and downloads are
+/// cached in `next.lock/turbopack`.
+pub(crate) async fn get_remote_url_resolve_plugins(
+    project_path: Vc<FileSystemPath>,
+    next_config: Vc<NextConfig>,
+) -> Result<Vec<ResolvedVc<Box<dyn BeforeResolvePlugin>>>> {
+    let allowed_origins = next_config.url_imports().await?;
+    if allowed_origins.is_empty() {
+        return Ok(vec![]);
+    }
+    Ok(vec![ResolvedVc::upcast(
+        RemoteUrlResolvePlugin::new(
+            allowed_origins.clone_value(),
+            project_path.join("next.lock/turbopack.json".into()),
+            project_path.join("next.lock/turbopack".into()),
+        )
+        .to_resolved()
+        .await?,
+    )])
+}
+
 /// Returns a resolve plugin if context have imports to `client-only`.
 /// Only the contexts that alises `client-only` to
 /// `next/dist/compiled/client-only/error` should use this.
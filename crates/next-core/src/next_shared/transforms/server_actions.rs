@@ -1,12 +1,29 @@
+use std::collections::BTreeMap;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use next_custom_transforms::transforms::server_actions::{server_actions, Config};
-use swc_core::{common::FileName, ecma::ast::Program};
+use swc_core::{
+    common::{comments::Comments, FileName},
+    ecma::ast::{Expr, Lit, ModuleItem, Program, Stmt},
+};
 use turbo_tasks::Vc;
 use turbopack::module_options::{ModuleRule, ModuleRuleEffect};
+use turbopack_core::{diagnostics::DiagnosticExt, module_metadata::ModuleMetadataKey};
 use turbopack_ecmascript::{CustomTransformer, EcmascriptInputTransform, TransformContext};
 
 use super::module_rule_match_js_no_url;
+use crate::next_telemetry::ModuleFeatureTelemetry;
+
+/// The Server Actions that a module defines, as a map from the hashed action
+/// id to the exported name of the action.
+pub const SERVER_ACTIONS: ModuleMetadataKey<BTreeMap<String, String>> =
+    ModuleMetadataKey::new("next/server-actions");
+
+/// Set for modules with a top-level `"use server"` directive, which only
+/// export Server Actions.
+pub const SERVER_ACTIONS_FILE: ModuleMetadataKey<bool> =
+    ModuleMetadataKey::new("next/server-actions-file");
 
 #[derive(Debug)]
 pub enum ActionsTransform {
@@ -39,6 +56,7 @@ struct NextServerActions {
 impl CustomTransformer for NextServerActions {
     #[tracing::instrument(level = tracing::Level::TRACE, name = "server_actions", skip_all)]
     async fn transform(&self, program: &mut Program, ctx: &TransformContext<'_>) -> Result<()> {
+        let is_server_actions_file = has_use_server_directive(program);
         let actions = server_actions(
             &FileName::Real(ctx.file_path_str.into()),
             Config {
@@ -50,6 +68,71 @@ impl CustomTransformer for NextServerActions {
         );
 
         program.mutate(actions);
+
+        // The transform marks modules that define actions with a magic comment.
+        let Some(actions) = parse_server_actions(program, ctx.comments) else {
+            return Ok(());
+        };
+        // Only the server layer is reported, so each module is counted once.
+        if matches!(self.transform, ActionsTransform::Server) {
+            ModuleFeatureTelemetry::new("serverActions".into(), 1)
+                .cell()
+                .emit();
+        }
+        let mut metadata = ctx.metadata.lock();
+        metadata.insert(&SERVER_ACTIONS, &actions)?;
+        if is_server_actions_file {
+            metadata.insert(&SERVER_ACTIONS_FILE, &true)?;
+        }
         Ok(())
     }
 }
+
+/// Parses the Server Actions comment for all exported action function names.
+///
+/// Action names are stored in a leading BlockComment prefixed by
+/// `__next_internal_action_entry_do_not_use__`.
+fn parse_server_actions(
+    program: &Program,
+    comments: &dyn Comments,
+) -> Option<BTreeMap<String, String>> {
+    let byte_pos = match program {
+        Program::Module(m) => m.span.lo,
+        Program::Script(s) => s.span.lo,
+    };
+    comments.get_leading(byte_pos).and_then(|comments| {
+        comments.iter().find_map(|c| {
+            c.text
+                .split_once("__next_internal_action_entry_do_not_use__")
+                .and_then(|(_, actions)| match serde_json::from_str(actions) {
+                    Ok(v) => Some(v),
+                    Err(_) => None,
+                })
+        })
+    })
+}
+
+/// Returns true when the module starts with a `"use server"` directive.
+fn has_use_server_directive(program: &Program) -> bool {
+    let is_directive = |stmt: &Stmt| match stmt {
+        Stmt::Expr(expr) => {
+            matches!(&*expr.expr, Expr::Lit(Lit::Str(str)) if &*str.value == "use server")
+        }
+        _ => false,
+    };
+    match program {
+        Program::Module(m) => m
+            .body
+            .iter()
+            .map_while(|item| match item {
+                ModuleItem::Stmt(stmt @ Stmt::Expr(_)) => Some(stmt),
+                _ => None,
+            })
+            .any(is_directive),
+        Program::Script(s) => s
+            .body
+            .iter()
+            .take_while(|stmt| matches!(stmt, Stmt::Expr(_)))
+            .any(is_directive),
+    }
+}
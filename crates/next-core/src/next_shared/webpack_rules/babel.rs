@@ -1,7 +1,7 @@
 use anyhow::Result;
 use turbo_tasks::{Value, Vc};
 use turbo_tasks_fs::{self, FileSystemEntryType, FileSystemPath};
-use turbopack::module_options::{LoaderRuleItem, OptionWebpackRules, WebpackRules};
+use turbopack::module_options::{LoaderEnforce, LoaderRuleItem, OptionWebpackRules, WebpackRules};
 use turbopack_core::{
     issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
     reference_type::{CommonJsReferenceSubType, ReferenceType},
@@ -50,15 +50,21 @@ pub async fn maybe_add_babel_loader(
         let mut has_emitted_babel_resolve_issue = false;
         let mut has_changed = false;
         for pattern in ["*.js", "*.jsx", "*.ts", "*.tsx", "*.cjs", "*.mjs"] {
-            let rule = rules.get_mut(pattern);
-            let has_babel_loader = if let Some(rule) = rule.as_ref() {
-                rule.loaders
-                    .await?
-                    .iter()
-                    .any(|c| c.loader == "babel-loader")
-            } else {
-                false
-            };
+            let pipeline = rules.get_mut(pattern);
+            let mut has_babel_loader = false;
+            if let Some(pipeline) = pipeline.as_ref() {
+                for rule in pipeline.iter() {
+                    if rule
+                        .loaders
+                        .await?
+                        .iter()
+                        .any(|c| c.loader == "babel-loader")
+                    {
+                        has_babel_loader = true;
+                        break;
+                    }
+                }
+            }
 
             if !has_babel_loader {
                 if !has_emitted_babel_resolve_issue
@@ -86,18 +92,25 @@ pub async fn maybe_add_babel_loader(
                     loader: "babel-loader".into(),
                     options: Default::default(),
                 };
+                // Add it to the last rule that applies to all files of the pattern
+                let rule = pipeline.and_then(|pipeline| {
+                    pipeline
+                        .iter_mut()
+                        .rev()
+                        .find(|rule| rule.enforce == LoaderEnforce::Normal && rule.issuer.is_none())
+                });
                 if let Some(rule) = rule {
                     let mut loaders = rule.loaders.await?.clone_value();
                     loaders.push(loader);
                     rule.loaders = Vc::cell(loaders);
                 } else {
-                    rules.insert(
-                        pattern.into(),
-                        LoaderRuleItem {
-                            loaders: Vc::cell(vec![loader]),
-                            rename_as: Some("*".into()),
-                        },
-                    );
+                    rules
+                        .entry(pattern.into())
+                        .or_default()
+                        .push(LoaderRuleItem::new(
+                            Vc::cell(vec![loader]),
+                            Some("*".into()),
+                        ));
                 }
                 has_changed = true;
             }
@@ -27,6 +27,7 @@ pub async fn webpack_loader_options(
         WebpackLoadersOptions {
             rules,
             loader_runner_package: Some(loader_runner_package_mapping()),
+            active_issuers: Vec::new(),
         }
         .cell()
     }))
@@ -3,7 +3,7 @@ use std::mem::take;
 use anyhow::{bail, Result};
 use serde_json::Value as JsonValue;
 use turbo_tasks::Vc;
-use turbopack::module_options::{LoaderRuleItem, OptionWebpackRules, WebpackRules};
+use turbopack::module_options::{LoaderEnforce, LoaderRuleItem, OptionWebpackRules, WebpackRules};
 use turbopack_node::transforms::webpack::WebpackLoaderItem;
 
 #[turbo_tasks::function]
@@ -31,7 +31,7 @@ pub async fn maybe_add_sass_loader(
         let additional_data = sass_options
             .get("prependData")
             .or(sass_options.get("additionalData"));
-        let rule = rules.get_mut(pattern);
+        let pipeline = rules.get_mut(pattern);
         let sass_loader = WebpackLoaderItem {
             loader: "next/dist/compiled/sass-loader".into(),
             options: take(
@@ -57,7 +57,15 @@ pub async fn maybe_add_sass_loader(
             ),
         };
 
-        if let Some(rule) = rule {
+        if let Some(pipeline) = pipeline {
+            // Apply it as part of the last rule that applies to all files of the pattern
+            let Some(rule) = pipeline
+                .iter_mut()
+                .rev()
+                .find(|rule| rule.enforce == LoaderEnforce::Normal && rule.issuer.is_none())
+            else {
+                continue;
+            };
             // Without `as`, loader result would be JS code, so we don't want to apply
             // sass-loader on that.
             let Some(rename_as) = rule.rename_as.as_ref() else {
@@ -75,10 +83,10 @@ pub async fn maybe_add_sass_loader(
         } else {
             rules.insert(
                 pattern.into(),
-                LoaderRuleItem {
-                    loaders: Vc::cell(vec![resolve_url_loader, sass_loader]),
-                    rename_as: Some(format!("*{rename}").into()),
-                },
+                vec![LoaderRuleItem::new(
+                    Vc::cell(vec![resolve_url_loader, sass_loader]),
+                    Some(format!("*{rename}").into()),
+                )],
             );
         }
     }
@@ -1,5 +1,13 @@
+use std::collections::BTreeMap;
+
 use turbo_tasks::{fxindexmap, RcStr, Vc};
-use turbopack_core::diagnostics::{Diagnostic, DiagnosticPayload};
+use turbopack_core::diagnostics::{Diagnostic, DiagnosticPayload, PlainDiagnostic};
+
+const EVENT_BUILD_FEATURE_USAGE: &str = "EVENT_BUILD_FEATURE_USAGE";
+
+/// The modules of the image component, as reported by [ModuleFeatureTelemetry].
+const IMAGE_COMPONENT_FEATURES: [&str; 3] =
+    ["next/image", "next/future/image", "next/legacy/image"];
 
 /// A structure that keeps track of whether a particular Next.js feature is
 /// enabled for the telemetry.
@@ -16,7 +24,7 @@ pub struct NextFeatureTelemetry {
 impl NextFeatureTelemetry {
     pub fn new(feature_name: RcStr, enabled: bool) -> Self {
         NextFeatureTelemetry {
-            event_name: "EVENT_BUILD_FEATURE_USAGE".into(),
+            event_name: EVENT_BUILD_FEATURE_USAGE.into(),
             feature_name,
             enabled,
         }
@@ -56,7 +64,7 @@ pub struct ModuleFeatureTelemetry {
 impl ModuleFeatureTelemetry {
     pub fn new(feature_name: RcStr, invocation_count: usize) -> Self {
         ModuleFeatureTelemetry {
-            event_name: "EVENT_BUILD_FEATURE_USAGE".into(),
+            event_name: EVENT_BUILD_FEATURE_USAGE.into(),
             feature_name,
             invocation_count,
         }
@@ -83,3 +91,106 @@ impl Diagnostic for ModuleFeatureTelemetry {
         })
     }
 }
+
+/// The usage of Next.js features by a project, aggregated from the
+/// [NextFeatureTelemetry] and [ModuleFeatureTelemetry] diagnostics that are
+/// emitted while building it.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Default, Clone)]
+pub struct FeatureUsage {
+    /// Whether the project has an app directory.
+    pub app_router: bool,
+    /// The number of modules that define Server Actions.
+    pub server_actions: usize,
+    /// The number of imports of the image component.
+    pub image_component: usize,
+    /// Whether MDX pages or the MDX compiler are enabled.
+    pub mdx: bool,
+    /// The invocation count of every reported feature by name. Enabled
+    /// features count as a single invocation.
+    pub features: BTreeMap<RcStr, usize>,
+}
+
+impl FeatureUsage {
+    pub fn from_diagnostics<'a>(
+        diagnostics: impl IntoIterator<Item = &'a PlainDiagnostic>,
+    ) -> Self {
+        let mut features = BTreeMap::<RcStr, usize>::new();
+        for diagnostic in diagnostics {
+            if diagnostic.name.as_str() != EVENT_BUILD_FEATURE_USAGE {
+                continue;
+            }
+            for (feature_name, value) in &diagnostic.payload {
+                let count = match value.as_str() {
+                    "true" => 1,
+                    "false" => 0,
+                    value => value.parse().unwrap_or_default(),
+                };
+                *features.entry(feature_name.clone()).or_default() += count;
+            }
+        }
+
+        let count = |feature_name: &str| features.get(feature_name).copied().unwrap_or_default();
+        FeatureUsage {
+            app_router: count("appRouter") > 0,
+            server_actions: count("serverActions"),
+            image_component: IMAGE_COMPONENT_FEATURES.into_iter().map(count).sum(),
+            mdx: count("mdx") > 0,
+            features,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use turbo_tasks::{fxindexmap, RcStr};
+    use turbopack_core::diagnostics::PlainDiagnostic;
+
+    use super::{FeatureUsage, EVENT_BUILD_FEATURE_USAGE};
+
+    fn diagnostic(name: &str, feature_name: &str, value: &str) -> PlainDiagnostic {
+        PlainDiagnostic {
+            category: "NextFeatureTelemetry_category_tbd".into(),
+            name: name.into(),
+            payload: fxindexmap! {
+                RcStr::from(feature_name) => RcStr::from(value),
+            },
+        }
+    }
+
+    #[test]
+    fn aggregates_feature_usage() {
+        let diagnostics = [
+            diagnostic(EVENT_BUILD_FEATURE_USAGE, "appRouter", "true"),
+            diagnostic(EVENT_BUILD_FEATURE_USAGE, "mdx", "false"),
+            diagnostic(EVENT_BUILD_FEATURE_USAGE, "serverActions", "1"),
+            diagnostic(EVENT_BUILD_FEATURE_USAGE, "serverActions", "1"),
+            diagnostic(EVENT_BUILD_FEATURE_USAGE, "next/image", "2"),
+            diagnostic(EVENT_BUILD_FEATURE_USAGE, "next/legacy/image", "1"),
+            // Other events are ignored.
+            diagnostic("EVENT_OTHER", "serverActions", "5"),
+        ];
+        let usage = FeatureUsage::from_diagnostics(&diagnostics);
+        assert!(usage.app_router);
+        assert!(!usage.mdx);
+        assert_eq!(usage.server_actions, 2);
+        assert_eq!(usage.image_component, 3);
+        assert_eq!(usage.features.get("mdx"), Some(&0));
+        assert_eq!(usage.features.get("next/image"), Some(&2));
+    }
+
+    #[test]
+    fn ignores_invalid_counts() {
+        let usage = FeatureUsage::from_diagnostics(&[diagnostic(
+            EVENT_BUILD_FEATURE_USAGE,
+            "serverActions",
+            "many",
+        )]);
+        assert_eq!(usage.server_actions, 0);
+        assert!(
+            FeatureUsage::from_diagnostics(&Vec::<PlainDiagnostic>::new())
+                .features
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::{future::Future, pin::Pin};
+
+use anyhow::Result;
+use turbo_tasks::{FxIndexMap, FxIndexSet, RcStr, ResolvedVc, Vc};
+use turbo_tasks_fs::{glob::Glob, DirectoryEntry, FileSystemPath, ReadGlobResult};
+use turbopack_core::issue::{
+    Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString,
+};
+
+use crate::next_config::NextConfig;
+
+/// The files that are needed to run a route on the server.
+#[turbo_tasks::value(transparent)]
+pub struct TracedFiles(Vec<ResolvedVc<FileSystemPath>>);
+
+/// Applies the `outputFileTracingIncludes` and `outputFileTracingExcludes`
+/// config to the files traced for `route`.
+///
+/// The globs of all keys matching the route are merged. Files matching the
+/// include globs are added to `traced_files`, then files matching the exclude
+/// globs are removed. Both are relative to the project directory. When any
+/// globs apply to the route, the resulting number of traced files is reported.
+#[turbo_tasks::function]
+pub async fn apply_output_file_tracing_config(
+    next_config: Vc<NextConfig>,
+    project_path: Vc<FileSystemPath>,
+    route: RcStr,
+    traced_files: Vc<TracedFiles>,
+) -> Result<Vc<TracedFiles>> {
+    let config = next_config.await?;
+    let includes = globs_for_route(config.output_file_tracing_includes.as_ref(), &route)?;
+    let excludes = globs_for_route(config.output_file_tracing_excludes.as_ref(), &route)?;
+    if includes.is_empty() && excludes.is_empty() {
+        return Ok(traced_files);
+    }
+
+    let mut files: FxIndexSet<ResolvedVc<FileSystemPath>> =
+        traced_files.await?.iter().copied().collect();
+    let traced = files.len();
+
+    for include in includes {
+        let (dir, glob) = glob_base(project_path, &include);
+        let result = dir.read_glob(Glob::new(glob), true);
+        add_glob_results(result, &mut files).await?;
+    }
+    let included = files.len() - traced;
+
+    let mut excluded = 0;
+    if !excludes.is_empty() {
+        let excludes = excludes
+            .iter()
+            .map(|exclude| Glob::parse(exclude.trim_start_matches("./")))
+            .collect::<Result<Vec<_>>>()?;
+        let project_path_value = project_path.await?;
+        let mut retained = FxIndexSet::default();
+        for file in files {
+            let path = file.await?;
+            let relative = project_path_value
+                .get_relative_path_to(&path)
+                .unwrap_or_else(|| path.path.clone());
+            let relative = relative.trim_start_matches("./");
+            if excludes.iter().any(|exclude| exclude.execute(relative)) {
+                excluded += 1;
+            } else {
+                retained.insert(file);
+            }
+        }
+        files = retained;
+    }
+
+    OutputFileTracingIssue {
+        project_path: project_path.to_resolved().await?,
+        route,
+        traced: files.len(),
+        included,
+        excluded,
+    }
+    .cell()
+    .emit();
+
+    Ok(Vc::cell(files.into_iter().collect()))
+}
+
+/// Collects the globs of all keys in `config` that match `route`.
+fn globs_for_route(
+    config: Option<&FxIndexMap<RcStr, Vec<RcStr>>>,
+    route: &str,
+) -> Result<Vec<RcStr>> {
+    let mut globs = Vec::new();
+    for (key, values) in config.into_iter().flatten() {
+        let key = Glob::parse(key)?;
+        // Keys are matched against the route with and without the leading slash,
+        // e.g. both `/api/*` and `api/*` match the `/api/hello` route.
+        if key.execute(route) || key.execute(route.trim_start_matches('/')) {
+            globs.extend(values.iter().map(|glob| glob.replace('\\', "/").into()));
+        }
+    }
+    Ok(globs)
+}
+
+/// Splits the leading `./` and `../` segments off `glob`, as globs can't match
+/// them, and returns the directory they refer to along with the rest of the
+/// glob.
+fn glob_base(project_path: Vc<FileSystemPath>, glob: &str) -> (Vc<FileSystemPath>, RcStr) {
+    let mut dir = project_path;
+    let mut glob = glob;
+    loop {
+        if let Some(rest) = glob.strip_prefix("./") {
+            glob = rest;
+        } else if let Some(rest) = glob.strip_prefix("../") {
+            dir = dir.parent();
+            glob = rest;
+        } else {
+            return (dir, glob.into());
+        }
+    }
+}
+
+async fn add_glob_results(
+    result: Vc<ReadGlobResult>,
+    files: &mut FxIndexSet<ResolvedVc<FileSystemPath>>,
+) -> Result<()> {
+    let result = result.await?;
+    for entry in result.results.values() {
+        if let DirectoryEntry::File(path) = entry {
+            files.insert(*path);
+        }
+    }
+    for result in result.inner.values() {
+        fn recurse<'a>(
+            result: Vc<ReadGlobResult>,
+            files: &'a mut FxIndexSet<ResolvedVc<FileSystemPath>>,
+        ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+            Box::pin(add_glob_results(result, files))
+        }
+        // Boxing for async recursion
+        recurse(**result, files).await?;
+    }
+    Ok(())
+}
+
+/// Reports the number of files traced for a route, so users can validate the
+/// contents of their serverless bundles.
+#[turbo_tasks::value(shared)]
+struct OutputFileTracingIssue {
+    project_path: ResolvedVc<FileSystemPath>,
+    route: RcStr,
+    traced: usize,
+    included: usize,
+    excluded: usize,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for OutputFileTracingIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Info.into()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Misc.into()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.project_path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(format!("Traced {} files for {}", self.traced, self.route).into()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(
+                format!(
+                    "{} files were added by outputFileTracingIncludes and {} files were removed \
+                     by outputFileTracingExcludes.",
+                    self.included, self.excluded
+                )
+                .into(),
+            )
+            .cell(),
+        ))
+    }
+}
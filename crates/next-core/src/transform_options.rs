@@ -41,21 +41,56 @@ pub async fn get_typescript_transform_options(
 ) -> Result<Vc<TypescriptTransformOptions>> {
     let tsconfig = get_typescript_options(project_path).await?;
 
-    let use_define_for_class_fields = if let Some(tsconfig) = tsconfig {
-        read_from_tsconfigs(&tsconfig, |json, _| {
-            json["compilerOptions"]["useDefineForClassFields"].as_bool()
-        })
-        .await?
-        .unwrap_or(false)
-    } else {
-        false
+    let Some(tsconfig) = tsconfig else {
+        return Ok(TypescriptTransformOptions::default().cell());
     };
 
-    let ts_transform_options = TypescriptTransformOptions {
-        use_define_for_class_fields,
-    };
+    // Each option is looked up individually so that options missing from a
+    // tsconfig.json fall back to the config it `extends`.
+    let mut compiler_options = serde_json::Map::new();
+    for name in [
+        "useDefineForClassFields",
+        "verbatimModuleSyntax",
+        "isolatedModules",
+        "preserveValueImports",
+        "importsNotUsedAsValues",
+    ] {
+        let value = read_from_tsconfigs(&tsconfig, move |json, _| {
+            let value = &json["compilerOptions"][name];
+            (!value.is_null()).then(|| value.clone())
+        })
+        .await?;
+        if let Some(value) = value {
+            compiler_options.insert(name.to_string(), value);
+        }
+    }
 
-    Ok(ts_transform_options.cell())
+    Ok(typescript_transform_options(&compiler_options).cell())
+}
+
+/// Maps the (merged) `compilerOptions` of a tsconfig.json to the options of
+/// the typescript transform.
+fn typescript_transform_options(
+    compiler_options: &serde_json::Map<String, serde_json::Value>,
+) -> TypescriptTransformOptions {
+    let read_bool = |name: &str| compiler_options.get(name).and_then(|value| value.as_bool());
+
+    // `preserveValueImports` combined with `isolatedModules` is the predecessor
+    // of `verbatimModuleSyntax` and has the same elision semantics.
+    let verbatim_module_syntax = read_bool("verbatimModuleSyntax").unwrap_or_else(|| {
+        read_bool("isolatedModules").unwrap_or(false)
+            && read_bool("preserveValueImports").unwrap_or(false)
+    });
+    let preserve_unused_imports = compiler_options
+        .get("importsNotUsedAsValues")
+        .and_then(|value| value.as_str())
+        .is_some_and(|value| matches!(value, "preserve" | "error"));
+
+    TypescriptTransformOptions {
+        use_define_for_class_fields: read_bool("useDefineForClassFields").unwrap_or(false),
+        verbatim_module_syntax,
+        preserve_unused_imports,
+    }
 }
 
 /// Build the transform options for the decorators.
@@ -176,3 +211,63 @@ pub async fn get_jsx_transform_options(
 
     Ok(react_transform_options.cell())
 }
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::typescript_transform_options;
+
+    fn options(compiler_options: serde_json::Value) -> (bool, bool, bool) {
+        let serde_json::Value::Object(compiler_options) = compiler_options else {
+            unreachable!()
+        };
+        let options = typescript_transform_options(&compiler_options);
+        (
+            options.use_define_for_class_fields,
+            options.verbatim_module_syntax,
+            options.preserve_unused_imports,
+        )
+    }
+
+    #[test]
+    fn test_typescript_transform_options() {
+        assert_eq!(options(json!({})), (false, false, false));
+        assert_eq!(
+            options(json!({ "useDefineForClassFields": true })),
+            (true, false, false)
+        );
+        assert_eq!(
+            options(json!({ "verbatimModuleSyntax": true })),
+            (false, true, false)
+        );
+        assert_eq!(
+            options(json!({ "preserveValueImports": true })),
+            (false, false, false)
+        );
+        assert_eq!(
+            options(json!({ "preserveValueImports": true, "isolatedModules": true })),
+            (false, true, false)
+        );
+        assert_eq!(
+            options(json!({
+                "verbatimModuleSyntax": false,
+                "preserveValueImports": true,
+                "isolatedModules": true
+            })),
+            (false, false, false)
+        );
+        assert_eq!(
+            options(json!({ "importsNotUsedAsValues": "preserve" })),
+            (false, false, true)
+        );
+        assert_eq!(
+            options(json!({ "importsNotUsedAsValues": "error" })),
+            (false, false, true)
+        );
+        assert_eq!(
+            options(json!({ "importsNotUsedAsValues": "remove" })),
+            (false, false, false)
+        );
+    }
+}
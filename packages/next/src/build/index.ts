@@ -149,6 +149,7 @@ import {
   teardownHeapProfiler,
   createDefineEnv,
 } from './swc'
+import type { Project } from './swc/types'
 import { getNamedRouteRegex } from '../shared/lib/router/utils/route-regex'
 import { getFilesInDir } from '../lib/get-files-in-dir'
 import { eventSwcPlugins } from '../telemetry/events/swc-plugins'
@@ -730,6 +731,30 @@ async function writeFullyStaticExport(
   appWorker.end()
 }
 
+/**
+ * Writes the static export from the pages that were prerendered into the
+ * dist directory, and fails the build for routes that can't be exported.
+ */
+async function writeTurbopackStaticExport(
+  project: Project,
+  configOutDir: string
+): Promise<void> {
+  const { issues } = await project.exportStaticSite(configOutDir)
+  const errors = issues.filter((issue) => issue.severity !== 'warning')
+  for (const issue of issues) {
+    if (issue.severity === 'warning' && isRelevantWarning(issue)) {
+      Log.warn(formatIssue(issue))
+    }
+  }
+  if (errors.length > 0) {
+    throw new Error(
+      `Static export failed with ${errors.length} errors:\n${errors
+        .map((issue) => formatIssue(issue))
+        .join('\n')}`
+    )
+  }
+}
+
 async function getBuildId(
   isGenerateMode: boolean,
   distDir: string,
@@ -1397,6 +1422,7 @@ export default async function build(
         duration: number
         buildTraceContext: undefined
         shutdownPromise: Promise<void>
+        staticExportProject: Project | undefined
       }> {
         if (!IS_TURBOPACK_BUILD) {
           throw new Error("next build doesn't support turbopack yet")
@@ -1416,6 +1442,9 @@ export default async function build(
           'last 1 Chrome versions, last 1 Firefox versions, last 1 Safari versions, last 1 Edge versions',
         ]
 
+        const cacheArchive = config.experimental.turbo?.cacheArchive
+          ? path.resolve(dir, config.experimental.turbo.cacheArchive)
+          : undefined
         const project = await bindings.turbo.createProject(
           {
             projectPath: dir,
@@ -1450,6 +1479,7 @@ export default async function build(
           {
             persistentCaching: isPersistentCachingEnabled(config),
             memoryLimit: config.experimental.turbo?.memoryLimit,
+            importCache: cacheArchive,
           }
         )
 
@@ -1641,7 +1671,32 @@ export default async function build(
           }
         }
 
-        const shutdownPromise = project.shutdown()
+        // The feature usage is computed from the module graph that was just
+        // built, so the source files don't need to be scanned again.
+        const featureUsage = await project.featureUsage()
+        telemetry.record(
+          Object.entries(featureUsage.features).map(
+            ([featureName, invocationCount]) => ({
+              eventName: EVENT_BUILD_FEATURE_USAGE,
+              payload: {
+                featureName,
+                invocationCount,
+              } as EventBuildFeatureUsage,
+            })
+          )
+        )
+
+        if (cacheArchive && isPersistentCachingEnabled(config)) {
+          await project.exportCache(cacheArchive)
+        }
+
+        // The static export is written after the pages were prerendered, so
+        // the project is kept until then.
+        const staticExportProject =
+          config.output === 'export' ? project : undefined
+        const shutdownPromise = staticExportProject
+          ? Promise.resolve()
+          : project.shutdown()
 
         if (warnings.length > 0) {
           Log.warn(
@@ -1668,6 +1723,7 @@ export default async function build(
           duration: time[0] + time[1] / 1e9,
           buildTraceContext: undefined,
           shutdownPromise,
+          staticExportProject,
         }
       }
 
@@ -1713,14 +1769,17 @@ export default async function build(
       })
 
       let shutdownPromise = Promise.resolve()
+      let staticExportProject: Project | undefined
       if (!isGenerateMode) {
         if (turboNextBuild) {
           const {
             duration: compilerDuration,
             shutdownPromise: p,
+            staticExportProject: project,
             ...rest
           } = await turbopackBuild()
           shutdownPromise = p
+          staticExportProject = project
           traceMemoryUsage('Finished build', nextBuildSpan)
 
           buildTraceContext = rest.buildTraceContext
@@ -3804,13 +3863,23 @@ export default async function build(
       }
 
       if (config.output === 'export') {
-        await writeFullyStaticExport(
-          config,
-          dir,
-          enabledDirectories,
-          configOutDir,
-          nextBuildSpan
-        )
+        if (staticExportProject) {
+          const project = staticExportProject
+          await nextBuildSpan
+            .traceChild('turbopack-static-export')
+            .traceAsyncFn(() =>
+              writeTurbopackStaticExport(project, configOutDir)
+            )
+          shutdownPromise = project.shutdown()
+        } else {
+          await writeFullyStaticExport(
+            config,
+            dir,
+            enabledDirectories,
+            configOutDir,
+            nextBuildSpan
+          )
+        }
       }
 
       if (config.output === 'standalone') {
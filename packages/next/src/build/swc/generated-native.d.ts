@@ -61,6 +61,12 @@ export interface NapiWrittenEndpoint {
   clientPaths: Array<string>
   serverPaths: Array<NapiServerPath>
   config: NapiEndpointConfig
+  /**
+   * The time to compile and write the endpoint in this call, in
+   * milliseconds. Work that is shared with other endpoints counts for the
+   * endpoint that computed it first.
+   */
+  durationMs: number
 }
 export function endpointWriteToDisk(endpoint: {
   __napiType: 'Endpoint'
@@ -177,6 +183,27 @@ export interface NapiTurboEngineOptions {
   persistentCaching?: boolean
   /** An upper bound of memory that turbopack will attempt to stay under. */
   memoryLimit?: number
+  /**
+   * The maximum number of root tasks, e.g. HMR subscriptions, that are
+   * recomputed concurrently. Unlimited by default.
+   */
+  rootTaskConcurrency?: number
+  /**
+   * The maximum number of tasks that are computed concurrently. Waiting
+   * tasks are admitted by priority, and HMR updates come first. Unlimited by
+   * default.
+   */
+  taskConcurrency?: number
+  /**
+   * A cache archive, written by `projectExportCache`, that is imported when
+   * the persistent cache doesn't exist yet.
+   */
+  importCache?: string
+  /**
+   * Serve the outputs of the persistent cache immediately after a restart
+   * while the files they depend on are checked in the background.
+   */
+  backgroundRevalidation?: boolean
 }
 export function projectNew(
   options: NapiProjectOptions,
@@ -186,6 +213,48 @@ export function projectUpdate(
   project: { __napiType: 'Project' },
   options: NapiPartialProjectOptions
 ): Promise<void>
+/**
+ * Invalidates all computations that depend on the invalidation key, e.g.
+ * after a CMS reports a content update. See [`turbo_tasks::depend_on_key`].
+ */
+export function projectInvalidateKey(
+  project: { __napiType: 'Project' },
+  key: string
+): Promise<void>
+/**
+ * Sets (strings) and removes (`null`) env variables for the computations,
+ * e.g. after `.env` files changed, and invalidates the computations that read
+ * the changed variables. The env of the process itself is not modified.
+ */
+export function updateProcessEnv(
+  env: Record<string, string | undefined | null>
+): void
+export interface NapiRootTaskQueue {
+  requester: string
+  queued: number
+}
+export interface NapiRootTaskAdmissionMetrics {
+  limit?: number
+  running: number
+  queues: Array<NapiRootTaskQueue>
+  totalQueued: number
+  totalWaitTimeMs: number
+}
+/**
+ * Returns the number of running root tasks and the number of root tasks that
+ * wait for admission by requester, see `rootTaskConcurrency`.
+ */
+export function projectRootTaskAdmissionMetrics(project: {
+  __napiType: 'Project'
+}): NapiRootTaskAdmissionMetrics
+/**
+ * Writes the persistent cache to a portable archive at `path`, which can be
+ * imported on another machine with the `importCache` option.
+ */
+export function projectExportCache(
+  project: { __napiType: 'Project' },
+  path: string
+): Promise<void>
 export function projectShutdown(project: {
   __napiType: 'Project'
 }): Promise<void>
@@ -230,6 +299,15 @@ export function projectEntrypointsSubscribe(
 export function projectHmrEvents(
   project: { __napiType: 'Project' },
   identifier: string,
+  version: string | undefined | null,
+  requester: string | undefined | null,
+  func: (...args: any[]) => any
+): { __napiType: 'RootTask' }
+export function projectHmrSubscribe(
+  project: { __napiType: 'Project' },
+  identifier: string,
+  version: string | undefined | null,
+  requester: string | undefined | null,
   func: (...args: any[]) => any
 ): { __napiType: 'RootTask' }
 export interface HmrIdentifiers {
@@ -285,9 +363,55 @@ export function projectGetSourceMap(
   project: { __napiType: 'Project' },
   filePath: string
 ): Promise<string | null>
+export interface NapiFeatureUsage {
+  appRouter: boolean
+  serverActions: number
+  imageComponent: number
+  mdx: boolean
+  /**
+   * The invocation count of every feature by name, as reported in
+   * `EVENT_BUILD_FEATURE_USAGE` telemetry events.
+   */
+  features: Record<string, number>
+}
+/**
+ * Returns the usage of Next.js features by the project, which is computed
+ * while building its endpoints.
+ */
+export function projectFeatureUsage(project: {
+  __napiType: 'Project'
+}): Promise<NapiFeatureUsage>
+/**
+ * Writes the `out_dir` of a static export (`output: "export"`), relative to
+ * the project directory, from the pages prerendered by `next build`. Files
+ * that were not exported are deleted from it. Routes that can't be exported
+ * are reported as issues.
+ */
+export function projectExportStaticSite(
+  project: { __napiType: 'Project' },
+  outDir: string
+): Promise<TurbopackResult>
+/**
+ * Whether the `images.remotePatterns` config allows optimizing the remote
+ * image at `url`. This is used by the image optimization endpoint.
+ */
+export function projectMatchImageRemotePattern(
+  project: { __napiType: 'Project' },
+  url: string
+): Promise<boolean>
 /** Runs exit handlers for the project registered using the [`ExitHandler`] API. */
 export function projectOnExit(project: { __napiType: 'Project' }): Promise<void>
 export function rootTaskDispose(rootTask: { __napiType: 'RootTask' }): void
+/**
+ * Returns the first free port of `port..port + max_attempts` on `hostname`,
+ * so tools that start the dev servers of several projects at the same time
+ * don't need to probe ports themselves.
+ */
+export function negotiateDevServerPort(
+  hostname: string,
+  port: number,
+  maxAttempts: number
+): number
 export interface NapiIssue {
   severity: string
   stage: string
@@ -32,9 +32,11 @@ import type {
   Binding,
   DefineEnv,
   Endpoint,
+  FeatureUsage,
   HmrIdentifiers,
   Project,
   ProjectOptions,
+  RootTaskAdmissionMetrics,
   Route,
   TurboEngineOptions,
   TurbopackResult,
@@ -733,9 +735,27 @@ function bindingToApi(
       })()
     }
 
-    hmrEvents(identifier: string) {
+    hmrEvents(identifier: string, version?: string, requester?: string) {
       return subscribe<TurbopackResult<Update>>(true, async (callback) =>
-        binding.projectHmrEvents(this._nativeProject, identifier, callback)
+        binding.projectHmrEvents(
+          this._nativeProject,
+          identifier,
+          version,
+          requester,
+          callback
+        )
+      )
+    }
+
+    hmrSubscribe(identifier: string, version?: string, requester?: string) {
+      return subscribe<TurbopackResult<Update>>(true, async (callback) =>
+        binding.projectHmrSubscribe(
+          this._nativeProject,
+          identifier,
+          version,
+          requester,
+          callback
+        )
       )
     }
 
@@ -771,6 +791,34 @@ function bindingToApi(
       )
     }
 
+    featureUsage(): Promise<FeatureUsage> {
+      return binding.projectFeatureUsage(this._nativeProject)
+    }
+
+    exportStaticSite(outDir: string): Promise<TurbopackResult> {
+      return binding.projectExportStaticSite(this._nativeProject, outDir)
+    }
+
+    matchImageRemotePattern(url: string): Promise<boolean> {
+      return binding.projectMatchImageRemotePattern(this._nativeProject, url)
+    }
+
+    invalidateKey(key: string): Promise<void> {
+      return binding.projectInvalidateKey(this._nativeProject, key)
+    }
+
+    updateProcessEnv(env: Record<string, string | undefined>): void {
+      binding.updateProcessEnv(env)
+    }
+
+    rootTaskAdmissionMetrics(): RootTaskAdmissionMetrics {
+      return binding.projectRootTaskAdmissionMetrics(this._nativeProject)
+    }
+
+    exportCache(path: string): Promise<void> {
+      return binding.projectExportCache(this._nativeProject, path)
+    }
+
     shutdown(): Promise<void> {
       return binding.projectShutdown(this._nativeProject)
     }
@@ -1228,6 +1276,7 @@ function loadNative(importPath?: string) {
       },
 
       getTargetTriple: bindings.getTargetTriple,
+      negotiateDevServerPort: bindings.negotiateDevServerPort,
       initCustomTraceSubscriber: bindings.initCustomTraceSubscriber,
       teardownTraceSubscriber: bindings.teardownTraceSubscriber,
       initHeapProfiler: bindings.initHeapProfiler,
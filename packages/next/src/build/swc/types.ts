@@ -34,6 +34,11 @@ export interface Binding {
   parse(src: string, options: any): Promise<string>
 
   getTargetTriple(): string | undefined
+  negotiateDevServerPort?(
+    hostname: string,
+    port: number,
+    maxAttempts: number
+  ): number
 
   initCustomTraceSubscriber?(traceOutFilePath?: string): ExternalObject<RefCell>
   teardownTraceSubscriber?(guardExternal: ExternalObject<RefCell>): void
@@ -121,6 +126,31 @@ export interface TurboEngineOptions {
    * An upper bound of memory that turbopack will attempt to stay under.
    */
   memoryLimit?: number
+
+  /**
+   * The maximum number of root tasks, e.g. HMR subscriptions, that are
+   * recomputed concurrently. Unlimited by default.
+   */
+  rootTaskConcurrency?: number
+
+  /**
+   * The maximum number of tasks that are computed concurrently. Waiting
+   * tasks are admitted by priority, and HMR updates come first. Unlimited by
+   * default.
+   */
+  taskConcurrency?: number
+
+  /**
+   * A cache archive, written by `Project.exportCache`, that is imported when
+   * the persistent cache doesn't exist yet.
+   */
+  importCache?: string
+
+  /**
+   * Serve the outputs of the persistent cache immediately after a restart
+   * while the files they depend on are checked in the background.
+   */
+  backgroundRevalidation?: boolean
 }
 
 export interface Middleware {
@@ -148,6 +178,8 @@ interface BaseUpdate {
   }
   diagnostics: unknown[]
   issues: Issue[]
+  /** The id of the version the resource is at after applying this update. */
+  version?: string
 }
 
 interface IssuesUpdate extends BaseUpdate {
@@ -160,15 +192,31 @@ interface EcmascriptMergedUpdate {
   entries: { [moduleName: string]: { code: string; map: string; url: string } }
 }
 
+interface ChunkListUpdate {
+  type: 'ChunkListUpdate'
+  merged: EcmascriptMergedUpdate[] | undefined
+}
+
 interface PartialUpdate extends BaseUpdate {
   type: 'partial'
-  instruction: {
-    type: 'ChunkListUpdate'
-    merged: EcmascriptMergedUpdate[] | undefined
-  }
+  instruction: ChunkListUpdate
+}
+
+/**
+ * Brings a client whose version isn't known anymore up to date by applying the
+ * updates it missed in order.
+ */
+interface ReplayUpdate extends BaseUpdate {
+  type: 'replay'
+  instructions: ChunkListUpdate[]
 }
 
-export type Update = IssuesUpdate | PartialUpdate
+interface SyncUpdate extends BaseUpdate {
+  type: 'sync'
+  content: string
+}
+
+export type Update = IssuesUpdate | PartialUpdate | ReplayUpdate | SyncUpdate
 
 export interface HmrIdentifiers {
   identifiers: string[]
@@ -195,17 +243,65 @@ export type UpdateMessage =
       value: UpdateInfo
     }
 
+export interface FeatureUsage {
+  appRouter: boolean
+  serverActions: number
+  imageComponent: number
+  mdx: boolean
+  /**
+   * The invocation count of every feature by name, as reported in
+   * `EVENT_BUILD_FEATURE_USAGE` telemetry events.
+   */
+  features: Record<string, number>
+}
+
 export interface UpdateInfo {
   duration: number
   tasks: number
 }
 
+export interface RootTaskAdmissionMetrics {
+  /**
+   * The maximum number of concurrent recomputations, if limited.
+   */
+  limit?: number
+  running: number
+  /**
+   * The number of waiting recomputations by requester.
+   */
+  queues: { requester: string; queued: number }[]
+  totalQueued: number
+  totalWaitTimeMs: number
+}
+
 export interface Project {
   update(options: Partial<ProjectOptions>): Promise<void>
 
   entrypointsSubscribe(): AsyncIterableIterator<TurbopackResult<Entrypoints>>
 
-  hmrEvents(identifier: string): AsyncIterableIterator<TurbopackResult<Update>>
+  /**
+   * Subscribes to HMR updates of `identifier`. When `version` is the id of the
+   * last version applied by a reconnecting client, the first event brings the
+   * client up to date from that version. `requester` identifies the client
+   * for the fair admission of recomputations, see `rootTaskConcurrency`.
+   */
+  hmrEvents(
+    identifier: string,
+    version?: string,
+    requester?: string
+  ): AsyncIterableIterator<TurbopackResult<Update>>
+
+  /**
+   * Like `hmrEvents`, but for clients that don't load the chunks from the dev
+   * server. Instead of restarting, they receive the full content of
+   * `identifier` in a `sync` update, which is also sent first when no
+   * `version` is given.
+   */
+  hmrSubscribe(
+    identifier: string,
+    version?: string,
+    requester?: string
+  ): AsyncIterableIterator<TurbopackResult<Update>>
 
   hmrIdentifiersSubscribe(): AsyncIterableIterator<
     TurbopackResult<HmrIdentifiers>
@@ -223,6 +319,51 @@ export interface Project {
     aggregationMs: number
   ): AsyncIterableIterator<TurbopackResult<UpdateMessage>>
 
+  /**
+   * Returns the usage of Next.js features by the project. This builds all
+   * endpoints, so it's cheap after they have been written to disk.
+   */
+  featureUsage(): Promise<FeatureUsage>
+
+  /**
+   * Writes the `outDir` of a static export (`output: "export"`), relative to
+   * the project directory, from the prerendered pages. Files that were not
+   * exported are deleted from it. Routes that can't be exported are reported
+   * as issues.
+   */
+  exportStaticSite(outDir: string): Promise<TurbopackResult>
+
+  /**
+   * Whether the `images.remotePatterns` config, as validated by Turbopack,
+   * allows optimizing the remote image at `url`.
+   */
+  matchImageRemotePattern(url: string): Promise<boolean>
+
+  /**
+   * Invalidates all computations that depend on the invalidation key, e.g.
+   * after a CMS reports a content update.
+   */
+  invalidateKey(key: string): Promise<void>
+
+  /**
+   * Sets (strings) and removes (`undefined`) env variables for the
+   * computations, e.g. after `.env` files changed, and invalidates the
+   * computations that read the changed variables.
+   */
+  updateProcessEnv(env: Record<string, string | undefined>): void
+
+  /**
+   * Returns the number of running recomputations and the number of
+   * recomputations that wait for admission by requester.
+   */
+  rootTaskAdmissionMetrics(): RootTaskAdmissionMetrics
+
+  /**
+   * Writes the persistent cache to a portable archive at `path`, e.g. to
+   * share a warmed cache from CI with other machines.
+   */
+  exportCache(path: string): Promise<void>
+
   shutdown(): Promise<void>
 
   onExit(): Promise<void>
@@ -307,6 +448,8 @@ export type WrittenEndpoint =
       /** All server paths that have been written for the endpoint. */
       serverPaths: ServerPath[]
       config: EndpointConfig
+      /** The time to compile and write the endpoint in this call, in milliseconds. */
+      durationMs: number
     }
   | {
       type: 'edge'
@@ -315,6 +458,8 @@ export type WrittenEndpoint =
       /** All server paths that have been written for the endpoint. */
       serverPaths: ServerPath[]
       config: EndpointConfig
+      /** The time to compile and write the endpoint in this call, in milliseconds. */
+      durationMs: number
     }
   | {
       type: 'none'
@@ -32,7 +32,10 @@ import type {
   HMR_ACTION_TYPES,
   TurbopackMsgToBrowser,
 } from '../../../../server/dev/hot-reloader-types'
-import { extractModulesFromTurbopackMessage } from '../../../../server/dev/extract-modules-from-turbopack-message'
+import {
+  extractModulesFromTurbopackMessage,
+  isTurbopackIssuesOnlyMessage,
+} from '../../../../server/dev/extract-modules-from-turbopack-message'
 import { REACT_REFRESH_FULL_RELOAD_FROM_ERROR } from '../shared'
 import type { HydrationErrorState } from '../internal/helpers/hydration-error-info'
 import type { DebugInfo } from '../types'
@@ -444,6 +447,13 @@ function processMessage(
       break
     }
     case HMR_ACTIONS_SENT_TO_BROWSER.TURBOPACK_MESSAGE: {
+      if (isTurbopackIssuesOnlyMessage(obj.data)) {
+        processTurbopackMessage({
+          type: HMR_ACTIONS_SENT_TO_BROWSER.TURBOPACK_MESSAGE,
+          data: obj.data,
+        })
+        break
+      }
       const updatedModules = extractModulesFromTurbopackMessage(obj.data)
       dispatcher.onBeforeRefresh()
       processTurbopackMessage({
@@ -44,7 +44,10 @@ import type {
   HMR_ACTION_TYPES,
   TurbopackMsgToBrowser,
 } from '../../../../server/dev/hot-reloader-types'
-import { extractModulesFromTurbopackMessage } from '../../../../server/dev/extract-modules-from-turbopack-message'
+import {
+  extractModulesFromTurbopackMessage,
+  isTurbopackIssuesOnlyMessage,
+} from '../../../../server/dev/extract-modules-from-turbopack-message'
 import { REACT_REFRESH_FULL_RELOAD_FROM_ERROR } from '../shared'
 import { RuntimeErrorHandler } from '../internal/helpers/runtime-error-handler'
 // This alternative WebpackDevServer combines the functionality of:
@@ -344,14 +347,20 @@ function processMessage(obj: HMR_ACTION_TYPES) {
       break
     }
     case HMR_ACTIONS_SENT_TO_BROWSER.TURBOPACK_MESSAGE: {
+      const issuesOnly = isTurbopackIssuesOnlyMessage(obj.data)
       const updatedModules = extractModulesFromTurbopackMessage(obj.data)
-      onBeforeFastRefresh(updatedModules)
+      if (!issuesOnly) {
+        onBeforeFastRefresh(updatedModules)
+      }
       for (const listener of turbopackMessageListeners) {
         listener({
           type: HMR_ACTIONS_SENT_TO_BROWSER.TURBOPACK_MESSAGE,
           data: obj.data,
         })
       }
+      if (issuesOnly) {
+        break
+      }
       if (RuntimeErrorHandler.hadRuntimeError) {
         console.warn(REACT_REFRESH_FULL_RELOAD_FROM_ERROR)
         performFullReload(null)
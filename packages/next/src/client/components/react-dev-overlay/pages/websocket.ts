@@ -48,12 +48,14 @@ export function connectHMR(options: { path: string; assetPrefix: string }) {
       ) {
         if (
           serverSessionId !== null &&
-          serverSessionId !== msg.data.sessionId
+          serverSessionId !== msg.data.sessionId &&
+          !msg.data.resumable
         ) {
           // Either the server's session id has changed and it's a new server, or
           // it's been too long since we disconnected and we should reload the page.
           // There could be 1) unhandled server errors and/or 2) stale content.
-          // Perform a hard reload of the page.
+          // Perform a hard reload of the page, unless the new server can bring
+          // the page up to date when it resubscribes.
           window.location.reload()
 
           reloading = true
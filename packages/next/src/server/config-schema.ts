@@ -112,6 +112,9 @@ const zTurboRuleConfigItemOptions: zod.ZodType<TurboRuleConfigItemOptions> =
   z.object({
     loaders: z.array(zTurboLoaderItem),
     as: z.string().optional(),
+    enforce: z.enum(['pre', 'post']).optional(),
+    exclude: z.string().optional(),
+    issuer: z.string().optional(),
   })
 
 const zTurboRuleConfigItem: zod.ZodType<TurboRuleConfigItem> = z.union([
@@ -124,7 +127,11 @@ const zTurboRuleConfigItem: zod.ZodType<TurboRuleConfigItem> = z.union([
 ])
 
 const zTurboRuleConfigItemOrShortcut: zod.ZodType<TurboRuleConfigItemOrShortcut> =
-  z.union([z.array(zTurboLoaderItem), zTurboRuleConfigItem])
+  z.union([
+    z.array(zTurboLoaderItem),
+    z.array(zTurboRuleConfigItem),
+    zTurboRuleConfigItem,
+  ])
 
 export const configSchema: zod.ZodType<NextConfig> = z.lazy(() =>
   z.strictObject({
@@ -409,8 +416,42 @@ export const configSchema: zod.ZodType<NextConfig> = z.lazy(() =>
             persistentCaching: z
               .union([z.number(), z.literal(false)])
               .optional(),
+            cacheArchive: z.string().optional(),
+            backgroundRevalidation: z.boolean().optional(),
             memoryLimit: z.number().optional(),
+            rootTaskConcurrency: z.number().int().positive().optional(),
+            taskConcurrency: z.number().int().positive().optional(),
             moduleIdStrategy: z.enum(['named', 'deterministic']).optional(),
+            extractStyledJsx: z.boolean().optional(),
+            usageBasedPolyfills: z.boolean().optional(),
+            reportDuplicatePackages: z.boolean().optional(),
+            excludeOptionalRequires: z.array(z.string()).optional(),
+            externalSourceMapThreshold: z.number().int().nonnegative().optional(),
+            chunkLoadRetries: z.number().int().nonnegative().optional(),
+            sourceMapSourceTemplate: z.string().optional(),
+            strictCsp: z.boolean().optional(),
+            chunkCodeWrapping: z
+              .strictObject({
+                banner: z.string().optional(),
+                footer: z.string().optional(),
+                runtimeInjection: z.string().optional(),
+              })
+              .optional(),
+            moduleFederation: z
+              .strictObject({
+                shareScope: z.string().optional(),
+                remotes: z.record(z.string(), z.string()).optional(),
+                shared: z
+                  .record(
+                    z.string(),
+                    z.strictObject({
+                      version: z.string().optional(),
+                      requiredVersion: z.string().optional(),
+                    })
+                  )
+                  .optional(),
+              })
+              .optional(),
           })
           .optional(),
         optimizePackageImports: z.array(z.string()).optional(),
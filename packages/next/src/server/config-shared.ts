@@ -101,11 +101,26 @@ export type TurboLoaderItem =
 
 export type TurboRuleConfigItemOrShortcut =
   | TurboLoaderItem[]
+  // A pipeline of rules that run one after another
+  | TurboRuleConfigItem[]
   | TurboRuleConfigItem
 
 export type TurboRuleConfigItemOptions = {
   loaders: TurboLoaderItem[]
   as?: string
+  /**
+   * Runs the rule before (`pre`) or after (`post`) the other rules that apply
+   * to the same file, instead of in the order of the configuration.
+   */
+  enforce?: 'pre' | 'post'
+  /**
+   * A glob of files that the rule doesn't apply to.
+   */
+  exclude?: string
+  /**
+   * A glob that the importing module has to match for the rule to apply.
+   */
+  issuer?: string
 }
 
 export type TurboRuleConfigItem =
@@ -155,6 +170,20 @@ export interface ExperimentalTurboOptions {
    */
   memoryLimit?: number
 
+  /**
+   * The maximum number of HMR and entrypoint subscriptions that are recomputed
+   * concurrently in development. Subscriptions of different clients are
+   * admitted fairly. Unlimited by default.
+   */
+  rootTaskConcurrency?: number
+
+  /**
+   * The maximum number of tasks that are computed concurrently in development.
+   * HMR updates are computed first, along with the work they wait for.
+   * Unlimited by default.
+   */
+  taskConcurrency?: number
+
   /**
    * Enable persistent caching for the turbopack dev server and build.
    * Need to provide the expected level of stability, otherwise it will fail.
@@ -162,6 +191,21 @@ export interface ExperimentalTurboOptions {
    */
   unstablePersistentCaching?: number | false
 
+  /**
+   * A cache archive, relative to the project directory, to share a warmed
+   * persistent cache, e.g. from CI with developer machines. `next build`
+   * writes the persistent cache to it, and `next dev` and `next build` import
+   * it when the persistent cache doesn't exist yet.
+   */
+  cacheArchive?: string
+
+  /**
+   * Serve the outputs of the persistent cache immediately when `next dev`
+   * starts, while the files they depend on are checked in the background.
+   * Outdated results are replaced once a changed file is found.
+   */
+  backgroundRevalidation?: boolean
+
   /**
    * Enable tree shaking for the turbopack dev server and build.
    */
@@ -174,6 +218,87 @@ export interface ExperimentalTurboOptions {
    */
   moduleIdStrategy?: 'named' | 'deterministic'
 
+  /**
+   * (`next --turbopack` only) Serve `<style jsx>` blocks without dynamic
+   * interpolations from CSS chunks instead of injecting them at runtime. Static
+   * `css` props of elements are moved to CSS chunks as well and replaced by a
+   * class name.
+   */
+  extractStyledJsx?: boolean
+
+  /**
+   * (`next --turbopack` only) Inject the core-js polyfills of the APIs that a
+   * page uses, but that not all targeted browsers support, at the entry of the
+   * page. Requires `core-js` to be installed.
+   */
+  usageBasedPolyfills?: boolean
+
+  /**
+   * (`next --turbopack` only) Warn about packages that are included in more
+   * than one version in the client code of a page, with the imports that lead
+   * to each version.
+   */
+  reportDuplicatePackages?: boolean
+
+  /**
+   * (`next --turbopack` only) Packages that are not bundled when they are
+   * required in a `try` block, like optional dependencies. The `require`
+   * throws a "Cannot find module" error at runtime instead.
+   */
+  excludeOptionalRequires?: string[]
+
+  /**
+   * (`next --turbopack` only) The size in bytes above which the source maps of
+   * modules in HMR updates are emitted as separate files instead of being
+   * inlined as data URLs, which the browser decodes slowly when they are large.
+   */
+  externalSourceMapThreshold?: number
+
+  /**
+   * (`next --turbopack` only) How often the browser retries loading a chunk
+   * that failed to load, e.g. because of a network error, with an increasing
+   * delay. Retries are disabled by default.
+   */
+  chunkLoadRetries?: number
+
+  /**
+   * (`next --turbopack` only) A template to rewrite the sources of emitted
+   * source maps with, e.g. `webpack://[namespace]/[resource-path]`.
+   * `[namespace]` is replaced with the name of the filesystem of the source,
+   * e.g. `project`, and `[resource-path]` with the path relative to it.
+   */
+  sourceMapSourceTemplate?: string
+
+  /**
+   * (`next --turbopack` only) Whether the pages enforce a strict Content
+   * Security Policy that forbids `eval`. HMR updates are then applied with
+   * `<script>` tags that carry the nonce of the Turbopack runtime.
+   */
+  strictCsp?: boolean
+
+  /**
+   * (`next --turbopack` only) Code to add to every emitted JavaScript chunk,
+   * e.g. a license banner. `runtimeInjection` is added after the Turbopack
+   * runtime, so it runs before any module is instantiated.
+   */
+  chunkCodeWrapping?: {
+    banner?: string
+    footer?: string
+    runtimeInjection?: string
+  }
+
+  /**
+   * (`next --turbopack` only) Consume Module Federation remotes in client code.
+   * `remotes` maps a remote name to `name@url` for script containers or to a
+   * plain url for ES module containers. Packages listed in `shared` are
+   * provided to the share scope.
+   */
+  moduleFederation?: {
+    shareScope?: string
+    remotes?: Record<string, string>
+    shared?: Record<string, { version?: string; requiredVersion?: string }>
+  }
+
   /**
    * This is the repo root usually and only files above this
    * directory can be resolved by turbopack.
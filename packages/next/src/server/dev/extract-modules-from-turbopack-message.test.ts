@@ -0,0 +1,62 @@
+import type { Update as TurbopackUpdate } from '../../build/swc/types'
+import {
+  extractModulesFromTurbopackMessage,
+  isTurbopackIssuesOnlyMessage,
+} from './extract-modules-from-turbopack-message'
+
+const issues: TurbopackUpdate = {
+  type: 'issues',
+  issues: [],
+  diagnostics: [],
+}
+
+const partial: TurbopackUpdate = {
+  type: 'partial',
+  instruction: { type: 'ChunkListUpdate', merged: undefined },
+  issues: [],
+  diagnostics: [],
+}
+
+describe('isTurbopackIssuesOnlyMessage', () => {
+  it('is true for the initial message of a subscription', () => {
+    expect(isTurbopackIssuesOnlyMessage(issues)).toBe(true)
+    expect(isTurbopackIssuesOnlyMessage([issues, issues])).toBe(true)
+  })
+
+  it('is false when the message contains updates', () => {
+    expect(isTurbopackIssuesOnlyMessage(partial)).toBe(false)
+    expect(isTurbopackIssuesOnlyMessage([issues, partial])).toBe(false)
+  })
+})
+
+describe('extractModulesFromTurbopackMessage', () => {
+  const instruction = (name: string) => ({
+    type: 'ChunkListUpdate' as const,
+    merged: [
+      {
+        type: 'EcmascriptMergedUpdate' as const,
+        chunks: {},
+        entries: {
+          [`${name} [app-client] (ecmascript)`]: {
+            code: '',
+            map: '',
+            url: '',
+          },
+        },
+      },
+    ],
+  })
+
+  it('includes the modules of replayed updates', () => {
+    const replay: TurbopackUpdate = {
+      type: 'replay',
+      instructions: [instruction('a.js'), instruction('b.js')],
+      issues: [],
+      diagnostics: [],
+    }
+    expect(extractModulesFromTurbopackMessage(replay)).toEqual([
+      'a.js',
+      'b.js',
+    ])
+  })
+})
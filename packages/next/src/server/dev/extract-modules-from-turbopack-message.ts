@@ -6,17 +6,23 @@ export function extractModulesFromTurbopackMessage(
   const updatedModules: Set<string> = new Set()
 
   const updates = Array.isArray(data) ? data : [data]
-  for (const update of updates) {
+  const instructions = updates.flatMap((update) =>
+    update.type === 'partial'
+      ? [update.instruction]
+      : update.type === 'replay'
+        ? update.instructions
+        : []
+  )
+  for (const instruction of instructions) {
     // TODO this won't capture changes to CSS since they don't result in a "merged" update
     if (
-      update.type !== 'partial' ||
-      update.instruction.type !== 'ChunkListUpdate' ||
-      update.instruction.merged === undefined
+      instruction.type !== 'ChunkListUpdate' ||
+      instruction.merged === undefined
     ) {
       continue
     }
 
-    for (const mergedUpdate of update.instruction.merged) {
+    for (const mergedUpdate of instruction.merged) {
       for (const name of Object.keys(mergedUpdate.entries)) {
         const res = /(.*)\s+\[.*/.exec(name)
         if (res === null) {
@@ -33,3 +39,15 @@ export function extractModulesFromTurbopackMessage(
 
   return [...updatedModules]
 }
+
+/**
+ * Whether the message doesn't contain any updates. This is the case for the
+ * initial message of a subscription, which only tells the client which version
+ * it is at.
+ */
+export function isTurbopackIssuesOnlyMessage(
+  data: TurbopackUpdate | TurbopackUpdate[]
+) {
+  const updates = Array.isArray(data) ? data : [data]
+  return updates.every((update) => update.type === 'issues')
+}
@@ -183,6 +183,14 @@ export async function createHotReloaderTurbopack(
     {
       persistentCaching: isPersistentCachingEnabled(opts.nextConfig),
       memoryLimit: opts.nextConfig.experimental.turbo?.memoryLimit,
+      rootTaskConcurrency:
+        opts.nextConfig.experimental.turbo?.rootTaskConcurrency,
+      taskConcurrency: opts.nextConfig.experimental.turbo?.taskConcurrency,
+      importCache: opts.nextConfig.experimental.turbo?.cacheArchive
+        ? join(dir, opts.nextConfig.experimental.turbo.cacheArchive)
+        : undefined,
+      backgroundRevalidation:
+        opts.nextConfig.experimental.turbo?.backgroundRevalidation,
     }
   )
   opts.onCleanup(() => project.onExit())
@@ -330,6 +338,7 @@ export async function createHotReloaderTurbopack(
 
   const clients = new Set<ws>()
   const clientStates = new WeakMap<ws, ClientState>()
+  let nextClientId = 0
 
   function sendToClient(client: ws, payload: HMR_ACTION_TYPES) {
     client.send(JSON.stringify(payload))
@@ -451,7 +460,11 @@ export async function createHotReloaderTurbopack(
     currentEntryIssues.delete(key)
   }
 
-  async function subscribeToHmrEvents(client: ws, id: string) {
+  async function subscribeToHmrEvents(
+    client: ws,
+    id: string,
+    version: string | undefined
+  ) {
     const key = getEntryKey('assets', 'client', id)
     if (!hasEntrypointForKey(currentEntrypoints, key, assetMapper)) {
       // maybe throw an error / force the client to reload?
@@ -463,13 +476,29 @@ export async function createHotReloaderTurbopack(
       return
     }
 
-    const subscription = project!.hmrEvents(id)
+    const subscription = project!.hmrEvents(
+      id,
+      version,
+      `hmr-client-${state.id}`
+    )
     state.subscriptions.set(id, subscription)
 
-    // The subscription will always emit once, which is the initial
-    // computation. This is not a change, so swallow it.
     try {
-      await subscription.next()
+      // The subscription will always emit once, which is the initial
+      // computation. It tells the client which version it is at, and contains
+      // the updates it missed if it is resubscribing after a reconnect. Only
+      // the subscribing client needs it, and it's not delayed by compilation
+      // errors like the other updates.
+      const initial = await subscription.next()
+      if (!initial.done) {
+        const payload = initial.value
+        payload.diagnostics = []
+        payload.issues = []
+        sendToClient(client, {
+          action: HMR_ACTIONS_SENT_TO_BROWSER.TURBOPACK_MESSAGE,
+          data: payload,
+        })
+      }
 
       for await (const data of subscription) {
         processIssues(state.clientIssues, key, data, false, true)
@@ -649,6 +678,7 @@ export async function createHotReloaderTurbopack(
 
         clients.add(client)
         clientStates.set(client, {
+          id: nextClientId++,
           clientIssues,
           hmrPayloads: new Map(),
           turbopackUpdates: [],
@@ -732,7 +762,7 @@ export async function createHotReloaderTurbopack(
           // Turbopack messages
           switch (parsedData.type) {
             case 'turbopack-subscribe':
-              subscribeToHmrEvents(client, parsedData.path)
+              subscribeToHmrEvents(client, parsedData.path, parsedData.version)
               break
 
             case 'turbopack-unsubscribe':
@@ -748,7 +778,10 @@ export async function createHotReloaderTurbopack(
 
         const turbopackConnected: TurbopackConnectedAction = {
           action: HMR_ACTIONS_SENT_TO_BROWSER.TURBOPACK_CONNECTED,
-          data: { sessionId },
+          data: {
+            sessionId,
+            resumable: isPersistentCachingEnabled(opts.nextConfig),
+          },
         }
         sendToClient(client, turbopackConnected)
 
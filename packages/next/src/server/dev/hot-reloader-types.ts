@@ -107,7 +107,14 @@ interface DevPagesManifestUpdateAction {
 
 export interface TurbopackConnectedAction {
   action: HMR_ACTIONS_SENT_TO_BROWSER.TURBOPACK_CONNECTED
-  data: { sessionId: number }
+  data: {
+    sessionId: number
+    /**
+     * Whether clients of a previous session can resume, by replaying the
+     * updates they missed, instead of reloading.
+     */
+    resumable: boolean
+  }
 }
 
 export interface AppIsrManifestAction {
@@ -894,6 +894,12 @@ export default class DevServer extends Server {
     }
   }
 
+  protected async matchImageRemotePattern(
+    url: URL
+  ): Promise<boolean | undefined> {
+    return this.bundlerService.matchImageRemotePattern(url)
+  }
+
   protected async getFallbackErrorComponents(
     url?: string
   ): Promise<LoadComponentsReturnType | null> {
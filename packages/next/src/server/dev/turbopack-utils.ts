@@ -346,6 +346,10 @@ export type StartBuilding = (
 export type ReadyIds = Set<string>
 
 export type ClientState = {
+  /**
+   * Identifies the client for the fair admission of its HMR recomputations.
+   */
+  id: number
   clientIssues: EntryIssuesMap
   hmrPayloads: Map<string, HMR_ACTION_TYPES>
   turbopackUpdates: TurbopackUpdate[]
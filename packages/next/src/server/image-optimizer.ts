@@ -203,7 +203,8 @@ export class ImageOptimizerCache {
     req: IncomingMessage,
     query: UrlWithParsedQuery['query'],
     nextConfig: NextConfigComplete,
-    isDev: boolean
+    isDev: boolean,
+    remotePatternMatch?: boolean
   ): ImageParamsResult | { errorMessage: string } {
     const imageData = nextConfig.images
     const {
@@ -272,7 +273,11 @@ export class ImageOptimizerCache {
         return { errorMessage: '"url" parameter is invalid' }
       }
 
-      if (!hasRemoteMatch(domains, remotePatterns, hrefParsed)) {
+      const isAllowed =
+        remotePatternMatch === undefined
+          ? hasRemoteMatch(domains, remotePatterns, hrefParsed)
+          : remotePatternMatch || hasRemoteMatch(domains, [], hrefParsed)
+      if (!isAllowed) {
         return { errorMessage: '"url" parameter is not allowed' }
       }
     }
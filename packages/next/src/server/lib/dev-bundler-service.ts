@@ -50,6 +50,18 @@ export class DevBundlerService {
     })
   }
 
+  /**
+   * Matches a remote image URL against the `images.remotePatterns` that
+   * Turbopack validated, or returns `undefined` without Turbopack.
+   */
+  public async matchImageRemotePattern(
+    url: URL
+  ): Promise<boolean | undefined> {
+    return this.bundler.hotReloader.turbopackProject?.matchImageRemotePattern(
+      url.href
+    )
+  }
+
   public async getCompilationError(page: string) {
     const errors = await this.bundler.hotReloader.getCompilationErrors(page)
     if (!errors) return
@@ -0,0 +1,49 @@
+import { getReactLoadableManifestItem } from './get-react-loadable-manifest-item'
+
+describe('getReactLoadableManifestItem', () => {
+  const manifest = {
+    'pages/index.js -> ../components/hello': {
+      id: 'pages/index.js -> ../components/hello',
+      files: ['static/chunks/hello.js'],
+    },
+    '[project]/components/hello.js [client] (ecmascript)': {
+      id: '[project]/components/hello.js [client] (ecmascript)',
+      files: ['static/chunks/hello.js'],
+    },
+  }
+
+  it('finds entries by their key', () => {
+    expect(
+      getReactLoadableManifestItem(
+        manifest,
+        'pages/index.js -> ../components/hello'
+      )
+    ).toBe(manifest['pages/index.js -> ../components/hello'])
+    expect(
+      getReactLoadableManifestItem(
+        manifest,
+        '[project]/components/hello.js [client] (ecmascript)'
+      )
+    ).toBe(manifest['[project]/components/hello.js [client] (ecmascript)'])
+  })
+
+  it('finds entries by the module id of a serialized key', () => {
+    expect(
+      getReactLoadableManifestItem(
+        manifest,
+        JSON.stringify({
+          id: '[project]/components/hello.js [client] (ecmascript)',
+          chunks: ['static/chunks/hello.js'],
+        })
+      )
+    ).toBe(manifest['[project]/components/hello.js [client] (ecmascript)'])
+  })
+
+  it('ignores unknown keys', () => {
+    expect(getReactLoadableManifestItem(manifest, 'unknown')).toBeUndefined()
+    expect(getReactLoadableManifestItem(manifest, '{invalid')).toBeUndefined()
+    expect(
+      getReactLoadableManifestItem(manifest, JSON.stringify({ id: 'unknown' }))
+    ).toBeUndefined()
+  })
+})
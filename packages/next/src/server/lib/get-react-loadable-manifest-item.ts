@@ -0,0 +1,27 @@
+import type { DeepReadonly } from '../../shared/lib/deep-readonly'
+import type { ReactLoadableManifest } from '../load-components'
+
+/**
+ * Finds the react-loadable manifest entry of a module reported by
+ * `next/dynamic`.
+ *
+ * Under Turbopack, the development server reports `JSON.stringify({ id,
+ * chunks })` instead of a manifest key. The manifest lists every dynamically
+ * imported module by its module id, so the entry of that id is used instead.
+ */
+export function getReactLoadableManifestItem(
+  manifest: DeepReadonly<ReactLoadableManifest>,
+  key: string
+) {
+  const item = manifest[key]
+  if (item || !key.startsWith('{')) {
+    return item
+  }
+
+  try {
+    const { id } = JSON.parse(key)
+    return id === undefined ? undefined : manifest[id]
+  } catch {
+    return undefined
+  }
+}
@@ -583,6 +583,7 @@ async function startWatcher(opts: SetupOpts) {
 
       if (envChange || tsconfigChange) {
         if (envChange) {
+          const previousEnv = { ...process.env }
           const { parsedEnv } = loadEnvConfig(
             dir,
             true,
@@ -593,6 +594,19 @@ async function startWatcher(opts: SetupOpts) {
             }
           )
 
+          if (hotReloader.turbopackProject) {
+            const changedEnv: Record<string, string | undefined> = {}
+            for (const key of new Set([
+              ...Object.keys(previousEnv),
+              ...Object.keys(process.env),
+            ])) {
+              if (previousEnv[key] !== process.env[key]) {
+                changedEnv[key] = process.env[key]
+              }
+            }
+            hotReloader.turbopackProject.updateProcessEnv(changedEnv)
+          }
+
           if (usingTypeScript && nextConfig.experimental?.typedEnv) {
             // do not await, this is not essential for further process
             createEnvDefinitions({
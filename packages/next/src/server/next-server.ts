@@ -865,11 +865,24 @@ export default class NextNodeServer extends BaseServer<
         return true
       }
 
+      // Remote URLs are matched by the bundler when it validated the
+      // `images.remotePatterns` config, e.g. with Turbopack in development.
+      let remotePatternMatch: boolean | undefined
+      const { url } = parsedUrl.query
+      if (
+        typeof url === 'string' &&
+        /^https?:\/\//.test(url) &&
+        URL.canParse(url)
+      ) {
+        remotePatternMatch = await this.matchImageRemotePattern(new URL(url))
+      }
+
       const paramsResult = ImageOptimizerCache.validateParams(
         req.originalRequest,
         parsedUrl.query,
         this.nextConfig,
-        !!this.renderOpts.dev
+        !!this.renderOpts.dev,
+        remotePatternMatch
       )
 
       if ('errorMessage' in paramsResult) {
@@ -1067,6 +1080,13 @@ export default class NextNodeServer extends BaseServer<
     )
   }
 
+  // Used in development only, overloaded in next-dev-server
+  protected async matchImageRemotePattern(
+    _url: URL
+  ): Promise<boolean | undefined> {
+    return undefined
+  }
+
   // Used in development only, overloaded in next-dev-server
   protected async ensurePage(_opts: {
     page: string
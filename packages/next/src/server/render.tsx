@@ -21,6 +21,7 @@ import {
   setLazyProp,
 } from './api-utils'
 import { getCookieParser } from './api-utils/get-cookie-parser'
+import { getReactLoadableManifestItem } from './lib/get-react-loadable-manifest-item'
 import type { LoadComponentsReturnType } from './load-components'
 import type {
   GetServerSideProps,
@@ -1406,7 +1407,10 @@ export async function renderToHTMLImpl(
   const dynamicImports = new Set<string>()
 
   for (const mod of reactLoadableModules) {
-    const manifestItem = reactLoadableManifest[mod]
+    const manifestItem = getReactLoadableManifestItem(
+      reactLoadableManifest,
+      mod
+    )
 
     if (manifestItem) {
       dynamicImportsIds.add(manifestItem.id)
@@ -22,6 +22,11 @@ import UtilImplementation from 'node:util'
 import AsyncHooksImplementation from 'node:async_hooks'
 import { intervalsManager, timeoutsManager } from './resource-managers'
 import { createLocalRequestContext } from '../../after/builtin-request-context'
+import { isBuiltin } from 'module'
+import {
+  getCallingModule,
+  getUnsupportedApiMessage,
+} from './unsupported-api'
 
 interface ModuleContext {
   runtime: EdgeRuntime
@@ -120,15 +125,21 @@ function buildEnvironmentVariablesFrom(
   return env
 }
 
-function throwUnsupportedAPIError(name: string) {
-  const error =
-    new Error(`A Node.js API is used (${name}) which is not supported in the Edge Runtime.
-Learn more: https://nextjs.org/docs/api-reference/edge-runtime`)
+/**
+ * Returns the module that called `callee`, see `getCallingModule`.
+ */
+type GetCaller = (callee: Function) => string | undefined
+
+function throwUnsupportedAPIError(name: string, caller: string | undefined) {
+  const error = new Error(getUnsupportedApiMessage(name, caller))
   decorateServerError(error, COMPILER_NAMES.edgeServer)
   throw error
 }
 
-function createProcessPolyfill(env: Record<string, string>) {
+function createProcessPolyfill(
+  env: Record<string, string>,
+  getCaller: GetCaller
+) {
   const processPolyfill = { env: buildEnvironmentVariablesFrom(env) }
   const overriddenValue: Record<string, any> = {}
 
@@ -140,7 +151,9 @@ function createProcessPolyfill(env: Record<string, string>) {
           return overriddenValue[key]
         }
         if (typeof (process as any)[key] === 'function') {
-          return () => throwUnsupportedAPIError(`process.${key}`)
+          return function unsupported() {
+            throwUnsupportedAPIError(`process.${key}`, getCaller(unsupported))
+          }
         }
         return undefined
       },
@@ -153,11 +166,15 @@ function createProcessPolyfill(env: Record<string, string>) {
   return processPolyfill
 }
 
-function addStub(context: EdgeRuntime['context'], name: string) {
+function addStub(
+  context: EdgeRuntime['context'],
+  name: string,
+  getCaller: GetCaller
+) {
   Object.defineProperty(context, name, {
     get() {
-      return function () {
-        throwUnsupportedAPIError(name)
+      return function unsupported() {
+        throwUnsupportedAPIError(name, getCaller(unsupported))
       }
     },
     enumerable: false,
@@ -255,19 +272,26 @@ async function createModuleContext(options: ModuleContextOptions) {
   const warnedWasmCodegens = new Set<string>()
   const { edgeFunctionEntry } = options
   const wasm = await loadWasm(edgeFunctionEntry.wasm ?? [])
+  // The evaluated files by path, which errors for unsupported APIs name the
+  // calling module from.
+  const paths = new Map<string, string>()
+  const getCaller: GetCaller = (callee) => getCallingModule(paths, callee)
   const runtime = new EdgeRuntime({
     codeGeneration:
       process.env.NODE_ENV !== 'production'
         ? { strings: true, wasm: true }
         : undefined,
     extend: (context) => {
-      context.process = createProcessPolyfill(edgeFunctionEntry.env)
+      context.process = createProcessPolyfill(edgeFunctionEntry.env, getCaller)
 
       Object.defineProperty(context, 'require', {
         enumerable: false,
-        value: (id: string) => {
+        value: function require(id: string) {
           const value = NativeModuleMap.get(id)
           if (!value) {
+            if (isBuiltin(id)) {
+              throwUnsupportedAPIError(`require('${id}')`, getCaller(require))
+            }
             throw TypeError('Native module not found: ' + id)
           }
           return value
@@ -430,7 +454,7 @@ Learn More: https://nextjs.org/docs/messages/edge-dynamic-code-evaluation`),
       }
 
       for (const name of EDGE_UNSUPPORTED_NODE_APIS) {
-        addStub(context, name)
+        addStub(context, name, getCaller)
       }
 
       Object.assign(context, wasm)
@@ -479,7 +503,7 @@ Learn More: https://nextjs.org/docs/messages/edge-dynamic-code-evaluation`),
 
   return {
     runtime,
-    paths: new Map<string, string>(),
+    paths,
     warnedEvals: new Set<string>(),
   }
 }
@@ -534,15 +558,15 @@ export async function getModuleContext(options: ModuleContextOptions): Promise<{
   const evaluateInContext = (filepath: string) => {
     if (!moduleContext.paths.has(filepath)) {
       const content = readFileSync(filepath, 'utf-8')
+      // Added before the evaluation, so uses of unsupported APIs on the top
+      // level name the calling module.
+      moduleContext.paths.set(filepath, content)
       try {
         runInContext(content, moduleContext.runtime.context, {
           filename: filepath,
         })
-        moduleContext.paths.set(filepath, content)
       } catch (error) {
-        if (options.useCache) {
-          moduleContext?.paths.delete(filepath)
-        }
+        moduleContext.paths.delete(filepath)
         throw error
       }
     }
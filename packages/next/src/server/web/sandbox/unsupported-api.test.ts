@@ -0,0 +1,84 @@
+import { createContext, runInContext } from 'vm'
+import {
+  getCallingModule,
+  getChunkModuleAtLine,
+  getUnsupportedApiMessage,
+} from './unsupported-api'
+
+const chunk = [
+  'const modules = {',
+  '"[project]/lib/util.ts [middleware-edge] (ecmascript)": ((__turbopack_context__) => {',
+  '  util()',
+  '}),',
+  '"[project]/middleware.ts [middleware-edge] (ecmascript) <locals>": ((__turbopack_context__) => {',
+  '  middleware()',
+  '}),',
+  '};',
+  'for (const id in modules) modules[id]()',
+].join('\n')
+
+describe('getChunkModuleAtLine', () => {
+  it('returns the module that contains the line', () => {
+    expect(getChunkModuleAtLine(chunk, 3)).toBe('./lib/util.ts')
+    expect(getChunkModuleAtLine(chunk, 6)).toBe('./middleware.ts')
+  })
+
+  it('returns undefined outside of modules', () => {
+    expect(getChunkModuleAtLine(chunk, 1)).toBeUndefined()
+    expect(getChunkModuleAtLine('setImmediate()', 1)).toBeUndefined()
+  })
+})
+
+describe('getCallingModule', () => {
+  /**
+   * Evaluates `content` as `file` and returns the modules that called the
+   * unsupported APIs `util` and `middleware`.
+   */
+  function evaluate(files: Map<string, string>, file: string, content: string) {
+    const callers: Array<string | undefined> = []
+    const context = createContext({
+      util: function util() {
+        callers.push(getCallingModule(files, util))
+      },
+      middleware: function middleware() {
+        callers.push(getCallingModule(files, middleware))
+      },
+    })
+    runInContext(content, context, { filename: file })
+    return callers
+  }
+
+  it('names the module of a Turbopack chunk', () => {
+    const file = '/app/.next/server/edge/chunks/middleware.js'
+    const files = new Map([[file, chunk]])
+    expect(evaluate(files, file, chunk)).toEqual([
+      './lib/util.ts',
+      './middleware.ts',
+    ])
+  })
+
+  it('names the position in other files', () => {
+    const file = `${process.cwd()}/.next/server/middleware.js`
+    const content = '\n  middleware()'
+    const files = new Map([[file, content]])
+    expect(evaluate(files, file, content)).toEqual([
+      '.next/server/middleware.js:2:3',
+    ])
+  })
+
+  it('skips frames outside of the evaluated files', () => {
+    const files = new Map([['/app/.next/server/other.js', '']])
+    expect(evaluate(files, '/app/test.js', 'middleware()')).toEqual([
+      undefined,
+    ])
+  })
+})
+
+describe('getUnsupportedApiMessage', () => {
+  it('names the API and the module', () => {
+    expect(getUnsupportedApiMessage('process.cwd', './lib/util.ts')).toBe(
+      `A Node.js API is used (process.cwd) in ./lib/util.ts which is not supported in the Edge Runtime.
+Learn more: https://nextjs.org/docs/api-reference/edge-runtime`
+    )
+  })
+})
@@ -0,0 +1,71 @@
+import { relative } from 'path'
+
+/**
+ * Matches the first line of a module in a Turbopack chunk, e.g.
+ * `"[project]/middleware.ts [middleware-edge] (ecmascript)": ((...) => {`.
+ */
+const TURBOPACK_MODULE_LINE = /^"\[project\]\/([^"\s]+) \[[^\]]+\][^"]*": /
+
+/**
+ * Returns the path of the module of a Turbopack chunk that contains the
+ * 1-based `line`, or `undefined` when the chunk isn't a Turbopack chunk.
+ */
+export function getChunkModuleAtLine(
+  content: string,
+  line: number
+): string | undefined {
+  const lines = content.split('\n', line)
+  for (let i = lines.length - 1; i >= 0; i--) {
+    const match = TURBOPACK_MODULE_LINE.exec(lines[i])
+    if (match) {
+      return `./${match[1]}`
+    }
+  }
+  return undefined
+}
+
+/**
+ * Returns the module that called `callee`, from the first stack frame in one
+ * of the evaluated `files`, which map a path to its content. Turbopack chunks
+ * are resolved to the module that contains the frame, other files are
+ * returned with the position of the frame.
+ */
+export function getCallingModule(
+  files: Map<string, string>,
+  callee: Function
+): string | undefined {
+  const prepareStackTrace = Error.prepareStackTrace
+  const holder: { stack?: NodeJS.CallSite[] } = {}
+  try {
+    Error.prepareStackTrace = (_, callSites) => callSites
+    Error.captureStackTrace(holder, callee)
+    for (const callSite of holder.stack ?? []) {
+      const file = callSite.getFileName()
+      const content = file ? files.get(file) : undefined
+      if (!file || content === undefined) {
+        continue
+      }
+      const line = callSite.getLineNumber() ?? 1
+      return (
+        getChunkModuleAtLine(content, line) ??
+        `${relative(process.cwd(), file)}:${line}:${callSite.getColumnNumber()}`
+      )
+    }
+    return undefined
+  } finally {
+    Error.prepareStackTrace = prepareStackTrace
+  }
+}
+
+/**
+ * The message of the error for a use of the Node.js API `name`, which the
+ * module `caller` used.
+ */
+export function getUnsupportedApiMessage(
+  name: string,
+  caller: string | undefined
+): string {
+  const usedIn = caller ? ` in ${caller}` : ''
+  return `A Node.js API is used (${name})${usedIn} which is not supported in the Edge Runtime.
+Learn more: https://nextjs.org/docs/api-reference/edge-runtime`
+}
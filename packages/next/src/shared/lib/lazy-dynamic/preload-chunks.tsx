@@ -3,6 +3,7 @@
 import { preload } from 'react-dom'
 
 import { workAsyncStorage } from '../../../server/app-render/work-async-storage.external'
+import { getReactLoadableManifestItem } from '../../../server/lib/get-react-loadable-manifest-item'
 import { encodeURIPath } from '../encode-uri-path'
 
 export function PreloadChunks({
@@ -20,26 +21,29 @@ export function PreloadChunks({
     return null
   }
 
-  const allFiles = []
+  const allFiles = new Set<string>()
 
   // Search the current dynamic call unique key id in react loadable manifest,
   // and find the corresponding CSS files to preload
   if (workStore.reactLoadableManifest && moduleIds) {
     const manifest = workStore.reactLoadableManifest
     for (const key of moduleIds) {
-      if (!manifest[key]) continue
-      const chunks = manifest[key].files
-      allFiles.push(...chunks)
+      const manifestItem = getReactLoadableManifestItem(manifest, key)
+      if (!manifestItem) continue
+      // Entries of different keys can share chunks
+      for (const chunk of manifestItem.files) {
+        allFiles.add(chunk)
+      }
     }
   }
 
-  if (allFiles.length === 0) {
+  if (allFiles.size === 0) {
     return null
   }
 
   return (
     <>
-      {allFiles.map((chunk) => {
+      {Array.from(allFiles, (chunk) => {
         const href = `${workStore.assetPrefix}/_next/${encodeURIPath(chunk)}`
         const isCss = chunk.endsWith('.css')
         // If it's stylesheet we use `precedence` o help hoist with React Float.
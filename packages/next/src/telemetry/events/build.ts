@@ -172,6 +172,9 @@ export type EventBuildFeatureUsage = {
     | 'skipTrailingSlashRedirect'
     | 'modularizeImports'
     | 'esmExternals'
+    | 'appRouter'
+    | 'serverActions'
+    | 'mdx'
   invocationCount: number
 }
 export function eventBuildFeatureUsage(
@@ -604,6 +604,40 @@ describe('next.rs api', () => {
       })
   }
 
+  it('should sync the content of HMR resources', async () => {
+    const entrypointsSubscribtion = project.entrypointsSubscribe()
+    const entrypoints: TurbopackResult<Entrypoints> = (
+      await entrypointsSubscribtion.next()
+    ).value
+    const route = entrypoints.routes.get('/')
+    entrypointsSubscribtion.return()
+
+    if (route.type !== 'page') throw new Error('unknown route type')
+    await route.htmlEndpoint.writeToDisk()
+
+    const result = await project.hmrIdentifiersSubscribe().next()
+    expect(result.done).toBe(false)
+    const [identifier] = result.value.identifiers
+
+    // Without a version, the client doesn't have any content yet.
+    const subscription = project.hmrSubscribe(identifier)
+    const sync = await subscription.next()
+    subscription.return()
+    expect(sync.done).toBe(false)
+    if (sync.value.type !== 'sync') throw new Error('expected a sync update')
+    expect(sync.value.content).toBeString()
+    expect(sync.value.content).not.toBeEmpty()
+    expect(sync.value.version).toBeString()
+
+    // A client that is up to date doesn't need to sync again.
+    const resubscription = project.hmrSubscribe(identifier, sync.value.version)
+    const resync = await resubscription.next()
+    resubscription.return()
+    expect(resync.done).toBe(false)
+    expect(resync.value).toHaveProperty('type', 'issues')
+    expect(resync.value).not.toHaveProperty('content')
+  })
+
   it.skip('should allow to make many HMR updates', async () => {
     console.log('start')
     await new Promise((r) => setTimeout(r, 1000))
@@ -0,0 +1,49 @@
+import { nextTestSetup } from 'e2e-utils'
+import { retry } from 'next-test-utils'
+
+describe('css-module-hmr', () => {
+  const { next, isTurbopack } = nextTestSetup({
+    files: __dirname,
+  })
+
+  // Turbopack updates the class mapping that importers already hold instead
+  // of re-executing them.
+  ;(isTurbopack ? it : it.skip)(
+    'should update the class mapping of a CSS module in place',
+    async () => {
+      const browser = await next.browser('/')
+      const className = await browser.elementByCss('#text').getAttribute('class')
+      expect(await browser.eval('window.initialStyles.text')).toBe(className)
+      expect(await browser.eval('typeof window.initialStyles.removed')).toBe(
+        'string'
+      )
+
+      await next.patchFile(
+        'pages/index.module.css',
+        '.text {\n  color: rgb(0, 0, 255);\n}\n\n.added {\n  color: rgb(0, 255, 0);\n}\n'
+      )
+
+      await retry(async () => {
+        expect(
+          await browser.eval(
+            `window.getComputedStyle(document.querySelector('#text')).color`
+          )
+        ).toBe('rgb(0, 0, 255)')
+      })
+      // Class names only depend on the path of the module.
+      expect(await browser.elementByCss('#text').getAttribute('class')).toBe(
+        className
+      )
+      expect(
+        await browser.eval('window.initialStyles === window.latestStyles')
+      ).toBe(true)
+      expect(await browser.eval('window.initialStyles.text')).toBe(className)
+      expect(await browser.eval('typeof window.initialStyles.added')).toBe(
+        'string'
+      )
+      expect(await browser.eval("'removed' in window.initialStyles")).toBe(
+        false
+      )
+    }
+  )
+})
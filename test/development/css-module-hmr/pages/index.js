@@ -0,0 +1,14 @@
+import styles from './index.module.css'
+
+if (typeof window !== 'undefined') {
+  window.initialStyles ??= styles
+  window.latestStyles = styles
+}
+
+export default function Page() {
+  return (
+    <p id="text" className={styles.text}>
+      hello world
+    </p>
+  )
+}
@@ -0,0 +1,32 @@
+import { nextTestSetup } from 'e2e-utils'
+import { retry } from 'next-test-utils'
+
+// Actions of "use server" files that are only imported by other "use server"
+// files still reach the client, so they need to be in the manifest.
+describe('actions-server-only-references', () => {
+  const { next } = nextTestSetup({
+    files: __dirname,
+  })
+
+  it('should call an action that another action returns', async () => {
+    const browser = await next.browser('/')
+    await browser.elementByCss('#returned').click()
+
+    await retry(async () => {
+      expect(await browser.elementByCss('#result').text()).toBe(
+        'returned action result'
+      )
+    })
+  })
+
+  it('should call an action that a "use server" file re-exports', async () => {
+    const browser = await next.browser('/')
+    await browser.elementByCss('#reexported').click()
+
+    await retry(async () => {
+      expect(await browser.elementByCss('#result').text()).toBe(
+        'reexported action result'
+      )
+    })
+  })
+})
@@ -0,0 +1,31 @@
+'use client'
+
+import { useState } from 'react'
+import { getAction } from './returning-actions'
+import { reexportedAction } from './reexporting-actions'
+
+export function Client() {
+  const [result, setResult] = useState('')
+  return (
+    <>
+      <button
+        id="returned"
+        onClick={async () => {
+          const action = await getAction()
+          setResult(await action())
+        }}
+      >
+        returned
+      </button>
+      <button
+        id="reexported"
+        onClick={async () => {
+          setResult(await reexportedAction())
+        }}
+      >
+        reexported
+      </button>
+      <p id="result">{result}</p>
+    </>
+  )
+}
@@ -0,0 +1,9 @@
+'use server'
+
+export async function returnedAction() {
+  return 'returned action result'
+}
+
+export async function reexportedAction() {
+  return 'reexported action result'
+}
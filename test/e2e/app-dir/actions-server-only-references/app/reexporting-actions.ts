@@ -0,0 +1,5 @@
+'use server'
+
+import { reexportedAction } from './inner-actions'
+
+export { reexportedAction }
@@ -0,0 +1,7 @@
+'use server'
+
+import { returnedAction } from './inner-actions'
+
+export async function getAction() {
+  return returnedAction
+}
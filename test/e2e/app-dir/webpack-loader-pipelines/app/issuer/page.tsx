@@ -0,0 +1,5 @@
+import file from '../file.pipeline.js'
+
+export default function Page() {
+  return <p>issuer: {JSON.stringify(file)}</p>
+}
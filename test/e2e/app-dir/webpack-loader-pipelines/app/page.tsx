@@ -0,0 +1,11 @@
+import file from './file.pipeline.js'
+import excluded from './file.excluded.pipeline.js'
+
+export default function Page() {
+  return (
+    <div>
+      <p>file: {JSON.stringify(file)}</p>
+      <p>excluded: {JSON.stringify(excluded)}</p>
+    </div>
+  )
+}
@@ -0,0 +1,7 @@
+// Appends the name of the loader to the list of loaders that ran, which the
+// module exports.
+module.exports = function (source) {
+  const { name } = this.getOptions()
+  const ran = JSON.parse(source.replace(/^export default /, '').trim() || '[]')
+  return `export default ${JSON.stringify([...ran, name])}`
+}
@@ -0,0 +1,28 @@
+const loader = (name) => ({
+  loader: require.resolve('./append-loader.js'),
+  options: { name },
+})
+
+/**
+ * @type {import('next').NextConfig}
+ */
+const nextConfig = {
+  experimental: {
+    turbo: {
+      rules: {
+        '*.pipeline.js': [
+          { loaders: [loader('post')], enforce: 'post' },
+          { loaders: [loader('first')] },
+          {
+            loaders: [loader('second')],
+            exclude: '*.excluded.pipeline.js',
+          },
+          { loaders: [loader('issuer')], issuer: 'app/issuer/**' },
+          { loaders: [loader('pre')], enforce: 'pre' },
+        ],
+      },
+    },
+  },
+}
+
+module.exports = nextConfig
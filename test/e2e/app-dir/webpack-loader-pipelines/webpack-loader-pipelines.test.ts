@@ -0,0 +1,41 @@
+import { nextTestSetup } from 'e2e-utils'
+
+describe('webpack-loader-pipelines', () => {
+  const { next, isTurbopack, skipped } = nextTestSetup({
+    files: __dirname,
+    // This test is skipped because it's only expected to run in turbopack, which isn't enabled for builds
+    skipDeployment: true,
+  })
+
+  if (!isTurbopack || skipped) {
+    it('should only run the test in turbopack', () => {})
+    return
+  }
+
+  async function text(pathname: string) {
+    const res = await next.fetch(pathname)
+    return (await res.text())
+      .replaceAll(/<!-- -->/g, '')
+      .replaceAll('&quot;', '"')
+  }
+
+  it('should run the rules in order with pre and post rules first and last', async () => {
+    expect(await text('/')).toContain(
+      `file: ${JSON.stringify(['pre', 'first', 'second', 'post'])}`
+    )
+  })
+
+  it('should not run rules for excluded files', async () => {
+    expect(await text('/')).toContain(
+      `excluded: ${JSON.stringify(['pre', 'first', 'post'])}`
+    )
+  })
+
+  it('should run rules with an issuer only for matching importers', async () => {
+    expect(await text('/issuer')).toContain(
+      `issuer: ${JSON.stringify(['pre', 'first', 'second', 'issuer', 'post'])}`
+    )
+    // The module imported by other modules doesn't run the rule
+    expect(await text('/')).not.toContain('"issuer"')
+  })
+})
@@ -0,0 +1,9 @@
+module.exports = {
+  outputFileTracingIncludes: {
+    '/api/*': ['./data/extra/**/*', './data/private/*'],
+    '/index': ['./data/extra/*'],
+  },
+  outputFileTracingExcludes: {
+    '/api/data': ['./data/private/*'],
+  },
+}
@@ -0,0 +1,43 @@
+/* eslint-env jest */
+
+import fs from 'fs-extra'
+import { join } from 'path'
+import { nextBuild } from 'next-test-utils'
+
+const appDir = join(__dirname, '../app')
+
+const readTrace = (page) =>
+  fs.readJSON(join(appDir, '.next/server', `${page}.js.nft.json`))
+
+describe('output file tracing config', () => {
+  ;(process.env.TURBOPACK_BUILD ? describe : describe.skip)(
+    'production mode',
+    () => {
+      beforeAll(async () => {
+        const result = await nextBuild(appDir, undefined, {
+          cwd: appDir,
+          stderr: true,
+          stdout: true,
+        })
+        expect(result.code).toBe(0)
+      })
+
+      it('should apply the includes and excludes of matching routes', async () => {
+        const { version, files } = await readTrace('app/api/data/route')
+        expect(version).toBe(1)
+        expect(files).toContain('../../../../../data/extra/config.json')
+        expect(files.some((file) => file.includes('data/private'))).toBe(false)
+      })
+
+      it('should match pages by their normalized path', async () => {
+        const { files } = await readTrace('pages/index')
+        expect(files).toContain('../../../data/extra/config.json')
+      })
+
+      it('should not apply the config to other routes', async () => {
+        const { files } = await readTrace('pages/other')
+        expect(files.some((file) => file.includes('data/'))).toBe(false)
+      })
+    }
+  )
+})
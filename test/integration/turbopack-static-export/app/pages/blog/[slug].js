@@ -0,0 +1,11 @@
+export default function Page({ slug }) {
+  return <p>post {slug}</p>
+}
+
+export function getStaticPaths() {
+  return { paths: [{ params: { slug: 'first' } }], fallback: false }
+}
+
+export function getStaticProps({ params }) {
+  return { props: { slug: params.slug } }
+}
@@ -0,0 +1,67 @@
+/* eslint-env jest */
+
+import fs from 'fs-extra'
+import { join } from 'path'
+import { nextBuild } from 'next-test-utils'
+
+const appDir = join(__dirname, '../app')
+const outDir = join(appDir, 'out')
+const apiRoute = join(appDir, 'pages/api/hello.js')
+
+describe('static export with turbopack', () => {
+  ;(process.env.TURBOPACK_BUILD ? describe : describe.skip)(
+    'production mode',
+    () => {
+      afterEach(() => fs.remove(apiRoute))
+
+      it('should export the prerendered pages and the public files', async () => {
+        const { code } = await nextBuild(appDir, undefined, { stderr: true })
+        expect(code).toBe(0)
+
+        expect(await fs.readFile(join(outDir, 'index.html'), 'utf8')).toContain(
+          'index'
+        )
+        expect(await fs.readFile(join(outDir, 'about.html'), 'utf8')).toContain(
+          'about'
+        )
+        expect(
+          await fs.readFile(join(outDir, 'blog/first.html'), 'utf8')
+        ).toContain('first')
+        expect(await fs.pathExists(join(outDir, '404.html'))).toBe(true)
+        expect(await fs.readFile(join(outDir, 'hello.txt'), 'utf8')).toBe(
+          'hello\n'
+        )
+      })
+
+      it('should delete files of previous exports', async () => {
+        await fs.outputFile(join(outDir, 'removed.html'), 'stale')
+        await fs.outputFile(join(outDir, 'removed/nested.txt'), 'stale')
+
+        const { code } = await nextBuild(appDir, undefined, { stderr: true })
+        expect(code).toBe(0)
+
+        expect(await fs.pathExists(join(outDir, 'removed.html'))).toBe(false)
+        expect(await fs.pathExists(join(outDir, 'removed/nested.txt'))).toBe(
+          false
+        )
+        expect(await fs.pathExists(join(outDir, 'index.html'))).toBe(true)
+      })
+
+      it('should report routes that cannot be exported at their file', async () => {
+        await fs.outputFile(
+          apiRoute,
+          'export default function handler(req, res) { res.end() }'
+        )
+
+        const { code, stderr } = await nextBuild(appDir, undefined, {
+          stderr: true,
+        })
+        expect(code).toBe(1)
+        expect(stderr).toContain(
+          'API route /api/hello cannot be used with "output: export"'
+        )
+        expect(stderr).toContain('pages/api/hello.js')
+      })
+    }
+  )
+})
@@ -231,7 +231,7 @@ async fn add_glob_results(
     Ok(())
 }
 
-#[turbo_tasks::function]
+#[turbo_tasks::function(transient)]
 async fn input_to_modules(
     fs: Vc<Box<dyn FileSystem>>,
     input: Vec<RcStr>,
@@ -271,7 +271,7 @@ async fn input_to_modules(
             add_glob_results(asset_context, root.read_glob(glob, false), &mut list).await?;
         };
     }
-    Ok(Vc::cell(list))
+    Ok(Vc::cell(list.into()))
 }
 
 fn process_context(dir: &Path, context_directory: Option<&String>) -> Result<String> {
@@ -456,7 +456,7 @@ async fn run<B: Backend + 'static, F: Future<Output = ()>>(
     Ok(output)
 }
 
-#[turbo_tasks::function]
+#[turbo_tasks::function(transient)]
 async fn main_operation(
     current_dir: TransientValue<PathBuf>,
     args: TransientInstance<Arc<Args>>,
@@ -567,7 +567,7 @@ async fn main_operation(
     Ok(Vc::cell(Vec::new()))
 }
 
-#[turbo_tasks::function]
+#[turbo_tasks::function(transient)]
 async fn create_module_asset(
     root: ResolvedVc<FileSystemPath>,
     process_cwd: Option<RcStr>,
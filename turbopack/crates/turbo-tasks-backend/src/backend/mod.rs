@@ -8,6 +8,7 @@ use std::{
     future::Future,
     hash::BuildHasherDefault,
     mem::take,
+    path::Path,
     pin::Pin,
     sync::{
         atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
@@ -16,17 +17,17 @@ use std::{
     thread::available_parallelism,
 };
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use auto_hash_map::{AutoMap, AutoSet};
 use dashmap::DashMap;
 use parking_lot::{Condvar, Mutex};
-use rustc_hash::FxHasher;
+use rustc_hash::{FxHashSet, FxHasher};
 use smallvec::smallvec;
 use tokio::time::{Duration, Instant};
 use turbo_tasks::{
     backend::{
-        Backend, BackendJobId, CachedTaskType, CellContent, TaskExecutionSpec, TransientTaskRoot,
-        TransientTaskType, TypedCellContent,
+        Backend, BackendJobId, CachedTaskType, CellContent, PendingTaskState, PendingTaskTree,
+        TaskExecutionSpec, TransientTaskRoot, TransientTaskType, TypedCellContent,
     },
     event::{Event, EventListener},
     registry,
@@ -132,8 +133,11 @@ struct TurboTasksBackendInner<B: BackingStorage> {
 
     stopping: AtomicBool,
     stopping_event: Event,
-    idle_start_event: Event,
-    idle_end_event: Event,
+
+    /// When set, strongly consistent reads serve the outputs of tasks that are only dirty because
+    /// they are session dependent, e.g. persisted reads of files, while these tasks are recomputed
+    /// in the background. See [`TurboTasksBackend::set_background_revalidation`].
+    background_revalidation: AtomicBool,
 
     backing_storage: B,
 }
@@ -142,6 +146,19 @@ impl<B: BackingStorage> TurboTasksBackend<B> {
     pub fn new(backing_storage: B) -> Self {
         Self(Arc::new(TurboTasksBackendInner::new(backing_storage)))
     }
+
+    /// Serves persisted outputs immediately instead of waiting for the session dependent tasks
+    /// they depend on, e.g. reads of files, to be recomputed after a restart. These tasks are
+    /// recomputed in the background and only invalidate their dependents when their outputs
+    /// changed, so reads settle on the same results as without revalidation.
+    ///
+    /// This makes startups with a persisted cache as fast as warm ones when nothing changed, at
+    /// the cost of briefly serving outdated results when something did.
+    pub fn set_background_revalidation(&self, enabled: bool) {
+        self.0
+            .background_revalidation
+            .store(enabled, Ordering::Relaxed);
+    }
 }
 
 impl<B: BackingStorage> TurboTasksBackendInner<B> {
@@ -172,8 +189,7 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
             last_snapshot: AtomicU64::new(0),
             stopping: AtomicBool::new(false),
             stopping_event: Event::new(|| "TurboTasksBackend::stopping_event".to_string()),
-            idle_start_event: Event::new(|| "TurboTasksBackend::idle_start_event".to_string()),
-            idle_end_event: Event::new(|| "TurboTasksBackend::idle_end_event".to_string()),
+            background_revalidation: AtomicBool::new(false),
             backing_storage,
         }
     }
@@ -365,15 +381,24 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
                 task = ctx.task(task_id, TaskDataCategory::All);
             }
 
+            let dirty_state = get!(task, Dirty).copied();
             let is_dirty =
-                get!(task, Dirty).map_or(false, |dirty_state| dirty_state.get(self.session_id));
+                dirty_state.map_or(false, |dirty_state| dirty_state.get(self.session_id));
 
             // Check the dirty count of the root node
-            let dirty_tasks = get!(task, AggregatedDirtyContainerCount)
+            let dirty_count = get!(task, AggregatedDirtyContainerCount)
                 .cloned()
-                .unwrap_or_default()
-                .get(self.session_id);
+                .unwrap_or_default();
+            let dirty_tasks = dirty_count.get(self.session_id);
             if dirty_tasks > 0 || is_dirty {
+                // When only session dependent tasks that were clean in a previous session are
+                // dirty, the output can be served while they are recomputed
+                let serve_stale = self.background_revalidation.load(Ordering::Relaxed)
+                    && get!(task, Output).is_some()
+                    && dirty_state
+                        .map_or(true, |dirty_state| dirty_state.clean_in_session.is_some())
+                    && dirty_count.get_without_stale(self.session_id) <= 0;
+
                 let root = get!(task, AggregateRoot);
                 let mut task_ids_to_schedule: Vec<_> = Vec::new();
                 // When there are dirty task, subscribe to the all_clean_event
@@ -400,11 +425,13 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
                     }
                     get!(task, AggregateRoot).unwrap()
                 };
-                let listener = root.all_clean_event.listen_with_note(move || {
-                    format!(
-                        "try_read_task_output (strongly consistent) from {:?}",
-                        reader
-                    )
+                let listener = (!serve_stale).then(|| {
+                    root.all_clean_event.listen_with_note(move || {
+                        format!(
+                            "try_read_task_output (strongly consistent) from {:?}",
+                            reader
+                        )
+                    })
                 });
                 drop(task);
                 if !task_ids_to_schedule.is_empty() {
@@ -413,7 +440,11 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
                     queue.execute(&mut ctx);
                 }
 
-                return Ok(Err(listener));
+                if let Some(listener) = listener {
+                    return Ok(Err(listener));
+                }
+                // The stale tasks invalidate the reader when their outputs changed.
+                task = ctx.task(task_id, TaskDataCategory::All);
             }
         }
 
@@ -697,12 +728,10 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
         self.stopping_event.notify(usize::MAX);
     }
 
-    fn idle_start(&self) {
-        self.idle_start_event.notify(usize::MAX);
-    }
-
-    fn idle_end(&self) {
-        self.idle_end_event.notify(usize::MAX);
+    fn export_cache(&self, path: &Path) -> Result<()> {
+        // Persist the changes since the last snapshot first
+        self.snapshot().context("Persisting the cache failed")?;
+        self.backing_storage.export_cache(path)
     }
 
     fn get_or_create_persistent_task(
@@ -846,6 +875,68 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
         )
     }
 
+    fn get_task_summary(&self, task_id: TaskId) -> std::string::String {
+        self.lookup_task_type(task_id).map_or_else(
+            || format!("{task_id:?} transient"),
+            |task_type| task_type.get_summary(),
+        )
+    }
+
+    fn pending_task_tree(
+        &self,
+        task_id: TaskId,
+        turbo_tasks: &dyn TurboTasksBackendApi<TurboTasksBackend<B>>,
+    ) -> Option<PendingTaskTree> {
+        let mut ctx = self.execute_context(turbo_tasks);
+        PendingTaskTree::build(
+            task_id,
+            |task_id| {
+                let task = ctx.task(task_id, TaskDataCategory::All);
+                let state = match get!(task, InProgress) {
+                    Some(InProgressState::Scheduled { .. }) => PendingTaskState::Scheduled,
+                    Some(InProgressState::InProgress { .. }) => PendingTaskState::InProgress,
+                    None if get!(task, Dirty)
+                        .map_or(false, |dirty_state| dirty_state.get(self.session_id)) =>
+                    {
+                        PendingTaskState::Dirty
+                    }
+                    None => PendingTaskState::Done,
+                };
+                let children: Vec<_> = get_many!(task, Child { task } => *task);
+                (state, children)
+            },
+            |task_id| self.get_task_description(task_id),
+        )
+    }
+
+    fn compact_subgraph(
+        &self,
+        task_id: TaskId,
+        turbo_tasks: &dyn TurboTasksBackendApi<TurboTasksBackend<B>>,
+    ) -> usize {
+        let mut ctx = self.execute_context(turbo_tasks);
+        let mut compacted = 0;
+        let mut visited = FxHashSet::default();
+        visited.insert(task_id);
+        let mut queue = vec![task_id];
+        while let Some(task_id) = queue.pop() {
+            // Restoring the task from the backing storage would only grow the memory usage.
+            let Some(mut task) = ctx.task_if_restored(task_id, TaskDataCategory::All) else {
+                continue;
+            };
+            let done = get!(task, InProgress).is_none()
+                && !get!(task, Dirty).map_or(false, |dirty_state| dirty_state.get(self.session_id));
+            if done {
+                task.shrink_to_fit();
+                compacted += 1;
+            }
+            queue.extend(
+                iter_many!(task, Child { task } => *task).filter(|child| visited.insert(*child)),
+            );
+        }
+        compacted
+    }
+
     fn try_get_function_id(&self, task_id: TaskId) -> Option<FunctionId> {
         self.lookup_task_type(task_id)
             .and_then(|task_type| match &*task_type {
@@ -1091,6 +1182,7 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
         &self,
         task_id: TaskId,
         _duration: Duration,
+        _wait_duration: Duration,
         _memory_usage: usize,
         cell_counters: &AutoMap<ValueTypeId, u32, BuildHasherDefault<FxHasher>, 8>,
         stateful: bool,
@@ -1347,37 +1439,12 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
 
                     let until = last_snapshot + time;
                     if until > Instant::now() {
-                        let mut stop_listener = self.stopping_event.listen();
+                        let stop_listener = self.stopping_event.listen();
                         if !self.stopping.load(Ordering::Acquire) {
-                            let mut idle_start_listener = self.idle_start_event.listen();
-                            let mut idle_end_listener = self.idle_end_event.listen();
-                            let mut idle_time = if turbo_tasks.is_idle() {
-                                Instant::now() + IDLE_TIMEOUT
-                            } else {
-                                far_future()
-                            };
-                            loop {
-                                tokio::select! {
-                                    _ = &mut stop_listener => {
-                                        break;
-                                    },
-                                    _ = &mut idle_start_listener => {
-                                        idle_time = Instant::now() + IDLE_TIMEOUT;
-                                        idle_start_listener = self.idle_start_event.listen()
-                                    },
-                                    _ = &mut idle_end_listener => {
-                                        idle_time = until + IDLE_TIMEOUT;
-                                        idle_end_listener = self.idle_end_event.listen()
-                                    },
-                                    _ = tokio::time::sleep_until(until) => {
-                                        break;
-                                    },
-                                    _ = tokio::time::sleep_until(idle_time) => {
-                                        if turbo_tasks.is_idle() {
-                                            break;
-                                        }
-                                    },
-                                }
+                            tokio::select! {
+                                _ = stop_listener => {},
+                                _ = tokio::time::sleep_until(until) => {},
+                                _ = turbo_tasks.wait_idle(IDLE_TIMEOUT) => {},
                             }
                         }
                     }
@@ -1623,12 +1690,12 @@ impl<B: BackingStorage> Backend for TurboTasksBackend<B> {
         self.0.stopping();
     }
 
-    fn idle_start(&self, _turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
-        self.0.idle_start();
-    }
-
-    fn idle_end(&self, _turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
-        self.0.idle_end();
+    fn export_cache(
+        &self,
+        path: &Path,
+        _turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) -> Result<()> {
+        self.0.export_cache(path)
     }
 
     fn get_or_create_persistent_task(
@@ -1679,6 +1746,26 @@ impl<B: BackingStorage> Backend for TurboTasksBackend<B> {
         self.0.get_task_description(task)
     }
 
+    fn get_task_summary(&self, task: TaskId) -> std::string::String {
+        self.0.get_task_summary(task)
+    }
+
+    fn pending_task_tree(
+        &self,
+        task: TaskId,
+        turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) -> Option<PendingTaskTree> {
+        self.0.pending_task_tree(task, turbo_tasks)
+    }
+
+    fn compact_subgraph(
+        &self,
+        task: TaskId,
+        turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) -> usize {
+        self.0.compact_subgraph(task, turbo_tasks)
+    }
+
     fn try_get_function_id(&self, task_id: TaskId) -> Option<FunctionId> {
         self.0.try_get_function_id(task_id)
     }
@@ -1707,6 +1794,7 @@ impl<B: BackingStorage> Backend for TurboTasksBackend<B> {
         &self,
         task_id: TaskId,
         _duration: Duration,
+        _wait_duration: Duration,
         _memory_usage: usize,
         cell_counters: &AutoMap<ValueTypeId, u32, BuildHasherDefault<FxHasher>, 8>,
         stateful: bool,
@@ -1715,6 +1803,7 @@ impl<B: BackingStorage> Backend for TurboTasksBackend<B> {
         self.0.task_execution_completed(
             task_id,
             _duration,
+            _wait_duration,
             _memory_usage,
             cell_counters,
             stateful,
@@ -1854,12 +1943,3 @@ impl<B: BackingStorage> Backend for TurboTasksBackend<B> {
         // TODO implement
     }
 }
-
-// from https://github.com/tokio-rs/tokio/blob/29cd6ec1ec6f90a7ee1ad641c03e0e00badbcb0e/tokio/src/time/instant.rs#L57-L63
-fn far_future() -> Instant {
-    // Roughly 30 years from now.
-    // API does not provide a way to obtain max `Instant`
-    // or convert specific date in the future to instant.
-    // 1000 years overflows on macOS, 100 years overflows on FreeBSD.
-    Instant::now() + Duration::from_secs(86400 * 365 * 30)
-}
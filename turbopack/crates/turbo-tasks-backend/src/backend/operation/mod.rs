@@ -65,6 +65,13 @@ impl<'a, 'tx1, B: BackingStorage> TransactionState<'a, 'tx1, B> {
 pub trait ExecuteContext<'e>: Sized {
     fn session_id(&self) -> SessionId;
     fn task(&mut self, task_id: TaskId, category: TaskDataCategory) -> impl TaskGuard + 'e;
+    /// Like [ExecuteContext::task], but returns `None` instead of restoring
+    /// the task from the backing storage.
+    fn task_if_restored(
+        &mut self,
+        task_id: TaskId,
+        category: TaskDataCategory,
+    ) -> Option<impl TaskGuard + 'e>;
     fn is_once_task(&self, task_id: TaskId) -> bool;
     fn task_pair(
         &mut self,
@@ -207,6 +214,22 @@ where
         }
     }
 
+    fn task_if_restored(
+        &mut self,
+        task_id: TaskId,
+        category: TaskDataCategory,
+    ) -> Option<impl TaskGuard + 'e> {
+        let task = self.backend.storage.access_mut(task_id);
+        if !task_id.is_transient() && !task.persistance_state().is_restored(category) {
+            return None;
+        }
+        Some(TaskGuardImpl {
+            task,
+            task_id,
+            backend: self.backend,
+        })
+    }
+
     fn is_once_task(&self, task_id: TaskId) -> bool {
         if !task_id.is_transient() {
             return false;
@@ -361,6 +384,7 @@ pub trait TaskGuard: Debug {
     ) -> impl Iterator<Item = (&CachedDataItemKey, &CachedDataItemValue)>;
     fn iter_all(&self) -> impl Iterator<Item = (&CachedDataItemKey, &CachedDataItemValue)>;
     fn invalidate_serialization(&mut self);
+    fn shrink_to_fit(&mut self);
 }
 
 struct TaskGuardImpl<'a, B: BackingStorage> {
@@ -595,6 +619,10 @@ impl<B: BackingStorage> TaskGuard for TaskGuardImpl<'_, B> {
                 .add_persisting_items(count);
         }
     }
+
+    fn shrink_to_fit(&mut self) {
+        self.task.shrink_to_fit();
+    }
 }
 
 macro_rules! impl_operation {
@@ -279,6 +279,21 @@ where
             }
         }
     }
+
+    /// Releases the excess capacity of the maps, e.g. once the task won't
+    /// change anymore.
+    pub fn shrink_to_fit(&mut self) {
+        match self {
+            InnerStorage::Plain { map, .. } => map.shrink_to_fit(),
+            InnerStorage::Indexed { map, .. } => {
+                map.retain(|_, m| !m.is_empty());
+                for m in map.values_mut() {
+                    m.shrink_to_fit();
+                }
+                map.shrink_to_fit();
+            }
+        }
+    }
 }
 
 impl<T: KeyValuePair> InnerStorage<T>
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use anyhow::Result;
 use turbo_tasks::{backend::CachedTaskType, SessionId, TaskId};
@@ -25,6 +25,8 @@ pub trait BackingStorage: 'static + Send + Sync {
         meta_updates: Vec<ChunkedVec<CachedDataUpdate>>,
         data_updates: Vec<ChunkedVec<CachedDataUpdate>>,
     ) -> Result<()>;
+    /// Writes all persisted data to a portable archive at `path`.
+    fn export_cache(&self, path: &Path) -> Result<()>;
     fn start_read_transaction(&self) -> Option<Self::ReadTransaction<'_>>;
     /// # Safety
     ///
@@ -129,6 +129,15 @@ impl DirtyContainerCount {
         self.count
     }
 
+    /// Get the count for a specific session, without the tasks that are only dirty because they are
+    /// session dependent and were clean in the session that last updated the count.
+    pub fn get_without_stale(&self, session: SessionId) -> i32 {
+        match self.count_in_session {
+            Some((s, count)) if s <= session => count,
+            _ => self.count,
+        }
+    }
+
     /// Increase/decrease the count by the given value.
     pub fn update(&mut self, count: i32) -> DirtyContainerCount {
         self.update_count(&DirtyContainerCount {
@@ -0,0 +1,212 @@
+use std::{
+    borrow::Cow,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::database::{
+    key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
+    startup_cache::{key_space_from_byte, write_key_value_pair},
+};
+
+const MAGIC: &[u8] = b"turbo-tasks-cache\n";
+
+/// The version of the archive contents. Archives can only be imported by the
+/// same version that exported them, like the database itself.
+fn version() -> &'static str {
+    env!("VERGEN_GIT_DESCRIBE")
+}
+
+/// Writes all key-value pairs of `database` to an archive file at `path`.
+pub fn export_cache(database: &impl KeyValueDatabase, path: &Path) -> Result<()> {
+    // Write to a temp file to avoid leaving a corrupted archive behind
+    let temp_path = path.with_extension("tmp");
+    let mut writer = BufWriter::new(
+        File::create(&temp_path)
+            .with_context(|| format!("Creating cache archive {} failed", path.display()))?,
+    );
+    writer.write_all(MAGIC)?;
+    let version = version();
+    writer.write_all(&(version.len() as u32).to_be_bytes())?;
+    writer.write_all(version.as_bytes())?;
+    let mut size_buffer = [0u8; 4];
+    for key_space in KeySpace::ALL {
+        database.for_each(key_space, &mut |key, value| {
+            write_key_value_pair(&mut writer, key_space, key, value, &mut size_buffer)?;
+            Ok(())
+        })?;
+    }
+    writer.flush()?;
+    drop(writer);
+    fs::rename(temp_path, path)?;
+    Ok(())
+}
+
+/// Writes all key-value pairs of the archive file at `path` to `database`.
+/// The archive is read pair by pair, so it's never held in memory as a whole.
+pub fn import_cache(database: &impl KeyValueDatabase, path: &Path) -> Result<()> {
+    let mut reader = BufReader::new(
+        File::open(path)
+            .with_context(|| format!("Reading cache archive {} failed", path.display()))?,
+    );
+    let archive_version = read_header(&mut reader)
+        .with_context(|| format!("{} is not a cache archive", path.display()))?;
+    if archive_version != version().as_bytes() {
+        bail!(
+            "The cache archive {} was created by version {}, but the current version is {}",
+            path.display(),
+            String::from_utf8_lossy(&archive_version),
+            version()
+        );
+    }
+
+    let mut batch = database.write_batch()?;
+    while let Some((key_space, key, value)) = read_key_value_pair(&mut reader)
+        .with_context(|| format!("The cache archive {} is corrupted", path.display()))?
+    {
+        batch.put(key_space, Cow::Owned(key), Cow::Owned(value))?;
+    }
+    batch.commit()?;
+    Ok(())
+}
+
+/// Reads the magic bytes and returns the version of an archive.
+fn read_header(reader: &mut impl Read) -> Result<Vec<u8>> {
+    let mut magic = [0u8; MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        bail!("Unexpected magic bytes");
+    }
+    let version_len = read_u32(reader)?;
+    let mut version = vec![0u8; version_len as usize];
+    reader.read_exact(&mut version)?;
+    Ok(version)
+}
+
+/// Reads the next key-value pair that [write_key_value_pair] wrote, or `None`
+/// at the end of the archive.
+fn read_key_value_pair(reader: &mut impl Read) -> Result<Option<(KeySpace, Vec<u8>, Vec<u8>)>> {
+    let mut key_space = [0u8; 1];
+    if reader.read(&mut key_space)? == 0 {
+        return Ok(None);
+    }
+    let key_space = key_space_from_byte(key_space[0])?;
+    let key_len = read_u32(reader)?;
+    let value_len = read_u32(reader)?;
+    let mut key = vec![0u8; key_len as usize];
+    reader.read_exact(&mut key)?;
+    let mut value = vec![0u8; value_len as usize];
+    reader.read_exact(&mut value)?;
+    Ok(Some((key_space, key, value)))
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, fs, path::PathBuf};
+
+    use super::{export_cache, import_cache};
+    use crate::database::{
+        key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
+        lmdb::LmbdKeyValueDatabase,
+    };
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cache-archive-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn pairs(database: &impl KeyValueDatabase) -> Vec<(String, Vec<u8>, Vec<u8>)> {
+        let mut pairs = Vec::new();
+        for key_space in KeySpace::ALL {
+            database
+                .for_each(key_space, &mut |key, value| {
+                    pairs.push((format!("{key_space:?}"), key.to_vec(), value.to_vec()));
+                    Ok(())
+                })
+                .unwrap();
+        }
+        pairs
+    }
+
+    #[test]
+    fn round_trips_all_key_spaces() {
+        let dir = temp_dir("round-trip");
+        let source = LmbdKeyValueDatabase::new(&dir.join("source")).unwrap();
+        let mut batch = source.write_batch().unwrap();
+        batch
+            .put(
+                KeySpace::Infra,
+                Cow::Borrowed(b"next_task_id"),
+                Cow::Borrowed(b"42"),
+            )
+            .unwrap();
+        batch
+            .put(
+                KeySpace::TaskData,
+                Cow::Borrowed(b"task"),
+                Cow::Borrowed(b"data"),
+            )
+            .unwrap();
+        // Keys that are too long for LMDB are stored as extended keys.
+        batch
+            .put(
+                KeySpace::ForwardTaskCache,
+                Cow::Owned(vec![7; 1000]),
+                Cow::Borrowed(b"task id"),
+            )
+            .unwrap();
+        batch.commit().unwrap();
+
+        let archive = dir.join("cache.archive");
+        export_cache(&source, &archive).unwrap();
+        let target = LmbdKeyValueDatabase::new(&dir.join("target")).unwrap();
+        import_cache(&target, &archive).unwrap();
+
+        let imported = pairs(&target);
+        assert_eq!(imported.len(), 3);
+        assert_eq!(imported, pairs(&source));
+
+        drop((source, target));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_archives() {
+        let dir = temp_dir("invalid");
+        let database = LmbdKeyValueDatabase::new(&dir.join("db")).unwrap();
+        let mut batch = database.write_batch().unwrap();
+        batch
+            .put(
+                KeySpace::TaskData,
+                Cow::Borrowed(b"task"),
+                Cow::Borrowed(b"data"),
+            )
+            .unwrap();
+        batch.commit().unwrap();
+        let archive = dir.join("cache.archive");
+        export_cache(&database, &archive).unwrap();
+
+        let mut truncated = fs::read(&archive).unwrap();
+        truncated.pop();
+        fs::write(&archive, &truncated).unwrap();
+        let target = LmbdKeyValueDatabase::new(&dir.join("truncated")).unwrap();
+        assert!(import_cache(&target, &archive).is_err());
+
+        fs::write(&archive, b"not an archive").unwrap();
+        assert!(import_cache(&target, &archive).is_err());
+
+        drop((database, target));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    borrow::{Borrow, Cow},
+    fs,
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::{bail, Context, Result};
+use turbo_tasks_hash::{DeterministicHasher, Xxh3Hash64Hasher};
+
+use crate::database::key_value_database::{KeySpace, KeyValueDatabase, WriteBatch};
+
+const CHECKSUM_SIZE: usize = 8;
+
+/// The file in the database directory that marks the database as corrupted.
+const CORRUPTED_FILE: &str = "corrupted";
+
+/// The checksum of a key-value pair. The key is part of it, so a value that
+/// ends up at the wrong key is detected too.
+fn checksum(key: &[u8], value: &[u8]) -> [u8; CHECKSUM_SIZE] {
+    let mut hasher = Xxh3Hash64Hasher::new();
+    hasher.write_bytes(&(key.len() as u32).to_be_bytes());
+    hasher.write_bytes(key);
+    hasher.write_bytes(value);
+    hasher.finish().to_be_bytes()
+}
+
+/// Returns the value of a stored value that ends with the checksum of the
+/// key-value pair, or `None` if the checksum doesn't match.
+fn verify<'a>(key: &[u8], stored: &'a [u8]) -> Option<&'a [u8]> {
+    let (value, stored_checksum) = stored.split_at(stored.len().checked_sub(CHECKSUM_SIZE)?);
+    (checksum(key, value) == stored_checksum).then_some(value)
+}
+
+fn with_checksum(key: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut stored = Vec::with_capacity(value.len() + CHECKSUM_SIZE);
+    stored.extend_from_slice(value);
+    stored.extend_from_slice(&checksum(key, value));
+    stored
+}
+
+/// A value that was verified against its checksum.
+pub struct ChecksummedValue<B>(B);
+
+impl<B: Borrow<[u8]>> Borrow<[u8]> for ChecksummedValue<B> {
+    fn borrow(&self) -> &[u8] {
+        let stored = self.0.borrow();
+        &stored[..stored.len() - CHECKSUM_SIZE]
+    }
+}
+
+/// Stores a checksum with every value and verifies it when reading, so a
+/// database that was left partially written by a crashed process is detected
+/// instead of failing to deserialize.
+///
+/// A corrupted entry is reported as an error, which the backing storage
+/// treats like a missing entry, and marks the database at `path` as
+/// corrupted, see [ChecksumLayer::check].
+pub struct ChecksumLayer<T: KeyValueDatabase> {
+    database: T,
+    corrupted_file: PathBuf,
+    marked_as_corrupted: AtomicBool,
+}
+
+impl<T: KeyValueDatabase> ChecksumLayer<T> {
+    pub fn new(database: T, path: &Path) -> Self {
+        Self {
+            database,
+            corrupted_file: path.join(CORRUPTED_FILE),
+            marked_as_corrupted: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns why the database is corrupted, if a previous session marked it
+    /// as corrupted or an entry of the small [KeySpace::Infra] is corrupted.
+    /// The entries of the other key spaces are only verified when they are
+    /// read, so starting doesn't read the whole database.
+    pub fn check(&self) -> Result<Option<String>> {
+        match fs::read_to_string(&self.corrupted_file) {
+            Ok(reason) => return Ok(Some(reason)),
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(err).context("Reading the corruption marker of the database failed")
+            }
+        }
+        let mut corrupt_entries = 0;
+        self.database
+            .for_each(KeySpace::Infra, &mut |key, stored| {
+                if verify(key, stored).is_none() {
+                    corrupt_entries += 1;
+                }
+                Ok(())
+            })?;
+        Ok((corrupt_entries > 0)
+            .then(|| format!("{corrupt_entries} entries of the Infra key space are corrupted")))
+    }
+
+    /// Marks the database as corrupted, so it's recomputed on the next start,
+    /// and returns the error for the corrupted entry.
+    fn corrupted_entry(&self, key_space: KeySpace, key: &[u8]) -> anyhow::Error {
+        let error = corrupted_entry(key_space, key);
+        if !self.marked_as_corrupted.swap(true, Ordering::Relaxed) {
+            println!(
+                "WARNING: {error}. Corrupted entries are recomputed, and the persistent cache is \
+                 recomputed on the next start."
+            );
+            if let Err(err) = fs::write(&self.corrupted_file, error.to_string()) {
+                println!("WARNING: Marking the persistent cache as corrupted failed: {err}");
+            }
+        }
+        error
+    }
+}
+
+fn corrupted_entry(key_space: KeySpace, key: &[u8]) -> anyhow::Error {
+    anyhow::anyhow!(
+        "The checksum of the {key_space:?} entry {key:?} doesn't match, the persistent cache is \
+         corrupted"
+    )
+}
+
+impl<T: KeyValueDatabase> KeyValueDatabase for ChecksumLayer<T> {
+    type ReadTransaction<'l>
+        = T::ReadTransaction<'l>
+    where
+        Self: 'l;
+
+    fn lower_read_transaction<'l: 'i + 'r, 'i: 'r, 'r>(
+        tx: &'r Self::ReadTransaction<'l>,
+    ) -> &'r Self::ReadTransaction<'i> {
+        T::lower_read_transaction(tx)
+    }
+
+    fn begin_read_transaction(&self) -> Result<Self::ReadTransaction<'_>> {
+        self.database.begin_read_transaction()
+    }
+
+    type ValueBuffer<'l>
+        = ChecksummedValue<T::ValueBuffer<'l>>
+    where
+        Self: 'l;
+
+    fn get<'l, 'db: 'l>(
+        &'l self,
+        transaction: &'l Self::ReadTransaction<'db>,
+        key_space: KeySpace,
+        key: &[u8],
+    ) -> Result<Option<Self::ValueBuffer<'l>>> {
+        let Some(stored) = self.database.get(transaction, key_space, key)? else {
+            return Ok(None);
+        };
+        if verify(key, stored.borrow()).is_none() {
+            return Err(self.corrupted_entry(key_space, key));
+        }
+        Ok(Some(ChecksummedValue(stored)))
+    }
+
+    type WriteBatch<'l>
+        = ChecksumWriteBatch<'l, T>
+    where
+        Self: 'l;
+
+    fn write_batch(&self) -> Result<Self::WriteBatch<'_>> {
+        Ok(ChecksumWriteBatch {
+            batch: self.database.write_batch()?,
+            this: self,
+        })
+    }
+
+    fn for_each(
+        &self,
+        key_space: KeySpace,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        self.database.for_each(key_space, &mut |key, stored| {
+            let value = verify(key, stored).ok_or_else(|| self.corrupted_entry(key_space, key))?;
+            f(key, value)
+        })
+    }
+}
+
+pub struct ChecksumWriteBatch<'a, T: KeyValueDatabase> {
+    batch: T::WriteBatch<'a>,
+    this: &'a ChecksumLayer<T>,
+}
+
+impl<'a, T: KeyValueDatabase> WriteBatch<'a> for ChecksumWriteBatch<'a, T> {
+    type ValueBuffer<'l>
+        = ChecksummedValue<<T::WriteBatch<'a> as WriteBatch<'a>>::ValueBuffer<'l>>
+    where
+        Self: 'l,
+        'a: 'l;
+
+    fn get<'l>(&'l self, key_space: KeySpace, key: &[u8]) -> Result<Option<Self::ValueBuffer<'l>>>
+    where
+        'a: 'l,
+    {
+        let Some(stored) = self.batch.get(key_space, key)? else {
+            return Ok(None);
+        };
+        if verify(key, stored.borrow()).is_none() {
+            return Err(self.this.corrupted_entry(key_space, key));
+        }
+        Ok(Some(ChecksummedValue(stored)))
+    }
+
+    fn put(&mut self, key_space: KeySpace, key: Cow<[u8]>, value: Cow<[u8]>) -> Result<()> {
+        let stored = with_checksum(&key, &value);
+        self.batch.put(key_space, key, Cow::Owned(stored))
+    }
+
+    fn delete(&mut self, key_space: KeySpace, key: Cow<[u8]>) -> Result<()> {
+        self.batch.delete(key_space, key)
+    }
+
+    fn commit(self) -> Result<()> {
+        self.batch.commit()
+    }
+}
+
+/// Moves the corrupted database at `path` aside, so a fresh database can be
+/// created in its place, and returns the new location. Only the last
+/// quarantined database is kept, for investigation.
+pub fn quarantine(path: &Path) -> Result<PathBuf> {
+    let mut file_name = path
+        .file_name()
+        .context("The database path has no file name")?
+        .to_os_string();
+    file_name.push(".corrupt");
+    let quarantine_path = path.with_file_name(file_name);
+    if quarantine_path.exists() {
+        fs::remove_dir_all(&quarantine_path)?;
+    }
+    fs::rename(path, &quarantine_path).with_context(|| {
+        format!(
+            "Moving the corrupted database {} to {} failed",
+            path.display(),
+            quarantine_path.display()
+        )
+    })?;
+    Ok(quarantine_path)
+}
+
+/// A writer that computes the checksum of everything written to it, for files
+/// that end with a checksum of their contents.
+pub struct ChecksumWriter<W: Write> {
+    writer: W,
+    hasher: Xxh3Hash64Hasher,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            hasher: Xxh3Hash64Hasher::new(),
+        }
+    }
+
+    /// Writes the checksum and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.write_all(&self.hasher.finish().to_be_bytes())?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.hasher.write_bytes(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Returns the contents of a file that was written with a [ChecksumWriter],
+/// without the checksum.
+pub fn verify_file_contents(contents: &[u8]) -> Result<&[u8]> {
+    let Some(split) = contents.len().checked_sub(CHECKSUM_SIZE) else {
+        bail!("The file is truncated");
+    };
+    let (contents, stored_checksum) = contents.split_at(split);
+    let mut hasher = Xxh3Hash64Hasher::new();
+    hasher.write_bytes(contents);
+    if hasher.finish().to_be_bytes() != stored_checksum {
+        bail!("The checksum of the file doesn't match");
+    }
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::{verify, verify_file_contents, with_checksum, ChecksumWriter};
+
+    #[test]
+    fn verifies_values() {
+        let stored = with_checksum(b"key", b"value");
+        assert_eq!(verify(b"key", &stored), Some(&b"value"[..]));
+        assert_eq!(verify(b"other key", &stored), None);
+        assert_eq!(verify(b"key", &stored[..stored.len() - 1]), None);
+        assert_eq!(verify(b"key", &stored[1..]), None);
+        assert_eq!(verify(b"key", b""), None);
+    }
+
+    #[test]
+    fn verifies_files() {
+        let mut writer = ChecksumWriter::new(Vec::new());
+        writer.write_all(b"contents").unwrap();
+        let file = writer.finish().unwrap();
+        assert_eq!(verify_file_contents(&file).unwrap(), b"contents");
+        assert!(verify_file_contents(&file[..file.len() - 1]).is_err());
+        assert!(verify_file_contents(&file[..4]).is_err());
+    }
+}
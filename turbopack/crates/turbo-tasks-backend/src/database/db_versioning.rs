@@ -12,6 +12,14 @@ use anyhow::Result;
 /// the current one and two older/newer ones.
 const MAX_OTHER_DB_VERSIONS: usize = 2;
 
+/// The version of the format of the stored entries, which is part of the name
+/// of the database. It needs to be bumped when the format changes, as a
+/// database of the same git version is reused otherwise, e.g. with
+/// `TURBO_ENGINE_DISABLE_VERSIONING`.
+///
+/// 2: Values end with a checksum.
+const DB_FORMAT_VERSION: u32 = 2;
+
 pub fn handle_db_versioning(base_path: &Path) -> Result<PathBuf> {
     // Database versioning. Pass `TURBO_ENGINE_IGNORE_DIRTY` at runtime to ignore a
     // dirty git repository. Pass `TURBO_ENGINE_DISABLE_VERSIONING` at runtime to disable
@@ -48,7 +56,8 @@ pub fn handle_db_versioning(base_path: &Path) -> Result<PathBuf> {
     };
     let path;
     if let Some(version) = version {
-        path = base_path.join(version);
+        let version = format!("{version}-v{DB_FORMAT_VERSION}");
+        path = base_path.join(&version);
 
         // Remove old databases if needed
         if let Ok(read_dir) = read_dir(base_path) {
@@ -74,6 +74,14 @@ impl<T: KeyValueDatabase> KeyValueDatabase for FreshDbOptimization<T> {
             fresh_db: &self.fresh_db,
         })
     }
+
+    fn for_each(
+        &self,
+        key_space: KeySpace,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        self.database.for_each(key_space, f)
+    }
 }
 
 pub struct FreshDbOptimizationWriteBatch<'a, T: KeyValueDatabase>
@@ -11,6 +11,16 @@ pub enum KeySpace {
     ReverseTaskCache,
 }
 
+impl KeySpace {
+    pub const ALL: [KeySpace; 5] = [
+        KeySpace::Infra,
+        KeySpace::TaskMeta,
+        KeySpace::TaskData,
+        KeySpace::ForwardTaskCache,
+        KeySpace::ReverseTaskCache,
+    ];
+}
+
 pub trait WriteBatch<'a> {
     type ValueBuffer<'l>: std::borrow::Borrow<[u8]>
     where
@@ -51,4 +61,11 @@ pub trait KeyValueDatabase {
     where
         Self: 'l;
     fn write_batch(&self) -> Result<Self::WriteBatch<'_>>;
+
+    /// Calls `f` with every key-value pair in `key_space`.
+    fn for_each(
+        &self,
+        key_space: KeySpace,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>,
+    ) -> Result<()>;
 }
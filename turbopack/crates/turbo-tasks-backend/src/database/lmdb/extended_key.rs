@@ -1,7 +1,8 @@
 use std::hash::{Hash, Hasher};
 
+use anyhow::Result;
 use byteorder::ByteOrder;
-use lmdb::{Database, RwTransaction, Transaction, WriteFlags};
+use lmdb::{Cursor, Database, RwTransaction, Transaction, WriteFlags};
 use rustc_hash::FxHasher;
 
 const MAX_KEY_SIZE: usize = 511;
@@ -102,6 +103,30 @@ pub fn delete(
     }
 }
 
+pub fn for_each<T: Transaction>(
+    tx: &T,
+    database: Database,
+    f: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>,
+) -> Result<()> {
+    let mut cursor = tx.open_ro_cursor(database)?;
+    for entry in cursor.iter_start() {
+        let (key, value) = entry?;
+        if key.len() == MAX_KEY_SIZE {
+            // A hashed key, which is followed by the shared part of the keys. The value contains
+            // the remaining parts of the keys along with their values.
+            let mut full_key = key[8..].to_vec();
+            for (k, v) in ExtendedValueIter::new(value) {
+                full_key.truncate(SHARED_KEY);
+                full_key.extend_from_slice(k);
+                f(&full_key, v)?;
+            }
+        } else {
+            f(key, value)?;
+        }
+    }
+    Ok(())
+}
+
 fn hashed_key(key: &[u8]) -> [u8; MAX_KEY_SIZE] {
     let mut result = [0; MAX_KEY_SIZE];
     let mut hash = FxHasher::default();
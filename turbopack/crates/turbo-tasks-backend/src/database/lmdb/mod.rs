@@ -122,6 +122,15 @@ impl KeyValueDatabase for LmbdKeyValueDatabase {
             this: self,
         })
     }
+
+    fn for_each(
+        &self,
+        key_space: KeySpace,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let tx = self.env.begin_ro_txn()?;
+        extended_key::for_each(&tx, self.db(key_space), f)
+    }
 }
 
 pub struct LmbdWriteBatch<'l> {
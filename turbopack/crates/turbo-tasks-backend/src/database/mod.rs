@@ -1,15 +1,21 @@
 mod by_key_space;
+pub mod cache_archive;
+pub mod checksum;
 pub mod db_versioning;
 pub mod fresh_db_optimization;
 pub mod key_value_database;
 pub mod lmdb;
 pub mod noop_kv;
+pub mod plugin_types;
 pub mod read_transaction_cache;
 mod startup_cache;
+pub mod write_ahead_log;
 
+pub use checksum::ChecksumLayer;
 pub use db_versioning::handle_db_versioning;
 pub use fresh_db_optimization::{is_fresh, FreshDbOptimization};
 #[allow(unused_imports)]
 pub use noop_kv::NoopKvDb;
 pub use read_transaction_cache::ReadTransactionCache;
 pub use startup_cache::StartupCacheLayer;
+pub use write_ahead_log::WriteAheadLogLayer;
@@ -44,6 +44,14 @@ impl KeyValueDatabase for NoopKvDb {
     fn write_batch(&self) -> Result<Self::WriteBatch<'_>> {
         Ok(NoopWriteBatch)
     }
+
+    fn for_each(
+        &self,
+        _key_space: KeySpace,
+        _f: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        Ok(())
+    }
 }
 
 pub struct NoopWriteBatch;
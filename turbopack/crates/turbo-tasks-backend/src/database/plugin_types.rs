@@ -0,0 +1,52 @@
+use std::{fs, io::ErrorKind, path::Path};
+
+use anyhow::{Context, Result};
+use turbo_tasks::registry::{check_plugin_type_compatibility, plugin_type_keys, PluginTypeKey};
+
+/// The file in the database directory that lists the plugin types the
+/// database was written with.
+const PLUGIN_TYPES_FILE: &str = "plugin_types";
+
+/// Checks whether the database at `path` can be read with the plugin types
+/// that are registered now, see [check_plugin_type_compatibility]. A database
+/// without the file was written without plugins.
+pub fn check(path: &Path) -> Result<()> {
+    let persisted = match fs::read(path.join(PLUGIN_TYPES_FILE)) {
+        Ok(bytes) => decode(&bytes)?,
+        Err(err) if err.kind() == ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err).context("failed to read the plugin types of the database"),
+    };
+    check_plugin_type_compatibility(&persisted)
+}
+
+/// Records the plugin types that are registered now as the ones the database
+/// at `path` is written with.
+pub fn write(path: &Path) -> Result<()> {
+    fs::write(path.join(PLUGIN_TYPES_FILE), encode(&plugin_type_keys())?)
+        .context("failed to write the plugin types of the database")
+}
+
+fn encode(keys: &[PluginTypeKey]) -> Result<Vec<u8>> {
+    Ok(pot::to_vec(keys)?)
+}
+
+fn decode(bytes: &[u8]) -> Result<Vec<PluginTypeKey>> {
+    pot::from_slice(bytes).context("the plugin types of the database are corrupted")
+}
+
+#[cfg(test)]
+mod tests {
+    use turbo_tasks::registry::PluginTypeKey;
+
+    use super::{decode, encode};
+
+    #[test]
+    fn round_trips_plugin_types() {
+        let keys = vec![
+            PluginTypeKey::new("my-plugin", "MyModule", 1),
+            PluginTypeKey::new("my-plugin", "MyTrait", 2),
+        ];
+        assert_eq!(decode(&encode(&keys).unwrap()).unwrap(), keys);
+        assert!(decode(b"corrupted").is_err());
+    }
+}
@@ -5,7 +5,7 @@ use arc_swap::ArcSwap;
 use smallvec::SmallVec;
 use thread_local::ThreadLocal;
 
-use crate::database::key_value_database::{KeyValueDatabase, WriteBatch};
+use crate::database::key_value_database::{KeySpace, KeyValueDatabase, WriteBatch};
 
 struct ThreadLocalReadTransactionsContainer<T: KeyValueDatabase + 'static>(
     UnsafeCell<SmallVec<[T::ReadTransaction<'static>; 4]>>,
@@ -94,6 +94,14 @@ impl<T: KeyValueDatabase + 'static> KeyValueDatabase for ReadTransactionCache<T>
             this: self,
         })
     }
+
+    fn for_each(
+        &self,
+        key_space: KeySpace,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        self.database.for_each(key_space, f)
+    }
 }
 
 pub struct CachedReadTransaction<'l, T: KeyValueDatabase + 'static> {
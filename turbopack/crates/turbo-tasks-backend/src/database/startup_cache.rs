@@ -8,13 +8,14 @@ use std::{
     sync::atomic::{AtomicUsize, Ordering},
 };
 
-use anyhow::{Ok, Result};
+use anyhow::{Context, Ok, Result};
 use byteorder::WriteBytesExt;
 use dashmap::DashMap;
 use rustc_hash::{FxHashMap, FxHasher};
 
 use crate::database::{
     by_key_space::ByKeySpace,
+    checksum::{verify_file_contents, ChecksumWriter},
     key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
 };
 
@@ -59,18 +60,15 @@ impl<T: KeyValueDatabase> StartupCacheLayer<T> {
             if let Result::Ok(mut cache_file) = File::open(&path) {
                 cache_file.read_to_end(&mut restored)?;
                 drop(cache_file);
-                let mut pos = 0;
-                while pos < restored.len() {
-                    let (key_space, key, value) = read_key_value_pair(&restored, &mut pos)?;
-                    let map = restored_map.get_mut(key_space);
-                    unsafe {
-                        // Safety: This is a self reference, it's valid as long the `restored`
-                        // buffer is alive
-                        map.insert(
-                            transmute::<&'_ [u8], &'static [u8]>(key),
-                            transmute::<&'_ [u8], &'static [u8]>(value),
-                        );
-                    }
+                // The startup cache only duplicates entries of the database, so a corrupted
+                // one is dropped and recreated on the next snapshot.
+                if let Err(err) = restore(&restored, &mut restored_map) {
+                    println!(
+                        "WARNING: The startup cache {} is corrupted and will be recreated: {err:#}",
+                        path.display()
+                    );
+                    restored_map = ByKeySpace::new(|_| FxHashMap::default());
+                    let _ = fs::remove_file(&path);
                 }
             }
         }
@@ -97,6 +95,28 @@ impl<T: KeyValueDatabase> StartupCacheLayer<T> {
     }
 }
 
+/// Fills `restored_map` with the key-value pairs of a startup cache file.
+fn restore(
+    restored: &[u8],
+    restored_map: &mut ByKeySpace<FxHashMap<&'static [u8], &'static [u8]>>,
+) -> Result<()> {
+    let pairs = verify_file_contents(restored)?;
+    let mut pos = 0;
+    while pos < pairs.len() {
+        let (key_space, key, value) = read_key_value_pair(pairs, &mut pos)?;
+        let map = restored_map.get_mut(key_space);
+        unsafe {
+            // Safety: This is a self reference, it's valid as long the `restored`
+            // buffer is alive
+            map.insert(
+                transmute::<&'_ [u8], &'static [u8]>(key),
+                transmute::<&'_ [u8], &'static [u8]>(value),
+            );
+        }
+    }
+    Ok(())
+}
+
 impl<T: KeyValueDatabase> KeyValueDatabase for StartupCacheLayer<T> {
     type ReadTransaction<'l>
         = T::ReadTransaction<'l>
@@ -166,6 +186,14 @@ impl<T: KeyValueDatabase> KeyValueDatabase for StartupCacheLayer<T> {
             this: self,
         })
     }
+
+    fn for_each(
+        &self,
+        key_space: KeySpace,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        self.database.for_each(key_space, f)
+    }
 }
 
 pub struct StartupCacheWriteBatch<'a, T: KeyValueDatabase> {
@@ -212,7 +240,7 @@ impl<'a, T: KeyValueDatabase> WriteBatch<'a> for StartupCacheWriteBatch<'a, T> {
         if !self.this.fresh_db {
             // write cache to a temp file to avoid corrupted file
             let temp_path = self.this.path.with_extension("cache.tmp");
-            let mut writer = BufWriter::new(File::create(&temp_path)?);
+            let mut writer = ChecksumWriter::new(BufWriter::new(File::create(&temp_path)?));
             let mut size_buffer = [0u8; 4];
             let mut pos = 0;
             for (key_space, cache) in self.this.cache.iter() {
@@ -249,6 +277,8 @@ impl<'a, T: KeyValueDatabase> WriteBatch<'a> for StartupCacheWriteBatch<'a, T> {
                 }
             }
 
+            writer.finish()?.flush()?;
+
             // move temp file to the final location
             fs::rename(temp_path, &self.this.path)?;
         }
@@ -256,8 +286,8 @@ impl<'a, T: KeyValueDatabase> WriteBatch<'a> for StartupCacheWriteBatch<'a, T> {
     }
 }
 
-fn write_key_value_pair(
-    writer: &mut BufWriter<File>,
+pub(crate) fn write_key_value_pair(
+    writer: &mut impl Write,
     key_space: KeySpace,
     key: &[u8],
     value: &[u8],
@@ -281,26 +311,34 @@ fn write_key_value_pair(
     Ok(9 + key_len + value_len)
 }
 
-fn read_key_value_pair<'l>(
-    buffer: &'l [u8],
-    pos: &mut usize,
-) -> Result<(KeySpace, &'l [u8], &'l [u8])> {
-    let key_space = match buffer[*pos] {
+/// The inverse of the key space byte of [write_key_value_pair].
+pub(crate) fn key_space_from_byte(byte: u8) -> Result<KeySpace> {
+    Ok(match byte {
         0 => KeySpace::Infra,
         1 => KeySpace::TaskMeta,
         2 => KeySpace::TaskData,
         3 => KeySpace::ForwardTaskCache,
         4 => KeySpace::ReverseTaskCache,
         _ => return Err(anyhow::anyhow!("Invalid key space")),
+    })
+}
+
+pub(crate) fn read_key_value_pair<'l>(
+    buffer: &'l [u8],
+    pos: &mut usize,
+) -> Result<(KeySpace, &'l [u8], &'l [u8])> {
+    // A truncated buffer is reported as an error instead of panicking
+    let mut take = |len: usize| -> Result<&'l [u8]> {
+        let bytes = buffer
+            .get(*pos..*pos + len)
+            .context("Truncated key-value pair")?;
+        *pos += len;
+        Ok(bytes)
     };
-    *pos += 1;
-    let key_len = u32::from_be_bytes(buffer[*pos..*pos + 4].try_into()?);
-    *pos += 4;
-    let value_len = u32::from_be_bytes(buffer[*pos..*pos + 4].try_into()?);
-    *pos += 4;
-    let key = &buffer[*pos..*pos + key_len as usize];
-    *pos += key_len as usize;
-    let value = &buffer[*pos..*pos + value_len as usize];
-    *pos += value_len as usize;
+    let key_space = key_space_from_byte(take(1)?[0])?;
+    let key_len = u32::from_be_bytes(take(4)?.try_into()?);
+    let value_len = u32::from_be_bytes(take(4)?.try_into()?);
+    let key = take(key_len as usize)?;
+    let value = take(value_len as usize)?;
     Ok((key_space, key, value))
 }
@@ -0,0 +1,435 @@
+use std::{
+    borrow::{Borrow, Cow},
+    cell::OnceCell,
+    fs::{File, OpenOptions},
+    hash::BuildHasherDefault,
+    io::{Read, Write},
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use byteorder::WriteBytesExt;
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use rustc_hash::{FxHashMap, FxHasher};
+
+use crate::database::{
+    by_key_space::ByKeySpace,
+    checksum::{verify_file_contents, ChecksumWriter},
+    key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
+    startup_cache::{read_key_value_pair, write_key_value_pair},
+};
+
+/// The logged batches are checkpointed into the database when the log grows
+/// beyond this size...
+const LOG_SIZE_LIMIT: u64 = 256 * 1024 * 1024;
+/// ...or when the last checkpoint is older than this.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+
+const PUT: u8 = 0;
+const DELETE: u8 = 1;
+
+/// The last value of each changed key, or `None` when it was deleted.
+type PendingChanges = ByKeySpace<DashMap<Vec<u8>, Option<Arc<[u8]>>, BuildHasherDefault<FxHasher>>>;
+type BatchChanges = ByKeySpace<FxHashMap<Vec<u8>, Option<Arc<[u8]>>>>;
+
+pub enum ValueBuffer<B> {
+    Database(B),
+    Logged(Arc<[u8]>),
+}
+
+impl<B: Borrow<[u8]>> Borrow<[u8]> for ValueBuffer<B> {
+    fn borrow(&self) -> &[u8] {
+        match self {
+            ValueBuffer::Database(value) => value.borrow(),
+            ValueBuffer::Logged(value) => value,
+        }
+    }
+}
+
+struct LogState {
+    file: File,
+    size: u64,
+    last_checkpoint: Instant,
+    /// Set when the last commit was checkpointed. The pending changes are only
+    /// dropped with the next commit, since read transactions that were started
+    /// before the checkpoint don't see the checkpointed values.
+    checkpointed: bool,
+}
+
+/// Persists write batches by appending them to a write-ahead log, which is
+/// much cheaper than writing the changed pages of the database, so snapshots
+/// can be persisted frequently.
+///
+/// The logged changes are kept in memory and checkpointed into the database
+/// periodically, with only the last value of each key. A process that crashed
+/// leaves the log behind, and its batches are recovered when the layer is
+/// created again. Only a batch that was cut off while it was appended is lost.
+pub struct WriteAheadLogLayer<T: KeyValueDatabase> {
+    database: T,
+    pending: PendingChanges,
+    state: Mutex<LogState>,
+}
+
+impl<T: KeyValueDatabase> WriteAheadLogLayer<T> {
+    /// Opens the write-ahead log at `path`, and checkpoints the batches that a
+    /// previous process logged into the database.
+    pub fn new(database: T, path: &Path) -> Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .with_context(|| format!("Opening the write-ahead log {} failed", path.display()))?;
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        let this = Self {
+            database,
+            pending: ByKeySpace::new(|_| DashMap::default()),
+            state: Mutex::new(LogState {
+                file,
+                size: contents.len() as u64,
+                last_checkpoint: Instant::now(),
+                checkpointed: false,
+            }),
+        };
+        if !contents.is_empty() {
+            recover(&contents, &this.pending);
+            let mut state = this.state.lock();
+            this.checkpoint(this.database.write_batch()?, &mut state)
+                .context("Recovering the write-ahead log failed")?;
+        }
+        Ok(this)
+    }
+
+    /// Writes all pending changes to the database with `batch`, and empties
+    /// the log.
+    fn checkpoint(&self, mut batch: T::WriteBatch<'_>, state: &mut LogState) -> Result<()> {
+        let _span = tracing::trace_span!("checkpoint write-ahead log", size = state.size).entered();
+        for (key_space, pending) in self.pending.iter() {
+            for entry in pending.iter() {
+                match entry.value() {
+                    Some(value) => batch.put(
+                        key_space,
+                        Cow::Borrowed(entry.key().as_slice()),
+                        Cow::Borrowed(&**value),
+                    )?,
+                    None => batch.delete(key_space, Cow::Borrowed(entry.key().as_slice()))?,
+                }
+            }
+        }
+        batch.commit()?;
+        state.file.set_len(0)?;
+        state.file.sync_data()?;
+        state.size = 0;
+        state.last_checkpoint = Instant::now();
+        state.checkpointed = true;
+        Ok(())
+    }
+}
+
+impl<T: KeyValueDatabase> Drop for WriteAheadLogLayer<T> {
+    fn drop(&mut self) {
+        let mut state = self.state.lock();
+        if state.size == 0 {
+            return;
+        }
+        // The log would be recovered on the next start otherwise.
+        if let Err(err) = self
+            .database
+            .write_batch()
+            .and_then(|batch| self.checkpoint(batch, &mut state))
+        {
+            tracing::warn!("Checkpointing the write-ahead log failed: {err:?}");
+        }
+    }
+}
+
+/// Encodes the `changes` as a record of the log: the length of the record,
+/// the changes and their checksum.
+fn encode_record(changes: &BatchChanges) -> Result<Vec<u8>> {
+    let mut writer = ChecksumWriter::new(vec![0; 4]);
+    let mut size_buffer = [0u8; 4];
+    for (key_space, changes) in changes.iter() {
+        for (key, value) in changes {
+            writer.write_u8(if value.is_some() { PUT } else { DELETE })?;
+            write_key_value_pair(
+                &mut writer,
+                key_space,
+                key,
+                value.as_deref().unwrap_or_default(),
+                &mut size_buffer,
+            )?;
+        }
+    }
+    let mut record = writer.finish()?;
+    let len = (record.len() - 4) as u32;
+    record[..4].copy_from_slice(&len.to_be_bytes());
+    Ok(record)
+}
+
+/// Decodes a record without its length.
+fn decode_record(record: &[u8]) -> Result<Vec<(KeySpace, &[u8], Option<&[u8]>)>> {
+    let entries = verify_file_contents(record)?;
+    let mut changes = Vec::new();
+    let mut pos = 0;
+    while pos < entries.len() {
+        let op = entries[pos];
+        pos += 1;
+        let (key_space, key, value) = read_key_value_pair(entries, &mut pos)?;
+        changes.push(match op {
+            PUT => (key_space, key, Some(value)),
+            DELETE => (key_space, key, None),
+            _ => bail!("Invalid operation {op} in the write-ahead log"),
+        });
+    }
+    Ok(changes)
+}
+
+/// Applies the records of the log `contents` to `pending`, in order. Returns
+/// the number of recovered records.
+///
+/// The records after the first incomplete or corrupted one are dropped, as
+/// that is where the process crashed while appending.
+fn recover(mut contents: &[u8], pending: &PendingChanges) -> usize {
+    let mut recovered = 0;
+    while let Some((len, rest)) = contents.split_first_chunk::<4>() {
+        let len = u32::from_be_bytes(*len) as usize;
+        let Some(changes) = rest
+            .get(..len)
+            .and_then(|record| decode_record(record).ok())
+        else {
+            break;
+        };
+        for (key_space, key, value) in changes {
+            pending
+                .get(key_space)
+                .insert(key.to_vec(), value.map(Arc::from));
+        }
+        recovered += 1;
+        contents = &rest[len..];
+    }
+    recovered
+}
+
+impl<T: KeyValueDatabase> KeyValueDatabase for WriteAheadLogLayer<T> {
+    type ReadTransaction<'l>
+        = T::ReadTransaction<'l>
+    where
+        Self: 'l;
+
+    fn lower_read_transaction<'l: 'i + 'r, 'i: 'r, 'r>(
+        tx: &'r Self::ReadTransaction<'l>,
+    ) -> &'r Self::ReadTransaction<'i> {
+        T::lower_read_transaction(tx)
+    }
+
+    fn begin_read_transaction(&self) -> Result<Self::ReadTransaction<'_>> {
+        self.database.begin_read_transaction()
+    }
+
+    type ValueBuffer<'l>
+        = ValueBuffer<T::ValueBuffer<'l>>
+    where
+        Self: 'l;
+
+    fn get<'l, 'db: 'l>(
+        &'l self,
+        transaction: &'l Self::ReadTransaction<'db>,
+        key_space: KeySpace,
+        key: &[u8],
+    ) -> Result<Option<Self::ValueBuffer<'l>>> {
+        if let Some(value) = self.pending.get(key_space).get(key) {
+            return Ok(value.clone().map(ValueBuffer::Logged));
+        }
+        Ok(self
+            .database
+            .get(transaction, key_space, key)?
+            .map(ValueBuffer::Database))
+    }
+
+    type WriteBatch<'l>
+        = WriteAheadLogWriteBatch<'l, T>
+    where
+        Self: 'l;
+
+    fn write_batch(&self) -> Result<Self::WriteBatch<'_>> {
+        Ok(WriteAheadLogWriteBatch {
+            batch: OnceCell::new(),
+            changes: ByKeySpace::new(|_| FxHashMap::default()),
+            this: self,
+        })
+    }
+
+    fn for_each(
+        &self,
+        key_space: KeySpace,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let pending = self.pending.get(key_space);
+        self.database.for_each(key_space, &mut |key, value| {
+            if pending.contains_key(key) {
+                return Ok(());
+            }
+            f(key, value)
+        })?;
+        for entry in pending.iter() {
+            if let Some(value) = entry.value() {
+                f(entry.key(), value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct WriteAheadLogWriteBatch<'a, T: KeyValueDatabase> {
+    /// Only opened when a value isn't logged or the log is checkpointed, as
+    /// write batches of the database exclude each other.
+    batch: OnceCell<T::WriteBatch<'a>>,
+    changes: BatchChanges,
+    this: &'a WriteAheadLogLayer<T>,
+}
+
+impl<'a, T: KeyValueDatabase> WriteBatch<'a> for WriteAheadLogWriteBatch<'a, T> {
+    type ValueBuffer<'l>
+        = ValueBuffer<<T::WriteBatch<'a> as WriteBatch<'a>>::ValueBuffer<'l>>
+    where
+        Self: 'l,
+        'a: 'l;
+
+    fn get<'l>(&'l self, key_space: KeySpace, key: &[u8]) -> Result<Option<Self::ValueBuffer<'l>>>
+    where
+        'a: 'l,
+    {
+        if let Some(value) = self.changes.get(key_space).get(key) {
+            return Ok(value.clone().map(ValueBuffer::Logged));
+        }
+        if let Some(value) = self.this.pending.get(key_space).get(key) {
+            return Ok(value.clone().map(ValueBuffer::Logged));
+        }
+        if self.batch.get().is_none() {
+            let _ = self.batch.set(self.this.database.write_batch()?);
+        }
+        let batch = self.batch.get().unwrap();
+        Ok(batch.get(key_space, key)?.map(ValueBuffer::Database))
+    }
+
+    fn put(&mut self, key_space: KeySpace, key: Cow<[u8]>, value: Cow<[u8]>) -> Result<()> {
+        self.changes
+            .get_mut(key_space)
+            .insert(key.into_owned(), Some(value.into_owned().into()));
+        Ok(())
+    }
+
+    fn delete(&mut self, key_space: KeySpace, key: Cow<[u8]>) -> Result<()> {
+        self.changes
+            .get_mut(key_space)
+            .insert(key.into_owned(), None);
+        Ok(())
+    }
+
+    fn commit(self) -> Result<()> {
+        let this = self.this;
+        let mut state = this.state.lock();
+        if state.checkpointed {
+            for (_, pending) in this.pending.iter() {
+                pending.clear();
+            }
+            state.checkpointed = false;
+        }
+
+        let record = encode_record(&self.changes)?;
+        {
+            let _span =
+                tracing::trace_span!("append to write-ahead log", size = record.len()).entered();
+            if let Err(err) = state
+                .file
+                .write_all(&record)
+                .and_then(|_| state.file.sync_data())
+            {
+                // Records after a partially written one would not be recovered.
+                let _ = state.file.set_len(state.size);
+                return Err(err).context("Appending to the write-ahead log failed");
+            }
+        }
+        state.size += record.len() as u64;
+        for (key_space, changes) in self.changes.iter() {
+            let pending = this.pending.get(key_space);
+            for (key, value) in changes {
+                pending.insert(key.clone(), value.clone());
+            }
+        }
+
+        if state.size >= LOG_SIZE_LIMIT || state.last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+            let batch = match self.batch.into_inner() {
+                Some(batch) => batch,
+                None => this.database.write_batch()?,
+            };
+            this.checkpoint(batch, &mut state)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rustc_hash::FxHashMap;
+
+    use super::{encode_record, recover, BatchChanges, PendingChanges};
+    use crate::database::{by_key_space::ByKeySpace, key_value_database::KeySpace};
+
+    fn changes(entries: &[(&str, Option<&str>)]) -> BatchChanges {
+        let mut changes = ByKeySpace::new(|_| FxHashMap::default());
+        for (key, value) in entries {
+            changes.get_mut(KeySpace::TaskData).insert(
+                key.as_bytes().to_vec(),
+                value.map(|value| Arc::from(value.as_bytes())),
+            );
+        }
+        changes
+    }
+
+    fn pending_value(pending: &PendingChanges, key: &[u8]) -> Option<Option<Vec<u8>>> {
+        pending
+            .get(KeySpace::TaskData)
+            .get(key)
+            .map(|value| value.as_deref().map(<[u8]>::to_vec))
+    }
+
+    #[test]
+    fn recovers_records_in_order() {
+        let mut log = encode_record(&changes(&[("a", Some("1")), ("b", Some("2"))])).unwrap();
+        log.extend(encode_record(&changes(&[("a", None), ("c", Some("3"))])).unwrap());
+
+        let pending: PendingChanges = ByKeySpace::new(|_| Default::default());
+        assert_eq!(recover(&log, &pending), 2);
+        assert_eq!(pending_value(&pending, b"a"), Some(None));
+        assert_eq!(pending_value(&pending, b"b"), Some(Some(b"2".to_vec())));
+        assert_eq!(pending_value(&pending, b"c"), Some(Some(b"3".to_vec())));
+        assert!(pending.get(KeySpace::TaskMeta).is_empty());
+    }
+
+    #[test]
+    fn drops_incomplete_records() {
+        let first = encode_record(&changes(&[("a", Some("1"))])).unwrap();
+        let second = encode_record(&changes(&[("a", Some("2"))])).unwrap();
+        let mut log = first.clone();
+        log.extend(&second[..second.len() - 1]);
+
+        let pending: PendingChanges = ByKeySpace::new(|_| Default::default());
+        assert_eq!(recover(&log, &pending), 1);
+        assert_eq!(pending_value(&pending, b"a"), Some(Some(b"1".to_vec())));
+
+        let mut log = first;
+        log.extend(&second);
+        let last = log.len() - 1;
+        log[last] ^= 1;
+        let pending: PendingChanges = ByKeySpace::new(|_| Default::default());
+        assert_eq!(recover(&log, &pending), 1);
+        assert_eq!(pending_value(&pending, b"a"), Some(Some(b"1".to_vec())));
+    }
+}
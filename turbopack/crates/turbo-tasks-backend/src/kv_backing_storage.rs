@@ -1,6 +1,7 @@
 use std::{
     borrow::{Borrow, Cow},
     collections::hash_map::Entry,
+    path::Path,
     sync::Arc,
 };
 
@@ -14,7 +15,10 @@ use crate::{
     backend::{AnyOperation, TaskDataCategory},
     backing_storage::BackingStorage,
     data::{CachedDataItem, CachedDataItemKey, CachedDataItemValue, CachedDataUpdate},
-    database::key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
+    database::{
+        cache_archive,
+        key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
+    },
     utils::chunked_vec::ChunkedVec,
 };
 
@@ -260,6 +264,10 @@ impl<T: KeyValueDatabase + Send + Sync + 'static> BackingStorage
         Ok(())
     }
 
+    fn export_cache(&self, path: &Path) -> Result<()> {
+        cache_archive::export_cache(&self.database, path)
+    }
+
     fn start_read_transaction(&self) -> Option<Self::ReadTransaction<'_>> {
         self.database.begin_read_transaction().ok()
     }
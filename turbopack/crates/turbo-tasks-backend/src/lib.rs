@@ -7,30 +7,99 @@ mod database;
 mod kv_backing_storage;
 mod utils;
 
-use std::path::Path;
+use std::{fs, path::Path};
 
 use anyhow::Result;
 
 pub use self::{backend::TurboTasksBackend, kv_backing_storage::KeyValueDatabaseBackingStorage};
 use crate::database::{
-    handle_db_versioning, is_fresh, lmdb::LmbdKeyValueDatabase, FreshDbOptimization, NoopKvDb,
-    ReadTransactionCache, StartupCacheLayer,
+    cache_archive, checksum, handle_db_versioning, is_fresh, lmdb::LmbdKeyValueDatabase,
+    plugin_types, ChecksumLayer, FreshDbOptimization, NoopKvDb, ReadTransactionCache,
+    StartupCacheLayer, WriteAheadLogLayer,
 };
 
 pub type LmdbBackingStorage = KeyValueDatabaseBackingStorage<
-    ReadTransactionCache<StartupCacheLayer<FreshDbOptimization<LmbdKeyValueDatabase>>>,
+    ReadTransactionCache<
+        StartupCacheLayer<
+            FreshDbOptimization<WriteAheadLogLayer<ChecksumLayer<LmbdKeyValueDatabase>>>,
+        >,
+    >,
 >;
 
 pub fn lmdb_backing_storage(path: &Path) -> Result<LmdbBackingStorage> {
     let path = handle_db_versioning(path)?;
-    let fresh_db = is_fresh(&path);
-    let database = LmbdKeyValueDatabase::new(&path)?;
+    let mut fresh_db = is_fresh(&path);
+    if !fresh_db {
+        if let Err(err) = plugin_types::check(&path) {
+            // Values of plugin types that are not registered anymore or changed their schema
+            // can't be read, so the database is recomputed.
+            println!(
+                "WARNING: The persistent cache can't be used with the loaded plugins and will be \
+                 recomputed: {err:#}"
+            );
+            fs::remove_dir_all(&path)?;
+            fresh_db = true;
+        }
+    }
+    let database = open_lmdb_database(&path, &mut fresh_db)?;
+    plugin_types::write(&path)?;
+    // Snapshots are appended to a write-ahead log, which is recovered here when the
+    // previous process crashed before checkpointing it into the database.
+    let database = WriteAheadLogLayer::new(database, &path.join("wal.log"))?;
     let database = FreshDbOptimization::new(database, fresh_db);
     let database = StartupCacheLayer::new(database, path.join("startup.cache"), fresh_db)?;
     let database = ReadTransactionCache::new(database);
     Ok(KeyValueDatabaseBackingStorage::new(database))
 }
 
+/// Opens the database at `path`, which is quarantined and recreated when it's
+/// corrupted, see [ChecksumLayer::check].
+fn open_lmdb_database(
+    path: &Path,
+    fresh_db: &mut bool,
+) -> Result<ChecksumLayer<LmbdKeyValueDatabase>> {
+    let database = ChecksumLayer::new(LmbdKeyValueDatabase::new(path)?, path);
+    if *fresh_db {
+        return Ok(database);
+    }
+    let Some(corruption) = database.check()? else {
+        return Ok(database);
+    };
+    // The entries of the database depend on each other, so the whole database is
+    // recomputed instead of only the corrupted entries.
+    drop(database);
+    let quarantine_path = checksum::quarantine(path)?;
+    println!(
+        "WARNING: The persistent cache is corrupted, likely because a previous process crashed \
+         while writing it: {corruption}. It was moved to {} and will be recomputed.",
+        quarantine_path.display()
+    );
+    *fresh_db = true;
+    Ok(ChecksumLayer::new(LmbdKeyValueDatabase::new(path)?, path))
+}
+
+/// Imports a cache archive, which was created by
+/// [turbo_tasks::TurboTasks::export_cache], into the database at `path`. This
+/// needs to happen before the database is opened by [lmdb_backing_storage],
+/// as the backend reads the next task id and session id when it starts.
+///
+/// The archive is only imported when the database doesn't exist yet, as the
+/// task ids of the archive would conflict with existing tasks. Returns whether
+/// it was imported.
+pub fn import_lmdb_cache(path: &Path, archive: &Path) -> Result<bool> {
+    let path = handle_db_versioning(path)?;
+    if !is_fresh(&path) {
+        return Ok(false);
+    }
+    let database = ChecksumLayer::new(LmbdKeyValueDatabase::new(&path)?, &path);
+    if let Err(err) = cache_archive::import_cache(&database, archive) {
+        drop(database);
+        let _ = fs::remove_dir_all(&path);
+        return Err(err);
+    }
+    Ok(true)
+}
+
 pub type NoopBackingStorage = KeyValueDatabaseBackingStorage<NoopKvDb>;
 
 pub fn noop_backing_storage(_path: &Path) -> Result<NoopBackingStorage> {
@@ -42,3 +111,72 @@ pub type DefaultBackingStorage = LmdbBackingStorage;
 pub fn default_backing_storage(path: &Path) -> Result<DefaultBackingStorage> {
     lmdb_backing_storage(path)
 }
+
+/// Imports a cache archive into the [DefaultBackingStorage] at `path`, see
+/// [import_lmdb_cache].
+pub fn import_cache(path: &Path, archive: &Path) -> Result<bool> {
+    import_lmdb_cache(path, archive)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::open_lmdb_database;
+    use crate::database::{
+        key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
+        lmdb::LmbdKeyValueDatabase,
+    };
+
+    const KEY: [u8; 4] = 1u32.to_ne_bytes();
+
+    fn put(database: &impl KeyValueDatabase, value: &[u8]) {
+        let mut batch = database.write_batch().unwrap();
+        batch
+            .put(
+                KeySpace::TaskData,
+                Cow::Borrowed(&KEY),
+                Cow::Borrowed(value),
+            )
+            .unwrap();
+        batch.commit().unwrap();
+    }
+
+    fn is_corrupted(database: &impl KeyValueDatabase) -> bool {
+        let tx = database.begin_read_transaction().unwrap();
+        database.get(&tx, KeySpace::TaskData, &KEY).is_err()
+    }
+
+    #[test]
+    fn quarantines_a_database_with_a_corrupted_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        let database = open_lmdb_database(&path, &mut true).unwrap();
+        put(&database, b"data");
+        assert!(!is_corrupted(&database));
+        drop(database);
+
+        // Overwrite the value without a checksum, like a partial write.
+        put(
+            &LmbdKeyValueDatabase::new(&path).unwrap(),
+            b"partially written",
+        );
+
+        // Only the entries that are read are verified.
+        let mut fresh_db = false;
+        let database = open_lmdb_database(&path, &mut fresh_db).unwrap();
+        assert!(!fresh_db);
+        assert!(is_corrupted(&database));
+        drop(database);
+
+        let mut fresh_db = false;
+        let database = open_lmdb_database(&path, &mut fresh_db).unwrap();
+        assert!(fresh_db);
+        assert!(dir.path().join("db.corrupt").exists());
+        let tx = database.begin_read_transaction().unwrap();
+        assert!(database
+            .get(&tx, KeySpace::TaskData, &KEY)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,84 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::Result;
+use turbo_tasks::{mark_session_dependent, TurboTasks, Vc};
+use turbo_tasks_backend::{default_backing_storage, DefaultBackingStorage, TurboTasksBackend};
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+/// The value that [session_value] reads, like a file that changes between
+/// sessions.
+static VALUE: AtomicU32 = AtomicU32::new(0);
+
+#[tokio::test]
+async fn serves_persisted_outputs_while_revalidating() {
+    REGISTRATION.ensure_registered();
+    let path = PathBuf::from(concat!(env!("OUT_DIR"), "/.cache/background_revalidation"));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+
+    VALUE.store(1, Ordering::SeqCst);
+    let tt = create_turbo_tasks(&path, false);
+    assert_eq!(read(&tt).await, 10);
+    tt.stop_and_wait().await;
+
+    // Without revalidation, the read waits for the session dependent task.
+    VALUE.store(2, Ordering::SeqCst);
+    let tt = create_turbo_tasks(&path, false);
+    assert_eq!(read(&tt).await, 20);
+    tt.stop_and_wait().await;
+
+    // With revalidation, the persisted output is served first and replaced
+    // once the session dependent task was recomputed.
+    VALUE.store(3, Ordering::SeqCst);
+    let tt = create_turbo_tasks(&path, true);
+    assert_eq!(read(&tt).await, 20);
+    let mut value = read(&tt).await;
+    for _ in 0..100 {
+        if value == 30 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        value = read(&tt).await;
+    }
+    assert_eq!(value, 30);
+    tt.stop_and_wait().await;
+}
+
+fn create_turbo_tasks(
+    path: &Path,
+    background_revalidation: bool,
+) -> Arc<TurboTasks<TurboTasksBackend<DefaultBackingStorage>>> {
+    let backend = TurboTasksBackend::new(default_backing_storage(path).unwrap());
+    backend.set_background_revalidation(background_revalidation);
+    TurboTasks::new(backend)
+}
+
+async fn read(tt: &Arc<TurboTasks<TurboTasksBackend<DefaultBackingStorage>>>) -> u32 {
+    tt.run_once(async { Ok(*outer().strongly_consistent().await?) })
+        .await
+        .unwrap()
+}
+
+#[turbo_tasks::function]
+async fn outer() -> Result<Vc<u32>> {
+    Ok(Vc::cell(*session_value().await? * 10))
+}
+
+#[turbo_tasks::function]
+fn session_value() -> Vc<u32> {
+    mark_session_dependent();
+    Vc::cell(VALUE.load(Ordering::SeqCst))
+}
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/generics.rs
\ No newline at end of file
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/keyed_function.rs
\ No newline at end of file
@@ -1,8 +1,18 @@
-use turbo_tasks::{mark_session_dependent, FxIndexMap, RcStr, Vc};
+use turbo_tasks::{mark_session_dependent, RcStr, Vc};
 
-use crate::{sorted_env_vars, EnvMap, ProcessEnv, GLOBAL_ENV_LOCK};
+use crate::{
+    refresh::{read_all_tracked, read_tracked},
+    EnvMap, ProcessEnv,
+};
 
 /// Load the environment variables defined via command line.
+///
+/// Reads are dependencies of the reading task, which is invalidated when
+/// [crate::refresh_process_env] finds that the variables it read changed, or
+/// when they are changed with [crate::update_process_env]. Reads
+/// are also session dependent, so they are repeated after a restart when the
+/// cache is persisted, which only invalidates dependent tasks when the values
+/// differ from the persisted ones.
 #[turbo_tasks::value]
 pub struct CommandLineProcessEnv;
 
@@ -14,17 +24,17 @@ impl CommandLineProcessEnv {
     }
 }
 
-/// Clones the current env vars into a FxIndexMap.
-fn env_snapshot() -> FxIndexMap<RcStr, RcStr> {
-    let _lock = GLOBAL_ENV_LOCK.lock().unwrap();
-    sorted_env_vars()
-}
-
 #[turbo_tasks::value_impl]
 impl ProcessEnv for CommandLineProcessEnv {
     #[turbo_tasks::function]
     fn read_all(&self) -> Vc<EnvMap> {
         mark_session_dependent();
-        Vc::cell(env_snapshot())
+        Vc::cell(read_all_tracked())
+    }
+
+    #[turbo_tasks::function]
+    fn read(&self, name: RcStr) -> Vc<Option<RcStr>> {
+        mark_session_dependent();
+        Vc::cell(read_tracked(&name))
     }
 }
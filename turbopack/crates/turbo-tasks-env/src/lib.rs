@@ -5,6 +5,7 @@ mod command_line;
 mod custom;
 mod dotenv;
 mod filter;
+mod refresh;
 
 use std::{env, sync::Mutex};
 
@@ -12,8 +13,13 @@ use anyhow::Result;
 use turbo_tasks::{FxIndexMap, RcStr, Vc};
 
 pub use self::{
-    command_line::CommandLineProcessEnv, custom::CustomProcessEnv, dotenv::DotenvProcessEnv,
+    command_line::CommandLineProcessEnv,
+    custom::CustomProcessEnv,
+    dotenv::DotenvProcessEnv,
     filter::FilterProcessEnv,
+    refresh::{
+        poll_process_env, refresh_process_env, update_process_env, EnvChange, ProcessEnvPolling,
+    },
 };
 
 #[turbo_tasks::value(transparent)]
@@ -0,0 +1,310 @@
+use std::{
+    fmt::{Display, Formatter},
+    mem::take,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use turbo_tasks::{
+    get_invalidator, util::StaticOrArc, FxIndexMap, FxIndexSet, InvalidationReason,
+    InvalidationReasonKind, Invalidator, RcStr,
+};
+
+use crate::{sorted_env_vars, GLOBAL_ENV_LOCK};
+
+/// The env variables that tasks read with [crate::CommandLineProcessEnv], so
+/// they can be invalidated when the variables change.
+#[derive(Default)]
+struct ProcessEnvReads {
+    /// The value that tasks read of each variable, by its uppercase name, and
+    /// the tasks that read it.
+    vars: FxIndexMap<RcStr, (Option<RcStr>, FxIndexSet<Invalidator>)>,
+    /// The variables when tasks last read all of them, and the tasks that did.
+    all: Option<(FxIndexMap<RcStr, RcStr>, FxIndexSet<Invalidator>)>,
+    /// The variables pushed with [update_process_env], which take precedence
+    /// over the variables of the process. `None` removes a variable.
+    pushed: FxIndexMap<RcStr, Option<RcStr>>,
+}
+
+impl ProcessEnvReads {
+    /// The variables of the process with the pushed ones applied, sorted by
+    /// name.
+    fn env_vars(&self) -> FxIndexMap<RcStr, RcStr> {
+        let mut vars = {
+            let _lock = GLOBAL_ENV_LOCK.lock().unwrap();
+            sorted_env_vars()
+        };
+        if !self.pushed.is_empty() {
+            for (name, value) in &self.pushed {
+                match value {
+                    Some(value) => {
+                        vars.insert(name.clone(), value.clone());
+                    }
+                    None => {
+                        vars.shift_remove(name);
+                    }
+                }
+            }
+            vars.sort_keys();
+        }
+        vars
+    }
+}
+
+static READS: Mutex<Option<ProcessEnvReads>> = Mutex::new(None);
+
+/// Reads the variables of the process and invalidates the current task when
+/// they change.
+pub(crate) fn read_all_tracked() -> FxIndexMap<RcStr, RcStr> {
+    let mut reads = READS.lock().unwrap();
+    let reads = reads.get_or_insert_with(Default::default);
+    let vars = reads.env_vars();
+    let mut invalidators = match reads.all.take() {
+        Some((previous, invalidators)) if previous == vars => invalidators,
+        Some((_, invalidators)) => {
+            // Tasks that read the variables before were not invalidated yet.
+            invalidate(invalidators, "*".into());
+            FxIndexSet::default()
+        }
+        None => FxIndexSet::default(),
+    };
+    track_current_task(&mut invalidators);
+    reads.all = Some((vars.clone(), invalidators));
+    vars
+}
+
+/// Reads the variable `name` of the process, ignoring casing, and invalidates
+/// the current task when it changes.
+pub(crate) fn read_tracked(name: &str) -> Option<RcStr> {
+    let name: RcStr = name.to_uppercase().into();
+    let mut reads = READS.lock().unwrap();
+    let reads = reads.get_or_insert_with(Default::default);
+    let value = read_case_insensitive(&reads.env_vars(), &name);
+    let (previous, invalidators) = reads.vars.entry(name.clone()).or_default();
+    if *previous != value {
+        // Tasks that read the variable before were not invalidated yet.
+        invalidate(take(invalidators), name);
+        previous.clone_from(&value);
+    }
+    track_current_task(invalidators);
+    value
+}
+
+/// Adds the invalidator of the current task. Invalidators are compared by
+/// task id only, so the invalidators of turbo tasks instances that were
+/// dropped since are removed and replaced, instead of accumulating and
+/// shadowing the tasks of newer instances.
+fn track_current_task(invalidators: &mut FxIndexSet<Invalidator>) {
+    invalidators.retain(Invalidator::is_alive);
+    invalidators.replace(get_invalidator());
+}
+
+fn read_case_insensitive(vars: &FxIndexMap<RcStr, RcStr>, uppercase_name: &str) -> Option<RcStr> {
+    vars.iter()
+        .find(|(name, _)| name.to_uppercase() == uppercase_name)
+        .map(|(_, value)| value.clone())
+}
+
+/// Compares the variables of the process with the values that tasks read and
+/// invalidates the tasks whose variables changed since.
+///
+/// Env variables can't be watched, so this needs to be called when they might
+/// have changed, or periodically with [poll_process_env].
+pub fn refresh_process_env() {
+    let mut reads = READS.lock().unwrap();
+    let Some(reads) = reads.as_mut() else {
+        return;
+    };
+    refresh(reads);
+}
+
+fn refresh(reads: &mut ProcessEnvReads) {
+    let vars = reads.env_vars();
+    if let Some((previous, invalidators)) = &mut reads.all {
+        if *previous != vars {
+            invalidate(take(invalidators), "*".into());
+            reads.all = None;
+        }
+    }
+    let uppercase_vars = vars
+        .iter()
+        .map(|(name, value)| (RcStr::from(name.to_uppercase()), value))
+        .collect::<FxIndexMap<_, _>>();
+    reads.vars.retain(|name, (previous, invalidators)| {
+        if previous.as_ref() == uppercase_vars.get(name).copied() {
+            return true;
+        }
+        invalidate(take(invalidators), name.clone());
+        false
+    });
+}
+
+/// Sets (`Some`) and removes (`None`) variables, e.g. when they are pushed by
+/// a parent process, and invalidates the tasks that read them.
+///
+/// The variables of the process are not modified, as that's not thread safe.
+/// The pushed values take precedence over them for all reads of
+/// [crate::CommandLineProcessEnv] instead.
+pub fn update_process_env(changes: impl IntoIterator<Item = (RcStr, Option<RcStr>)>) {
+    let mut reads = READS.lock().unwrap();
+    let reads = reads.get_or_insert_with(Default::default);
+    reads.pushed.extend(changes);
+    refresh(reads);
+}
+
+/// Calls [refresh_process_env] every `interval` on a separate thread until the
+/// returned handle is dropped.
+#[must_use]
+pub fn poll_process_env(interval: Duration) -> ProcessEnvPolling {
+    let stopped = Arc::new(AtomicBool::new(false));
+    thread::Builder::new()
+        .name("process env polling".into())
+        .spawn({
+            let stopped = stopped.clone();
+            move || {
+                while !stopped.load(Ordering::Relaxed) {
+                    thread::sleep(interval);
+                    refresh_process_env();
+                }
+            }
+        })
+        .expect("failed to spawn the process env polling thread");
+    ProcessEnvPolling { stopped }
+}
+
+/// Stops polling the variables of the process when dropped, see
+/// [poll_process_env].
+pub struct ProcessEnvPolling {
+    stopped: Arc<AtomicBool>,
+}
+
+impl Drop for ProcessEnvPolling {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+fn invalidate(invalidators: FxIndexSet<Invalidator>, name: RcStr) {
+    for invalidator in invalidators {
+        invalidator.invalidate_with_reason(EnvChange { name: name.clone() });
+    }
+}
+
+/// Invalidation was caused by a change of an env variable of the process.
+#[derive(PartialEq, Eq, Hash)]
+pub struct EnvChange {
+    /// The uppercase name of the variable, or `*` for all variables.
+    pub name: RcStr,
+}
+
+impl InvalidationReason for EnvChange {
+    fn kind(&self) -> Option<StaticOrArc<dyn InvalidationReasonKind>> {
+        Some(StaticOrArc::Static(&ENV_CHANGE_KIND))
+    }
+}
+
+impl Display for EnvChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "env variable {} changed", self.name)
+    }
+}
+
+/// Invalidation kind for [EnvChange]
+#[derive(PartialEq, Eq, Hash)]
+struct EnvChangeKind;
+
+static ENV_CHANGE_KIND: EnvChangeKind = EnvChangeKind;
+
+impl InvalidationReasonKind for EnvChangeKind {
+    fn fmt(
+        &self,
+        reasons: &FxIndexSet<StaticOrArc<dyn InvalidationReason>>,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            "{} env variables changed ({}, ...)",
+            reasons.len(),
+            reasons[0]
+                .as_any()
+                .downcast_ref::<EnvChange>()
+                .unwrap()
+                .name
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+
+    use turbo_tasks::{RcStr, TurboTasks, Vc};
+    use turbo_tasks_memory::MemoryBackend;
+
+    use super::update_process_env;
+    use crate::{CommandLineProcessEnv, ProcessEnv};
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    async fn read(tt: &TurboTasks<MemoryBackend>, name: &str) -> Option<RcStr> {
+        let name = RcStr::from(name);
+        tt.run_once(async move {
+            let value: Vc<Option<RcStr>> = CommandLineProcessEnv::new().read(name);
+            Ok(value.strongly_consistent().await?.clone_value())
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn read_all(tt: &TurboTasks<MemoryBackend>, name: &str) -> Option<RcStr> {
+        let name = RcStr::from(name);
+        tt.run_once(async move {
+            let vars = CommandLineProcessEnv::new().read_all();
+            Ok(vars.strongly_consistent().await?.get(&name).cloned())
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn invalidates_reads_of_pushed_variables() {
+        register();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        let name = "TURBO_TASKS_ENV_TEST_PUSHED";
+        assert_eq!(read(&tt, name).await, None);
+        assert_eq!(read_all(&tt, name).await, None);
+
+        update_process_env([(name.into(), Some("1".into()))]);
+        assert_eq!(read(&tt, name).await, Some("1".into()));
+        assert_eq!(read(&tt, &name.to_lowercase()).await, Some("1".into()));
+        assert_eq!(read_all(&tt, name).await, Some("1".into()));
+
+        update_process_env([(name.into(), None)]);
+        assert_eq!(read(&tt, name).await, None);
+        assert_eq!(read_all(&tt, name).await, None);
+    }
+
+    #[tokio::test]
+    async fn invalidates_reads_of_newer_instances() {
+        register();
+        let name = "TURBO_TASKS_ENV_TEST_INSTANCES";
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        assert_eq!(read(&tt, name).await, None);
+        tt.stop_and_wait().await;
+        drop(tt);
+
+        // The read of the dropped instance must not shadow the read of the new
+        // one, even when their tasks have the same id.
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        assert_eq!(read(&tt, name).await, None);
+        update_process_env([(name.into(), Some("1".into()))]);
+        assert_eq!(read(&tt, name).await, Some("1".into()));
+    }
+}
@@ -7,6 +7,10 @@ use turbo_tasks::{mark_session_dependent, RcStr, Vc};
 use turbo_tasks_fs::FileSystemPath;
 use turbopack_core::issue::{Issue, IssueSeverity, IssueStage, OptionStyledString, StyledString};
 
+mod remote_url;
+
+pub use remote_url::{RemoteUrlResolvePlugin, RemoteUrlSource};
+
 pub fn register() {
     turbo_tasks::register();
     turbo_tasks_fs::register();
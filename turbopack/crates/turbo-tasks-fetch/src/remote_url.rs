@@ -0,0 +1,360 @@
+use std::path::Path;
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::Url;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use turbo_tasks::{FxIndexMap, RcStr, ResolvedVc, Value, ValueToString, Vc};
+use turbo_tasks_fs::{to_sys_path, File, FileContent, FileJsonContent, FileSystemPath};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    ident::AssetIdent,
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    reference_type::ReferenceType,
+    resolve::{
+        parse::Request,
+        plugin::{BeforeResolvePlugin, BeforeResolvePluginCondition},
+        RequestKey, ResolveResult, ResolveResultItem, ResolveResultOption,
+    },
+    source::Source,
+};
+
+use crate::fetch;
+
+/// The [subresource integrity] of `content` with the hash `algorithm`, or
+/// `None` if the algorithm is not supported.
+///
+/// [subresource integrity]: https://developer.mozilla.org/docs/Web/Security/Subresource_Integrity
+fn compute_integrity(algorithm: &str, content: &[u8]) -> Option<String> {
+    let digest = match algorithm {
+        "sha256" => Sha256::digest(content).to_vec(),
+        "sha384" => Sha384::digest(content).to_vec(),
+        "sha512" => Sha512::digest(content).to_vec(),
+        _ => return None,
+    };
+    Some(format!("{algorithm}-{}", STANDARD.encode(digest)))
+}
+
+fn integrity_matches(integrity: &str, content: &[u8]) -> bool {
+    integrity
+        .split_once('-')
+        .and_then(|(algorithm, _)| compute_integrity(algorithm, content))
+        .is_some_and(|computed| computed == integrity)
+}
+
+/// Whether `url` has one of the `allowed_origins`, e.g. `https://esm.sh`.
+fn is_allowed(url: &str, allowed_origins: &[RcStr]) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+    let origin = url.origin().ascii_serialization();
+    allowed_origins.iter().any(|allowed| {
+        Url::parse(allowed).is_ok_and(|allowed| allowed.origin().ascii_serialization() == origin)
+    })
+}
+
+/// The integrities of the lockfile by URL.
+#[turbo_tasks::value(transparent)]
+struct RemoteUrlIntegrities(FxIndexMap<RcStr, RcStr>);
+
+/// The integrities by URL of a lockfile, or `None` if it's not an object.
+fn parse_lockfile(lockfile: &serde_json::Value) -> Option<FxIndexMap<RcStr, RcStr>> {
+    let serde_json::Value::Object(entries) = lockfile else {
+        return None;
+    };
+    Some(
+        entries
+            .iter()
+            .filter_map(|(url, integrity)| Some((url.as_str().into(), integrity.as_str()?.into())))
+            .collect(),
+    )
+}
+
+#[turbo_tasks::function]
+async fn lockfile_integrities(lockfile: Vc<FileSystemPath>) -> Result<Vc<RemoteUrlIntegrities>> {
+    let integrities = match &*lockfile.read_json().await? {
+        FileJsonContent::Content(content) => parse_lockfile(content),
+        FileJsonContent::NotFound => Some(FxIndexMap::default()),
+        _ => None,
+    };
+    let Some(integrities) = integrities else {
+        RemoteUrlIssue {
+            path: lockfile,
+            title: "Invalid remote URL lockfile".into(),
+            message: "The lockfile needs to be a JSON object that maps URLs to their subresource \
+                      integrity, e.g. \"sha384-...\"."
+                .into(),
+        }
+        .cell()
+        .emit();
+        return Ok(Vc::cell(FxIndexMap::default()));
+    };
+    Ok(Vc::cell(integrities))
+}
+
+/// Resolves imports of remote URLs, e.g. `https://esm.sh/canvas-confetti`, to
+/// their contents, instead of leaving them to the runtime.
+///
+/// Only URLs of the allowed origins are resolved, and only when the lockfile
+/// pins their integrity. Without an explicit list, the origins of the pinned
+/// URLs are allowed. The lockfile is a JSON object that maps URLs to
+/// subresource integrity hashes, e.g. `{"https://esm.sh/x": "sha384-..."}`.
+/// Downloads are stored in the cache directory by their integrity, so pinned
+/// URLs resolve offline once they were downloaded.
+#[turbo_tasks::value]
+pub struct RemoteUrlResolvePlugin {
+    allowed_origins: Option<Vec<RcStr>>,
+    lockfile: ResolvedVc<FileSystemPath>,
+    cache_dir: ResolvedVc<FileSystemPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl RemoteUrlResolvePlugin {
+    #[turbo_tasks::function]
+    pub async fn new(
+        allowed_origins: Vec<RcStr>,
+        lockfile: Vc<FileSystemPath>,
+        cache_dir: Vc<FileSystemPath>,
+    ) -> Result<Vc<Self>> {
+        Ok(RemoteUrlResolvePlugin {
+            allowed_origins: Some(allowed_origins),
+            lockfile: lockfile.to_resolved().await?,
+            cache_dir: cache_dir.to_resolved().await?,
+        }
+        .cell())
+    }
+
+    /// Resolves the URLs of the origins that are pinned in the lockfile.
+    #[turbo_tasks::function]
+    pub async fn pinned(
+        lockfile: Vc<FileSystemPath>,
+        cache_dir: Vc<FileSystemPath>,
+    ) -> Result<Vc<Self>> {
+        Ok(RemoteUrlResolvePlugin {
+            allowed_origins: None,
+            lockfile: lockfile.to_resolved().await?,
+            cache_dir: cache_dir.to_resolved().await?,
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl BeforeResolvePlugin for RemoteUrlResolvePlugin {
+    #[turbo_tasks::function]
+    fn before_resolve_condition(&self) -> Vc<BeforeResolvePluginCondition> {
+        BeforeResolvePluginCondition::from_protocols(Vc::cell(vec!["https:".into()]))
+    }
+
+    #[turbo_tasks::function]
+    async fn before_resolve(
+        &self,
+        lookup_path: Vc<FileSystemPath>,
+        _reference_type: Value<ReferenceType>,
+        request: Vc<Request>,
+    ) -> Result<Vc<ResolveResultOption>> {
+        let Request::Uri {
+            protocol,
+            remainder,
+            ..
+        } = &*request.await?
+        else {
+            return Ok(ResolveResultOption::none());
+        };
+        let url: RcStr = format!("{protocol}{remainder}").into();
+        let integrities = lockfile_integrities(*self.lockfile).await?;
+        let allowed = match &self.allowed_origins {
+            Some(allowed_origins) => is_allowed(&url, allowed_origins),
+            None => is_allowed(&url, &integrities.keys().cloned().collect::<Vec<_>>()),
+        };
+        if !allowed {
+            return Ok(ResolveResultOption::none());
+        }
+
+        let Some(integrity) = integrities.get(&url) else {
+            RemoteUrlIssue {
+                path: lookup_path,
+                title: format!("{url} is not pinned").into(),
+                message: format!(
+                    "Remote URLs are only resolved when the lockfile {} contains their \
+                     subresource integrity.",
+                    self.lockfile.to_string().await?
+                )
+                .into(),
+            }
+            .cell()
+            .emit();
+            return Ok(ResolveResultOption::some(
+                ResolveResult::primary(ResolveResultItem::Error(Vc::cell(
+                    format!("{url} is not pinned in the lockfile").into(),
+                )))
+                .cell(),
+            ));
+        };
+
+        let source = RemoteUrlSource::new(url.clone(), integrity.clone(), *self.cache_dir)
+            .to_resolved()
+            .await?;
+        Ok(ResolveResultOption::some(
+            ResolveResult::source_with_key(RequestKey::new(url), ResolvedVc::upcast(source)).cell(),
+        ))
+    }
+}
+
+/// Reads the download of `integrity` from the cache, or `None` if it's not
+/// cached or was modified.
+///
+/// The cache is read and written outside of the task graph: a download never
+/// changes, as it's stored by its integrity, and tracking the file would
+/// invalidate the task that writes it.
+async fn read_cache(cache_file: &Path, integrity: &str) -> Option<Vec<u8>> {
+    let content = tokio::fs::read(cache_file).await.ok()?;
+    integrity_matches(integrity, &content).then_some(content)
+}
+
+async fn write_cache(cache_file: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = cache_file.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(cache_file, content).await?;
+    Ok(())
+}
+
+/// A [Source] of a remote URL. Its content is verified against the pinned
+/// integrity, both when it's downloaded and when it's read from the cache.
+#[turbo_tasks::value]
+pub struct RemoteUrlSource {
+    url: RcStr,
+    integrity: RcStr,
+    /// Where the download is cached.
+    path: ResolvedVc<FileSystemPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl RemoteUrlSource {
+    #[turbo_tasks::function]
+    pub async fn new(
+        url: RcStr,
+        integrity: RcStr,
+        cache_dir: Vc<FileSystemPath>,
+    ) -> Result<Vc<Self>> {
+        // The extension selects the module rules. URLs without a known one are
+        // usually modules, e.g. on CDNs for ES modules.
+        let extension = Url::parse(&url)
+            .ok()
+            .and_then(|url| {
+                let (_, extension) = url.path().rsplit_once('.')?;
+                matches!(extension, "js" | "mjs" | "cjs" | "json" | "css" | "wasm")
+                    .then(|| extension.to_string())
+            })
+            .unwrap_or_else(|| "js".to_string());
+        // Integrities are base64 encoded, which can contain `/`.
+        let name = integrity.replace('/', "_").replace('+', "-");
+        Ok(RemoteUrlSource {
+            url,
+            integrity,
+            path: cache_dir
+                .join(format!("{name}.{extension}").into())
+                .to_resolved()
+                .await?,
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for RemoteUrlSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        AssetIdent::from_path(*self.path).with_modifier(Vc::cell(self.url.clone()))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for RemoteUrlSource {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let cache_file = to_sys_path(*self.path).await?;
+        if let Some(cache_file) = &cache_file {
+            if let Some(content) = read_cache(cache_file, &self.integrity).await {
+                return Ok(AssetContent::file(
+                    FileContent::Content(File::from(content)).cell(),
+                ));
+            }
+        }
+
+        let response = &*fetch(Vc::cell(self.url.clone()), Vc::cell(None), Vc::cell(None)).await?;
+        let body = match response {
+            Ok(response) => response.await?.body.await?,
+            Err(err) => {
+                err.to_issue(IssueSeverity::Error.into(), *self.path).emit();
+                return Ok(AssetContent::file(FileContent::NotFound.cell()));
+            }
+        };
+        if !integrity_matches(&self.integrity, &body.0) {
+            let algorithm = self.integrity.split_once('-').map_or("", |(a, _)| a);
+            RemoteUrlIssue {
+                path: *self.path,
+                title: format!("The integrity of {} doesn't match", self.url).into(),
+                message: match compute_integrity(algorithm, &body.0) {
+                    Some(actual) => format!(
+                        "The lockfile pins {}, but the downloaded content has {actual}.",
+                        self.integrity
+                    ),
+                    None => format!(
+                        "The integrity {} is not supported, it needs to be a sha256, sha384 or \
+                         sha512 hash.",
+                        self.integrity
+                    ),
+                }
+                .into(),
+            }
+            .cell()
+            .emit();
+            return Ok(AssetContent::file(FileContent::NotFound.cell()));
+        }
+
+        if let Some(cache_file) = &cache_file {
+            // The download can still be used when it can't be cached.
+            let _ = write_cache(cache_file, &body.0).await;
+        }
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(body.0.clone())).cell(),
+        ))
+    }
+}
+
+#[turbo_tasks::value(shared)]
+struct RemoteUrlIssue {
+    path: Vc<FileSystemPath>,
+    title: RcStr,
+    message: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for RemoteUrlIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.into()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Resolve.into()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(self.title.clone()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(StyledString::Text(self.message.clone()).cell()))
+    }
+}
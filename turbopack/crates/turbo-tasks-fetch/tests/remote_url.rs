@@ -0,0 +1,182 @@
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+#![cfg(test)]
+
+use std::path::PathBuf;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha384};
+use turbo_tasks::{RcStr, Value, Vc};
+use turbo_tasks_fetch::{RemoteUrlResolvePlugin, RemoteUrlSource};
+use turbo_tasks_fs::{DiskFileSystem, FileContent, FileSystem, FileSystemPath};
+use turbo_tasks_testing::{register, run, Registration};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    reference_type::ReferenceType,
+    resolve::{parse::Request, plugin::BeforeResolvePlugin, ResolveResultItem},
+};
+
+static REGISTRATION: Registration = register!(turbo_tasks_fetch::register);
+
+fn integrity(content: &str) -> RcStr {
+    format!("sha384-{}", STANDARD.encode(Sha384::digest(content))).into()
+}
+
+/// A fresh directory for the lockfile and the cache of a test.
+fn test_dir(name: &str) -> (PathBuf, Vc<FileSystemPath>) {
+    let dir = std::env::temp_dir().join(format!(
+        "turbo-tasks-fetch-remote-url-{name}-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let root = DiskFileSystem::new(name.into(), dir.to_str().unwrap().into(), vec![]).root();
+    (dir, root)
+}
+
+async fn file_content(asset: Vc<RemoteUrlSource>) -> anyhow::Result<Option<String>> {
+    let AssetContent::File(file) = &*asset.content().await? else {
+        panic!("expected a file");
+    };
+    Ok(match &*file.await? {
+        FileContent::Content(file) => Some(file.content().to_str()?.to_string()),
+        FileContent::NotFound => None,
+    })
+}
+
+#[tokio::test]
+async fn resolves_only_pinned_urls() {
+    run(&REGISTRATION, || async {
+        let (dir, root) = test_dir("pinning");
+        std::fs::write(
+            dir.join("lock.json"),
+            format!(
+                r#"{{"https://esm.sh/pinned": "{}"}}"#,
+                integrity("export default 1")
+            ),
+        )?;
+        let plugin = RemoteUrlResolvePlugin::new(
+            vec!["https://esm.sh".into()],
+            root.join("lock.json".into()),
+            root.join("cache".into()),
+        );
+
+        let resolve = |url: &str| {
+            plugin.before_resolve(
+                root,
+                Value::new(ReferenceType::Undefined),
+                Request::parse_string(url.into()),
+            )
+        };
+
+        let pinned = resolve("https://esm.sh/pinned").await?;
+        let pinned = pinned.expect("pinned URLs are resolved").await?;
+        assert!(matches!(
+            pinned.primary.values().next(),
+            Some(ResolveResultItem::Source(_))
+        ));
+
+        let unpinned = resolve("https://esm.sh/unpinned").await?;
+        let unpinned = unpinned.expect("unpinned URLs are errors").await?;
+        assert!(matches!(
+            unpinned.primary.values().next(),
+            Some(ResolveResultItem::Error(_))
+        ));
+
+        // URLs of other origins are left to the runtime
+        assert!(resolve("https://example.com/pinned").await?.is_none());
+
+        // without a list, the origins of the pinned URLs are allowed
+        let plugin = RemoteUrlResolvePlugin::pinned(
+            root.join("lock.json".into()),
+            root.join("cache".into()),
+        );
+        let resolve = |url: &str| {
+            plugin.before_resolve(
+                root,
+                Value::new(ReferenceType::Undefined),
+                Request::parse_string(url.into()),
+            )
+        };
+        assert!(resolve("https://esm.sh/unpinned").await?.is_some());
+        assert!(resolve("https://example.com/pinned").await?.is_none());
+
+        std::fs::remove_dir_all(dir)?;
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn downloads_into_the_cache() {
+    run(&REGISTRATION, || async {
+        let (dir, root) = test_dir("cache");
+        let server = httpmock::MockServer::start();
+        let download = server.mock(|when, then| {
+            when.path("/a.js");
+            then.status(200).body("export default 1");
+        });
+        let cached = server.mock(|when, then| {
+            when.path("/b.js");
+            then.status(200).body("export default 1");
+        });
+        let cache_dir = root.join("cache".into());
+
+        // cache miss
+        let source = RemoteUrlSource::new(
+            server.url("/a.js").into(),
+            integrity("export default 1"),
+            cache_dir,
+        );
+        assert_eq!(
+            file_content(source).await?.as_deref(),
+            Some("export default 1")
+        );
+        download.assert();
+        assert_eq!(std::fs::read_dir(dir.join("cache"))?.count(), 1);
+
+        // cache hit, as the download is cached by its integrity
+        let source = RemoteUrlSource::new(
+            server.url("/b.js").into(),
+            integrity("export default 1"),
+            cache_dir,
+        );
+        assert_eq!(
+            file_content(source).await?.as_deref(),
+            Some("export default 1")
+        );
+        cached.assert_hits(0);
+
+        std::fs::remove_dir_all(dir)?;
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn rejects_integrity_mismatches() {
+    run(&REGISTRATION, || async {
+        let (dir, root) = test_dir("mismatch");
+        let server = httpmock::MockServer::start();
+        let download = server.mock(|when, then| {
+            when.path("/tampered.js");
+            then.status(200).body("export default 2");
+        });
+
+        let source = RemoteUrlSource::new(
+            server.url("/tampered.js").into(),
+            integrity("export default 1"),
+            root.join("cache".into()),
+        );
+        assert_eq!(file_content(source).await?, None);
+        download.assert();
+        // the tampered download is not cached
+        assert!(!dir.join("cache").exists());
+
+        std::fs::remove_dir_all(dir)?;
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
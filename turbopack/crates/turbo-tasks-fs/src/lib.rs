@@ -16,6 +16,7 @@ mod invalidation;
 mod invalidator_map;
 pub mod json;
 mod mutex_map;
+mod read_ahead;
 mod read_glob;
 mod retry;
 pub mod rope;
@@ -47,6 +48,7 @@ use invalidator_map::InvalidatorMap;
 use jsonc_parser::{parse_to_serde_value, ParseOptions};
 use mime::Mime;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use read_ahead::ReadAhead;
 use read_glob::read_glob;
 pub use read_glob::ReadGlobResult;
 use serde::{Deserialize, Serialize};
@@ -215,6 +217,9 @@ pub struct DiskFileSystem {
     invalidation_lock: Arc<RwLock<()>>,
     #[turbo_tasks(debug_ignore, trace_ignore)]
     watcher: Arc<DiskWatcher>,
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    #[serde(skip)]
+    read_ahead: Arc<ReadAhead>,
 }
 
 impl DiskFileSystem {
@@ -271,6 +276,7 @@ impl DiskFileSystem {
         let _span = tracing::info_span!("invalidate filesystem", path = &*self.root).entered();
         let span = tracing::Span::current();
         let handle = tokio::runtime::Handle::current();
+        self.read_ahead.invalidate();
         let invalidator_map = take(&mut *self.invalidator_map.lock().unwrap());
         let dir_invalidator_map = take(&mut *self.dir_invalidator_map.lock().unwrap());
         let iter = invalidator_map
@@ -288,6 +294,7 @@ impl DiskFileSystem {
         let _span = tracing::info_span!("invalidate filesystem", path = &*self.root).entered();
         let span = tracing::Span::current();
         let handle = tokio::runtime::Handle::current();
+        self.read_ahead.invalidate();
         let invalidator_map = take(&mut *self.invalidator_map.lock().unwrap());
         let dir_invalidator_map = take(&mut *self.dir_invalidator_map.lock().unwrap());
         let iter = invalidator_map
@@ -349,6 +356,7 @@ impl DiskFileSystem {
             invalidation_lock,
             invalidator_map,
             dir_invalidator_map,
+            self.read_ahead.clone(),
             poll_interval,
         )?;
 
@@ -359,6 +367,13 @@ impl DiskFileSystem {
         self.watcher.stop_watching();
     }
 
+    /// Enables reading `package.json` and `index.*` files in the background
+    /// when their directory is read, as resolving is likely to read them next.
+    /// This reduces the latency of resolving on slow filesystems.
+    pub fn enable_read_ahead(&self) {
+        self.read_ahead.enable();
+    }
+
     pub async fn to_sys_path(&self, fs_path: Vc<FileSystemPath>) -> Result<PathBuf> {
         // just in case there's a windows unc path prefix we remove it with `dunce`
         let path = self.root_path();
@@ -439,6 +454,7 @@ impl DiskFileSystem {
             watcher: Arc::new(DiskWatcher::new(
                 ignored_subpaths.into_iter().map(PathBuf::from).collect(),
             )),
+            read_ahead: Default::default(),
         };
 
         Ok(Self::cell(instance))
@@ -475,7 +491,7 @@ impl DiskFileSystem {
             }
         };
 
-        let entries = read_dir
+        let entries: Vec<(RcStr, InternalDirectoryEntry)> = read_dir
             .filter_map(|r| {
                 let e = match r {
                     Ok(e) => e,
@@ -503,6 +519,16 @@ impl DiskFileSystem {
             .collect::<Result<_>>()
             .with_context(|| format!("reading directory item in {}", full_path.display()))?;
 
+        if self.read_ahead.is_enabled() {
+            for (file_name, entry) in &entries {
+                if matches!(entry, InternalDirectoryEntry::File(_))
+                    && ReadAhead::is_candidate(file_name)
+                {
+                    self.read_ahead.prefetch(full_path.join(&**file_name));
+                }
+            }
+        }
+
         Ok(InternalDirectoryContent::new(entries))
     }
 }
@@ -522,6 +548,9 @@ impl FileSystem for DiskFileSystem {
         self.register_invalidator(&full_path)?;
 
         let _lock = self.lock_path(&full_path).await;
+        if let Some(file) = self.read_ahead.take(&full_path) {
+            return Ok(FileContent::new(file).cell());
+        }
         let content = match retry_future(|| File::from_path(full_path.clone()))
             .instrument(tracing::info_span!(
                 "read file",
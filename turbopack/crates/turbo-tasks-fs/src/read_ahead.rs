@@ -0,0 +1,166 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tokio::sync::Semaphore;
+
+use crate::{retry::retry_future, File};
+
+/// The number of files that are read ahead at the same time. Reading ahead
+/// is skipped when all permits are taken, so it never delays regular reads
+/// for long.
+const MAX_CONCURRENT_READS: usize = 4;
+
+/// The number of files that are kept in memory until they are read.
+const MAX_PREFETCHED_FILES: usize = 1024;
+
+/// The extensions of `index` files that are read ahead.
+const INDEX_EXTENSIONS: [&str; 7] = ["js", "mjs", "cjs", "jsx", "ts", "tsx", "json"];
+
+/// Reads the files that resolving is likely to read next, when a directory is
+/// read. Resolving reads `package.json` and `index.*` files of the directories
+/// it looks into, so reading them ahead in the background hides the latency of
+/// slow, e.g. network, filesystems.
+///
+/// Prefetched contents are discarded when changes are observed, as they might
+/// be outdated.
+pub(crate) struct ReadAhead {
+    enabled: AtomicBool,
+    /// Incremented when changes are observed, so reads that were in flight
+    /// at that time are discarded too.
+    generation: AtomicU64,
+    files: Mutex<HashMap<PathBuf, File>>,
+    semaphore: Arc<Semaphore>,
+}
+
+impl Default for ReadAhead {
+    fn default() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            generation: AtomicU64::new(0),
+            files: Default::default(),
+            semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_READS)),
+        }
+    }
+}
+
+impl ReadAhead {
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::Release);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    /// Discards all prefetched contents, as files might have changed.
+    pub fn invalidate(&self) {
+        let mut files = self.files.lock().unwrap();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        files.clear();
+    }
+
+    /// Returns true when `file_name` is likely read after its directory.
+    pub fn is_candidate(file_name: &str) -> bool {
+        file_name == "package.json"
+            || file_name
+                .strip_prefix("index.")
+                .is_some_and(|ext| INDEX_EXTENSIONS.contains(&ext))
+    }
+
+    /// Reads `path` in the background, unless too many files are already
+    /// being read or kept.
+    pub fn prefetch(self: &Arc<Self>, path: PathBuf) {
+        {
+            let files = self.files.lock().unwrap();
+            if files.len() >= MAX_PREFETCHED_FILES || files.contains_key(&path) {
+                return;
+            }
+        }
+        let Ok(permit) = self.semaphore.clone().try_acquire_owned() else {
+            return;
+        };
+        let generation = self.generation.load(Ordering::Acquire);
+        let this = self.clone();
+        tokio::spawn(async move {
+            let _permit = permit;
+            let Ok(file) = retry_future(|| File::from_path(path.clone())).await else {
+                // Errors are reported by the regular read.
+                return;
+            };
+            let mut files = this.files.lock().unwrap();
+            if this.generation.load(Ordering::Acquire) == generation {
+                files.insert(path, file);
+            }
+        });
+    }
+
+    /// Removes and returns the prefetched content of `path`.
+    pub fn take(&self, path: &Path) -> Option<File> {
+        self.files.lock().unwrap().remove(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{ReadAhead, MAX_CONCURRENT_READS};
+
+    /// Waits until the reads in flight are done.
+    async fn settle(read_ahead: &ReadAhead) {
+        let _permits = read_ahead
+            .semaphore
+            .acquire_many(MAX_CONCURRENT_READS as u32)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn reads_package_json_and_index_files_ahead() {
+        assert!(ReadAhead::is_candidate("package.json"));
+        assert!(ReadAhead::is_candidate("index.js"));
+        assert!(ReadAhead::is_candidate("index.tsx"));
+        assert!(!ReadAhead::is_candidate("index.css"));
+        assert!(!ReadAhead::is_candidate("main.js"));
+        assert!(!ReadAhead::is_candidate("package.json5"));
+    }
+
+    #[tokio::test]
+    async fn takes_prefetched_files_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.json");
+        std::fs::write(&path, "{}").unwrap();
+
+        let read_ahead = Arc::new(ReadAhead::default());
+        read_ahead.prefetch(path.clone());
+        settle(&read_ahead).await;
+
+        let file = read_ahead.take(&path).expect("the file was read ahead");
+        assert_eq!(file.content().to_str().unwrap(), "{}");
+        assert!(read_ahead.take(&path).is_none());
+    }
+
+    #[tokio::test]
+    async fn discards_prefetched_files_on_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.js");
+        std::fs::write(&path, "").unwrap();
+
+        let read_ahead = Arc::new(ReadAhead::default());
+        read_ahead.prefetch(path.clone());
+        settle(&read_ahead).await;
+        read_ahead.invalidate();
+        assert!(read_ahead.take(&path).is_none());
+
+        // Missing files are left to the regular read.
+        read_ahead.prefetch(dir.path().join("missing.js"));
+        settle(&read_ahead).await;
+        assert!(read_ahead.take(&dir.path().join("missing.js")).is_none());
+    }
+}
@@ -25,6 +25,7 @@ use crate::{
     invalidation::{WatchChange, WatchStart},
     invalidator_map::InvalidatorMap,
     path_to_key,
+    read_ahead::ReadAhead,
 };
 
 enum DiskWatcherInternal {
@@ -143,6 +144,7 @@ impl DiskWatcher {
         invalidation_lock: Arc<RwLock<()>>,
         invalidator_map: Arc<InvalidatorMap>,
         dir_invalidator_map: Arc<InvalidatorMap>,
+        read_ahead: Arc<ReadAhead>,
         poll_interval: Option<Duration>,
     ) -> Result<()> {
         let mut watcher_guard = self.watcher.lock().unwrap();
@@ -181,6 +183,7 @@ impl DiskWatcher {
         {
             let _span = tracing::info_span!("invalidate filesystem").entered();
             let span = tracing::Span::current();
+            read_ahead.invalidate();
             let invalidator_map = take(&mut *invalidator_map.lock().unwrap());
             let dir_invalidator_map = take(&mut *dir_invalidator_map.lock().unwrap());
             let iter = invalidator_map
@@ -227,6 +230,7 @@ impl DiskWatcher {
                 invalidation_lock,
                 invalidator_map,
                 dir_invalidator_map,
+                read_ahead,
             )
         });
 
@@ -252,6 +256,7 @@ impl DiskWatcher {
         invalidation_lock: Arc<RwLock<()>>,
         invalidator_map: Arc<InvalidatorMap>,
         dir_invalidator_map: Arc<InvalidatorMap>,
+        read_ahead: Arc<ReadAhead>,
     ) {
         let mut batched_invalidate_path = HashSet::new();
         let mut batched_invalidate_path_dir = HashSet::new();
@@ -417,6 +422,7 @@ impl DiskWatcher {
             }
 
             let _lock = invalidation_lock.blocking_write();
+            read_ahead.invalidate();
             {
                 let mut invalidator_map = invalidator_map.lock().unwrap();
                 invalidate_path(
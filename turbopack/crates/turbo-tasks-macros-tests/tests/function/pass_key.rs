@@ -0,0 +1,35 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(dead_code)]
+
+use turbo_tasks::{RcStr, Vc};
+
+#[turbo_tasks::value]
+struct Options {
+    verbose: bool,
+}
+
+#[turbo_tasks::function(key = path)]
+fn single_argument(path: RcStr, options: Vc<Options>) -> Vc<Options> {
+    options
+}
+
+#[turbo_tasks::function(key = (path, depth))]
+fn multiple_arguments(path: RcStr, depth: u32, options: Vc<Options>) -> Vc<Options> {
+    options
+}
+
+#[turbo_tasks::function(key = path.len() as u32, key_type = u32)]
+fn expression(path: RcStr, options: Vc<Options>) -> Vc<Options> {
+    options
+}
+
+#[turbo_tasks::value_impl]
+impl Options {
+    #[turbo_tasks::function(key = path)]
+    fn method(self: Vc<Self>, path: RcStr, options: Vc<Options>) -> Vc<Options> {
+        options
+    }
+}
+
+fn main() {}
@@ -0,0 +1,46 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(dead_code)]
+
+use turbo_tasks::{TaskInput, TransientInstance, TransientValue, Vc};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, TaskInput)]
+struct Request {
+    id: u32,
+    callback: TransientInstance<fn()>,
+}
+
+#[turbo_tasks::function(transient)]
+fn transient_instance(instance: TransientInstance<()>) -> Vc<u32> {
+    Vc::cell(0)
+}
+
+#[turbo_tasks::function(transient)]
+fn transient_value(value: TransientValue<u32>) -> Vc<u32> {
+    Vc::cell(*value)
+}
+
+#[turbo_tasks::function(transient)]
+fn nested_transient_input(request: Request) -> Vc<u32> {
+    Vc::cell(request.id)
+}
+
+#[turbo_tasks::function(transient)]
+fn persistent_inputs(value: u32) -> Vc<u32> {
+    Vc::cell(value)
+}
+
+#[turbo_tasks::value]
+struct Counter {
+    value: u32,
+}
+
+#[turbo_tasks::value_impl]
+impl Counter {
+    #[turbo_tasks::function(transient)]
+    fn add(&self, value: TransientValue<u32>) -> Vc<u32> {
+        Vc::cell(self.value + *value)
+    }
+}
+
+fn main() {}
@@ -20,6 +20,7 @@ pub use value_debug_macro::derive_value_debug;
 struct FieldAttributes {
     trace_ignore: bool,
     debug_ignore: bool,
+    redact: bool,
 }
 
 impl From<&[Attribute]> for FieldAttributes {
@@ -27,6 +28,7 @@ impl From<&[Attribute]> for FieldAttributes {
         let mut result = Self {
             trace_ignore: false,
             debug_ignore: false,
+            redact: false,
         };
 
         for attr in attrs {
@@ -47,16 +49,17 @@ impl From<&[Attribute]> for FieldAttributes {
                         match path.get_ident().map(|ident| ident.to_string()).as_deref() {
                             Some("trace_ignore") => result.trace_ignore = true,
                             Some("debug_ignore") => result.debug_ignore = true,
+                            Some("redact") => result.redact = true,
                             _ => path
                                 .span()
                                 .unwrap()
-                                .error("expected `trace_ignore` or `debug_ignore`")
+                                .error("expected `trace_ignore`, `debug_ignore` or `redact`")
                                 .emit(),
                         }
                     } else {
                         meta.span()
                             .unwrap()
-                            .error("expected `trace_ignore` or `debug_ignore`")
+                            .error("expected `trace_ignore`, `debug_ignore` or `redact`")
                             .emit();
                     }
                 }
@@ -103,6 +103,15 @@ pub fn derive_task_input(input: TokenStream) -> TokenStream {
         },
         &|_ident| quote! {false},
     );
+    let field_types: Vec<_> = match &derive_input.data {
+        syn::Data::Struct(data) => data.fields.iter().map(|field| &field.ty).collect(),
+        syn::Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter().map(|field| &field.ty))
+            .collect(),
+        syn::Data::Union(_) => Vec::new(),
+    };
     let resolve_impl = match_expansion(
         &derive_input,
         &|ident, fields| {
@@ -154,6 +163,8 @@ pub fn derive_task_input(input: TokenStream) -> TokenStream {
         where
             #(#generic_params: turbo_tasks::TaskInput,)*
         {
+            const TRANSIENT: bool = #(<#field_types as turbo_tasks::TaskInput>::TRANSIENT ||)* false;
+
             #[allow(non_snake_case)]
             #[allow(unreachable_code)] // This can occur for enums with no variants.
             fn is_resolved(&self) -> bool {
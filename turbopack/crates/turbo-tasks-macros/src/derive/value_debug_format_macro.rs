@@ -14,7 +14,9 @@ fn filter_field(field: &Field) -> bool {
 /// a given type.
 ///
 /// Fields annotated with `#[debug_ignore]` will not appear in the
-/// `ValueDebugFormat` representation of the type.
+/// `ValueDebugFormat` representation of the type. Fields annotated with
+/// `#[turbo_tasks(redact)]` appear as a hash of their value instead of the value
+/// itself.
 pub fn derive_value_debug_format(input: TokenStream) -> TokenStream {
     let mut derive_input = parse_macro_input!(input as DeriveInput);
 
@@ -32,6 +34,7 @@ pub fn derive_value_debug_format(input: TokenStream) -> TokenStream {
 
     let formatting_logic =
         match_expansion(&derive_input, &format_named, &format_unnamed, &format_unit);
+    let json_logic = match_expansion(&derive_input, &json_named, &json_unnamed, &json_unit);
 
     quote! {
         impl #impl_generics turbo_tasks::debug::ValueDebugFormat for #ident #ty_generics #where_clause {
@@ -48,22 +51,55 @@ pub fn derive_value_debug_format(input: TokenStream) -> TokenStream {
                     })
                 )
             }
+
+            fn value_debug_json<'a>(&'a self, depth: usize) -> turbo_tasks::debug::ValueDebugFormatJson<'a> {
+                turbo_tasks::debug::ValueDebugFormatJson::Async(
+                    Box::pin(async move {
+                        use turbo_tasks::debug::internal::*;
+                        use turbo_tasks::debug::ValueDebugFormat;
+                        if depth == 0 {
+                            return Ok(truncated_json(stringify!(#ident)));
+                        }
+
+                        Ok(#json_logic)
+                    })
+                )
+            }
         }
     }
     .into()
 }
 
 /// Formats a single field nested inside named or unnamed fields.
-fn format_field(value: TokenStream2) -> TokenStream2 {
+fn format_field(value: TokenStream2, redact: bool) -> TokenStream2 {
+    let future = if redact {
+        // The full value is hashed, so the hash doesn't depend on the depth.
+        quote! {
+            turbo_tasks::macro_helpers::value_debug_format_redacted_field(#value.value_debug_format(usize::MAX))
+        }
+    } else {
+        quote! {
+            turbo_tasks::macro_helpers::value_debug_format_field(#value.value_debug_format(depth.saturating_sub(1)))
+        }
+    };
     quote! {
-        turbo_tasks::macro_helpers::value_debug_format_field(#value.value_debug_format(depth.saturating_sub(1)))
+        Box::pin(#future) as FormattingFieldFuture<'_>
     }
 }
 
+fn is_redacted(field: &Field) -> bool {
+    FieldAttributes::from(field.attrs.as_slice()).redact
+}
+
 /// Formats a struct or enum variant with named fields (e.g. `struct Foo {
 /// bar: u32 }`, `Foo::Bar { baz: u32 }`).
 fn format_named(ident: TokenStream2, fields: &FieldsNamed) -> (TokenStream2, TokenStream2) {
     let (captures, fields_idents) = generate_destructuring(fields.named.iter(), &filter_field);
+    let redacted = fields
+        .named
+        .iter()
+        .filter(|field| filter_field(field))
+        .map(is_redacted);
     (
         captures,
         if fields_idents.is_empty() {
@@ -73,7 +109,11 @@ fn format_named(ident: TokenStream2, fields: &FieldsNamed) -> (TokenStream2, Tok
                 FormattingStruct::new_named(turbo_tasks::stringify_path!(#ident), vec![])
             }
         } else {
-            let fields_values = fields_idents.iter().cloned().map(format_field);
+            let fields_values = fields_idents
+                .iter()
+                .cloned()
+                .zip(redacted)
+                .map(|(value, redact)| format_field(value, redact));
             quote! {
                 FormattingStruct::new_named_async(
                     turbo_tasks::stringify_path!(#ident),
@@ -93,6 +133,11 @@ fn format_named(ident: TokenStream2, fields: &FieldsNamed) -> (TokenStream2, Tok
 /// Foo(u32)`, `Foo::Bar(u32)`).
 fn format_unnamed(ident: TokenStream2, fields: &FieldsUnnamed) -> (TokenStream2, TokenStream2) {
     let (captures, fields_idents) = generate_destructuring(fields.unnamed.iter(), &filter_field);
+    let redacted = fields
+        .unnamed
+        .iter()
+        .filter(|field| filter_field(field))
+        .map(is_redacted);
     (
         captures,
         if fields_idents.is_empty() {
@@ -102,7 +147,10 @@ fn format_unnamed(ident: TokenStream2, fields: &FieldsUnnamed) -> (TokenStream2,
                 FormattingStruct::new_unnamed(turbo_tasks::stringify_path!(#ident), vec![])
             }
         } else {
-            let fields_values = fields_idents.into_iter().map(format_field);
+            let fields_values = fields_idents
+                .into_iter()
+                .zip(redacted)
+                .map(|(value, redact)| format_field(value, redact));
             quote! {
                 FormattingStruct::new_unnamed_async(
                     turbo_tasks::stringify_path!(#ident),
@@ -124,3 +172,90 @@ fn format_unit(ident: TokenStream2) -> TokenStream2 {
         )
     }
 }
+
+/// Converts a single field nested inside named or unnamed fields to JSON.
+fn json_field(value: TokenStream2, redact: bool) -> TokenStream2 {
+    let future = if redact {
+        quote! {
+            turbo_tasks::macro_helpers::value_debug_json_redacted_field(#value.value_debug_format(usize::MAX))
+        }
+    } else {
+        quote! {
+            turbo_tasks::macro_helpers::value_debug_json_field(#value.value_debug_json(depth.saturating_sub(1)))
+        }
+    };
+    quote! {
+        Box::pin(#future) as JsonFieldFuture<'_>
+    }
+}
+
+/// Converts a struct or enum variant with named fields to JSON, see
+/// [`format_named`].
+fn json_named(ident: TokenStream2, fields: &FieldsNamed) -> (TokenStream2, TokenStream2) {
+    let (captures, fields_idents) = generate_destructuring(fields.named.iter(), &filter_field);
+    let redacted = fields
+        .named
+        .iter()
+        .filter(|field| filter_field(field))
+        .map(is_redacted);
+    (
+        captures,
+        if fields_idents.is_empty() {
+            json_unit(ident)
+        } else {
+            let fields_values = fields_idents
+                .iter()
+                .cloned()
+                .zip(redacted)
+                .map(|(value, redact)| json_field(value, redact));
+            quote! {
+                json_struct_named(
+                    turbo_tasks::stringify_path!(#ident),
+                    vec![#(
+                        (
+                            stringify!(#fields_idents),
+                            #fields_values,
+                        ),
+                    )*],
+                ).await
+            }
+        },
+    )
+}
+
+/// Converts a struct or enum variant with unnamed fields to JSON, see
+/// [`format_unnamed`].
+fn json_unnamed(ident: TokenStream2, fields: &FieldsUnnamed) -> (TokenStream2, TokenStream2) {
+    let (captures, fields_idents) = generate_destructuring(fields.unnamed.iter(), &filter_field);
+    let redacted = fields
+        .unnamed
+        .iter()
+        .filter(|field| filter_field(field))
+        .map(is_redacted);
+    (
+        captures,
+        if fields_idents.is_empty() {
+            json_unit(ident)
+        } else {
+            let fields_values = fields_idents
+                .into_iter()
+                .zip(redacted)
+                .map(|(value, redact)| json_field(value, redact));
+            quote! {
+                json_struct_unnamed(
+                    turbo_tasks::stringify_path!(#ident),
+                    vec![#(
+                        #fields_values,
+                    )*],
+                ).await
+            }
+        },
+    )
+}
+
+/// Converts a unit struct or enum variant to JSON.
+fn json_unit(ident: TokenStream2) -> TokenStream2 {
+    quote! {
+        json_struct_unit(turbo_tasks::stringify_path!(#ident))
+    }
+}
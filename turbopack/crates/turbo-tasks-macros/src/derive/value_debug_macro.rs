@@ -21,6 +21,11 @@ pub fn derive_value_debug(input: TokenStream) -> TokenStream {
             async fn dbg_depth(&self, depth: usize) -> anyhow::Result<turbo_tasks::Vc<turbo_tasks::debug::ValueDebugString>> {
                 turbo_tasks::debug::ValueDebugFormat::value_debug_format(self, depth).try_to_value_debug_string().await
             }
+
+            #[turbo_tasks::function]
+            async fn dbg_json(&self, depth: usize) -> anyhow::Result<turbo_tasks::Vc<turbo_tasks::debug::ValueDebugJson>> {
+                turbo_tasks::debug::ValueDebugFormat::value_debug_json(self, depth).try_to_value_debug_json().await
+            }
         }
     }
     .into()
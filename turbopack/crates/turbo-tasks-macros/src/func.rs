@@ -1,4 +1,4 @@
-use std::{borrow::Cow, collections::HashSet};
+use std::{borrow::Cow, collections::HashSet, time::Duration};
 
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, quote_spanned, ToTokens};
@@ -10,9 +10,10 @@ use syn::{
     spanned::Spanned,
     token::Paren,
     visit_mut::VisitMut,
-    AngleBracketedGenericArguments, Block, Expr, ExprBlock, ExprPath, FnArg, GenericArgument,
-    Local, Meta, Pat, PatIdent, PatType, Path, PathArguments, PathSegment, Receiver, ReturnType,
-    Signature, Stmt, Token, Type, TypeGroup, TypePath, TypeTuple,
+    AngleBracketedGenericArguments, Block, Expr, ExprBlock, ExprParen, ExprPath, ExprTuple, FnArg,
+    GenericArgument, Lit, Local, Meta, MetaNameValue, Pat, PatIdent, PatType, Path, PathArguments,
+    PathSegment, Receiver, ReturnType, Signature, Stmt, Token, Type, TypeGroup, TypePath,
+    TypeTuple,
 };
 
 #[derive(Debug)]
@@ -32,6 +33,18 @@ pub struct TurboFn<'a> {
     resolved: Option<Span>,
     /// Should this function use `TaskPersistence::LocalCells`?
     local_cells: bool,
+    /// Does this function accept transient inputs, see [`FunctionArguments::transient`]?
+    transient: bool,
+    /// Derives the cache key from the arguments, see [`FunctionArguments::key`].
+    key: Option<FunctionKey>,
+}
+
+#[derive(Debug)]
+struct FunctionKey {
+    expr: Expr,
+    ty: Type,
+    /// The name of the generated type implementing `TaskInputKey`.
+    marker_ident: Ident,
 }
 
 #[derive(Debug)]
@@ -260,6 +273,57 @@ impl TurboFn<'_> {
         let output = return_type_to_type(&orig_signature.output);
 
         let orig_ident = &orig_signature.ident;
+
+        let key = if let Some(key_expr) = args.key {
+            match definition_context {
+                DefinitionContext::NakedFn | DefinitionContext::ValueInherentImpl => {}
+                DefinitionContext::ValueTraitImpl | DefinitionContext::ValueTrait => {
+                    key_expr
+                        .span()
+                        .unwrap()
+                        .error(format!(
+                            "{} do not support a custom key",
+                            definition_context.function_type(),
+                        ))
+                        .emit();
+                    return None;
+                }
+            }
+            let ty = match args.key_type {
+                Some(ty) => ty,
+                None => key_type_from_inputs(&key_expr, &inputs)?,
+            };
+            if references_self(&key_expr) {
+                key_expr
+                    .span()
+                    .unwrap()
+                    .error(
+                        "the key can only be derived from the arguments, `self` is always part of \
+                         the key",
+                    )
+                    .emit();
+                return None;
+            }
+            Some(FunctionKey {
+                expr: key_expr,
+                ty,
+                marker_ident: Ident::new(
+                    &format!("{orig_ident}_turbo_tasks_function_key"),
+                    orig_ident.span(),
+                ),
+            })
+        } else {
+            if let Some(key_type) = args.key_type {
+                key_type
+                    .span()
+                    .unwrap()
+                    .error("`key_type` requires `key`")
+                    .emit();
+                return None;
+            }
+            None
+        };
+
         let inline_ident = Ident::new(
             // Hygiene: This should use `.resolved_at(Span::def_site())`, but that's unstable, so
             // instead we just pick a long, unique name
@@ -275,10 +339,81 @@ impl TurboFn<'_> {
             inputs,
             resolved: args.resolved,
             local_cells: args.local_cells.is_some(),
+            transient: args.transient.is_some(),
+            key,
             inline_ident,
         })
     }
 
+    /// Prefixes the name of the generated key type, which is defined next to
+    /// the native function. Needed when several types define a function with
+    /// the same name in one module.
+    pub fn with_key_marker_prefix(mut self, prefix: &Ident) -> Self {
+        if let Some(key) = &mut self.key {
+            key.marker_ident = Ident::new(
+                &format!("{prefix}_{}", key.marker_ident),
+                key.marker_ident.span(),
+            );
+        }
+        self
+    }
+
+    /// The definition of the type that derives the cache key from the
+    /// arguments, if a custom key was specified.
+    pub fn key_definition(&self) -> TokenStream {
+        let Some(FunctionKey {
+            expr,
+            ty,
+            marker_ident,
+        }) = &self.key
+        else {
+            return quote! {};
+        };
+        let input_idents = self.input_idents();
+        let input_types = self.exposed_input_types();
+        quote! {
+            #[doc(hidden)]
+            #[allow(non_camel_case_types)]
+            struct #marker_ident;
+
+            impl turbo_tasks::macro_helpers::TaskInputKey<(#(#input_types,)*)> for #marker_ident {
+                type Key = #ty;
+
+                #[allow(unused_variables)]
+                fn key(inputs: &(#(#input_types,)*)) -> #ty {
+                    let (#(#input_idents,)*) = std::clone::Clone::clone(inputs);
+                    #expr
+                }
+            }
+        }
+    }
+
+    /// The argument types of the exposed function.
+    fn exposed_input_types(&self) -> Vec<Type> {
+        self.inputs
+            .iter()
+            .map(|input| expand_task_input_type(&input.ty).into_owned())
+            .collect()
+    }
+
+    /// Wraps the tuple of arguments in a `KeyedTaskInput` if a custom key was
+    /// specified.
+    fn boxed_inputs(&self) -> TokenStream {
+        let inputs = self.input_idents();
+        match &self.key {
+            Some(FunctionKey { marker_ident, .. }) => quote! {
+                std::boxed::Box::new((
+                    turbo_tasks::macro_helpers::KeyedTaskInput::<#marker_ident, _>::new(
+                        (#(#inputs,)*)
+                    ),
+                ))
+            },
+            None => quote! {
+                std::boxed::Box::new((#(#inputs,)*))
+            },
+        }
+    }
+
     /// The signature of the exposed function. This is the original signature
     /// converted to a standard turbo_tasks function signature.
     pub fn signature(&self) -> Signature {
@@ -414,7 +549,40 @@ impl TurboFn<'_> {
                 }
             })
             .unzip();
-        let transform_stmts: Vec<Stmt> = transform_stmts.into_iter().flatten().collect();
+        let mut transform_stmts: Vec<Stmt> = transform_stmts.into_iter().flatten().collect();
+
+        let inputs = if let Some(FunctionKey { marker_ident, .. }) = &self.key {
+            // All arguments except for `self` are passed as a single `KeyedTaskInput`, which is
+            // unpacked again before running the original function body.
+            let mut inputs = inputs.into_iter();
+            let this = if self.this.is_some() {
+                inputs.next()
+            } else {
+                None
+            };
+            let (pats, types): (Vec<_>, Vec<_>) = inputs
+                .map(|arg| match arg {
+                    FnArg::Typed(PatType { pat, ty, .. }) => (pat, ty),
+                    FnArg::Receiver(_) => unreachable!("only the first argument can be self"),
+                })
+                .unzip();
+            let keyed_inputs = Ident::new("turbo_tasks_keyed_inputs", Span::mixed_site());
+            transform_stmts.insert(
+                0,
+                parse_quote! {
+                    let (#(#pats,)*) = #keyed_inputs.into_inputs();
+                },
+            );
+            let keyed_arg: FnArg = parse_quote! {
+                #keyed_inputs: turbo_tasks::macro_helpers::KeyedTaskInput<
+                    #marker_ident,
+                    (#(#types,)*)
+                >
+            };
+            this.into_iter().chain(std::iter::once(keyed_arg)).collect()
+        } else {
+            inputs
+        };
 
         let inline_signature = Signature {
             ident: self.inline_ident.clone(),
@@ -509,6 +677,23 @@ impl TurboFn<'_> {
         }
     }
 
+    /// Checks at compile time that no input is always transient, unless the
+    /// function accepts transient inputs.
+    fn get_input_assertions(&self) -> TokenStream {
+        if self.transient {
+            return quote! {};
+        }
+        let assertions = self.inputs.iter().map(|Input { ty, .. }| {
+            quote_spanned! {
+                ty.span() =>
+                const { turbo_tasks::macro_helpers::assert_not_transient_input::<#ty>() };
+            }
+        });
+        quote! {
+            #(#assertions)*
+        }
+    }
+
     /// The block of the exposed function for a dynamic dispatch call to the
     /// given trait.
     pub fn dynamic_block(&self, trait_type_id_ident: &Ident) -> Block {
@@ -528,13 +713,16 @@ impl TurboFn<'_> {
         parse_quote! {
             {
                 #assertions
+                static TRAIT_METHOD_CACHE: turbo_tasks::macro_helpers::TraitMethodCache =
+                    turbo_tasks::macro_helpers::TraitMethodCache::new();
                 let inputs = std::boxed::Box::new((#(#inputs,)*));
                 let this = #converted_this;
                 let persistence = #persistence;
                 <#output as turbo_tasks::task::TaskOutput>::try_from_raw_vc(
-                    turbo_tasks::trait_call(
+                    turbo_tasks::trait_call_cached(
+                        &TRAIT_METHOD_CACHE,
                         *#trait_type_id_ident,
-                        std::borrow::Cow::Borrowed(stringify!(#ident)),
+                        stringify!(#ident),
                         this,
                         inputs as std::boxed::Box<dyn turbo_tasks::MagicAny>,
                         persistence,
@@ -548,14 +736,16 @@ impl TurboFn<'_> {
     /// given native function.
     pub fn static_block(&self, native_function_id_ident: &Ident) -> Block {
         let output = &self.output;
-        let inputs = self.input_idents();
+        let boxed_inputs = self.boxed_inputs();
         let assertions = self.get_assertions();
+        let input_assertions = self.get_input_assertions();
         if let Some(converted_this) = self.converted_this() {
             let persistence = self.persistence_with_this();
             parse_quote! {
                 {
                     #assertions
-                    let inputs = std::boxed::Box::new((#(#inputs,)*));
+                    #input_assertions
+                    let inputs = #boxed_inputs;
                     let this = #converted_this;
                     let persistence = #persistence;
                     <#output as turbo_tasks::task::TaskOutput>::try_from_raw_vc(
@@ -573,7 +763,8 @@ impl TurboFn<'_> {
             parse_quote! {
                 {
                     #assertions
-                    let inputs = std::boxed::Box::new((#(#inputs,)*));
+                    #input_assertions
+                    let inputs = #boxed_inputs;
                     let persistence = #persistence;
                     <#output as turbo_tasks::task::TaskOutput>::try_from_raw_vc(
                         turbo_tasks::dynamic_call(
@@ -662,13 +853,54 @@ pub struct FunctionArguments {
     ///
     /// Setting this option will also set [`Self::resolved`] to the same span.
     pub local_cells: Option<Span>,
+    /// An expression that derives the cache key from the arguments, e.g. `key = path` or
+    /// `key = (path, options.mode)`. Calls with the same key share a single task, which runs with
+    /// the full arguments of the first call.
+    ///
+    /// The expression is evaluated on owned clones of the arguments. The type of the key is
+    /// inferred when the expression is an argument or a tuple of arguments, otherwise it must be
+    /// given as `key_type`.
+    key: Option<Expr>,
+    key_type: Option<Type>,
+    /// Allows inputs that are always transient, like `TransientInstance` and `TransientValue`.
+    /// Without it, passing them is a compile error, as tasks with transient inputs are never
+    /// persisted, which would silently disable caching for the function.
+    ///
+    /// Results of such a function are transient too. Use `Vc::to_persistent` where they are
+    /// expected to be persistent again.
+    transient: Option<Span>,
+    /// Invalidates the result of a task after this duration, e.g. `ttl = "1h"`, for computations
+    /// that depend on time, like fetching metadata that is updated remotely.
+    pub ttl: Option<Duration>,
 }
 
 impl Parse for FunctionArguments {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut parsed_args = FunctionArguments::default();
-        let punctuated: Punctuated<Meta, Token![,]> = input.parse_terminated(Meta::parse)?;
-        for meta in punctuated {
+        while !input.is_empty() {
+            // `key` and `key_type` take arbitrary expressions and types, which can't be parsed as
+            // `Meta`.
+            if input.peek(syn::Ident) && input.peek2(Token![=]) && !input.peek2(Token![==]) {
+                let fork = input.fork();
+                let ident: Ident = fork.parse()?;
+                if ident == "key" || ident == "key_type" {
+                    input.parse::<Ident>()?;
+                    input.parse::<Token![=]>()?;
+                    if ident == "key" {
+                        parsed_args.key = Some(input.parse()?);
+                    } else {
+                        parsed_args.key_type = Some(input.parse()?);
+                    }
+                    if !input.is_empty() {
+                        input.parse::<Token![,]>()?;
+                    }
+                    continue;
+                }
+            }
+            let meta: Meta = input.parse()?;
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
             match (
                 meta.path()
                     .get_ident()
@@ -691,11 +923,29 @@ impl Parse for FunctionArguments {
                     parsed_args.local_cells = span;
                     parsed_args.resolved = span;
                 }
+                ("transient", Meta::Path(_)) => {
+                    parsed_args.transient = Some(meta.span());
+                }
+                (
+                    "ttl",
+                    Meta::NameValue(MetaNameValue {
+                        lit: Lit::Str(str), ..
+                    }),
+                ) => {
+                    let ttl = parse_duration(&str.value()).ok_or_else(|| {
+                        syn::Error::new_spanned(
+                            str,
+                            "expected a positive duration with a unit, e.g. \"30s\", \"10m\" or \
+                             \"1h\"",
+                        )
+                    })?;
+                    parsed_args.ttl = Some(ttl);
+                }
                 (_, meta) => {
                     return Err(syn::Error::new_spanned(
                         meta,
                         "unexpected token, expected one of: \"fs\", \"network\", \"resolved\", \
-                         \"local_cells\"",
+                         \"local_cells\", \"transient\", \"key\", \"key_type\", \"ttl\"",
                     ))
                 }
             }
@@ -704,6 +954,79 @@ impl Parse for FunctionArguments {
     }
 }
 
+/// Parses a duration like `500ms`, `30s`, `10m`, `1h` or `1d`.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let unit_start = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(unit_start);
+    let amount: u64 = amount.parse().ok().filter(|&amount| amount > 0)?;
+    let millis_per_unit = match unit.trim() {
+        "ms" => 1,
+        "s" => 1000,
+        "m" => 60 * 1000,
+        "h" => 60 * 60 * 1000,
+        "d" => 24 * 60 * 60 * 1000,
+        _ => return None,
+    };
+    Some(Duration::from_millis(amount.checked_mul(millis_per_unit)?))
+}
+
+/// Infers the type of a key expression that is an argument or a tuple of
+/// arguments.
+fn key_type_from_inputs(key: &Expr, inputs: &[Input]) -> Option<Type> {
+    let input_type = |expr: &Expr| {
+        let Expr::Path(ExprPath {
+            qself: None, path, ..
+        }) = expr
+        else {
+            return None;
+        };
+        let ident = path.get_ident()?;
+        inputs
+            .iter()
+            .find(|input| &input.ident == ident)
+            .map(|input| expand_task_input_type(&input.ty).into_owned())
+    };
+    let ty = match key {
+        Expr::Tuple(ExprTuple { elems, .. }) => elems
+            .iter()
+            .map(input_type)
+            .collect::<Option<Vec<_>>>()
+            .map(|types| parse_quote! { (#(#types,)*) }),
+        Expr::Paren(ExprParen { expr, .. }) => input_type(expr),
+        expr => input_type(expr),
+    };
+    if ty.is_none() {
+        key.span()
+            .unwrap()
+            .error(
+                "the type of the key can only be inferred if the key is an argument or a tuple of \
+                 arguments, specify it with `key_type = ...`",
+            )
+            .emit();
+    }
+    ty
+}
+
+/// Checks whether an expression refers to `self`.
+fn references_self(expr: &Expr) -> bool {
+    struct FindSelfVisitMut {
+        found: bool,
+    }
+
+    impl VisitMut for FindSelfVisitMut {
+        fn visit_ident_mut(&mut self, ident: &mut Ident) {
+            if ident == "self" {
+                self.found = true;
+            }
+        }
+    }
+
+    let mut visitor = FindSelfVisitMut { found: false };
+    visitor.visit_expr_mut(&mut expr.clone());
+    visitor.found
+}
+
 fn return_type_to_type(return_type: &ReturnType) -> Type {
     match return_type {
         ReturnType::Default => parse_quote! { () },
@@ -961,6 +1284,7 @@ pub struct NativeFn {
     function_path: ExprPath,
     is_method: bool,
     local_cells: bool,
+    ttl: Option<Duration>,
 }
 
 impl NativeFn {
@@ -969,12 +1293,14 @@ impl NativeFn {
         function_path: &ExprPath,
         is_method: bool,
         local_cells: bool,
+        ttl: Option<Duration>,
     ) -> NativeFn {
         NativeFn {
             function_path_string: function_path_string.to_owned(),
             function_path: function_path.clone(),
             is_method,
             local_cells,
+            ttl,
         }
     }
 
@@ -988,8 +1314,17 @@ impl NativeFn {
             function_path,
             is_method,
             local_cells,
+            ttl,
         } = self;
 
+        let ttl = match ttl {
+            Some(ttl) => {
+                let millis = ttl.as_millis() as u64;
+                quote! { Some(::std::time::Duration::from_millis(#millis)) }
+            }
+            None => quote! { None },
+        };
+
         let constructor = if *is_method {
             quote! { new_method }
         } else {
@@ -1003,6 +1338,7 @@ impl NativeFn {
                     #function_path_string.to_owned(),
                     turbo_tasks::FunctionMeta {
                         local_cells: #local_cells,
+                        ttl: #ttl,
                     },
                     #function_path,
                 )
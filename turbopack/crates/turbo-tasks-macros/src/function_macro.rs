@@ -24,6 +24,33 @@ use crate::func::{DefinitionContext, FunctionArguments, NativeFn, TurboFn};
 ///     // access filesystem
 /// }
 /// ```
+///
+/// Only a part of the arguments can be used as the cache key. Calls with the
+/// same key share a single task, which runs with the arguments of the first
+/// call:
+///
+/// ```rust
+/// use turbo_tasks::{RcStr, Vc};
+///
+/// #[turbo_tasks::function(key = path)]
+/// async fn my_task(path: RcStr, verbose_options: Vc<Options>) -> Vc<usize> {
+///     // ...
+/// }
+/// ```
+///
+/// Results of computations that depend on time can expire, which invalidates
+/// the task after the duration. Results that expire while the engine is idle
+/// are invalidated once it becomes busy again. These tasks are session
+/// dependent, so results restored from a persistent cache are recomputed.
+///
+/// ```rust
+/// use turbo_tasks::{RcStr, Vc};
+///
+/// #[turbo_tasks::function(network, ttl = "1h")]
+/// async fn font_metadata(family: RcStr) -> Vc<FontMetadata> {
+///     // fetch the metadata
+/// }
+/// ```
 pub fn function(args: TokenStream, input: TokenStream) -> TokenStream {
     let mut errors = Vec::new();
 
@@ -38,6 +65,7 @@ pub fn function(args: TokenStream, input: TokenStream) -> TokenStream {
         .inspect_err(|err| errors.push(err.to_compile_error()))
         .unwrap_or_default();
     let local_cells = args.local_cells.is_some();
+    let ttl = args.ttl;
 
     let Some(turbo_fn) = TurboFn::new(&sig, DefinitionContext::NakedFn, args) else {
         return quote! {
@@ -56,6 +84,7 @@ pub fn function(args: TokenStream, input: TokenStream) -> TokenStream {
         &parse_quote! { #inline_function_ident },
         turbo_fn.is_method(),
         local_cells,
+        ttl,
     );
     let native_function_ident = get_native_function_ident(ident);
     let native_function_ty = native_fn.ty();
@@ -67,6 +96,7 @@ pub fn function(args: TokenStream, input: TokenStream) -> TokenStream {
 
     let exposed_signature = turbo_fn.signature();
     let exposed_block = turbo_fn.static_block(&native_function_id_ident);
+    let key_definition = turbo_fn.key_definition();
 
     quote! {
         #(#attrs)*
@@ -82,6 +112,8 @@ pub fn function(args: TokenStream, input: TokenStream) -> TokenStream {
         #[doc(hidden)]
         pub(crate) static #native_function_id_ident: #native_function_id_ty = #native_function_id_def;
 
+        #key_definition
+
         #(#errors)*
     }
     .into()
@@ -83,6 +83,7 @@ pub fn generic_type(input: TokenStream) -> TokenStream {
 
         impl #impl_generics Vc<#ty> #where_clause {
             /// Converts this `Vc` to a generic representation.
+            #[allow(dead_code)]
             fn to_repr(vc: Self) -> Vc<#repr> {
                 unsafe {
                     turbo_tasks::Vc::from_raw(Vc::into_raw(vc))
@@ -94,6 +95,7 @@ pub fn generic_type(input: TokenStream) -> TokenStream {
             /// # Safety
             ///
             /// The caller must ensure that the `repr` is a valid representation of this `Vc`.
+            #[allow(dead_code)]
             unsafe fn from_repr(vc: Vc<#repr>) -> Self {
                 unsafe {
                     turbo_tasks::Vc::from_raw(Vc::into_raw(vc))
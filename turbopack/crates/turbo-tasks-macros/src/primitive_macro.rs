@@ -40,6 +40,12 @@ pub fn primitive(input: TokenStream) -> TokenStream {
                 use turbo_tasks::debug::ValueDebugFormat;
                 self.value_debug_format(depth).try_to_value_debug_string().await
             }
+
+            #[turbo_tasks::function]
+            async fn dbg_json(&self, depth: usize) -> anyhow::Result<turbo_tasks::Vc<turbo_tasks::debug::ValueDebugJson>> {
+                use turbo_tasks::debug::ValueDebugFormat;
+                self.value_debug_json(depth).try_to_value_debug_json().await
+            }
         }
     };
 
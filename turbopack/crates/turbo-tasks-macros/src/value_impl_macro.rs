@@ -120,6 +120,7 @@ pub fn value_impl(args: TokenStream, input: TokenStream) -> TokenStream {
                     .inspect_err(|err| errors.push(err.to_compile_error()))
                     .unwrap_or_default();
                 let local_cells = func_args.local_cells.is_some();
+                let ttl = func_args.ttl;
 
                 let Some(turbo_fn) =
                     TurboFn::new(sig, DefinitionContext::ValueInherentImpl, func_args)
@@ -128,6 +129,7 @@ pub fn value_impl(args: TokenStream, input: TokenStream) -> TokenStream {
                         // An error occurred while parsing the function signature.
                     };
                 };
+                let turbo_fn = turbo_fn.with_key_marker_prefix(ty_ident);
                 let inline_function_ident = turbo_fn.inline_ident();
                 let (inline_signature, inline_block) = turbo_fn.inline_signature_and_block(block);
 
@@ -136,6 +138,7 @@ pub fn value_impl(args: TokenStream, input: TokenStream) -> TokenStream {
                     &parse_quote! { <#ty>::#inline_function_ident },
                     turbo_fn.is_method(),
                     local_cells,
+                    ttl,
                 );
 
                 let native_function_ident = get_inherent_impl_function_ident(ty_ident, ident);
@@ -149,6 +152,7 @@ pub fn value_impl(args: TokenStream, input: TokenStream) -> TokenStream {
 
                 let turbo_signature = turbo_fn.signature();
                 let turbo_block = turbo_fn.static_block(&native_function_id_ident);
+                let key_definition = turbo_fn.key_definition();
                 exposed_impl_items.push(quote! {
                     #(#attrs)*
                     #vis #turbo_signature #turbo_block
@@ -177,6 +181,8 @@ pub fn value_impl(args: TokenStream, input: TokenStream) -> TokenStream {
                     pub(crate) static #native_function_ident: #native_function_ty = <#ty>::#native_function_ident;
                     #[doc(hidden)]
                     pub(crate) static #native_function_id_ident: #native_function_id_ty = <#ty>::#native_function_id_ident;
+
+                    #key_definition
                 })
             }
         }
@@ -221,6 +227,7 @@ pub fn value_impl(args: TokenStream, input: TokenStream) -> TokenStream {
                     .inspect_err(|err| errors.push(err.to_compile_error()))
                     .unwrap_or_default();
                 let local_cells = func_args.local_cells.is_some();
+                let ttl = func_args.ttl;
 
                 let Some(turbo_fn) =
                     TurboFn::new(sig, DefinitionContext::ValueTraitImpl, func_args)
@@ -248,6 +255,7 @@ pub fn value_impl(args: TokenStream, input: TokenStream) -> TokenStream {
                     },
                     turbo_fn.is_method(),
                     local_cells,
+                    ttl,
                 );
 
                 let native_function_ident =
@@ -430,6 +430,12 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
                     use turbo_tasks::debug::ValueDebugFormat;
                     (&self.0).value_debug_format(depth).try_to_value_debug_string().await
                 }
+
+                #[turbo_tasks::function]
+                async fn dbg_json(&self, depth: usize) -> anyhow::Result<turbo_tasks::Vc<turbo_tasks::debug::ValueDebugJson>> {
+                    use turbo_tasks::debug::ValueDebugFormat;
+                    (&self.0).value_debug_json(depth).try_to_value_debug_json().await
+                }
             }
         }
     } else {
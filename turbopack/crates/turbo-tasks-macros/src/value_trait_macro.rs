@@ -84,6 +84,30 @@ pub fn value_trait(args: TokenStream, input: TokenStream) -> TokenStream {
 
         let ident = &sig.ident;
 
+        // The default implementation is registered without reading the function arguments, so a
+        // `ttl` would be silently ignored.
+        for attr in attrs {
+            if !attr
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "function")
+            {
+                continue;
+            }
+            if let Ok(args) = attr.parse_args::<FunctionArguments>() {
+                if args.ttl.is_some() {
+                    attr.span()
+                        .unwrap()
+                        .error(
+                            "`ttl` is not supported on value trait methods, set it on the \
+                             `#[turbo_tasks::value_impl]` implementations instead",
+                        )
+                        .emit();
+                }
+            }
+        }
+
         // Value trait method declarations don't have `#[turbo_tasks::function]`
         // annotations on them, though their `impl`s do. It may make sense to require it
         // in the future when defining a default implementation.
@@ -122,6 +146,7 @@ pub fn value_trait(args: TokenStream, input: TokenStream) -> TokenStream {
                 //   argument.
                 // - This only makes sense when a default implementation is present.
                 false,
+                None,
             );
 
             let native_function_ident = get_trait_default_impl_function_ident(trait_ident, ident);
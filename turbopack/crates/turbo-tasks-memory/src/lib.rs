@@ -17,4 +17,4 @@ mod task;
 mod task_statistics;
 
 pub use memory_backend::MemoryBackend;
-pub use task_statistics::{TaskStatistics, TaskStatisticsApi};
+pub use task_statistics::{TaskFunctionOutputSize, TaskStatistics, TaskStatisticsApi};
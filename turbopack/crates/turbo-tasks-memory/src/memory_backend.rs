@@ -1,4 +1,5 @@
 use std::{
+    backtrace::Backtrace,
     borrow::{Borrow, Cow},
     future::Future,
     hash::{BuildHasher, BuildHasherDefault, Hash},
@@ -14,13 +15,14 @@ use std::{
 use anyhow::{anyhow, bail, Result};
 use auto_hash_map::AutoMap;
 use dashmap::{mapref::entry::Entry, DashMap};
-use rustc_hash::FxHasher;
+use rustc_hash::{FxHashSet, FxHasher};
 use tracing::trace_span;
 use turbo_prehash::{BuildHasherExt, PassThroughHash, PreHashed};
 use turbo_tasks::{
     backend::{
-        Backend, BackendJobId, CachedTaskType, CellContent, TaskCollectiblesMap, TaskExecutionSpec,
-        TransientTaskType, TypedCellContent,
+        Backend, BackendJobId, CachedTaskType, CellContent, LeakedTask, PendingTaskState,
+        PendingTaskTree, TaskCollectiblesMap, TaskExecutionSpec, TransientTaskType,
+        TypedCellContent,
     },
     event::EventListener,
     util::{IdFactoryWithReuse, NoMoveVec},
@@ -63,6 +65,7 @@ pub struct MemoryBackend {
     idle_gc_active: AtomicBool,
     task_statistics: TaskStatisticsApi,
     pub(crate) print_task_invalidation: bool,
+    task_creation_backtraces: Option<DashMap<TaskId, Backtrace>>,
 }
 
 impl Default for MemoryBackend {
@@ -90,6 +93,7 @@ impl MemoryBackend {
             idle_gc_active: AtomicBool::new(false),
             task_statistics: TaskStatisticsApi::default(),
             print_task_invalidation: false,
+            task_creation_backtraces: None,
         }
     }
 
@@ -101,6 +105,20 @@ impl MemoryBackend {
         self.print_task_invalidation = value;
     }
 
+    /// A debug feature that captures a backtrace whenever a task is created.
+    /// These are reported by [`Backend::leak_check`] for leaked tasks.
+    pub fn track_task_creation(&mut self, value: bool) {
+        self.task_creation_backtraces = value.then(DashMap::new);
+    }
+
+    fn task_created(&self, task: TaskId) {
+        if let Some(backtraces) = &self.task_creation_backtraces {
+            backtraces
+                .entry(task)
+                .or_insert_with(Backtrace::force_capture);
+        }
+    }
+
     fn connect_task_child(
         &self,
         parent: TaskId,
@@ -450,6 +468,76 @@ impl Backend for MemoryBackend {
         self.with_task(task, |task| task.get_description())
     }
 
+    fn get_task_summary(&self, task: TaskId) -> String {
+        self.with_task(task, |task| task.get_summary())
+    }
+
+    fn pending_task_tree(
+        &self,
+        task: TaskId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) -> Option<PendingTaskTree> {
+        PendingTaskTree::build(
+            task,
+            |task| self.with_task(task, |task| task.pending_state_and_children()),
+            |task| self.get_task_description(task),
+        )
+    }
+
+    fn compact_subgraph(
+        &self,
+        task: TaskId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) -> usize {
+        let mut compacted = 0;
+        let mut visited = FxHashSet::default();
+        visited.insert(task);
+        let mut queue = vec![task];
+        while let Some(task) = queue.pop() {
+            let (done, children) = self.with_task(task, |task| task.compact());
+            if done {
+                compacted += 1;
+            }
+            queue.extend(children.into_iter().filter(|child| visited.insert(*child)));
+        }
+        compacted
+    }
+
+    fn leak_check(
+        &self,
+        turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) -> Option<Vec<LeakedTask>> {
+        // Unloading a task disconnects its children, which might allow to unload
+        // them in the next round.
+        loop {
+            let mut unloaded = false;
+            self.with_all_cached_tasks(|task| {
+                unloaded |= self.with_task(task, |task| task.unload_if_inactive(self, turbo_tasks));
+            });
+            if !unloaded {
+                break;
+            }
+        }
+        let mut leaked = Vec::new();
+        self.with_all_cached_tasks(|id| {
+            self.with_task(id, |task| {
+                if let Some(cells) = task.loaded_cells() {
+                    leaked.push(LeakedTask {
+                        task: id,
+                        description: task.get_description(),
+                        cells,
+                        creation_backtrace: self
+                            .task_creation_backtraces
+                            .as_ref()
+                            .and_then(|backtraces| backtraces.get(&id))
+                            .map(|backtrace| backtrace.to_string()),
+                    });
+                }
+            })
+        });
+        Some(leaked)
+    }
+
     type TaskState = TaskState;
     fn new_task_state(&self, _task: TaskId) -> Self::TaskState {
         TaskState {
@@ -487,11 +575,17 @@ impl Backend for MemoryBackend {
         &self,
         task_id: TaskId,
         duration: Duration,
+        wait_duration: Duration,
         memory_usage: usize,
         cell_counters: &AutoMap<ValueTypeId, u32, BuildHasherDefault<FxHasher>, 8>,
         stateful: bool,
         turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
     ) -> bool {
+        if let Some(stats) = self.task_statistics().get() {
+            if let Some(function_id) = self.try_get_function_id(task_id) {
+                stats.add_execution_duration(function_id, duration, wait_duration);
+            }
+        }
         let generation = if let Some(gc_queue) = &self.gc_queue {
             gc_queue.generation()
         } else {
@@ -673,6 +767,11 @@ impl Backend for MemoryBackend {
         content: CellContent,
         turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
     ) {
+        if let Some(stats) = self.task_statistics().get() {
+            if let Some(function_id) = self.try_get_function_id(task) {
+                stats.add_cell_write(function_id, index.type_id, &content);
+            }
+        }
         self.with_task(task, |task| {
             task.access_cell_for_write(index, |cell, clean| {
                 cell.assign(content, clean, turbo_tasks)
@@ -727,7 +826,7 @@ impl Backend for MemoryBackend {
                 *unsafe { id.get_unchecked() },
                 task_type.clone(),
             );
-            self.insert_and_connect_fresh_task(
+            let task = self.insert_and_connect_fresh_task(
                 parent_task,
                 &self.task_cache,
                 &self.persistent_tasks,
@@ -736,7 +835,9 @@ impl Backend for MemoryBackend {
                 id,
                 task,
                 turbo_tasks,
-            )
+            );
+            self.task_created(task);
+            task
         }
     }
 
@@ -770,7 +871,7 @@ impl Backend for MemoryBackend {
                 *unsafe { id.get_unchecked() },
                 task_type.clone(),
             );
-            self.insert_and_connect_fresh_task(
+            let task = self.insert_and_connect_fresh_task(
                 parent_task,
                 &self.transient_task_cache,
                 &self.transient_tasks,
@@ -779,7 +880,9 @@ impl Backend for MemoryBackend {
                 id,
                 task,
                 turbo_tasks,
-            )
+            );
+            self.task_created(task);
+            task
         }
     }
 
@@ -19,7 +19,9 @@ use smallvec::SmallVec;
 use tracing::Span;
 use turbo_prehash::PreHashed;
 use turbo_tasks::{
-    backend::{CachedTaskType, CellContent, TaskCollectiblesMap, TaskExecutionSpec},
+    backend::{
+        CachedTaskType, CellContent, PendingTaskState, TaskCollectiblesMap, TaskExecutionSpec,
+    },
     event::{Event, EventListener},
     get_invalidator, registry, CellId, Invalidator, RawVc, ReadConsistency, TaskId, TaskIdSet,
     TraitTypeId, TurboTasksBackendApi, TurboTasksBackendApiExt, ValueTypeId,
@@ -614,6 +616,15 @@ impl Task {
         Self::format_description(&TaskTypeForDescription::from(&self.ty), self.id)
     }
 
+    pub(crate) fn get_summary(&self) -> String {
+        match &self.ty {
+            TaskType::Persistent { ty } | TaskType::Transient { ty } => {
+                format!("[{}] {}", self.id, ty.get_summary())
+            }
+            TaskType::Root(_) | TaskType::Once(_) => self.get_description(),
+        }
+    }
+
     fn format_description(ty: &TaskTypeForDescription, id: TaskId) -> String {
         match ty {
             TaskTypeForDescription::Root => format!("[{}] root", id),
@@ -1524,6 +1535,75 @@ impl Task {
         }
     }
 
+    /// Returns the [PendingTaskState] of the task and its children. Only used
+    /// for diagnostics.
+    pub(crate) fn pending_state_and_children(&self) -> (PendingTaskState, Vec<TaskId>) {
+        if let TaskMetaStateReadGuard::Full(state) = self.state() {
+            let pending_state = match state.state_type {
+                Done { .. } => PendingTaskState::Done,
+                Dirty { .. } => PendingTaskState::Dirty,
+                Scheduled(..) => PendingTaskState::Scheduled,
+                InProgress(..) => PendingTaskState::InProgress,
+            };
+            (pending_state, state.state_type.children().collect())
+        } else {
+            // Unloaded tasks need to be recomputed when they are read, see `is_pending`.
+            (PendingTaskState::Dirty, Vec::new())
+        }
+    }
+
+    /// Releases the excess capacity of the state of this task when it is done.
+    /// Returns whether the task was done, and its children. The children of an
+    /// unloaded task are unknown.
+    pub(crate) fn compact(&self) -> (bool, Vec<TaskId>) {
+        let TaskMetaStateWriteGuard::Full(mut state) = self.state_mut() else {
+            return (false, Vec::new());
+        };
+        let children = state.state_type.children().collect();
+        if !matches!(state.state_type, Done { .. }) {
+            return (false, children);
+        }
+        state.aggregation_node.shrink_to_fit();
+        state.output.dependent_tasks.shrink_to_fit();
+        state.cells.shrink_to_fit();
+        for cells in state.cells.values_mut() {
+            cells.shrink_to_fit();
+            for cell in cells.iter_mut() {
+                cell.shrink_to_fit();
+            }
+        }
+        (true, children)
+    }
+
+    /// Unloads this task when it is no longer active, like the garbage
+    /// collection would do. Returns whether the task was unloaded.
+    pub(crate) fn unload_if_inactive(
+        &self,
+        backend: &MemoryBackend,
+        turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) -> bool {
+        if !self.is_pure() {
+            return false;
+        }
+        let aggregation_context = TaskAggregationContext::new(turbo_tasks, backend);
+        if query_root_info(&aggregation_context, ActiveQuery::default(), self.id) {
+            return false;
+        }
+        match self.state_mut() {
+            TaskMetaStateWriteGuard::Full(state) => self.unload(state, backend, turbo_tasks),
+            _ => false,
+        }
+    }
+
+    /// Returns the number of cells of this task when it is fully loaded.
+    pub(crate) fn loaded_cells(&self) -> Option<usize> {
+        if let TaskMetaStateReadGuard::Full(state) = self.state() {
+            Some(state.cells.values().map(|cells| cells.len()).sum())
+        } else {
+            None
+        }
+    }
+
     fn state_string(state: &TaskState) -> &'static str {
         match state.state_type {
             Scheduled { .. } => "scheduled",
@@ -1862,6 +1942,7 @@ impl Task {
             }
         }
         // Task is now dirty, so we can safely unload it
+        turbo_tasks.forget_key_dependencies(self.id);
 
         let mut state = full_state.into_inner();
         let old_state = replace(
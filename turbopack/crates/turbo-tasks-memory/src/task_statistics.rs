@@ -1,12 +1,14 @@
 use std::{
     hash::BuildHasherDefault,
+    io::{self, Write},
     sync::{Arc, OnceLock},
+    time::Duration,
 };
 
 use dashmap::DashMap;
 use rustc_hash::FxHasher;
 use serde::{ser::SerializeMap, Serialize, Serializer};
-use turbo_tasks::{registry, FunctionId};
+use turbo_tasks::{backend::CellContent, registry, FunctionId, ValueTypeId};
 
 /// An API for optionally enabling, updating, and reading aggregated statistics.
 #[derive(Default)]
@@ -55,6 +57,65 @@ impl TaskStatistics {
         self.with_task_type_statistics(function_id, |stats| stats.cache_miss += 1)
     }
 
+    /// Adds the time spent executing a task of the function itself, and the
+    /// time spent waiting in between, e.g. for the tasks it reads.
+    pub(crate) fn add_execution_duration(
+        &self,
+        function_id: FunctionId,
+        duration: Duration,
+        wait_duration: Duration,
+    ) {
+        self.with_task_type_statistics(function_id, |stats| {
+            stats.self_time_us += duration.as_micros() as u64;
+            stats.wait_time_us += wait_duration.as_micros() as u64;
+        })
+    }
+
+    /// Adds a cell that a task of the function wrote, with the size of its
+    /// content when it's serialized like for persistent caching.
+    pub(crate) fn add_cell_write(
+        &self,
+        function_id: FunctionId,
+        type_id: ValueTypeId,
+        content: &CellContent,
+    ) {
+        let size = serialized_size(type_id, content);
+        self.with_task_type_statistics(function_id, |stats| {
+            stats.cells_written += 1;
+            match size {
+                Some(size) => stats.output_bytes += size,
+                None => stats.unserializable_cells += 1,
+            }
+        })
+    }
+
+    /// Returns the `count` functions whose tasks wrote the most bytes into
+    /// cells, largest first.
+    ///
+    /// Values that are not serializable (e.g. ASTs) can't be measured, so
+    /// functions writing them are only listed by their
+    /// [`TaskFunctionOutputSize::unserializable_cells`].
+    pub fn largest_outputs(&self, count: usize) -> Vec<TaskFunctionOutputSize> {
+        let mut outputs = self
+            .inner
+            .iter()
+            .filter(|entry| entry.cells_written > 0)
+            .map(|entry| TaskFunctionOutputSize {
+                function: registry::get_function_global_name(*entry.key()),
+                cells_written: entry.cells_written,
+                unserializable_cells: entry.unserializable_cells,
+                output_bytes: entry.output_bytes,
+            })
+            .collect::<Vec<_>>();
+        outputs.sort_by(|a, b| {
+            b.output_bytes
+                .cmp(&a.output_bytes)
+                .then(b.unserializable_cells.cmp(&a.unserializable_cells))
+        });
+        outputs.truncate(count);
+        outputs
+    }
+
     fn with_task_type_statistics(
         &self,
         task_function_id: FunctionId,
@@ -69,6 +130,55 @@ impl TaskStatistics {
 struct TaskFunctionStatistics {
     cache_hit: u32,
     cache_miss: u32,
+    /// The total time spent executing tasks of the function, in microseconds.
+    self_time_us: u64,
+    /// The total time tasks of the function spent waiting during their
+    /// execution, e.g. for the tasks they read, in microseconds.
+    wait_time_us: u64,
+    /// The number of cells that tasks of the function wrote.
+    cells_written: u64,
+    /// The number of written cells whose values are not serializable.
+    unserializable_cells: u64,
+    /// The total size of the serializable values that tasks of the function
+    /// wrote into cells, in bytes.
+    output_bytes: u64,
+}
+
+/// The sizes of the values that tasks of a function wrote into cells, see
+/// [`TaskStatistics::largest_outputs`].
+pub struct TaskFunctionOutputSize {
+    pub function: &'static str,
+    pub cells_written: u64,
+    pub unserializable_cells: u64,
+    pub output_bytes: u64,
+}
+
+/// The size of the content of a cell when serialized like for persistent
+/// caching, or `None` when its value type is not serializable.
+fn serialized_size(type_id: ValueTypeId, content: &CellContent) -> Option<u64> {
+    let Some(reference) = &content.0 else {
+        return Some(0);
+    };
+    if !registry::get_value_type(type_id).is_serializable() {
+        return None;
+    }
+    let mut counter = ByteCounter(0);
+    pot::to_writer(&reference.clone().into_typed(type_id), &mut counter).ok()?;
+    Some(counter.0)
+}
+
+/// A writer that only counts the bytes written to it.
+struct ByteCounter(u64);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 impl Serialize for TaskStatistics {
@@ -0,0 +1,57 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{RcStr, TurboTasks, Vc};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn interns_equal_cells_of_different_tasks() {
+    REGISTRATION.ensure_registered();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.set_cell_interning(Some(16));
+    tt.run_once(async {
+        let a = names("a".into(), 10).await?;
+        let b = names("b".into(), 10).await?;
+        assert_eq!(a, b);
+        assert!(a.ptr_eq(&b));
+
+        // Values smaller than the minimum size are not interned
+        let a = names("a".into(), 1).await?;
+        let b = names("b".into(), 1).await?;
+        assert_eq!(a, b);
+        assert!(!a.ptr_eq(&b));
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn interning_is_disabled_by_default() {
+    REGISTRATION.ensure_registered();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async {
+        let a = names("a".into(), 10).await?;
+        let b = names("b".into(), 10).await?;
+        assert_eq!(a, b);
+        assert!(!a.ptr_eq(&b));
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[turbo_tasks::value(transparent)]
+struct Names(Vec<RcStr>);
+
+/// Computes the same names for every `key`, so the tasks of different keys
+/// have equal cells.
+#[turbo_tasks::function]
+fn names(_key: RcStr, count: usize) -> Vc<Names> {
+    Vc::cell((0..count).map(|i| format!("name-{i}").into()).collect())
+}
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/compact_subgraph.rs
\ No newline at end of file
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/execution_local.rs
\ No newline at end of file
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/leak_check.rs
\ No newline at end of file
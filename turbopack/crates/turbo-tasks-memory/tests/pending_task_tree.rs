@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/pending_task_tree.rs
\ No newline at end of file
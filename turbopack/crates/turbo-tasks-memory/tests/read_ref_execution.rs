@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/read_ref_execution.rs
\ No newline at end of file
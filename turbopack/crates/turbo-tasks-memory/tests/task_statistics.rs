@@ -5,6 +5,7 @@
 use std::{
     future::{Future, IntoFuture},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::Result;
@@ -186,6 +187,51 @@ async fn test_no_execution() {
     .await;
 }
 
+#[tokio::test]
+async fn test_execution_time() {
+    run_with_tt(|tt| async move {
+        wait_for_sleep().await.unwrap();
+        let stats = serde_json::to_value(tt.backend().task_statistics().get()).unwrap();
+        let stats = remove_hashes(stats);
+        let parent = &stats["turbo-tasks-memory::::wait_for_sleep"];
+        let child = &stats["turbo-tasks-memory::::sleep"];
+        // The time spent sleeping is waiting time of both tasks, not self time
+        assert!(child["wait_time_us"].as_u64().unwrap() >= 50_000);
+        assert!(child["self_time_us"].as_u64().unwrap() < 50_000);
+        assert!(parent["wait_time_us"].as_u64().unwrap() >= 50_000);
+        assert!(parent["self_time_us"].as_u64().unwrap() < 50_000);
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_output_sizes() {
+    run_with_tt(|tt| async move {
+        for i in 0..10 {
+            double(i).await.unwrap();
+            repeat(i as usize * 100).await.unwrap();
+        }
+        let largest = tt
+            .backend()
+            .task_statistics()
+            .get()
+            .unwrap()
+            .largest_outputs(1);
+        assert_eq!(largest.len(), 1);
+        assert!(largest[0].function.ends_with("::repeat"));
+        assert_eq!(largest[0].cells_written, 10);
+        assert_eq!(largest[0].unserializable_cells, 0);
+        assert!(largest[0].output_bytes >= 4500);
+
+        let stats =
+            remove_hashes(serde_json::to_value(tt.backend().task_statistics().get()).unwrap());
+        let double = &stats["turbo-tasks-memory::::double"];
+        assert_eq!(double["cells_written"], 10);
+        assert!(double["output_bytes"].as_u64().unwrap() < largest[0].output_bytes);
+    })
+    .await;
+}
+
 // Internally, this function uses `CachedTaskType::Native`.
 #[turbo_tasks::function]
 fn double(val: u64) -> Vc<u64> {
@@ -232,6 +278,23 @@ impl Doublable for WrappedU64 {
     }
 }
 
+#[turbo_tasks::function]
+fn repeat(len: usize) -> Vc<String> {
+    Vc::cell("a".repeat(len))
+}
+
+#[turbo_tasks::function]
+async fn sleep() -> Result<Vc<()>> {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    Ok(Vc::cell(()))
+}
+
+#[turbo_tasks::function]
+async fn wait_for_sleep() -> Result<Vc<()>> {
+    sleep().await?;
+    Ok(Vc::cell(()))
+}
+
 #[turbo_tasks::function]
 async fn fail(val: u64) -> Result<Vc<()>> {
     anyhow::bail!("failed using {val}");
@@ -254,7 +317,26 @@ where
 }
 
 fn stats_json(tt: &TurboTasks<MemoryBackend>) -> serde_json::Value {
-    remove_hashes(serde_json::to_value(tt.backend().task_statistics().get()).unwrap())
+    remove_timing_and_outputs(remove_hashes(
+        serde_json::to_value(tt.backend().task_statistics().get()).unwrap(),
+    ))
+}
+
+// Execution times differ between runs, so they are tested separately, like the
+// outputs.
+fn remove_timing_and_outputs(mut json: serde_json::Value) -> serde_json::Value {
+    if let serde_json::Value::Object(map) = &mut json {
+        for stats in map.values_mut() {
+            if let serde_json::Value::Object(stats) = stats {
+                stats.remove("self_time_us");
+                stats.remove("wait_time_us");
+                stats.remove("cells_written");
+                stats.remove("unserializable_cells");
+                stats.remove("output_bytes");
+            }
+        }
+    }
+    json
 }
 
 // Global task identifiers can contain a hash of the crate and dependencies.
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/wait_idle.rs
\ No newline at end of file
@@ -14,17 +14,21 @@ use std::{
 
 use anyhow::{anyhow, Result};
 use futures::FutureExt;
+use tokio::sync::oneshot;
 use turbo_tasks::{
-    backend::{CellContent, TaskCollectiblesMap, TypedCellContent},
+    backend::{CellContent, LeakedTask, PendingTaskTree, TaskCollectiblesMap, TypedCellContent},
     event::{Event, EventListener},
     registry,
     test_helpers::with_turbo_tasks_for_testing,
     util::{SharedError, StaticOrArc},
-    CellId, ExecutionId, InvalidationReason, LocalTaskId, MagicAny, RawVc, ReadConsistency, TaskId,
-    TaskPersistence, TraitTypeId, TurboTasksApi, TurboTasksCallApi,
+    CellId, ExecutionId, InvalidationReason, LocalTaskId, MagicAny, RawVc, RcStr, ReadConsistency,
+    SamplingProfile, SharedReference, SharedReferenceEqFn, StronglyConsistentReadWatchdog, TaskId,
+    TaskPersistence, TraitTypeId, TurboTasksApi, TurboTasksCallApi, ValueTypeId,
 };
 
-pub use crate::run::{run, run_with_tt, run_without_cache_check, Registration};
+pub use crate::run::{
+    assert_no_leaks, run, run_with_leak_check, run_with_tt, run_without_cache_check, Registration,
+};
 
 enum Task {
     Spawned(Event),
@@ -181,6 +185,14 @@ impl TurboTasksApi for VcStorage {
         // ingore
     }
 
+    fn depend_on_key(&self, _key: RcStr, _task: TaskId) {
+        // ignore
+    }
+
+    fn invalidate_key(&self, _key: &str) {
+        // ignore, as key dependencies are ignored
+    }
+
     fn notify_scheduled_tasks(&self) {
         // ignore
     }
@@ -309,6 +321,43 @@ impl TurboTasksApi for VcStorage {
         // no-op
     }
 
+    fn strongly_consistent_read_watchdog(&self) -> Option<StronglyConsistentReadWatchdog> {
+        None
+    }
+
+    fn sampling_profile(&self) -> Option<SamplingProfile> {
+        None
+    }
+
+    fn pending_task_tree(&self, _task: TaskId) -> Option<PendingTaskTree> {
+        None
+    }
+
+    fn start_waiting(&self, _reader: TaskId, _task: TaskId) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn stop_waiting(&self, _reader: TaskId, _task: TaskId) -> Option<oneshot::Receiver<()>> {
+        None
+    }
+
+    fn intern_cell_content(
+        &self,
+        _ty: ValueTypeId,
+        content: SharedReference,
+        _eq: SharedReferenceEqFn,
+    ) -> SharedReference {
+        content
+    }
+
+    fn compact_subgraph(&self, _task: TaskId) -> usize {
+        0
+    }
+
+    fn leak_check(&self) -> Option<Vec<LeakedTask>> {
+        None
+    }
+
     fn mark_own_task_as_finished(&self, _task: TaskId) {
         // no-op
     }
@@ -327,6 +376,13 @@ impl TurboTasksApi for VcStorage {
     fn stop_and_wait(&self) -> std::pin::Pin<Box<dyn Future<Output = ()> + Send + 'static>> {
         Box::pin(async {})
     }
+
+    fn wait_idle(
+        &self,
+        _idle_for: std::time::Duration,
+    ) -> std::pin::Pin<Box<dyn Future<Output = ()> + Send + 'static>> {
+        unimplemented!()
+    }
 }
 
 impl VcStorage {
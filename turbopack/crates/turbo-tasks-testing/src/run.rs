@@ -2,6 +2,7 @@ use std::{
     fmt::Debug,
     future::Future,
     sync::{Arc, OnceLock},
+    time::Duration,
 };
 
 use anyhow::Result;
@@ -128,3 +129,44 @@ where
     assert_eq!(first, third);
     Ok(())
 }
+
+/// Runs `fut` once in a new TurboTasks instance and asserts that all of its
+/// tasks and cells are freed once the root task is dropped. See
+/// [`assert_no_leaks`].
+pub async fn run_with_leak_check<T, F>(
+    registration: &Registration,
+    fut: impl FnOnce() -> F + Send + 'static,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    registration.ensure_registered();
+
+    let name = closure_to_name(&fut);
+    let tt = registration.create_turbo_tasks(&name, true);
+    let result = run_once(tt.clone(), fut()).await?;
+    tt.wait_idle(Duration::from_millis(10)).await;
+    assert_no_leaks(&*tt);
+    tt.stop_and_wait().await;
+    Ok(result)
+}
+
+/// Collects all tasks that are not reachable from an active root task and
+/// panics when any tasks are still loaded afterwards, listing them with their
+/// creation backtraces if the backend tracks them. Also panics when the
+/// backend doesn't support leak checks, so a test can't pass without being
+/// checked.
+pub fn assert_no_leaks(tt: &dyn TurboTasksApi) {
+    let leaked = tt
+        .leak_check()
+        .expect("the backend doesn't support leak checks");
+    if leaked.is_empty() {
+        return;
+    }
+    let mut message = format!("{} tasks were leaked:\n", leaked.len());
+    for task in &leaked {
+        message.push_str(&task.to_string());
+    }
+    panic!("{message}");
+}
@@ -0,0 +1,41 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{turbo_tasks, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn compact_subgraph() {
+    run(&REGISTRATION, || async {
+        let out_vc = outer();
+        assert_eq!(*out_vc.strongly_consistent().await?, 42);
+
+        let task = Vc::into_raw(out_vc).get_task_id();
+        let compacted = turbo_tasks().compact_subgraph(task);
+        assert!(
+            compacted >= 2,
+            "expected outer and inner to be compacted, got {compacted}"
+        );
+
+        // compacted tasks can still be read
+        assert_eq!(*out_vc.strongly_consistent().await?, 42);
+        assert_eq!(*inner().strongly_consistent().await?, 42);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::function]
+async fn outer() -> Result<Vc<u32>> {
+    Ok(Vc::cell(*inner().await?))
+}
+
+#[turbo_tasks::function]
+fn inner() -> Vc<u32> {
+    Vc::cell(42)
+}
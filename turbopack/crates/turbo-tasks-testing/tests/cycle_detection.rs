@@ -0,0 +1,38 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use tokio::time::{timeout, Duration};
+use turbo_tasks::Vc;
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn cycle_detection() {
+    run(&REGISTRATION, || async {
+        // The test configs of the backends enable cycle detection
+        let err = timeout(Duration::from_secs(10), ping(42).strongly_consistent())
+            .await
+            .expect("should fail instead of waiting forever")
+            .expect_err("should detect the cycle");
+        let message = format!("{err:?}");
+        assert!(message.contains("Detected a cycle of tasks waiting for each other"));
+        assert!(message.contains("ping(42)"));
+        assert!(message.contains("pong(42)"));
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::function]
+async fn ping(n: u32) -> Result<Vc<u32>> {
+    Ok(Vc::cell(*pong(n).await? + 1))
+}
+
+#[turbo_tasks::function]
+async fn pong(n: u32) -> Result<Vc<u32>> {
+    Ok(Vc::cell(*ping(n).await? + 1))
+}
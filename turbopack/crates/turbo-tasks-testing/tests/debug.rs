@@ -4,7 +4,7 @@
 
 use std::sync::Mutex;
 
-use turbo_tasks::{debug::ValueDebug, ResolvedVc, Vc};
+use turbo_tasks::{debug::ValueDebug, RcStr, ResolvedVc, Vc};
 use turbo_tasks_testing::{register, run, Registration};
 
 static REGISTRATION: Registration = register!();
@@ -155,6 +155,139 @@ async fn struct_ignore_debug() {
     .unwrap()
 }
 
+#[tokio::test]
+async fn struct_redact_debug() {
+    run(&REGISTRATION, || async {
+        let a: Vc<StructWithRedact> = StructWithRedact {
+            dont_redact: 42,
+            redact: "secret".into(),
+        }
+        .cell();
+        let b: Vc<StructWithRedact> = StructWithRedact {
+            dont_redact: 43,
+            redact: "secret".into(),
+        }
+        .cell();
+        let c: Vc<StructWithRedact> = StructWithRedact {
+            dont_redact: 42,
+            redact: "other secret".into(),
+        }
+        .cell();
+        let a = format!("{:?}", a.dbg().await?);
+        let b = format!("{:?}", b.dbg().await?);
+        let c = format!("{:?}", c.dbg().await?);
+        assert!(!a.contains("secret"));
+        assert!(a.starts_with("StructWithRedact {\n    dont_redact: 42,\n    redact: [redacted "));
+        // Equal values are redacted to the same hash
+        let redacted = |debug: &str| debug.rsplit_once("redact: ").unwrap().1.to_string();
+        assert_eq!(redacted(&a), redacted(&b));
+        assert_ne!(redacted(&a), redacted(&c));
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn primitive_debug_json() {
+    run(&REGISTRATION, || async {
+        let a: Vc<u32> = Vc::cell(42);
+        assert_eq!(a.dbg_json(usize::MAX).await?.as_json().to_string(), "42");
+        let b: Vc<RcStr> = Vc::cell("hello".into());
+        assert_eq!(
+            b.dbg_json(usize::MAX).await?.as_json().to_string(),
+            r#""hello""#
+        );
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn enum_debug_json() {
+    run(&REGISTRATION, || async {
+        let a: Vc<Enum> = Enum::None.cell();
+        assert_eq!(
+            a.dbg_json(usize::MAX).await?.as_json().to_string(),
+            r#"{"$type":"Enum::None"}"#
+        );
+        let b: Vc<Enum> = Enum::Transparent(Transparent(42).resolved_cell()).cell();
+        assert_eq!(
+            b.dbg_json(usize::MAX).await?.as_json().to_string(),
+            r#"{"$type":"Enum::Transparent","fields":[42]}"#
+        );
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn struct_debug_json() {
+    run(&REGISTRATION, || async {
+        let a: Vc<StructUnit> = StructUnit.cell();
+        assert_eq!(
+            a.dbg_json(usize::MAX).await?.as_json().to_string(),
+            r#"{"$type":"StructUnit"}"#
+        );
+        let b: Vc<StructWithVec> = StructWithVec {
+            vec: vec![Transparent(42).resolved_cell()],
+        }
+        .cell();
+        assert_eq!(
+            b.dbg_json(usize::MAX).await?.as_json().to_string(),
+            r#"{"$type":"StructWithVec","vec":[42]}"#
+        );
+        let c: Vc<StructWithIgnore> = StructWithIgnore {
+            dont_ignore: 42,
+            ignore: Mutex::new(()),
+        }
+        .cell();
+        assert_eq!(
+            c.dbg_json(usize::MAX).await?.as_json().to_string(),
+            r#"{"$type":"StructWithIgnore","dont_ignore":42}"#
+        );
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn struct_redact_debug_json() {
+    run(&REGISTRATION, || async {
+        let a: Vc<StructWithRedact> = StructWithRedact {
+            dont_redact: 42,
+            redact: "secret".into(),
+        }
+        .cell();
+        let json = a.dbg_json(usize::MAX).await?;
+        let json = json.as_json();
+        assert_eq!(json["dont_redact"], 42);
+        assert!(json["redact"].as_str().unwrap().starts_with("[redacted "));
+        assert!(!json.to_string().contains("secret"));
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn depth_debug_json() {
+    run(&REGISTRATION, || async {
+        let a: Vc<Enum> = Enum::Enum(Enum::None.resolved_cell()).cell();
+        // The inner `Vc` is past the depth limit and replaced by a placeholder
+        let json = a.dbg_json(1).await?;
+        let json = json.as_json();
+        assert_eq!(json["$type"], "Enum::Enum");
+        assert!(json["fields"][0].get("$vc").is_some());
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
 #[turbo_tasks::value(transparent, shared)]
 struct Transparent(u32);
 
@@ -200,3 +333,10 @@ impl PartialEq for StructWithIgnore {
 }
 
 impl Eq for StructWithIgnore {}
+
+#[turbo_tasks::value(shared)]
+struct StructWithRedact {
+    dont_redact: u32,
+    #[turbo_tasks(redact)]
+    redact: RcStr,
+}
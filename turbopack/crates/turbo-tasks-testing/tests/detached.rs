@@ -45,7 +45,7 @@ async fn test_spawns_detached() -> anyhow::Result<()> {
     .await
 }
 
-#[turbo_tasks::function]
+#[turbo_tasks::function(transient)]
 async fn spawns_detached(
     notify: TransientInstance<Notify>,
     sender: TransientInstance<watch::Sender<Option<Vc<u32>>>>,
@@ -111,7 +111,7 @@ struct ChangingInput {
     state: State<u32>,
 }
 
-#[turbo_tasks::function]
+#[turbo_tasks::function(transient)]
 async fn spawns_detached_changing(
     sender: TransientInstance<watch::Sender<Option<Vc<u32>>>>,
     changing_input_detached: Vc<ChangingInput>,
@@ -0,0 +1,76 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{set_execution_local, with_execution_local, State, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+struct RequestId(u32);
+
+#[tokio::test]
+async fn execution_local() {
+    run(&REGISTRATION, || async {
+        let input = ChangingInput {
+            state: State::new(1),
+        }
+        .cell();
+        let output = compute(input);
+        let read = output.strongly_consistent().await?;
+        assert_eq!(read.value, 1);
+        assert!(!read.set_before_execution);
+        assert!(!read.visible_in_child);
+        assert_eq!(read.after_await, Some(1));
+        assert_eq!(read.after_guard, None);
+
+        input.await?.state.set(2);
+        let read = output.strongly_consistent().await?;
+        assert_eq!(read.value, 2);
+        assert!(!read.set_before_execution);
+        assert_eq!(read.after_await, Some(2));
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value]
+struct ChangingInput {
+    state: State<u32>,
+}
+
+#[turbo_tasks::value]
+struct Output {
+    value: u32,
+    set_before_execution: bool,
+    visible_in_child: bool,
+    after_await: Option<u32>,
+    after_guard: Option<u32>,
+}
+
+#[turbo_tasks::function]
+async fn compute(input: Vc<ChangingInput>) -> Result<Vc<Output>> {
+    let value = *input.await?.state.get();
+    let set_before_execution = with_execution_local(|id: Option<&RequestId>| id.is_some());
+    let guard = set_execution_local(RequestId(value));
+    let visible_in_child = *child().await?;
+    let after_await = with_execution_local(|id: Option<&RequestId>| id.map(|id| id.0));
+    drop(guard);
+    let after_guard = with_execution_local(|id: Option<&RequestId>| id.map(|id| id.0));
+    Ok(Output {
+        value,
+        set_before_execution,
+        visible_in_child,
+        after_await,
+        after_guard,
+    }
+    .cell())
+}
+
+#[turbo_tasks::function]
+fn child() -> Vc<bool> {
+    Vc::cell(with_execution_local(|id: Option<&RequestId>| id.is_some()))
+}
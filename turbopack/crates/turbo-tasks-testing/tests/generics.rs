@@ -0,0 +1,75 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{RcStr, ResolvedVc, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[turbo_tasks::value]
+struct Value(u32);
+
+#[turbo_tasks::value]
+struct ParseError(RcStr);
+
+#[turbo_tasks::function]
+fn find(value: u32) -> Vc<Option<ResolvedVc<Value>>> {
+    if value == 0 {
+        Vc::none()
+    } else {
+        Vc::some(Value(value).resolved_cell())
+    }
+}
+
+#[turbo_tasks::function]
+fn parse(value: u32) -> Vc<Result<ResolvedVc<Value>, ResolvedVc<ParseError>>> {
+    if value == 0 {
+        Vc::err_cell(ParseError("zero".into()).resolved_cell())
+    } else {
+        Vc::ok_cell(Value(value).resolved_cell())
+    }
+}
+
+#[tokio::test]
+async fn option() {
+    run(&REGISTRATION, || async {
+        assert!(*find(1).is_some().await?);
+        assert!(!*find(1).is_none().await?);
+        assert!(*find(0).is_none().await?);
+        assert_eq!(find(1).await?.unwrap().await?.0, 1);
+
+        let replaced = find(1).map(|_| Value(2).resolved_cell()).await?;
+        assert_eq!(replaced.await?.unwrap().await?.0, 2);
+        let replaced = find(0).map(|_| Value(2).resolved_cell()).await?;
+        assert!(replaced.await?.is_none());
+
+        let value = find(3).unwrap_or_else_vc(|| Value(42).cell()).await?;
+        assert_eq!(value.await?.0, 3);
+        let value = find(0).unwrap_or_else_vc(|| Value(42).cell()).await?;
+        assert_eq!(value.await?.0, 42);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn result() {
+    run(&REGISTRATION, || async {
+        assert!(*parse(1).is_ok().await?);
+        assert!(*parse(0).is_err().await?);
+        assert_eq!(&*parse(0).await?.unwrap_err().await?.0, "zero");
+
+        let ok = parse(1).ok().await?;
+        assert_eq!(ok.await?.unwrap().await?.0, 1);
+        assert!(parse(0).ok().await?.await?.is_none());
+
+        let value = parse(0).unwrap_or_else_vc(|_| Value(42).cell()).await?;
+        assert_eq!(value.await?.0, 42);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
@@ -0,0 +1,85 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use anyhow::Result;
+use turbo_tasks::{depend_on_key, turbo_tasks, RcStr, State, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+static EXECUTIONS: AtomicU32 = AtomicU32::new(0);
+static CONDITIONAL_EXECUTIONS: AtomicU32 = AtomicU32::new(0);
+
+#[tokio::test]
+async fn invalidation_keys() {
+    run(&REGISTRATION, || async {
+        let output = compute("cms".into());
+        let first = *output.strongly_consistent().await?;
+
+        turbo_tasks().invalidate_key("other");
+        let read = *output.strongly_consistent().await?;
+        assert_eq!(read, first);
+
+        turbo_tasks().invalidate_key("cms");
+        let read = *output.strongly_consistent().await?;
+        assert_ne!(read, first);
+
+        // The task depends on the key again after it was executed again
+        turbo_tasks().invalidate_key("cms");
+        let second = *output.strongly_consistent().await?;
+        assert_ne!(second, read);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn dropped_invalidation_keys() {
+    run(&REGISTRATION, || async {
+        let input = ChangingInput {
+            state: State::new(true),
+        }
+        .cell();
+        let output = compute_conditional(input);
+        let first = *output.strongly_consistent().await?;
+
+        // The execution doesn't depend on the key anymore
+        input.await?.state.set(false);
+        let read = *output.strongly_consistent().await?;
+        assert_ne!(read, first);
+
+        turbo_tasks().invalidate_key("conditional");
+        let second = *output.strongly_consistent().await?;
+        assert_eq!(second, read);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value]
+struct ChangingInput {
+    state: State<bool>,
+}
+
+#[turbo_tasks::function]
+async fn compute_conditional(input: Vc<ChangingInput>) -> Result<Vc<u32>> {
+    if *input.await?.state.get() {
+        depend_on_key("conditional");
+    }
+    Ok(Vc::cell(
+        CONDITIONAL_EXECUTIONS.fetch_add(1, Ordering::SeqCst),
+    ))
+}
+
+#[turbo_tasks::function]
+fn compute(key: RcStr) -> Result<Vc<u32>> {
+    depend_on_key(key);
+    Ok(Vc::cell(EXECUTIONS.fetch_add(1, Ordering::SeqCst)))
+}
@@ -0,0 +1,52 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{RcStr, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn shares_task_for_same_key() {
+    run(&REGISTRATION, || async {
+        // The second call has the same key, so it reuses the result of the first call.
+        assert_eq!(*keyed("a".into(), 1).await?, 1);
+        assert_eq!(*keyed("a".into(), 2).await?, 1);
+        assert_eq!(*keyed("b".into(), 3).await?, 3);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn resolves_key() {
+    run(&REGISTRATION, || async {
+        // Different unresolved `Vc`s that resolve to the same cell share a task.
+        let value = Vc::<u32>::cell(1).resolve().await?;
+        assert_eq!(*keyed_vc(identity(value), 10).await?, 10);
+        assert_eq!(*keyed_vc(value, 20).await?, 10);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::function(key = path)]
+fn keyed(path: RcStr, value: u32) -> Vc<u32> {
+    let _ = path;
+    Vc::cell(value)
+}
+
+#[turbo_tasks::function(key = key)]
+async fn keyed_vc(key: Vc<u32>, value: u32) -> Result<Vc<u32>> {
+    let _ = key.await?;
+    Ok(Vc::cell(value))
+}
+
+#[turbo_tasks::function]
+fn identity(value: Vc<u32>) -> Vc<u32> {
+    value
+}
@@ -0,0 +1,59 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use tokio::time::Duration;
+use turbo_tasks::{run_once, State, Vc};
+use turbo_tasks_testing::{register, run_with_leak_check, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn no_leaks() {
+    let value = run_with_leak_check(&REGISTRATION, || async {
+        let value = double(double(Vc::cell(21))).await?;
+        anyhow::Ok(*value)
+    })
+    .await
+    .unwrap();
+    assert_eq!(value, 84);
+}
+
+#[tokio::test]
+async fn stateful_task_is_leaked() {
+    REGISTRATION.ensure_registered();
+    let tt = REGISTRATION.create_turbo_tasks("stateful_task_is_leaked", true);
+    run_once(tt.clone(), async {
+        stateful().await?;
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+    tt.wait_idle(Duration::from_millis(10)).await;
+    // Stateful tasks can't be unloaded
+    let leaked = tt
+        .leak_check()
+        .expect("the backend doesn't support leak checks");
+    assert_eq!(leaked.len(), 1);
+    assert!(leaked[0].description.contains("stateful"));
+    tt.stop_and_wait().await;
+}
+
+#[turbo_tasks::function]
+async fn double(value: Vc<u32>) -> Result<Vc<u32>> {
+    Ok(Vc::cell(*value.await? * 2))
+}
+
+#[turbo_tasks::value]
+struct Counter {
+    state: State<u32>,
+}
+
+#[turbo_tasks::function]
+fn stateful() -> Vc<Counter> {
+    Counter {
+        state: State::new(0),
+    }
+    .cell()
+}
@@ -0,0 +1,57 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use tokio::{
+    sync::Notify,
+    time::{timeout, Duration},
+};
+use turbo_tasks::{backend::PendingTaskState, turbo_tasks, TransientInstance, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn pending_task_tree() {
+    run(&REGISTRATION, || async {
+        let notify = TransientInstance::new(Notify::new());
+        let out_vc = outer(notify.clone());
+
+        timeout(Duration::from_millis(100), out_vc.strongly_consistent())
+            .await
+            .expect_err("should wait on the inner task");
+
+        let task = Vc::into_raw(out_vc).get_task_id();
+        let tree = turbo_tasks()
+            .pending_task_tree(task)
+            .expect("backend should support pending task trees");
+        assert_eq!(tree.task, task);
+        assert_eq!(tree.state, PendingTaskState::InProgress);
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].state, PendingTaskState::InProgress);
+        assert!(tree.children[0].description.contains("inner"));
+        assert!(tree.to_string().contains("in progress"));
+
+        notify.notify_waiters();
+        out_vc.strongly_consistent().await?;
+
+        let tree = turbo_tasks().pending_task_tree(task).unwrap();
+        assert_eq!(tree.state, PendingTaskState::Done);
+        assert!(tree.children.is_empty());
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::function(transient)]
+async fn outer(notify: TransientInstance<Notify>) -> Result<Vc<u32>> {
+    Ok(Vc::cell(*inner(notify).await?))
+}
+
+#[turbo_tasks::function(transient)]
+async fn inner(notify: TransientInstance<Notify>) -> Vc<u32> {
+    notify.notified().await;
+    Vc::cell(42)
+}
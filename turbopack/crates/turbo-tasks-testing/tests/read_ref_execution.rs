@@ -0,0 +1,58 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::sync::Mutex;
+
+use anyhow::Result;
+use turbo_tasks::{ReadRef, State, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+static KEPT: Mutex<Option<ReadRef<Number>>> = Mutex::new(None);
+
+#[tokio::test]
+#[cfg_attr(not(debug_assertions), ignore = "only validated in debug builds")]
+async fn read_ref_kept_across_executions() {
+    run(&REGISTRATION, || async {
+        let input = ChangingInput {
+            state: State::new(1),
+        }
+        .cell();
+        let output = keep_read_ref(input);
+        assert_eq!(*output.strongly_consistent().await?, 1);
+
+        input.await?.state.set(2);
+        let error = output.strongly_consistent().await.unwrap_err();
+        assert!(
+            format!("{error:?}").contains("previous execution"),
+            "{error:?}"
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value]
+struct ChangingInput {
+    state: State<u32>,
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::function]
+async fn number(input: Vc<ChangingInput>) -> Result<Vc<Number>> {
+    Ok(Vc::cell(*input.await?.state.get()))
+}
+
+#[turbo_tasks::function]
+async fn keep_read_ref(input: Vc<ChangingInput>) -> Result<Vc<Number>> {
+    let number = number(input).await?;
+    // The first read is kept, so the second execution uses a stale one.
+    let kept = KEPT.lock().unwrap().get_or_insert(number).clone();
+    Ok(Vc::cell(*kept))
+}
@@ -0,0 +1,75 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use anyhow::Result;
+use tokio::time::{sleep, Duration};
+use turbo_tasks::Vc;
+use turbo_tasks_testing::{register, run_without_cache_check, Registration};
+
+static REGISTRATION: Registration = register!();
+
+static EXECUTIONS: AtomicU32 = AtomicU32::new(0);
+
+#[tokio::test]
+async fn task_ttl() {
+    run_without_cache_check(&REGISTRATION, async {
+        let first = *compute().strongly_consistent().await?;
+        let read = *compute().strongly_consistent().await?;
+        assert_eq!(read, first);
+
+        sleep(Duration::from_millis(300)).await;
+        let second = *compute().strongly_consistent().await?;
+        assert_ne!(second, first);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::function(ttl = "100ms")]
+fn compute() -> Result<Vc<u32>> {
+    Ok(Vc::cell(EXECUTIONS.fetch_add(1, Ordering::SeqCst)))
+}
+
+static EXPIRING_EXECUTIONS: AtomicU32 = AtomicU32::new(0);
+
+#[tokio::test]
+async fn task_ttl_invalidates_dependents() {
+    run_without_cache_check(&REGISTRATION, async {
+        let first = *double_expiring().strongly_consistent().await?;
+        assert_eq!(first, 0);
+
+        // The result expires while the engine is idle, so it's only invalidated once the engine
+        // becomes busy again.
+        sleep(Duration::from_millis(300)).await;
+        let mut retries = 0;
+        let second = loop {
+            let value = *double_expiring().strongly_consistent().await?;
+            if value != first || retries == 20 {
+                break value;
+            }
+            retries += 1;
+            sleep(Duration::from_millis(50)).await;
+        };
+        assert_eq!(second, 2);
+        assert_eq!(EXPIRING_EXECUTIONS.load(Ordering::SeqCst), 2);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::function(ttl = "100ms")]
+fn expiring() -> Result<Vc<u32>> {
+    Ok(Vc::cell(EXPIRING_EXECUTIONS.fetch_add(1, Ordering::SeqCst)))
+}
+
+#[turbo_tasks::function]
+async fn double_expiring() -> Result<Vc<u32>> {
+    Ok(Vc::cell(*expiring().await? * 2))
+}
@@ -0,0 +1,33 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use tokio::time::{sleep, timeout, Duration};
+use turbo_tasks::run_once;
+use turbo_tasks_testing::{register, run_with_tt, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn wait_idle() {
+    run_with_tt(&REGISTRATION, |tt| async move {
+        const IDLE_FOR: Duration = Duration::from_millis(200);
+
+        // nothing is scheduled yet
+        timeout(Duration::from_secs(5), tt.wait_idle(IDLE_FOR)).await?;
+
+        let idle = tokio::spawn(tt.wait_idle(IDLE_FOR));
+        let finished_while_busy = run_once(tt.clone(), async move {
+            // this task keeps the engine busy for longer than `IDLE_FOR`
+            sleep(IDLE_FOR + Duration::from_millis(100)).await;
+            anyhow::Ok(idle.is_finished())
+        })
+        .await?;
+        assert!(!finished_while_busy);
+
+        timeout(Duration::from_secs(5), tt.wait_idle(IDLE_FOR)).await?;
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
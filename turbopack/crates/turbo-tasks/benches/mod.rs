@@ -0,0 +1,10 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+pub(crate) mod trait_call;
+
+criterion_group!(
+    name = turbo_tasks;
+    config = Criterion::default();
+    targets = trait_call::trait_method_resolution
+);
+criterion_main!(turbo_tasks);
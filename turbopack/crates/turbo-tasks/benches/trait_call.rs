@@ -0,0 +1,65 @@
+use std::{borrow::Cow, hint::black_box};
+
+use criterion::{BenchmarkId, Criterion};
+use turbo_tasks::{
+    debug::{ValueDebug, ValueDebugString},
+    macro_helpers::TraitMethodCache,
+    registry, Completion, Completions, TraitTypeId, ValueTypeId, VcValueTrait, VcValueType,
+};
+
+/// Resolves a trait method like the dynamic dispatch of a trait call on a
+/// resolved `Vc`, e.g. `module.references()` for every module of a graph. A
+/// call site sees a single value type (monomorphic) or a few alternating
+/// ones (polymorphic).
+pub fn trait_method_resolution(c: &mut Criterion) {
+    turbo_tasks::register();
+
+    let trait_type = <Box<dyn ValueDebug> as VcValueTrait>::get_trait_type_id();
+    let value_types = [
+        <Completion as VcValueType>::get_value_type_id(),
+        <Completions as VcValueType>::get_value_type_id(),
+        <ValueDebugString as VcValueType>::get_value_type_id(),
+    ];
+
+    let mut group = c.benchmark_group("turbo_tasks_trait_call");
+    for (name, value_types) in [
+        ("monomorphic", &value_types[..1]),
+        ("polymorphic", &value_types[..]),
+    ] {
+        group.bench_with_input(
+            BenchmarkId::new("registry", name),
+            value_types,
+            |b, value_types| {
+                b.iter(|| {
+                    for &value_type in value_types {
+                        black_box(resolve_with_registry(trait_type, value_type, "dbg"));
+                    }
+                })
+            },
+        );
+        let cache = TraitMethodCache::new();
+        group.bench_with_input(
+            BenchmarkId::new("inline_cache", name),
+            value_types,
+            |b, value_types| {
+                b.iter(|| {
+                    for &value_type in value_types {
+                        black_box(cache.get_or_resolve(trait_type, black_box(value_type), "dbg"));
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// The lookup of a trait call without an inline cache.
+fn resolve_with_registry(
+    trait_type: TraitTypeId,
+    value_type: ValueTypeId,
+    name: &'static str,
+) -> bool {
+    registry::get_value_type(black_box(value_type))
+        .get_trait_method(&(trait_type, Cow::Borrowed(name)))
+        .is_some()
+}
@@ -3,14 +3,15 @@ use std::{
     fmt::{self, Debug, Display, Write},
     future::Future,
     hash::BuildHasherDefault,
+    path::Path,
     pin::Pin,
     sync::Arc,
     time::Duration,
 };
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use auto_hash_map::AutoMap;
-use rustc_hash::FxHasher;
+use rustc_hash::{FxHashSet, FxHasher};
 use tracing::Span;
 
 pub use crate::id::{BackendJobId, ExecutionId};
@@ -410,6 +411,36 @@ impl CachedTaskType {
         }
     }
 
+    /// Returns the name of the function followed by a shortened debug
+    /// representation of its arguments, e.g. `parse(42, "foo")`.
+    pub fn get_summary(&self) -> String {
+        const MAX_ARGS_LEN: usize = 80;
+
+        let (Self::Native { this, arg, .. }
+        | Self::ResolveNative { this, arg, .. }
+        | Self::ResolveTrait { this, arg, .. }) = self;
+        // Arguments are stored as a tuple
+        let arg = format!("{arg:?}");
+        let arg = arg
+            .strip_prefix('(')
+            .and_then(|arg| arg.strip_suffix(')'))
+            .map_or(&*arg, |arg| arg.strip_suffix(',').unwrap_or(arg));
+        let mut args = match (this, arg) {
+            (Some(this), "") => format!("{this:?}"),
+            (Some(this), arg) => format!("{this:?}, {arg}"),
+            (None, arg) => arg.to_string(),
+        };
+        if args.len() > MAX_ARGS_LEN {
+            let mut end = MAX_ARGS_LEN;
+            while !args.is_char_boundary(end) {
+                end -= 1;
+            }
+            args.truncate(end);
+            args.push('…');
+        }
+        format!("{}({args})", self.get_name())
+    }
+
     pub fn try_get_function_id(&self) -> Option<FunctionId> {
         match self {
             Self::Native { fn_type, .. } | Self::ResolveNative { fn_type, .. } => Some(*fn_type),
@@ -523,6 +554,142 @@ impl TryFrom<CellContent> for SharedReference {
 
 pub type TaskCollectiblesMap = AutoMap<RawVc, i32, BuildHasherDefault<FxHasher>, 1>;
 
+/// The state of a task in a [`PendingTaskTree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingTaskState {
+    /// The task is invalidated, but not scheduled for execution yet.
+    Dirty,
+    /// The task is scheduled and waits for a worker to execute it.
+    Scheduled,
+    /// The task is executing. This includes waiting on I/O or on other tasks.
+    InProgress,
+    /// The task is done, but some of its children are not.
+    Done,
+}
+
+impl Display for PendingTaskState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PendingTaskState::Dirty => "dirty",
+            PendingTaskState::Scheduled => "scheduled",
+            PendingTaskState::InProgress => "in progress",
+            PendingTaskState::Done => "done",
+        })
+    }
+}
+
+/// A snapshot of the unfinished tasks below a task. Used to diagnose strongly
+/// consistent reads that don't complete.
+#[derive(Debug, Clone)]
+pub struct PendingTaskTree {
+    pub task: TaskId,
+    pub description: String,
+    pub state: PendingTaskState,
+    /// Only children that are unfinished or have unfinished descendants.
+    pub children: Vec<PendingTaskTree>,
+}
+
+impl PendingTaskTree {
+    /// Walks the children of `task` depth-first and collects the tasks that
+    /// are unfinished or have unfinished descendants. `state_and_children`
+    /// returns the state and the children of a task. Only `task` itself is
+    /// always included.
+    pub fn build(
+        task: TaskId,
+        mut state_and_children: impl FnMut(TaskId) -> (PendingTaskState, Vec<TaskId>),
+        description: impl Fn(TaskId) -> String,
+    ) -> Option<Self> {
+        struct Frame {
+            task: TaskId,
+            state: PendingTaskState,
+            children: std::vec::IntoIter<TaskId>,
+            pending_children: Vec<PendingTaskTree>,
+        }
+
+        let mut new_frame = |task: TaskId| {
+            let (state, children) = state_and_children(task);
+            Frame {
+                task,
+                state,
+                children: children.into_iter(),
+                pending_children: Vec::new(),
+            }
+        };
+
+        // An explicit stack is used as the task graph can be deep.
+        let mut visited = FxHashSet::default();
+        visited.insert(task);
+        let mut stack = vec![new_frame(task)];
+        loop {
+            let frame = stack.last_mut().unwrap();
+            if let Some(child) = frame.children.next() {
+                if visited.insert(child) {
+                    stack.push(new_frame(child));
+                }
+                continue;
+            }
+            let frame = stack.pop().unwrap();
+            let is_root = stack.is_empty();
+            let node = (is_root
+                || frame.state != PendingTaskState::Done
+                || !frame.pending_children.is_empty())
+            .then(|| PendingTaskTree {
+                task: frame.task,
+                description: description(frame.task),
+                state: frame.state,
+                children: frame.pending_children,
+            });
+            match stack.last_mut() {
+                Some(parent) => parent.pending_children.extend(node),
+                None => return node,
+            }
+        }
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{} ({})",
+            "",
+            self.description,
+            self.state,
+            indent = depth * 2
+        )?;
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for PendingTaskTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// A task that is still loaded after all root tasks were dropped and all
+/// unused tasks were collected. See [`Backend::leak_check`].
+#[derive(Debug, Clone)]
+pub struct LeakedTask {
+    pub task: TaskId,
+    pub description: String,
+    /// The number of cells the task still holds.
+    pub cells: usize,
+    /// Where the task was created, if the backend tracks task creation.
+    pub creation_backtrace: Option<String>,
+}
+
+impl Display for LeakedTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} ({} cells)", self.description, self.cells)?;
+        if let Some(backtrace) = &self.creation_backtrace {
+            writeln!(f, "created at:\n{}", backtrace)?;
+        }
+        Ok(())
+    }
+}
+
 pub trait Backend: Sync + Send {
     #[allow(unused_variables)]
     fn startup(&self, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {}
@@ -537,6 +704,17 @@ pub trait Backend: Sync + Send {
     #[allow(unused_variables)]
     fn idle_end(&self, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {}
 
+    /// Writes the persisted task outputs to a portable archive at `path`, so
+    /// a warmed cache can be shared with other machines.
+    #[allow(unused_variables)]
+    fn export_cache(
+        &self,
+        path: &Path,
+        turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) -> Result<()> {
+        bail!("The backend doesn't persist its cache")
+    }
+
     fn invalidate_task(&self, task: TaskId, turbo_tasks: &dyn TurboTasksBackendApi<Self>);
 
     fn invalidate_tasks(&self, tasks: &[TaskId], turbo_tasks: &dyn TurboTasksBackendApi<Self>);
@@ -551,6 +729,50 @@ pub trait Backend: Sync + Send {
 
     fn get_task_description(&self, task: TaskId) -> String;
 
+    /// Describes `task` including a summary of its arguments, e. g. to report
+    /// a cycle of tasks. See [`CachedTaskType::get_summary`].
+    fn get_task_summary(&self, task: TaskId) -> String {
+        self.get_task_description(task)
+    }
+
+    /// Captures the tree of unfinished tasks below `task`, e. g. the tasks a
+    /// strongly consistent read of `task` is waiting on. Returns `None` when the
+    /// backend doesn't support this.
+    fn pending_task_tree(
+        &self,
+        _task: TaskId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) -> Option<PendingTaskTree> {
+        None
+    }
+
+    /// Releases the excess capacity of the storage of the finished tasks below
+    /// `task`, e. g. once a production build is done and its task graph won't
+    /// change anymore. The tasks are stored like before and can still be
+    /// invalidated and recomputed. Tasks that are not in memory are skipped
+    /// with their children instead of being restored. Returns the number of
+    /// tasks that were compacted.
+    ///
+    /// The tasks are not converted to an immutable representation, and equal
+    /// cell contents are not deduplicated here. Cells with equal values share
+    /// one allocation when [`crate::TurboTasks::set_cell_interning`] is
+    /// enabled while they are computed.
+    fn compact_subgraph(
+        &self,
+        _task: TaskId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) -> usize {
+        0
+    }
+
+    /// Collects all tasks that are no longer reachable from an active root
+    /// task and returns the tasks that are still loaded afterwards. Once all
+    /// root tasks are dropped, every returned task is a leak. Only intended
+    /// for tests. Returns `None` when the backend doesn't support this.
+    fn leak_check(&self, _turbo_tasks: &dyn TurboTasksBackendApi<Self>) -> Option<Vec<LeakedTask>> {
+        None
+    }
+
     /// Task-local state that stored inside of [`TurboTasksBackendApi`]. Constructed with
     /// [`Self::new_task_state`].
     ///
@@ -587,10 +809,14 @@ pub trait Backend: Sync + Send {
         turbo_tasks: &dyn TurboTasksBackendApi<Self>,
     );
 
+    /// Called when the execution of a task has completed. `duration` is the
+    /// time spent executing the task itself, and `wait_duration` is the time
+    /// spent waiting in between, e.g. for the tasks it reads.
     fn task_execution_completed(
         &self,
         task: TaskId,
         duration: Duration,
+        wait_duration: Duration,
         memory_usage: usize,
         cell_counters: &AutoMap<ValueTypeId, u32, BuildHasherDefault<FxHasher>, 8>,
         stateful: bool,
@@ -830,6 +1056,31 @@ pub(crate) mod tests {
         fn mock_method_task() -> Vc<()>;
     }
 
+    #[test]
+    fn builds_pending_task_trees() {
+        let task = |id: u32| TaskId::from(id);
+        // 1 -> 2 -> 3 (in progress), 1 -> 4 -> 1 (cycle), 1 -> 5 -> 3
+        let tree = PendingTaskTree::build(
+            task(1),
+            |id| match *id {
+                1 => (PendingTaskState::Done, vec![task(2), task(4), task(5)]),
+                2 => (PendingTaskState::Done, vec![task(3)]),
+                3 => (PendingTaskState::InProgress, vec![]),
+                4 => (PendingTaskState::Done, vec![task(1)]),
+                5 => (PendingTaskState::Dirty, vec![task(3)]),
+                _ => unreachable!(),
+            },
+            |id| format!("task {}", *id),
+        )
+        .unwrap();
+        // Task 4 has no unfinished descendants, and task 3 is only listed
+        // below the first task that reaches it.
+        assert_eq!(
+            tree.to_string(),
+            "task 1 (done)\n  task 2 (done)\n    task 3 (in progress)\n  task 5 (dirty)\n"
+        );
+    }
+
     #[test]
     fn test_get_name() {
         crate::register();
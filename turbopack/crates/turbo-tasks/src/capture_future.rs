@@ -20,6 +20,7 @@ pin_project! {
         #[pin]
         future: TaskLocalFuture<Arc<Mutex<(Duration, usize, usize)>>, F>,
         duration: Duration,
+        start: Option<Instant>,
         allocations: usize,
         deallocations: usize,
     }
@@ -32,6 +33,7 @@ impl<T, F: Future<Output = T>> CaptureFuture<T, F> {
             future: EXTRA.scope(cell.clone(), future),
             cell,
             duration: Duration::ZERO,
+            start: None,
             allocations: 0,
             deallocations: 0,
         }
@@ -51,11 +53,14 @@ pub fn add_allocation_info(alloc_info: AllocationInfo) {
 }
 
 impl<T, F: Future<Output = T>> Future for CaptureFuture<T, F> {
-    type Output = (T, Duration, usize);
+    /// The result of the future, the time spent in polling it, the time spent
+    /// waiting in between, e.g. for other tasks, and its memory usage.
+    type Output = (T, Duration, Duration, usize);
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.project();
         let start = Instant::now();
+        let first_start = *this.start.get_or_insert(start);
         let start_allocations = TurboMalloc::allocation_counters();
         let result = this.future.poll(cx);
         let elapsed = start.elapsed();
@@ -68,7 +73,9 @@ impl<T, F: Future<Output = T>> Future for CaptureFuture<T, F> {
                 let (duration, allocations, deallocations) = *this.cell.lock().unwrap();
                 let memory_usage = (*this.allocations + allocations)
                     .saturating_sub(*this.deallocations + deallocations);
-                Poll::Ready((r, *this.duration + duration, memory_usage))
+                let self_duration = *this.duration + duration;
+                let wait_duration = first_start.elapsed().saturating_sub(self_duration);
+                Poll::Ready((r, self_duration, wait_duration, memory_usage))
             }
             Poll::Pending => Poll::Pending,
         }
@@ -0,0 +1,131 @@
+use std::{
+    hash::{BuildHasherDefault, Hasher},
+    io::{self, Write},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use dashmap::DashMap;
+use rustc_hash::FxHasher;
+
+use crate::{registry, task::SharedReference, ValueTypeId};
+
+/// Compares the values of two [SharedReference]s of the same value type.
+pub type SharedReferenceEqFn = fn(&SharedReference, &SharedReference) -> bool;
+
+/// The minimum number of entries before the interner removes entries that are
+/// no longer used by any cell.
+const MIN_SWEEP_THRESHOLD: usize = 1024;
+
+/// Interns the content of cells, so cells with equal values share one
+/// allocation. This saves memory when many tasks compute the same values, and
+/// lets the comparison of cells with equal values short-circuit on pointer
+/// equality.
+///
+/// Values are hashed by their serialization, which is streamed into the hasher
+/// without being buffered, so only serializable values that are at least the
+/// configured minimum size when serialized are interned.
+/// Values with the same hash are compared with [PartialEq] before they are
+/// shared.
+///
+/// See [`crate::TurboTasks::set_cell_interning`].
+pub(crate) struct CellInterner {
+    /// The minimum serialized size of interned values, or `usize::MAX` when
+    /// interning is disabled.
+    min_size: AtomicUsize,
+    entries: DashMap<(ValueTypeId, u64), Vec<SharedReference>, BuildHasherDefault<FxHasher>>,
+    len: AtomicUsize,
+    sweep_threshold: AtomicUsize,
+}
+
+impl Default for CellInterner {
+    fn default() -> Self {
+        Self {
+            min_size: AtomicUsize::new(usize::MAX),
+            entries: Default::default(),
+            len: AtomicUsize::new(0),
+            sweep_threshold: AtomicUsize::new(MIN_SWEEP_THRESHOLD),
+        }
+    }
+}
+
+impl CellInterner {
+    pub fn set_min_size(&self, min_size: Option<usize>) {
+        self.min_size
+            .store(min_size.unwrap_or(usize::MAX), Ordering::Release);
+        if min_size.is_none() {
+            self.entries.clear();
+            self.len.store(0, Ordering::Release);
+        }
+    }
+
+    /// Returns an interned [SharedReference] that is equal to `content`
+    /// according to `eq`, or interns and returns `content` when there is none.
+    pub fn intern(
+        &self,
+        ty: ValueTypeId,
+        content: SharedReference,
+        eq: SharedReferenceEqFn,
+    ) -> SharedReference {
+        let min_size = self.min_size.load(Ordering::Acquire);
+        if min_size == usize::MAX {
+            return content;
+        }
+        let Some(hash) = content_hash(ty, &content, min_size) else {
+            return content;
+        };
+        let interned = {
+            let mut entry = self.entries.entry((ty, hash)).or_default();
+            if let Some(interned) = entry.iter().find(|interned| eq(interned, &content)) {
+                interned.clone()
+            } else {
+                entry.push(content.clone());
+                self.len.fetch_add(1, Ordering::AcqRel);
+                content
+            }
+        };
+        if self.len.load(Ordering::Acquire) >= self.sweep_threshold.load(Ordering::Acquire) {
+            self.sweep();
+        }
+        interned
+    }
+
+    /// Removes the values that are only referenced by the interner.
+    fn sweep(&self) {
+        self.entries.retain(|_, values| {
+            values.retain(|value| triomphe::Arc::count(&value.0) > 1);
+            !values.is_empty()
+        });
+        let len = self.entries.iter().map(|entry| entry.len()).sum::<usize>();
+        self.len.store(len, Ordering::Release);
+        self.sweep_threshold
+            .store((len * 2).max(MIN_SWEEP_THRESHOLD), Ordering::Release);
+    }
+}
+
+/// Hashes the serialization of `content`. Returns `None` when the value isn't
+/// serializable or when its serialization is smaller than `min_size`.
+fn content_hash(ty: ValueTypeId, content: &SharedReference, min_size: usize) -> Option<u64> {
+    let serializable = registry::get_value_type(ty).any_as_serializable(&content.0)?;
+    let mut writer = HashWriter::default();
+    serde_json::to_writer(&mut writer, serializable).ok()?;
+    (writer.len >= min_size).then(|| writer.hasher.finish())
+}
+
+/// Hashes the bytes written to it, and counts them.
+#[derive(Default)]
+struct HashWriter {
+    hasher: FxHasher,
+    len: usize,
+}
+
+impl Write for HashWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.hasher.write(buf);
+        self.len += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
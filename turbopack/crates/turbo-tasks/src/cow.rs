@@ -0,0 +1,630 @@
+//! Clone-on-write collections for large cell values.
+//!
+//! Updating a [`State`][crate::State] or creating a new cell from the value of
+//! another one usually clones the whole collection, even when only a single
+//! item is added. [`CowVec`] and [`CowMap`] share their storage between
+//! clones in chunks, so cloning is cheap and an edit only copies the chunk it
+//! touches.
+
+use std::{
+    borrow::Borrow,
+    fmt::{Debug, Formatter},
+    hash::{BuildHasher, BuildHasherDefault, Hash},
+    iter::FlatMap,
+    sync::Arc,
+};
+
+use rustc_hash::{FxHashMap, FxHasher};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    debug::{
+        internal::{json_map_key, PassthroughDebug},
+        value_debug_json_array, value_debug_json_object, ValueDebugFormat, ValueDebugFormatJson,
+        ValueDebugFormatString,
+    },
+    trace::{TraceRawVcs, TraceRawVcsContext},
+    ShrinkToFit,
+};
+
+/// The number of items in a chunk of a [`CowVec`].
+const CHUNK_SIZE: usize = 64;
+
+/// The number of shards of a [`CowMap`].
+const SHARD_COUNT: usize = 16;
+
+/// A vector that shares its items with its clones.
+///
+/// The items are stored in chunks of [`CHUNK_SIZE`] items behind [`Arc`]s.
+/// Cloning only increments a reference count, and editing a clone copies the
+/// list of chunks and the edited chunk, but not the other items.
+pub struct CowVec<T> {
+    /// All chunks but the last one are full.
+    chunks: Arc<Vec<Arc<Vec<T>>>>,
+    len: usize,
+}
+
+impl<T> CowVec<T> {
+    pub fn new() -> Self {
+        Self {
+            chunks: Arc::new(Vec::new()),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.chunks
+            .get(index / CHUNK_SIZE)
+            .and_then(|chunk| chunk.get(index % CHUNK_SIZE))
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.chunks.last().and_then(|chunk| chunk.last())
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let chunk_iter: ChunkIterFn<'_, T> = |chunk| chunk.iter();
+        Iter {
+            inner: self.chunks.iter().flat_map(chunk_iter),
+            remaining: self.len,
+        }
+    }
+
+    pub fn contains(&self, item: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|i| i == item)
+    }
+
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Clone> CowVec<T> {
+    pub fn push(&mut self, item: T) {
+        let chunks = Arc::make_mut(&mut self.chunks);
+        match chunks.last_mut() {
+            Some(chunk) if chunk.len() < CHUNK_SIZE => Arc::make_mut(chunk).push(item),
+            _ => {
+                let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+                chunk.push(item);
+                chunks.push(Arc::new(chunk));
+            }
+        }
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let chunks = Arc::make_mut(&mut self.chunks);
+        let chunk = chunks.last_mut()?;
+        let item = Arc::make_mut(chunk).pop();
+        if chunk.is_empty() {
+            chunks.pop();
+        }
+        self.len -= 1;
+        item
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        let chunk = &mut Arc::make_mut(&mut self.chunks)[index / CHUNK_SIZE];
+        Some(&mut Arc::make_mut(chunk)[index % CHUNK_SIZE])
+    }
+
+    /// Appends the items of `other`. When this vector ends at a chunk
+    /// boundary, the chunks of `other` are shared instead of copied.
+    pub fn append_shared(&mut self, other: &Self) {
+        if self.len % CHUNK_SIZE == 0 {
+            Arc::make_mut(&mut self.chunks).extend(other.chunks.iter().cloned());
+            self.len += other.len;
+        } else {
+            self.extend(other.iter().cloned());
+        }
+    }
+}
+
+impl<T> Default for CowVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for CowVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            chunks: self.chunks.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T: Debug> Debug for CowVec<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for CowVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.chunks, &other.chunks)
+            || (self.len == other.len && self.iter().eq(other.iter()))
+    }
+}
+
+impl<T: Eq> Eq for CowVec<T> {}
+
+impl<T: Hash> Hash for CowVec<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for item in self.iter() {
+            item.hash(state);
+        }
+    }
+}
+
+impl<T: Clone> Extend<T> for CowVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a> Extend<&'a T> for CowVec<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T: Clone> FromIterator<T> for CowVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T: Clone> From<Vec<T>> for CowVec<T> {
+    fn from(items: Vec<T>) -> Self {
+        items.into_iter().collect()
+    }
+}
+
+impl<T: Clone> From<CowVec<T>> for Vec<T> {
+    fn from(items: CowVec<T>) -> Self {
+        items.into_iter().collect()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CowVec<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Clone> IntoIterator for CowVec<T> {
+    type Item = T;
+    type IntoIter = FlatMap<std::vec::IntoIter<Arc<Vec<T>>>, Vec<T>, fn(Arc<Vec<T>>) -> Vec<T>>;
+
+    /// Moves the items out of the chunks that are not shared, and clones the
+    /// others.
+    fn into_iter(self) -> Self::IntoIter {
+        Arc::unwrap_or_clone(self.chunks)
+            .into_iter()
+            .flat_map(Arc::unwrap_or_clone as fn(_) -> _)
+    }
+}
+
+type ChunkIterFn<'a, T> = fn(&'a Arc<Vec<T>>) -> std::slice::Iter<'a, T>;
+
+/// An iterator over the items of a [`CowVec`].
+pub struct Iter<'a, T> {
+    inner: FlatMap<std::slice::Iter<'a, Arc<Vec<T>>>, std::slice::Iter<'a, T>, ChunkIterFn<'a, T>>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next()?;
+        self.remaining -= 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next_back()?;
+        self.remaining -= 1;
+        Some(item)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T: Serialize> Serialize for CowVec<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Clone> Deserialize<'de> for CowVec<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<T>::deserialize(deserializer)?.into())
+    }
+}
+
+impl<T: TraceRawVcs> TraceRawVcs for CowVec<T> {
+    fn trace_raw_vcs(&self, trace_context: &mut TraceRawVcsContext) {
+        for item in self.iter() {
+            TraceRawVcs::trace_raw_vcs(item, trace_context);
+        }
+    }
+}
+
+impl<T> ShrinkToFit for CowVec<T> {
+    /// Only shrinks the storage that is not shared with other clones, since
+    /// shrinking shared storage would copy it.
+    fn shrink_to_fit(&mut self) {
+        if let Some(chunks) = Arc::get_mut(&mut self.chunks) {
+            chunks.shrink_to_fit();
+            if let Some(chunk) = chunks.last_mut().and_then(Arc::get_mut) {
+                chunk.shrink_to_fit();
+            }
+        }
+    }
+}
+
+impl<T: ValueDebugFormat> ValueDebugFormat for CowVec<T> {
+    fn value_debug_format(&self, depth: usize) -> ValueDebugFormatString {
+        if depth == 0 {
+            return ValueDebugFormatString::Sync(std::any::type_name::<Self>().to_string());
+        }
+
+        let values = self
+            .iter()
+            .map(|value| value.value_debug_format(depth.saturating_sub(1)))
+            .collect::<Vec<_>>();
+
+        ValueDebugFormatString::Async(Box::pin(async move {
+            let mut values_string = Vec::with_capacity(values.len());
+            for value in values {
+                values_string.push(PassthroughDebug::new_string(match value {
+                    ValueDebugFormatString::Sync(string) => string,
+                    ValueDebugFormatString::Async(future) => future.await?,
+                }));
+            }
+            Ok(format!("{:#?}", values_string))
+        }))
+    }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        value_debug_json_array(std::any::type_name::<Self>(), self.iter(), depth)
+    }
+}
+
+/// A hash map that shares its entries with its clones.
+///
+/// The entries are distributed by their hash over [`SHARD_COUNT`] maps behind
+/// [`Arc`]s. Cloning only increments a reference count, and editing a clone
+/// copies the edited shard, but not the other entries.
+pub struct CowMap<K, V> {
+    /// Empty until the first entry is inserted, so empty maps don't allocate.
+    shards: Arc<Vec<Arc<FxHashMap<K, V>>>>,
+    len: usize,
+}
+
+impl<K, V> CowMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            shards: Arc::new(Vec::new()),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.shards.iter().flat_map(|shard| shard.iter())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+fn shard_index<Q: Hash + ?Sized>(key: &Q) -> usize {
+    // The low and high bits are used by the shard itself, so the shard is
+    // selected by the bits in between.
+    (BuildHasherDefault::<FxHasher>::default().hash_one(key) >> 32) as usize % SHARD_COUNT
+}
+
+impl<K: Hash + Eq, V> CowMap<K, V> {
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shards.get(shard_index(key))?.get(key)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> CowMap<K, V> {
+    fn shard_mut<Q: Hash + ?Sized>(&mut self, key: &Q) -> &mut FxHashMap<K, V> {
+        let shards = Arc::make_mut(&mut self.shards);
+        if shards.is_empty() {
+            shards.resize_with(SHARD_COUNT, Default::default);
+        }
+        Arc::make_mut(&mut shards[shard_index(key)])
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let previous = self.shard_mut(&key).insert(key, value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        // Avoid copying the shard when the key is not in the map.
+        if !self.contains_key(key) {
+            return None;
+        }
+        let removed = self.shard_mut(key).remove(key);
+        self.len -= 1;
+        removed
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !self.contains_key(key) {
+            return None;
+        }
+        self.shard_mut(key).get_mut(key)
+    }
+}
+
+impl<K, V> Default for CowMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Clone for CowMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            shards: self.shards.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for CowMap<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for CowMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shards, &other.shards)
+            || (self.len == other.len
+                && self
+                    .iter()
+                    .all(|(key, value)| other.get(key) == Some(value)))
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for CowMap<K, V> {}
+
+impl<K: Hash + Eq + Clone, V: Clone> Extend<(K, V)> for CowMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> FromIterator<(K, V)> for CowMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for CowMap<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for CowMap<K, V>
+where
+    K: Deserialize<'de> + Hash + Eq + Clone,
+    V: Deserialize<'de> + Clone,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(FxHashMap::<K, V>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+impl<K: TraceRawVcs, V: TraceRawVcs> TraceRawVcs for CowMap<K, V> {
+    fn trace_raw_vcs(&self, trace_context: &mut TraceRawVcsContext) {
+        for (key, value) in self.iter() {
+            TraceRawVcs::trace_raw_vcs(key, trace_context);
+            TraceRawVcs::trace_raw_vcs(value, trace_context);
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> ShrinkToFit for CowMap<K, V> {
+    /// Only shrinks the shards that are not shared with other clones, since
+    /// shrinking shared shards would copy them.
+    fn shrink_to_fit(&mut self) {
+        if let Some(shards) = Arc::get_mut(&mut self.shards) {
+            for shard in shards.iter_mut().filter_map(Arc::get_mut) {
+                shard.shrink_to_fit();
+            }
+        }
+    }
+}
+
+impl<K: Debug, V: ValueDebugFormat> ValueDebugFormat for CowMap<K, V> {
+    fn value_debug_format(&self, depth: usize) -> ValueDebugFormatString {
+        if depth == 0 {
+            return ValueDebugFormatString::Sync(std::any::type_name::<Self>().to_string());
+        }
+
+        let values = self
+            .iter()
+            .map(|(key, value)| {
+                (
+                    format!("{:#?}", key),
+                    value.value_debug_format(depth.saturating_sub(1)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        ValueDebugFormatString::Async(Box::pin(async move {
+            let mut values_string = std::collections::HashMap::new();
+            for (key, value) in values {
+                values_string.insert(
+                    key,
+                    PassthroughDebug::new_string(match value {
+                        ValueDebugFormatString::Sync(string) => string,
+                        ValueDebugFormatString::Async(future) => future.await?,
+                    }),
+                );
+            }
+            Ok(format!("{:#?}", values_string))
+        }))
+    }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        value_debug_json_object(
+            std::any::type_name::<Self>(),
+            self.iter()
+                .map(|(key, value)| (json_map_key(format!("{:?}", key)), value)),
+            depth,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cow_vec_clones_share_chunks() {
+        let mut vec = (0..200).collect::<CowVec<_>>();
+        let clone = vec.clone();
+        vec.push(200);
+        *vec.get_mut(10).unwrap() = 1000;
+
+        assert_eq!(vec.len(), 201);
+        assert_eq!(vec.get(10), Some(&1000));
+        assert_eq!(vec.last(), Some(&200));
+        assert_eq!(clone.len(), 200);
+        assert_eq!(clone.get(10), Some(&10));
+        assert_eq!(clone.last(), Some(&199));
+        // Only the edited chunks are copied.
+        assert!(Arc::ptr_eq(&vec.chunks[1], &clone.chunks[1]));
+        assert!(!Arc::ptr_eq(&vec.chunks[0], &clone.chunks[0]));
+    }
+
+    #[test]
+    fn cow_vec_append_shared() {
+        let mut vec = (0..CHUNK_SIZE).collect::<CowVec<_>>();
+        let other = (0..100).collect::<CowVec<_>>();
+        vec.append_shared(&other);
+
+        assert_eq!(vec.len(), CHUNK_SIZE + 100);
+        assert!(Arc::ptr_eq(&vec.chunks[1], &other.chunks[0]));
+        assert!(vec.iter().copied().eq((0..CHUNK_SIZE).chain(0..100)));
+        assert_eq!(vec.iter().rev().next(), Some(&99));
+        assert_eq!(vec.pop(), Some(99));
+        assert_eq!(other.last(), Some(&99));
+    }
+
+    #[test]
+    fn cow_map_clones_share_shards() {
+        let mut map = (0..100).map(|i| (i, i)).collect::<CowMap<_, _>>();
+        let clone = map.clone();
+        map.insert(0, 1000);
+        assert_eq!(map.remove(&1), Some(1));
+        assert_eq!(map.remove(&1), None);
+
+        assert_eq!(map.len(), 99);
+        assert_eq!(map.get(&0), Some(&1000));
+        assert_eq!(clone.len(), 100);
+        assert_eq!(clone.get(&0), Some(&0));
+        assert_eq!(clone.get(&1), Some(&1));
+        assert_ne!(map, clone);
+        let shared = map
+            .shards
+            .iter()
+            .zip(clone.shards.iter())
+            .filter(|(a, b)| Arc::ptr_eq(a, b))
+            .count();
+        assert!(shared >= SHARD_COUNT - 2);
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use rustc_hash::FxHashMap;
+
+use crate::{
+    manager::{try_current_task_id, TurboTasksApi},
+    TaskId,
+};
+
+/// The tasks that are currently waiting for other tasks to finish, used to
+/// detect tasks that transitively wait for themselves. Such a read would
+/// never complete.
+///
+/// See [`crate::TurboTasks::set_cycle_detection`].
+#[derive(Default)]
+pub(crate) struct WaitGraph {
+    /// Maps a waiting task to the tasks it waits for, with the number of
+    /// pending reads of each.
+    edges: Mutex<FxHashMap<TaskId, FxHashMap<TaskId, usize>>>,
+}
+
+impl WaitGraph {
+    /// Records that `reader` waits for `task`. When `task` is transitively
+    /// waiting for `reader`, nothing is recorded and the cycle is returned,
+    /// starting and ending with `reader`.
+    pub fn start_waiting(&self, reader: TaskId, task: TaskId) -> Result<(), Vec<TaskId>> {
+        let mut edges = self.edges.lock().unwrap();
+        if let Some(path) = find_path(&edges, task, reader) {
+            let mut cycle = Vec::with_capacity(path.len() + 1);
+            cycle.push(reader);
+            cycle.extend(path);
+            return Err(cycle);
+        }
+        *edges.entry(reader).or_default().entry(task).or_default() += 1;
+        Ok(())
+    }
+
+    pub fn stop_waiting(&self, reader: TaskId, task: TaskId) {
+        let mut edges = self.edges.lock().unwrap();
+        let Some(tasks) = edges.get_mut(&reader) else {
+            return;
+        };
+        if let Some(count) = tasks.get_mut(&task) {
+            *count -= 1;
+            if *count == 0 {
+                tasks.remove(&task);
+            }
+        }
+        if tasks.is_empty() {
+            edges.remove(&reader);
+        }
+    }
+}
+
+/// Returns the path of waiting tasks from `from` to `to`, including both.
+fn find_path(
+    edges: &FxHashMap<TaskId, FxHashMap<TaskId, usize>>,
+    from: TaskId,
+    to: TaskId,
+) -> Option<Vec<TaskId>> {
+    if from == to {
+        return Some(vec![from]);
+    }
+    let mut parents = FxHashMap::default();
+    let mut stack = vec![from];
+    while let Some(task) = stack.pop() {
+        let Some(next_tasks) = edges.get(&task) else {
+            continue;
+        };
+        for &next in next_tasks.keys() {
+            if next == from || parents.contains_key(&next) {
+                continue;
+            }
+            parents.insert(next, task);
+            if next == to {
+                let mut path = vec![to];
+                let mut current = to;
+                while let Some(&parent) = parents.get(&current) {
+                    path.push(parent);
+                    current = parent;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            stack.push(next);
+        }
+    }
+    None
+}
+
+/// Records that the current task waits for another task until it's stopped or
+/// dropped.
+pub(crate) struct WaitingGuard {
+    turbo_tasks: Arc<dyn TurboTasksApi>,
+    reader: TaskId,
+    task: TaskId,
+    stopped: bool,
+}
+
+impl WaitingGuard {
+    /// Returns an error when the current task would wait for itself. Returns
+    /// `None` when nothing was recorded, see [`TurboTasksApi::start_waiting`],
+    /// or when not called from a task.
+    pub fn new(turbo_tasks: &dyn TurboTasksApi, task: TaskId) -> Result<Option<Self>> {
+        let Some(reader) = try_current_task_id() else {
+            return Ok(None);
+        };
+        Ok(turbo_tasks
+            .start_waiting(reader, task)?
+            .then(|| WaitingGuard {
+                turbo_tasks: turbo_tasks.pin(),
+                reader,
+                task,
+                stopped: false,
+            }))
+    }
+
+    /// Removes the wait, and waits until the current task may continue when
+    /// the task concurrency is limited.
+    pub async fn stop(mut self) {
+        self.stopped = true;
+        if let Some(resumed) = self.turbo_tasks.stop_waiting(self.reader, self.task) {
+            // The sender was dropped with the turbo tasks instance, there is
+            // nothing left to limit
+            let _ = resumed.await;
+        }
+    }
+}
+
+impl Drop for WaitingGuard {
+    fn drop(&mut self) {
+        if !self.stopped {
+            self.turbo_tasks.stop_waiting(self.reader, self.task);
+        }
+    }
+}
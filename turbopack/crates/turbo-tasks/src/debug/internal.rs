@@ -1,6 +1,7 @@
-use std::{borrow::Cow, future::Future};
+use std::{borrow::Cow, future::Future, pin::Pin};
 
 use futures::future::join_all;
+use serde_json::Value as JsonValue;
 pub use turbo_tasks_macros::ValueDebug;
 
 /// Representation of a named field of a structure for formatting purposes of
@@ -17,6 +18,10 @@ impl<'a> FormattingField<'a> {
     }
 }
 
+/// The formatted contents of a field, boxed so redacted and regular fields can
+/// be collected into the same `Vec`.
+pub type FormattingFieldFuture<'a> = Pin<Box<dyn Future<Output = String> + Send + 'a>>;
+
 /// Representation of a named field of a structure for formatting purposes of
 /// `ValueDebug` implementations.
 #[derive(Debug)]
@@ -127,3 +132,74 @@ impl std::fmt::Debug for PassthroughDebug<'_> {
         f.write_str(self.0.as_ref())
     }
 }
+
+/// The JSON of a field, boxed so redacted and regular fields can be collected
+/// into the same `Vec`.
+pub type JsonFieldFuture<'a> = Pin<Box<dyn Future<Output = JsonValue> + Send + 'a>>;
+
+/// The JSON representation of a struct or enum variant with named fields for
+/// `ValueDebugFormat::value_debug_json` implementations.
+pub async fn json_struct_named(
+    name: &str,
+    fields: Vec<(&str, impl Future<Output = JsonValue>)>,
+) -> JsonValue {
+    let (names, values): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+    let mut object = serde_json::Map::with_capacity(names.len() + 1);
+    object.insert("$type".to_string(), json_type_name(name).into());
+    for (name, value) in names.into_iter().zip(join_all(values).await) {
+        object.insert(name.to_string(), value);
+    }
+    JsonValue::Object(object)
+}
+
+/// The JSON representation of a struct or enum variant with unnamed fields
+/// for `ValueDebugFormat::value_debug_json` implementations.
+pub async fn json_struct_unnamed(
+    name: &str,
+    fields: Vec<impl Future<Output = JsonValue>>,
+) -> JsonValue {
+    let mut object = serde_json::Map::with_capacity(2);
+    object.insert("$type".to_string(), json_type_name(name).into());
+    if !fields.is_empty() {
+        object.insert("fields".to_string(), join_all(fields).await.into());
+    }
+    JsonValue::Object(object)
+}
+
+/// The JSON representation of a unit struct or enum variant, or of a struct
+/// whose fields are all ignored.
+pub fn json_struct_unit(name: &str) -> JsonValue {
+    serde_json::json!({ "$type": json_type_name(name) })
+}
+
+/// The placeholder for a value that is past the depth limit.
+pub fn truncated_json(name: &str) -> JsonValue {
+    serde_json::json!({
+        "$type": json_type_name(name),
+        "$truncated": true,
+    })
+}
+
+/// `stringify!` separates path segments with spaces, e.g. `Enum :: None`.
+fn json_type_name(name: &str) -> String {
+    name.replace(" :: ", "::")
+}
+
+/// Converts a debug string into JSON. Scalars, like numbers, booleans and
+/// quoted strings, are converted to the JSON value they represent, anything
+/// else is kept as a string.
+pub(crate) fn debug_string_to_json(string: String) -> JsonValue {
+    match serde_json::from_str::<JsonValue>(&string) {
+        Ok(value) if !value.is_object() && !value.is_array() => value,
+        _ => JsonValue::String(string),
+    }
+}
+
+/// Converts the debug string of a map key into a JSON object key, removing the
+/// quotes of string keys.
+pub(crate) fn json_map_key(string: String) -> String {
+    match serde_json::from_str::<String>(&string) {
+        Ok(key) => key,
+        Err(_) => string,
+    }
+}
@@ -1,6 +1,7 @@
 use std::fmt::{Debug, Display};
 
 use auto_hash_map::{AutoMap, AutoSet};
+use serde_json::Value as JsonValue;
 use turbo_tasks::{FxIndexMap, FxIndexSet, Vc};
 pub use turbo_tasks_macros::ValueDebugFormat;
 
@@ -10,7 +11,7 @@ use crate::{self as turbo_tasks};
 pub mod internal;
 mod vdbg;
 
-use internal::PassthroughDebug;
+use internal::{debug_string_to_json, json_map_key, truncated_json, PassthroughDebug};
 
 /// The return type of [`ValueDebug::dbg`].
 ///
@@ -45,6 +46,22 @@ impl ValueDebugString {
     }
 }
 
+/// The return type of [`ValueDebug::dbg_json`].
+#[turbo_tasks::value]
+pub struct ValueDebugJson(JsonValue);
+
+impl ValueDebugJson {
+    /// Create a new `ValueDebugJson` from a JSON value.
+    pub fn new(value: JsonValue) -> Vc<Self> {
+        ValueDebugJson::cell(ValueDebugJson(value))
+    }
+
+    /// Returns the underlying JSON value.
+    pub fn as_json(&self) -> &JsonValue {
+        &self.0
+    }
+}
+
 /// [`Debug`]-like trait for [`Vc`] types, automatically derived when using
 /// [`macro@turbo_tasks::value`] and [`turbo_tasks::value_trait`].
 ///
@@ -59,6 +76,16 @@ pub trait ValueDebug {
 
     /// Like `dbg`, but with a depth limit.
     fn dbg_depth(self: Vc<Self>, depth: usize) -> Vc<ValueDebugString>;
+
+    /// Like `dbg_depth`, but returns a JSON tree for machine consumption, e.g.
+    /// to render expandable trees in devtools.
+    ///
+    /// Structs and enum variants are objects with a `$type` key, and their
+    /// fields as keys (or as a `fields` array for unnamed fields). Values past
+    /// the depth limit are replaced by placeholders: `{ "$type": ...,
+    /// "$truncated": true }` for values and `{ "$vc": ... }` for `Vc`
+    /// references.
+    fn dbg_json(self: Vc<Self>, depth: usize) -> Vc<ValueDebugJson>;
 }
 
 /// Use [autoref specialization] to implement [`ValueDebug`] for `T: Debug`.
@@ -66,12 +93,44 @@ pub trait ValueDebug {
 /// [autoref specialization]: https://github.com/dtolnay/case-studies/blob/master/autoref-specialization/README.md
 pub trait ValueDebugFormat {
     fn value_debug_format(&self, depth: usize) -> ValueDebugFormatString;
+
+    /// The structured counterpart of `value_debug_format`, see
+    /// [`ValueDebug::dbg_json`].
+    ///
+    /// Defaults to the debug string, or to the JSON literal it represents for
+    /// scalars like numbers and booleans.
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        match self.value_debug_format(depth) {
+            ValueDebugFormatString::Sync(string) => {
+                ValueDebugFormatJson::Sync(debug_string_to_json(string))
+            }
+            ValueDebugFormatString::Async(future) => {
+                ValueDebugFormatJson::Async(Box::pin(async move {
+                    Ok(debug_string_to_json(future.await?))
+                }))
+            }
+        }
+    }
 }
 
 impl ValueDebugFormat for String {
     fn value_debug_format(&self, _depth: usize) -> ValueDebugFormatString {
         ValueDebugFormatString::Sync(format!("{:#?}", self))
     }
+
+    fn value_debug_json(&self, _depth: usize) -> ValueDebugFormatJson {
+        ValueDebugFormatJson::Sync(JsonValue::String(self.clone()))
+    }
+}
+
+impl ValueDebugFormat for JsonValue {
+    fn value_debug_format(&self, _depth: usize) -> ValueDebugFormatString {
+        ValueDebugFormatString::Sync(format!("{:#}", self))
+    }
+
+    fn value_debug_json(&self, _depth: usize) -> ValueDebugFormatJson {
+        ValueDebugFormatJson::Sync(self.clone())
+    }
 }
 
 // Use autoref specialization [1] to implement `ValueDebugFormat` for `T:
@@ -117,6 +176,17 @@ where
             },
         }
     }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        if depth == 0 {
+            return ValueDebugFormatJson::Sync(truncated_json(std::any::type_name::<Self>()));
+        }
+
+        match self {
+            None => ValueDebugFormatJson::Sync(JsonValue::Null),
+            Some(value) => value.value_debug_json(depth.saturating_sub(1)),
+        }
+    }
 }
 
 impl<T> ValueDebugFormat for Vec<T>
@@ -148,6 +218,10 @@ where
             Ok(format!("{:#?}", values_string))
         }))
     }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        value_debug_json_array(std::any::type_name::<Self>(), self.iter(), depth)
+    }
 }
 
 impl<K> ValueDebugFormat for AutoSet<K>
@@ -179,6 +253,10 @@ where
             Ok(format!("{:#?}", values_string))
         }))
     }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        value_debug_json_array(std::any::type_name::<Self>(), self.iter(), depth)
+    }
 }
 
 impl<K, V> ValueDebugFormat for std::collections::HashMap<K, V>
@@ -216,6 +294,15 @@ where
             Ok(format!("{:#?}", values_string))
         }))
     }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        value_debug_json_object(
+            std::any::type_name::<Self>(),
+            self.iter()
+                .map(|(key, value)| (json_map_key(format!("{:?}", key)), value)),
+            depth,
+        )
+    }
 }
 
 impl<K, V> ValueDebugFormat for AutoMap<K, V>
@@ -253,6 +340,15 @@ where
             Ok(format!("{:#?}", values_string))
         }))
     }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        value_debug_json_object(
+            std::any::type_name::<Self>(),
+            self.iter()
+                .map(|(key, value)| (json_map_key(format!("{:?}", key)), value)),
+            depth,
+        )
+    }
 }
 
 impl<T> ValueDebugFormat for FxIndexSet<T>
@@ -281,6 +377,10 @@ where
             Ok(format!("{:#?}", values_string))
         }))
     }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        value_debug_json_array(std::any::type_name::<Self>(), self.iter(), depth)
+    }
 }
 
 impl<K, V> ValueDebugFormat for FxIndexMap<K, V>
@@ -322,6 +422,34 @@ where
             Ok(format!("{:#?}", values_string))
         }))
     }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        if depth == 0 {
+            return ValueDebugFormatJson::Sync(truncated_json(std::any::type_name::<Self>()));
+        }
+
+        let values = self
+            .iter()
+            .map(|(key, value)| {
+                (
+                    key.value_debug_json(depth.saturating_sub(1)),
+                    value.value_debug_json(depth.saturating_sub(1)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        ValueDebugFormatJson::Async(Box::pin(async move {
+            let mut object = serde_json::Map::with_capacity(values.len());
+            for (key, value) in values {
+                let key = match key.try_to_json().await? {
+                    JsonValue::String(key) => key,
+                    key => key.to_string(),
+                };
+                object.insert(key, value.try_to_json().await?);
+            }
+            Ok(JsonValue::Object(object))
+        }))
+    }
 }
 
 macro_rules! tuple_impls {
@@ -342,6 +470,20 @@ macro_rules! tuple_impls {
                     Ok(format!("{:#?}", values))
                 }))
             }
+
+            #[allow(non_snake_case)]
+            fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+                if depth == 0 {
+                    return ValueDebugFormatJson::Sync(truncated_json(std::any::type_name::<Self>()));
+                }
+
+                let ($($name,)+) = self;
+                let ($($name,)+) = ($($name.value_debug_json(depth.saturating_sub(1)),)+);
+
+                ValueDebugFormatJson::Async(Box::pin(async move {
+                    Ok(JsonValue::Array(vec![$($name.try_to_json().await?,)+]))
+                }))
+            }
         }
     };
 }
@@ -359,6 +501,56 @@ tuple_impls! { A B C D E F G H I J }
 tuple_impls! { A B C D E F G H I J K }
 tuple_impls! { A B C D E F G H I J K L }
 
+pub(crate) fn value_debug_json_array<'a, T>(
+    type_name: &str,
+    values: impl Iterator<Item = &'a T>,
+    depth: usize,
+) -> ValueDebugFormatJson<'a>
+where
+    T: ValueDebugFormat + 'a,
+{
+    if depth == 0 {
+        return ValueDebugFormatJson::Sync(truncated_json(type_name));
+    }
+
+    let values = values
+        .map(|value| value.value_debug_json(depth.saturating_sub(1)))
+        .collect::<Vec<_>>();
+
+    ValueDebugFormatJson::Async(Box::pin(async move {
+        let mut array = Vec::with_capacity(values.len());
+        for value in values {
+            array.push(value.try_to_json().await?);
+        }
+        Ok(JsonValue::Array(array))
+    }))
+}
+
+pub(crate) fn value_debug_json_object<'a, V>(
+    type_name: &str,
+    entries: impl Iterator<Item = (String, &'a V)>,
+    depth: usize,
+) -> ValueDebugFormatJson<'a>
+where
+    V: ValueDebugFormat + 'a,
+{
+    if depth == 0 {
+        return ValueDebugFormatJson::Sync(truncated_json(type_name));
+    }
+
+    let entries = entries
+        .map(|(key, value)| (key, value.value_debug_json(depth.saturating_sub(1))))
+        .collect::<Vec<_>>();
+
+    ValueDebugFormatJson::Async(Box::pin(async move {
+        let mut object = serde_json::Map::with_capacity(entries.len());
+        for (key, value) in entries {
+            object.insert(key, value.try_to_json().await?);
+        }
+        Ok(JsonValue::Object(object))
+    }))
+}
+
 /// Output of `ValueDebugFormat::value_debug_format`.
 pub enum ValueDebugFormatString<'a> {
     /// For the `T: Debug` fallback implementation, we can output a string
@@ -390,3 +582,33 @@ impl<'a> ValueDebugFormatString<'a> {
         Ok(ValueDebugString::new(self.try_to_string().await?))
     }
 }
+
+/// Output of `ValueDebugFormat::value_debug_json`, see
+/// [`ValueDebugFormatString`].
+pub enum ValueDebugFormatJson<'a> {
+    Sync(JsonValue),
+    Async(
+        core::pin::Pin<
+            Box<dyn std::future::Future<Output = anyhow::Result<JsonValue>> + Send + 'a>,
+        >,
+    ),
+}
+
+impl<'a> ValueDebugFormatJson<'a> {
+    /// Convert the `ValueDebugFormatJson` into a JSON value.
+    ///
+    /// This can fail when resolving `Vc` types.
+    pub async fn try_to_json(self) -> anyhow::Result<JsonValue> {
+        Ok(match self {
+            ValueDebugFormatJson::Sync(value) => value,
+            ValueDebugFormatJson::Async(future) => future.await?,
+        })
+    }
+
+    /// Convert the `ValueDebugFormatJson` into a `Vc<ValueDebugJson>`.
+    ///
+    /// This can fail when resolving `Vc` types.
+    pub async fn try_to_value_debug_json(self) -> anyhow::Result<Vc<ValueDebugJson>> {
+        Ok(ValueDebugJson::new(self.try_to_json().await?))
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    any::{Any, TypeId},
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::manager::try_with_execution_locals;
+
+/// The values that are local to an execution of a task, by their type.
+pub(crate) type ExecutionLocals = FxHashMap<TypeId, Arc<dyn Any + Send + Sync>>;
+
+/// Attaches `value` to the current execution of the current task, e.g. the
+/// route or request that is being processed, so tracing or diagnostics can
+/// read it with [`with_execution_local`] anywhere in the execution, across
+/// awaits and in local tasks, without passing it as an argument.
+///
+/// Execution locals are not inputs of the task: they are neither part of its
+/// cache key nor visible to the tasks it calls, and they are cleared when the
+/// task is executed again. So they must not influence the result of the task,
+/// or the cached result would depend on whatever was set by the execution
+/// that computed it.
+///
+/// There is one value per type. The value is replaced until the returned
+/// guard is dropped, which restores the previous value.
+///
+/// Panics when called outside of a task execution.
+pub fn set_execution_local<T: Any + Send + Sync>(value: T) -> ExecutionLocalGuard<T> {
+    let previous = with_execution_locals("set_execution_local", |locals| {
+        locals.insert(TypeId::of::<T>(), Arc::new(value))
+    });
+    ExecutionLocalGuard {
+        previous,
+        _type: PhantomData,
+    }
+}
+
+/// Calls `f` with the value of type `T` that is attached to the current
+/// execution with [`set_execution_local`], if any.
+///
+/// Panics when called outside of a task execution.
+pub fn with_execution_local<T: Any + Send + Sync, R>(f: impl FnOnce(Option<&T>) -> R) -> R {
+    // The value is read outside of the lock of the task state, so `f` can use
+    // turbo-tasks.
+    let value = with_execution_locals("with_execution_local", |locals| {
+        locals.get(&TypeId::of::<T>()).cloned()
+    });
+    f(value.as_deref().and_then(|value| value.downcast_ref()))
+}
+
+fn with_execution_locals<R>(from: &str, f: impl FnOnce(&mut ExecutionLocals) -> R) -> R {
+    try_with_execution_locals(f).unwrap_or_else(|| {
+        panic!("{from} can only be used in the context of turbo_tasks task execution")
+    })
+}
+
+/// Restores the previous execution local of type `T` when dropped, see
+/// [`set_execution_local`]. It needs to be dropped in the same execution.
+#[must_use]
+pub struct ExecutionLocalGuard<T: Any + Send + Sync> {
+    previous: Option<Arc<dyn Any + Send + Sync>>,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T: Any + Send + Sync> Drop for ExecutionLocalGuard<T> {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        // The execution has ended already when the guard outlived it.
+        try_with_execution_locals(|locals| match previous {
+            Some(previous) => {
+                locals.insert(TypeId::of::<T>(), previous);
+            }
+            None => {
+                locals.remove(&TypeId::of::<T>());
+            }
+        });
+    }
+}
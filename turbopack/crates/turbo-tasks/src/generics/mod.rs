@@ -0,0 +1,10 @@
+//! Value types that are generic over the `Vc`s they contain, so `Vc<Option<ResolvedVc<T>>>` can
+//! be used instead of declaring a transparent wrapper type for each `T`. Cells should only contain
+//! resolved `Vc`s, so only `ResolvedVc`s are supported.
+//!
+//! All instances of a generic type share a single registered value type, its representation with
+//! `()` in place of the type parameters. Helpers that don't depend on `T` are turbo-tasks
+//! functions on that representation.
+
+pub(crate) mod option;
+pub(crate) mod result;
@@ -0,0 +1,76 @@
+use anyhow::Result;
+// This specific macro identifier is detected by turbo-tasks-build.
+use turbo_tasks_macros::generic_type as __turbo_tasks_internal_generic_type;
+
+use crate::{self as turbo_tasks, ResolvedVc, ShrinkToFit, Vc};
+
+__turbo_tasks_internal_generic_type!(<T>, Option<ResolvedVc<T>>);
+
+impl<T> ShrinkToFit for Option<ResolvedVc<T>>
+where
+    T: Send,
+{
+    fn shrink_to_fit(&mut self) {}
+}
+
+#[turbo_tasks::function]
+fn option_none() -> Vc<Option<ResolvedVc<()>>> {
+    Vc::cell(None)
+}
+
+#[turbo_tasks::function]
+async fn option_is_some(option: Vc<Option<ResolvedVc<()>>>) -> Result<Vc<bool>> {
+    Ok(Vc::cell(option.await?.is_some()))
+}
+
+#[turbo_tasks::function]
+async fn option_is_none(option: Vc<Option<ResolvedVc<()>>>) -> Result<Vc<bool>> {
+    Ok(Vc::cell(option.await?.is_none()))
+}
+
+impl<T> Vc<Option<ResolvedVc<T>>>
+where
+    T: Send + 'static,
+{
+    /// Returns a cell that contains `None`. It is shared by all `T`.
+    pub fn none() -> Self {
+        // Safety: `None` is a valid value for every `T`.
+        unsafe { Self::from_repr(option_none()) }
+    }
+
+    /// Returns a cell that contains `Some(vc)`.
+    pub fn some(vc: ResolvedVc<T>) -> Self {
+        Vc::cell(Some(vc))
+    }
+
+    /// See [`Option::is_some`]. Unlike awaiting the option, a task reading the
+    /// returned `Vc` is not invalidated when the contained `Vc` changes.
+    pub fn is_some(self) -> Vc<bool> {
+        option_is_some(Self::to_repr(self))
+    }
+
+    /// See [`Option::is_none`].
+    pub fn is_none(self) -> Vc<bool> {
+        option_is_none(Self::to_repr(self))
+    }
+
+    /// See [`Option::map`].
+    pub async fn map<U>(
+        self,
+        f: impl FnOnce(ResolvedVc<T>) -> ResolvedVc<U>,
+    ) -> Result<Vc<Option<ResolvedVc<U>>>>
+    where
+        U: Send + 'static,
+    {
+        Ok(Vc::cell(self.await?.map(f)))
+    }
+
+    /// Returns the contained `Vc`, or the `Vc` returned by `f` when the
+    /// option is `None`.
+    pub async fn unwrap_or_else_vc(self, f: impl FnOnce() -> Vc<T>) -> Result<Vc<T>> {
+        Ok(match *self.await? {
+            Some(vc) => *vc,
+            None => f(),
+        })
+    }
+}
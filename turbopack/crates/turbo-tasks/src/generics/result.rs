@@ -0,0 +1,79 @@
+use anyhow::Result;
+// This specific macro identifier is detected by turbo-tasks-build.
+use turbo_tasks_macros::generic_type as __turbo_tasks_internal_generic_type;
+
+use crate::{self as turbo_tasks, ResolvedVc, ShrinkToFit, Vc};
+
+// Errors are cells as well, e.g. issues, because `anyhow::Error`s can't be
+// stored in a cell.
+__turbo_tasks_internal_generic_type!(<T, E>, Result<ResolvedVc<T>, ResolvedVc<E>>);
+
+impl<T, E> ShrinkToFit for Result<ResolvedVc<T>, ResolvedVc<E>>
+where
+    T: Send,
+    E: Send,
+{
+    fn shrink_to_fit(&mut self) {}
+}
+
+#[turbo_tasks::function]
+async fn result_is_ok(result: Vc<Result<ResolvedVc<()>, ResolvedVc<()>>>) -> Result<Vc<bool>> {
+    Ok(Vc::cell(result.await?.is_ok()))
+}
+
+#[turbo_tasks::function]
+async fn result_is_err(result: Vc<Result<ResolvedVc<()>, ResolvedVc<()>>>) -> Result<Vc<bool>> {
+    Ok(Vc::cell(result.await?.is_err()))
+}
+
+impl<T, E> Vc<Result<ResolvedVc<T>, ResolvedVc<E>>>
+where
+    T: Send + 'static,
+    E: Send + 'static,
+{
+    /// Returns a cell that contains `Ok(vc)`.
+    pub fn ok_cell(vc: ResolvedVc<T>) -> Self {
+        Vc::cell(Ok(vc))
+    }
+
+    /// Returns a cell that contains `Err(error)`.
+    pub fn err_cell(error: ResolvedVc<E>) -> Self {
+        Vc::cell(Err(error))
+    }
+
+    /// See [`Result::is_ok`]. Unlike awaiting the result, a task reading the
+    /// returned `Vc` is not invalidated when the contained `Vc`s change.
+    pub fn is_ok(self) -> Vc<bool> {
+        result_is_ok(Self::to_repr(self))
+    }
+
+    /// See [`Result::is_err`].
+    pub fn is_err(self) -> Vc<bool> {
+        result_is_err(Self::to_repr(self))
+    }
+
+    /// See [`Result::ok`].
+    pub async fn ok(self) -> Result<Vc<Option<ResolvedVc<T>>>> {
+        Ok(Vc::cell(self.await?.ok()))
+    }
+
+    /// See [`Result::map`].
+    pub async fn map<U>(
+        self,
+        f: impl FnOnce(ResolvedVc<T>) -> ResolvedVc<U>,
+    ) -> Result<Vc<Result<ResolvedVc<U>, ResolvedVc<E>>>>
+    where
+        U: Send + 'static,
+    {
+        Ok(Vc::cell(self.await?.map(f)))
+    }
+
+    /// Returns the contained `Ok` value, or the `Vc` returned by `f` for the
+    /// error.
+    pub async fn unwrap_or_else_vc(self, f: impl FnOnce(ResolvedVc<E>) -> Vc<T>) -> Result<Vc<T>> {
+        Ok(match *self.await? {
+            Ok(vc) => *vc,
+            Err(error) => f(error),
+        })
+    }
+}
@@ -37,6 +37,12 @@ pub struct Invalidator {
 }
 
 impl Invalidator {
+    /// Whether the turbo tasks instance of the task still exists. Invalidating
+    /// the task has no effect otherwise.
+    pub fn is_alive(&self) -> bool {
+        self.turbo_tasks.strong_count() > 0
+    }
+
     pub fn invalidate(self) {
         let Invalidator {
             task,
@@ -0,0 +1,131 @@
+use std::{
+    fmt::{Display, Formatter},
+    sync::Mutex,
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::{
+    manager::{current_task, with_turbo_tasks},
+    util::StaticOrArc,
+    FxIndexSet, InvalidationReason, InvalidationReasonKind, RcStr, TaskId,
+};
+
+/// Makes the current task depend on the invalidation key `key`. The task is
+/// invalidated when the key is invalidated with
+/// [`crate::TurboTasks::invalidate_key`].
+///
+/// This allows to invalidate computations based on external events that
+/// turbo-tasks can't observe, e.g. a content update in a CMS. The dependency
+/// is dropped once the task is invalidated or executed again, and registered
+/// again when the execution depends on the key again.
+///
+/// Dependencies are not persisted, so the task is marked as session dependent,
+/// which executes it again instead of restoring it from a persistent cache.
+pub fn depend_on_key(key: impl Into<RcStr>) {
+    let task = current_task("turbo_tasks::depend_on_key()");
+    let key = key.into();
+    with_turbo_tasks(|tt| {
+        tt.mark_own_task_as_session_dependent(task);
+        tt.depend_on_key(key, task);
+    });
+}
+
+/// The tasks that depend on invalidation keys, see [depend_on_key].
+#[derive(Default)]
+pub(crate) struct KeyDependencies {
+    inner: Mutex<KeyDependenciesInner>,
+}
+
+#[derive(Default)]
+struct KeyDependenciesInner {
+    tasks_by_key: FxHashMap<RcStr, FxIndexSet<TaskId>>,
+    keys_by_task: FxHashMap<TaskId, FxIndexSet<RcStr>>,
+}
+
+impl KeyDependencies {
+    pub fn insert(&self, key: RcStr, task: TaskId) {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .tasks_by_key
+            .entry(key.clone())
+            .or_default()
+            .insert(task);
+        inner.keys_by_task.entry(task).or_default().insert(key);
+    }
+
+    /// Removes and returns the tasks that depend on `key`.
+    pub fn take(&self, key: &str) -> FxIndexSet<TaskId> {
+        let mut inner = self.inner.lock().unwrap();
+        let tasks = inner.tasks_by_key.remove(key).unwrap_or_default();
+        for task in &tasks {
+            if let Some(keys) = inner.keys_by_task.get_mut(task) {
+                keys.swap_remove(key);
+                if keys.is_empty() {
+                    inner.keys_by_task.remove(task);
+                }
+            }
+        }
+        tasks
+    }
+
+    /// Removes the dependencies of `task`, e.g. before it's executed again or
+    /// when it's unloaded.
+    pub fn remove_task(&self, task: TaskId) {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(keys) = inner.keys_by_task.remove(&task) else {
+            return;
+        };
+        for key in keys {
+            if let Some(tasks) = inner.tasks_by_key.get_mut(&key) {
+                tasks.swap_remove(&task);
+                if tasks.is_empty() {
+                    inner.tasks_by_key.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+/// Invalidation was caused by [`crate::TurboTasks::invalidate_key`]
+#[derive(PartialEq, Eq, Hash)]
+pub struct KeyInvalidation {
+    pub key: RcStr,
+}
+
+impl InvalidationReason for KeyInvalidation {
+    fn kind(&self) -> Option<StaticOrArc<dyn InvalidationReasonKind>> {
+        Some(StaticOrArc::Static(&KEY_INVALIDATION_KIND))
+    }
+}
+
+impl Display for KeyInvalidation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} invalidated", self.key)
+    }
+}
+
+/// Invalidation kind for [KeyInvalidation]
+#[derive(PartialEq, Eq, Hash)]
+struct KeyInvalidationKind;
+
+static KEY_INVALIDATION_KIND: KeyInvalidationKind = KeyInvalidationKind;
+
+impl InvalidationReasonKind for KeyInvalidationKind {
+    fn fmt(
+        &self,
+        reasons: &FxIndexSet<StaticOrArc<dyn InvalidationReason>>,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            "{} keys invalidated ({}, ...)",
+            reasons.len(),
+            reasons[0]
+                .as_any()
+                .downcast_ref::<KeyInvalidation>()
+                .unwrap()
+                .key
+        )
+    }
+}
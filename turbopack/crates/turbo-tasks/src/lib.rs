@@ -38,17 +38,23 @@
 
 pub mod backend;
 mod capture_future;
+mod cell_interner;
 mod collectibles;
 mod completion;
+mod cow;
+mod cycle_detection;
 pub mod debug;
 mod display;
 pub mod duration_span;
 pub mod event;
+mod execution_local;
+mod generics;
 pub mod graph;
 mod id;
 mod id_factory;
 mod invalidation;
 mod join_iter_ext;
+mod key_invalidation;
 mod key_value_pair;
 #[doc(hidden)]
 pub mod macro_helpers;
@@ -64,28 +70,37 @@ mod raw_vc;
 mod rcstr;
 mod read_ref;
 pub mod registry;
+mod root_task_admission;
+mod sampling;
 mod scope;
 mod serialization_invalidation;
 mod shrink_to_fit;
 pub mod small_duration;
 mod state;
 pub mod task;
+mod task_expiry;
+mod task_scheduler;
 pub mod trace;
 mod trait_helpers;
+mod trait_method_cache;
 mod trait_ref;
 mod triomphe_utils;
 pub mod util;
 mod value;
 mod value_type;
 mod vc;
+mod watchdog;
 
 use std::hash::BuildHasherDefault;
 
 pub use anyhow::{Error, Result};
 use auto_hash_map::AutoSet;
+pub use cell_interner::SharedReferenceEqFn;
 pub use collectibles::CollectiblesSource;
 pub use completion::{Completion, Completions};
+pub use cow::{CowMap, CowVec};
 pub use display::ValueToString;
+pub use execution_local::{set_execution_local, with_execution_local, ExecutionLocalGuard};
 pub use id::{
     ExecutionId, FunctionId, LocalTaskId, SessionId, TaskId, TraitTypeId, ValueTypeId,
     TRANSIENT_TASK_BIT,
@@ -95,26 +110,31 @@ pub use invalidation::{
     InvalidationReasonSet, Invalidator,
 };
 pub use join_iter_ext::{JoinIterExt, TryFlatJoinIterExt, TryJoinIterExt};
+pub use key_invalidation::{depend_on_key, KeyInvalidation};
 pub use key_value_pair::KeyValuePair;
 pub use magic_any::MagicAny;
 pub use manager::{
     dynamic_call, dynamic_this_call, emit, mark_finished, mark_session_dependent, mark_stateful,
     prevent_gc, run_once, run_once_with_reason, spawn_blocking, spawn_thread, trait_call,
-    turbo_tasks, turbo_tasks_scope, CurrentCellRef, ReadConsistency, TaskPersistence, TurboTasks,
-    TurboTasksApi, TurboTasksBackendApi, TurboTasksBackendApiExt, TurboTasksCallApi, Unused,
-    UpdateInfo,
+    trait_call_cached, turbo_tasks, turbo_tasks_scope, CurrentCellRef, ReadConsistency,
+    TaskPersistence, TurboTasks, TurboTasksApi, TurboTasksBackendApi, TurboTasksBackendApiExt,
+    TurboTasksCallApi, Unused, UpdateInfo,
 };
 pub use native_function::{FunctionMeta, NativeFunction};
 pub use output::OutputContent;
 pub use raw_vc::{CellId, RawVc, ReadRawVcFuture, ResolveTypeError};
 pub use rcstr::RcStr;
 pub use read_ref::ReadRef;
+pub use root_task_admission::RootTaskAdmissionMetrics;
 use rustc_hash::FxHasher;
+pub use sampling::SamplingProfile;
 pub use scope::scope;
 pub use serialization_invalidation::SerializationInvalidator;
 pub use shrink_to_fit::ShrinkToFit;
 pub use state::{State, TransientState};
 pub use task::{task_input::TaskInput, SharedReference, TypedSharedReference};
+pub use task_expiry::TtlExpiration;
+pub use task_scheduler::TaskPriority;
 pub use trait_ref::{IntoTraitRef, TraitRef};
 pub use turbo_tasks_macros::{function, value_impl, value_trait, KeyValuePair, TaskInput};
 pub use value::{TransientInstance, TransientValue, Value};
@@ -124,6 +144,7 @@ pub use vc::{
     VcCellNewMode, VcCellSharedMode, VcDefaultRead, VcRead, VcTransparentRead, VcValueTrait,
     VcValueTraitCast, VcValueType, VcValueTypeCast,
 };
+pub use watchdog::StronglyConsistentReadWatchdog;
 
 pub type FxIndexSet<T> = indexmap::IndexSet<T, BuildHasherDefault<FxHasher>>;
 pub type FxIndexMap<K, V> = indexmap::IndexMap<K, V, BuildHasherDefault<FxHasher>>;
@@ -9,10 +9,14 @@ pub use tracing;
 pub use super::{
     magic_any::MagicAny,
     manager::{find_cell_by_type, notify_scheduled_tasks, spawn_detached_for_testing},
+    task::{KeyedTaskInput, TaskInputKey},
+    trait_method_cache::TraitMethodCache,
 };
 use crate::{
-    debug::ValueDebugFormatString, shrink_to_fit::ShrinkToFit, task::TaskOutput, RawVc,
-    ResolvedValue, TaskInput, TaskPersistence, Vc,
+    debug::{ValueDebugFormatJson, ValueDebugFormatString},
+    shrink_to_fit::ShrinkToFit,
+    task::TaskOutput,
+    RawVc, ResolvedValue, TaskInput, TaskPersistence, Vc,
 };
 
 #[inline(never)]
@@ -26,6 +30,34 @@ pub async fn value_debug_format_field(value: ValueDebugFormatString<'_>) -> Stri
     }
 }
 
+/// Formats a field annotated with `#[turbo_tasks(redact)]`. The value is
+/// replaced by a hash of its debug representation, so equal values can still
+/// be recognized without revealing them.
+#[inline(never)]
+pub async fn value_debug_format_redacted_field(value: ValueDebugFormatString<'_>) -> String {
+    let value = value_debug_format_field(value).await;
+    format!(
+        "[redacted {}]",
+        turbo_tasks_hash::encode_hex(turbo_tasks_hash::hash_xxh3_hash64(value.as_str()))
+    )
+}
+
+#[inline(never)]
+pub async fn value_debug_json_field(value: ValueDebugFormatJson<'_>) -> serde_json::Value {
+    match value.try_to_json().await {
+        Ok(result) => result,
+        Err(err) => serde_json::Value::String(format!("{0:?}", err)),
+    }
+}
+
+/// The JSON counterpart of [`value_debug_format_redacted_field`].
+#[inline(never)]
+pub async fn value_debug_json_redacted_field(
+    value: ValueDebugFormatString<'_>,
+) -> serde_json::Value {
+    serde_json::Value::String(value_debug_format_redacted_field(value).await)
+}
+
 pub fn get_non_local_persistence_from_inputs(inputs: &impl TaskInput) -> TaskPersistence {
     if inputs.is_transient() {
         TaskPersistence::Transient
@@ -52,6 +84,16 @@ where
 {
 }
 
+/// Fails to compile when `T` is always transient, see
+/// [`TaskInput::TRANSIENT`].
+pub const fn assert_not_transient_input<T: TaskInput>() {
+    assert!(
+        !T::TRANSIENT,
+        "transient inputs like `TransientInstance` and `TransientValue` can only be passed to \
+         functions marked with `#[turbo_tasks::function(transient)]`"
+    );
+}
+
 #[macro_export]
 macro_rules! stringify_path {
     ($path:path) => {
@@ -5,6 +5,7 @@ use std::{
     hash::BuildHasherDefault,
     mem::take,
     panic::AssertUnwindSafe,
+    path::Path,
     pin::Pin,
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
@@ -14,39 +15,49 @@ use std::{
     time::{Duration, Instant},
 };
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use auto_hash_map::AutoMap;
-use futures::FutureExt;
+use futures::{future::Either, FutureExt};
 use rustc_hash::FxHasher;
 use serde::{Deserialize, Serialize};
-use tokio::{runtime::Handle, select, task_local};
+use tokio::{runtime::Handle, select, sync::oneshot, task_local};
 use tokio_util::task::TaskTracker;
-use tracing::{info_span, instrument, trace_span, Instrument, Level};
+use tracing::{info_span, instrument, trace_span, Instrument, Level, Span};
 use turbo_tasks_malloc::TurboMalloc;
 
 use crate::{
     backend::{
-        Backend, CachedTaskType, CellContent, TaskCollectiblesMap, TaskExecutionSpec,
-        TransientTaskType, TypedCellContent,
+        Backend, CachedTaskType, CellContent, LeakedTask, PendingTaskTree, TaskCollectiblesMap,
+        TaskExecutionSpec, TransientTaskType, TypedCellContent,
     },
     capture_future::{self, CaptureFuture},
+    cell_interner::{CellInterner, SharedReferenceEqFn},
+    cycle_detection::{WaitGraph, WaitingGuard},
     event::{Event, EventListener},
+    execution_local::ExecutionLocals,
     id::{
         BackendJobId, ExecutionId, FunctionId, LocalCellId, LocalTaskId, TraitTypeId,
         TRANSIENT_TASK_BIT,
     },
     id_factory::{IdFactory, IdFactoryWithReuse},
+    key_invalidation::{KeyDependencies, KeyInvalidation},
     magic_any::MagicAny,
     raw_vc::{CellId, RawVc},
     registry::{self, get_function},
+    root_task_admission::{RootTaskAdmission, RootTaskAdmissionMetrics},
+    sampling::{SamplingProfile, SamplingProfiler},
     serialization_invalidation::SerializationInvalidator,
     task::shared_reference::TypedSharedReference,
+    task_expiry::{TaskExpiry, TtlExpiration},
+    task_scheduler::{TaskPriority, TaskScheduler},
     trace::TraceRawVcs,
     trait_helpers::get_trait_method,
+    trait_method_cache::TraitMethodCache,
     util::StaticOrArc,
     vc::ReadVcFuture,
-    Completion, FunctionMeta, InvalidationReason, InvalidationReasonSet, SharedReference, TaskId,
-    TaskIdSet, ValueTypeId, Vc, VcRead, VcValueTrait, VcValueType,
+    watchdog::{ReadWatchdog, StronglyConsistentReadWatchdog},
+    Completion, FunctionMeta, InvalidationReason, InvalidationReasonSet, RcStr, SharedReference,
+    TaskId, TaskIdSet, ValueTypeId, Vc, VcRead, VcValueTrait, VcValueType,
 };
 
 pub trait TurboTasksCallApi: Sync + Send {
@@ -118,6 +129,13 @@ pub trait TurboTasksApi: TurboTasksCallApi + Sync + Send {
 
     fn invalidate_serialization(&self, task: TaskId);
 
+    /// Makes `task` depend on the invalidation key `key`. See
+    /// [`crate::depend_on_key`].
+    fn depend_on_key(&self, key: RcStr, task: TaskId);
+
+    /// See [`TurboTasks::invalidate_key`].
+    fn invalidate_key(&self, key: &str);
+
     /// Eagerly notifies all tasks that were scheduled for notifications via
     /// `schedule_notify_tasks_set()`
     fn notify_scheduled_tasks(&self);
@@ -187,6 +205,50 @@ pub trait TurboTasksApi: TurboTasksCallApi + Sync + Send {
 
     fn connect_task(&self, task: TaskId);
 
+    /// See [`TurboTasks::set_strongly_consistent_read_watchdog`].
+    fn strongly_consistent_read_watchdog(&self) -> Option<StronglyConsistentReadWatchdog>;
+
+    /// The profile recorded so far, if the sampling profiler is running. See
+    /// [`TurboTasks::start_sampling_profiler`].
+    fn sampling_profile(&self) -> Option<SamplingProfile>;
+
+    /// Captures the tree of unfinished tasks below `task`. See
+    /// [`Backend::pending_task_tree`].
+    fn pending_task_tree(&self, task: TaskId) -> Option<PendingTaskTree>;
+
+    /// Records that `reader` waits for `task` to finish. Fails with an error
+    /// listing the cycle when `task` is transitively waiting for `reader`, as
+    /// the read would never complete. While the task concurrency is limited,
+    /// `reader` gives up its slot and `task` inherits its priority. Returns
+    /// `false` when neither is enabled and nothing was recorded.
+    ///
+    /// See [`TurboTasks::set_cycle_detection`] and
+    /// [`TurboTasks::set_task_concurrency`].
+    fn start_waiting(&self, reader: TaskId, task: TaskId) -> Result<bool>;
+
+    /// Removes a wait recorded by [`TurboTasksApi::start_waiting`]. Returns a
+    /// receiver that completes once `reader` got a slot again, when it has to
+    /// wait for one.
+    fn stop_waiting(&self, reader: TaskId, task: TaskId) -> Option<oneshot::Receiver<()>>;
+
+    /// Returns a shared reference to a value that is equal to `content`
+    /// according to `eq` when one was interned already, or `content`
+    /// otherwise.
+    ///
+    /// See [`TurboTasks::set_cell_interning`].
+    fn intern_cell_content(
+        &self,
+        ty: ValueTypeId,
+        content: SharedReference,
+        eq: SharedReferenceEqFn,
+    ) -> SharedReference;
+
+    /// See [`Backend::compact_subgraph`].
+    fn compact_subgraph(&self, task: TaskId) -> usize;
+
+    /// See [`Backend::leak_check`].
+    fn leak_check(&self) -> Option<Vec<LeakedTask>>;
+
     /// Wraps the given future in the current task.
     ///
     /// Beware: this method is not safe to use in production code. It is only intended for use in
@@ -197,6 +259,9 @@ pub trait TurboTasksApi: TurboTasksCallApi + Sync + Send {
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>;
 
     fn stop_and_wait(&self) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    /// See [`TurboTasks::wait_idle`].
+    fn wait_idle(&self, idle_for: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
 }
 
 /// A wrapper around a value that is unused.
@@ -274,8 +339,16 @@ pub trait TurboTasksBackendApi<B: Backend + 'static>: TurboTasksCallApi + Sync +
     /// Returns true if the system is idle.
     fn is_idle(&self) -> bool;
 
+    /// See [`TurboTasks::wait_idle`].
+    fn wait_idle<'a>(&'a self, idle_for: Duration)
+        -> Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
     /// Returns a reference to the backend.
     fn backend(&self) -> &B;
+
+    /// Drops the invalidation key dependencies of a task that was unloaded,
+    /// see [`crate::depend_on_key`].
+    fn forget_key_dependencies(&self, task: TaskId);
 }
 
 /// An extension trait for methods of `TurboTasksBackendApi` that are not object-safe. This is
@@ -378,6 +451,18 @@ pub struct TurboTasks<B: Backend + 'static> {
     event_foreground: Event,
     event_background: Event,
     program_start: Instant,
+    strongly_consistent_read_watchdog: Mutex<Option<StronglyConsistentReadWatchdog>>,
+    sampling_profiler: Mutex<Option<Arc<SamplingProfiler>>>,
+    /// Whether `sampling_profiler` is set, so scheduling a task doesn't need
+    /// to lock it while the profiler isn't running.
+    sampling_profiler_running: AtomicBool,
+    cycle_detection: AtomicBool,
+    wait_graph: WaitGraph,
+    cell_interner: CellInterner,
+    key_dependencies: KeyDependencies,
+    task_expiry: TaskExpiry,
+    root_task_admission: Arc<RootTaskAdmission>,
+    task_scheduler: Arc<TaskScheduler>,
 }
 
 /// Information about a "global" task. A global task can contain multiple "local" tasks (see
@@ -413,6 +498,9 @@ struct CurrentGlobalTaskState {
     /// complete.
     local_task_tracker: TaskTracker,
 
+    /// Values attached to this execution with [`crate::set_execution_local`].
+    execution_locals: ExecutionLocals,
+
     backend_state: Box<dyn Any + Send + Sync>,
 }
 
@@ -425,6 +513,7 @@ impl CurrentGlobalTaskState {
             cell_counters: Some(AutoMap::default()),
             local_cells: Vec::new(),
             local_task_tracker: TaskTracker::new(),
+            execution_locals: Default::default(),
             backend_state,
         }
     }
@@ -437,6 +526,10 @@ impl CurrentGlobalTaskState {
 /// of the global task.
 #[derive(Clone)]
 struct CurrentLocalTaskState {
+    /// The id of the global task. It's the same as [`CurrentGlobalTaskState::task_id`], but can be
+    /// read without locking the global task state.
+    task_id: TaskId,
+
     /// A unique identifier created for each unique [`CurrentLocalTaskState`]. Used to check that
     /// [`CurrentTaskState::local_cells`] are valid for the current [`RawVc::LocalCell`].
     execution_id: ExecutionId,
@@ -447,8 +540,13 @@ struct CurrentLocalTaskState {
 }
 
 impl CurrentLocalTaskState {
-    fn new(execution_id: ExecutionId, function_meta: Option<&'static FunctionMeta>) -> Self {
+    fn new(
+        task_id: TaskId,
+        execution_id: ExecutionId,
+        function_meta: Option<&'static FunctionMeta>,
+    ) -> Self {
         Self {
+            task_id,
             execution_id,
             function_meta,
         }
@@ -492,6 +590,16 @@ impl<B: Backend + 'static> TurboTasks<B> {
             event_foreground: Event::new(|| "TurboTasks::event_foreground".to_string()),
             event_background: Event::new(|| "TurboTasks::event_background".to_string()),
             program_start: Instant::now(),
+            strongly_consistent_read_watchdog: Mutex::new(None),
+            sampling_profiler: Mutex::new(None),
+            sampling_profiler_running: AtomicBool::new(false),
+            cycle_detection: AtomicBool::new(cfg!(debug_assertions)),
+            wait_graph: WaitGraph::default(),
+            cell_interner: CellInterner::default(),
+            key_dependencies: KeyDependencies::default(),
+            task_expiry: TaskExpiry::default(),
+            root_task_admission: Default::default(),
+            task_scheduler: Default::default(),
         });
         this.backend.startup(&*this);
         this
@@ -501,6 +609,112 @@ impl<B: Backend + 'static> TurboTasks<B> {
         self.this.upgrade().unwrap()
     }
 
+    /// Enables (or disables with `None`) the diagnostics for strongly
+    /// consistent reads that don't complete within the configured timeout.
+    pub fn set_strongly_consistent_read_watchdog(
+        &self,
+        watchdog: Option<StronglyConsistentReadWatchdog>,
+    ) {
+        *self.strongly_consistent_read_watchdog.lock().unwrap() = watchdog;
+    }
+
+    /// Starts recording which task functions are executing on each thread
+    /// every `interval`, along with the chain of tasks that scheduled them.
+    /// This is much cheaper than tracing, so it can be used to find out why a
+    /// long build is slow. A running profiler is replaced, which discards its
+    /// profile.
+    ///
+    /// Only tasks that are scheduled after the start are sampled.
+    pub fn start_sampling_profiler(&self, interval: Duration) {
+        let profiler = SamplingProfiler::start(interval);
+        let mut sampling_profiler = self.sampling_profiler.lock().unwrap();
+        if let Some(previous) = sampling_profiler.replace(profiler) {
+            previous.stop();
+        }
+        self.sampling_profiler_running
+            .store(true, Ordering::Release);
+    }
+
+    /// Stops the sampling profiler and returns its profile, if it was running.
+    pub fn stop_sampling_profiler(&self) -> Option<SamplingProfile> {
+        let mut sampling_profiler = self.sampling_profiler.lock().unwrap();
+        self.sampling_profiler_running
+            .store(false, Ordering::Release);
+        let profiler = sampling_profiler.take()?;
+        profiler.stop();
+        Some(profiler.profile())
+    }
+
+    /// Enables or disables the detection of tasks that transitively wait for
+    /// themselves. Reads that would never complete because of such a cycle
+    /// fail with an error listing the tasks of the cycle instead. Enabled by
+    /// default in debug builds.
+    pub fn set_cycle_detection(&self, enabled: bool) {
+        self.cycle_detection.store(enabled, Ordering::Release);
+    }
+
+    /// Enables (or disables with `None`) the interning of cell contents whose
+    /// serialization is at least `min_size` bytes. Cells with equal values
+    /// then share one allocation, even when they belong to different tasks.
+    ///
+    /// Only cells of value types that are compared when updated (the default
+    /// cell mode) and that are serializable are interned. Interning costs a
+    /// serialization of every updated cell, so it's disabled by default.
+    pub fn set_cell_interning(&self, min_size: Option<usize>) {
+        self.cell_interner.set_min_size(min_size);
+    }
+
+    /// Invalidates all tasks that depend on the invalidation key `key`, see
+    /// [`crate::depend_on_key`]. This allows external integrations to
+    /// invalidate computations, e.g. when a CMS reports a content update.
+    pub fn invalidate_key(&self, key: &str) {
+        let tasks = self.key_dependencies.take(key);
+        if tasks.is_empty() {
+            return;
+        }
+        let reason: StaticOrArc<dyn InvalidationReason> =
+            (Arc::new(KeyInvalidation { key: key.into() }) as Arc<dyn InvalidationReason>).into();
+        for task in tasks {
+            self.invalidate_with_reason(task, reason.clone());
+        }
+    }
+
+    /// Limits the number of root task executions that run concurrently to
+    /// `limit` (or removes the limit with `None`). Executions that exceed the
+    /// limit wait in a queue per requester (see
+    /// [`TurboTasks::spawn_root_task_with_requester`]), and the queues are
+    /// served in turn. This keeps a requester that spawns many root tasks,
+    /// e.g. a build, from delaying the updates of other requesters, e.g. HMR
+    /// clients. Unlimited by default.
+    pub fn set_root_task_concurrency(&self, limit: Option<usize>) {
+        self.root_task_admission.set_limit(limit);
+    }
+
+    /// Returns the number of running and waiting root task executions.
+    pub fn root_task_admission_metrics(&self) -> RootTaskAdmissionMetrics {
+        self.root_task_admission.metrics()
+    }
+
+    /// Limits the number of task executions that run concurrently to `limit`
+    /// (or removes the limit with `None`). A task that waits for another task
+    /// doesn't count towards the limit while waiting. Executions that exceed
+    /// the limit are admitted by priority, see
+    /// [`TurboTasks::set_task_priority`]. Unlimited by default, and only
+    /// executions that are scheduled while the limit is set are limited.
+    pub fn set_task_concurrency(&self, limit: Option<usize>) {
+        self.task_scheduler.set_limit(limit);
+    }
+
+    /// Sets the priority of the executions of `task`, e.g. of the root task
+    /// of an HMR update. Tasks without a priority inherit the priority of the
+    /// task that scheduled them. While a task waits for another task, the
+    /// other task and the tasks it transitively waits for run with at least
+    /// the priority of the waiting task, so a background compilation that
+    /// computes a shared dependency can't hold up an HMR update.
+    pub fn set_task_priority(&self, task: TaskId, priority: TaskPriority) {
+        self.task_scheduler.set_priority(task, priority);
+    }
+
     /// Creates a new root task
     pub fn spawn_root_task<T, F, Fut>(&self, functor: F) -> TaskId
     where
@@ -508,10 +722,29 @@ impl<B: Backend + 'static> TurboTasks<B> {
         F: Fn() -> Fut + Send + Sync + Clone + 'static,
         Fut: Future<Output = Result<Vc<T>>> + Send,
     {
+        self.spawn_root_task_with_requester("default".into(), functor)
+    }
+
+    /// Creates a new root task on behalf of `requester`, e.g. an HMR client.
+    /// The executions of root tasks of different requesters are admitted
+    /// fairly when the root task concurrency is limited, see
+    /// [`TurboTasks::set_root_task_concurrency`].
+    pub fn spawn_root_task_with_requester<T, F, Fut>(&self, requester: RcStr, functor: F) -> TaskId
+    where
+        T: Send,
+        F: Fn() -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = Result<Vc<T>>> + Send,
+    {
+        let admission = self.root_task_admission.clone();
         let id = self.backend.create_transient_task(
             TransientTaskType::Root(Box::new(move || {
                 let functor = functor.clone();
-                Box::pin(async move { Ok(functor().await?.node) })
+                let admission = admission.clone();
+                let requester = requester.clone();
+                Box::pin(async move {
+                    let _permit = admission.acquire(requester).await;
+                    Ok(functor().await?.node)
+                })
             })),
             self,
         );
@@ -520,6 +753,7 @@ impl<B: Backend + 'static> TurboTasks<B> {
     }
 
     pub fn dispose_root_task(&self, task_id: TaskId) {
+        self.task_scheduler.remove_priority(task_id);
         self.backend.dispose_root_task(task_id, self);
     }
 
@@ -755,25 +989,38 @@ impl<B: Backend + 'static> TurboTasks<B> {
     pub(crate) fn schedule(&self, task_id: TaskId) {
         self.begin_primary_job();
         self.scheduled_tasks.fetch_add(1, Ordering::AcqRel);
+        let inherited_priority = self
+            .task_scheduler
+            .inherited_priority(try_current_task_id());
 
         #[cfg(feature = "tokio_tracing")]
         let description = self.backend.get_task_description(task_id);
 
         let this = self.pin();
         let future = async move {
+            let _permit = this
+                .task_scheduler
+                .acquire(task_id, inherited_priority)
+                .await;
             let mut schedule_again = true;
             while schedule_again {
+                // The execution registers the keys it depends on again.
+                this.key_dependencies.remove_task(task_id);
                 let backend_state = this.backend.new_task_state(task_id);
                 let global_task_state = Arc::new(RwLock::new(CurrentGlobalTaskState::new(
                     task_id,
                     Box::new(backend_state),
                 )));
                 let local_task_state = CurrentLocalTaskState::new(
+                    task_id,
                     this.execution_id_factory.get(),
                     this.backend
                         .try_get_function_id(task_id)
                         .map(|func_id| &get_function(func_id).function_meta),
                 );
+                let ttl = local_task_state
+                    .function_meta
+                    .and_then(|function_meta| function_meta.ttl);
                 let single_execution_future = async {
                     if this.stopped.load(Ordering::Acquire) {
                         return false;
@@ -784,10 +1031,19 @@ impl<B: Backend + 'static> TurboTasks<B> {
                     else {
                         return false;
                     };
+                    if ttl.is_some() {
+                        // An expiry isn't persisted, so a result restored from a persistent cache
+                        // would never expire.
+                        this.backend
+                            .mark_own_task_as_session_dependent(task_id, &*this);
+                    }
 
                     async {
-                        let (result, duration, memory_usage) =
+                        let (result, duration, wait_duration, memory_usage) =
                             CaptureFuture::new(AssertUnwindSafe(future).catch_unwind()).await;
+                        let span = Span::current();
+                        span.record("self_time", duration.as_micros() as u64);
+                        span.record("wait_time", wait_duration.as_micros() as u64);
 
                         // wait for all spawned local tasks using `local_cells` to finish
                         let ltt = CURRENT_GLOBAL_TASK_STATE
@@ -809,6 +1065,7 @@ impl<B: Backend + 'static> TurboTasks<B> {
                         let schedule_again = this.backend.task_execution_completed(
                             task_id,
                             duration,
+                            wait_duration,
                             memory_usage,
                             &cell_counters,
                             stateful,
@@ -816,6 +1073,9 @@ impl<B: Backend + 'static> TurboTasks<B> {
                         );
                         // task_execution_completed might need to notify tasks
                         this.notify_scheduled_tasks();
+                        if let Some(ttl) = ttl {
+                            this.schedule_expiry(task_id, ttl);
+                        }
                         schedule_again
                     }
                     .instrument(span)
@@ -833,6 +1093,20 @@ impl<B: Backend + 'static> TurboTasks<B> {
         };
 
         let future = TURBO_TASKS.scope(self.pin(), future).in_current_span();
+        let profiler = if self.sampling_profiler_running.load(Ordering::Acquire) {
+            self.sampling_profiler.lock().unwrap().clone()
+        } else {
+            None
+        };
+        let future = if let Some(profiler) = profiler {
+            let name = self
+                .backend
+                .try_get_function_id(task_id)
+                .map_or("<root task>", |func_id| get_function(func_id).name.as_str());
+            Either::Left(profiler.sampled(profiler.task_stack(task_id, name), future))
+        } else {
+            Either::Right(future)
+        };
 
         #[cfg(feature = "tokio_tracing")]
         tokio::task::Builder::new()
@@ -852,7 +1126,51 @@ impl<B: Backend + 'static> TurboTasks<B> {
             *self.start.lock().unwrap() = Some(Instant::now());
             self.event_start.notify(usize::MAX);
             self.backend.idle_end(self);
+            self.invalidate_expired_while_idle();
+        }
+    }
+
+    /// Invalidates the result of `task` after `ttl`, see
+    /// `#[turbo_tasks::function(ttl = "...")]`.
+    fn schedule_expiry(&self, task: TaskId, ttl: Duration) {
+        let deadline = self.task_expiry.insert(task, ttl);
+        let this = self.this.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(ttl).await;
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            let idle = this.currently_scheduled_tasks.load(Ordering::Acquire) == 0;
+            if this.task_expiry.expire(task, deadline, idle) {
+                this.invalidate_expired(task);
+            }
+        });
+    }
+
+    /// Invalidates the tasks that expired while the engine was idle. This runs
+    /// in a separate tokio task, as the engine becomes busy while a task is
+    /// scheduled.
+    fn invalidate_expired_while_idle(&self) {
+        let tasks = self.task_expiry.take_expired_while_idle();
+        if tasks.is_empty() {
+            return;
         }
+        let this = self.pin();
+        tokio::spawn(async move {
+            for task in tasks {
+                this.invalidate_expired(task);
+            }
+        });
+    }
+
+    fn invalidate_expired(&self, task: TaskId) {
+        let function = self
+            .backend
+            .try_get_function_id(task)
+            .map_or("<unknown>", |func_id| get_function(func_id).name.as_str());
+        let reason: StaticOrArc<dyn InvalidationReason> =
+            (Arc::new(TtlExpiration { function }) as Arc<dyn InvalidationReason>).into();
+        self.invalidate_with_reason(task, reason);
     }
 
     fn begin_foreground_job(&self) {
@@ -1055,6 +1373,40 @@ impl<B: Backend + 'static> TurboTasks<B> {
         }
     }
 
+    /// Waits until the engine is idle, i.e. until no tasks have been scheduled
+    /// or executing for `idle_for`. Background jobs don't count as activity.
+    ///
+    /// This is the signal for opportunistic work like persisting caches,
+    /// garbage collection or prefetching compilations, which shouldn't delay
+    /// foreground work. The engine might become busy again at any time after
+    /// this returns.
+    pub async fn wait_idle(&self, idle_for: Duration) {
+        loop {
+            while self.currently_scheduled_tasks.load(Ordering::Acquire) != 0 {
+                let listener = self.event.listen_with_note(|| "wait for idle".to_string());
+                if self.currently_scheduled_tasks.load(Ordering::Acquire) != 0 {
+                    listener.await;
+                }
+            }
+            let start_listener = self
+                .event_start
+                .listen_with_note(|| "wait for idle".to_string());
+            if self.currently_scheduled_tasks.load(Ordering::Acquire) != 0 {
+                continue;
+            }
+            select! {
+                () = tokio::time::sleep(idle_for) => {
+                    if self.currently_scheduled_tasks.load(Ordering::Acquire) == 0 {
+                        return;
+                    }
+                }
+                () = start_listener => {
+                    // Activity started, wait for it to finish again
+                }
+            }
+        }
+    }
+
     pub async fn stop_and_wait(&self) {
         self.backend.stopping(self);
         self.stopped.store(true, Ordering::Release);
@@ -1156,6 +1508,12 @@ impl<B: Backend + 'static> TurboTasks<B> {
     pub fn backend(&self) -> &B {
         &self.backend
     }
+
+    /// Writes the persisted cache of the backend to a portable archive at
+    /// `path`. It blocks until the current state is persisted.
+    pub fn export_cache(&self, path: &Path) -> Result<()> {
+        self.backend.export_cache(path, self)
+    }
 }
 
 impl<B: Backend + 'static> TurboTasksCallApi for TurboTasks<B> {
@@ -1269,6 +1627,14 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         self.backend.invalidate_serialization(task, self);
     }
 
+    fn depend_on_key(&self, key: RcStr, task: TaskId) {
+        self.key_dependencies.insert(key, task);
+    }
+
+    fn invalidate_key(&self, key: &str) {
+        TurboTasks::invalidate_key(self, key);
+    }
+
     fn notify_scheduled_tasks(&self) {
         let _ = CURRENT_GLOBAL_TASK_STATE.try_with(|cell| {
             let tasks = {
@@ -1412,6 +1778,61 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         self.backend.mark_own_task_as_session_dependent(task, self);
     }
 
+    fn strongly_consistent_read_watchdog(&self) -> Option<StronglyConsistentReadWatchdog> {
+        *self.strongly_consistent_read_watchdog.lock().unwrap()
+    }
+
+    fn sampling_profile(&self) -> Option<SamplingProfile> {
+        if !self.sampling_profiler_running.load(Ordering::Acquire) {
+            return None;
+        }
+        Some(self.sampling_profiler.lock().unwrap().as_ref()?.profile())
+    }
+
+    fn pending_task_tree(&self, task: TaskId) -> Option<PendingTaskTree> {
+        self.backend.pending_task_tree(task, self)
+    }
+
+    fn start_waiting(&self, reader: TaskId, task: TaskId) -> Result<bool> {
+        if !self.cycle_detection.load(Ordering::Acquire) {
+            return Ok(self.task_scheduler.start_waiting(reader, task));
+        }
+        if let Err(cycle) = self.wait_graph.start_waiting(reader, task) {
+            let cycle = cycle
+                .into_iter()
+                .map(|task| self.backend.get_task_summary(task))
+                .collect::<Vec<_>>();
+            bail!(
+                "Detected a cycle of tasks waiting for each other:\n  {}",
+                cycle.join("\n  waits for ")
+            );
+        }
+        self.task_scheduler.start_waiting(reader, task);
+        Ok(true)
+    }
+
+    fn stop_waiting(&self, reader: TaskId, task: TaskId) -> Option<oneshot::Receiver<()>> {
+        self.wait_graph.stop_waiting(reader, task);
+        self.task_scheduler.stop_waiting(reader, task)
+    }
+
+    fn compact_subgraph(&self, task: TaskId) -> usize {
+        self.backend.compact_subgraph(task, self)
+    }
+
+    fn leak_check(&self) -> Option<Vec<LeakedTask>> {
+        self.backend.leak_check(self)
+    }
+
+    fn intern_cell_content(
+        &self,
+        ty: ValueTypeId,
+        content: SharedReference,
+        eq: SharedReferenceEqFn,
+    ) -> SharedReference {
+        self.cell_interner.intern(ty, content, eq)
+    }
+
     /// Creates a future that inherits the current task id and task state. The current global task
     /// will wait for this future to be dropped before exiting.
     fn detached_for_testing(
@@ -1441,6 +1862,13 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
             this.stop_and_wait().await;
         })
     }
+
+    fn wait_idle(&self, idle_for: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> {
+        let this = self.pin();
+        Box::pin(async move {
+            this.wait_idle(idle_for).await;
+        })
+    }
 }
 
 impl<B: Backend + 'static> TurboTasksBackendApi<B> for TurboTasks<B> {
@@ -1451,6 +1879,10 @@ impl<B: Backend + 'static> TurboTasksBackendApi<B> for TurboTasks<B> {
         &self.backend
     }
 
+    fn forget_key_dependencies(&self, task: TaskId) {
+        self.key_dependencies.remove_task(task);
+    }
+
     #[track_caller]
     fn schedule_backend_background_job(&self, id: BackendJobId) {
         self.schedule_background_job(move |this| async move {
@@ -1571,6 +2003,25 @@ impl<B: Backend + 'static> TurboTasksBackendApi<B> for TurboTasks<B> {
     fn is_idle(&self) -> bool {
         self.currently_scheduled_tasks.load(Ordering::Acquire) == 0
     }
+
+    fn wait_idle<'a>(
+        &'a self,
+        idle_for: Duration,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(self.wait_idle(idle_for))
+    }
+}
+
+pub(crate) fn try_current_task_id() -> Option<TaskId> {
+    CURRENT_GLOBAL_TASK_STATE
+        .try_with(|ts| ts.read().unwrap().task_id)
+        .ok()
+}
+
+pub(crate) fn try_with_execution_locals<R>(f: impl FnOnce(&mut ExecutionLocals) -> R) -> Option<R> {
+    CURRENT_GLOBAL_TASK_STATE
+        .try_with(|ts| f(&mut ts.write().unwrap().execution_locals))
+        .ok()
 }
 
 pub(crate) fn current_task(from: &str) -> TaskId {
@@ -1660,6 +2111,30 @@ pub fn trait_call(
     with_turbo_tasks(|tt| tt.trait_call(trait_type, trait_fn_name, this, arg, persistence))
 }
 
+/// Like [`trait_call`], but resolves the method with the inline `cache` of the
+/// call site when `this` is already resolved.
+pub fn trait_call_cached(
+    cache: &TraitMethodCache,
+    trait_type: TraitTypeId,
+    trait_fn_name: &'static str,
+    this: RawVc,
+    arg: Box<dyn MagicAny>,
+    persistence: TaskPersistence,
+) -> RawVc {
+    if let RawVc::TaskCell(_, CellId { type_id, .. }) = this {
+        if let Some(native_fn) = cache.get_or_resolve(trait_type, type_id, trait_fn_name) {
+            return dynamic_this_call(native_fn, this, arg, persistence);
+        }
+    }
+    trait_call(
+        trait_type,
+        Cow::Borrowed(trait_fn_name),
+        this,
+        arg,
+        persistence,
+    )
+}
+
 pub fn turbo_tasks() -> Arc<dyn TurboTasksApi> {
     TURBO_TASKS.with(|arc| arc.clone())
 }
@@ -1685,7 +2160,10 @@ pub fn with_turbo_tasks_for_testing<T>(
                 current_task,
                 Box::new(()),
             ))),
-            CURRENT_LOCAL_TASK_STATE.scope(CurrentLocalTaskState::new(execution_id, None), f),
+            CURRENT_LOCAL_TASK_STATE.scope(
+                CurrentLocalTaskState::new(current_task, execution_id, None),
+                f,
+            ),
         ),
     )
 }
@@ -1779,10 +2257,17 @@ pub(crate) async fn read_task_output(
     id: TaskId,
     consistency: ReadConsistency,
 ) -> Result<RawVc> {
+    let mut watchdog = ReadWatchdog::new(consistency);
     loop {
         match this.try_read_task_output(id, consistency)? {
             Ok(result) => return Ok(result),
-            Err(listener) => listener.await,
+            Err(listener) => {
+                let waiting = WaitingGuard::new(this, id)?;
+                watchdog.wait(this, id, listener).await;
+                if let Some(waiting) = waiting {
+                    waiting.stop().await;
+                }
+            }
         }
     }
 }
@@ -1794,10 +2279,17 @@ pub(crate) async fn read_task_output_untracked(
     id: TaskId,
     consistency: ReadConsistency,
 ) -> Result<RawVc> {
+    let mut watchdog = ReadWatchdog::new(consistency);
     loop {
         match this.try_read_task_output_untracked(id, consistency)? {
             Ok(result) => return Ok(result),
-            Err(listener) => listener.await,
+            Err(listener) => {
+                let waiting = WaitingGuard::new(this, id)?;
+                watchdog.wait(this, id, listener).await;
+                if let Some(waiting) = waiting {
+                    waiting.stop().await;
+                }
+            }
         }
     }
 }
@@ -1810,7 +2302,13 @@ pub(crate) async fn read_task_cell(
     loop {
         match this.try_read_task_cell(id, index)? {
             Ok(result) => return Ok(result),
-            Err(listener) => listener.await,
+            Err(listener) => {
+                let waiting = WaitingGuard::new(this, id)?;
+                listener.await;
+                if let Some(waiting) = waiting {
+                    waiting.stop().await;
+                }
+            }
         }
     }
 }
@@ -1828,6 +2326,13 @@ pub struct CurrentCellRef {
 
 type VcReadRepr<T> = <<T as VcValueType>::Read as VcRead<T>>::Repr;
 
+fn extract_sr_value<T: VcValueType>(sr: &SharedReference) -> &T {
+    <T::Read as VcRead<T>>::repr_to_value_ref(
+        sr.0.downcast_ref::<VcReadRepr<T>>()
+            .expect("cannot update SharedReference of different type"),
+    )
+}
+
 impl CurrentCellRef {
     /// Updates the cell if the given `functor` returns a value.
     pub fn conditional_update<T>(&self, functor: impl FnOnce(Option<&T>) -> Option<T>)
@@ -1899,13 +2404,15 @@ impl CurrentCellRef {
     where
         T: PartialEq + VcValueType,
     {
-        self.conditional_update(|old_value| {
-            if let Some(old_value) = old_value {
-                if old_value == &new_value {
+        self.conditional_update_with_shared_reference(|old_sr| {
+            if let Some(old_sr) = old_sr {
+                if extract_sr_value::<T>(old_sr) == &new_value {
                     return None;
                 }
             }
-            Some(new_value)
+            Some(self.intern::<T>(SharedReference::new(triomphe::Arc::new(
+                <T::Read as VcRead<T>>::value_to_repr(new_value),
+            ))))
         });
     }
 
@@ -1921,24 +2428,33 @@ impl CurrentCellRef {
     where
         T: VcValueType + PartialEq,
     {
-        fn extract_sr_value<T: VcValueType>(sr: &SharedReference) -> &T {
-            <T::Read as VcRead<T>>::repr_to_value_ref(
-                sr.0.downcast_ref::<VcReadRepr<T>>()
-                    .expect("cannot update SharedReference of different type"),
-            )
-        }
         self.conditional_update_with_shared_reference(|old_sr| {
             if let Some(old_sr) = old_sr {
+                // Interned values are equal when they share the allocation
+                if old_sr == &new_shared_reference {
+                    return None;
+                }
                 let old_value: &T = extract_sr_value(old_sr);
                 let new_value = extract_sr_value(&new_shared_reference);
                 if old_value == new_value {
                     return None;
                 }
             }
-            Some(new_shared_reference)
+            Some(self.intern::<T>(new_shared_reference))
         });
     }
 
+    /// Returns an equal interned value when interning is enabled, see
+    /// [`TurboTasks::set_cell_interning`].
+    fn intern<T>(&self, shared_reference: SharedReference) -> SharedReference
+    where
+        T: VcValueType + PartialEq,
+    {
+        turbo_tasks().intern_cell_content(self.index.type_id, shared_reference, |a, b| {
+            extract_sr_value::<T>(a) == extract_sr_value::<T>(b)
+        })
+    }
+
     /// Unconditionally updates the content of the cell.
     pub fn update<T>(&self, new_value: T)
     where
@@ -2049,6 +2565,14 @@ pub(crate) async fn read_local_output(
     todo!("bgw: local outputs");
 }
 
+/// The current task and its execution, or `None` outside of a task. Unlike
+/// [`try_current_task_id`], this doesn't lock the global task state.
+pub(crate) fn try_current_task_execution() -> Option<(TaskId, ExecutionId)> {
+    CURRENT_LOCAL_TASK_STATE
+        .try_with(|ts| (ts.task_id, ts.execution_id))
+        .ok()
+}
+
 /// Panics if the [`ExecutionId`] does not match the current task's
 /// `execution_id`.
 pub(crate) fn assert_execution_id(execution_id: ExecutionId) {
@@ -1,4 +1,4 @@
-use std::{fmt::Debug, hash::Hash, pin::Pin};
+use std::{fmt::Debug, hash::Hash, pin::Pin, time::Duration};
 
 use anyhow::{Context, Result};
 use futures::Future;
@@ -90,6 +90,9 @@ pub struct FunctionMeta {
     /// cached across task executions. Cells can be converted to their non-local
     /// versions by calling `Vc::resolve`.
     pub local_cells: bool,
+    /// Invalidates the result of a task this long after it was computed, for
+    /// computations that depend on time.
+    pub ttl: Option<Duration>,
 }
 
 /// A native (rust) turbo-tasks function. It's used internally by
@@ -164,12 +167,24 @@ impl NativeFunction {
         }
     }
 
+    /// The span of an execution of the function. `self_time` and `wait_time`
+    /// are recorded in microseconds when the execution has completed.
     pub fn span(&'static self) -> Span {
-        tracing::trace_span!("turbo_tasks::function", name = self.name.as_str())
+        tracing::trace_span!(
+            "turbo_tasks::function",
+            name = self.name.as_str(),
+            self_time = tracing::field::Empty,
+            wait_time = tracing::field::Empty,
+        )
     }
 
     pub fn resolve_span(&'static self) -> Span {
-        tracing::trace_span!("turbo_tasks::resolve_call", name = self.name.as_str())
+        tracing::trace_span!(
+            "turbo_tasks::resolve_call",
+            name = self.name.as_str(),
+            self_time = tracing::field::Empty,
+            wait_time = tracing::field::Empty,
+        )
     }
 
     pub fn register(&'static self, global_name: &'static str) {
@@ -14,6 +14,7 @@ use thiserror::Error;
 
 use crate::{
     backend::{CellContent, TypedCellContent},
+    cycle_detection::WaitingGuard,
     event::EventListener,
     id::{ExecutionId, LocalCellId, LocalTaskId},
     manager::{
@@ -21,8 +22,10 @@ use crate::{
         read_task_output, TurboTasksApi,
     },
     registry::{self, get_value_type},
-    turbo_tasks, CollectiblesSource, ReadConsistency, TaskId, TraitTypeId, ValueType, ValueTypeId,
-    Vc, VcValueTrait,
+    turbo_tasks,
+    watchdog::ReadWatchdog,
+    CollectiblesSource, ReadConsistency, TaskId, TraitTypeId, ValueType, ValueTypeId, Vc,
+    VcValueTrait,
 };
 
 #[derive(Error, Debug)]
@@ -278,6 +281,9 @@ pub struct ReadRawVcFuture {
     current: RawVc,
     untracked: bool,
     listener: Option<EventListener>,
+    /// Records that the current task waits for `listener`, to detect cycles.
+    waiting: Option<WaitingGuard>,
+    watchdog: ReadWatchdog,
 }
 
 impl ReadRawVcFuture {
@@ -289,6 +295,8 @@ impl ReadRawVcFuture {
             current: vc,
             untracked: false,
             listener: None,
+            waiting: None,
+            watchdog: ReadWatchdog::new(ReadConsistency::Eventual),
         }
     }
 
@@ -300,6 +308,8 @@ impl ReadRawVcFuture {
             current: vc,
             untracked: true,
             listener: None,
+            waiting: None,
+            watchdog: ReadWatchdog::new(ReadConsistency::Eventual),
         }
     }
 
@@ -311,6 +321,8 @@ impl ReadRawVcFuture {
             current: vc,
             untracked: true,
             listener: None,
+            waiting: None,
+            watchdog: ReadWatchdog::new(ReadConsistency::Eventual),
         }
     }
 
@@ -322,6 +334,8 @@ impl ReadRawVcFuture {
             current: vc,
             untracked: false,
             listener: None,
+            waiting: None,
+            watchdog: ReadWatchdog::new(ReadConsistency::Strong),
         }
     }
 
@@ -333,6 +347,19 @@ impl ReadRawVcFuture {
             current: vc,
             untracked: true,
             listener: None,
+            waiting: None,
+            watchdog: ReadWatchdog::new(ReadConsistency::Strong),
+        }
+    }
+}
+
+impl ReadRawVcFuture {
+    fn poll_watchdog(&mut self, cx: &mut std::task::Context<'_>) {
+        if self.consistency != ReadConsistency::Strong {
+            return;
+        }
+        if let RawVc::TaskOutput(task) | RawVc::LocalOutput(task, _) = self.current {
+            self.watchdog.poll(&*self.turbo_tasks, task, cx);
         }
     }
 }
@@ -349,9 +376,11 @@ impl Future for ReadRawVcFuture {
                 // SAFETY: listener is from previous pinned this
                 let listener = unsafe { Pin::new_unchecked(listener) };
                 if listener.poll(cx).is_pending() {
+                    this.poll_watchdog(cx);
                     return Poll::Pending;
                 }
                 this.listener = None;
+                this.waiting = None;
             }
             let mut listener = match this.current {
                 RawVc::TaskOutput(task) => {
@@ -423,6 +452,13 @@ impl Future for ReadRawVcFuture {
                 Poll::Ready(_) => continue,
                 Poll::Pending => {
                     this.listener = Some(listener);
+                    if let RawVc::TaskOutput(task) | RawVc::TaskCell(task, _) = this.current {
+                        match WaitingGuard::new(&*this.turbo_tasks, task) {
+                            Ok(waiting) => this.waiting = waiting,
+                            Err(err) => return Poll::Ready(Err(err)),
+                        }
+                    }
+                    this.poll_watchdog(cx);
                     return Poll::Pending;
                 }
             };
@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 use triomphe::Arc;
 use turbo_tasks_hash::{DeterministicHash, DeterministicHasher};
 
-use crate::debug::{ValueDebugFormat, ValueDebugFormatString};
+use crate::debug::{ValueDebugFormat, ValueDebugFormatJson, ValueDebugFormatString};
 
 /// A reference counted [`String`], similar to [`Arc<String>`][std::sync::Arc].
 ///
@@ -155,4 +155,8 @@ impl ValueDebugFormat for RcStr {
     fn value_debug_format(&self, _: usize) -> ValueDebugFormatString {
         ValueDebugFormatString::Sync(self.to_string())
     }
+
+    fn value_debug_json(&self, _: usize) -> ValueDebugFormatJson {
+        ValueDebugFormatJson::Sync(self.as_str().into())
+    }
 }
@@ -1,3 +1,5 @@
+#[cfg(debug_assertions)]
+use std::{backtrace::Backtrace, sync::Arc};
 use std::{
     fmt::{Debug, Display},
     hash::Hash,
@@ -5,16 +7,20 @@ use std::{
     mem::transmute_copy,
 };
 
+#[cfg(debug_assertions)]
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use turbo_tasks_hash::DeterministicHash;
 
 use crate::{
-    debug::{ValueDebugFormat, ValueDebugFormatString},
+    debug::{ValueDebugFormat, ValueDebugFormatJson, ValueDebugFormatString},
     trace::{TraceRawVcs, TraceRawVcsContext},
     triomphe_utils::unchecked_sidecast_triomphe_arc,
     vc::VcCellMode,
     SharedReference, Vc, VcRead, VcValueType,
 };
+#[cfg(debug_assertions)]
+use crate::{manager::try_current_task_execution, ExecutionId, TaskId};
 
 type VcReadTarget<T> = <<T as VcValueType>::Read as VcRead<T>>::Target;
 
@@ -23,11 +29,59 @@ type VcReadTarget<T> = <<T as VcValueType>::Read as VcRead<T>>::Target;
 /// certain point in time.
 ///
 /// Internally it stores a reference counted reference to a value on the heap.
-pub struct ReadRef<T>(triomphe::Arc<T>);
+///
+/// A read value is only tracked as a dependency of the task execution that read
+/// it. It must not be kept across executions of a task, e.g. in a [`State`] or a
+/// global cache, as the later execution doesn't depend on the cell it was read
+/// from. In debug builds, using such a read value panics. Set
+/// `TURBO_TASKS_READ_REF_BACKTRACE=1` to include where it was read.
+///
+/// Only uses in later executions of the same task are detected. Read values
+/// that are stored in cells, e.g. the sources of a `PlainIssue`, or passed to
+/// other tasks are valid, as the cell or the other task is recomputed when the
+/// read cell changes.
+///
+/// [`State`]: crate::State
+pub struct ReadRef<T> {
+    value: triomphe::Arc<T>,
+    #[cfg(debug_assertions)]
+    origin: Option<ReadRefOrigin>,
+}
+
+/// Where a [`ReadRef`] was read, to detect when it's used in another execution
+/// of the same task.
+#[cfg(debug_assertions)]
+#[derive(Clone)]
+struct ReadRefOrigin {
+    task_id: TaskId,
+    execution_id: ExecutionId,
+    /// Only captured with `TURBO_TASKS_READ_REF_BACKTRACE`, as it's expensive.
+    backtrace: Option<Arc<Backtrace>>,
+}
+
+#[cfg(debug_assertions)]
+static CAPTURE_BACKTRACE: Lazy<bool> =
+    Lazy::new(|| std::env::var_os("TURBO_TASKS_READ_REF_BACKTRACE").is_some_and(|v| v != "0"));
+
+#[cfg(debug_assertions)]
+impl ReadRefOrigin {
+    fn current() -> Option<Self> {
+        let (task_id, execution_id) = try_current_task_execution()?;
+        Some(Self {
+            task_id,
+            execution_id,
+            backtrace: CAPTURE_BACKTRACE.then(|| Arc::new(Backtrace::force_capture())),
+        })
+    }
+}
 
 impl<T> Clone for ReadRef<T> {
     fn clone(&self) -> Self {
-        Self(self.0.clone())
+        Self {
+            value: self.value.clone(),
+            #[cfg(debug_assertions)]
+            origin: self.origin.clone(),
+        }
     }
 }
 
@@ -38,7 +92,8 @@ where
     type Target = VcReadTarget<T>;
 
     fn deref(&self) -> &Self::Target {
-        T::Read::value_to_target_ref(&self.0)
+        self.assert_current_execution();
+        self.value_unchecked()
     }
 }
 
@@ -48,7 +103,7 @@ where
     VcReadTarget<T>: Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Display::fmt(&**self, f)
+        Display::fmt(self.value_unchecked(), f)
     }
 }
 
@@ -58,7 +113,7 @@ where
     VcReadTarget<T>: Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Debug::fmt(&**self, f)
+        Debug::fmt(self.value_unchecked(), f)
     }
 }
 
@@ -68,7 +123,7 @@ where
     VcReadTarget<T>: TraceRawVcs,
 {
     fn trace_raw_vcs(&self, trace_context: &mut TraceRawVcsContext) {
-        (**self).trace_raw_vcs(trace_context);
+        self.value_unchecked().trace_raw_vcs(trace_context);
     }
 }
 
@@ -78,8 +133,11 @@ where
     VcReadTarget<T>: ValueDebugFormat + 'static,
 {
     fn value_debug_format(&self, depth: usize) -> ValueDebugFormatString {
-        let value = &**self;
-        value.value_debug_format(depth)
+        self.value_unchecked().value_debug_format(depth)
+    }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        self.value_unchecked().value_debug_json(depth)
     }
 }
 
@@ -89,7 +147,7 @@ where
     VcReadTarget<T>: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
-        PartialEq::eq(&**self, &**other)
+        PartialEq::eq(self.value_unchecked(), other.value_unchecked())
     }
 }
 
@@ -106,7 +164,7 @@ where
     VcReadTarget<T>: PartialOrd,
 {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        PartialOrd::partial_cmp(&**self, &**other)
+        PartialOrd::partial_cmp(self.value_unchecked(), other.value_unchecked())
     }
 }
 
@@ -116,7 +174,7 @@ where
     VcReadTarget<T>: Ord,
 {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        Ord::cmp(&**self, &**other)
+        Ord::cmp(self.value_unchecked(), other.value_unchecked())
     }
 }
 
@@ -126,7 +184,7 @@ where
     VcReadTarget<T>: Hash,
 {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        Hash::hash(&**self, state)
+        Hash::hash(self.value_unchecked(), state)
     }
 }
 
@@ -136,8 +194,7 @@ where
     VcReadTarget<T>: DeterministicHash,
 {
     fn deterministic_hash<H: turbo_tasks_hash::DeterministicHasher>(&self, state: &mut H) {
-        let p = &**self;
-        p.deterministic_hash(state);
+        self.value_unchecked().deterministic_hash(state);
     }
 }
 
@@ -205,7 +262,7 @@ where
     where
         S: serde::Serializer,
     {
-        (**self).serialize(serializer)
+        self.value_unchecked().serialize(serializer)
     }
 }
 
@@ -218,21 +275,62 @@ where
         D: serde::Deserializer<'de>,
     {
         let value = T::deserialize(deserializer)?;
-        Ok(Self(triomphe::Arc::new(value)))
+        Ok(Self::new_owned(value))
     }
 }
 
 impl<T> ReadRef<T> {
     pub fn new_owned(value: T) -> Self {
-        Self(triomphe::Arc::new(value))
+        Self::new_arc(triomphe::Arc::new(value))
     }
 
     pub fn new_arc(arc: triomphe::Arc<T>) -> Self {
-        Self(arc)
+        Self {
+            value: arc,
+            #[cfg(debug_assertions)]
+            origin: ReadRefOrigin::current(),
+        }
     }
 
     pub fn ptr_eq(&self, other: &ReadRef<T>) -> bool {
-        triomphe::Arc::ptr_eq(&self.0, &other.0)
+        triomphe::Arc::ptr_eq(&self.value, &other.value)
+    }
+
+    /// Panics in debug builds when the value was read in a previous execution
+    /// of the current task.
+    fn assert_current_execution(&self) {
+        #[cfg(debug_assertions)]
+        if let Some(origin) = &self.origin {
+            if try_current_task_execution().is_some_and(|(task_id, execution_id)| {
+                task_id == origin.task_id && execution_id != origin.execution_id
+            }) {
+                let read_at = match &origin.backtrace {
+                    Some(backtrace) => format!("The ReadRef was read at:\n{backtrace}"),
+                    None => "Set TURBO_TASKS_READ_REF_BACKTRACE=1 to see where the ReadRef was \
+                             read."
+                        .to_string(),
+                };
+                panic!(
+                    "A ReadRef was read in a previous execution of the task {} and is used in a \
+                     later one. The later execution doesn't depend on the cell it was read from, \
+                     so it won't be invalidated when the cell changes. Read the Vc again instead \
+                     of keeping the ReadRef, e.g. in a State or a global cache.\n\n{read_at}",
+                    origin.task_id
+                );
+            }
+        }
+    }
+}
+
+impl<T> ReadRef<T>
+where
+    T: VcValueType,
+{
+    /// The value without checking where it was read. Used for comparing,
+    /// hashing and serializing, e.g. when the backend compares a new cell value
+    /// with the previous one, which was read in an earlier execution.
+    fn value_unchecked(&self) -> &VcReadTarget<T> {
+        T::Read::value_to_target_ref(&self.value)
     }
 }
 
@@ -243,11 +341,12 @@ where
     /// Returns a new cell that points to the same value as the given
     /// reference.
     pub fn cell(read_ref: ReadRef<T>) -> Vc<T> {
+        read_ref.assert_current_execution();
         let type_id = T::get_value_type_id();
         // SAFETY: `T` and `T::Read::Repr` must have equivalent memory representations,
         // guaranteed by the unsafe implementation of `VcValueType`.
         let value = unsafe {
-            unchecked_sidecast_triomphe_arc::<T, <T::Read as VcRead<T>>::Repr>(read_ref.0)
+            unchecked_sidecast_triomphe_arc::<T, <T::Read as VcRead<T>>::Repr>(read_ref.value)
         };
         Vc {
             node: <T::CellMode as VcCellMode<T>>::raw_cell(
@@ -1,19 +1,24 @@
 use std::{
-    fmt::Debug,
+    fmt::{Debug, Display},
     hash::{BuildHasherDefault, Hash},
     num::NonZeroU64,
     ops::Deref,
+    sync::Mutex,
 };
 
+use anyhow::{bail, Result};
 use dashmap::{mapref::entry::Entry, DashMap};
+use indexmap::map::Entry as IndexEntry;
 use once_cell::sync::Lazy;
 use rustc_hash::FxHasher;
+use serde::{Deserialize, Serialize};
+use turbo_tasks_hash::hash_xxh3_hash64;
 
 use crate::{
     id::{FunctionId, TraitTypeId, ValueTypeId},
     id_factory::IdFactory,
     no_move_vec::NoMoveVec,
-    NativeFunction, TraitType, ValueType,
+    FxIndexMap, NativeFunction, TraitType, ValueType,
 };
 
 type FxDashMap<K, V> = DashMap<K, V, BuildHasherDefault<FxHasher>>;
@@ -156,3 +161,312 @@ pub fn get_trait(id: TraitTypeId) -> &'static TraitType {
 pub fn get_trait_type_global_name(id: TraitTypeId) -> &'static str {
     TRAIT_TYPES.get(*id as usize).unwrap().1
 }
+
+/// Identifies a value type, trait type or function of a plugin crate that is
+/// loaded at runtime, e.g. a native plugin that provides module types to
+/// turbopack.
+///
+/// Types and functions that are linked into the binary are registered by the
+/// code that `turbo-tasks-build` generates. Plugins register theirs with
+/// [register_plugin_value_type], [register_plugin_trait_type] and
+/// [register_plugin_function] instead, before any of them are used and before
+/// the backing storage of the persistent cache is opened.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PluginTypeKey {
+    /// The namespace of the plugin, usually its crate name.
+    pub namespace: String,
+    /// The name of the type within the namespace, e.g. its module path.
+    pub name: String,
+    /// A hash of the schema of the type. It needs to change whenever values
+    /// that were persisted with a previous version of the plugin can't be
+    /// read anymore.
+    pub schema_hash: u64,
+}
+
+impl PluginTypeKey {
+    pub fn new(namespace: impl Into<String>, name: impl Into<String>, schema_hash: u64) -> Self {
+        Self {
+            namespace: namespace.into(),
+            name: name.into(),
+            schema_hash,
+        }
+    }
+
+    /// An id derived from the namespace, the name and the schema hash, which
+    /// is the same in every process.
+    pub fn stable_id(&self) -> u64 {
+        hash_xxh3_hash64((&self.namespace, &self.name, self.schema_hash))
+    }
+
+    /// The global name of the type, which identifies it in persisted data. It
+    /// contains the stable id, so values that were persisted with another
+    /// schema are never read as this type.
+    pub fn global_name(&self) -> String {
+        format!(
+            "{}@{:016x}::{}",
+            self.namespace,
+            self.stable_id(),
+            self.name
+        )
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.namespace.is_empty() || self.namespace.contains(['@', ':']) {
+            bail!(
+                "invalid namespace {:?} of plugin type {}, it must be non-empty and must not \
+                 contain '@' or ':'",
+                self.namespace,
+                self.name
+            );
+        }
+        if self.name.is_empty() {
+            bail!(
+                "plugin type of namespace {} has an empty name",
+                self.namespace
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Display for PluginTypeKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}::{}", self.namespace, self.name)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PluginThing {
+    ValueType(&'static ValueType),
+    TraitType(&'static TraitType),
+    Function(&'static NativeFunction),
+}
+
+impl PluginThing {
+    fn is_registered(self) -> bool {
+        match self {
+            PluginThing::ValueType(ty) => VALUE_TYPES_BY_VALUE.contains_key(ty),
+            PluginThing::TraitType(ty) => TRAIT_TYPES_BY_VALUE.contains_key(ty),
+            PluginThing::Function(func) => FUNCTIONS_BY_VALUE.contains_key(&func),
+        }
+    }
+
+    fn register(self, global_name: &'static str) {
+        match self {
+            PluginThing::ValueType(ty) => register_value_type(global_name, ty),
+            PluginThing::TraitType(ty) => register_trait_type(global_name, ty),
+            PluginThing::Function(func) => register_function(global_name, func),
+        }
+    }
+}
+
+/// The registered plugin types by namespace and name, with their schema hash.
+static PLUGIN_TYPES: Lazy<Mutex<FxIndexMap<(String, String), (u64, PluginThing)>>> =
+    Lazy::new(Default::default);
+
+fn register_plugin_thing(key: &PluginTypeKey, thing: PluginThing) -> Result<()> {
+    key.validate()?;
+    let mut plugin_types = PLUGIN_TYPES.lock().unwrap();
+    match plugin_types.entry((key.namespace.clone(), key.name.clone())) {
+        IndexEntry::Occupied(e) => {
+            let (schema_hash, registered) = *e.get();
+            if schema_hash != key.schema_hash {
+                bail!(
+                    "plugin type {key} is already registered with schema hash {schema_hash:016x}, \
+                     but is registered again with schema hash {:016x}. Plugins that share types \
+                     need to be built from the same version.",
+                    key.schema_hash
+                );
+            }
+            if registered != thing {
+                bail!("another type is already registered as plugin type {key}");
+            }
+            // Registering the same type again, e.g. when a plugin is loaded again, is allowed.
+            Ok(())
+        }
+        IndexEntry::Vacant(e) => {
+            if thing.is_registered() {
+                bail!("plugin type {key} is already registered as a type of the binary");
+            }
+            let global_name = key.global_name();
+            if VALUE_TYPES_BY_NAME.contains_key(global_name.as_str())
+                || TRAIT_TYPES_BY_NAME.contains_key(global_name.as_str())
+                || FUNCTIONS_BY_NAME.contains_key(global_name.as_str())
+            {
+                bail!("the global name {global_name} of plugin type {key} is already taken");
+            }
+            // Plugins stay loaded until the process exits, like their types.
+            thing.register(Box::leak(global_name.into_boxed_str()));
+            e.insert((key.schema_hash, thing));
+            Ok(())
+        }
+    }
+}
+
+/// Registers a value type of a plugin crate that is loaded at runtime.
+///
+/// Fails when another type or another schema of the type is registered with
+/// the same namespace and name already. Registering the same type again
+/// returns the id it already has.
+pub fn register_plugin_value_type(
+    key: &PluginTypeKey,
+    ty: &'static ValueType,
+) -> Result<ValueTypeId> {
+    register_plugin_thing(key, PluginThing::ValueType(ty))?;
+    Ok(get_value_type_id(ty))
+}
+
+/// Registers a trait type of a plugin crate that is loaded at runtime, see
+/// [register_plugin_value_type].
+pub fn register_plugin_trait_type(
+    key: &PluginTypeKey,
+    ty: &'static TraitType,
+) -> Result<TraitTypeId> {
+    register_plugin_thing(key, PluginThing::TraitType(ty))?;
+    Ok(get_trait_type_id(ty))
+}
+
+/// Registers a function of a plugin crate that is loaded at runtime, i.e. the
+/// [NativeFunction] of a `#[turbo_tasks::function]` or of a method of a value
+/// type, see [register_plugin_value_type]. Tasks of unregistered functions
+/// can't be created.
+pub fn register_plugin_function(
+    key: &PluginTypeKey,
+    func: &'static NativeFunction,
+) -> Result<FunctionId> {
+    register_plugin_thing(key, PluginThing::Function(func))?;
+    Ok(get_function_id(func))
+}
+
+/// The keys of all registered plugin types, in registration order. They are
+/// stored with persisted data, so [check_plugin_type_compatibility] can check
+/// whether it can still be read.
+pub fn plugin_type_keys() -> Vec<PluginTypeKey> {
+    PLUGIN_TYPES
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|((namespace, name), (schema_hash, _))| {
+            PluginTypeKey::new(namespace.clone(), name.clone(), *schema_hash)
+        })
+        .collect()
+}
+
+/// Checks whether data that was persisted while the plugin types `persisted`
+/// were registered can be read with the plugin types that are registered now.
+///
+/// Fails with a list of the types that are not registered anymore or whose
+/// schema changed, in which case the persisted data needs to be discarded.
+/// Types that were registered since are compatible.
+pub fn check_plugin_type_compatibility(persisted: &[PluginTypeKey]) -> Result<()> {
+    let plugin_types = PLUGIN_TYPES.lock().unwrap();
+    let mut incompatible = Vec::new();
+    for key in persisted {
+        match plugin_types.get(&(key.namespace.clone(), key.name.clone())) {
+            Some((schema_hash, _)) if *schema_hash == key.schema_hash => {}
+            Some(_) => incompatible.push(format!("{key} (schema changed)")),
+            None => incompatible.push(format!("{key} (not registered)")),
+        }
+    }
+    if !incompatible.is_empty() {
+        bail!(
+            "persisted data uses plugin types that are incompatible: {}",
+            incompatible.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native_function::FunctionMeta;
+
+    fn trait_type(name: &str) -> &'static TraitType {
+        Box::leak(Box::new(TraitType::new(name.to_string())))
+    }
+
+    #[test]
+    fn plugin_type_global_name_is_stable() {
+        let key = PluginTypeKey::new("my-plugin", "module::MyModule", 1);
+        assert_eq!(key.stable_id(), key.clone().stable_id());
+        assert_ne!(
+            key.stable_id(),
+            PluginTypeKey::new("my-plugin", "module::MyModule", 2).stable_id()
+        );
+        assert_eq!(
+            key.global_name(),
+            format!("my-plugin@{:016x}::module::MyModule", key.stable_id())
+        );
+    }
+
+    #[test]
+    fn register_plugin_trait_types() {
+        let key = PluginTypeKey::new("registry-test", "MyTrait", 1);
+        let ty = trait_type("MyTrait");
+        let id = register_plugin_trait_type(&key, ty).unwrap();
+        assert_eq!(get_trait_type_global_name(id), key.global_name());
+        assert_eq!(
+            get_trait_type_id_by_global_name(&key.global_name()),
+            Some(id)
+        );
+
+        // Registering again is allowed, conflicts are not.
+        assert_eq!(register_plugin_trait_type(&key, ty).unwrap(), id);
+        assert!(register_plugin_trait_type(&key, trait_type("MyTrait")).is_err());
+        assert!(
+            register_plugin_trait_type(&PluginTypeKey::new("registry-test", "MyTrait", 2), ty)
+                .is_err()
+        );
+        assert!(
+            register_plugin_trait_type(&PluginTypeKey::new("registry@test", "MyTrait", 1), ty)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn register_plugin_functions() {
+        fn plugin_function() {}
+
+        let key = PluginTypeKey::new("registry-test", "plugin_function", 1);
+        let func: &'static NativeFunction = Box::leak(Box::new(NativeFunction::new_function(
+            "plugin_function".to_string(),
+            FunctionMeta {
+                local_cells: false,
+                ttl: None,
+            },
+            plugin_function,
+        )));
+        let id = register_plugin_function(&key, func).unwrap();
+        assert_eq!(get_function_id(func), id);
+        assert_eq!(get_function_global_name(id), key.global_name());
+        assert_eq!(get_function_id_by_global_name(&key.global_name()), Some(id));
+        assert_eq!(register_plugin_function(&key, func).unwrap(), id);
+
+        // The global name is taken by the function already.
+        let ty = trait_type("plugin_function");
+        assert!(register_plugin_trait_type(&key, ty).is_err());
+    }
+
+    #[test]
+    fn check_persisted_plugin_types() {
+        let key = PluginTypeKey::new("registry-compat-test", "MyTrait", 1);
+        register_plugin_trait_type(&key, trait_type("MyTrait")).unwrap();
+        assert!(plugin_type_keys().contains(&key));
+
+        check_plugin_type_compatibility(&[key.clone()]).unwrap();
+        check_plugin_type_compatibility(&[]).unwrap();
+        assert!(check_plugin_type_compatibility(&[PluginTypeKey::new(
+            "registry-compat-test",
+            "MyTrait",
+            2
+        )])
+        .is_err());
+        assert!(check_plugin_type_compatibility(&[PluginTypeKey::new(
+            "registry-compat-test",
+            "Removed",
+            1
+        )])
+        .is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::oneshot;
+
+use crate::{FxIndexMap, RcStr};
+
+/// Limits the number of root task executions that run concurrently. See
+/// [`crate::TurboTasks::set_root_task_concurrency`].
+///
+/// Executions that exceed the limit wait in a queue per requester, and the
+/// queues are served in turn, so a requester that spawns many root tasks can't
+/// starve the other requesters.
+#[derive(Default)]
+pub(crate) struct RootTaskAdmission {
+    state: Mutex<AdmissionState>,
+}
+
+#[derive(Default)]
+struct AdmissionState {
+    /// The maximum number of running executions, or `None` when unlimited.
+    limit: Option<usize>,
+    running: usize,
+    /// The waiting executions by requester. Empty queues are removed.
+    queues: FxIndexMap<RcStr, VecDeque<oneshot::Sender<RootTaskPermit>>>,
+    /// The index of the queue that is served next.
+    next_queue: usize,
+    total_queued: u64,
+    total_wait_time: Duration,
+}
+
+/// The metrics of the root task admission, see
+/// [`crate::TurboTasks::root_task_admission_metrics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RootTaskAdmissionMetrics {
+    /// The maximum number of concurrently running root task executions, or
+    /// `None` when unlimited.
+    pub limit: Option<usize>,
+    /// The number of root task executions that are running.
+    pub running: usize,
+    /// The number of waiting root task executions by requester.
+    pub queued: Vec<(RcStr, usize)>,
+    /// The number of root task executions that had to wait since the start.
+    pub total_queued: u64,
+    /// The time all root task executions spent waiting since the start.
+    pub total_wait_time: Duration,
+}
+
+/// Allows a root task execution to run. The slot is released when the permit
+/// is dropped.
+pub(crate) struct RootTaskPermit {
+    admission: Option<Arc<RootTaskAdmission>>,
+}
+
+impl Drop for RootTaskPermit {
+    fn drop(&mut self) {
+        if let Some(admission) = self.admission.take() {
+            admission.release();
+        }
+    }
+}
+
+impl RootTaskAdmission {
+    pub fn set_limit(self: &Arc<Self>, limit: Option<usize>) {
+        let mut state = self.state.lock().unwrap();
+        state.limit = limit.map(|limit| limit.max(1));
+        self.admit_waiting(&mut state);
+    }
+
+    /// Waits until the execution of a root task of `requester` may run.
+    pub async fn acquire(self: &Arc<Self>, requester: RcStr) -> RootTaskPermit {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+            if state.has_free_slot() {
+                state.running += 1;
+                return self.permit();
+            }
+            let (sender, receiver) = oneshot::channel();
+            state.queues.entry(requester).or_default().push_back(sender);
+            state.total_queued += 1;
+            receiver
+        };
+
+        let start = Instant::now();
+        let permit = match receiver.await {
+            Ok(permit) => permit,
+            // The queues were dropped with the turbo tasks instance, there is
+            // nothing left to limit
+            Err(_) => RootTaskPermit { admission: None },
+        };
+        self.state.lock().unwrap().total_wait_time += start.elapsed();
+        permit
+    }
+
+    pub fn metrics(&self) -> RootTaskAdmissionMetrics {
+        let state = self.state.lock().unwrap();
+        RootTaskAdmissionMetrics {
+            limit: state.limit,
+            running: state.running,
+            queued: state
+                .queues
+                .iter()
+                .map(|(requester, queue)| (requester.clone(), queue.len()))
+                .collect(),
+            total_queued: state.total_queued,
+            total_wait_time: state.total_wait_time,
+        }
+    }
+
+    fn permit(self: &Arc<Self>) -> RootTaskPermit {
+        RootTaskPermit {
+            admission: Some(self.clone()),
+        }
+    }
+
+    fn release(self: &Arc<Self>) {
+        let mut state = self.state.lock().unwrap();
+        state.running -= 1;
+        self.admit_waiting(&mut state);
+    }
+
+    fn admit_waiting(self: &Arc<Self>, state: &mut AdmissionState) {
+        while state.has_free_slot() {
+            let Some(sender) = state.next_waiter() else {
+                break;
+            };
+            state.running += 1;
+            if let Err(mut permit) = sender.send(self.permit()) {
+                // The execution stopped waiting. Releasing the permit here
+                // would lock the state again.
+                permit.admission = None;
+                state.running -= 1;
+            }
+        }
+    }
+}
+
+impl AdmissionState {
+    fn has_free_slot(&self) -> bool {
+        self.limit.map_or(true, |limit| self.running < limit)
+    }
+
+    /// Takes the first waiter of the requester that is served next.
+    fn next_waiter(&mut self) -> Option<oneshot::Sender<RootTaskPermit>> {
+        if self.queues.is_empty() {
+            return None;
+        }
+        if self.next_queue >= self.queues.len() {
+            self.next_queue = 0;
+        }
+        let (_, queue) = self.queues.get_index_mut(self.next_queue)?;
+        let waiter = queue.pop_front();
+        if queue.is_empty() {
+            // The next queue moves to the current index
+            self.queues.shift_remove_index(self.next_queue);
+        } else {
+            self.next_queue += 1;
+        }
+        waiter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::RootTaskAdmission;
+
+    #[tokio::test]
+    async fn admits_requesters_in_turn() {
+        let admission = Arc::new(RootTaskAdmission::default());
+        admission.set_limit(Some(1));
+
+        let running = admission.acquire("build".into()).await;
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        for requester in ["build", "build", "build", "hmr"] {
+            let admission = admission.clone();
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                let permit = admission.acquire(requester.into()).await;
+                sender.send((requester, permit)).unwrap();
+            });
+            tokio::task::yield_now().await;
+        }
+
+        let metrics = admission.metrics();
+        assert_eq!(metrics.running, 1);
+        assert_eq!(metrics.queued, vec![("build".into(), 3), ("hmr".into(), 1)]);
+
+        drop(running);
+        let mut order = Vec::new();
+        for _ in 0..4 {
+            let (requester, permit) = receiver.recv().await.unwrap();
+            order.push(requester);
+            drop(permit);
+        }
+        assert_eq!(order, vec!["build", "hmr", "build", "build"]);
+        assert_eq!(admission.metrics().running, 0);
+    }
+
+    #[tokio::test]
+    async fn unlimited_by_default() {
+        let admission = Arc::new(RootTaskAdmission::default());
+        let permits = [
+            admission.acquire("a".into()).await,
+            admission.acquire("a".into()).await,
+        ];
+        assert_eq!(admission.metrics().running, 2);
+        drop(permits);
+        assert_eq!(admission.metrics().running, 0);
+    }
+}
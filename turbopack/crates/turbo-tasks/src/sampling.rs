@@ -0,0 +1,286 @@
+use std::{
+    cell::RefCell,
+    fmt::Write,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Context, Poll},
+    thread,
+    time::Duration,
+};
+
+use pin_project_lite::pin_project;
+use rustc_hash::FxHashMap;
+
+use crate::TaskId;
+
+/// Stacks are cut off at this depth, so deep recursions don't make sampling
+/// expensive.
+const MAX_STACK_DEPTH: usize = 64;
+
+/// The names of the task functions of an execution, from the outermost parent
+/// task to the executing task.
+type TaskStack = Arc<[&'static str]>;
+
+/// The task that is being polled on a thread, tagged with the id of the
+/// profiler it was scheduled with.
+#[derive(Default)]
+struct ThreadSlot {
+    current: Mutex<Option<(usize, TaskStack)>>,
+}
+
+/// The slots of all threads that ever polled a sampled task.
+static THREAD_SLOTS: Mutex<Vec<Weak<ThreadSlot>>> = Mutex::new(Vec::new());
+
+static NEXT_PROFILER_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_SLOT: RefCell<Option<Arc<ThreadSlot>>> = const { RefCell::new(None) };
+}
+
+fn with_thread_slot<T>(f: impl FnOnce(&ThreadSlot) -> T) -> T {
+    THREAD_SLOT.with(|slot| {
+        let mut slot = slot.borrow_mut();
+        let slot = slot.get_or_insert_with(|| {
+            let slot = Arc::new(ThreadSlot::default());
+            let mut slots = THREAD_SLOTS.lock().unwrap();
+            slots.retain(|slot| slot.strong_count() > 0);
+            slots.push(Arc::downgrade(&slot));
+            slot
+        });
+        f(slot)
+    })
+}
+
+/// A profile recorded by the sampling profiler, see
+/// [`crate::TurboTasks::start_sampling_profiler`].
+#[derive(Debug, Clone, Default)]
+pub struct SamplingProfile {
+    /// The time between two samples.
+    pub interval: Duration,
+    /// The number of times each stack of task functions was executing when a
+    /// thread was sampled, most frequent first. Stacks start with the
+    /// outermost parent task.
+    pub stacks: Vec<(Vec<&'static str>, u64)>,
+}
+
+impl SamplingProfile {
+    /// Formats the profile as folded stacks, one `parent;child count` line per
+    /// stack, which most flame graph tools can read.
+    pub fn to_folded(&self) -> String {
+        let mut folded = String::new();
+        for (stack, count) in &self.stacks {
+            let _ = writeln!(folded, "{} {count}", stack.join(";"));
+        }
+        folded
+    }
+}
+
+/// Periodically records which task functions are being executed on each
+/// thread. Only the thread that polls a task is known, so tasks that are
+/// waiting for other tasks are not sampled.
+pub(crate) struct SamplingProfiler {
+    id: usize,
+    interval: Duration,
+    stopped: AtomicBool,
+    samples: Mutex<FxHashMap<TaskStack, u64>>,
+    /// The stack of each task that was scheduled, so a task that is executed
+    /// again keeps the parents it was scheduled by the first time, and not the
+    /// task that invalidated it.
+    task_stacks: Mutex<FxHashMap<TaskId, TaskStack>>,
+}
+
+impl SamplingProfiler {
+    /// Creates a profiler and starts the thread that samples every `interval`
+    /// until the profiler is stopped or dropped.
+    pub fn start(interval: Duration) -> Arc<Self> {
+        let profiler = Arc::new(Self {
+            id: NEXT_PROFILER_ID.fetch_add(1, Ordering::Relaxed),
+            interval,
+            stopped: AtomicBool::new(false),
+            samples: Mutex::new(FxHashMap::default()),
+            task_stacks: Mutex::new(FxHashMap::default()),
+        });
+        let weak = Arc::downgrade(&profiler);
+        thread::Builder::new()
+            .name("turbo-tasks sampling profiler".to_string())
+            .spawn(move || loop {
+                thread::sleep(interval);
+                let Some(profiler) = weak.upgrade() else {
+                    return;
+                };
+                if profiler.stopped.load(Ordering::Acquire) {
+                    return;
+                }
+                profiler.sample();
+            })
+            .expect("failed to spawn the sampling profiler thread");
+        profiler
+    }
+
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Release);
+    }
+
+    fn sample(&self) {
+        let slots = THREAD_SLOTS
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect::<Vec<_>>();
+        let mut samples = self.samples.lock().unwrap();
+        for slot in slots {
+            if let Some((id, stack)) = &*slot.current.lock().unwrap() {
+                if *id == self.id {
+                    *samples.entry(stack.clone()).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    /// The samples recorded so far.
+    pub fn profile(&self) -> SamplingProfile {
+        let mut stacks = self
+            .samples
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(stack, count)| (stack.to_vec(), *count))
+            .collect::<Vec<_>>();
+        stacks.sort_by(|(a_stack, a_count), (b_stack, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_stack.cmp(b_stack))
+        });
+        SamplingProfile {
+            interval: self.interval,
+            stacks,
+        }
+    }
+
+    /// The stack of the task `task_id` with the function `name`. When it's
+    /// scheduled for the first time, that's the stack of the task that is
+    /// currently polled on this thread, if any.
+    pub fn task_stack(&self, task_id: TaskId, name: &'static str) -> TaskStack {
+        if let Some(stack) = self.task_stacks.lock().unwrap().get(&task_id) {
+            return stack.clone();
+        }
+        let parent = with_thread_slot(|slot| {
+            slot.current
+                .lock()
+                .unwrap()
+                .as_ref()
+                .filter(|(id, _)| *id == self.id)
+                .map(|(_, stack)| stack.clone())
+        });
+        let parent = parent.as_deref().unwrap_or_default();
+        let skip = (parent.len() + 1).saturating_sub(MAX_STACK_DEPTH);
+        let stack: TaskStack = parent[skip..]
+            .iter()
+            .copied()
+            .chain([name])
+            .collect::<Vec<_>>()
+            .into();
+        self.task_stacks
+            .lock()
+            .unwrap()
+            .entry(task_id)
+            .or_insert(stack)
+            .clone()
+    }
+
+    /// Wraps the execution of a task, so the profiler knows when it's polled.
+    pub fn sampled<F: Future>(&self, stack: TaskStack, future: F) -> SampledFuture<F> {
+        SampledFuture {
+            future,
+            profiler_id: self.id,
+            stack,
+        }
+    }
+}
+
+pin_project! {
+    /// A future that is visible to the [SamplingProfiler] while it's polled.
+    pub(crate) struct SampledFuture<F> {
+        #[pin]
+        future: F,
+        profiler_id: usize,
+        stack: TaskStack,
+    }
+}
+
+impl<F: Future> Future for SampledFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let previous = with_thread_slot(|slot| {
+            slot.current
+                .lock()
+                .unwrap()
+                .replace((*this.profiler_id, this.stack.clone()))
+        });
+        let result = this.future.poll(cx);
+        with_thread_slot(|slot| *slot.current.lock().unwrap() = previous);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use super::{SamplingProfile, SamplingProfiler};
+    use crate::TaskId;
+
+    #[test]
+    fn task_stacks() {
+        let profiler = SamplingProfiler::start(Duration::from_secs(3600));
+        let root = profiler.task_stack(TaskId::from(1), "root");
+        assert_eq!(&*root, ["root"]);
+
+        let child = futures::executor::block_on(profiler.sampled(root, async {
+            profiler.task_stack(TaskId::from(2), "child")
+        }));
+        assert_eq!(&*child, ["root", "child"]);
+        assert_eq!(&*profiler.task_stack(TaskId::from(3), "other"), ["other"]);
+        profiler.stop();
+    }
+
+    #[test]
+    fn reexecuted_tasks_keep_their_parents() {
+        let profiler = SamplingProfiler::start(Duration::from_secs(3600));
+        let root = profiler.task_stack(TaskId::from(1), "root");
+        let child = futures::executor::block_on(profiler.sampled(root, async {
+            profiler.task_stack(TaskId::from(2), "child")
+        }));
+
+        // The child is invalidated by another task and scheduled again.
+        let invalidator = profiler.task_stack(TaskId::from(3), "invalidator");
+        let reexecuted = futures::executor::block_on(profiler.sampled(invalidator, async {
+            profiler.task_stack(TaskId::from(2), "child")
+        }));
+        assert_eq!(reexecuted, child);
+        assert_eq!(&*reexecuted, ["root", "child"]);
+        profiler.stop();
+    }
+
+    #[test]
+    fn samples_polled_tasks() {
+        let profiler = SamplingProfiler::start(Duration::from_secs(3600));
+        let stack: Arc<[&'static str]> = Arc::new(["root", "child"]);
+        futures::executor::block_on(profiler.sampled(stack, async {
+            profiler.sample();
+            profiler.sample();
+        }));
+        profiler.sample();
+        profiler.stop();
+
+        let profile = profiler.profile();
+        assert_eq!(profile.stacks, vec![(vec!["root", "child"], 2)]);
+        assert_eq!(profile.to_folded(), "root;child 2\n");
+        assert_eq!(SamplingProfile::default().to_folded(), "");
+    }
+}
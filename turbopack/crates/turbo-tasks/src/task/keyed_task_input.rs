@@ -0,0 +1,144 @@
+use std::{
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::TaskInput;
+
+/// Derives the cache key of a `#[turbo_tasks::function(key = ...)]` from its
+/// arguments. This is implemented by a marker type generated by the macro.
+pub trait TaskInputKey<Inputs>: 'static {
+    type Key: TaskInput;
+
+    fn key(inputs: &Inputs) -> Self::Key;
+}
+
+/// The arguments of a `#[turbo_tasks::function(key = ...)]`.
+///
+/// They are hashed and compared by the derived key only, so all calls with the
+/// same key share a single task. That task is executed with the full arguments
+/// of the call that created it.
+pub struct KeyedTaskInput<K: TaskInputKey<T>, T> {
+    key: K::Key,
+    inputs: T,
+    _marker: PhantomData<fn() -> K>,
+}
+
+impl<K: TaskInputKey<T>, T> KeyedTaskInput<K, T> {
+    pub fn new(inputs: T) -> Self {
+        let key = K::key(&inputs);
+        debug_assert!(
+            key == K::key(&inputs),
+            "the key of a turbo_tasks::function must be deterministic, but deriving it twice from \
+             the same arguments returned {:?} and {:?}",
+            key,
+            K::key(&inputs),
+        );
+        Self {
+            key,
+            inputs,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn key(&self) -> &K::Key {
+        &self.key
+    }
+
+    pub fn into_inputs(self) -> T {
+        self.inputs
+    }
+}
+
+impl<K: TaskInputKey<T>, T: Clone> Clone for KeyedTaskInput<K, T> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            inputs: self.inputs.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K: TaskInputKey<T>, T: Debug> Debug for KeyedTaskInput<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedTaskInput")
+            .field("key", &self.key)
+            .field("inputs", &self.inputs)
+            .finish()
+    }
+}
+
+impl<K: TaskInputKey<T>, T> PartialEq for KeyedTaskInput<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: TaskInputKey<T>, T> Eq for KeyedTaskInput<K, T> {}
+
+impl<K: TaskInputKey<T>, T> Hash for KeyedTaskInput<K, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state)
+    }
+}
+
+impl<K: TaskInputKey<T>, T: TaskInput> TaskInput for KeyedTaskInput<K, T> {
+    const TRANSIENT: bool = T::TRANSIENT;
+
+    async fn resolve(&self) -> Result<Self> {
+        // The key is derived again, as it might contain unresolved `Vc`s.
+        Ok(Self::new(self.inputs.resolve().await?))
+    }
+
+    fn is_resolved(&self) -> bool {
+        self.inputs.is_resolved()
+    }
+
+    fn is_transient(&self) -> bool {
+        self.inputs.is_transient()
+    }
+}
+
+impl<K: TaskInputKey<T>, T: Serialize> Serialize for KeyedTaskInput<K, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.inputs.serialize(serializer)
+    }
+}
+
+impl<'de, K: TaskInputKey<T>, T: Deserialize<'de>> Deserialize<'de> for KeyedTaskInput<K, T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RcStr;
+
+    struct FirstKey;
+
+    impl TaskInputKey<(u32, RcStr)> for FirstKey {
+        type Key = u32;
+
+        fn key(inputs: &(u32, RcStr)) -> u32 {
+            inputs.0
+        }
+    }
+
+    #[test]
+    fn compares_by_key() {
+        let a = KeyedTaskInput::<FirstKey, _>::new((1, RcStr::from("a")));
+        let b = KeyedTaskInput::<FirstKey, _>::new((1, RcStr::from("b")));
+        let c = KeyedTaskInput::<FirstKey, _>::new((2, RcStr::from("a")));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(*a.key(), 1);
+        assert_eq!(&*b.into_inputs().1, "b");
+    }
+}
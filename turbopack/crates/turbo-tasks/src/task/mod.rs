@@ -1,11 +1,13 @@
 mod from_task_input;
 pub(crate) mod function;
+pub(crate) mod keyed_task_input;
 pub(crate) mod shared_reference;
 pub(crate) mod task_input;
 pub(crate) mod task_output;
 
 pub use from_task_input::FromTaskInput;
 pub use function::{AsyncFunctionMode, FunctionMode, IntoTaskFn, TaskFn};
+pub use keyed_task_input::{KeyedTaskInput, TaskInputKey};
 pub use shared_reference::{SharedReference, TypedSharedReference};
 pub use task_input::TaskInput;
 pub use task_output::TaskOutput;
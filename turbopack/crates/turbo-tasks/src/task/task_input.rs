@@ -12,6 +12,14 @@ use crate::{
 ///
 /// See also [`ConcreteTaskInput`].
 pub trait TaskInput: Send + Sync + Clone + Debug + PartialEq + Eq + Hash {
+    /// Whether values of this type are always transient, e.g. because they
+    /// contain a [`TransientInstance`]. Only functions marked with
+    /// `#[turbo_tasks::function(transient)]` accept such inputs.
+    ///
+    /// Values that are only transient at runtime, like [`Vc`]s of transient
+    /// tasks, are reported by [`TaskInput::is_transient`] instead.
+    const TRANSIENT: bool = false;
+
     fn resolve(&self) -> impl Future<Output = Result<Self>> + Send + '_ {
         async { Ok(self.clone()) }
     }
@@ -50,6 +58,8 @@ impl<T> TaskInput for Vec<T>
 where
     T: TaskInput,
 {
+    const TRANSIENT: bool = T::TRANSIENT;
+
     fn is_resolved(&self) -> bool {
         self.iter().all(TaskInput::is_resolved)
     }
@@ -71,6 +81,8 @@ impl<T> TaskInput for Option<T>
 where
     T: TaskInput,
 {
+    const TRANSIENT: bool = T::TRANSIENT;
+
     fn is_resolved(&self) -> bool {
         match self {
             Some(value) => value.is_resolved(),
@@ -155,6 +167,8 @@ impl<T> TaskInput for TransientValue<T>
 where
     T: MagicAny + Clone + Debug + Hash + Eq + 'static,
 {
+    const TRANSIENT: bool = true;
+
     fn is_transient(&self) -> bool {
         true
     }
@@ -192,6 +206,8 @@ impl<T> TaskInput for TransientInstance<T>
 where
     T: Sync + Send + 'static,
 {
+    const TRANSIENT: bool = true;
+
     fn is_transient(&self) -> bool {
         true
     }
@@ -224,6 +240,8 @@ macro_rules! tuple_impls {
         impl<$($name: TaskInput),+> TaskInput for ($($name,)+)
         where $($name: TaskInput),+
         {
+            const TRANSIENT: bool = $($name::TRANSIENT ||)+ false;
+
             #[allow(non_snake_case)]
             fn is_resolved(&self) -> bool {
                 let ($($name,)+) = self;
@@ -401,4 +419,18 @@ mod tests {
         ));
         Ok(())
     }
+
+    #[test]
+    fn test_transient_fields() {
+        #[derive(Clone, TaskInput, Eq, PartialEq, Hash, Debug)]
+        enum TransientFields {
+            Persistent(u32),
+            Transient { value: Option<TransientValue<u32>> },
+        }
+
+        assert!(!<MultipleVariantsAndHeterogeneousFields as TaskInput>::TRANSIENT);
+        assert!(<TransientFields as TaskInput>::TRANSIENT);
+        assert!(<(RcStr, TransientInstance<()>) as TaskInput>::TRANSIENT);
+        assert!(!<Vec<Vc<()>> as TaskInput>::TRANSIENT);
+    }
 }
@@ -0,0 +1,134 @@
+use std::{
+    fmt::{Display, Formatter},
+    mem::take,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::{util::StaticOrArc, FxIndexSet, InvalidationReason, InvalidationReasonKind, TaskId};
+
+/// The deadlines of the results of tasks of functions with a `ttl`, see
+/// `#[turbo_tasks::function(ttl = "...")]`.
+#[derive(Default)]
+pub(crate) struct TaskExpiry {
+    deadlines: Mutex<FxHashMap<TaskId, Instant>>,
+    /// Tasks that expired while the engine was idle. They are invalidated once
+    /// it becomes busy again, so expiry doesn't recompute anything in an idle
+    /// process.
+    expired_while_idle: Mutex<FxIndexSet<TaskId>>,
+}
+
+impl TaskExpiry {
+    /// Records that the result of `task` expires after `ttl`. Returns the
+    /// deadline, which is passed to [TaskExpiry::expire] once it's reached.
+    pub fn insert(&self, task: TaskId, ttl: Duration) -> Instant {
+        let deadline = Instant::now() + ttl;
+        self.deadlines.lock().unwrap().insert(task, deadline);
+        deadline
+    }
+
+    /// Expires the result of `task` when `deadline` is still its deadline,
+    /// i.e. the task wasn't executed again in the meantime. Returns true when
+    /// the task needs to be invalidated now, which is deferred when the engine
+    /// is `idle`.
+    pub fn expire(&self, task: TaskId, deadline: Instant, idle: bool) -> bool {
+        {
+            let mut deadlines = self.deadlines.lock().unwrap();
+            if deadlines.get(&task) != Some(&deadline) {
+                return false;
+            }
+            deadlines.remove(&task);
+        }
+        if idle {
+            self.expired_while_idle.lock().unwrap().insert(task);
+            return false;
+        }
+        true
+    }
+
+    /// Removes and returns the tasks that expired while the engine was idle.
+    pub fn take_expired_while_idle(&self) -> FxIndexSet<TaskId> {
+        take(&mut *self.expired_while_idle.lock().unwrap())
+    }
+}
+
+/// Invalidation was caused by the expiry of a task result, see
+/// `#[turbo_tasks::function(ttl = "...")]`.
+#[derive(PartialEq, Eq, Hash)]
+pub struct TtlExpiration {
+    pub function: &'static str,
+}
+
+impl InvalidationReason for TtlExpiration {
+    fn kind(&self) -> Option<StaticOrArc<dyn InvalidationReasonKind>> {
+        Some(StaticOrArc::Static(&TTL_EXPIRATION_KIND))
+    }
+}
+
+impl Display for TtlExpiration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "result of {} expired", self.function)
+    }
+}
+
+/// Invalidation kind for [TtlExpiration]
+#[derive(PartialEq, Eq, Hash)]
+struct TtlExpirationKind;
+
+static TTL_EXPIRATION_KIND: TtlExpirationKind = TtlExpirationKind;
+
+impl InvalidationReasonKind for TtlExpirationKind {
+    fn fmt(
+        &self,
+        reasons: &FxIndexSet<StaticOrArc<dyn InvalidationReason>>,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            "{} task results expired ({}, ...)",
+            reasons.len(),
+            reasons[0]
+                .as_any()
+                .downcast_ref::<TtlExpiration>()
+                .unwrap()
+                .function
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::TaskExpiry;
+    use crate::TaskId;
+
+    #[test]
+    fn expires_latest_deadline() {
+        let expiry = TaskExpiry::default();
+        let task = unsafe { TaskId::new_unchecked(1) };
+        let outdated = expiry.insert(task, Duration::from_secs(1));
+        let deadline = expiry.insert(task, Duration::from_secs(2));
+        assert!(!expiry.expire(task, outdated, false));
+        assert!(expiry.expire(task, deadline, false));
+        assert!(!expiry.expire(task, deadline, false));
+    }
+
+    #[test]
+    fn defers_expiry_while_idle() {
+        let expiry = TaskExpiry::default();
+        let task = unsafe { TaskId::new_unchecked(1) };
+        let deadline = expiry.insert(task, Duration::from_secs(1));
+        assert!(!expiry.expire(task, deadline, true));
+        assert_eq!(
+            expiry
+                .take_expired_while_idle()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![task]
+        );
+        assert!(expiry.take_expired_while_idle().is_empty());
+    }
+}
@@ -0,0 +1,484 @@
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::TaskId;
+
+/// The priority of a task's executions when the number of concurrently
+/// running executions is limited, see
+/// [`crate::TurboTasks::set_task_concurrency`].
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum TaskPriority {
+    /// Work nobody waits for, e.g. a background compilation.
+    Background,
+    #[default]
+    Normal,
+    /// Work somebody waits for, e.g. an HMR update.
+    Interactive,
+}
+
+/// Limits the number of task executions that run concurrently and admits the
+/// waiting ones by priority.
+///
+/// A task that waits for another task inherits its priority to that task,
+/// and transitively to the tasks that task waits for, for the duration of the
+/// wait. Otherwise a high priority task could wait for a low priority task
+/// that is queued behind unrelated work of a medium priority. A waiting task
+/// gives up its slot until the wait is over, so tasks can't wait for tasks
+/// that never get a slot.
+#[derive(Default)]
+pub(crate) struct TaskScheduler {
+    /// Whether `limit` is set, so executions don't need to lock the state
+    /// while the concurrency is unlimited.
+    limited: AtomicBool,
+    state: Mutex<SchedulerState>,
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    /// The maximum number of running executions, or `None` when unlimited.
+    limit: Option<usize>,
+    running: usize,
+    /// The priorities set by [`TaskScheduler::set_priority`].
+    priorities: FxHashMap<TaskId, TaskPriority>,
+    /// The executions that were scheduled while the concurrency was limited
+    /// and didn't finish yet.
+    executions: FxHashMap<TaskId, Execution>,
+    /// Maps a waiting task to the tasks it waits for, with the number of
+    /// pending reads of each.
+    waits: FxHashMap<TaskId, FxHashMap<TaskId, usize>>,
+    /// The reverse of `waits`.
+    waiters: FxHashMap<TaskId, FxHashMap<TaskId, usize>>,
+    /// The executions that wait for a slot, by their effective priority and
+    /// in the order they started waiting.
+    queue: BTreeMap<(Reverse<TaskPriority>, u64), (TaskId, oneshot::Sender<()>)>,
+    next_seq: u64,
+}
+
+struct Execution {
+    /// The priority of the execution without inherited priorities.
+    priority: TaskPriority,
+    /// The key in the queue while the execution waits for a slot.
+    queued: Option<(Reverse<TaskPriority>, u64)>,
+    started: bool,
+    holds_slot: bool,
+    /// The number of reads of other tasks the execution waits for.
+    pending_reads: usize,
+}
+
+/// Allows a task execution to run. Its slot is released when the permit is
+/// dropped.
+pub(crate) struct TaskPermit {
+    scheduler: Option<(Arc<TaskScheduler>, TaskId)>,
+}
+
+impl Drop for TaskPermit {
+    fn drop(&mut self) {
+        if let Some((scheduler, task)) = self.scheduler.take() {
+            scheduler.finish(task);
+        }
+    }
+}
+
+impl TaskScheduler {
+    pub fn set_limit(&self, limit: Option<usize>) {
+        let mut state = self.state.lock().unwrap();
+        state.limit = limit.map(|limit| limit.max(1));
+        self.limited.store(limit.is_some(), Ordering::Release);
+        state.admit_waiting();
+    }
+
+    /// Sets the priority of the executions of `task`, including a scheduled
+    /// one.
+    pub fn set_priority(&self, task: TaskId, priority: TaskPriority) {
+        let mut state = self.state.lock().unwrap();
+        state.priorities.insert(task, priority);
+        if let Some(execution) = state.executions.get_mut(&task) {
+            execution.priority = priority;
+            state.update_queued(task);
+        }
+    }
+
+    pub fn remove_priority(&self, task: TaskId) {
+        self.state.lock().unwrap().priorities.remove(&task);
+    }
+
+    /// Returns the priority a task scheduled by `scheduled_by` inherits, or
+    /// `None` when the concurrency is unlimited.
+    pub fn inherited_priority(&self, scheduled_by: Option<TaskId>) -> Option<TaskPriority> {
+        if !self.limited.load(Ordering::Acquire) {
+            return None;
+        }
+        let state = self.state.lock().unwrap();
+        Some(scheduled_by.map_or(TaskPriority::default(), |task| {
+            state.effective_priority(task)
+        }))
+    }
+
+    /// Waits until an execution of `task` may run. It runs with the priority
+    /// set for the task, or else with the `inherited` priority of the task
+    /// that scheduled it.
+    pub async fn acquire(
+        self: &Arc<Self>,
+        task: TaskId,
+        inherited: Option<TaskPriority>,
+    ) -> TaskPermit {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+            if state.limit.is_none() {
+                return TaskPermit { scheduler: None };
+            }
+            let priority = state
+                .priorities
+                .get(&task)
+                .copied()
+                .or(inherited)
+                .unwrap_or_default();
+            state.executions.insert(
+                task,
+                Execution {
+                    priority,
+                    queued: None,
+                    started: false,
+                    holds_slot: false,
+                    pending_reads: 0,
+                },
+            );
+            state.take_slot(task)
+        };
+        if let Some(receiver) = receiver {
+            // The queue was dropped with the turbo tasks instance, there is
+            // nothing left to limit
+            let _ = receiver.await;
+        }
+        TaskPermit {
+            scheduler: Some((self.clone(), task)),
+        }
+    }
+
+    /// Records that `reader` waits for `task`, which gives up the slot of
+    /// `reader` and makes `task` inherit its priority. Returns `false` when
+    /// the concurrency is unlimited and nothing was recorded.
+    pub fn start_waiting(&self, reader: TaskId, task: TaskId) -> bool {
+        if !self.limited.load(Ordering::Acquire) {
+            return false;
+        }
+        let mut state = self.state.lock().unwrap();
+        *state
+            .waits
+            .entry(reader)
+            .or_default()
+            .entry(task)
+            .or_default() += 1;
+        *state
+            .waiters
+            .entry(task)
+            .or_default()
+            .entry(reader)
+            .or_default() += 1;
+        if let Some(execution) = state.executions.get_mut(&reader) {
+            execution.pending_reads += 1;
+            if execution.holds_slot {
+                execution.holds_slot = false;
+                state.running -= 1;
+            }
+        }
+        state.update_queued_below(task);
+        state.admit_waiting();
+        true
+    }
+
+    /// Removes a wait recorded by [`TaskScheduler::start_waiting`]. Returns
+    /// a receiver that completes once `reader` may continue, when it has to
+    /// wait for a slot again.
+    pub fn stop_waiting(&self, reader: TaskId, task: TaskId) -> Option<oneshot::Receiver<()>> {
+        let mut state = self.state.lock().unwrap();
+        if !remove_edge(&mut state.waits, reader, task) {
+            return None;
+        }
+        remove_edge(&mut state.waiters, task, reader);
+        state.update_queued_below(task);
+        let execution = state.executions.get_mut(&reader)?;
+        execution.pending_reads -= 1;
+        if execution.pending_reads > 0 || execution.holds_slot {
+            return None;
+        }
+        state.take_slot(reader)
+    }
+
+    fn finish(&self, task: TaskId) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(execution) = state.executions.remove(&task) {
+            if execution.holds_slot {
+                state.running -= 1;
+            }
+            if let Some(key) = execution.queued {
+                state.queue.remove(&key);
+            }
+        }
+        state.admit_waiting();
+    }
+
+    #[cfg(test)]
+    fn effective_priority(&self, task: TaskId) -> TaskPriority {
+        self.state.lock().unwrap().effective_priority(task)
+    }
+}
+
+impl SchedulerState {
+    fn has_free_slot(&self) -> bool {
+        self.limit.map_or(true, |limit| self.running < limit)
+    }
+
+    /// Gives the execution of `task` a slot, or queues it when there is no
+    /// free slot or when other executions wait for one already.
+    fn take_slot(&mut self, task: TaskId) -> Option<oneshot::Receiver<()>> {
+        if self.queue.is_empty() && self.has_free_slot() {
+            self.running += 1;
+            let execution = self.executions.get_mut(&task).unwrap();
+            execution.started = true;
+            execution.holds_slot = true;
+            return None;
+        }
+        let (sender, receiver) = oneshot::channel();
+        let key = (Reverse(self.effective_priority(task)), self.next_seq);
+        self.next_seq += 1;
+        self.queue.insert(key, (task, sender));
+        self.executions.get_mut(&task).unwrap().queued = Some(key);
+        self.admit_waiting();
+        Some(receiver)
+    }
+
+    fn admit_waiting(&mut self) {
+        while self.has_free_slot() {
+            let Some((_, (task, sender))) = self.queue.pop_first() else {
+                break;
+            };
+            let Some(execution) = self.executions.get_mut(&task) else {
+                continue;
+            };
+            execution.queued = None;
+            if sender.send(()).is_err() {
+                // The execution stopped waiting
+                if !execution.started {
+                    self.executions.remove(&task);
+                }
+                continue;
+            }
+            execution.started = true;
+            execution.holds_slot = true;
+            self.running += 1;
+        }
+    }
+
+    /// The highest priority of `task` and of the tasks that transitively wait
+    /// for it.
+    fn effective_priority(&self, task: TaskId) -> TaskPriority {
+        let mut priority = TaskPriority::Background;
+        let mut visited = FxHashSet::default();
+        let mut stack = vec![task];
+        while let Some(task) = stack.pop() {
+            if !visited.insert(task) {
+                continue;
+            }
+            priority = priority.max(self.own_priority(task));
+            if priority == TaskPriority::Interactive {
+                break;
+            }
+            if let Some(readers) = self.waiters.get(&task) {
+                stack.extend(readers.keys().copied());
+            }
+        }
+        priority
+    }
+
+    fn own_priority(&self, task: TaskId) -> TaskPriority {
+        match self.executions.get(&task) {
+            Some(execution) => execution.priority,
+            None => self.priorities.get(&task).copied().unwrap_or_default(),
+        }
+    }
+
+    /// Moves the queued executions of `task` and of the tasks it transitively
+    /// waits for to their effective priority.
+    fn update_queued_below(&mut self, task: TaskId) {
+        let mut visited = FxHashSet::default();
+        let mut stack = vec![task];
+        while let Some(task) = stack.pop() {
+            if !visited.insert(task) {
+                continue;
+            }
+            self.update_queued(task);
+            if let Some(tasks) = self.waits.get(&task) {
+                stack.extend(tasks.keys().copied());
+            }
+        }
+    }
+
+    fn update_queued(&mut self, task: TaskId) {
+        let Some(key) = self.executions.get(&task).and_then(|e| e.queued) else {
+            return;
+        };
+        let priority = Reverse(self.effective_priority(task));
+        if key.0 == priority {
+            return;
+        }
+        // Keeps the position among the executions of the same priority
+        let new_key = (priority, key.1);
+        let entry = self.queue.remove(&key).unwrap();
+        self.queue.insert(new_key, entry);
+        self.executions.get_mut(&task).unwrap().queued = Some(new_key);
+    }
+}
+
+/// Removes one pending read from `edges`. Returns `false` when there was none.
+fn remove_edge(
+    edges: &mut FxHashMap<TaskId, FxHashMap<TaskId, usize>>,
+    from: TaskId,
+    to: TaskId,
+) -> bool {
+    let Some(tasks) = edges.get_mut(&from) else {
+        return false;
+    };
+    let Some(count) = tasks.get_mut(&to) else {
+        return false;
+    };
+    *count -= 1;
+    if *count == 0 {
+        tasks.remove(&to);
+        if tasks.is_empty() {
+            edges.remove(&from);
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::sync::mpsc;
+
+    use super::{TaskPriority, TaskScheduler};
+    use crate::TaskId;
+
+    fn task(id: u32) -> TaskId {
+        TaskId::from(id)
+    }
+
+    /// Queues an execution of `id` and sends the id once it runs.
+    fn spawn_execution(
+        scheduler: &Arc<TaskScheduler>,
+        id: u32,
+        priority: TaskPriority,
+        sender: &mpsc::UnboundedSender<(u32, super::TaskPermit)>,
+    ) {
+        let scheduler = scheduler.clone();
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            let permit = scheduler.acquire(task(id), Some(priority)).await;
+            sender.send((id, permit)).unwrap();
+        });
+    }
+
+    #[tokio::test]
+    async fn admits_executions_by_priority() {
+        let scheduler = Arc::new(TaskScheduler::default());
+        scheduler.set_limit(Some(1));
+        let running = scheduler.acquire(task(1), None).await;
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        for (id, priority) in [
+            (2, TaskPriority::Background),
+            (3, TaskPriority::Interactive),
+            (4, TaskPriority::Normal),
+            (5, TaskPriority::Interactive),
+        ] {
+            spawn_execution(&scheduler, id, priority, &sender);
+            tokio::task::yield_now().await;
+        }
+
+        drop(running);
+        let mut order = Vec::new();
+        for _ in 0..4 {
+            let (id, permit) = receiver.recv().await.unwrap();
+            order.push(id);
+            drop(permit);
+        }
+        assert_eq!(order, vec![3, 5, 4, 2]);
+    }
+
+    #[tokio::test]
+    async fn inherits_the_priority_of_waiting_tasks() {
+        let scheduler = Arc::new(TaskScheduler::default());
+        scheduler.set_limit(Some(1));
+        scheduler.set_priority(task(1), TaskPriority::Interactive);
+        let hmr = scheduler.acquire(task(1), None).await;
+
+        // The background compilation and the task it waits for are queued
+        // behind an unrelated task
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        spawn_execution(&scheduler, 2, TaskPriority::Normal, &sender);
+        tokio::task::yield_now().await;
+        spawn_execution(&scheduler, 3, TaskPriority::Background, &sender);
+        tokio::task::yield_now().await;
+        spawn_execution(&scheduler, 4, TaskPriority::Background, &sender);
+        tokio::task::yield_now().await;
+        assert!(scheduler.start_waiting(task(3), task(4)));
+
+        // Waiting gives up the slot, which the boosted task gets
+        assert!(scheduler.start_waiting(task(1), task(3)));
+        assert_eq!(
+            scheduler.effective_priority(task(4)),
+            TaskPriority::Interactive
+        );
+        let (id, compilation) = receiver.recv().await.unwrap();
+        assert_eq!(id, 3);
+
+        // The priority ends with the wait, and the waiting task is admitted
+        // before the unrelated one again
+        let resumed = scheduler.stop_waiting(task(1), task(3)).unwrap();
+        assert_eq!(
+            scheduler.effective_priority(task(4)),
+            TaskPriority::Background
+        );
+        drop(compilation);
+        resumed.await.unwrap();
+        drop(hmr);
+        let (id, _) = receiver.recv().await.unwrap();
+        assert_eq!(id, 2);
+    }
+
+    #[tokio::test]
+    async fn waits_for_a_slot_after_waiting() {
+        let scheduler = Arc::new(TaskScheduler::default());
+        scheduler.set_limit(Some(1));
+        let reader = scheduler.acquire(task(1), Some(TaskPriority::Normal)).await;
+        assert!(scheduler.start_waiting(task(1), task(2)));
+        let other = scheduler.acquire(task(3), None).await;
+
+        let resumed = scheduler.stop_waiting(task(1), task(2)).unwrap();
+        drop(other);
+        resumed.await.unwrap();
+        drop(reader);
+    }
+
+    #[tokio::test]
+    async fn does_not_track_unlimited_executions() {
+        let scheduler = Arc::new(TaskScheduler::default());
+        let _permit = scheduler.acquire(task(1), None).await;
+        assert_eq!(scheduler.inherited_priority(Some(task(1))), None);
+        assert!(!scheduler.start_waiting(task(1), task(2)));
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    borrow::Cow,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{trait_helpers::get_trait_method, FunctionId, TraitTypeId, ValueTypeId};
+
+/// The number of value types a call site caches methods of. Call sites of
+/// traits like `Module` see more types than this, but most calls are for a few
+/// of them.
+const SLOTS: usize = 8;
+
+/// An inline cache of the native functions that the trait calls of a call site
+/// resolved to, by the value type of `self`. `#[turbo_tasks::value_trait]`
+/// creates one for each trait method, so resolving a method doesn't need to
+/// look up the method by name in the registry for every call.
+///
+/// A slot holds the value type id in the upper and the function id in the
+/// lower 32 bits, so it's read and written atomically without locking. Value
+/// types that map to the same slot replace each other.
+pub struct TraitMethodCache {
+    slots: [AtomicU64; SLOTS],
+}
+
+impl TraitMethodCache {
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { AtomicU64::new(0) }; SLOTS],
+        }
+    }
+
+    /// Returns the native function of the method `name` of `trait_type` for
+    /// `value_type`, or `None` when the value type doesn't implement it.
+    ///
+    /// A call site always passes the same `trait_type` and `name`, so they are
+    /// not part of the key.
+    pub fn get_or_resolve(
+        &self,
+        trait_type: TraitTypeId,
+        value_type: ValueTypeId,
+        name: &'static str,
+    ) -> Option<FunctionId> {
+        let slot = &self.slots[*value_type as usize % SLOTS];
+        let entry = slot.load(Ordering::Relaxed);
+        // Ids are never zero, so an empty slot never matches.
+        if (entry >> 32) as u32 == *value_type {
+            // SAFETY: Only non-zero function ids are stored.
+            return Some(unsafe { FunctionId::new_unchecked(entry as u32) });
+        }
+        let native_fn = get_trait_method(trait_type, value_type, Cow::Borrowed(name)).ok()?;
+        slot.store(
+            (u64::from(*value_type) << 32) | u64::from(*native_fn),
+            Ordering::Relaxed,
+        );
+        Some(native_fn)
+    }
+}
+
+impl Default for TraitMethodCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TraitMethodCache;
+    use crate::{
+        debug::ValueDebug, trait_helpers::get_trait_method, Completion, ValueToString,
+        VcValueTrait, VcValueType,
+    };
+
+    #[test]
+    fn caches_resolved_methods() {
+        crate::register();
+        let value_debug = <Box<dyn ValueDebug> as VcValueTrait>::get_trait_type_id();
+        let completion = <Completion as VcValueType>::get_value_type_id();
+        let cache = TraitMethodCache::new();
+
+        let native_fn = cache.get_or_resolve(value_debug, completion, "dbg");
+        assert!(native_fn.is_some());
+        assert!(native_fn == get_trait_method(value_debug, completion, "dbg".into()).ok());
+        assert!(cache.get_or_resolve(value_debug, completion, "dbg") == native_fn);
+
+        let value_to_string = <Box<dyn ValueToString> as VcValueTrait>::get_trait_type_id();
+        assert!(TraitMethodCache::new()
+            .get_or_resolve(value_to_string, completion, "to_string")
+            .is_none());
+    }
+}
@@ -4,7 +4,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    debug::{ValueDebugFormat, ValueDebugString},
+    debug::{ValueDebugFormat, ValueDebugJson, ValueDebugString},
     ReadRef, SharedReference,
 };
 
@@ -54,6 +54,14 @@ impl<T: ValueDebugFormat> Value<T> {
             .await?
             .await
     }
+
+    pub async fn dbg_json(&self, depth: usize) -> Result<ReadRef<ValueDebugJson>> {
+        self.inner
+            .value_debug_json(depth)
+            .try_to_value_debug_json()
+            .await?
+            .await
+    }
 }
 
 /// Pass a value by value (`Value<Xxx>`) instead of by reference (`Vc<Xxx>`).
@@ -13,7 +13,7 @@ use std::{
     ops::Deref,
 };
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use auto_hash_map::AutoSet;
 use serde::{Deserialize, Serialize};
 
@@ -26,7 +26,7 @@ pub use self::{
     traits::{Dynamic, TypedForInput, Upcast, VcValueTrait, VcValueType},
 };
 use crate::{
-    debug::{ValueDebug, ValueDebugFormat, ValueDebugFormatString},
+    debug::{ValueDebug, ValueDebugFormat, ValueDebugFormatJson, ValueDebugFormatString},
     manager::{create_local_cell, try_get_function_meta},
     registry,
     trace::{TraceRawVcs, TraceRawVcsContext},
@@ -378,6 +378,24 @@ where
         })
     }
 
+    /// Resolves the reference like [`Vc::to_resolved`], and returns an error
+    /// when it points to a cell of a transient task.
+    ///
+    /// This is the boundary where results of
+    /// `#[turbo_tasks::function(transient)]` functions are handed back to
+    /// persistently cached code. Passing a transient `Vc` on would silently
+    /// make every function that receives it transient too.
+    pub async fn to_persistent(self) -> Result<ResolvedVc<T>> {
+        let resolved = self.to_resolved().await?;
+        if resolved.node.node.is_transient() {
+            bail!(
+                "Expected a persistent Vc<{}>, but it was created by a transient task",
+                std::any::type_name::<T>()
+            );
+        }
+        Ok(resolved)
+    }
+
     /// Returns `true` if the reference is resolved.
     ///
     /// See also [`Vc::resolve`].
@@ -527,6 +545,19 @@ where
             })
         }))
     }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        if depth == 0 {
+            // A placeholder that doesn't need to resolve the reference
+            return ValueDebugFormatJson::Sync(serde_json::json!({
+                "$vc": format!("{:?}", self.node),
+            }));
+        }
+        ValueDebugFormatJson::Async(Box::pin(async move {
+            let vc_value_debug = Vc::upcast::<Box<dyn ValueDebug>>(*self);
+            Ok(vc_value_debug.dbg_json(depth).await?.as_json().clone())
+        }))
+    }
 }
 
 macro_rules! into_future {
@@ -22,10 +22,11 @@ use indexmap::{IndexMap, IndexSet};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    debug::{ValueDebug, ValueDebugFormat, ValueDebugFormatString},
+    debug::{ValueDebug, ValueDebugFormat, ValueDebugFormatJson, ValueDebugFormatString},
     trace::{TraceRawVcs, TraceRawVcsContext},
     vc::Vc,
-    RcStr, ResolveTypeError, Upcast, VcRead, VcTransparentRead, VcValueTrait, VcValueType,
+    CowMap, CowVec, RcStr, ResolveTypeError, Upcast, VcRead, VcTransparentRead, VcValueTrait,
+    VcValueType,
 };
 
 #[derive(Serialize, Deserialize)]
@@ -225,6 +226,10 @@ where
     fn value_debug_format(&self, depth: usize) -> ValueDebugFormatString {
         self.node.value_debug_format(depth)
     }
+
+    fn value_debug_json(&self, depth: usize) -> ValueDebugFormatJson {
+        self.node.value_debug_json(depth)
+    }
 }
 
 /// Indicates that a type does not contain any instances of [`Vc`]. It may
@@ -285,6 +290,7 @@ impl_resolved_tuple!(E D C B A Z Y X W V U T);
 
 unsafe impl<T: ResolvedValue> ResolvedValue for Option<T> {}
 unsafe impl<T: ResolvedValue> ResolvedValue for Vec<T> {}
+unsafe impl<T: ResolvedValue> ResolvedValue for CowVec<T> {}
 unsafe impl<T: ResolvedValue, const N: usize> ResolvedValue for [T; N] {}
 unsafe impl<T: ResolvedValue> ResolvedValue for [T] {}
 unsafe impl<T: ResolvedValue, S> ResolvedValue for HashSet<T, S> {}
@@ -297,6 +303,7 @@ unsafe impl<K: ResolvedValue, V: ResolvedValue, S, const I: usize> ResolvedValue
 {
 }
 unsafe impl<K: ResolvedValue, V: ResolvedValue> ResolvedValue for BTreeMap<K, V> {}
+unsafe impl<K: ResolvedValue, V: ResolvedValue> ResolvedValue for CowMap<K, V> {}
 unsafe impl<K: ResolvedValue, V: ResolvedValue, S> ResolvedValue for IndexMap<K, V, S> {}
 unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for Box<T> {}
 unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for Arc<T> {}
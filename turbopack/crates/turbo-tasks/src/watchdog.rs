@@ -0,0 +1,124 @@
+use std::{
+    future::{poll_fn, Future},
+    pin::{pin, Pin},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use tokio::time::{sleep, Sleep};
+
+use crate::{event::EventListener, manager::TurboTasksApi, ReadConsistency, TaskId};
+
+/// Diagnostics for strongly consistent reads that don't complete. When a read
+/// is pending for longer than `timeout`, the tree of tasks it is waiting on is
+/// reported as a warning via `tracing`.
+///
+/// See [`crate::TurboTasks::set_strongly_consistent_read_watchdog`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StronglyConsistentReadWatchdog {
+    pub timeout: Duration,
+    /// Report again every `timeout` as long as the read is still pending.
+    pub repeat: bool,
+}
+
+enum WatchdogState {
+    /// The read is not strongly consistent or no watchdog is configured.
+    Disabled,
+    /// The configuration is looked up when the read has to wait for the first
+    /// time.
+    Unchecked,
+    Armed {
+        config: StronglyConsistentReadWatchdog,
+        start: Instant,
+        sleep: Pin<Box<Sleep>>,
+    },
+    /// Already reported and not configured to repeat.
+    Expired,
+}
+
+/// Tracks how long a single strongly consistent read is waiting and reports
+/// the pending tasks when the configured timeout is exceeded.
+pub(crate) struct ReadWatchdog {
+    state: WatchdogState,
+}
+
+impl ReadWatchdog {
+    pub fn new(consistency: ReadConsistency) -> Self {
+        Self {
+            state: match consistency {
+                ReadConsistency::Strong => WatchdogState::Unchecked,
+                ReadConsistency::Eventual => WatchdogState::Disabled,
+            },
+        }
+    }
+
+    /// Polls the watchdog timer. Must be called whenever the read returns
+    /// [`Poll::Pending`], so that the read is woken up when the timer expires.
+    pub fn poll(&mut self, turbo_tasks: &dyn TurboTasksApi, task: TaskId, cx: &mut Context<'_>) {
+        loop {
+            match &mut self.state {
+                WatchdogState::Disabled | WatchdogState::Expired => return,
+                WatchdogState::Unchecked => {
+                    self.state = match turbo_tasks.strongly_consistent_read_watchdog() {
+                        Some(config) => WatchdogState::Armed {
+                            config,
+                            start: Instant::now(),
+                            sleep: Box::pin(sleep(config.timeout)),
+                        },
+                        None => WatchdogState::Disabled,
+                    };
+                }
+                WatchdogState::Armed {
+                    config,
+                    start,
+                    sleep,
+                } => {
+                    if sleep.as_mut().poll(cx).is_pending() {
+                        return;
+                    }
+                    report_pending_read(turbo_tasks, task, start.elapsed());
+                    if config.repeat {
+                        let deadline = tokio::time::Instant::now() + config.timeout;
+                        sleep.as_mut().reset(deadline);
+                    } else {
+                        self.state = WatchdogState::Expired;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Waits for `listener` while watching the read of `task`.
+    pub async fn wait(
+        &mut self,
+        turbo_tasks: &dyn TurboTasksApi,
+        task: TaskId,
+        listener: EventListener,
+    ) {
+        let mut listener = pin!(listener);
+        poll_fn(|cx| {
+            if listener.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(());
+            }
+            self.poll(turbo_tasks, task, cx);
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+fn report_pending_read(turbo_tasks: &dyn TurboTasksApi, task: TaskId, elapsed: Duration) {
+    match turbo_tasks.pending_task_tree(task) {
+        Some(tree) => tracing::warn!(
+            "strongly consistent read of {} is still pending after {:.1?}, waiting on:\n{}",
+            tree.description,
+            elapsed,
+            tree
+        ),
+        None => tracing::warn!(
+            "strongly consistent read of task {} is still pending after {:.1?}",
+            task,
+            elapsed
+        ),
+    }
+}
@@ -7,8 +7,8 @@ use turbopack_core::{
         availability_info::AvailabilityInfo,
         chunk_group::{make_chunk_group, MakeChunkGroupResult},
         module_id_strategies::{DevModuleIdStrategy, ModuleIdStrategy},
-        Chunk, ChunkGroupResult, ChunkItem, ChunkableModule, ChunkingContext,
-        EntryChunkGroupResult, EvaluatableAssets, MinifyType, ModuleId,
+        Chunk, ChunkCodeWrapping, ChunkGroupResult, ChunkItem, ChunkableModule, ChunkingContext,
+        EntryChunkGroupResult, EvaluatableAssets, KeepNames, MinifyType, ModuleId,
     },
     environment::Environment,
     ident::AssetIdent,
@@ -20,7 +20,7 @@ use turbopack_ecmascript::{
     chunk::EcmascriptChunk,
     manifest::{chunk_asset::ManifestAsyncModule, loader_item::ManifestLoaderChunkItem},
 };
-use turbopack_ecmascript_runtime::RuntimeType;
+use turbopack_ecmascript_runtime::{ChunkLoadRetry, OutputFormat, RuntimeType};
 
 use crate::ecmascript::{
     chunk::EcmascriptDevChunk,
@@ -48,11 +48,30 @@ impl BrowserChunkingContextBuilder {
         self
     }
 
+    pub fn source_map_source_template(mut self, template: RcStr) -> Self {
+        self.chunking_context.source_map_source_template = Some(template);
+        self
+    }
+
+    /// Adds a banner, a footer or a runtime injection to every emitted chunk.
+    pub fn chunk_code_wrapping(mut self, chunk_code_wrapping: ChunkCodeWrapping) -> Self {
+        self.chunking_context.chunk_code_wrapping = chunk_code_wrapping;
+        self
+    }
+
     pub fn asset_base_path(mut self, asset_base_path: Vc<Option<RcStr>>) -> Self {
         self.chunking_context.asset_base_path = asset_base_path;
         self
     }
 
+    /// Makes `url()` references in CSS refer to static assets by their url,
+    /// which starts with the `asset_base_path`, instead of relative to the CSS
+    /// chunk, so they stay correct no matter where the CSS is emitted.
+    pub fn asset_urls_in_css(mut self) -> Self {
+        self.chunking_context.asset_urls_in_css = true;
+        self
+    }
+
     pub fn chunk_base_path(mut self, chunk_base_path: Vc<Option<RcStr>>) -> Self {
         self.chunking_context.chunk_base_path = chunk_base_path;
         self
@@ -73,6 +92,34 @@ impl BrowserChunkingContextBuilder {
         self
     }
 
+    /// Emits ES module chunks instead of scripts, see [OutputFormat].
+    pub fn output_format(mut self, output_format: OutputFormat) -> Self {
+        self.chunking_context.output_format = output_format;
+        self
+    }
+
+    /// Configures how the runtime retries chunks that failed to load, see
+    /// [ChunkLoadRetry].
+    pub fn chunk_load_retry(mut self, chunk_load_retry: ChunkLoadRetry) -> Self {
+        self.chunking_context.chunk_load_retry = chunk_load_retry;
+        self
+    }
+
+    /// Emits the source maps of modules in HMR updates that are larger than
+    /// `threshold` bytes as separate files instead of inlining them as data
+    /// URLs, which slow down the browser when they are large.
+    pub fn external_source_map_threshold(mut self, threshold: u64) -> Self {
+        self.chunking_context.external_source_map_threshold = Some(threshold);
+        self
+    }
+
+    /// Reports an issue for large source maps that are inlined in HMR updates,
+    /// when there's no external source map threshold.
+    pub fn warn_large_inline_source_maps(mut self) -> Self {
+        self.chunking_context.warn_large_inline_source_maps = true;
+        self
+    }
+
     pub fn manifest_chunks(mut self, manifest_chunks: bool) -> Self {
         self.chunking_context.manifest_chunks = manifest_chunks;
         self
@@ -83,6 +130,21 @@ impl BrowserChunkingContextBuilder {
         self
     }
 
+    /// Keeps the names of functions and classes in minified chunks, see
+    /// [KeepNames].
+    pub fn keep_names(mut self, keep_names: KeepNames) -> Self {
+        self.chunking_context.keep_names = keep_names;
+        self
+    }
+
+    /// Writes development chunks for debugging: every module is preceded by a
+    /// comment with its path and chunks are never minified. Production chunks
+    /// are not affected.
+    pub fn readable_output(mut self, readable_output: bool) -> Self {
+        self.chunking_context.readable_output = readable_output;
+        self
+    }
+
     pub fn module_id_strategy(mut self, module_id_strategy: Vc<Box<dyn ModuleIdStrategy>>) -> Self {
         self.chunking_context.module_id_strategy = module_id_strategy;
         self
@@ -108,6 +170,10 @@ pub struct BrowserChunkingContext {
     context_path: Vc<FileSystemPath>,
     /// Whether to write file sources as file:// paths in source maps
     should_use_file_source_map_uris: bool,
+    /// Template to rewrite `turbopack://` sources in source maps with
+    source_map_source_template: Option<RcStr>,
+    /// Code to add to every emitted chunk
+    chunk_code_wrapping: ChunkCodeWrapping,
     /// This path is used to compute the url to request chunks from
     output_root: Vc<FileSystemPath>,
     /// This path is used to compute the url to request assets from
@@ -118,6 +184,11 @@ pub struct BrowserChunkingContext {
     reference_chunk_source_maps: bool,
     /// Css chunks reference source maps assets
     reference_css_chunk_source_maps: bool,
+    /// Source maps of modules in HMR updates larger than this are emitted as
+    /// separate files instead of being inlined
+    external_source_map_threshold: Option<u64>,
+    /// Whether to report large source maps that are inlined in HMR updates
+    warn_large_inline_source_maps: bool,
     /// Static assets are placed at this path
     asset_root_path: Vc<FileSystemPath>,
     /// Base path that will be prepended to all chunk URLs when loading them.
@@ -126,14 +197,25 @@ pub struct BrowserChunkingContext {
     /// URL prefix that will be prepended to all static asset URLs when loading
     /// them.
     asset_base_path: Vc<Option<RcStr>>,
+    /// Whether `url()` references in CSS refer to static assets by their url
+    /// instead of relative to the CSS chunk
+    asset_urls_in_css: bool,
     /// Enable HMR for this chunking
     enable_hot_module_replacement: bool,
     /// The environment chunks will be evaluated in.
     environment: Vc<Environment>,
     /// The kind of runtime to include in the output.
     runtime_type: RuntimeType,
+    /// The format of the emitted chunks.
+    output_format: OutputFormat,
+    /// How the runtime retries chunks that failed to load.
+    chunk_load_retry: ChunkLoadRetry,
     /// Whether to minify resulting chunks
     minify_type: MinifyType,
+    /// Which functions and classes keep their names in minified chunks
+    keep_names: KeepNames,
+    /// Whether development chunks are written for debugging
+    readable_output: bool,
     /// Whether to use manifest chunks for lazy compilation
     manifest_chunks: bool,
     /// The module id strategy to use
@@ -158,15 +240,24 @@ impl BrowserChunkingContext {
                 client_root,
                 chunk_root_path,
                 should_use_file_source_map_uris: false,
+                source_map_source_template: None,
+                chunk_code_wrapping: Default::default(),
                 reference_chunk_source_maps: true,
                 reference_css_chunk_source_maps: true,
+                external_source_map_threshold: None,
+                warn_large_inline_source_maps: false,
                 asset_root_path,
                 chunk_base_path: Default::default(),
                 asset_base_path: Default::default(),
+                asset_urls_in_css: false,
                 enable_hot_module_replacement: false,
                 environment,
                 runtime_type,
+                output_format: Default::default(),
+                chunk_load_retry: Default::default(),
                 minify_type: MinifyType::NoMinify,
+                keep_names: Default::default(),
+                readable_output: false,
                 manifest_chunks: false,
                 module_id_strategy: Vc::upcast(DevModuleIdStrategy::new()),
             },
@@ -192,6 +283,40 @@ impl BrowserChunkingContext {
     pub fn minify_type(&self) -> MinifyType {
         self.minify_type
     }
+
+    /// Returns which functions and classes keep their names in minified
+    /// chunks.
+    pub fn keep_names(&self) -> KeepNames {
+        self.keep_names
+    }
+
+    /// Returns whether chunks are written for debugging, which only applies
+    /// to the development runtime.
+    pub fn readable_output(&self) -> bool {
+        self.readable_output && matches!(self.runtime_type, RuntimeType::Development)
+    }
+
+    /// Returns the format of the emitted chunks.
+    pub fn output_format(&self) -> OutputFormat {
+        self.output_format
+    }
+
+    /// Returns how the runtime retries chunks that failed to load.
+    pub fn chunk_load_retry(&self) -> ChunkLoadRetry {
+        self.chunk_load_retry
+    }
+
+    /// Returns the size in bytes above which source maps of modules in HMR
+    /// updates are emitted as separate files.
+    pub fn external_source_map_threshold(&self) -> Option<u64> {
+        self.external_source_map_threshold
+    }
+
+    /// Returns whether large source maps that are inlined in HMR updates are
+    /// reported.
+    pub fn warn_large_inline_source_maps(&self) -> bool {
+        self.warn_large_inline_source_maps
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -313,6 +438,11 @@ impl ChunkingContext for BrowserChunkingContext {
         ))
     }
 
+    #[turbo_tasks::function]
+    fn asset_urls_in_css(&self) -> Vc<bool> {
+        Vc::cell(self.asset_urls_in_css)
+    }
+
     #[turbo_tasks::function]
     async fn reference_chunk_source_maps(
         &self,
@@ -363,6 +493,16 @@ impl ChunkingContext for BrowserChunkingContext {
         Vc::cell(self.should_use_file_source_map_uris)
     }
 
+    #[turbo_tasks::function]
+    fn source_map_source_template(&self) -> Vc<Option<RcStr>> {
+        Vc::cell(self.source_map_source_template.clone())
+    }
+
+    #[turbo_tasks::function]
+    fn chunk_code_wrapping(&self) -> Vc<ChunkCodeWrapping> {
+        self.chunk_code_wrapping.clone().cell()
+    }
+
     #[turbo_tasks::function]
     async fn chunk_group(
         self: Vc<Self>,
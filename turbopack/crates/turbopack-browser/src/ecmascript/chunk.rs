@@ -11,7 +11,13 @@ use turbopack_core::{
 };
 use turbopack_ecmascript::chunk::EcmascriptChunk;
 
-use crate::{ecmascript::content::EcmascriptDevChunkContent, BrowserChunkingContext};
+use crate::{
+    ecmascript::{
+        content::EcmascriptDevChunkContent,
+        item_source_map::{item_source_map_size, EcmascriptDevItemSourceMapAsset},
+    },
+    BrowserChunkingContext,
+};
 
 /// Development Ecmascript chunk.
 #[turbo_tasks::value(shared)]
@@ -111,6 +117,28 @@ impl OutputAsset for EcmascriptDevChunk {
             ));
         }
 
+        // HMR updates refer to the source maps of modules above the threshold
+        // instead of inlining them, so they need to be emitted.
+        if let Some(threshold) = this.chunking_context.await?.external_source_map_threshold() {
+            let chunk_path = self.ident().path();
+            for (id, entry) in self.own_content().entries().await?.iter() {
+                if item_source_map_size(entry.code)
+                    .await?
+                    .is_some_and(|size| size > threshold)
+                {
+                    references.push(ResolvedVc::upcast(
+                        EcmascriptDevItemSourceMapAsset::new(
+                            chunk_path,
+                            (**id).clone().cell(),
+                            entry.code,
+                        )
+                        .to_resolved()
+                        .await?,
+                    ));
+                }
+            }
+        }
+
         Ok(Vc::cell(references))
     }
 }
@@ -2,17 +2,21 @@ use std::io::Write;
 
 use anyhow::{bail, Result};
 use indoc::writedoc;
-use turbo_tasks::{RcStr, Vc};
+use turbo_tasks::{RcStr, ValueToString, Vc};
 use turbo_tasks_fs::File;
 use turbopack_core::{
     asset::AssetContent,
     chunk::{ChunkingContext, MinifyType, ModuleId},
-    code_builder::{Code, CodeBuilder},
+    code_builder::{apply_source_map_source_template, Code, CodeBuilder},
     output::OutputAsset,
     source_map::{GenerateSourceMap, OptionSourceMap},
     version::{MergeableVersionedContent, Version, VersionedContent, VersionedContentMerger},
 };
-use turbopack_ecmascript::{chunk::EcmascriptChunkContent, minify::minify, utils::StringifyJs};
+use turbopack_ecmascript::{
+    chunk::EcmascriptChunkContent,
+    minify::{minify_code, pretty_print, source_map_reference},
+    utils::StringifyJs,
+};
 
 use super::{
     chunk::EcmascriptDevChunk, content_entry::EcmascriptDevChunkContentEntries,
@@ -95,7 +99,15 @@ impl EcmascriptDevChunkContent {
             chunk_path = StringifyJs(chunk_server_path)
         )?;
 
+        let chunking_context = this.chunking_context.await?;
+        let readable_output = chunking_context.readable_output();
+
         for (id, entry) in this.entries.await?.iter() {
+            if readable_output {
+                // Line breaks of queries would end the comment.
+                let ident = entry.ident.to_string().await?;
+                write!(code, "\n\n// {}", ident.replace(['\n', '\r'], " "))?;
+            }
             write!(code, "\n{}: ", StringifyJs(&id))?;
             code.push_code(&*entry.code.await?);
             write!(code, ",")?;
@@ -103,24 +115,26 @@ impl EcmascriptDevChunkContent {
 
         write!(code, "\n}}]);")?;
 
-        if code.has_source_map() {
-            let filename = chunk_path.file_name();
-            write!(
-                code,
-                "\n\n//# sourceMappingURL={}.map",
-                urlencoding::encode(filename)
-            )?;
-        }
+        let content = code.build().cell();
+        let content = if readable_output {
+            pretty_print(chunk_path_vc, content)
+        } else if matches!(chunking_context.minify_type(), MinifyType::Minify) {
+            minify_code(chunk_path_vc, content, chunking_context.keep_names())
+        } else {
+            content
+        };
+
+        let chunk_code_wrapping = this.chunking_context.chunk_code_wrapping().await?;
+        let mut code = CodeBuilder::default();
+        chunk_code_wrapping.write_banner(&mut code)?;
+        code.push_code(&*content.await?);
+        chunk_code_wrapping.write_footer(&mut code)?;
 
-        let code = code.build().cell();
-        if matches!(
-            this.chunking_context.await?.minify_type(),
-            MinifyType::Minify
-        ) {
-            return Ok(minify(chunk_path_vc, code));
+        if code.has_source_map() {
+            write!(code, "{}", source_map_reference(chunk_path.file_name()))?;
         }
 
-        Ok(code)
+        Ok(code.build().cell())
     }
 }
 
@@ -151,8 +165,12 @@ impl MergeableVersionedContent for EcmascriptDevChunkContent {
 #[turbo_tasks::value_impl]
 impl GenerateSourceMap for EcmascriptDevChunkContent {
     #[turbo_tasks::function]
-    fn generate_source_map(self: Vc<Self>) -> Vc<OptionSourceMap> {
-        self.code().generate_source_map()
+    async fn generate_source_map(self: Vc<Self>) -> Result<Vc<OptionSourceMap>> {
+        let this = self.await?;
+        Ok(apply_source_map_source_template(
+            self.code().generate_source_map(),
+            Vc::upcast(this.chunking_context),
+        ))
     }
 
     #[turbo_tasks::function]
@@ -163,7 +181,10 @@ impl GenerateSourceMap for EcmascriptDevChunkContent {
             for (entry_id, entry) in self.entries.await?.iter() {
                 if id == **entry_id {
                     let sm = entry.code.generate_source_map();
-                    return Ok(sm);
+                    return Ok(apply_source_map_source_template(
+                        sm,
+                        Vc::upcast(self.chunking_context),
+                    ));
                 }
             }
         }
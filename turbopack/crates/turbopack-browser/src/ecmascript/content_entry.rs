@@ -7,6 +7,7 @@ use turbopack_core::{
     chunk::{AsyncModuleInfo, ChunkItem, ChunkItemExt, ModuleId},
     code_builder::{Code, CodeBuilder},
     error::PrettyPrintError,
+    ident::AssetIdent,
     issue::{code_gen::CodeGenerationIssue, IssueExt, IssueSeverity, StyledString},
 };
 use turbopack_ecmascript::chunk::{
@@ -24,6 +25,8 @@ use turbopack_ecmascript::chunk::{
 pub struct EcmascriptDevChunkContentEntry {
     pub code: Vc<Code>,
     pub hash: Vc<u64>,
+    /// The ident of the module, written before its code in readable output.
+    pub ident: Vc<AssetIdent>,
 }
 
 impl EcmascriptDevChunkContentEntry {
@@ -35,6 +38,7 @@ impl EcmascriptDevChunkContentEntry {
         Ok(EcmascriptDevChunkContentEntry {
             code,
             hash: code.source_code_hash().resolve().await?,
+            ident: chunk_item.asset_ident().resolve().await?,
         })
     }
 }
@@ -11,18 +11,18 @@ use turbopack_core::{
         ChunkData, ChunkItemExt, ChunkableModule, ChunkingContext, ChunksData, EvaluatableAssets,
         MinifyType, ModuleId,
     },
-    code_builder::{Code, CodeBuilder},
+    code_builder::{apply_source_map_source_template, Code, CodeBuilder},
     ident::AssetIdent,
     module::Module,
     output::{OutputAsset, OutputAssets},
     source_map::{GenerateSourceMap, OptionSourceMap, SourceMapAsset},
 };
 use turbopack_ecmascript::{
-    chunk::{EcmascriptChunkData, EcmascriptChunkPlaceable},
-    minify::minify,
-    utils::StringifyJs,
+    chunk::{EcmascriptChunkData, EcmascriptChunkPlaceable, EcmascriptExports},
+    minify::{minify_code, source_map_reference},
+    utils::{is_identifier_name, StringifyJs},
 };
-use turbopack_ecmascript_runtime::RuntimeType;
+use turbopack_ecmascript_runtime::{OutputFormat, RuntimeType};
 
 use crate::BrowserChunkingContext;
 
@@ -120,23 +120,48 @@ impl EcmascriptDevEvaluateChunk {
             runtime_module_ids,
         };
 
+        let output_format = chunking_context.output_format();
+
         let mut code = CodeBuilder::default();
 
-        // We still use the `TURBOPACK` global variable to store the chunk here,
-        // as there may be another runtime already loaded in the page.
-        // This is the case in integration tests.
-        writedoc!(
-            code,
-            r#"
-                (globalThis.TURBOPACK = globalThis.TURBOPACK || []).push([
-                    {},
-                    {{}},
-                    {}
-                ]);
-            "#,
-            StringifyJs(&chunk_public_path),
-            StringifyJs(&params),
-        )?;
+        match output_format {
+            OutputFormat::Runtime => {
+                // We still use the `TURBOPACK` global variable to store the chunk here,
+                // as there may be another runtime already loaded in the page.
+                // This is the case in integration tests.
+                writedoc!(
+                    code,
+                    r#"
+                        (globalThis.TURBOPACK = globalThis.TURBOPACK || []).push([
+                            {},
+                            {{}},
+                            {}
+                        ]);
+                    "#,
+                    StringifyJs(&chunk_public_path),
+                    StringifyJs(&params),
+                )?;
+            }
+            OutputFormat::EsModule => {
+                // Imported chunks are evaluated first and push themselves to the
+                // `TURBOPACK` global, which the runtime registers below.
+                let chunk_dir = chunk_path_vc.parent().await?;
+                let other_chunk_paths = this
+                    .other_chunks
+                    .await?
+                    .iter()
+                    .map(|other_chunk| other_chunk.ident().path())
+                    .try_join()
+                    .await?;
+                write_chunk_imports(
+                    &mut code,
+                    other_chunk_paths
+                        .iter()
+                        .filter(|path| path.extension_ref() == Some("js"))
+                        .filter_map(|path| chunk_dir.get_relative_path_to(path)),
+                )?;
+            }
+        }
 
         match chunking_context.runtime_type() {
             RuntimeType::Development => {
@@ -144,6 +169,8 @@ impl EcmascriptDevEvaluateChunk {
                     environment,
                     chunking_context.chunk_base_path(),
                     Value::new(chunking_context.runtime_type()),
+                    Value::new(output_format),
+                    Value::new(chunking_context.chunk_load_retry()),
                     Vc::cell(output_root.to_string().into()),
                 );
                 code.push_code(&*runtime_code.await?);
@@ -153,6 +180,8 @@ impl EcmascriptDevEvaluateChunk {
                     environment,
                     chunking_context.chunk_base_path(),
                     Value::new(chunking_context.runtime_type()),
+                    Value::new(output_format),
+                    Value::new(chunking_context.chunk_load_retry()),
                     Vc::cell(output_root.to_string().into()),
                 );
                 code.push_code(&*runtime_code.await?);
@@ -164,25 +193,110 @@ impl EcmascriptDevEvaluateChunk {
             }
         }
 
-        if code.has_source_map() {
-            let filename = chunk_path.file_name();
-            write!(
+        this.chunking_context
+            .chunk_code_wrapping()
+            .await?
+            .write_runtime_injection(&mut code)?;
+
+        if output_format == OutputFormat::EsModule {
+            // The runtime is loaded now, so pushing registers the chunk right away
+            // and resolves to the exports of the entry module once the other
+            // chunks are loaded.
+            writedoc!(
                 code,
-                "\n\n//# sourceMappingURL={}.map",
-                urlencoding::encode(filename)
+                r#"
+                    const __turbopack_exports__ = await globalThis.TURBOPACK.push([
+                        {},
+                        {{}},
+                        {}
+                    ]);
+                "#,
+                StringifyJs(&chunk_public_path),
+                StringifyJs(&params),
             )?;
+            write_entry_exports(&mut code, this.evaluatable_assets).await?;
         }
 
-        let code = code.build().cell();
-        if matches!(
-            this.chunking_context.await?.minify_type(),
-            MinifyType::Minify
-        ) {
-            return Ok(minify(chunk_path_vc, code));
+        let content = code.build().cell();
+        let content = if matches!(chunking_context.minify_type(), MinifyType::Minify) {
+            minify_code(chunk_path_vc, content, chunking_context.keep_names())
+        } else {
+            content
+        };
+
+        let chunk_code_wrapping = this.chunking_context.chunk_code_wrapping().await?;
+        let mut code = CodeBuilder::default();
+        chunk_code_wrapping.write_banner(&mut code)?;
+        code.push_code(&*content.await?);
+        chunk_code_wrapping.write_footer(&mut code)?;
+
+        if code.has_source_map() {
+            write!(code, "{}", source_map_reference(chunk_path.file_name()))?;
+        }
+
+        Ok(code.build().cell())
+    }
+}
+
+/// Imports the chunks at the relative `paths` from an ES module chunk, before
+/// the chunk itself and the runtime are evaluated.
+fn write_chunk_imports(
+    code: &mut impl Write,
+    paths: impl IntoIterator<Item = RcStr>,
+) -> Result<()> {
+    for path in paths {
+        writeln!(code, "import {};", StringifyJs(&path))?;
+    }
+    writeln!(code, "globalThis.TURBOPACK = globalThis.TURBOPACK || [];")?;
+    Ok(())
+}
+
+/// Re-exports the exports of the last evaluated module from an ES module chunk.
+async fn write_entry_exports(
+    code: &mut CodeBuilder,
+    evaluatable_assets: Vc<EvaluatableAssets>,
+) -> Result<()> {
+    let entry = match evaluatable_assets.await?.last() {
+        Some(entry) => {
+            Vc::try_resolve_sidecast::<Box<dyn EcmascriptChunkPlaceable>>(**entry).await?
         }
+        None => None,
+    };
+    let Some(entry) = entry else {
+        return Ok(());
+    };
+    let EcmascriptExports::EsmExports(exports) = *entry.get_exports().await? else {
+        // CommonJS modules only have a default export, like in Node.js.
+        writeln!(code, "export default __turbopack_exports__;")?;
+        return Ok(());
+    };
+    let exports = exports.expand_exports().await?;
+    write_export_bindings(code, exports.exports.keys().map(|name| name.as_str()))
+}
 
-        Ok(code)
+/// Exports the `names` of `__turbopack_exports__` from an ES module chunk.
+fn write_export_bindings<'a>(
+    code: &mut impl Write,
+    names: impl IntoIterator<Item = &'a str>,
+) -> Result<()> {
+    let mut specifiers = Vec::new();
+    for (i, name) in names.into_iter().enumerate() {
+        writeln!(
+            code,
+            "const __turbopack_export_{i}__ = __turbopack_exports__[{}];",
+            StringifyJs(name)
+        )?;
+        // Other names need arbitrary module namespace names, e.g. `as "a-b"`.
+        if is_identifier_name(name) {
+            specifiers.push(format!("__turbopack_export_{i}__ as {name}"));
+        } else {
+            specifiers.push(format!("__turbopack_export_{i}__ as {}", StringifyJs(name)));
+        }
+    }
+    if !specifiers.is_empty() {
+        writeln!(code, "export {{ {} }};", specifiers.join(", "))?;
     }
+    Ok(())
 }
 
 #[turbo_tasks::value_impl]
@@ -261,8 +375,12 @@ impl Asset for EcmascriptDevEvaluateChunk {
 #[turbo_tasks::value_impl]
 impl GenerateSourceMap for EcmascriptDevEvaluateChunk {
     #[turbo_tasks::function]
-    fn generate_source_map(self: Vc<Self>) -> Vc<OptionSourceMap> {
-        self.code().generate_source_map()
+    async fn generate_source_map(self: Vc<Self>) -> Result<Vc<OptionSourceMap>> {
+        let this = self.await?;
+        Ok(apply_source_map_source_template(
+            self.code().generate_source_map(),
+            Vc::upcast(this.chunking_context),
+        ))
     }
 }
 
@@ -278,3 +396,38 @@ struct EcmascriptDevChunkRuntimeParams<'a, T> {
     /// List of module IDs that this chunk should instantiate when executed.
     runtime_module_ids: Vec<ReadRef<ModuleId>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{write_chunk_imports, write_export_bindings};
+
+    #[test]
+    fn imports_the_other_chunks() {
+        let mut code = Vec::new();
+        write_chunk_imports(&mut code, ["./a.js".into(), "../b c.js".into()]).unwrap();
+        assert_eq!(
+            String::from_utf8(code).unwrap(),
+            "import \"./a.js\";\nimport \"../b c.js\";\nglobalThis.TURBOPACK = \
+             globalThis.TURBOPACK || [];\n"
+        );
+    }
+
+    #[test]
+    fn exports_the_entry_bindings() {
+        let mut code = Vec::new();
+        write_export_bindings(&mut code, ["default", "a-b"]).unwrap();
+        assert_eq!(
+            String::from_utf8(code).unwrap(),
+            "const __turbopack_export_0__ = __turbopack_exports__[\"default\"];\nconst \
+             __turbopack_export_1__ = __turbopack_exports__[\"a-b\"];\nexport { \
+             __turbopack_export_0__ as default, __turbopack_export_1__ as \"a-b\" };\n"
+        );
+    }
+
+    #[test]
+    fn exports_nothing_without_bindings() {
+        let mut code = Vec::new();
+        write_export_bindings(&mut code, []).unwrap();
+        assert!(code.is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::Result;
+use turbo_tasks::{RcStr, ResolvedVc, Vc};
+use turbo_tasks_fs::{File, FileSystemPath};
+use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    chunk::ModuleId,
+    code_builder::Code,
+    ident::AssetIdent,
+    issue::{Issue, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    output::OutputAsset,
+    source_map::{GenerateSourceMap, SourceMap},
+};
+
+/// Source maps of modules in HMR updates that are larger than this are
+/// reported when they are inlined, as decoding them slows down the browser.
+pub(crate) const LARGE_INLINE_SOURCE_MAP_SIZE: u64 = 1024 * 1024;
+
+/// Where the source map of a module in an HMR update goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ItemSourceMapPlacement {
+    Inline,
+    /// Inlined, but large enough to report a [LargeInlineSourceMapIssue].
+    InlineLarge,
+    /// Emitted as a separate [EcmascriptDevItemSourceMapAsset].
+    External,
+}
+
+/// Decides where a source map of `size` bytes goes, given the external source
+/// map threshold of the chunking context and whether large inline source maps
+/// are reported.
+pub(crate) fn item_source_map_placement(
+    size: u64,
+    external_threshold: Option<u64>,
+    warn_large_inline: bool,
+) -> ItemSourceMapPlacement {
+    match external_threshold {
+        Some(threshold) if size > threshold => ItemSourceMapPlacement::External,
+        None if warn_large_inline && size > LARGE_INLINE_SOURCE_MAP_SIZE => {
+            ItemSourceMapPlacement::InlineLarge
+        }
+        _ => ItemSourceMapPlacement::Inline,
+    }
+}
+
+/// The suffix of the path of the external source map of a module, relative
+/// to the path of its chunk.
+pub(crate) fn item_source_map_suffix(id: &ModuleId) -> String {
+    format!(".{}.map", encode_hex(hash_xxh3_hash64(id.to_string())))
+}
+
+/// The size of the source map of the code of a module, if it has one. The
+/// source map is only stringified once, as [SourceMap::to_rope] is cached.
+#[turbo_tasks::function]
+pub(crate) async fn item_source_map_size(code: Vc<Code>) -> Result<Vc<Option<u64>>> {
+    Ok(Vc::cell(match &*code.generate_source_map().await? {
+        Some(map) => Some(map.to_rope().await?.len() as u64),
+        None => None,
+    }))
+}
+
+/// The source map of a single module of a development chunk. HMR updates
+/// refer to it instead of inlining the map when it's larger than the
+/// external source map threshold of the chunking context.
+#[turbo_tasks::value]
+pub(crate) struct EcmascriptDevItemSourceMapAsset {
+    chunk_path: Vc<FileSystemPath>,
+    suffix: RcStr,
+    code: Vc<Code>,
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptDevItemSourceMapAsset {
+    #[turbo_tasks::function]
+    pub async fn new(
+        chunk_path: Vc<FileSystemPath>,
+        id: Vc<ModuleId>,
+        code: Vc<Code>,
+    ) -> Result<Vc<Self>> {
+        Ok(EcmascriptDevItemSourceMapAsset {
+            chunk_path,
+            suffix: item_source_map_suffix(&*id.await?).into(),
+            code,
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for EcmascriptDevItemSourceMapAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        AssetIdent::from_path(self.chunk_path.append(self.suffix.clone()))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for EcmascriptDevItemSourceMapAsset {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let map = if let Some(map) = &*self.code.generate_source_map().await? {
+            *map
+        } else {
+            SourceMap::empty()
+        };
+        let map = map.to_rope().await?;
+        Ok(AssetContent::file(File::from(map).into()))
+    }
+}
+
+/// A source map of a module in an HMR update is so large that inlining it
+/// slows down the browser.
+#[turbo_tasks::value(shared)]
+pub(crate) struct LargeInlineSourceMapIssue {
+    pub chunk_path: ResolvedVc<FileSystemPath>,
+    pub id: RcStr,
+    pub size: u64,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for LargeInlineSourceMapIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.into()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::CodeGen.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.chunk_path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Line(vec![
+            StyledString::Text("The inline source map of ".into()),
+            StyledString::Code(self.id.clone()),
+            StyledString::Text(format!(" is {} KiB large", self.size / 1024).into()),
+        ])
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(
+                "Source maps of updated modules are inlined as data URLs, which the browser \
+                 decodes slowly when they are large. Set an external source map threshold on the \
+                 chunking context to emit larger source maps as separate files instead."
+                    .into(),
+            )
+            .cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{item_source_map_placement, ItemSourceMapPlacement, LARGE_INLINE_SOURCE_MAP_SIZE};
+
+    #[test]
+    fn places_source_maps_by_size() {
+        assert_eq!(
+            item_source_map_placement(100, Some(100), true),
+            ItemSourceMapPlacement::Inline
+        );
+        assert_eq!(
+            item_source_map_placement(101, Some(100), true),
+            ItemSourceMapPlacement::External
+        );
+        // With a threshold, large maps are never reported
+        assert_eq!(
+            item_source_map_placement(LARGE_INLINE_SOURCE_MAP_SIZE + 1, Some(u64::MAX), true),
+            ItemSourceMapPlacement::Inline
+        );
+    }
+
+    #[test]
+    fn reports_large_inline_source_maps_when_enabled() {
+        let large = LARGE_INLINE_SOURCE_MAP_SIZE + 1;
+        assert_eq!(
+            item_source_map_placement(large, None, true),
+            ItemSourceMapPlacement::InlineLarge
+        );
+        assert_eq!(
+            item_source_map_placement(large, None, false),
+            ItemSourceMapPlacement::Inline
+        );
+        assert_eq!(
+            item_source_map_placement(LARGE_INLINE_SOURCE_MAP_SIZE, None, true),
+            ItemSourceMapPlacement::Inline
+        );
+    }
+}
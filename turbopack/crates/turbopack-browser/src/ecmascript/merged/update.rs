@@ -3,10 +3,11 @@ use std::sync::Arc;
 use anyhow::Result;
 use serde::Serialize;
 use turbo_tasks::{FxIndexMap, FxIndexSet, IntoTraitRef, ReadRef, TryJoinIterExt, Vc};
-use turbo_tasks_fs::rope::Rope;
+use turbo_tasks_fs::{rope::Rope, FileSystemPath};
 use turbopack_core::{
     chunk::{ChunkingContext, ModuleId},
     code_builder::Code,
+    issue::IssueExt,
     output::OutputAsset,
     source_map::GenerateSourceMap,
     version::{PartialUpdate, TotalUpdate, Update, Version},
@@ -14,6 +15,10 @@ use turbopack_core::{
 
 use super::{
     super::{
+        item_source_map::{
+            item_source_map_placement, item_source_map_suffix, ItemSourceMapPlacement,
+            LargeInlineSourceMapIssue,
+        },
         update::{update_ecmascript_chunk, EcmascriptChunkUpdate},
         version::EcmascriptDevChunkVersion,
     },
@@ -73,29 +78,69 @@ struct EcmascriptMergedChunkPartial {
 }
 
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 struct EcmascriptModuleEntry {
     #[serde(with = "turbo_tasks_fs::rope::ser_as_string")]
     code: Rope,
     url: String,
     map: Option<String>,
+    /// The path of the source map relative to the output root, when it's
+    /// emitted as a separate file instead of being inlined.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    map_url: Option<String>,
+}
+
+/// The chunk of module entries and where their source maps go.
+struct EntryChunk<'a> {
+    /// The path of the chunk relative to the output root.
+    path: &'a str,
+    ident_path: Vc<FileSystemPath>,
+    external_source_map_threshold: Option<u64>,
+    warn_large_inline_source_maps: bool,
 }
 
 impl EcmascriptModuleEntry {
-    async fn from_code(id: &ModuleId, code: Vc<Code>, chunk_path: &str) -> Result<Self> {
-        let map = match &*code.generate_source_map().await? {
-            Some(map) => {
-                let map = map.await?.to_source_map().await?;
-                let mut map_str = vec![];
-                (*map).to_writer(&mut map_str)?;
-                Some(String::from_utf8(map_str)?)
+    async fn from_code(id: &ModuleId, code: Vc<Code>, chunk: &EntryChunk<'_>) -> Result<Self> {
+        let mut map = None;
+        let mut map_url = None;
+        if let Some(source_map) = &*code.generate_source_map().await? {
+            // The same rope is used by the external source map asset, so the map is only
+            // stringified once.
+            let rope = source_map.to_rope().await?;
+            let size = rope.len() as u64;
+            match item_source_map_placement(
+                size,
+                chunk.external_source_map_threshold,
+                chunk.warn_large_inline_source_maps,
+            ) {
+                ItemSourceMapPlacement::External => {
+                    map_url = Some(format!("{}{}", chunk.path, item_source_map_suffix(id)));
+                }
+                placement => {
+                    if placement == ItemSourceMapPlacement::InlineLarge {
+                        LargeInlineSourceMapIssue {
+                            chunk_path: chunk.ident_path.to_resolved().await?,
+                            id: id.to_string().into(),
+                            size,
+                        }
+                        .cell()
+                        .emit();
+                    }
+                    map = Some(rope.to_str()?.into_owned());
+                }
             }
-            None => None,
-        };
+        }
 
-        Ok(Self::new(id, code.await?, map, chunk_path))
+        Ok(Self::new(id, code.await?, map, map_url, chunk.path))
     }
 
-    fn new(id: &ModuleId, code: ReadRef<Code>, map: Option<String>, chunk_path: &str) -> Self {
+    fn new(
+        id: &ModuleId,
+        code: ReadRef<Code>,
+        map: Option<String>,
+        map_url: Option<String>,
+        chunk_path: &str,
+    ) -> Self {
         /// serde_qs can't serialize a lone enum when it's [serde::untagged].
         #[derive(Serialize)]
         struct Id<'a> {
@@ -108,6 +153,7 @@ impl EcmascriptModuleEntry {
             code: code.source_code().clone(),
             url: format!("{}?{}", chunk_path, &id),
             map,
+            map_url,
         }
     }
 }
@@ -193,65 +239,74 @@ pub(super) async fn update_ecmascript_merged_chunk(
         let Some(chunk_path) = output_root.get_path_to(path) else {
             continue;
         };
+        let chunking_context = content_ref.chunking_context.await?;
+        let entry_chunk = EntryChunk {
+            path: chunk_path,
+            ident_path: content_ref.chunk.ident().path(),
+            external_source_map_threshold: chunking_context.external_source_map_threshold(),
+            warn_large_inline_source_maps: chunking_context.warn_large_inline_source_maps(),
+        };
 
-        let chunk_update = if let Some(from_version) =
-            from_versions_by_chunk_path.remove(chunk_path)
-        {
-            // The chunk was present in the previous version, so we must update it.
-            let update = update_ecmascript_chunk(*content, from_version).await?;
+        let chunk_update =
+            if let Some(from_version) = from_versions_by_chunk_path.remove(chunk_path) {
+                // The chunk was present in the previous version, so we must update it.
+                let update = update_ecmascript_chunk(*content, from_version).await?;
 
-            match update {
-                EcmascriptChunkUpdate::None => {
-                    // Nothing changed, so we can skip this chunk.
-                    continue;
-                }
-                EcmascriptChunkUpdate::Partial(chunk_partial) => {
-                    // The chunk was updated.
-                    let mut partial = EcmascriptMergedChunkPartial::default();
+                match update {
+                    EcmascriptChunkUpdate::None => {
+                        // Nothing changed, so we can skip this chunk.
+                        continue;
+                    }
+                    EcmascriptChunkUpdate::Partial(chunk_partial) => {
+                        // The chunk was updated.
+                        let mut partial = EcmascriptMergedChunkPartial::default();
+
+                        for (module_id, (module_hash, module_code)) in chunk_partial.added {
+                            partial.added.insert(module_id.clone());
+
+                            if merged_module_map.get(&module_id) != Some(module_hash) {
+                                let entry = EcmascriptModuleEntry::from_code(
+                                    &module_id,
+                                    *module_code,
+                                    &entry_chunk,
+                                )
+                                .await?;
+                                merged_update.entries.insert(module_id, entry);
+                            }
+                        }
 
-                    for (module_id, (module_hash, module_code)) in chunk_partial.added {
-                        partial.added.insert(module_id.clone());
+                        partial.deleted.extend(chunk_partial.deleted.into_keys());
 
-                        if merged_module_map.get(&module_id) != Some(module_hash) {
+                        for (module_id, module_code) in chunk_partial.modified {
                             let entry = EcmascriptModuleEntry::from_code(
                                 &module_id,
                                 *module_code,
-                                chunk_path,
+                                &entry_chunk,
                             )
                             .await?;
                             merged_update.entries.insert(module_id, entry);
                         }
+
+                        EcmascriptMergedChunkUpdate::Partial(partial)
                     }
+                }
+            } else {
+                // The chunk was added in this version.
+                let mut added = EcmascriptMergedChunkAdded::default();
 
-                    partial.deleted.extend(chunk_partial.deleted.into_keys());
+                for (id, entry) in &content_ref.entries.await? {
+                    let hash = *entry.hash.await?;
+                    added.modules.insert(id.clone());
 
-                    for (module_id, module_code) in chunk_partial.modified {
+                    if merged_module_map.get(id) != Some(hash) {
                         let entry =
-                            EcmascriptModuleEntry::from_code(&module_id, *module_code, chunk_path)
-                                .await?;
-                        merged_update.entries.insert(module_id, entry);
+                            EcmascriptModuleEntry::from_code(id, entry.code, &entry_chunk).await?;
+                        merged_update.entries.insert(id.clone(), entry);
                     }
-
-                    EcmascriptMergedChunkUpdate::Partial(partial)
-                }
-            }
-        } else {
-            // The chunk was added in this version.
-            let mut added = EcmascriptMergedChunkAdded::default();
-
-            for (id, entry) in &content_ref.entries.await? {
-                let hash = *entry.hash.await?;
-                added.modules.insert(id.clone());
-
-                if merged_module_map.get(id) != Some(hash) {
-                    let entry =
-                        EcmascriptModuleEntry::from_code(id, entry.code, chunk_path).await?;
-                    merged_update.entries.insert(id.clone(), entry);
                 }
-            }
 
-            EcmascriptMergedChunkUpdate::Added(added)
-        };
+                EcmascriptMergedChunkUpdate::Added(added)
+            };
 
         merged_update.chunks.insert(chunk_path, chunk_update);
     }
@@ -2,6 +2,7 @@ pub(crate) mod chunk;
 pub(crate) mod content;
 pub(crate) mod content_entry;
 pub(crate) mod evaluate;
+pub(crate) mod item_source_map;
 pub(crate) mod list;
 pub(crate) mod merged;
 pub(crate) mod update;
@@ -322,7 +322,7 @@ impl PartialEq for ConsoleUi {
 
 #[turbo_tasks::value_impl]
 impl ConsoleUi {
-    #[turbo_tasks::function]
+    #[turbo_tasks::function(transient)]
     pub fn new(options: TransientInstance<LogOptions>) -> Vc<Self> {
         ConsoleUi {
             options: (*options).clone(),
@@ -334,7 +334,7 @@ impl ConsoleUi {
 
 #[turbo_tasks::value_impl]
 impl IssueReporter for ConsoleUi {
-    #[turbo_tasks::function]
+    #[turbo_tasks::function(transient)]
     async fn report_issues(
         &self,
         issues: TransientInstance<CapturedIssues>,
@@ -1,5 +1,5 @@
 use anyhow::{bail, Result};
-use turbo_tasks::{ResolvedVc, ValueToString, Vc};
+use turbo_tasks::{CowVec, ResolvedVc, ValueToString, Vc};
 use turbo_tasks_fs::FileSystemPath;
 use turbopack_core::{
     chunk::{EvaluatableAsset, EvaluatableAssetExt, EvaluatableAssets},
@@ -43,7 +43,7 @@ impl RuntimeEntry {
         .primary_modules()
         .await?;
 
-        let mut runtime_entries = Vec::with_capacity(modules.len());
+        let mut runtime_entries = CowVec::new();
         for &module in &modules {
             if let Some(entry) =
                 ResolvedVc::try_sidecast::<Box<dyn EvaluatableAsset>>(module).await?
@@ -71,11 +71,11 @@ impl RuntimeEntries {
         &self,
         asset_context: Vc<Box<dyn AssetContext>>,
     ) -> Result<Vc<EvaluatableAssets>> {
-        let mut runtime_entries = Vec::new();
+        let mut runtime_entries = CowVec::new();
 
         for reference in &self.0 {
             let resolved_entries = reference.resolve_entry(asset_context).await?;
-            runtime_entries.extend(&resolved_entries);
+            runtime_entries.append_shared(&resolved_entries);
         }
 
         Ok(Vc::cell(runtime_entries))
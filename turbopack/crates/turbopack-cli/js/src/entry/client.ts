@@ -1,3 +1,4 @@
+import { forwardConsole } from "@vercel/turbopack-ecmascript-runtime/browser/dev/hmr-client/console-forwarding";
 import { connect } from "@vercel/turbopack-ecmascript-runtime/browser/dev/hmr-client/hmr-client";
 import {
   connectHMR,
@@ -16,4 +17,7 @@ export function initializeHMR(options: { assetPrefix: string }) {
     log: true,
     path: "/turbopack-hmr",
   });
+  forwardConsole({
+    path: "/turbopack-console",
+  });
 }
@@ -0,0 +1,229 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use serde::Serialize;
+use turbopack_core::package_json::package_of_path;
+use turbopack_ecmascript::duplicate_packages::DuplicatePackageVersion;
+
+use super::{AnalyzeReport, ChunkReport};
+
+/// The number of packages listed in the ranking of the largest dependencies.
+const LARGEST_PACKAGES_LIMIT: usize = 30;
+
+const TEMPLATE: &str = include_str!("report.html");
+const DATA_PLACEHOLDER: &str = "/*REPORT_DATA*/null";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportData<'a> {
+    chunks: &'a [ChunkReport],
+    duplicate_packages: Vec<DuplicatePackage<'a>>,
+    largest_packages: Vec<PackageSize>,
+}
+
+#[derive(Serialize)]
+struct DuplicatePackage<'a> {
+    name: &'a str,
+    versions: Vec<DuplicatePackageVersionData<'a>>,
+}
+
+#[derive(Serialize)]
+struct DuplicatePackageVersionData<'a> {
+    #[serde(flatten)]
+    version: &'a DuplicatePackageVersion,
+    /// The chunks that contain modules of this version of the package.
+    chunks: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageSize {
+    name: String,
+    size: u64,
+    module_count: usize,
+}
+
+/// Renders the report as a single HTML file, which contains the data and the
+/// code to display it.
+pub fn render(report: &AnalyzeReport) -> Result<String> {
+    let packages = collect_packages(report);
+    let data = ReportData {
+        chunks: &report.chunks,
+        duplicate_packages: duplicate_packages(report),
+        largest_packages: largest_packages(&packages),
+    };
+    // Escape `<` so the data can't close the script element
+    let data = serde_json::to_string(&data)?.replace('<', "\\u003c");
+    Ok(TEMPLATE.replace(DATA_PLACEHOLDER, &data))
+}
+
+/// The modules of a package, by the directory of each copy of the package.
+type Packages<'a> = BTreeMap<&'a str, BTreeMap<&'a str, BTreeMap<&'a str, u64>>>;
+
+/// Groups the modules of the report by the package that contains them. Modules
+/// that are included in multiple chunks are only counted once.
+fn collect_packages(report: &AnalyzeReport) -> Packages<'_> {
+    let mut packages: Packages = BTreeMap::new();
+    for module in report.chunks.iter().flat_map(|chunk| &chunk.modules) {
+        if let Some((name, package_dir)) = package_of_path(&module.path) {
+            packages
+                .entry(name)
+                .or_default()
+                .entry(package_dir)
+                .or_default()
+                .insert(&*module.path, module.size);
+        }
+    }
+    packages
+}
+
+/// Attributes the versions of the duplicate packages to the chunks that
+/// contain them.
+fn duplicate_packages(report: &AnalyzeReport) -> Vec<DuplicatePackage<'_>> {
+    report
+        .duplicate_packages
+        .iter()
+        .map(|package| DuplicatePackage {
+            name: &package.name,
+            versions: package
+                .versions
+                .iter()
+                .map(|version| DuplicatePackageVersionData {
+                    version,
+                    chunks: report
+                        .chunks
+                        .iter()
+                        .filter(|chunk| {
+                            chunk.modules.iter().any(|module| {
+                                package_of_path(&module.path)
+                                    .is_some_and(|(_, dir)| dir == &*version.path)
+                            })
+                        })
+                        .map(|chunk| &*chunk.path)
+                        .collect(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn largest_packages(packages: &Packages) -> Vec<PackageSize> {
+    let mut sizes = packages
+        .iter()
+        .map(|(name, copies)| {
+            let modules: BTreeSet<_> = copies.values().flat_map(|modules| modules.iter()).collect();
+            PackageSize {
+                name: name.to_string(),
+                size: modules.iter().map(|(_, size)| **size).sum(),
+                module_count: modules.len(),
+            }
+        })
+        .collect::<Vec<_>>();
+    sizes.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    sizes.truncate(LARGEST_PACKAGES_LIMIT);
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use turbopack_ecmascript::duplicate_packages::{DuplicatePackage, DuplicatePackageVersion};
+
+    use super::{collect_packages, duplicate_packages, largest_packages, render, DATA_PLACEHOLDER};
+    use crate::analyze::{AnalyzeReport, ChunkReport, ModuleReport};
+
+    fn chunk(path: &str, modules: &[(&str, u64)]) -> ChunkReport {
+        ChunkReport {
+            path: path.into(),
+            size: modules.iter().map(|(_, size)| size).sum(),
+            modules: modules
+                .iter()
+                .map(|(path, size)| ModuleReport {
+                    path: (*path).into(),
+                    size: *size,
+                })
+                .collect(),
+        }
+    }
+
+    fn report() -> AnalyzeReport {
+        AnalyzeReport {
+            chunks: vec![
+                chunk(
+                    "main.js",
+                    &[
+                        ("src/index.js", 100),
+                        ("node_modules/react/index.js", 10),
+                        ("node_modules/react/cjs/react.js", 200),
+                        ("node_modules/@scope/a/index.js", 50),
+                    ],
+                ),
+                chunk(
+                    "other.js",
+                    &[
+                        ("node_modules/react/index.js", 10),
+                        ("node_modules/b/node_modules/@scope/a/index.js", 70),
+                    ],
+                ),
+            ],
+            duplicate_packages: vec![DuplicatePackage {
+                name: "@scope/a".into(),
+                versions: vec![
+                    DuplicatePackageVersion {
+                        version: Some("1.0.0".into()),
+                        path: "node_modules/@scope/a".into(),
+                        import_chain: vec![],
+                    },
+                    DuplicatePackageVersion {
+                        version: Some("2.0.0".into()),
+                        path: "node_modules/b/node_modules/@scope/a".into(),
+                        import_chain: vec![],
+                    },
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn sums_the_sizes_of_packages() {
+        let report = report();
+        let packages = collect_packages(&report);
+        let sizes = largest_packages(&packages)
+            .into_iter()
+            .map(|package| (package.name, package.size, package.module_count))
+            .collect::<Vec<_>>();
+        // The react module in both chunks is counted once, and both copies of
+        // `@scope/a` are counted.
+        assert_eq!(
+            sizes,
+            vec![
+                ("react".to_string(), 210, 2),
+                ("@scope/a".to_string(), 120, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes_duplicate_packages_to_chunks() {
+        let report = report();
+        let packages = duplicate_packages(&report);
+        assert_eq!(packages.len(), 1);
+        let chunks = packages[0]
+            .versions
+            .iter()
+            .map(|version| version.chunks.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(chunks, vec![vec!["main.js"], vec!["other.js"]]);
+    }
+
+    #[test]
+    fn renders_the_data_into_the_template() {
+        let mut report = report();
+        report.chunks.push(chunk("</script>.js", &[]));
+        let html = render(&report).unwrap();
+        assert!(!html.contains(DATA_PLACEHOLDER));
+        assert!(html.contains(r#""largestPackages":[{"name":"react","size":210,"moduleCount":2}"#));
+        // The data can't close the script element.
+        assert!(!html.contains("</script>.js"));
+        assert!(html.contains(r#""path":"\u003c/script>.js""#));
+    }
+}
@@ -0,0 +1,125 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{trace::TraceRawVcs, RcStr, ResolvedVc, Vc};
+use turbopack_core::chunk::Chunk;
+use turbopack_ecmascript::duplicate_packages::DuplicatePackage;
+use turbopack_nodejs::EcmascriptBuildNodeChunk;
+
+use crate::{
+    arguments::AnalyzeArguments,
+    build::{create_build_builder, BuildOutput},
+    util::normalize_dirs,
+};
+
+mod html;
+
+/// The chunks and modules of a build, from which the analyze report is
+/// generated. It's written next to the report, so the report can be generated
+/// again without building.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone)]
+pub struct AnalyzeReport {
+    pub chunks: Vec<ChunkReport>,
+    /// The packages that are included in more than one version in the chunk
+    /// group of an entry.
+    #[serde(default)]
+    pub duplicate_packages: Vec<DuplicatePackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+pub struct ChunkReport {
+    /// The path of the chunk, relative to the output directory.
+    pub path: RcStr,
+    /// The size of the chunk in bytes.
+    pub size: u64,
+    pub modules: Vec<ModuleReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+pub struct ModuleReport {
+    /// The path of the module, relative to the project root.
+    pub path: RcStr,
+    /// The size of the source of the module in bytes.
+    pub size: u64,
+}
+
+/// Collects the chunks of the build output and the modules in them. Source
+/// maps are not included.
+#[turbo_tasks::function]
+pub(crate) async fn analyze_build_output(
+    build_output: Vc<BuildOutput>,
+) -> Result<Vc<AnalyzeReport>> {
+    let build_output = build_output.await?;
+    let mut chunks = Vec::new();
+    for &asset in build_output.output_assets.await?.iter() {
+        let path = asset.ident().path().await?;
+        if path.extension_ref() == Some("map") {
+            continue;
+        }
+        let size = asset.content().len().await?.unwrap_or_default();
+
+        let mut modules = Vec::new();
+        if let Some(chunk) =
+            ResolvedVc::try_downcast_type::<EcmascriptBuildNodeChunk>(asset).await?
+        {
+            for item in chunk.chunk().chunk_items().await?.iter() {
+                let module = item.module();
+                modules.push(ModuleReport {
+                    path: module.ident().path().await?.path.clone(),
+                    size: module.content().len().await?.unwrap_or_default(),
+                });
+            }
+        }
+
+        chunks.push(ChunkReport {
+            path: path.path.clone(),
+            size,
+            modules,
+        });
+    }
+    Ok(AnalyzeReport {
+        chunks,
+        duplicate_packages: build_output.duplicate_packages.clone(),
+    }
+    .cell())
+}
+
+pub async fn analyze(args: &AnalyzeArguments) -> Result<()> {
+    let report_data = match &args.from {
+        Some(from) => {
+            let data = fs::read_to_string(from)
+                .with_context(|| format!("Unable to read {}", from.display()))?;
+            serde_json::from_str::<AnalyzeReport>(&data)
+                .with_context(|| format!("{} is not an analyze report", from.display()))?
+        }
+        None => {
+            let report = create_build_builder(&args.build)?.analyze().await?;
+            let report = (*report).clone();
+            let data_path = default_output_dir(args)?.join("analyze.json");
+            fs::write(&data_path, serde_json::to_string(&report)?)
+                .with_context(|| format!("Unable to write {}", data_path.display()))?;
+            report
+        }
+    };
+
+    let output = match &args.output {
+        Some(output) => output.clone(),
+        None => default_output_dir(args)?.join("analyze.html"),
+    };
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&output, html::render(&report_data)?)
+        .with_context(|| format!("Unable to write {}", output.display()))?;
+    println!("Wrote analyze report to {}", output.display());
+
+    Ok(())
+}
+
+/// The `dist` directory of the build, which contains the chunks.
+fn default_output_dir(args: &AnalyzeArguments) -> Result<PathBuf> {
+    let dirs = normalize_dirs(&args.build.common.dir, &args.build.common.root)?;
+    Ok(PathBuf::from(&*dirs.project_dir).join("dist"))
+}
@@ -11,6 +11,7 @@ use turbopack_cli_utils::issue::IssueSeverityCliOption;
 pub enum Arguments {
     Build(BuildArguments),
     Dev(DevArguments),
+    Analyze(AnalyzeArguments),
 }
 
 impl Arguments {
@@ -19,6 +20,7 @@ impl Arguments {
         match self {
             Arguments::Build(args) => args.common.dir.as_deref(),
             Arguments::Dev(args) => args.common.dir.as_deref(),
+            Arguments::Analyze(args) => args.build.common.dir.as_deref(),
         }
     }
 }
@@ -61,6 +63,23 @@ pub struct CommonArguments {
     /// MB.
     #[clap(long)]
     pub memory_limit: Option<usize>,
+
+    /// Read `package.json` and `index.*` files in the background when their
+    /// directory is read, which speeds up resolving on slow, e.g. network,
+    /// filesystems.
+    #[clap(long)]
+    pub read_ahead: bool,
+
+    /// Share one allocation between cells with equal values whose
+    /// serialization is at least the provided size in bytes.
+    #[clap(long)]
+    pub cell_interning: Option<usize>,
+
+    /// The pages enforce a strict Content Security Policy that forbids
+    /// `eval`. The development runtime then applies HMR updates with
+    /// `<script>` tags that carry the nonce of the runtime script.
+    #[clap(long)]
+    pub strict_csp: bool,
 }
 
 #[derive(Debug, Args)]
@@ -85,10 +104,49 @@ pub struct DevArguments {
     #[clap(long)]
     pub eager_compile: bool,
 
+    /// Check the env variables of the process every given number of
+    /// milliseconds and recompute what depends on the ones that changed.
+    #[clap(long, value_parser)]
+    pub poll_env: Option<u64>,
+
+    /// Write chunks for debugging the bundler: every module is preceded by a
+    /// comment with its path and the code is never minified.
+    #[clap(long)]
+    pub readable_output: bool,
+
+    /// Print the errors and warnings of the browser console in the terminal,
+    /// with the HMR update that the page applied last.
+    #[clap(long)]
+    pub forward_browser_console: bool,
+
     /// Don't open the browser automatically when the dev server has started.
     #[clap(long)]
     pub no_open: bool,
 
+    /// Inject the latency, errors and dropped HMR updates that the given JSON
+    /// file configures, to test how the app handles them. See
+    /// `FaultInjectionConfig` of turbopack-dev-server for the format.
+    #[clap(long, value_parser, env = "TURBOPACK_FAULT_INJECTION")]
+    pub fault_injection: Option<PathBuf>,
+
+    /// Serve another project to the requests with the given `Host` header, as
+    /// `<HOST>=<DIR>`, e.g. `docs.localhost=../docs`. It's built with the same
+    /// entries as the main project, and needs to be inside `--root` when
+    /// that's set. Can be repeated.
+    #[clap(long = "host-project", value_parser = parse_host_project)]
+    pub host_projects: Vec<(String, PathBuf)>,
+
+    /// Serve the given HTML document, e.g. `/index.html`, to page requests
+    /// that no asset matches, for single page applications that route on the
+    /// client.
+    #[clap(long, value_parser)]
+    pub spa_fallback: Option<String>,
+
+    /// A path prefix, e.g. `api/`, whose unmatched requests respond with a 404
+    /// instead of the document of `--spa-fallback`. Can be repeated.
+    #[clap(long, value_parser, requires = "spa_fallback")]
+    pub spa_fallback_exclude: Vec<String>,
+
     // ==
     // = Inherited options from next-dev, need revisit later.
     // ==
@@ -96,6 +154,11 @@ pub struct DevArguments {
     /// in use.
     #[clap(long)]
     pub allow_retry: bool,
+
+    /// Sample the executing tasks of Turbo Engine every given number of
+    /// milliseconds. The profile can be downloaded from `/turbopack-profile`.
+    #[clap(long, value_parser)]
+    pub sampling_profiler: Option<u64>,
 }
 
 #[derive(Debug, Args)]
@@ -107,4 +170,53 @@ pub struct BuildArguments {
     /// Don't minify build output.
     #[clap(long)]
     pub no_minify: bool,
+
+    /// Build a library instead of an application. The dependencies of the
+    /// package.json are not bundled, and the entries are emitted as CommonJS
+    /// and ES modules with their TypeScript declarations.
+    #[clap(long)]
+    pub library: bool,
+
+    /// Emit the entries as ES modules for `<script type="module">`. They
+    /// import the chunks they depend on and export the bindings of the entry
+    /// module.
+    #[clap(long, conflicts_with = "library")]
+    pub esm: bool,
+
+    /// Print the given number of Turbo Engine functions that wrote the largest
+    /// values into cells after building.
+    #[clap(long, value_parser)]
+    pub largest_outputs: Option<usize>,
+
+    /// Print the result of the build as JSON instead of logging the issues:
+    /// the issues, the emitted assets with their sizes and hashes, the timings
+    /// and the features the build ran with. The schema is versioned with its
+    /// `schemaVersion` field.
+    #[clap(long, conflicts_with = "largest_outputs")]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+#[clap(author, version, about, long_about = None)]
+pub struct AnalyzeArguments {
+    #[clap(flatten)]
+    pub build: BuildArguments,
+
+    /// Generate the report from the `analyze.json` of a previous analysis
+    /// instead of building the project.
+    #[clap(long, value_parser)]
+    pub from: Option<PathBuf>,
+
+    /// The path of the HTML report.
+    /// If no path is provided, `dist/analyze.html` in the project's directory
+    /// will be used.
+    #[clap(short, long, value_parser)]
+    pub output: Option<PathBuf>,
+}
+
+fn parse_host_project(value: &str) -> Result<(String, PathBuf), String> {
+    let (host, dir) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected `<HOST>=<DIR>`, got `{value}`"))?;
+    Ok((host.to_string(), PathBuf::from(dir)))
 }
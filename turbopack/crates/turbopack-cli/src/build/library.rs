@@ -0,0 +1,355 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use serde_json::json;
+use turbo_tasks::{FxIndexSet, ResolvedVc, Vc};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::AssetContent,
+    module::{Module, Modules},
+    output::{OutputAsset, OutputAssets},
+    virtual_output::VirtualOutputAsset,
+};
+use turbopack_ecmascript::{
+    chunk::{EcmascriptChunkPlaceable, EcmascriptExports},
+    utils::{is_identifier_name, StringifyJs},
+};
+
+/// The outputs of a library build in addition to the CommonJS entry chunks
+/// (`dist/<name>.js`):
+///
+/// * an ES module wrapper (`dist/<name>.mjs`) that re-exports the exports of the entry,
+/// * a `dist/package.json` that marks the `.js` files as CommonJS, also in packages with `"type":
+///   "module"`,
+/// * the TypeScript declaration files that are referenced by the entry, and
+/// * a `dist/package-exports.json` with the `main`, `module`, `types` and `exports` fields the
+///   package.json of the library can use.
+#[turbo_tasks::function]
+pub(crate) async fn library_output_assets(
+    build_output_root: Vc<FileSystemPath>,
+    entries: Vc<Modules>,
+) -> Result<Vc<OutputAssets>> {
+    let mut assets: Vec<ResolvedVc<Box<dyn OutputAsset>>> = Vec::new();
+    let mut exports = serde_json::Map::new();
+    let mut fields = serde_json::Map::new();
+    for (i, &entry) in entries.await?.iter().enumerate() {
+        let entry_path = entry.ident().path();
+        let Some(name) = (*entry_path.file_stem().await?).clone() else {
+            continue;
+        };
+
+        assets.push(ResolvedVc::upcast(
+            VirtualOutputAsset::new(
+                build_output_root.join(format!("{name}.mjs").into()),
+                AssetContent::file(File::from(esm_wrapper(*entry, &name).await?).into()),
+            )
+            .to_resolved()
+            .await?,
+        ));
+
+        let entry_dir = entry_path.parent().await?;
+        let mut has_types = false;
+        for declaration in declaration_files(entry_path, &name).await? {
+            let Some(relative) = entry_dir.get_relative_path_to(&*declaration.await?) else {
+                continue;
+            };
+            // Declarations outside of the directory of the entry have no
+            // place in the output
+            if relative.starts_with("../") {
+                continue;
+            }
+            has_types |= relative == format!("./{name}.d.ts");
+            assets.push(ResolvedVc::upcast(
+                VirtualOutputAsset::new(
+                    build_output_root.join(relative.trim_start_matches("./").into()),
+                    AssetContent::file(declaration.read()),
+                )
+                .to_resolved()
+                .await?,
+            ));
+        }
+
+        let mut conditions = serde_json::Map::new();
+        let types = format!("./dist/{name}.d.ts");
+        if has_types {
+            conditions.insert("types".into(), types.clone().into());
+        }
+        conditions.insert("import".into(), format!("./dist/{name}.mjs").into());
+        conditions.insert("require".into(), format!("./dist/{name}.js").into());
+        if i == 0 {
+            fields.insert("main".into(), format!("./dist/{name}.js").into());
+            fields.insert("module".into(), format!("./dist/{name}.mjs").into());
+            if has_types {
+                fields.insert("types".into(), types.into());
+            }
+            exports.insert(".".into(), conditions.into());
+        } else {
+            exports.insert(format!("./{name}"), conditions.into());
+        }
+    }
+    fields.insert("exports".into(), exports.into());
+
+    // The chunks are CommonJS, but the package.json of the library decides
+    // how Node.js loads `.js` files, unless there is one closer to them.
+    assets.push(ResolvedVc::upcast(
+        VirtualOutputAsset::new(
+            build_output_root.join("package.json".into()),
+            AssetContent::file(
+                File::from(serde_json::to_string_pretty(
+                    &json!({ "type": "commonjs" }),
+                )?)
+                .into(),
+            ),
+        )
+        .to_resolved()
+        .await?,
+    ));
+    assets.push(ResolvedVc::upcast(
+        VirtualOutputAsset::new(
+            build_output_root.join("package-exports.json".into()),
+            AssetContent::file(File::from(serde_json::to_string_pretty(&json!(fields))?).into()),
+        )
+        .to_resolved()
+        .await?,
+    ));
+
+    Ok(Vc::cell(assets))
+}
+
+/// Generates an ES module that imports the CommonJS entry chunk of `entry`
+/// and re-exports its exports under the same names.
+async fn esm_wrapper(entry: Vc<Box<dyn Module>>, name: &str) -> Result<String> {
+    let mut code = String::new();
+    writeln!(
+        code,
+        "import lib from {};",
+        StringifyJs(&format!("./{name}.js"))
+    )?;
+
+    let exports = match Vc::try_resolve_sidecast::<Box<dyn EcmascriptChunkPlaceable>>(entry).await?
+    {
+        Some(placeable) => placeable.get_exports().await?,
+        None => return Ok(code),
+    };
+    let EcmascriptExports::EsmExports(esm_exports) = &*exports else {
+        // The names of CommonJS exports are not known statically, so the
+        // exports object is the default export, like when Node.js imports a
+        // CommonJS module
+        writeln!(code, "export default lib;")?;
+        return Ok(code);
+    };
+
+    let expanded = esm_exports.expand_exports().await?;
+    let names = expanded
+        .exports
+        .keys()
+        .filter(|name| name.as_str() != "default")
+        .collect::<Vec<_>>();
+    if !names.is_empty() {
+        let mut bindings = Vec::new();
+        for (i, name) in names.into_iter().enumerate() {
+            writeln!(code, "const __export{i} = lib[{}];", StringifyJs(name))?;
+            bindings.push(if is_identifier_name(name) {
+                format!("__export{i} as {name}")
+            } else {
+                format!("__export{i} as {}", StringifyJs(name))
+            });
+        }
+        writeln!(code, "export {{ {} }};", bindings.join(", "))?;
+    }
+    if expanded.exports.contains_key("default") {
+        writeln!(code, "export default lib.default;")?;
+    }
+    Ok(code)
+}
+
+/// Collects the TypeScript declaration files that are referenced by the entry
+/// at `entry_path`: the declaration file next to the entry (e.g. `index.d.ts`
+/// for `index.js`) first, followed by the declaration files that the entry or
+/// the collected declaration files import or reference with a triple-slash
+/// directive.
+async fn declaration_files(
+    entry_path: Vc<FileSystemPath>,
+    name: &str,
+) -> Result<Vec<Vc<FileSystemPath>>> {
+    let mut declarations = FxIndexSet::default();
+    let sibling = entry_path
+        .parent()
+        .join(format!("{name}.d.ts").into())
+        .resolve()
+        .await?;
+    if matches!(&*sibling.read().await?, FileContent::Content(_)) {
+        declarations.insert(sibling);
+    }
+
+    let mut queue = vec![entry_path.resolve().await?];
+    queue.extend(declarations.iter().copied());
+    while let Some(path) = queue.pop() {
+        let FileContent::Content(file) = &*path.read().await? else {
+            continue;
+        };
+        let source = file.content().to_str()?;
+        let dir = path.parent();
+        for specifier in declaration_references(&source) {
+            for candidate in declaration_candidates(specifier) {
+                let candidate = dir.join(candidate.into()).resolve().await?;
+                if matches!(&*candidate.read().await?, FileContent::Content(_)) {
+                    if declarations.insert(candidate) {
+                        queue.push(candidate);
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    Ok(declarations.into_iter().collect())
+}
+
+/// Returns the relative specifiers of the imports, re-exports and
+/// `/// <reference path="..." />` directives in `source`.
+///
+/// The source is scanned for string literals that follow `from`, `import` or
+/// `require`, so statements can span multiple lines. Comments and other
+/// string literals are skipped.
+fn declaration_references(source: &str) -> Vec<&str> {
+    let mut references = source
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("///"))
+        .filter_map(|directive| {
+            directive
+                .split_once("path=")
+                .and_then(|(_, rest)| quoted(rest))
+        })
+        .collect::<Vec<_>>();
+
+    let bytes = source.as_bytes();
+    // Whether the last token is a keyword that is followed by a specifier
+    let mut after_keyword = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = source[i..].find('\n').map_or(bytes.len(), |end| i + end);
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = source[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + 2 + end + 2);
+                continue;
+            }
+            quote @ (b'"' | b'\'' | b'`') => {
+                let start = i + 1;
+                let mut end = start;
+                while end < bytes.len() && bytes[end] != quote {
+                    end += if bytes[end] == b'\\' { 2 } else { 1 };
+                }
+                let end = end.min(bytes.len());
+                if after_keyword && quote != b'`' {
+                    references.push(&source[start..end]);
+                }
+                after_keyword = false;
+                i = end + 1;
+                continue;
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' || c == b'$' => {
+                let start = i;
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b'$')
+                {
+                    i += 1;
+                }
+                after_keyword = matches!(&source[start..i], "from" | "import" | "require");
+                continue;
+            }
+            // `import("./a")` and `require("./a")`
+            c if c.is_ascii_whitespace() || c == b'(' => {}
+            _ => after_keyword = false,
+        }
+        i += 1;
+    }
+
+    references.retain(|specifier| specifier.starts_with("./") || specifier.starts_with("../"));
+    references
+}
+
+/// Returns the content of the string literal at the start of `s`.
+fn quoted(s: &str) -> Option<&str> {
+    let quote = s.chars().next().filter(|&c| c == '"' || c == '\'')?;
+    let rest = &s[1..];
+    rest.find(quote).map(|end| &rest[..end])
+}
+
+/// The paths at which the declarations of the module `specifier` may be found.
+fn declaration_candidates(specifier: &str) -> Vec<String> {
+    if specifier.ends_with(".d.ts") {
+        return vec![specifier.to_string()];
+    }
+    let stem = [".js", ".mjs", ".cjs", ".ts", ".tsx", ".jsx"]
+        .iter()
+        .find_map(|extension| specifier.strip_suffix(extension))
+        .unwrap_or(specifier);
+    vec![format!("{stem}.d.ts"), format!("{specifier}/index.d.ts")]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{declaration_candidates, declaration_references};
+
+    #[test]
+    fn finds_multi_line_imports_and_exports() {
+        let source = r#"
+/// <reference path="./globals.d.ts" />
+import {
+    a,
+    b,
+} from "./ab";
+import type { C } from './c.js';
+import './side-effect';
+export * from
+    "./reexport";
+export { d } from "./d";
+import e = require("./e");
+type F = typeof import("./f");
+import { g } from "package";
+"#;
+        assert_eq!(
+            declaration_references(source),
+            [
+                "./globals.d.ts",
+                "./ab",
+                "./c.js",
+                "./side-effect",
+                "./reexport",
+                "./d",
+                "./e",
+                "./f",
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_comments_and_other_strings() {
+        let source = r#"
+// import { a } from "./commented";
+/* export * from "./block-commented"; */
+export declare const message = "import from './quoted'";
+export declare function from(path: "./not-an-import"): void;
+import { from } from "../parent";
+"#;
+        assert_eq!(declaration_references(source), ["../parent"]);
+    }
+
+    #[test]
+    fn finds_declarations_of_modules() {
+        assert_eq!(declaration_candidates("./a.d.ts"), ["./a.d.ts"]);
+        assert_eq!(
+            declaration_candidates("./a.js"),
+            ["./a.d.ts", "./a.js/index.d.ts"]
+        );
+        assert_eq!(
+            declaration_candidates("./dir"),
+            ["./dir.d.ts", "./dir/index.d.ts"]
+        );
+    }
+}
@@ -1,16 +1,19 @@
 use std::{
-    collections::HashSet,
     env::current_dir,
     path::{PathBuf, MAIN_SEPARATOR},
     sync::Arc,
+    time::Instant,
 };
 
 use anyhow::{bail, Context, Result};
+use tracing::Instrument;
 use turbo_tasks::{
-    RcStr, ReadConsistency, ResolvedVc, TransientInstance, TryJoinIterExt, TurboTasks, Value, Vc,
+    util::FormatBytes, FxIndexSet, RcStr, ReadConsistency, ReadRef, ResolvedVc, TransientInstance,
+    TryJoinIterExt, TurboTasks, Value, Vc,
 };
 use turbo_tasks_fs::FileSystem;
-use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_memory::{MemoryBackend, TaskStatistics};
+use turbopack_browser::BrowserChunkingContext;
 use turbopack_cli_utils::issue::{ConsoleUi, LogOptions};
 use turbopack_core::{
     asset::Asset,
@@ -18,8 +21,7 @@ use turbopack_core::{
         availability_info::AvailabilityInfo, ChunkableModule, ChunkingContext, ChunkingContextExt,
         EvaluatableAsset, EvaluatableAssets, MinifyType,
     },
-    environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
-    issue::{handle_issues, IssueReporter, IssueSeverity},
+    issue::{handle_issues, IssueDescriptionExt, IssueReporter, IssueSeverity},
     module::Module,
     output::{OutputAsset, OutputAssets},
     reference::all_assets_from_entries,
@@ -29,20 +31,38 @@ use turbopack_core::{
         parse::Request,
     },
 };
-use turbopack_ecmascript_runtime::RuntimeType;
+use turbopack_ecmascript::duplicate_packages::{find_duplicate_packages, DuplicatePackage};
+use turbopack_ecmascript_runtime::{OutputFormat, RuntimeType};
 use turbopack_env::dotenv::load_env;
 use turbopack_node::execution_context::ExecutionContext;
 use turbopack_nodejs::NodeJsChunkingContext;
 
+pub use self::timings::{BuildTimings, BuildTimingsLayer};
+use self::{
+    library::library_output_assets,
+    report::{
+        asset_reports, issue_reports, BuildReport, EntryTimingReport, FeaturesReport,
+        TimingsReport, BUILD_REPORT_SCHEMA_VERSION,
+    },
+    timings::{EMIT_SPAN, ENTRY_SPAN},
+};
 use crate::{
+    analyze::{analyze_build_output, AnalyzeReport},
     arguments::BuildArguments,
-    contexts::{get_client_asset_context, get_client_compile_time_info, NodeEnv},
+    contexts::{
+        get_browser_import_map, get_client_asset_context, get_client_compile_time_info,
+        get_library_asset_context, NodeEnv,
+    },
     util::{
         normalize_dirs, normalize_entries, output_fs, project_fs, EntryRequest, EntryRequests,
         NormalizedDirs,
     },
 };
 
+mod library;
+pub mod report;
+mod timings;
+
 pub fn register() {
     turbopack::register();
     include!(concat!(env!("OUT_DIR"), "/register.rs"));
@@ -58,6 +78,10 @@ pub struct TurbopackBuildBuilder {
     show_all: bool,
     log_detail: bool,
     minify_type: MinifyType,
+    library: bool,
+    esm: bool,
+    read_ahead: bool,
+    strict_csp: bool,
 }
 
 impl TurbopackBuildBuilder {
@@ -76,6 +100,10 @@ impl TurbopackBuildBuilder {
             show_all: false,
             log_detail: false,
             minify_type: MinifyType::Minify,
+            library: false,
+            esm: false,
+            read_ahead: false,
+            strict_csp: false,
         }
     }
 
@@ -109,38 +137,46 @@ impl TurbopackBuildBuilder {
         self
     }
 
+    /// Builds a library instead of an application: the dependencies of the
+    /// package are not bundled, and the entries are emitted as CommonJS and ES
+    /// modules with their TypeScript declarations and a suggested `exports`
+    /// field for the package.json.
+    pub fn library(mut self, library: bool) -> Self {
+        self.library = library;
+        self
+    }
+
+    /// Emits the entries as ES modules for the browser, which import the
+    /// chunks they depend on and export the bindings of the entry module.
+    pub fn esm(mut self, esm: bool) -> Self {
+        self.esm = esm;
+        self
+    }
+
+    /// Reads `package.json` and `index.*` files of the project ahead, see
+    /// [turbo_tasks_fs::DiskFileSystem::enable_read_ahead].
+    pub fn read_ahead(mut self, read_ahead: bool) -> Self {
+        self.read_ahead = read_ahead;
+        self
+    }
+
+    /// Sets [turbopack_core::environment::BrowserEnvironment::strict_csp] of
+    /// the client environment.
+    pub fn strict_csp(mut self, strict_csp: bool) -> Self {
+        self.strict_csp = strict_csp;
+        self
+    }
+
     pub async fn build(self) -> Result<()> {
         let task = self.turbo_tasks.spawn_once_task::<(), _>(async move {
-            let build_result = build_internal(
-                self.project_dir.clone(),
-                self.root_dir,
-                EntryRequests(
-                    self.entry_requests
-                        .iter()
-                        .cloned()
-                        .map(EntryRequest::resolved_cell)
-                        .collect(),
-                )
-                .cell(),
-                self.browserslist_query,
-                self.minify_type,
-            );
+            let build_result = build_internal(self.build_output());
 
             // Await the result to propagate any errors.
             build_result.await?;
 
-            let issue_reporter: Vc<Box<dyn IssueReporter>> =
-                Vc::upcast(ConsoleUi::new(TransientInstance::new(LogOptions {
-                    project_dir: PathBuf::from(self.project_dir),
-                    current_dir: current_dir().unwrap(),
-                    show_all: self.show_all,
-                    log_detail: self.log_detail,
-                    log_level: self.log_level,
-                })));
-
             handle_issues(
                 build_result,
-                issue_reporter,
+                self.issue_reporter(),
                 IssueSeverity::Error.into(),
                 None,
                 None,
@@ -156,27 +192,161 @@ impl TurbopackBuildBuilder {
 
         Ok(())
     }
+
+    /// Builds the entries like [TurbopackBuildBuilder::build] and reports the
+    /// chunks and modules of the build.
+    pub async fn analyze(self) -> Result<ReadRef<AnalyzeReport>> {
+        let turbo_tasks = self.turbo_tasks.clone();
+        turbo_tasks
+            .run_once(async move {
+                let build_output = self.build_output();
+                let build_result = build_internal(build_output);
+                build_result.await?;
+
+                handle_issues(
+                    build_result,
+                    self.issue_reporter(),
+                    IssueSeverity::Error.into(),
+                    None,
+                    None,
+                )
+                .await?;
+
+                analyze_build_output(build_output).await
+            })
+            .await
+    }
+
+    /// Builds the entries like [TurbopackBuildBuilder::build], but reports the
+    /// issues, the emitted assets and the timings of the build instead of
+    /// printing the issues.
+    ///
+    /// The timings are taken from the spans of the build, which `timings` only
+    /// records while its layer is installed.
+    pub async fn build_report(self, timings: BuildTimings) -> Result<BuildReport> {
+        let turbo_tasks = self.turbo_tasks.clone();
+        turbo_tasks
+            .run_once(async move {
+                let start = Instant::now();
+
+                let build_output = self.build_output();
+                let build_result = build_internal(build_output);
+                build_result.strongly_consistent().await?;
+
+                let issues = build_result
+                    .peek_issues_with_path()
+                    .await?
+                    .get_plain_issues()
+                    .await?;
+                let success = issues
+                    .iter()
+                    .all(|issue| issue.severity > IssueSeverity::Error);
+
+                Ok(BuildReport {
+                    schema_version: BUILD_REPORT_SCHEMA_VERSION,
+                    success,
+                    issues: issue_reports(&issues, self.log_level),
+                    assets: asset_reports(*build_output.await?.output_assets).await?,
+                    timings: TimingsReport {
+                        entries: self
+                            .entry_requests
+                            .iter()
+                            .map(|entry_request| {
+                                let entry = entry_request.name();
+                                EntryTimingReport {
+                                    duration_ms: timings.get(ENTRY_SPAN, Some(&entry)).as_millis()
+                                        as u64,
+                                    entry,
+                                }
+                            })
+                            .collect(),
+                        emit_ms: timings.get(EMIT_SPAN, None).as_millis() as u64,
+                        total_ms: start.elapsed().as_millis() as u64,
+                    },
+                    features: FeaturesReport {
+                        minify: matches!(self.minify_type, MinifyType::Minify),
+                        library: self.library,
+                        esm: self.esm,
+                        browserslist_query: self.browserslist_query.clone(),
+                        cargo_features: FeaturesReport::cargo_features(),
+                    },
+                })
+            })
+            .await
+    }
+
+    fn build_output(&self) -> Vc<BuildOutput> {
+        build_output(
+            self.project_dir.clone(),
+            self.root_dir.clone(),
+            EntryRequests(
+                self.entry_requests
+                    .iter()
+                    .cloned()
+                    .map(EntryRequest::resolved_cell)
+                    .collect(),
+            )
+            .cell(),
+            self.browserslist_query.clone(),
+            self.minify_type,
+            self.library,
+            self.esm,
+            self.read_ahead,
+            self.strict_csp,
+        )
+    }
+
+    fn issue_reporter(&self) -> Vc<Box<dyn IssueReporter>> {
+        Vc::upcast(ConsoleUi::new(TransientInstance::new(LogOptions {
+            project_dir: PathBuf::from(&*self.project_dir),
+            current_dir: current_dir().unwrap(),
+            show_all: self.show_all,
+            log_detail: self.log_detail,
+            log_level: self.log_level,
+        })))
+    }
+}
+
+/// The output of a build.
+#[turbo_tasks::value(shared)]
+pub(crate) struct BuildOutput {
+    /// The chunks of the entries and the assets they reference.
+    pub output_assets: ResolvedVc<OutputAssets>,
+    /// The packages that are included in more than one version in the chunk
+    /// group of an entry.
+    pub duplicate_packages: Vec<DuplicatePackage>,
+}
+
+#[turbo_tasks::function]
+async fn build_internal(build_output: Vc<BuildOutput>) -> Result<Vc<()>> {
+    let output_assets = build_output.await?.output_assets.await?;
+    output_assets
+        .iter()
+        .map(|c| c.content().write(c.ident().path()))
+        .try_join()
+        .instrument(tracing::info_span!(EMIT_SPAN))
+        .await?;
+
+    Ok(Default::default())
 }
 
+/// Returns all output assets of the build, i.e. the chunks of the entries and
+/// the assets they reference, and reports packages that are included in more
+/// than one version.
 #[turbo_tasks::function]
-async fn build_internal(
+pub(crate) async fn build_output(
     project_dir: RcStr,
     root_dir: RcStr,
     entry_requests: Vc<EntryRequests>,
     browserslist_query: RcStr,
     minify_type: MinifyType,
-) -> Result<Vc<()>> {
-    let env = Environment::new(Value::new(ExecutionEnvironment::Browser(
-        BrowserEnvironment {
-            dom: true,
-            web_worker: false,
-            service_worker: false,
-            browserslist_query: browserslist_query.clone(),
-        }
-        .into(),
-    )));
+    library: bool,
+    esm: bool,
+    read_ahead: bool,
+    strict_csp: bool,
+) -> Result<Vc<BuildOutput>> {
     let output_fs = output_fs(project_dir.clone());
-    let project_fs = project_fs(root_dir.clone());
+    let project_fs = project_fs(root_dir.clone(), read_ahead);
     let project_relative = project_dir.strip_prefix(&*root_dir).unwrap();
     let project_relative: RcStr = project_relative
         .strip_prefix(MAIN_SEPARATOR)
@@ -187,7 +357,14 @@ async fn build_internal(
     let build_output_root = output_fs.root().join("dist".into());
 
     let node_env = NodeEnv::Production.cell();
-
+    let compile_time_info =
+        get_client_compile_time_info(project_path, browserslist_query, node_env, strict_csp);
+    let env = compile_time_info.environment();
+
+    let runtime_type = match *node_env.await? {
+        NodeEnv::Development => RuntimeType::Development,
+        NodeEnv::Production => RuntimeType::Production,
+    };
     let chunking_context = Vc::upcast(
         NodeJsChunkingContext::builder(
             project_path,
@@ -196,27 +373,42 @@ async fn build_internal(
             build_output_root,
             build_output_root,
             env,
-            match *node_env.await? {
-                NodeEnv::Development => RuntimeType::Development,
-                NodeEnv::Production => RuntimeType::Production,
-            },
+            runtime_type,
         )
         .minify_type(minify_type)
         .build(),
     );
+    // ES module entries are loaded by the browser, so they are chunked for it.
+    let esm_chunking_context = esm.then(|| {
+        BrowserChunkingContext::builder(
+            project_path,
+            build_output_root,
+            build_output_root,
+            build_output_root,
+            build_output_root,
+            env,
+            runtime_type,
+        )
+        .minify_type(minify_type)
+        .output_format(OutputFormat::EsModule)
+        .build()
+    });
 
-    let compile_time_info = get_client_compile_time_info(browserslist_query, node_env);
     let execution_context =
         ExecutionContext::new(project_path, chunking_context, load_env(project_path));
-    let asset_context =
-        get_client_asset_context(project_path, execution_context, compile_time_info, node_env);
+    let asset_context = if library {
+        get_library_asset_context(project_path, execution_context, compile_time_info, node_env)
+    } else {
+        get_client_asset_context(project_path, execution_context, compile_time_info, node_env)
+    };
 
     let entry_requests = (*entry_requests
         .await?
         .iter()
         .cloned()
         .map(|r| async move {
-            Ok(match &*r.await? {
+            let r = r.await?;
+            let request = match &*r {
                 EntryRequest::Relative(p) => Request::relative(
                     Value::new(p.clone().into()),
                     Default::default(),
@@ -229,7 +421,8 @@ async fn build_internal(
                     Default::default(),
                     Default::default(),
                 ),
-            })
+            };
+            Ok((r.name(), request))
         })
         .try_join()
         .await?)
@@ -237,14 +430,18 @@ async fn build_internal(
 
     let origin = PlainResolveOrigin::new(asset_context, output_fs.root().join("_".into()));
     let project_dir = &project_dir;
+    let entry_names = entry_requests
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
     let entries = entry_requests
         .into_iter()
-        .map(|request_vc| async move {
+        .map(|(name, request_vc)| async move {
             let ty = Value::new(ReferenceType::Entry(EntryReferenceSubType::Undefined));
             let request = request_vc.await?;
-            origin
-                .resolve_asset(request_vc, origin.resolve_options(ty.clone()), ty)
-                .first_module()
+            let resolved = origin.resolve_asset(request_vc, origin.resolve_options(ty.clone()), ty);
+            async move { resolved.first_module().await }
+                .instrument(tracing::info_span!(ENTRY_SPAN, name = &*name))
                 .await?
                 .with_context(|| {
                     format!(
@@ -257,13 +454,34 @@ async fn build_internal(
         .try_join()
         .await?;
 
-    let entry_chunk_groups = entries
-        .into_iter()
-        .map(|entry_module| async move {
-            Ok(
-                if let Some(ecmascript) =
-                    ResolvedVc::try_sidecast::<Box<dyn EvaluatableAsset>>(entry_module).await?
+    // Each entry has its own chunk group
+    let mut duplicate_packages = Vec::new();
+    for &entry_module in &entries {
+        duplicate_packages.extend(
+            find_duplicate_packages(Vc::cell(vec![entry_module].into()))
+                .await?
+                .iter()
+                .cloned(),
+        );
+    }
+
+    // The chunk group of each entry is measured by the span of the entry
+    let entry_assets = entries
+        .iter()
+        .zip(&entry_names)
+        .map(|(&entry_module, name)| {
+            async move {
+                let evaluatable =
+                    ResolvedVc::try_sidecast::<Box<dyn EvaluatableAsset>>(entry_module).await?;
+                let chunk_group = if let (Some(ecmascript), Some(esm_chunking_context)) =
+                    (evaluatable, esm_chunking_context)
                 {
+                    esm_chunking_context.evaluated_chunk_group_assets(
+                        ecmascript.ident(),
+                        EvaluatableAssets::one(*ResolvedVc::upcast(ecmascript)),
+                        Value::new(AvailabilityInfo::Root),
+                    )
+                } else if let Some(ecmascript) = evaluatable {
                     Vc::cell(vec![
                         Vc::try_resolve_downcast_type::<NodeJsChunkingContext>(chunking_context)
                             .await?
@@ -280,7 +498,7 @@ async fn build_internal(
                                             .unwrap()
                                             .into(),
                                     )
-                                    .with_extension("entry.js".into()),
+                                    .with_extension(if library { "js" } else { "entry.js" }.into()),
                                 *ResolvedVc::upcast(ecmascript),
                                 EvaluatableAssets::one(*ResolvedVc::upcast(ecmascript)),
                                 OutputAssets::empty(),
@@ -299,27 +517,86 @@ async fn build_internal(
                         "Entry module is not chunkable, so it can't be used to bootstrap the \
                          application"
                     )
-                },
-            )
+                };
+                all_assets_from_entries(chunk_group).await
+            }
+            .instrument(tracing::info_span!(ENTRY_SPAN, name = &**name))
         })
         .try_join()
         .await?;
 
-    let mut chunks: HashSet<ResolvedVc<Box<dyn OutputAsset>>> = HashSet::new();
-    for chunk_group in entry_chunk_groups {
-        chunks.extend(&*all_assets_from_entries(chunk_group).await?);
+    let mut chunks: FxIndexSet<ResolvedVc<Box<dyn OutputAsset>>> = FxIndexSet::default();
+    for assets in entry_assets {
+        chunks.extend(&*assets);
+    }
+    let browser_import_map = get_browser_import_map(project_path);
+    if !*browser_import_map.is_empty().await? {
+        browser_import_map
+            .validate(Vc::cell(entries.clone().into()))
+            .await?;
+        chunks.insert(
+            browser_import_map
+                .output_asset(build_output_root.join("importmap.json".into()))
+                .to_resolved()
+                .await?,
+        );
+    }
+    if library {
+        chunks.extend(&*library_output_assets(build_output_root, Vc::cell(entries.into())).await?);
     }
 
-    chunks
-        .iter()
-        .map(|c| c.content().write(c.ident().path()))
-        .try_join()
-        .await?;
+    Ok(BuildOutput {
+        output_assets: ResolvedVc::cell(chunks.into_iter().collect()),
+        duplicate_packages,
+    }
+    .cell())
+}
 
-    Ok(Default::default())
+/// Builds the entries of `args`. With `--json`, the timings of the report are
+/// taken from `timings`, whose layer needs to be installed.
+pub async fn build(args: &BuildArguments, timings: BuildTimings) -> Result<()> {
+    let builder = create_build_builder(args)?;
+    if args.json {
+        let report = builder.build_report(timings).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if !report.success {
+            bail!("Fatal issue(s) occurred");
+        }
+        return Ok(());
+    }
+    let statistics = args.largest_outputs.map(|count| {
+        let statistics = builder.turbo_tasks.backend().task_statistics().enable();
+        (count, statistics.clone())
+    });
+
+    builder.build().await?;
+
+    if let Some((count, statistics)) = statistics {
+        print_largest_outputs(&statistics, count);
+    }
+    Ok(())
+}
+
+/// Prints the functions that wrote the largest values into cells, which
+/// drive the memory usage and the size of the persistent cache.
+fn print_largest_outputs(statistics: &TaskStatistics, count: usize) {
+    println!(
+        "{:>12} {:>8} {:>14}  function",
+        "output", "cells", "unserializable"
+    );
+    for output in statistics.largest_outputs(count) {
+        println!(
+            "{:>12} {:>8} {:>14}  {}",
+            FormatBytes(output.output_bytes as usize).to_string(),
+            output.cells_written,
+            output.unserializable_cells,
+            output.function
+        );
+    }
 }
 
-pub async fn build(args: &BuildArguments) -> Result<()> {
+/// Creates a [TurbopackBuildBuilder] that builds the entries of the arguments.
+pub(crate) fn create_build_builder(args: &BuildArguments) -> Result<TurbopackBuildBuilder> {
     let NormalizedDirs {
         project_dir,
         root_dir,
@@ -330,6 +607,7 @@ pub async fn build(args: &BuildArguments) -> Result<()> {
             .memory_limit
             .map_or(usize::MAX, |l| l * 1024 * 1024),
     ));
+    tt.set_cell_interning(args.common.cell_interning);
 
     let mut builder = TurbopackBuildBuilder::new(tt, project_dir, root_dir)
         .log_detail(args.common.log_detail)
@@ -343,13 +621,15 @@ pub async fn build(args: &BuildArguments) -> Result<()> {
         } else {
             MinifyType::Minify
         })
-        .show_all(args.common.show_all);
+        .show_all(args.common.show_all)
+        .read_ahead(args.common.read_ahead)
+        .strict_csp(args.common.strict_csp)
+        .library(args.library)
+        .esm(args.esm);
 
     for entry in normalize_entries(&args.common.entries) {
         builder = builder.entry_request(EntryRequest::Relative(entry));
     }
 
-    builder.build().await?;
-
-    Ok(())
+    Ok(builder)
 }
@@ -0,0 +1,357 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{trace::TraceRawVcs, RcStr, ReadRef, TryJoinIterExt, Vc};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    issue::{IssueSeverity, PlainIssue, StyledString},
+    output::OutputAssets,
+    source_pos::SourcePos,
+};
+
+/// The version of the [BuildReport] schema. It's increased when fields are
+/// removed or change their meaning, but not when fields are added.
+pub const BUILD_REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// The result of a build, printed with `--json` for CI annotations and other
+/// tools.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildReport {
+    pub schema_version: u32,
+    /// Whether the build has no issues that fail it.
+    pub success: bool,
+    pub issues: Vec<IssueReport>,
+    pub assets: Vec<AssetReport>,
+    pub timings: TimingsReport,
+    pub features: FeaturesReport,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueReport {
+    pub severity: RcStr,
+    pub stage: RcStr,
+    /// The file the issue is about.
+    pub file_path: RcStr,
+    /// The source the issue points to, which can be another file than
+    /// `file_path`, e.g. the importing module of a module that can't be
+    /// resolved.
+    pub source: Option<IssueSourceReport>,
+    pub title: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueSourceReport {
+    pub file_path: RcStr,
+    /// The start and end of the span. The lines and columns are 0-indexed and
+    /// the end is exclusive.
+    pub range: Option<(SourcePos, SourcePos)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetReport {
+    /// The path of the asset, relative to the project directory.
+    pub path: RcStr,
+    /// The size of the asset in bytes.
+    pub size: u64,
+    /// The xxh3 hash of the content as hex.
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingsReport {
+    /// The time to resolve each entry and to compute its chunk group, in the
+    /// order of the entries. The entries are compiled in parallel, so modules
+    /// that are shared between entries count for each of them.
+    pub entries: Vec<EntryTimingReport>,
+    /// The time to generate the code of the assets and to write them.
+    pub emit_ms: u64,
+    pub total_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryTimingReport {
+    pub entry: RcStr,
+    pub duration_ms: u64,
+}
+
+/// The options and compiled in features the build ran with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct FeaturesReport {
+    pub minify: bool,
+    pub library: bool,
+    /// Whether the entries are emitted as ES modules for the browser.
+    pub esm: bool,
+    pub browserslist_query: RcStr,
+    /// The cargo features of turbopack-cli, e.g. `custom_allocator`.
+    pub cargo_features: Vec<RcStr>,
+}
+
+impl FeaturesReport {
+    pub fn cargo_features() -> Vec<RcStr> {
+        [
+            ("custom_allocator", cfg!(feature = "custom_allocator")),
+            ("native-tls", cfg!(feature = "native-tls")),
+            ("rustls-tls", cfg!(feature = "rustls-tls")),
+            ("serializable", cfg!(feature = "serializable")),
+            ("tokio_console", cfg!(feature = "tokio_console")),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(feature, _)| feature.into())
+        .collect()
+    }
+}
+
+/// Reports the issues with at least the severity `log_level`.
+pub(crate) fn issue_reports(
+    issues: &[ReadRef<PlainIssue>],
+    log_level: IssueSeverity,
+) -> Vec<IssueReport> {
+    issues
+        .iter()
+        .filter(|issue| issue.severity <= log_level)
+        .map(|issue| issue_report(issue))
+        .collect()
+}
+
+fn issue_report(issue: &PlainIssue) -> IssueReport {
+    IssueReport {
+        severity: issue.severity.as_str().into(),
+        stage: issue.stage.to_string().into(),
+        file_path: issue.file_path.clone(),
+        source: issue.source.as_ref().map(|source| IssueSourceReport {
+            file_path: (*source.asset.ident).clone(),
+            range: source.range,
+        }),
+        title: styled_string_to_text(&issue.title),
+        description: issue.description.as_ref().map(styled_string_to_text),
+    }
+}
+
+fn styled_string_to_text(styled_string: &StyledString) -> String {
+    match styled_string {
+        StyledString::Line(parts) => parts.iter().map(styled_string_to_text).collect(),
+        StyledString::Stack(parts) => parts
+            .iter()
+            .map(styled_string_to_text)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        StyledString::Text(string) | StyledString::Code(string) | StyledString::Strong(string) => {
+            string.to_string()
+        }
+    }
+}
+
+pub(crate) async fn asset_reports(output_assets: Vc<OutputAssets>) -> Result<Vec<AssetReport>> {
+    output_assets
+        .await?
+        .iter()
+        .map(|asset| async move {
+            let content = asset.content();
+            let hash = match &*content.await? {
+                AssetContent::File(file) => *file.hash().await?,
+                AssetContent::Redirect { .. } => 0,
+            };
+            Ok(AssetReport {
+                path: asset.ident().path().await?.path.clone(),
+                size: content.len().await?.unwrap_or_default(),
+                hash: format!("{hash:016x}"),
+            })
+        })
+        .try_join()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+
+    use serde_json::json;
+    use turbo_tasks::{RcStr, ReadRef, TurboTasks, Vc};
+    use turbo_tasks_fs::FileContent;
+    use turbo_tasks_memory::MemoryBackend;
+    use turbopack_core::{
+        issue::{
+            IssueSeverity, IssueStage, PlainIssue, PlainIssueProcessingPath, PlainIssueSource,
+            PlainSource, StyledString,
+        },
+        source_pos::SourcePos,
+    };
+
+    use super::{
+        issue_reports, styled_string_to_text, AssetReport, BuildReport, EntryTimingReport,
+        FeaturesReport, IssueReport, IssueSourceReport, TimingsReport,
+    };
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    #[test]
+    fn converts_styled_strings_to_text() {
+        let styled = StyledString::Stack(vec![
+            StyledString::Line(vec![
+                StyledString::Text("Module not found: Can't resolve ".into()),
+                StyledString::Code("'./missing'".into()),
+            ]),
+            StyledString::Strong("Did you mean './existing'?".into()),
+        ]);
+        assert_eq!(
+            styled_string_to_text(&styled),
+            "Module not found: Can't resolve './missing'\nDid you mean './existing'?"
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_issues_of_the_log_level() {
+        register();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async {
+            let processing_path: ReadRef<PlainIssueProcessingPath> =
+                Vc::<PlainIssueProcessingPath>::cell(None).await?;
+            let issue = |severity, title: &str| {
+                ReadRef::new_owned(PlainIssue {
+                    severity,
+                    file_path: "[project]/src/index.js".into(),
+                    stage: IssueStage::Resolve,
+                    title: StyledString::Text(title.into()),
+                    description: Some(StyledString::Text("description".into())),
+                    detail: None,
+                    documentation_link: RcStr::default(),
+                    source: Some(ReadRef::new_owned(PlainIssueSource {
+                        asset: ReadRef::new_owned(PlainSource {
+                            ident: ReadRef::new_owned(RcStr::from("[project]/src/app.js")),
+                            content: ReadRef::new_owned(FileContent::NotFound),
+                        }),
+                        range: Some((
+                            SourcePos { line: 1, column: 2 },
+                            SourcePos { line: 1, column: 8 },
+                        )),
+                    })),
+                    sub_issues: vec![],
+                    processing_path: processing_path.clone(),
+                })
+            };
+            let issues = [
+                issue(IssueSeverity::Error, "error"),
+                issue(IssueSeverity::Warning, "warning"),
+                issue(IssueSeverity::Hint, "hint"),
+            ];
+
+            let reports = issue_reports(&issues, IssueSeverity::Warning);
+            assert_eq!(
+                reports
+                    .iter()
+                    .map(|report| report.title.as_str())
+                    .collect::<Vec<_>>(),
+                ["error", "warning"]
+            );
+            assert_eq!(
+                reports[0],
+                IssueReport {
+                    severity: "error".into(),
+                    stage: IssueStage::Resolve.to_string().into(),
+                    file_path: "[project]/src/index.js".into(),
+                    source: Some(IssueSourceReport {
+                        file_path: "[project]/src/app.js".into(),
+                        range: Some((
+                            SourcePos { line: 1, column: 2 },
+                            SourcePos { line: 1, column: 8 }
+                        )),
+                    }),
+                    title: "error".to_string(),
+                    description: Some("description".to_string()),
+                }
+            );
+            Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn serializes_the_schema() {
+        let report = BuildReport {
+            schema_version: 1,
+            success: false,
+            issues: vec![IssueReport {
+                severity: "error".into(),
+                stage: "resolve".into(),
+                file_path: "[project]/src/index.js".into(),
+                source: Some(IssueSourceReport {
+                    file_path: "[project]/src/index.js".into(),
+                    range: Some((
+                        SourcePos { line: 0, column: 0 },
+                        SourcePos { line: 0, column: 4 },
+                    )),
+                }),
+                title: "Module not found".to_string(),
+                description: None,
+            }],
+            assets: vec![AssetReport {
+                path: "dist/index.js".into(),
+                size: 42,
+                hash: "00000000000000ff".to_string(),
+            }],
+            timings: TimingsReport {
+                entries: vec![EntryTimingReport {
+                    entry: "./src/index.js".into(),
+                    duration_ms: 10,
+                }],
+                emit_ms: 5,
+                total_ms: 20,
+            },
+            features: FeaturesReport {
+                minify: true,
+                library: false,
+                esm: true,
+                browserslist_query: "defaults".into(),
+                cargo_features: vec![],
+            },
+        };
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "schemaVersion": 1,
+                "success": false,
+                "issues": [{
+                    "severity": "error",
+                    "stage": "resolve",
+                    "filePath": "[project]/src/index.js",
+                    "source": {
+                        "filePath": "[project]/src/index.js",
+                        "range": [{ "line": 0, "column": 0 }, { "line": 0, "column": 4 }],
+                    },
+                    "title": "Module not found",
+                    "description": null,
+                }],
+                "assets": [{ "path": "dist/index.js", "size": 42, "hash": "00000000000000ff" }],
+                "timings": {
+                    "entries": [{ "entry": "./src/index.js", "durationMs": 10 }],
+                    "emitMs": 5,
+                    "totalMs": 20,
+                },
+                "features": {
+                    "minify": true,
+                    "library": false,
+                    "esm": true,
+                    "browserslistQuery": "defaults",
+                    "cargoFeatures": [],
+                },
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<BuildReport>(value).unwrap(),
+            report
+        );
+    }
+}
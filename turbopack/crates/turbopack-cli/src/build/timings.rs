@@ -0,0 +1,143 @@
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tracing::{
+    field::{Field, Visit},
+    span, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+use turbo_tasks::{FxIndexMap, RcStr};
+
+/// The span of compiling an entry of the build, with the entry as its `name`.
+pub(crate) const ENTRY_SPAN: &str = "build entry";
+/// The span of writing the output assets of the build.
+pub(crate) const EMIT_SPAN: &str = "emit build output";
+
+/// The total durations of the spans of a build, by span and `name` field, for
+/// the [super::report::TimingsReport].
+///
+/// The durations are only recorded while [BuildTimings::layer] is installed
+/// as a tracing layer.
+#[derive(Clone, Default)]
+pub struct BuildTimings {
+    durations: Arc<Mutex<FxIndexMap<(&'static str, Option<RcStr>), Duration>>>,
+}
+
+impl BuildTimings {
+    pub fn layer<S: Subscriber + for<'a> LookupSpan<'a>>(&self) -> BuildTimingsLayer<S> {
+        BuildTimingsLayer {
+            timings: self.clone(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// The total duration of the spans `span` with the `name`.
+    pub(crate) fn get(&self, span: &'static str, name: Option<&str>) -> Duration {
+        self.durations
+            .lock()
+            .unwrap()
+            .get(&(span, name.map(RcStr::from)))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn add(&self, span: &'static str, name: Option<RcStr>, duration: Duration) {
+        *self
+            .durations
+            .lock()
+            .unwrap()
+            .entry((span, name))
+            .or_default() += duration;
+    }
+}
+
+/// The time a span was created, and its `name` field.
+struct SpanStart {
+    start: Instant,
+    name: Option<RcStr>,
+}
+
+/// A tracing layer that records the spans of a build into [BuildTimings].
+/// Spans are measured from their creation to their close, so the time a span
+/// waits for other work counts too.
+pub struct BuildTimingsLayer<S> {
+    timings: BuildTimings,
+    _phantom: PhantomData<fn(S)>,
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for BuildTimingsLayer<S> {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if ![ENTRY_SPAN, EMIT_SPAN].contains(&attrs.metadata().name()) {
+            return;
+        }
+        let mut visitor = NameVisitor(None);
+        attrs.record(&mut visitor);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanStart {
+                start: Instant::now(),
+                name: visitor.0,
+            });
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(SpanStart { start, name }) = span.extensions_mut().remove::<SpanStart>() else {
+            return;
+        };
+        self.timings
+            .add(span.metadata().name(), name, start.elapsed());
+    }
+}
+
+struct NameVisitor(Option<RcStr>);
+
+impl Visit for NameVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "name" {
+            self.0 = Some(value.into());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "name" {
+            self.0 = Some(format!("{value:?}").into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::{BuildTimings, EMIT_SPAN, ENTRY_SPAN};
+
+    #[test]
+    fn records_the_build_spans() {
+        let timings = BuildTimings::default();
+        let subscriber = Registry::default().with(timings.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..2 {
+                let _span = tracing::info_span!(ENTRY_SPAN, name = "./a.js").entered();
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+            let _span = tracing::info_span!(EMIT_SPAN).entered();
+            let _other = tracing::info_span!("other", name = "./b.js").entered();
+        });
+
+        // The durations of the spans of an entry are summed up.
+        assert!(timings.get(ENTRY_SPAN, Some("./a.js")).as_millis() >= 10);
+        assert!(timings.get(ENTRY_SPAN, Some("./b.js")).is_zero());
+        assert!(timings
+            .durations
+            .lock()
+            .unwrap()
+            .contains_key(&(EMIT_SPAN, None)));
+        assert_eq!(timings.durations.lock().unwrap().len(), 2);
+    }
+}
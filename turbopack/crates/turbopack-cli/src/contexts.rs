@@ -2,6 +2,7 @@ use std::fmt;
 
 use anyhow::Result;
 use turbo_tasks::{RcStr, ResolvedVc, Value, Vc};
+use turbo_tasks_fetch::RemoteUrlResolvePlugin;
 use turbo_tasks_fs::{FileSystem, FileSystemPath};
 use turbopack::{
     ecmascript::{EcmascriptInputTransform, TreeShakingMode},
@@ -17,9 +18,14 @@ use turbopack_core::{
     compile_time_info::{CompileTimeDefines, CompileTimeInfo},
     condition::ContextCondition,
     context::AssetContext,
-    environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
-    resolve::options::{ImportMap, ImportMapping},
+    environment::{BrowserEnvironment, Environment, EnvironmentCapabilities, ExecutionEnvironment},
+    package_json::read_package_dependencies,
+    resolve::{
+        options::{ImportMap, ImportMapping},
+        ExternalType,
+    },
 };
+use turbopack_ecmascript::browser_import_map::BrowserImportMap;
 use turbopack_ecmascript_plugins::transform::{
     emotion::{EmotionTransformConfig, EmotionTransformer},
     styled_components::{StyledComponentsTransformConfig, StyledComponentsTransformer},
@@ -49,6 +55,13 @@ async fn foreign_code_context_condition() -> Result<ContextCondition> {
     Ok(ContextCondition::InDirectory("node_modules".to_string()))
 }
 
+/// The import map of the page, from the `importmap.json` of the project.
+/// Imports of its specifiers are left to the browser.
+#[turbo_tasks::function]
+pub fn get_browser_import_map(project_path: Vc<FileSystemPath>) -> Vc<BrowserImportMap> {
+    BrowserImportMap::read(project_path.join("importmap.json".into()))
+}
+
 #[turbo_tasks::function]
 pub async fn get_client_import_map(
     project_path: ResolvedVc<FileSystemPath>,
@@ -74,6 +87,10 @@ pub async fn get_client_import_map(
         .resolved_cell(),
     );
 
+    get_browser_import_map(*project_path)
+        .await?
+        .insert_externals(&mut import_map);
+
     Ok(import_map.cell())
 }
 
@@ -82,12 +99,20 @@ pub async fn get_client_resolve_options_context(
     project_path: Vc<FileSystemPath>,
 ) -> Result<Vc<ResolveOptionsContext>> {
     let next_client_import_map = get_client_import_map(project_path).to_resolved().await?;
+    // Remote URLs of the origins that are pinned in the lockfile are bundled
+    let remote_url_resolve_plugin = RemoteUrlResolvePlugin::pinned(
+        project_path.join("turbopack.lock.json".into()),
+        project_path.join(".turbopack/remote".into()),
+    )
+    .to_resolved()
+    .await?;
     let module_options_context = ResolveOptionsContext {
         enable_node_modules: Some(project_path.root().to_resolved().await?),
         custom_conditions: vec!["development".into()],
         import_map: Some(next_client_import_map),
         browser: true,
         module: true,
+        before_resolve_plugins: vec![ResolvedVc::upcast(remote_url_resolve_plugin)],
         ..Default::default()
     };
     Ok(ResolveOptionsContext {
@@ -102,6 +127,40 @@ pub async fn get_client_resolve_options_context(
     .cell())
 }
 
+/// The import map of a library build. The dependencies of the package are
+/// externals, so they are installed along with the library instead of being
+/// bundled into it.
+#[turbo_tasks::function]
+pub async fn get_library_import_map(project_path: Vc<FileSystemPath>) -> Result<Vc<ImportMap>> {
+    let mut import_map = get_client_import_map(project_path).await?.clone_value();
+    for name in read_package_dependencies(project_path.join("package.json".into()))
+        .await?
+        .iter()
+    {
+        import_map.insert_external_package(name.clone(), ExternalType::CommonJs);
+    }
+    Ok(import_map.cell())
+}
+
+#[turbo_tasks::function]
+pub async fn get_library_resolve_options_context(
+    project_path: Vc<FileSystemPath>,
+) -> Result<Vc<ResolveOptionsContext>> {
+    let import_map = Some(get_library_import_map(project_path).to_resolved().await?);
+    let mut resolve_options_context = get_client_resolve_options_context(project_path)
+        .await?
+        .clone_value();
+    resolve_options_context.import_map = import_map;
+    for (_, rule) in &mut resolve_options_context.rules {
+        *rule = ResolveOptionsContext {
+            import_map,
+            ..rule.await?.clone_value()
+        }
+        .resolved_cell();
+    }
+    Ok(resolve_options_context.cell())
+}
+
 #[turbo_tasks::function]
 async fn get_client_module_options_context(
     project_path: Vc<FileSystemPath>,
@@ -205,6 +264,31 @@ pub fn get_client_asset_context(
     asset_context
 }
 
+/// The asset context of a library build, see [get_library_import_map].
+#[turbo_tasks::function]
+pub fn get_library_asset_context(
+    project_path: Vc<FileSystemPath>,
+    execution_context: Vc<ExecutionContext>,
+    compile_time_info: Vc<CompileTimeInfo>,
+    node_env: Vc<NodeEnv>,
+) -> Vc<Box<dyn AssetContext>> {
+    let resolve_options_context = get_library_resolve_options_context(project_path);
+    let module_options_context = get_client_module_options_context(
+        project_path,
+        execution_context,
+        compile_time_info.environment(),
+        node_env,
+    );
+
+    Vc::upcast(ModuleAssetContext::new(
+        Default::default(),
+        compile_time_info,
+        module_options_context,
+        resolve_options_context,
+        Vc::cell("library".into()),
+    ))
+}
+
 fn client_defines(node_env: &NodeEnv) -> Vc<CompileTimeDefines> {
     compile_time_defines!(
         process.turbopack = true,
@@ -216,20 +300,32 @@ fn client_defines(node_env: &NodeEnv) -> Vc<CompileTimeDefines> {
 
 #[turbo_tasks::function]
 pub async fn get_client_compile_time_info(
+    project_path: Vc<FileSystemPath>,
     browserslist_query: RcStr,
     node_env: Vc<NodeEnv>,
+    strict_csp: bool,
 ) -> Result<Vc<CompileTimeInfo>> {
-    Ok(
-        CompileTimeInfo::builder(Environment::new(Value::new(ExecutionEnvironment::Browser(
-            BrowserEnvironment {
-                dom: true,
-                web_worker: false,
-                service_worker: false,
-                browserslist_query,
+    let mut environment = Environment::new(Value::new(ExecutionEnvironment::Browser(
+        BrowserEnvironment {
+            dom: true,
+            web_worker: false,
+            service_worker: false,
+            browserslist_query,
+            strict_csp,
+        }
+        .into(),
+    )));
+    // The specifiers of the import map are imported natively
+    if !*get_browser_import_map(project_path).is_empty().await? {
+        environment = environment.with_capabilities(
+            EnvironmentCapabilities {
+                esm_externals: true,
+                ..*environment.capabilities().await?
             }
-            .into(),
-        ))))
+            .resolved_cell(),
+        );
+    }
+    Ok(CompileTimeInfo::builder(environment)
         .defines(client_defines(&*node_env.await?))
-        .cell(),
-    )
+        .cell())
 }
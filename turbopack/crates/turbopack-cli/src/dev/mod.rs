@@ -3,7 +3,7 @@ use std::{
     env::current_dir,
     future::{join, Future},
     io::{stdout, Write},
-    net::{IpAddr, SocketAddr},
+    net::IpAddr,
     path::{PathBuf, MAIN_SEPARATOR},
     sync::Arc,
     time::{Duration, Instant},
@@ -15,6 +15,7 @@ use turbo_tasks::{
     util::{FormatBytes, FormatDuration},
     RcStr, TransientInstance, TurboTasks, UpdateInfo, Value, Vc,
 };
+use turbo_tasks_env::poll_process_env;
 use turbo_tasks_fs::FileSystem;
 use turbo_tasks_malloc::TurboMalloc;
 use turbo_tasks_memory::MemoryBackend;
@@ -26,12 +27,17 @@ use turbopack_core::{
     server_fs::ServerFileSystem,
 };
 use turbopack_dev_server::{
+    fault_injection::FaultInjection,
     introspect::IntrospectionSource,
+    port::activated_listener,
     source::{
-        combined::CombinedContentSource, router::PrefixedRouterContentSource,
-        static_assets::StaticAssetsContentSource, ContentSource,
+        combined::CombinedContentSource,
+        router::PrefixedRouterContentSource,
+        spa_fallback::{SpaFallbackContentSource, SpaFallbackOptions},
+        static_assets::StaticAssetsContentSource,
+        ContentSource,
     },
-    DevServer, DevServerBuilder,
+    DevServer, DevServerBuilder, DevServerProject,
 };
 use turbopack_ecmascript_runtime::RuntimeType;
 use turbopack_env::dotenv::load_env;
@@ -55,6 +61,7 @@ pub struct TurbopackDevServerBuilder {
     root_dir: RcStr,
     entry_requests: Vec<EntryRequest>,
     eager_compile: bool,
+    readable_output: bool,
     hostname: Option<IpAddr>,
     issue_reporter: Option<Box<dyn IssueReporterProvider>>,
     port: Option<u16>,
@@ -63,6 +70,20 @@ pub struct TurbopackDevServerBuilder {
     show_all: bool,
     log_detail: bool,
     allow_retry: bool,
+    fault_injection: Option<FaultInjection>,
+    forward_browser_console: bool,
+    read_ahead: bool,
+    strict_csp: bool,
+    host_projects: Vec<HostProject>,
+    spa_fallback: Option<RcStr>,
+    spa_fallback_excluded_prefixes: Vec<RcStr>,
+}
+
+/// A project that is served to the requests with the `Host` header `host`.
+struct HostProject {
+    host: RcStr,
+    project_dir: RcStr,
+    root_dir: RcStr,
 }
 
 impl TurbopackDevServerBuilder {
@@ -77,6 +98,7 @@ impl TurbopackDevServerBuilder {
             root_dir,
             entry_requests: vec![],
             eager_compile: false,
+            readable_output: false,
             hostname: None,
             issue_reporter: None,
             port: None,
@@ -87,6 +109,13 @@ impl TurbopackDevServerBuilder {
             show_all: false,
             log_detail: false,
             allow_retry: false,
+            fault_injection: None,
+            forward_browser_console: false,
+            read_ahead: false,
+            strict_csp: false,
+            host_projects: vec![],
+            spa_fallback: None,
+            spa_fallback_excluded_prefixes: vec![],
         }
     }
 
@@ -100,6 +129,11 @@ impl TurbopackDevServerBuilder {
         self
     }
 
+    pub fn readable_output(mut self, readable_output: bool) -> TurbopackDevServerBuilder {
+        self.readable_output = readable_output;
+        self
+    }
+
     pub fn hostname(mut self, hostname: IpAddr) -> TurbopackDevServerBuilder {
         self.hostname = Some(hostname);
         self
@@ -135,6 +169,63 @@ impl TurbopackDevServerBuilder {
         self
     }
 
+    pub fn fault_injection(mut self, fault_injection: FaultInjection) -> TurbopackDevServerBuilder {
+        self.fault_injection = Some(fault_injection);
+        self
+    }
+
+    /// Prints the errors and warnings of the browser console in the terminal.
+    pub fn forward_browser_console(
+        mut self,
+        forward_browser_console: bool,
+    ) -> TurbopackDevServerBuilder {
+        self.forward_browser_console = forward_browser_console;
+        self
+    }
+
+    /// Applies HMR updates with `<script>` tags instead of `eval`, for pages
+    /// with a strict Content Security Policy.
+    pub fn strict_csp(mut self, strict_csp: bool) -> TurbopackDevServerBuilder {
+        self.strict_csp = strict_csp;
+        self
+    }
+
+    /// Reads `package.json` and `index.*` files of the project ahead, see
+    /// [turbo_tasks_fs::DiskFileSystem::enable_read_ahead].
+    /// Serves another project to the requests with the `Host` header `host`,
+    /// e.g. `docs.localhost`, with the same entries as the main project.
+    pub fn host_project(
+        mut self,
+        host: RcStr,
+        project_dir: RcStr,
+        root_dir: RcStr,
+    ) -> TurbopackDevServerBuilder {
+        self.host_projects.push(HostProject {
+            host,
+            project_dir,
+            root_dir,
+        });
+        self
+    }
+
+    /// Serves the HTML document `index` to page requests that no asset
+    /// matches, except for requests of `excluded_prefixes`, see
+    /// [SpaFallbackContentSource].
+    pub fn spa_fallback(
+        mut self,
+        index: RcStr,
+        excluded_prefixes: Vec<RcStr>,
+    ) -> TurbopackDevServerBuilder {
+        self.spa_fallback = Some(index);
+        self.spa_fallback_excluded_prefixes = excluded_prefixes;
+        self
+    }
+
+    pub fn read_ahead(mut self, read_ahead: bool) -> TurbopackDevServerBuilder {
+        self.read_ahead = read_ahead;
+        self
+    }
+
     pub fn issue_reporter(
         mut self,
         issue_reporter: Box<dyn IssueReporterProvider>,
@@ -143,59 +234,47 @@ impl TurbopackDevServerBuilder {
         self
     }
 
-    /// Attempts to find an open port to bind.
-    fn find_port(&self, host: IpAddr, port: u16, max_attempts: u16) -> Result<DevServerBuilder> {
-        // max_attempts of 1 means we loop 0 times.
-        let max_attempts = max_attempts - 1;
-        let mut attempts = 0;
-        loop {
-            let current_port = port + attempts;
-            let addr = SocketAddr::new(host, current_port);
-            let listen_result = DevServer::listen(addr);
-
-            if let Err(e) = &listen_result {
-                if self.allow_retry && attempts < max_attempts {
-                    // Returned error from `listen` is not `std::io::Error` but `anyhow::Error`,
-                    // so we need to access its source to check if it is
-                    // `std::io::ErrorKind::AddrInUse`.
-                    let should_retry = e
-                        .source()
-                        .and_then(|e| {
-                            e.downcast_ref::<std::io::Error>()
-                                .map(|e| e.kind() == std::io::ErrorKind::AddrInUse)
-                        })
-                        .unwrap_or(false);
-
-                    if should_retry {
-                        println!(
-                            "{} - Port {} is in use, trying {} instead",
-                            "warn ".yellow(),
-                            current_port,
-                            current_port + 1
-                        );
-                        attempts += 1;
-                        continue;
-                    }
-                }
-            }
-
-            return listen_result;
+    /// Uses the listener passed by socket activation, or attempts to find an
+    /// open port to bind.
+    fn listen(&self, host: IpAddr, port: u16, max_attempts: u16) -> Result<DevServerBuilder> {
+        if let Some(listener) = activated_listener()? {
+            return DevServer::from_listener(listener);
+        }
+        let max_attempts = if self.allow_retry { max_attempts } else { 1 };
+        let server = DevServer::listen_negotiated(host, port, max_attempts)?;
+        if port != 0 && server.addr.port() != port {
+            println!(
+                "{} - Port {} is in use, using {} instead",
+                "warn ".yellow(),
+                port,
+                server.addr.port()
+            );
         }
+        Ok(server)
     }
 
     pub async fn build(self) -> Result<DevServer> {
         let port = self.port.context("port must be set")?;
         let host = self.hostname.context("hostname must be set")?;
 
-        let server = self.find_port(host, port, 10)?;
+        let mut server = self.listen(host, port, 10)?;
+        if let Some(fault_injection) = self.fault_injection {
+            server = server.fault_injection(fault_injection);
+        }
+        server = server.forward_browser_console(self.forward_browser_console);
 
         let turbo_tasks = self.turbo_tasks;
         let project_dir: RcStr = self.project_dir;
         let root_dir: RcStr = self.root_dir;
         let eager_compile = self.eager_compile;
+        let readable_output = self.readable_output;
+        let read_ahead = self.read_ahead;
+        let strict_csp = self.strict_csp;
         let show_all = self.show_all;
         let log_detail: bool = self.log_detail;
         let browserslist_query: RcStr = self.browserslist_query;
+        let spa_fallback = self.spa_fallback;
+        let spa_fallback_excluded_prefixes = self.spa_fallback_excluded_prefixes;
         let log_args = TransientInstance::new(LogOptions {
             current_dir: current_dir().unwrap(),
             project_dir: PathBuf::from(project_dir.clone()),
@@ -210,28 +289,58 @@ impl TurbopackDevServerBuilder {
             Box::new(move || Vc::upcast(ConsoleUi::new(log_args.clone())))
         });
 
-        let source = move || {
-            source(
-                root_dir.clone(),
-                project_dir.clone(),
-                entry_requests.clone(),
-                eager_compile,
-                browserslist_query.clone(),
+        let issue_reporter_arc: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync> =
+            Arc::new(move || issue_provider.get_issue_reporter());
+        let project = |name: RcStr, root_dir: RcStr, project_dir: RcStr| {
+            let entry_requests = entry_requests.clone();
+            let browserslist_query = browserslist_query.clone();
+            let spa_fallback = spa_fallback.clone();
+            let spa_fallback_excluded_prefixes = spa_fallback_excluded_prefixes.clone();
+            DevServerProject::new(
+                name,
+                move || {
+                    source(
+                        root_dir.clone(),
+                        project_dir.clone(),
+                        entry_requests.clone(),
+                        eager_compile,
+                        readable_output,
+                        read_ahead,
+                        browserslist_query.clone(),
+                        strict_csp,
+                        spa_fallback.clone(),
+                        spa_fallback_excluded_prefixes.clone(),
+                    )
+                },
+                issue_reporter_arc.clone(),
             )
         };
 
-        let issue_reporter_arc = Arc::new(move || issue_provider.get_issue_reporter());
-        Ok(server.serve(tasks, source, issue_reporter_arc))
+        let mut projects = vec![project("default".into(), root_dir, project_dir)];
+        for HostProject {
+            host,
+            project_dir,
+            root_dir,
+        } in self.host_projects
+        {
+            projects.push(project(host.clone(), root_dir, project_dir).host(host));
+        }
+        Ok(server.serve_projects(tasks, projects))
     }
 }
 
-#[turbo_tasks::function]
+#[turbo_tasks::function(transient)]
 async fn source(
     root_dir: RcStr,
     project_dir: RcStr,
     entry_requests: TransientInstance<Vec<EntryRequest>>,
     eager_compile: bool,
+    readable_output: bool,
+    read_ahead: bool,
     browserslist_query: RcStr,
+    strict_csp: bool,
+    spa_fallback: Option<RcStr>,
+    spa_fallback_excluded_prefixes: Vec<RcStr>,
 ) -> Vc<Box<dyn ContentSource>> {
     let project_relative = project_dir.strip_prefix(&*root_dir).unwrap();
     let project_relative: RcStr = project_relative
@@ -241,7 +350,7 @@ async fn source(
         .into();
 
     let output_fs = output_fs(project_dir);
-    let fs = project_fs(root_dir);
+    let fs = project_fs(root_dir, read_ahead);
     let project_path: Vc<turbo_tasks_fs::FileSystemPath> = fs.root().join(project_relative);
 
     let env = load_env(project_path);
@@ -288,8 +397,10 @@ async fn source(
         server_root,
         env,
         eager_compile,
+        readable_output,
         NodeEnv::Development.cell(),
         browserslist_query,
+        strict_csp,
     );
     let static_source = Vc::upcast(StaticAssetsContentSource::new(
         Default::default(),
@@ -302,7 +413,17 @@ async fn source(
         }
         .cell(),
     );
-    let main_source = Vc::upcast(main_source);
+    let mut main_source = Vc::upcast(main_source);
+    if let Some(index) = spa_fallback {
+        main_source = Vc::upcast(SpaFallbackContentSource::new(
+            main_source,
+            SpaFallbackOptions {
+                index,
+                excluded_prefixes: spa_fallback_excluded_prefixes,
+            }
+            .cell(),
+        ));
+    }
     Vc::upcast(PrefixedRouterContentSource::new(
         Default::default(),
         vec![("__turbopack__".into(), introspect)],
@@ -333,15 +454,28 @@ pub async fn start_server(args: &DevArguments) -> Result<()> {
             .memory_limit
             .map_or(usize::MAX, |l| l * 1024 * 1024),
     ));
+    tt.set_cell_interning(args.common.cell_interning);
+
+    if let Some(interval) = args.sampling_profiler {
+        tt.start_sampling_profiler(Duration::from_millis(interval));
+    }
+
+    let _env_polling = args
+        .poll_env
+        .map(|interval| poll_process_env(Duration::from_millis(interval)));
 
     let tt_clone = tt.clone();
 
     let mut server = TurbopackDevServerBuilder::new(tt, project_dir, root_dir)
         .eager_compile(args.eager_compile)
+        .readable_output(args.readable_output)
+        .forward_browser_console(args.forward_browser_console)
         .hostname(args.hostname)
         .port(args.port)
         .log_detail(args.common.log_detail)
         .show_all(args.common.show_all)
+        .read_ahead(args.common.read_ahead)
+        .strict_csp(args.common.strict_csp)
         .log_level(
             args.common
                 .log_level
@@ -352,11 +486,37 @@ pub async fn start_server(args: &DevArguments) -> Result<()> {
         server = server.entry_request(EntryRequest::Relative(entry))
     }
 
+    if let Some(index) = &args.spa_fallback {
+        server = server.spa_fallback(
+            index.as_str().into(),
+            args.spa_fallback_exclude
+                .iter()
+                .map(|prefix| prefix.as_str().into())
+                .collect(),
+        );
+    }
+
+    for (host, dir) in &args.host_projects {
+        let NormalizedDirs {
+            project_dir,
+            root_dir,
+        } = normalize_dirs(&Some(dir.clone()), &args.common.root)?;
+        server = server.host_project(host.as_str().into(), project_dir, root_dir);
+    }
+
     #[cfg(feature = "serializable")]
     {
         server = server.allow_retry(args.allow_retry);
     }
 
+    if let Some(path) = &args.fault_injection {
+        let config = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let config = serde_json::from_str(&config)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        server = server.fault_injection(FaultInjection::new(config)?);
+    }
+
     let server = server.build().await?;
 
     {
@@ -23,8 +23,8 @@ use turbopack_node::execution_context::ExecutionContext;
 
 use crate::{
     contexts::{
-        get_client_asset_context, get_client_compile_time_info, get_client_resolve_options_context,
-        NodeEnv,
+        get_browser_import_map, get_client_asset_context, get_client_compile_time_info,
+        get_client_resolve_options_context, NodeEnv,
     },
     embed_js::embed_file_path,
 };
@@ -34,6 +34,7 @@ pub fn get_client_chunking_context(
     project_path: Vc<FileSystemPath>,
     server_root: Vc<FileSystemPath>,
     environment: Vc<Environment>,
+    readable_output: bool,
 ) -> Vc<Box<dyn ChunkingContext>> {
     Vc::upcast(
         BrowserChunkingContext::builder(
@@ -47,6 +48,8 @@ pub fn get_client_chunking_context(
         )
         .hot_module_replacement()
         .use_file_source_map_uris()
+        .warn_large_inline_source_maps()
+        .readable_output(readable_output)
         .build(),
     )
 }
@@ -96,14 +99,21 @@ pub async fn create_web_entry_source(
     server_root: Vc<FileSystemPath>,
     _env: Vc<Box<dyn ProcessEnv>>,
     eager_compile: bool,
+    readable_output: bool,
     node_env: Vc<NodeEnv>,
     browserslist_query: RcStr,
+    strict_csp: bool,
 ) -> Result<Vc<Box<dyn ContentSource>>> {
-    let compile_time_info = get_client_compile_time_info(browserslist_query, node_env);
+    let compile_time_info =
+        get_client_compile_time_info(project_path, browserslist_query, node_env, strict_csp);
     let asset_context =
         get_client_asset_context(project_path, execution_context, compile_time_info, node_env);
-    let chunking_context =
-        get_client_chunking_context(project_path, server_root, compile_time_info.environment());
+    let chunking_context = get_client_chunking_context(
+        project_path,
+        server_root,
+        compile_time_info.environment(),
+        readable_output,
+    );
     let entries = get_client_runtime_entries(project_path);
 
     let runtime_entries = entries.resolve_entries(asset_context);
@@ -155,10 +165,16 @@ pub async fn create_web_entry_source(
         .try_join()
         .await?;
 
-    let entry_asset = Vc::upcast(DevHtmlAsset::new(
-        server_root.join("index.html".into()),
-        entries,
-    ));
+    let browser_import_map = get_browser_import_map(project_path).await?;
+    let entry_asset = Vc::upcast(if browser_import_map.imports.is_empty() {
+        DevHtmlAsset::new(server_root.join("index.html".into()), entries)
+    } else {
+        DevHtmlAsset::new_with_body(
+            server_root.join("index.html".into()),
+            entries,
+            browser_import_map.script_tag()?.into(),
+        )
+    });
 
     let graph = Vc::upcast(if eager_compile {
         AssetGraphContentSource::new_eager(server_root, entry_asset)
@@ -3,6 +3,7 @@
 #![feature(arbitrary_self_types)]
 #![feature(arbitrary_self_types_pointers)]
 
+pub mod analyze;
 pub mod arguments;
 pub mod build;
 pub(crate) mod contexts;
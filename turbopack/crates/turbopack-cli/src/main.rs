@@ -5,9 +5,11 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Registry};
+use tracing_subscriber::{
+    layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer, Registry,
+};
 use turbo_tasks_malloc::TurboMalloc;
-use turbopack_cli::{arguments::Arguments, register};
+use turbopack_cli::{arguments::Arguments, build::BuildTimings, register};
 use turbopack_trace_utils::{
     exit::ExitHandler,
     raw_trace::RawTraceLayer,
@@ -37,8 +39,13 @@ fn main() {
 async fn main_inner(args: Arguments) -> Result<()> {
     let exit_handler = ExitHandler::listen();
 
+    // The timings of the build report are taken from the spans of the build.
+    let build_timings = BuildTimings::default();
+    let build_timings_layer =
+        matches!(&args, Arguments::Build(args) if args.json).then(|| build_timings.layer());
+
     let trace = std::env::var("TURBOPACK_TRACING").ok();
-    if let Some(mut trace) = trace {
+    let trace_layer = if let Some(mut trace) = trace {
         // Trace presets
         match trace.as_str() {
             "overview" => {
@@ -53,10 +60,6 @@ async fn main_inner(args: Arguments) -> Result<()> {
             _ => {}
         }
 
-        let subscriber = Registry::default();
-
-        let subscriber = subscriber.with(EnvFilter::builder().parse(trace).unwrap());
-
         let internal_dir = args
             .dir()
             .unwrap_or_else(|| Path::new("."))
@@ -67,18 +70,30 @@ async fn main_inner(args: Arguments) -> Result<()> {
         let trace_file = internal_dir.join("trace.log");
         let trace_writer = std::fs::File::create(trace_file).unwrap();
         let (trace_writer, guard) = TraceWriter::new(trace_writer);
-        let subscriber = subscriber.with(RawTraceLayer::new(trace_writer));
 
         exit_handler
             .on_exit(async move { tokio::task::spawn_blocking(|| drop(guard)).await.unwrap() });
 
-        subscriber.init();
+        Some(
+            RawTraceLayer::new(trace_writer)
+                .with_filter(EnvFilter::builder().parse(trace).unwrap()),
+        )
+    } else {
+        None
+    };
+
+    if trace_layer.is_some() || build_timings_layer.is_some() {
+        Registry::default()
+            .with(trace_layer)
+            .with(build_timings_layer)
+            .init();
     }
 
     register();
 
     match args {
-        Arguments::Build(args) => turbopack_cli::build::build(&args).await,
+        Arguments::Build(args) => turbopack_cli::build::build(&args, build_timings).await,
         Arguments::Dev(args) => turbopack_cli::dev::start_server(&args).await,
+        Arguments::Analyze(args) => turbopack_cli::analyze::analyze(&args).await,
     }
 }
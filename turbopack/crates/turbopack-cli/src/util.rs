@@ -15,6 +15,16 @@ pub enum EntryRequest {
     Module(RcStr, RcStr),
 }
 
+impl EntryRequest {
+    /// The entry as it was passed on the command line.
+    pub fn name(&self) -> RcStr {
+        match self {
+            EntryRequest::Relative(path) => path.clone(),
+            EntryRequest::Module(module, path) => format!("{module}{path}").into(),
+        }
+    }
+}
+
 pub struct NormalizedDirs {
     /// Normalized project directory path as an absolute path
     pub project_dir: RcStr,
@@ -60,8 +70,11 @@ pub fn normalize_entries(entries: &Option<Vec<String>>) -> Vec<RcStr> {
 }
 
 #[turbo_tasks::function]
-pub async fn project_fs(project_dir: RcStr) -> Result<Vc<Box<dyn FileSystem>>> {
+pub async fn project_fs(project_dir: RcStr, read_ahead: bool) -> Result<Vc<Box<dyn FileSystem>>> {
     let disk_fs = DiskFileSystem::new("project".into(), project_dir, vec![]);
+    if read_ahead {
+        disk_fs.await?.enable_read_ahead();
+    }
     disk_fs.await?.start_watching(None).await?;
     Ok(Vc::upcast(disk_fs))
 }
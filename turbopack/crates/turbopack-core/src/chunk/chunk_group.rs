@@ -8,8 +8,8 @@ use turbo_tasks::{
 
 use super::{
     availability_info::AvailabilityInfo, available_chunk_items::AvailableChunkItemInfo,
-    chunk_content, chunking::make_chunks, AsyncModuleInfo, Chunk, ChunkContentResult, ChunkItem,
-    ChunkingContext,
+    chunk_content, chunking::make_chunks, condition_variants::check_condition_variants,
+    AsyncModuleInfo, Chunk, ChunkContentResult, ChunkItem, ChunkingContext,
 };
 use crate::{module::Module, output::OutputAssets, reference::ModuleReference};
 
@@ -33,6 +33,8 @@ pub async fn make_chunk_group(
         available_async_modules_back_edges_inherit_async,
     } = chunk_content(chunking_context, chunk_group_entries, availability_info).await?;
 
+    check_condition_variants(chunk_items.iter().copied()).await?;
+
     // Find all local chunk items that are self async
     let self_async_children = chunk_items
         .iter()
@@ -4,7 +4,9 @@ use turbo_tasks::{trace::TraceRawVcs, RcStr, ResolvedVc, TaskInput, Upcast, Valu
 use turbo_tasks_fs::FileSystemPath;
 use turbo_tasks_hash::DeterministicHash;
 
-use super::{availability_info::AvailabilityInfo, ChunkableModule, EvaluatableAssets};
+use super::{
+    availability_info::AvailabilityInfo, ChunkCodeWrapping, ChunkableModule, EvaluatableAssets,
+};
 use crate::{
     chunk::{ChunkItem, ModuleId},
     environment::Environment,
@@ -35,6 +37,41 @@ pub enum MinifyType {
     NoMinify,
 }
 
+/// Which functions and classes keep their `name` when minified chunks are
+/// mangled, e.g. for error tracking that groups errors by stack frames. The
+/// minifier assigns the original name with `Object.defineProperty` like
+/// esbuild's `keepNames`.
+#[derive(
+    Debug,
+    Default,
+    TaskInput,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    TraceRawVcs,
+    DeterministicHash,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum KeepNames {
+    /// Names may change when they are mangled.
+    #[default]
+    Off,
+    /// All named functions and classes, including anonymous ones that are
+    /// named by the variable they're assigned to.
+    All,
+    /// Only classes.
+    ClassesOnly,
+    /// Only functions and classes whose declaration is annotated with a
+    /// `/* @__KEEP_NAME__ */` (or `#__KEEP_NAME__`) comment.
+    AnnotatedOnly,
+}
+
 #[turbo_tasks::value(shared)]
 pub struct ChunkGroupResult {
     pub assets: Vc<OutputAssets>,
@@ -52,6 +89,16 @@ pub struct EntryChunkGroupResult {
 pub trait ChunkingContext {
     fn name(self: Vc<Self>) -> Vc<RcStr>;
     fn should_use_file_source_map_uris(self: Vc<Self>) -> Vc<bool>;
+    /// A template to rewrite `turbopack://[namespace]/path` sources in emitted
+    /// source maps with, e.g. `webpack://[namespace]/[resource-path]`. See
+    /// [`crate::code_builder::remap_source_map_sources`].
+    fn source_map_source_template(self: Vc<Self>) -> Vc<Option<RcStr>> {
+        Vc::cell(None)
+    }
+    /// Code to add to every emitted chunk. See [`ChunkCodeWrapping`].
+    fn chunk_code_wrapping(self: Vc<Self>) -> Vc<ChunkCodeWrapping> {
+        ChunkCodeWrapping::default().cell()
+    }
     // Often the project root
     fn context_path(self: Vc<Self>) -> Vc<FileSystemPath>;
     fn output_root(self: Vc<Self>) -> Vc<FileSystemPath>;
@@ -80,6 +127,13 @@ pub trait ChunkingContext {
         original_asset_ident: Vc<AssetIdent>,
     ) -> Vc<FileSystemPath>;
 
+    /// Whether `url()` references in CSS refer to static assets by their
+    /// [ChunkingContext::asset_url] instead of relative to the CSS chunk, so
+    /// they stay correct no matter which chunk the CSS ends up in.
+    fn asset_urls_in_css(self: Vc<Self>) -> Vc<bool> {
+        Vc::cell(false)
+    }
+
     fn is_hot_module_replacement_enabled(self: Vc<Self>) -> Vc<bool> {
         Vc::cell(false)
     }
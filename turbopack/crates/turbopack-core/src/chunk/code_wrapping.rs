@@ -0,0 +1,79 @@
+use std::io::Write;
+
+use anyhow::Result;
+use turbo_tasks::RcStr;
+
+use crate::code_builder::CodeBuilder;
+
+/// Code that is added to every emitted chunk, e.g. a license banner or the
+/// setup of a monitoring library. It is part of the chunk content, so it is
+/// included in content hashes and shifts the source maps accordingly.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Default, Clone, Hash)]
+pub struct ChunkCodeWrapping {
+    /// Written before the content of each chunk.
+    pub banner: Option<RcStr>,
+    /// Written after the content of each chunk, before the source map
+    /// reference.
+    pub footer: Option<RcStr>,
+    /// Written after the runtime of chunks that include the runtime, so it
+    /// runs before any module is instantiated.
+    pub runtime_injection: Option<RcStr>,
+}
+
+impl ChunkCodeWrapping {
+    pub fn write_banner(&self, code: &mut CodeBuilder) -> Result<()> {
+        if let Some(banner) = &self.banner {
+            writeln!(code, "{banner}")?;
+        }
+        Ok(())
+    }
+
+    pub fn write_footer(&self, code: &mut CodeBuilder) -> Result<()> {
+        if let Some(footer) = &self.footer {
+            write!(code, "\n{footer}")?;
+        }
+        Ok(())
+    }
+
+    pub fn write_runtime_injection(&self, code: &mut CodeBuilder) -> Result<()> {
+        if let Some(runtime_injection) = &self.runtime_injection {
+            writeln!(code, "\n{runtime_injection}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChunkCodeWrapping;
+    use crate::code_builder::CodeBuilder;
+
+    fn wrap(wrapping: &ChunkCodeWrapping) -> String {
+        let mut code = CodeBuilder::default();
+        wrapping.write_banner(&mut code).unwrap();
+        code += "runtime();";
+        wrapping.write_runtime_injection(&mut code).unwrap();
+        code += "content();";
+        wrapping.write_footer(&mut code).unwrap();
+        code.build().source_code().to_str().unwrap().into_owned()
+    }
+
+    #[test]
+    fn wraps_chunk_code() {
+        let wrapping = ChunkCodeWrapping {
+            banner: Some("/*! license */".into()),
+            footer: Some("//# footer".into()),
+            runtime_injection: Some("setupMonitoring();".into()),
+        };
+        assert_eq!(
+            wrap(&wrapping),
+            "/*! license */\nruntime();\nsetupMonitoring();\ncontent();\n//# footer"
+        );
+    }
+
+    #[test]
+    fn keeps_chunk_code_without_wrapping() {
+        assert_eq!(wrap(&ChunkCodeWrapping::default()), "runtime();content();");
+    }
+}
@@ -0,0 +1,213 @@
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use turbo_tasks::{FxIndexMap, FxIndexSet, RcStr, ResolvedVc, TryJoinIterExt, Vc};
+use turbo_tasks_fs::{FileSystem, FileSystemPath};
+
+use super::ChunkItem;
+use crate::{
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    package_json::{package_of_path, read_package_json},
+};
+
+/// Pairs of files of a package, relative to the package root, that the
+/// `development` and the `production` condition of the same export select.
+#[turbo_tasks::value(transparent)]
+pub struct ConditionVariants(Vec<(RcStr, RcStr)>);
+
+/// Finds the files that the `development` and the `production` conditions
+/// select in the `exports` field of the package at `package_root`.
+#[turbo_tasks::function]
+pub async fn package_condition_variants(
+    package_root: Vc<FileSystemPath>,
+) -> Result<Vc<ConditionVariants>> {
+    let mut variants = FxIndexSet::default();
+    if let Some(package_json) =
+        &*read_package_json(package_root.join("package.json".into())).await?
+    {
+        if let Some(exports) = package_json.get("exports") {
+            collect_condition_variants(exports, &mut variants);
+        }
+    }
+    Ok(Vc::cell(variants.into_iter().collect()))
+}
+
+fn collect_condition_variants(value: &JsonValue, variants: &mut FxIndexSet<(RcStr, RcStr)>) {
+    match value {
+        JsonValue::Object(map) => {
+            if let (Some(development), Some(production)) =
+                (map.get("development"), map.get("production"))
+            {
+                let mut development_targets = Vec::new();
+                collect_targets(development, &mut development_targets);
+                let mut production_targets = Vec::new();
+                collect_targets(production, &mut production_targets);
+                for &development in &development_targets {
+                    for &production in &production_targets {
+                        if development != production {
+                            variants.insert((development.into(), production.into()));
+                        }
+                    }
+                }
+            }
+            for value in map.values() {
+                collect_condition_variants(value, variants);
+            }
+        }
+        JsonValue::Array(values) => {
+            for value in values {
+                collect_condition_variants(value, variants);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collects the targets of an `exports` field value. Subpath patterns are
+/// skipped, as they don't select a single file.
+fn collect_targets<'a>(value: &'a JsonValue, targets: &mut Vec<&'a str>) {
+    match value {
+        JsonValue::String(target) => {
+            if !target.contains('*') {
+                targets.push(target.trim_start_matches("./"));
+            }
+        }
+        JsonValue::Object(map) => {
+            for value in map.values() {
+                collect_targets(value, targets);
+            }
+        }
+        JsonValue::Array(values) => {
+            for value in values {
+                collect_targets(value, targets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Emits a [ConditionVariantsIssue] when `chunk_items` contain the files of
+/// both the `development` and the `production` condition of the same export.
+///
+/// This happens when modules of a chunk group resolve a package with
+/// different conditions, e.g. in different layers, which includes both
+/// variants of the package in the bundle.
+pub async fn check_condition_variants(
+    chunk_items: impl IntoIterator<Item = Vc<Box<dyn ChunkItem>>>,
+) -> Result<()> {
+    let paths = chunk_items
+        .into_iter()
+        .map(|chunk_item| async move { chunk_item.asset_ident().path().await })
+        .try_join()
+        .await?;
+
+    let mut packages = FxIndexMap::<_, FxIndexSet<&str>>::default();
+    for path in &paths {
+        let Some((_, root)) = package_of_path(&path.path) else {
+            continue;
+        };
+        let Some(file) = path.path[root.len()..].strip_prefix('/') else {
+            continue;
+        };
+        packages.entry((path.fs, root)).or_default().insert(file);
+    }
+
+    for ((fs, root), files) in packages {
+        if files.len() < 2 {
+            continue;
+        }
+        let package_root = fs.root().join(root.into());
+        for (development, production) in &*package_condition_variants(package_root).await? {
+            if files.contains(development.as_str()) && files.contains(production.as_str()) {
+                ConditionVariantsIssue {
+                    package_json: package_root
+                        .join("package.json".into())
+                        .to_resolved()
+                        .await?,
+                    development: development.clone(),
+                    production: production.clone(),
+                }
+                .cell()
+                .emit();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[turbo_tasks::value(shared)]
+pub struct ConditionVariantsIssue {
+    package_json: ResolvedVc<FileSystemPath>,
+    development: RcStr,
+    production: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for ConditionVariantsIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Resolve.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.package_json
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(
+            "The development and the production variant of a package are included in the same \
+             bundle"
+                .into(),
+        )
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(
+                format!(
+                    "Both {} (development condition) and {} (production condition) are included, \
+                     because modules of the bundle resolve the package with different conditions. \
+                     Make sure all of them use the same condition.",
+                    self.development, self.production
+                )
+                .into(),
+            )
+            .cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_condition_variants() {
+        let exports = serde_json::json!({
+            ".": {
+                "development": "./dev.js",
+                "production": { "import": "./prod.mjs", "require": "./prod.cjs" },
+                "default": "./prod.cjs"
+            },
+            "./feature/*": { "development": "./dev/*.js", "production": "./prod/*.js" }
+        });
+        let mut variants = FxIndexSet::default();
+        collect_condition_variants(&exports, &mut variants);
+        assert_eq!(
+            variants.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("dev.js".into(), "prod.mjs".into()),
+                ("dev.js".into(), "prod.cjs".into()),
+            ]
+        );
+    }
+}
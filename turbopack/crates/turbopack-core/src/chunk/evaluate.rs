@@ -1,5 +1,5 @@
 use anyhow::{bail, Result};
-use turbo_tasks::{Upcast, Value, ValueToString, Vc};
+use turbo_tasks::{CowVec, Upcast, Value, ValueToString, Vc};
 
 use super::ChunkableModule;
 use crate::{
@@ -57,23 +57,23 @@ async fn to_evaluatable(
 }
 
 #[turbo_tasks::value(transparent)]
-pub struct EvaluatableAssets(Vec<Vc<Box<dyn EvaluatableAsset>>>);
+pub struct EvaluatableAssets(CowVec<Vc<Box<dyn EvaluatableAsset>>>);
 
 #[turbo_tasks::value_impl]
 impl EvaluatableAssets {
     #[turbo_tasks::function]
     pub fn empty() -> Vc<EvaluatableAssets> {
-        EvaluatableAssets(vec![]).cell()
+        EvaluatableAssets(CowVec::new()).cell()
     }
 
     #[turbo_tasks::function]
     pub fn one(entry: Vc<Box<dyn EvaluatableAsset>>) -> Vc<EvaluatableAssets> {
-        EvaluatableAssets(vec![entry]).cell()
+        EvaluatableAssets([entry].into_iter().collect()).cell()
     }
 
     #[turbo_tasks::function]
     pub fn many(assets: Vec<Vc<Box<dyn EvaluatableAsset>>>) -> Vc<EvaluatableAssets> {
-        EvaluatableAssets(assets).cell()
+        EvaluatableAssets(assets.into()).cell()
     }
 
     #[turbo_tasks::function]
@@ -81,6 +81,8 @@ impl EvaluatableAssets {
         self: Vc<Self>,
         entry: Vc<Box<dyn EvaluatableAsset>>,
     ) -> Result<Vc<EvaluatableAssets>> {
+        // The clone shares the full chunks of entries and only copies the last one, which is
+        // the whole list while it has fewer entries than a chunk holds.
         let mut entries = self.await?.clone_value();
         entries.push(entry);
         Ok(EvaluatableAssets(entries).cell())
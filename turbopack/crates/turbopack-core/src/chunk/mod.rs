@@ -3,6 +3,8 @@ pub mod available_chunk_items;
 pub mod chunk_group;
 pub mod chunking;
 pub(crate) mod chunking_context;
+pub(crate) mod code_wrapping;
+pub mod condition_variants;
 pub(crate) mod containment_tree;
 pub(crate) mod data;
 pub(crate) mod evaluate;
@@ -33,8 +35,10 @@ use turbo_tasks_hash::DeterministicHash;
 use self::{availability_info::AvailabilityInfo, available_chunk_items::AvailableChunkItems};
 pub use self::{
     chunking_context::{
-        ChunkGroupResult, ChunkingContext, ChunkingContextExt, EntryChunkGroupResult, MinifyType,
+        ChunkGroupResult, ChunkingContext, ChunkingContextExt, EntryChunkGroupResult, KeepNames,
+        MinifyType,
     },
+    code_wrapping::ChunkCodeWrapping,
     data::{ChunkData, ChunkDataOption, ChunksData},
     evaluate::{EvaluatableAsset, EvaluatableAssetExt, EvaluatableAssets},
 };
@@ -6,7 +6,7 @@ use std::{
 
 use anyhow::{Context, Result};
 use indexmap::{IndexMap, IndexSet};
-use turbo_tasks::Vc;
+use turbo_tasks::{RcStr, Vc};
 use turbo_tasks_fs::{
     rope::{Rope, RopeBuilder},
     util::uri_from_file,
@@ -15,6 +15,7 @@ use turbo_tasks_fs::{
 use turbo_tasks_hash::hash_xxh3_hash64;
 
 use crate::{
+    chunk::ChunkingContext,
     source_map::{GenerateSourceMap, OptionSourceMap, SourceMap, SourceMapSection},
     source_pos::SourcePos,
     SOURCE_MAP_PREFIX,
@@ -273,3 +274,105 @@ pub async fn fileify_source_map(
         SourceMap::new_decoded(sourcemap::DecodedMap::Regular(transformed)).cell(),
     )))
 }
+
+/// Rewrites `turbopack://[namespace]/resource/path` references in sourcemap
+/// sources according to `template`, e.g. `webpack://[namespace]/[resource-path]`
+/// or `../../[resource-path]`.
+///
+/// The template can contain these placeholders:
+/// * `[namespace]`: the name of the filesystem the source belongs to, e.g. `project`.
+/// * `[resource-path]`: the path of the source relative to the root of that filesystem.
+///
+/// Sources that don't use the `turbopack://` scheme, e.g. ones that were
+/// already turned into `file://` uris, are kept as they are.
+#[turbo_tasks::function]
+pub async fn remap_source_map_sources(
+    map: Vc<OptionSourceMap>,
+    template: RcStr,
+) -> Result<Vc<OptionSourceMap>> {
+    let Some(map) = &*map.await? else {
+        return Ok(OptionSourceMap::none());
+    };
+
+    let flattened = map.await?.to_source_map().await?;
+    let flattened = flattened.as_regular_source_map();
+
+    let Some(flattened) = flattened else {
+        return Ok(OptionSourceMap::none());
+    };
+
+    let mut transformed = flattened.into_owned();
+    let mut updates = IndexMap::new();
+    for (src_id, src) in transformed.sources().enumerate() {
+        if let Some(src) = remap_source(src, &template) {
+            updates.insert(src_id, src);
+        }
+    }
+
+    for (src_id, src) in updates {
+        transformed.set_source(src_id as _, &src);
+    }
+
+    Ok(Vc::cell(Some(
+        SourceMap::new_decoded(sourcemap::DecodedMap::Regular(transformed)).cell(),
+    )))
+}
+
+/// Rewrites the sources of the source map of a chunk according to the
+/// [ChunkingContext::source_map_source_template] of `chunking_context`.
+/// Chunks apply it to their whole source map, so it covers all of their items,
+/// no matter how their content was created.
+#[turbo_tasks::function]
+pub async fn apply_source_map_source_template(
+    map: Vc<OptionSourceMap>,
+    chunking_context: Vc<Box<dyn ChunkingContext>>,
+) -> Result<Vc<OptionSourceMap>> {
+    Ok(
+        match &*chunking_context.source_map_source_template().await? {
+            Some(template) => remap_source_map_sources(map, template.clone()),
+            None => map,
+        },
+    )
+}
+
+/// Applies a source template to a single `turbopack://[namespace]/path`
+/// source. Returns `None` when the source doesn't use that format.
+fn remap_source(src: &str, template: &str) -> Option<String> {
+    let src = src.strip_prefix(SOURCE_MAP_PREFIX)?.strip_prefix('[')?;
+    let (namespace, path) = src.split_once(']')?;
+    let path = path.strip_prefix('/').unwrap_or(path);
+    Some(
+        template
+            .replace("[namespace]", namespace)
+            .replace("[resource-path]", path),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::remap_source;
+
+    #[test]
+    fn remaps_turbopack_sources() {
+        assert_eq!(
+            remap_source(
+                "turbopack://[project]/src/index.js",
+                "webpack://[namespace]/[resource-path]"
+            )
+            .as_deref(),
+            Some("webpack://project/src/index.js")
+        );
+        assert_eq!(
+            remap_source(
+                "turbopack://[project]/packages/app/page.tsx",
+                "[resource-path]"
+            )
+            .as_deref(),
+            Some("packages/app/page.tsx")
+        );
+        assert_eq!(
+            remap_source("file:///root/src/index.js", "[resource-path]"),
+            None
+        );
+    }
+}
@@ -5,7 +5,7 @@ use turbo_tasks_fs::{glob::Glob, FileSystemPath};
 use crate::{
     compile_time_info::CompileTimeInfo,
     issue::module::emit_unknown_module_type_error,
-    module::{Module, OptionModule},
+    module::Module,
     reference_type::ReferenceType,
     resolve::{options::ResolveOptions, parse::Request, ModuleResolveResult, ResolveResult},
     source::Source,
@@ -41,7 +41,7 @@ impl ProcessResult {
 
     /// Unwrap the module, or return None and emit an issue
     #[turbo_tasks::function]
-    pub async fn try_into_module(&self) -> Result<Vc<OptionModule>> {
+    pub async fn try_into_module(&self) -> Result<Vc<Option<ResolvedVc<Box<dyn Module>>>>> {
         Ok(Vc::cell(match self {
             ProcessResult::Module(module) => Some(*module),
             ProcessResult::Unknown(source) => {
@@ -0,0 +1,127 @@
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use turbo_tasks::{RcStr, ResolvedVc, Vc};
+use turbo_tasks_fs::{File, FileSystemPath};
+use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64};
+
+use crate::{
+    asset::{Asset, AssetContent},
+    ident::AssetIdent,
+    source::Source,
+};
+
+/// The media type, whether the data is base64 encoded and the data of a
+/// `data:` URI, or `None` for other URIs.
+fn parse_data_uri(uri: &str) -> Option<(&str, bool, &str)> {
+    let (header, data) = uri.strip_prefix("data:")?.split_once(',')?;
+    Some(match header.strip_suffix(";base64") {
+        Some(media_type) => (media_type, true, data),
+        None => (header, false, data),
+    })
+}
+
+pub fn is_data_uri(uri: &str) -> bool {
+    parse_data_uri(uri).is_some()
+}
+
+/// The file extension of a media type, so that a data URI is processed with
+/// the module rules of a file of that type.
+fn extension(media_type: &str) -> &'static str {
+    let essence = media_type.split(';').next().unwrap_or_default().trim();
+    match essence.to_ascii_lowercase().as_str() {
+        "text/javascript" | "application/javascript" => "js",
+        "application/json" => "json",
+        "text/css" => "css",
+        "application/wasm" => "wasm",
+        "image/svg+xml" => "svg",
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        // The default media type of data URIs is `text/plain`.
+        _ => "txt",
+    }
+}
+
+/// A [Source] of a `data:` URI, e.g. `data:text/javascript,export default 1`,
+/// which contains its content inline.
+///
+/// It's placed next to the module that imports it, with a file extension that
+/// matches its media type.
+#[turbo_tasks::value]
+pub struct DataUriSource {
+    uri: RcStr,
+    lookup_path: ResolvedVc<FileSystemPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl DataUriSource {
+    /// `uri` needs to be a data URI, see [is_data_uri].
+    #[turbo_tasks::function]
+    pub async fn new(uri: RcStr, lookup_path: Vc<FileSystemPath>) -> Result<Vc<Self>> {
+        Ok(DataUriSource {
+            uri,
+            lookup_path: lookup_path.to_resolved().await?,
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for DataUriSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Result<Vc<AssetIdent>> {
+        let Some((media_type, _, _)) = parse_data_uri(&self.uri) else {
+            bail!("{} is not a data URI", self.uri);
+        };
+        let hash = encode_hex(hash_xxh3_hash64(self.uri.as_bytes()));
+        Ok(AssetIdent::from_path(self.lookup_path.join(
+            format!("data-uri-{hash}.{}", extension(media_type)).into(),
+        )))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for DataUriSource {
+    #[turbo_tasks::function]
+    fn content(&self) -> Result<Vc<AssetContent>> {
+        let Some((_, base64, data)) = parse_data_uri(&self.uri) else {
+            bail!("{} is not a data URI", self.uri);
+        };
+        let data = urlencoding::decode_binary(data.as_bytes());
+        let content = if base64 {
+            STANDARD
+                .decode(&*data)
+                .context("The base64 data of the data URI is invalid")?
+        } else {
+            data.into_owned()
+        };
+        Ok(AssetContent::file(File::from(content).into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_data_uri() {
+        assert_eq!(
+            parse_data_uri("data:text/javascript,export default 1"),
+            Some(("text/javascript", false, "export default 1"))
+        );
+        assert_eq!(
+            parse_data_uri("data:application/json;base64,e30="),
+            Some(("application/json", true, "e30="))
+        );
+        assert_eq!(parse_data_uri("data:,hello"), Some(("", false, "hello")));
+        assert_eq!(parse_data_uri("https://example.com/a.js"), None);
+    }
+
+    #[test]
+    fn test_extension() {
+        assert_eq!(extension("text/javascript;charset=utf-8"), "js");
+        assert_eq!(extension("Image/PNG"), "png");
+        assert_eq!(extension(""), "txt");
+    }
+}
@@ -40,6 +40,7 @@ pub enum ChunkLoading {
 pub struct Environment {
     // members must be private to avoid leaking non-custom types
     execution: ExecutionEnvironment,
+    capabilities: Option<ResolvedVc<EnvironmentCapabilities>>,
 }
 
 #[turbo_tasks::value_impl]
@@ -48,10 +49,190 @@ impl Environment {
     pub fn new(execution: Value<ExecutionEnvironment>) -> Vc<Self> {
         Self::cell(Environment {
             execution: execution.into_value(),
+            capabilities: None,
         })
     }
+
+    /// Returns an environment that reports `capabilities` instead of the ones
+    /// derived from the execution environment, e.g. to generate more modern
+    /// code for a single layer.
+    #[turbo_tasks::function]
+    pub async fn with_capabilities(
+        self: Vc<Self>,
+        capabilities: ResolvedVc<EnvironmentCapabilities>,
+    ) -> Result<Vc<Self>> {
+        Ok(Self::cell(Environment {
+            execution: self.await?.execution,
+            capabilities: Some(capabilities),
+        }))
+    }
+}
+
+/// What the code generated for an [Environment] can rely on. Code generation
+/// queries these instead of checking the kind of environment or its versions.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone, Copy)]
+pub struct EnvironmentCapabilities {
+    /// Whether Node.js built-in modules can be required.
+    pub node_externals: bool,
+    /// Whether external ES modules can be imported at runtime.
+    pub esm_externals: bool,
+    /// Whether external CommonJS modules can be required at runtime.
+    pub commonjs_externals: bool,
+    /// Whether WebAssembly modules can be loaded from the filesystem.
+    pub wasm: bool,
+    /// Whether class fields are supported natively.
+    pub class_fields: bool,
+    /// Whether private methods and accessors are supported natively.
+    pub private_methods: bool,
+    /// Whether `#field in obj` brand checks are supported natively.
+    pub private_brand_checks: bool,
+    /// Whether `static { }` blocks in classes are supported natively.
+    pub class_static_blocks: bool,
+    /// Whether CSS nesting is supported natively.
+    pub css_nesting: bool,
 }
 
+impl EnvironmentCapabilities {
+    /// The language capabilities of the runtimes in `versions`. Support for
+    /// external modules is not included.
+    pub fn from_versions(versions: &Versions) -> Self {
+        EnvironmentCapabilities {
+            node_externals: false,
+            esm_externals: false,
+            commonjs_externals: false,
+            wasm: false,
+            class_fields: CLASS_FIELDS.is_supported_by(versions),
+            private_methods: PRIVATE_METHODS.is_supported_by(versions),
+            private_brand_checks: PRIVATE_BRAND_CHECKS.is_supported_by(versions),
+            class_static_blocks: CLASS_STATIC_BLOCKS.is_supported_by(versions),
+            css_nesting: CSS_NESTING.is_supported_by(versions),
+        }
+    }
+
+    /// The language capabilities of an up-to-date runtime.
+    pub fn modern() -> Self {
+        EnvironmentCapabilities {
+            node_externals: false,
+            esm_externals: false,
+            commonjs_externals: false,
+            wasm: false,
+            class_fields: true,
+            private_methods: true,
+            private_brand_checks: true,
+            class_static_blocks: true,
+            css_nesting: true,
+        }
+    }
+
+    /// Whether classes can be emitted as written. Otherwise all class
+    /// features are lowered together: lowering only some of them, e.g. brand
+    /// checks of private fields that are kept native, is not supported.
+    pub fn native_class_features(&self) -> bool {
+        self.class_fields
+            && self.private_methods
+            && self.private_brand_checks
+            && self.class_static_blocks
+    }
+}
+
+/// The first versions of the runtimes that support a feature, as `(major,
+/// minor)`. `None` means that a runtime doesn't support it at all.
+pub struct MinVersions {
+    pub chrome: Option<(u32, u32)>,
+    pub edge: Option<(u32, u32)>,
+    pub firefox: Option<(u32, u32)>,
+    pub safari: Option<(u32, u32)>,
+    pub ios: Option<(u32, u32)>,
+    pub samsung: Option<(u32, u32)>,
+    pub opera: Option<(u32, u32)>,
+    pub node: Option<(u32, u32)>,
+}
+
+impl MinVersions {
+    /// Whether all runtimes in `versions` support the feature.
+    pub fn is_supported_by(&self, versions: &Versions) -> bool {
+        [
+            (versions.chrome, self.chrome),
+            (versions.edge, self.edge),
+            (versions.firefox, self.firefox),
+            (versions.safari, self.safari),
+            (versions.ios, self.ios),
+            (versions.samsung, self.samsung),
+            (versions.opera, self.opera),
+            (versions.node, self.node),
+        ]
+        .into_iter()
+        .all(|(version, min)| match (version, min) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(version), Some((major, minor))) => {
+                version
+                    >= Version {
+                        major,
+                        minor,
+                        patch: 0,
+                    }
+            }
+        })
+    }
+}
+
+const CLASS_FIELDS: MinVersions = MinVersions {
+    chrome: Some((74, 0)),
+    edge: Some((79, 0)),
+    firefox: Some((90, 0)),
+    safari: Some((14, 1)),
+    ios: Some((14, 5)),
+    samsung: Some((11, 0)),
+    opera: Some((62, 0)),
+    node: Some((12, 0)),
+};
+
+const PRIVATE_METHODS: MinVersions = MinVersions {
+    chrome: Some((84, 0)),
+    edge: Some((84, 0)),
+    firefox: Some((90, 0)),
+    safari: Some((15, 0)),
+    ios: Some((15, 0)),
+    samsung: Some((14, 0)),
+    opera: Some((70, 0)),
+    node: Some((14, 6)),
+};
+
+const PRIVATE_BRAND_CHECKS: MinVersions = MinVersions {
+    chrome: Some((91, 0)),
+    edge: Some((91, 0)),
+    firefox: Some((90, 0)),
+    safari: Some((15, 0)),
+    ios: Some((15, 0)),
+    samsung: Some((16, 0)),
+    opera: Some((77, 0)),
+    node: Some((16, 4)),
+};
+
+const CLASS_STATIC_BLOCKS: MinVersions = MinVersions {
+    chrome: Some((94, 0)),
+    edge: Some((94, 0)),
+    firefox: Some((93, 0)),
+    safari: Some((16, 4)),
+    ios: Some((16, 4)),
+    samsung: Some((17, 0)),
+    opera: Some((80, 0)),
+    node: Some((16, 11)),
+};
+
+const CSS_NESTING: MinVersions = MinVersions {
+    chrome: Some((120, 0)),
+    edge: Some((120, 0)),
+    firefox: Some((117, 0)),
+    safari: Some((17, 2)),
+    ios: Some((17, 2)),
+    samsung: None,
+    opera: Some((106, 0)),
+    node: None,
+};
+
 #[turbo_tasks::value(serialization = "auto_for_input")]
 #[derive(Debug, Hash, Clone, Copy)]
 pub enum ExecutionEnvironment {
@@ -92,52 +273,66 @@ impl Environment {
         })
     }
 
+    /// The capabilities of this environment, unless they are overridden with
+    /// [Environment::with_capabilities].
     #[turbo_tasks::function]
-    pub fn node_externals(&self) -> Vc<bool> {
-        match self.execution {
+    pub async fn capabilities(self: Vc<Self>) -> Result<Vc<EnvironmentCapabilities>> {
+        let this = self.await?;
+        if let Some(capabilities) = this.capabilities {
+            return Ok(*capabilities);
+        }
+        Ok(match this.execution {
             ExecutionEnvironment::NodeJsBuildTime(..) | ExecutionEnvironment::NodeJsLambda(_) => {
-                Vc::cell(true)
+                EnvironmentCapabilities {
+                    node_externals: true,
+                    esm_externals: true,
+                    commonjs_externals: true,
+                    wasm: true,
+                    ..EnvironmentCapabilities::from_versions(&*self.runtime_versions().await?)
+                }
             }
-            ExecutionEnvironment::Browser(_) => Vc::cell(false),
-            ExecutionEnvironment::EdgeWorker(_) => Vc::cell(false),
+            ExecutionEnvironment::Browser(_) => {
+                EnvironmentCapabilities::from_versions(&*self.runtime_versions().await?)
+            }
+            // Edge runtimes are kept up to date with recent V8 versions.
+            ExecutionEnvironment::EdgeWorker(_) => EnvironmentCapabilities {
+                commonjs_externals: true,
+                ..EnvironmentCapabilities::modern()
+            },
             ExecutionEnvironment::Custom(_) => todo!(),
         }
+        .cell())
     }
 
     #[turbo_tasks::function]
-    pub fn supports_esm_externals(&self) -> Vc<bool> {
-        match self.execution {
-            ExecutionEnvironment::NodeJsBuildTime(..) | ExecutionEnvironment::NodeJsLambda(_) => {
-                Vc::cell(true)
-            }
-            ExecutionEnvironment::Browser(_) => Vc::cell(false),
-            ExecutionEnvironment::EdgeWorker(_) => Vc::cell(false),
-            ExecutionEnvironment::Custom(_) => todo!(),
-        }
+    pub async fn node_externals(self: Vc<Self>) -> Result<Vc<bool>> {
+        Ok(Vc::cell(self.capabilities().await?.node_externals))
     }
 
     #[turbo_tasks::function]
-    pub fn supports_commonjs_externals(&self) -> Vc<bool> {
-        match self.execution {
-            ExecutionEnvironment::NodeJsBuildTime(..) | ExecutionEnvironment::NodeJsLambda(_) => {
-                Vc::cell(true)
-            }
-            ExecutionEnvironment::Browser(_) => Vc::cell(false),
-            ExecutionEnvironment::EdgeWorker(_) => Vc::cell(true),
-            ExecutionEnvironment::Custom(_) => todo!(),
-        }
+    pub async fn supports_esm_externals(self: Vc<Self>) -> Result<Vc<bool>> {
+        Ok(Vc::cell(self.capabilities().await?.esm_externals))
     }
 
     #[turbo_tasks::function]
-    pub fn supports_wasm(&self) -> Vc<bool> {
-        match self.execution {
-            ExecutionEnvironment::NodeJsBuildTime(..) | ExecutionEnvironment::NodeJsLambda(_) => {
-                Vc::cell(true)
-            }
-            ExecutionEnvironment::Browser(_) => Vc::cell(false),
-            ExecutionEnvironment::EdgeWorker(_) => Vc::cell(false),
+    pub async fn supports_commonjs_externals(self: Vc<Self>) -> Result<Vc<bool>> {
+        Ok(Vc::cell(self.capabilities().await?.commonjs_externals))
+    }
+
+    #[turbo_tasks::function]
+    pub async fn strict_csp(&self) -> Result<Vc<bool>> {
+        Ok(match self.execution {
+            ExecutionEnvironment::Browser(browser_env) => Vc::cell(browser_env.await?.strict_csp),
+            ExecutionEnvironment::NodeJsBuildTime(..)
+            | ExecutionEnvironment::NodeJsLambda(_)
+            | ExecutionEnvironment::EdgeWorker(_) => Vc::cell(false),
             ExecutionEnvironment::Custom(_) => todo!(),
-        }
+        })
+    }
+
+    #[turbo_tasks::function]
+    pub async fn supports_wasm(self: Vc<Self>) -> Result<Vc<bool>> {
+        Ok(Vc::cell(self.capabilities().await?.wasm))
     }
 
     #[turbo_tasks::function]
@@ -288,6 +483,10 @@ pub struct BrowserEnvironment {
     pub web_worker: bool,
     pub service_worker: bool,
     pub browserslist_query: RcStr,
+    /// Whether the page enforces a strict Content Security Policy that forbids
+    /// `eval` and `new Function`. The development runtime then evaluates
+    /// updated modules by injecting `<script>` tags instead.
+    pub strict_csp: bool,
 }
 
 #[turbo_tasks::value(shared)]
@@ -317,3 +516,80 @@ pub async fn get_current_nodejs_version(env: Vc<Box<dyn ProcessEnv>>) -> Result<
             .into(),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use swc_core::ecma::preset_env::{Version, Versions};
+
+    use super::EnvironmentCapabilities;
+
+    fn version(major: u32, minor: u32) -> Option<Version> {
+        Some(Version {
+            major,
+            minor,
+            patch: 0,
+        })
+    }
+
+    #[test]
+    fn capabilities_from_versions() {
+        let capabilities = EnvironmentCapabilities::from_versions(&Versions {
+            chrome: version(102, 0),
+            safari: version(12, 1),
+            ..Default::default()
+        });
+        assert!(!capabilities.class_fields);
+        assert!(!capabilities.css_nesting);
+        // Support for external modules doesn't depend on the versions.
+        assert!(!capabilities.node_externals);
+        assert!(!capabilities.wasm);
+
+        let capabilities = EnvironmentCapabilities::from_versions(&Versions {
+            node: version(18, 0),
+            ..Default::default()
+        });
+        assert!(capabilities.class_fields);
+        assert!(!capabilities.css_nesting);
+
+        let capabilities = EnvironmentCapabilities::from_versions(&Versions {
+            chrome: version(120, 0),
+            firefox: version(117, 0),
+            safari: version(17, 2),
+            ..Default::default()
+        });
+        assert!(capabilities.css_nesting);
+    }
+
+    #[test]
+    fn class_features_from_versions() {
+        // Safari 14.1 supports class fields, but neither private methods nor
+        // static blocks.
+        let capabilities = EnvironmentCapabilities::from_versions(&Versions {
+            safari: version(14, 1),
+            ..Default::default()
+        });
+        assert!(capabilities.class_fields);
+        assert!(!capabilities.private_methods);
+        assert!(!capabilities.private_brand_checks);
+        assert!(!capabilities.class_static_blocks);
+        assert!(!capabilities.native_class_features());
+
+        let capabilities = EnvironmentCapabilities::from_versions(&Versions {
+            safari: version(15, 4),
+            ..Default::default()
+        });
+        assert!(capabilities.private_methods);
+        assert!(capabilities.private_brand_checks);
+        assert!(!capabilities.class_static_blocks);
+        assert!(!capabilities.native_class_features());
+
+        let capabilities = EnvironmentCapabilities::from_versions(&Versions {
+            chrome: version(102, 0),
+            safari: version(16, 4),
+            node: version(18, 0),
+            ..Default::default()
+        });
+        assert!(capabilities.native_class_features());
+        assert!(EnvironmentCapabilities::modern().native_class_features());
+    }
+}
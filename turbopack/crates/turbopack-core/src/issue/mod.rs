@@ -13,8 +13,8 @@ use async_trait::async_trait;
 use auto_hash_map::AutoSet;
 use serde::Serialize;
 use turbo_tasks::{
-    emit, CollectiblesSource, RawVc, RcStr, ReadRef, ResolvedVc, TransientInstance, TransientValue,
-    TryJoinIterExt, Upcast, ValueToString, Vc,
+    emit, CollectiblesSource, CowVec, RawVc, RcStr, ReadRef, ResolvedVc, TransientInstance,
+    TransientValue, TryJoinIterExt, Upcast, ValueToString, Vc,
 };
 use turbo_tasks_fs::{FileContent, FileLine, FileLinesContent, FileSystemPath};
 use turbo_tasks_hash::{DeterministicHash, Xxh3Hash64Hasher};
@@ -361,7 +361,9 @@ pub struct Issues(Vec<ResolvedVc<Box<dyn Issue>>>);
 #[turbo_tasks::value(shared)]
 #[derive(Debug)]
 pub struct CapturedIssues {
-    issues: AutoSet<Vc<Box<dyn Issue>>>,
+    /// The issues are unique, as they are collected from a set. Cloning the
+    /// captured issues shares them.
+    issues: CowVec<Vc<Box<dyn Issue>>>,
     #[cfg(feature = "issue_path")]
     processing_path: Vc<ItemIssueProcessingPath>,
 }
@@ -983,7 +985,10 @@ where
 
     async fn peek_issues_with_path(self) -> Result<CapturedIssues> {
         Ok(CapturedIssues {
-            issues: self.peek_collectibles(),
+            issues: self
+                .peek_collectibles::<Box<dyn Issue>>()
+                .into_iter()
+                .collect(),
             #[cfg(feature = "issue_path")]
             processing_path: ItemIssueProcessingPath::cell(ItemIssueProcessingPath(
                 None,
@@ -994,7 +999,10 @@ where
 
     async fn take_issues_with_path(self) -> Result<CapturedIssues> {
         Ok(CapturedIssues {
-            issues: self.take_collectibles(),
+            issues: self
+                .take_collectibles::<Box<dyn Issue>>()
+                .into_iter()
+                .collect(),
             #[cfg(feature = "issue_path")]
             processing_path: ItemIssueProcessingPath::cell(ItemIssueProcessingPath(
                 None,
@@ -13,6 +13,7 @@ pub mod code_builder;
 pub mod compile_time_info;
 pub mod condition;
 pub mod context;
+pub mod data_uri_source;
 pub mod diagnostics;
 pub mod environment;
 pub mod error;
@@ -21,7 +22,9 @@ pub mod ident;
 pub mod introspect;
 pub mod issue;
 pub mod module;
+pub mod module_metadata;
 pub mod output;
+pub mod output_pruning;
 pub mod package_json;
 pub mod proxied_asset;
 pub mod raw_module;
@@ -1,6 +1,8 @@
-use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks::{CowVec, ResolvedVc, Vc};
 
-use crate::{asset::Asset, ident::AssetIdent, reference::ModuleReferences};
+use crate::{
+    asset::Asset, ident::AssetIdent, module_metadata::ModuleMetadata, reference::ModuleReferences,
+};
 
 /// A module. This usually represents parsed source code, which has references
 /// to other modules.
@@ -19,18 +21,23 @@ pub trait Module: Asset {
     fn additional_layers_modules(self: Vc<Self>) -> Vc<Modules> {
         Vc::cell(vec![])
     }
-}
 
-#[turbo_tasks::value(transparent)]
-pub struct OptionModule(Option<ResolvedVc<Box<dyn Module>>>);
+    /// Information about this [Module] that transforms and its analysis
+    /// discovered, for chunking and manifests.
+    fn metadata(self: Vc<Self>) -> Vc<ModuleMetadata> {
+        ModuleMetadata::empty()
+    }
+}
 
+/// A list of modules. Clones share the modules, so a list that is derived from
+/// another one only copies the chunks it changes.
 #[turbo_tasks::value(transparent)]
-pub struct Modules(Vec<ResolvedVc<Box<dyn Module>>>);
+pub struct Modules(CowVec<ResolvedVc<Box<dyn Module>>>);
 
 #[turbo_tasks::value_impl]
 impl Modules {
     #[turbo_tasks::function]
     pub fn empty() -> Vc<Self> {
-        Vc::cell(Vec::new())
+        Vc::cell(CowVec::new())
     }
 }
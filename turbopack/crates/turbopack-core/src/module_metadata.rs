@@ -0,0 +1,156 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value as JsonValue;
+use turbo_tasks::{RcStr, Vc};
+
+/// A key of [ModuleMetadata] with the type of its value.
+///
+/// Keys are usually declared as constants next to the code that writes them,
+/// e.g. `const SERVER_ACTIONS: ModuleMetadataKey<Vec<RcStr>> =
+/// ModuleMetadataKey::new("next/server-actions");`. Names should be prefixed
+/// with the name of their package to avoid conflicts.
+pub struct ModuleMetadataKey<T> {
+    name: &'static str,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T> ModuleMetadataKey<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _value: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Information about a module that transforms and the analysis of the module
+/// discover, and that chunking and manifests need, e.g. that a module
+/// registers server actions or needs a polyfill.
+///
+/// Values are stored as JSON, so new keys can be added without changing this
+/// type, and the metadata is cached and compared like any other value.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug, Default)]
+pub struct ModuleMetadata {
+    #[turbo_tasks(trace_ignore)]
+    entries: BTreeMap<RcStr, JsonValue>,
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleMetadata {
+    #[turbo_tasks::function]
+    pub fn empty() -> Vc<Self> {
+        Self::default().cell()
+    }
+}
+
+impl ModuleMetadata {
+    /// Sets the value of `key`, replacing the previous one.
+    pub fn insert<T: Serialize>(&mut self, key: &ModuleMetadataKey<T>, value: &T) -> Result<()> {
+        let value = serde_json::to_value(value)
+            .with_context(|| format!("Serializing module metadata {} failed", key.name))?;
+        self.entries.insert(key.name.into(), value);
+        Ok(())
+    }
+
+    /// Returns the value of `key`, or `None` when it wasn't set.
+    pub fn get<T: DeserializeOwned>(&self, key: &ModuleMetadataKey<T>) -> Result<Option<T>> {
+        self.entries
+            .get(key.name)
+            .map(|value| {
+                serde_json::from_value(value.clone())
+                    .with_context(|| format!("Deserializing module metadata {} failed", key.name))
+            })
+            .transpose()
+    }
+
+    pub fn contains_key<T>(&self, key: &ModuleMetadataKey<T>) -> bool {
+        self.entries.contains_key(key.name)
+    }
+
+    pub fn remove<T>(&mut self, key: &ModuleMetadataKey<T>) {
+        self.entries.remove(key.name);
+    }
+
+    /// Sets all values of `other`, replacing the values of the same keys.
+    pub fn extend(&mut self, other: &ModuleMetadata) {
+        self.entries.extend(
+            other
+                .entries
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use turbo_tasks::RcStr;
+
+    use super::{ModuleMetadata, ModuleMetadataKey};
+
+    const ACTIONS: ModuleMetadataKey<Vec<RcStr>> = ModuleMetadataKey::new("test/actions");
+    const COUNT: ModuleMetadataKey<u32> = ModuleMetadataKey::new("test/actions");
+    const POLYFILL: ModuleMetadataKey<bool> = ModuleMetadataKey::new("test/polyfill");
+
+    #[test]
+    fn round_trips_values() {
+        let mut metadata = ModuleMetadata::default();
+        assert!(metadata.is_empty());
+        assert_eq!(metadata.get(&ACTIONS).unwrap(), None);
+
+        metadata
+            .insert(&ACTIONS, &vec!["a".into(), "b".into()])
+            .unwrap();
+        assert!(metadata.contains_key(&ACTIONS));
+        assert_eq!(
+            metadata.get(&ACTIONS).unwrap(),
+            Some(vec![RcStr::from("a"), RcStr::from("b")])
+        );
+
+        metadata.insert(&ACTIONS, &vec!["c".into()]).unwrap();
+        assert_eq!(
+            metadata.get(&ACTIONS).unwrap(),
+            Some(vec![RcStr::from("c")])
+        );
+
+        metadata.remove(&ACTIONS);
+        assert!(metadata.is_empty());
+    }
+
+    #[test]
+    fn fails_on_a_type_mismatch() {
+        let mut metadata = ModuleMetadata::default();
+        metadata.insert(&ACTIONS, &vec!["a".into()]).unwrap();
+        let err = metadata.get(&COUNT).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Deserializing module metadata test/actions failed"));
+    }
+
+    #[test]
+    fn extends_with_other_metadata() {
+        let mut metadata = ModuleMetadata::default();
+        metadata.insert(&ACTIONS, &vec!["a".into()]).unwrap();
+        let mut other = ModuleMetadata::default();
+        other.insert(&ACTIONS, &vec!["b".into()]).unwrap();
+        other.insert(&POLYFILL, &true).unwrap();
+
+        metadata.extend(&other);
+        assert_eq!(
+            metadata.get(&ACTIONS).unwrap(),
+            Some(vec![RcStr::from("b")])
+        );
+        assert_eq!(metadata.get(&POLYFILL).unwrap(), Some(true));
+    }
+}
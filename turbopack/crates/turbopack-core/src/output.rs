@@ -3,9 +3,6 @@ use turbo_tasks::{FxIndexSet, ResolvedVc, Vc};
 
 use crate::{asset::Asset, ident::AssetIdent};
 
-#[turbo_tasks::value(transparent)]
-pub struct OptionOutputAsset(Option<ResolvedVc<Box<dyn OutputAsset>>>);
-
 /// An asset that should be outputted, e. g. written to disk or served from a
 /// server.
 #[turbo_tasks::value_trait]
@@ -0,0 +1,252 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::Result;
+use turbo_tasks::{
+    Completion, Completions, FxIndexSet, RcStr, ResolvedVc, State, TryFlatJoinIterExt,
+    ValueDefault, Vc,
+};
+use turbo_tasks_fs::{glob::Glob, to_sys_path, FileContent, FileSystemPath};
+
+use crate::output::OutputAssets;
+
+/// The paths of the files that were emitted for a set of output assets.
+#[turbo_tasks::value(transparent)]
+pub struct EmittedPaths(Vec<ResolvedVc<FileSystemPath>>);
+
+type EmittedPathsByEmitter = HashMap<Vc<OutputAssets>, FxIndexSet<RcStr>>;
+
+/// Deletes output files that are no longer emitted in dev and watch builds,
+/// e.g. the chunks of a route that was removed.
+///
+/// The files are tracked per emitter, i.e. the output assets operation that
+/// emitted them. After an emit, the files in the output directory are diffed
+/// against the union of the files of all live emitters, and the ones that no
+/// emitter emits anymore are deleted. An emitter that emits no files anymore,
+/// e.g. because its route was removed, is gone and its entry is dropped.
+///
+/// Only files that were emitted in this session or that were in the output
+/// directory before the first emit are ever deleted, so files that other tools
+/// write while turbopack is running are never touched. Files matching the
+/// `keep` glob of [OutputPruning::prune] are never deleted either.
+#[turbo_tasks::value]
+pub struct OutputPruning {
+    /// The files of each live emitter, relative to the output directory.
+    emitted: State<EmittedPathsByEmitter>,
+    /// The files emitted in this session that weren't deleted yet.
+    emitted_in_session: State<FxIndexSet<RcStr>>,
+    /// Whether the files of previous sessions were pruned already.
+    pruned_previous_session: State<bool>,
+}
+
+impl ValueDefault for OutputPruning {
+    fn value_default() -> Vc<Self> {
+        OutputPruning {
+            emitted: State::new(HashMap::new()),
+            emitted_in_session: State::new(FxIndexSet::default()),
+            pruned_previous_session: State::new(false),
+        }
+        .cell()
+    }
+}
+
+impl OutputPruning {
+    // This must not be a `#[turbo_tasks::function]` because it should be a
+    // singleton for each output directory.
+    pub fn new() -> Vc<Self> {
+        Self::value_default()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputPruning {
+    /// Records the files inside `output_dir` that `emitter` emits. This must
+    /// be awaited before the files are written, so a concurrent
+    /// [OutputPruning::prune] never deletes them.
+    #[turbo_tasks::function]
+    pub async fn record(
+        &self,
+        emitter: Vc<OutputAssets>,
+        emitted: Vc<EmittedPaths>,
+        output_dir: Vc<FileSystemPath>,
+    ) -> Result<Vc<Completion>> {
+        let output_dir = &*output_dir.await?;
+        let emitted = emitted
+            .await?
+            .iter()
+            .map(|path| async move { Ok(output_dir.get_path_to(&*path.await?).map(RcStr::from)) })
+            .try_flat_join()
+            .await?
+            .into_iter()
+            .collect::<FxIndexSet<_>>();
+        self.emitted_in_session.update_conditionally(|paths| {
+            let mut changed = false;
+            for path in &emitted {
+                changed |= paths.insert(path.clone());
+            }
+            changed
+        });
+        self.emitted.update_conditionally(|map| {
+            if emitted.is_empty() {
+                // The emitter is gone, e.g. its route was removed.
+                return map.remove(&emitter).is_some();
+            }
+            map.insert(emitter, emitted.clone()).as_ref() != Some(&emitted)
+        });
+        Ok(Completion::new())
+    }
+
+    /// Deletes the files inside `output_dir` that no live emitter emits
+    /// anymore, after `emit` has written the current ones.
+    ///
+    /// `keep` is matched against the path relative to `output_dir`.
+    #[turbo_tasks::function]
+    pub async fn prune(
+        &self,
+        emit: Vc<Completion>,
+        output_dir: Vc<FileSystemPath>,
+        keep: Vc<Glob>,
+    ) -> Result<Vc<Completion>> {
+        // Stale files are only deleted after the current ones were written.
+        emit.await?;
+
+        let live = self
+            .emitted
+            .get_untracked()
+            .values()
+            .flatten()
+            .cloned()
+            .collect::<FxIndexSet<_>>();
+        let prune_previous_session = !*self.pruned_previous_session.get_untracked();
+        let emitted_in_session = self.emitted_in_session.get_untracked().clone();
+        // Only the first prune needs to read the disk when no file was dropped.
+        if !prune_previous_session && emitted_in_session.iter().all(|path| live.contains(path)) {
+            return Ok(Completion::unchanged());
+        }
+        let Some(root) = to_sys_path(output_dir).await? else {
+            return Ok(Completion::unchanged());
+        };
+        let on_disk = files_on_disk(&root).await?;
+
+        let keep = &*keep.await?;
+        let stale = stale_files(&on_disk, &live, keep, |path| {
+            prune_previous_session || emitted_in_session.contains(path)
+        });
+        self.pruned_previous_session.set(true);
+        // The files that are not live anymore were deleted now, or are kept. Files that were
+        // recorded in the meantime are pruned next time.
+        self.emitted_in_session.update_conditionally(|paths| {
+            let len = paths.len();
+            paths.retain(|path| live.contains(path) || !emitted_in_session.contains(path));
+            paths.len() != len
+        });
+        if stale.is_empty() {
+            return Ok(Completion::unchanged());
+        }
+
+        let deletions = stale
+            .into_iter()
+            .map(|path| output_dir.join(path).write(FileContent::NotFound.cell()))
+            .collect();
+        Ok(Vc::<Completions>::cell(deletions).completed())
+    }
+}
+
+/// The files of `on_disk` that are not `live` and may be deleted, i.e. are
+/// `owned` and not kept.
+fn stale_files(
+    on_disk: &FxIndexSet<RcStr>,
+    live: &FxIndexSet<RcStr>,
+    keep: &Glob,
+    owned: impl Fn(&RcStr) -> bool,
+) -> Vec<RcStr> {
+    on_disk
+        .iter()
+        .filter(|path| !live.contains(*path) && owned(path) && !keep.execute(path))
+        .cloned()
+        .collect()
+}
+
+/// Lists the files inside `root` recursively, relative to it and separated by
+/// `/`. This reads the disk without tracking it, as pruning writes to the
+/// directory itself.
+pub async fn files_on_disk(root: &Path) -> Result<FxIndexSet<RcStr>> {
+    let mut files = FxIndexSet::default();
+    let mut directories = vec![(root.to_path_buf(), String::new())];
+    while let Some((directory, prefix)) = directories.pop() {
+        let mut entries = match tokio::fs::read_dir(&directory).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            let relative = format!("{prefix}{name}");
+            if entry.file_type().await?.is_dir() {
+                directories.push((entry.path(), format!("{relative}/")));
+            } else {
+                files.insert(relative.into());
+            }
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use turbo_tasks::{FxIndexSet, RcStr};
+    use turbo_tasks_fs::glob::Glob;
+
+    use super::{files_on_disk, stale_files};
+
+    fn set(paths: &[&str]) -> FxIndexSet<RcStr> {
+        paths.iter().map(|path| RcStr::from(*path)).collect()
+    }
+
+    #[test]
+    fn deletes_owned_files_that_are_not_live() {
+        let on_disk = set(&["chunks/a.js", "chunks/b.js", "chunks/c.js", "user.txt"]);
+        let live = set(&["chunks/a.js"]);
+        let owned = set(&["chunks/a.js", "chunks/b.js", "chunks/c.js"]);
+        let keep = Glob::parse("chunks/c.js").unwrap();
+        assert_eq!(
+            stale_files(&on_disk, &live, &keep, |path| owned.contains(path)),
+            vec![RcStr::from("chunks/b.js")]
+        );
+    }
+
+    #[test]
+    fn keeps_files_of_other_tools() {
+        let on_disk = set(&["cache/x", "types/app.d.ts", "trace", "chunks/a.js"]);
+        let live = set(&[]);
+        let keep = Glob::parse("{cache/**,types/**,trace}").unwrap();
+        assert_eq!(
+            stale_files(&on_disk, &live, &keep, |_| true),
+            vec![RcStr::from("chunks/a.js")]
+        );
+    }
+
+    #[tokio::test]
+    async fn lists_files_recursively() {
+        let root = std::env::temp_dir().join(format!("output-pruning-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("chunks/nested")).unwrap();
+        std::fs::write(root.join("a.js"), "").unwrap();
+        std::fs::write(root.join("chunks/nested/b.js"), "").unwrap();
+
+        let mut files = files_on_disk(&root)
+            .await
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![RcStr::from("a.js"), "chunks/nested/b.js".into()]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+        assert!(files_on_disk(&root).await.unwrap().is_empty());
+    }
+}
@@ -54,6 +54,59 @@ pub async fn read_package_json(path: Vc<FileSystemPath>) -> Result<Vc<OptionPack
     }
 }
 
+/// The value of a single top-level field of a package.json file.
+#[turbo_tasks::value(transparent, serialization = "none")]
+pub struct OptionPackageJsonField(Option<JsonValue>);
+
+/// Reads the top-level `field` of the package.json file at `path`, if both
+/// exist.
+///
+/// Resolving reads the fields it consults through this instead of
+/// [read_package_json], so a change to the package.json only invalidates the
+/// resolves whose consulted fields changed. Unchanged fields are equal to their
+/// previous value and short-circuit the invalidation.
+#[turbo_tasks::function]
+pub async fn read_package_json_field(
+    path: Vc<FileSystemPath>,
+    field: RcStr,
+) -> Result<Vc<OptionPackageJsonField>> {
+    Ok(Vc::cell(
+        read_package_json(path)
+            .await?
+            .as_ref()
+            .and_then(|package_json| package_json.get(field.as_str()))
+            .cloned(),
+    ))
+}
+
+/// The names of the packages a package depends on.
+#[turbo_tasks::value(transparent)]
+pub struct PackageDependencies(Vec<RcStr>);
+
+/// Reads the names of the `dependencies`, `peerDependencies` and
+/// `optionalDependencies` of the package.json file at `path`, e.g. to
+/// externalize them when building a library. Development dependencies are not
+/// included, as they are not installed along with the package.
+#[turbo_tasks::function]
+pub async fn read_package_dependencies(
+    path: Vc<FileSystemPath>,
+) -> Result<Vc<PackageDependencies>> {
+    let mut dependencies = Vec::new();
+    if let Some(package_json) = &*read_package_json(path).await? {
+        for field in ["dependencies", "peerDependencies", "optionalDependencies"] {
+            if let Some(JsonValue::Object(map)) = package_json.get(field) {
+                for name in map.keys() {
+                    let name = RcStr::from(name.as_str());
+                    if !dependencies.contains(&name) {
+                        dependencies.push(name);
+                    }
+                }
+            }
+        }
+    }
+    Ok(Vc::cell(dependencies))
+}
+
 /// Reusable Issue struct representing any problem with a `package.json`
 #[turbo_tasks::value(shared)]
 pub struct PackageJsonIssue {
@@ -83,3 +136,50 @@ impl Issue for PackageJsonIssue {
         Vc::cell(Some(StyledString::Text(self.error_message.clone()).cell()))
     }
 }
+
+/// Returns the name of the package that contains the module at `path`, and the
+/// directory of the package, e.g. `("react", "node_modules/react")` for
+/// `node_modules/react/index.js`. Nested `node_modules` directories, like the
+/// ones of pnpm, are resolved to the innermost package.
+pub fn package_of_path(path: &str) -> Option<(&str, &str)> {
+    const NODE_MODULES: &str = "node_modules/";
+    let start = path.rfind(NODE_MODULES)? + NODE_MODULES.len();
+    let rest = &path[start..];
+    let mut segments = rest.splitn(3, '/');
+    let first = segments.next()?;
+    let name_len = if first.starts_with('@') {
+        first.len() + 1 + segments.next()?.len()
+    } else {
+        first.len()
+    };
+    // The package needs to contain the module
+    if name_len >= rest.len() {
+        return None;
+    }
+    let name = &rest[..name_len];
+    Some((name, &path[..start + name_len]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::package_of_path;
+
+    #[test]
+    fn package_of_module_path() {
+        assert_eq!(
+            package_of_path("node_modules/react/index.js"),
+            Some(("react", "node_modules/react"))
+        );
+        assert_eq!(
+            package_of_path("node_modules/@scope/pkg/lib/index.js"),
+            Some(("@scope/pkg", "node_modules/@scope/pkg"))
+        );
+        assert_eq!(
+            package_of_path("node_modules/.pnpm/a@1.0.0/node_modules/a/index.js"),
+            Some(("a", "node_modules/.pnpm/a@1.0.0/node_modules/a"))
+        );
+        assert_eq!(package_of_path("src/index.js"), None);
+        assert_eq!(package_of_path("node_modules/react"), None);
+        assert_eq!(package_of_path("node_modules/@scope/pkg"), None);
+    }
+}
@@ -30,20 +30,21 @@ use self::{
 };
 use crate::{
     context::AssetContext,
+    data_uri_source::{is_data_uri, DataUriSource},
     file_source::FileSource,
     issue::{
         module::emit_unknown_module_type_error, resolve::ResolvingIssue, IssueExt, IssueSource,
     },
-    module::{Module, Modules, OptionModule},
+    module::{Module, Modules},
     output::{OutputAsset, OutputAssets},
-    package_json::{read_package_json, PackageJsonIssue},
+    package_json::{read_package_json_field, OptionPackageJsonField, PackageJsonIssue},
     raw_module::RawModule,
     reference_type::ReferenceType,
     resolve::{
         pattern::{read_matches, PatternMatch},
         plugin::AfterResolvePlugin,
     },
-    source::{OptionSource, Source, Sources},
+    source::{Source, Sources},
 };
 
 mod alias_map;
@@ -326,13 +327,13 @@ impl ModuleResolveResult {
     }
 
     #[turbo_tasks::function]
-    pub async fn first_module(&self) -> Result<Vc<OptionModule>> {
+    pub async fn first_module(&self) -> Result<Vc<Option<ResolvedVc<Box<dyn Module>>>>> {
         for (_, item) in self.primary.iter() {
             if let Some(module) = item.as_module().await? {
-                return Ok(Vc::cell(Some(module)));
+                return Ok(Vc::some(module));
             }
         }
-        Ok(Vc::cell(None))
+        Ok(Vc::none())
     }
 
     /// Returns a set (no duplicates) of primary modules in the result. All
@@ -837,7 +838,7 @@ impl ResolveResult {
     }
 
     #[turbo_tasks::function]
-    pub fn first_source(&self) -> Vc<OptionSource> {
+    pub fn first_source(&self) -> Vc<Option<ResolvedVc<Box<dyn Source>>>> {
         Vc::cell(self.primary.iter().find_map(|(_, item)| {
             if let &ResolveResultItem::Source(a) = item {
                 Some(a)
@@ -1043,13 +1044,8 @@ enum ExportsFieldResult {
 /// appropriate [AliasMap] for lookups.
 #[turbo_tasks::function]
 async fn exports_field(package_json_path: Vc<FileSystemPath>) -> Result<Vc<ExportsFieldResult>> {
-    let read = read_package_json(package_json_path).await?;
-    let package_json = match &*read {
-        Some(json) => json,
-        None => return Ok(ExportsFieldResult::None.cell()),
-    };
-
-    let Some(exports) = package_json.get("exports") else {
+    let exports = read_package_json_field(package_json_path, "exports".into()).await?;
+    let Some(exports) = &*exports else {
         return Ok(ExportsFieldResult::None.cell());
     };
     match exports.try_into() {
@@ -1084,13 +1080,8 @@ async fn imports_field(lookup_path: Vc<FileSystemPath>) -> Result<Vc<ImportsFiel
         return Ok(ImportsFieldResult::None.cell());
     };
 
-    let read = read_package_json(**package_json_path).await?;
-    let package_json = match &*read {
-        Some(json) => json,
-        None => return Ok(ImportsFieldResult::None.cell()),
-    };
-
-    let Some(imports) = package_json.get("imports") else {
+    let imports = read_package_json_field(**package_json_path, "imports".into()).await?;
+    let Some(imports) = &*imports else {
         return Ok(ImportsFieldResult::None.cell());
     };
     match imports.try_into() {
@@ -1167,14 +1158,13 @@ pub async fn find_context_file_or_package_key(
     let mut refs = Vec::new();
     let package_json_path = lookup_path.join("package.json".into());
     if let Some(package_json_path) = exists(package_json_path, &mut refs).await? {
-        if let Some(json) = &*read_package_json(*package_json_path).await? {
-            if json.get(&**package_key).is_some() {
-                return Ok(FindContextFileResult::Found(
-                    package_json_path.to_resolved().await?,
-                    refs,
-                )
-                .into());
-            }
+        if read_package_json_field(*package_json_path, package_key.into_value())
+            .await?
+            .is_some()
+        {
+            return Ok(
+                FindContextFileResult::Found(package_json_path.to_resolved().await?, refs).into(),
+            );
         }
     }
     for name in &*names.await? {
@@ -1854,11 +1844,19 @@ async fn resolve_internal_inline(
                 fragment: _,
             } => {
                 let uri: RcStr = format!("{}{}", protocol, remainder).into();
-                ResolveResult::primary_with_key(
-                    RequestKey::new(uri.clone()),
-                    ResolveResultItem::External(uri, ExternalType::Url),
-                )
-                .into()
+                if is_data_uri(&uri) {
+                    let source = DataUriSource::new(uri.clone(), *lookup_path)
+                        .to_resolved()
+                        .await?;
+                    ResolveResult::source_with_key(RequestKey::new(uri), ResolvedVc::upcast(source))
+                        .into()
+                } else {
+                    ResolveResult::primary_with_key(
+                        RequestKey::new(uri.clone()),
+                        ResolveResultItem::External(uri, ExternalType::Url),
+                    )
+                    .into()
+                }
             }
             Request::Unknown { path } => {
                 if !has_alias {
@@ -1916,37 +1914,36 @@ async fn resolve_into_folder(
     for resolve_into_package in options_value.into_package.iter() {
         match resolve_into_package {
             ResolveIntoPackage::MainField { field: name } => {
-                if let Some(package_json) = &*read_package_json(package_json_path).await? {
-                    if let Some(field_value) = package_json[name.as_str()].as_str() {
-                        let normalized_request: RcStr = normalize_request(field_value).into();
-                        if normalized_request.is_empty()
-                            || &*normalized_request == "."
-                            || &*normalized_request == "./"
-                        {
-                            continue;
-                        }
-                        let request = Request::parse(Value::new(normalized_request.into()))
-                            .to_resolved()
-                            .await?;
+                let field_value = read_package_json_field(package_json_path, name.clone()).await?;
+                if let Some(field_value) = field_value.as_ref().and_then(|value| value.as_str()) {
+                    let normalized_request: RcStr = normalize_request(field_value).into();
+                    if normalized_request.is_empty()
+                        || &*normalized_request == "."
+                        || &*normalized_request == "./"
+                    {
+                        continue;
+                    }
+                    let request = Request::parse(Value::new(normalized_request.into()))
+                        .to_resolved()
+                        .await?;
 
-                        // main field will always resolve not fully specified
-                        let options = if options_value.fully_specified {
-                            options.with_fully_specified(false).resolve().await?
-                        } else {
-                            options
-                        };
-                        let result = &*resolve_internal_inline(package_path, request, options)
-                            .await?
-                            .await?;
-                        // we are not that strict when a main field fails to resolve
-                        // we continue to try other alternatives
-                        if !result.is_unresolvable_ref() {
-                            let mut result = result.with_request_ref(".".into());
-                            result.add_affecting_source_ref(ResolvedVc::upcast(
-                                FileSource::new(package_json_path).to_resolved().await?,
-                            ));
-                            return Ok(result.cell());
-                        }
+                    // main field will always resolve not fully specified
+                    let options = if options_value.fully_specified {
+                        options.with_fully_specified(false).resolve().await?
+                    } else {
+                        options
+                    };
+                    let result = &*resolve_internal_inline(package_path, request, options)
+                        .await?
+                        .await?;
+                    // we are not that strict when a main field fails to resolve
+                    // we continue to try other alternatives
+                    if !result.is_unresolvable_ref() {
+                        let mut result = result.with_request_ref(".".into());
+                        result.add_affecting_source_ref(ResolvedVc::upcast(
+                            FileSource::new(package_json_path).to_resolved().await?,
+                        ));
+                        return Ok(result.cell());
                     }
                 };
             }
@@ -2211,29 +2208,14 @@ async fn apply_in_package(
             continue;
         };
 
-        let read = read_package_json(**package_json_path).await?;
-        let Some(package_json) = &*read else {
-            continue;
-        };
-
-        let Some(field_value) = package_json[field.as_str()].as_object() else {
-            continue;
-        };
-
         let package_path = package_json_path.parent().resolve().await?;
 
         let Some(request) = get_request(&*package_path.await?) else {
             continue;
         };
 
-        let value = if let Some(value) = field_value.get(&*request) {
-            value
-        } else if let Some(request) = request.strip_prefix("./") {
-            let Some(value) = field_value.get(request) else {
-                continue;
-            };
-            value
-        } else {
+        let value = alias_field_entry(**package_json_path, field.clone(), request.clone()).await?;
+        let Some(value) = &*value else {
             continue;
         };
 
@@ -2252,7 +2234,12 @@ async fn apply_in_package(
         }
 
         if let Some(value) = value.as_str() {
-            if value == &*request {
+            if value == &*request
+                || (request.starts_with("./")
+                    && value.starts_with('.')
+                    && relative_alias_field_candidates(&request)
+                        .contains(&&*normalize_alias_field_key(value)))
+            {
                 // This would be a cycle, so we ignore it
                 return Ok(None);
             }
@@ -2288,6 +2275,71 @@ async fn apply_in_package(
     Ok(None)
 }
 
+/// Looks up `request` in the alias field `field` (e.g. `browser`) of the
+/// package.json at `package_json_path`. Only this entry is consulted, so
+/// changes to other entries of the alias field don't invalidate the resolve.
+///
+/// Module requests only match their exact key. Relative requests (relative to
+/// the package) also match keys that are not normalized (`./lib/../file.js`),
+/// keys without extension (`./file`) and the directory of an index file
+/// (`./lib` for `./lib/index.js`), in that order of precedence.
+#[turbo_tasks::function]
+async fn alias_field_entry(
+    package_json_path: Vc<FileSystemPath>,
+    field: RcStr,
+    request: RcStr,
+) -> Result<Vc<OptionPackageJsonField>> {
+    let field_value = read_package_json_field(package_json_path, field).await?;
+    let Some(field_value) = field_value.as_ref().and_then(|value| value.as_object()) else {
+        return Ok(Vc::cell(None));
+    };
+    if let Some(value) = field_value.get(&*request) {
+        return Ok(Vc::cell(Some(value.clone())));
+    }
+    if !request.starts_with("./") {
+        return Ok(Vc::cell(None));
+    }
+    let candidates = relative_alias_field_candidates(&request);
+    let value = candidates
+        .iter()
+        .find_map(|candidate| {
+            field_value.iter().find_map(|(key, value)| {
+                (normalize_alias_field_key(key) == *candidate).then_some(value)
+            })
+        })
+        .cloned();
+    Ok(Vc::cell(value))
+}
+
+/// The relative keys of an alias field that `request` matches, in order of
+/// precedence: the request itself, without extension and the directory of an
+/// index file.
+fn relative_alias_field_candidates(request: &str) -> Vec<&str> {
+    let mut candidates = vec![request];
+    let (dir, file_name) = request.rsplit_once('/').unwrap_or((".", request));
+    if let Some((stem, _)) = file_name
+        .rsplit_once('.')
+        .filter(|(stem, _)| !stem.is_empty())
+    {
+        candidates.push(&request[..request.len() - file_name.len() + stem.len()]);
+        if stem == "index" {
+            candidates.push(dir);
+        }
+    }
+    candidates
+}
+
+/// Normalizes a key of an alias field to a `./`-prefixed path relative to the
+/// package. Keys without `./` prefix refer to files of the package too when
+/// the request is relative.
+fn normalize_alias_field_key(key: &str) -> String {
+    let key = normalize_request(key);
+    match key.strip_suffix('/') {
+        Some(key) if !key.is_empty() => key.to_string(),
+        _ => key,
+    }
+}
+
 #[turbo_tasks::value]
 enum FindSelfReferencePackageResult {
     Found {
@@ -2305,16 +2357,15 @@ async fn find_self_reference(
 ) -> Result<Vc<FindSelfReferencePackageResult>> {
     let package_json_context = find_context_file(lookup_path, package_json()).await?;
     if let FindContextFileResult::Found(package_json_path, _refs) = &*package_json_context {
-        let read = read_package_json(**package_json_path).await?;
-        if let Some(json) = &*read {
-            if json.get("exports").is_some() {
-                if let Some(name) = json["name"].as_str() {
-                    return Ok(FindSelfReferencePackageResult::Found {
-                        name: name.to_string(),
-                        package_path: package_json_path.parent(),
-                    }
-                    .cell());
+        let exports = read_package_json_field(**package_json_path, "exports".into()).await?;
+        if exports.is_some() {
+            let name = read_package_json_field(**package_json_path, "name".into()).await?;
+            if let Some(name) = name.as_ref().and_then(|name| name.as_str()) {
+                return Ok(FindSelfReferencePackageResult::Found {
+                    name: name.to_string(),
+                    package_path: package_json_path.parent(),
                 }
+                .cell());
             }
         }
     }
@@ -3006,3 +3057,132 @@ impl ValueToString for ModulePart {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::Path,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Once,
+        },
+    };
+
+    use anyhow::Result;
+    use turbo_tasks::{RcStr, TurboTasks, Vc};
+    use turbo_tasks_fs::{DiskFileSystem, FileSystem, FileSystemPath};
+    use turbo_tasks_memory::MemoryBackend;
+
+    use super::alias_field_entry;
+    use crate::package_json::read_package_json_field;
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    static EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Stands in for a resolve task that consults the `main` field and the
+    /// `browser` entry of `request`, and counts its executions.
+    #[turbo_tasks::function]
+    async fn resolve_with_fields(
+        package_json_path: Vc<FileSystemPath>,
+        request: RcStr,
+    ) -> Result<Vc<RcStr>> {
+        EXECUTIONS.fetch_add(1, Ordering::SeqCst);
+        let main = read_package_json_field(package_json_path, "main".into()).await?;
+        let browser = alias_field_entry(package_json_path, "browser".into(), request).await?;
+        Ok(Vc::cell(
+            format!(
+                "{} {}",
+                main.as_ref()
+                    .and_then(|main| main.as_str())
+                    .unwrap_or_default(),
+                browser
+                    .as_ref()
+                    .and_then(|browser| browser.as_str())
+                    .unwrap_or_default()
+            )
+            .into(),
+        ))
+    }
+
+    async fn resolve(fs: Vc<DiskFileSystem>) -> Result<RcStr> {
+        let package_json_path = fs.root().join("package.json".into());
+        Ok(resolve_with_fields(package_json_path, "./server.js".into())
+            .strongly_consistent()
+            .await?
+            .clone_value())
+    }
+
+    fn write_package_json(root: &Path, package_json: serde_json::Value) {
+        fs::write(root.join("package.json"), package_json.to_string()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn resolving_only_depends_on_the_consulted_fields() {
+        register();
+        let root = tempfile::tempdir().unwrap();
+        let root_path = root.path().to_path_buf();
+        write_package_json(
+            &root_path,
+            serde_json::json!({
+                "version": "1.0.0",
+                "main": "index.js",
+                "browser": { "./server.js": "./client.js", "./other.js": false }
+            }),
+        );
+
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            let fs = DiskFileSystem::new("test".into(), root_path.to_string_lossy().into(), vec![]);
+            assert_eq!(&*resolve(fs).await?, "index.js ./client.js");
+            assert_eq!(EXECUTIONS.load(Ordering::SeqCst), 1);
+
+            // Other fields and other entries of the browser field are not consulted
+            write_package_json(
+                &root_path,
+                serde_json::json!({
+                    "version": "2.0.0",
+                    "main": "index.js",
+                    "browser": {
+                        "./server.js": "./client.js",
+                        "./other.js": "./other.browser.js"
+                    }
+                }),
+            );
+            fs.await?.invalidate();
+            assert_eq!(&*resolve(fs).await?, "index.js ./client.js");
+            assert_eq!(EXECUTIONS.load(Ordering::SeqCst), 1);
+
+            write_package_json(
+                &root_path,
+                serde_json::json!({
+                    "version": "2.0.0",
+                    "main": "index.js",
+                    "browser": { "./server.js": "./server.browser.js" }
+                }),
+            );
+            fs.await?.invalidate();
+            assert_eq!(&*resolve(fs).await?, "index.js ./server.browser.js");
+            assert_eq!(EXECUTIONS.load(Ordering::SeqCst), 2);
+
+            write_package_json(
+                &root_path,
+                serde_json::json!({
+                    "version": "2.0.0",
+                    "main": "main.js",
+                    "browser": { "./server.js": "./server.browser.js" }
+                }),
+            );
+            fs.await?.invalidate();
+            assert_eq!(&*resolve(fs).await?, "main.js ./server.browser.js");
+            assert_eq!(EXECUTIONS.load(Ordering::SeqCst), 3);
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
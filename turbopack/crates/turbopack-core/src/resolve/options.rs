@@ -280,6 +280,20 @@ impl ImportMap {
             .insert(AliasPattern::wildcard(prefix, suffix), mapping);
     }
 
+    /// Inserts aliases that resolve the package `name` and its subpaths, e.g.
+    /// `name/feature`, to externals of the type `ty`.
+    pub fn insert_external_package<'a>(&mut self, name: impl Into<RcStr> + 'a, ty: ExternalType) {
+        let name: RcStr = name.into();
+        self.insert_exact_alias(
+            name.clone(),
+            ImportMapping::External(Some(name.clone()), ty).resolved_cell(),
+        );
+        self.insert_wildcard_alias(
+            format!("{name}/"),
+            ImportMapping::External(Some(format!("{name}/*").into()), ty).resolved_cell(),
+        );
+    }
+
     /// Inserts an alias that resolves an prefix always from a certain location
     /// to create a singleton.
     pub fn insert_singleton_alias<'a>(
@@ -1,9 +1,9 @@
 use anyhow::Result;
-use turbo_tasks::{RcStr, Upcast, Value, Vc};
+use turbo_tasks::{RcStr, ResolvedVc, Upcast, Value, Vc};
 use turbo_tasks_fs::FileSystemPath;
 
 use super::{options::ResolveOptions, parse::Request, ModuleResolveResult};
-use crate::{context::AssetContext, module::OptionModule, reference_type::ReferenceType};
+use crate::{context::AssetContext, module::Module, reference_type::ReferenceType};
 
 /// A location where resolving can occur from. It carries some meta information
 /// that are needed for resolving from here.
@@ -21,9 +21,12 @@ pub trait ResolveOrigin {
 
     /// Get an inner asset form this origin that doesn't require resolving but
     /// is directly attached
-    fn get_inner_asset(self: Vc<Self>, request: Vc<Request>) -> Vc<OptionModule> {
+    fn get_inner_asset(
+        self: Vc<Self>,
+        request: Vc<Request>,
+    ) -> Vc<Option<ResolvedVc<Box<dyn Module>>>> {
         let _ = request;
-        Vc::cell(None)
+        Vc::none()
     }
 }
 
@@ -155,7 +158,7 @@ impl ResolveOrigin for ResolveOriginWithTransition {
     }
 
     #[turbo_tasks::function]
-    fn get_inner_asset(&self, request: Vc<Request>) -> Vc<OptionModule> {
+    fn get_inner_asset(&self, request: Vc<Request>) -> Vc<Option<ResolvedVc<Box<dyn Module>>>> {
         self.previous.get_inner_asset(request)
     }
 }
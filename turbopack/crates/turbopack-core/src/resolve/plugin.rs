@@ -43,6 +43,8 @@ impl AfterResolvePluginCondition {
 pub enum BeforeResolvePluginCondition {
     Request(ResolvedVc<Glob>),
     Modules(ResolvedVc<Vec<RcStr>>),
+    /// Matches URI requests with one of the protocols, e.g. `https:`.
+    Protocols(ResolvedVc<Vec<RcStr>>),
 }
 
 #[turbo_tasks::value_impl]
@@ -56,6 +58,11 @@ impl BeforeResolvePluginCondition {
     pub fn from_request_glob(glob: ResolvedVc<Glob>) -> Vc<Self> {
         BeforeResolvePluginCondition::Request(glob).cell()
     }
+
+    #[turbo_tasks::function]
+    pub fn from_protocols(protocols: ResolvedVc<Vec<RcStr>>) -> Vc<Self> {
+        BeforeResolvePluginCondition::Protocols(protocols).cell()
+    }
 }
 
 impl BeforeResolvePluginCondition {
@@ -72,6 +79,13 @@ impl BeforeResolvePluginCondition {
                     false
                 }
             }
+            BeforeResolvePluginCondition::Protocols(protocols) => {
+                if let Request::Uri { protocol, .. } = &*request.await? {
+                    protocols.await?.iter().any(|p| p.as_str() == protocol)
+                } else {
+                    false
+                }
+            }
         })
     }
 }
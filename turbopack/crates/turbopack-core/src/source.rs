@@ -11,9 +11,6 @@ pub trait Source: Asset {
     fn ident(&self) -> Vc<AssetIdent>;
 }
 
-#[turbo_tasks::value(transparent)]
-pub struct OptionSource(Option<ResolvedVc<Box<dyn Source>>>);
-
 #[turbo_tasks::value(transparent)]
 pub struct Sources(Vec<ResolvedVc<Box<dyn Source>>>);
 
@@ -1,8 +1,17 @@
-use std::sync::Arc;
+use std::{
+    collections::VecDeque,
+    fs,
+    hash::Hash,
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
 
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use turbo_tasks::{
-    debug::ValueDebugFormat, trace::TraceRawVcs, IntoTraitRef, RcStr, ReadRef, State, TraitRef, Vc,
+    debug::ValueDebugFormat, trace::TraceRawVcs, FxIndexMap, IntoTraitRef, RcStr, ReadRef, State,
+    TraitRef, Vc,
 };
 use turbo_tasks_fs::{FileContent, LinkType};
 use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64};
@@ -268,3 +277,390 @@ impl VersionState {
         Ok(())
     }
 }
+
+/// The number of versions kept by a [`VersionHistory`].
+const VERSION_HISTORY_CAPACITY: usize = 256;
+
+/// The number of partial updates kept by a [`VersionHistory`].
+const UPDATE_LOG_CAPACITY: usize = 256;
+
+/// Where a client that reconnects is brought up to date from.
+pub enum ResumedVersion {
+    /// The client is at this version, and the next update is computed from it.
+    Version(TraitRef<Box<dyn Version>>),
+    /// The client's version isn't known anymore, but it is brought to the
+    /// current version by applying these partial update instructions in order.
+    Replay(Vec<Arc<serde_json::Value>>),
+}
+
+/// A partial update that was sent to a client, as it's written to the update
+/// log.
+#[derive(Serialize, Deserialize)]
+struct RecordedUpdate {
+    resource: RcStr,
+    from: RcStr,
+    to: RcStr,
+    instruction: Arc<serde_json::Value>,
+}
+
+/// The partial updates that were sent to clients, by resource and the ids of
+/// the versions they update from and to.
+#[derive(Default)]
+struct UpdateLog {
+    updates: FxIndexMap<(RcStr, RcStr, RcStr), Arc<serde_json::Value>>,
+    /// The file the updates are appended to, if they are persisted.
+    path: Option<PathBuf>,
+    /// The number of lines in the file, which is rewritten with the kept
+    /// updates when it has grown too large.
+    lines: usize,
+}
+
+/// Remembers the most recent versions that were sent to clients by their id.
+///
+/// A [`Version`] can't be recovered from its id, so a client that reconnects
+/// with the id of the last update it applied can only be brought up to date
+/// with a partial update when the version is still known here.
+///
+/// The versions are kept in memory only. The partial updates that were sent
+/// are remembered too, and can be persisted with
+/// [`VersionHistory::persistent`]. After a restart, a client can then be
+/// brought up to date by replaying the updates it missed, from the version it
+/// is at to the current one.
+#[derive(Default)]
+pub struct VersionHistory {
+    versions: Mutex<FxIndexMap<(RcStr, RcStr), TraitRef<Box<dyn Version>>>>,
+    log: Mutex<UpdateLog>,
+}
+
+impl VersionHistory {
+    /// Creates a history that persists the partial updates sent to clients in
+    /// the file at `path`, and reads the ones persisted by a previous server.
+    pub fn persistent(path: PathBuf) -> Self {
+        let mut log = UpdateLog::default();
+        if let Ok(file) = fs::File::open(&path) {
+            for line in BufReader::new(file).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                log.lines += 1;
+                // The last line is incomplete when the server exited while writing it.
+                if let Ok(update) = serde_json::from_str::<RecordedUpdate>(&line) {
+                    remember(
+                        &mut log.updates,
+                        (update.resource, update.from, update.to),
+                        update.instruction,
+                        UPDATE_LOG_CAPACITY,
+                    );
+                }
+            }
+        }
+        log.path = Some(path);
+        Self {
+            versions: Default::default(),
+            log: Mutex::new(log),
+        }
+    }
+
+    /// Records `version` as a version of `resource` that a client might have
+    /// applied, and returns its id.
+    pub async fn insert(
+        &self,
+        resource: RcStr,
+        version: TraitRef<Box<dyn Version>>,
+    ) -> Result<RcStr> {
+        let id = TraitRef::cell(version.clone()).id().await?.clone_value();
+        remember(
+            &mut self.versions.lock().unwrap(),
+            (resource, id.clone()),
+            version,
+            VERSION_HISTORY_CAPACITY,
+        );
+        Ok(id)
+    }
+
+    /// Records that `update` was sent to a client that was at the version with
+    /// the id `from`, like [`VersionHistory::insert`] does for the version it
+    /// updates to, and returns the id of that version.
+    pub async fn insert_update(
+        &self,
+        resource: RcStr,
+        from: RcStr,
+        update: &PartialUpdate,
+    ) -> Result<RcStr> {
+        let to = self.insert(resource.clone(), update.to.clone()).await?;
+        let mut log = self.log.lock().unwrap();
+        let key = (resource, from, to.clone());
+        if log.updates.get(&key) == Some(&update.instruction) {
+            return Ok(to);
+        }
+        remember(
+            &mut log.updates,
+            key.clone(),
+            update.instruction.clone(),
+            UPDATE_LOG_CAPACITY,
+        );
+        // The log only helps clients to resume after a restart, so failing to write it must
+        // not interrupt the updates.
+        let _ = log.persist(key);
+        Ok(to)
+    }
+
+    /// Returns the version a client that last applied `client_version` is at,
+    /// given that `current` is the latest version of `resource`.
+    ///
+    /// Clients that don't know their version are assumed to be up to date. When
+    /// the client's version isn't known, but the updates from it to the current
+    /// version were recorded, they are replayed. Otherwise, a
+    /// [`NotFoundVersion`] is returned, which causes a total update.
+    pub async fn resume(
+        &self,
+        resource: &RcStr,
+        current: TraitRef<Box<dyn Version>>,
+        client_version: Option<&str>,
+    ) -> Result<ResumedVersion> {
+        let Some(client_version) = client_version else {
+            return Ok(ResumedVersion::Version(current));
+        };
+        // Version ids are derived from the content, so they stay the same when
+        // the server restarts without anything having changed.
+        let current_id = TraitRef::cell(current.clone()).id().await?;
+        if *current_id == client_version {
+            return Ok(ResumedVersion::Version(current));
+        }
+        let known = self
+            .versions
+            .lock()
+            .unwrap()
+            .get(&(resource.clone(), client_version.into()))
+            .cloned();
+        if let Some(version) = known {
+            return Ok(ResumedVersion::Version(version));
+        }
+        let replay = self
+            .log
+            .lock()
+            .unwrap()
+            .replay(resource, client_version, &current_id);
+        match replay {
+            Some(instructions) => Ok(ResumedVersion::Replay(instructions)),
+            None => Ok(ResumedVersion::Version(
+                Vc::upcast::<Box<dyn Version>>(NotFoundVersion::new())
+                    .into_trait_ref()
+                    .await?,
+            )),
+        }
+    }
+}
+
+impl UpdateLog {
+    /// Appends the update with `key` to the file, or rewrites the file with all
+    /// kept updates when it has grown too large.
+    fn persist(&mut self, key: (RcStr, RcStr, RcStr)) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let line = |(resource, from, to): &(RcStr, RcStr, RcStr),
+                    instruction: &Arc<serde_json::Value>| {
+            serde_json::to_string(&RecordedUpdate {
+                resource: resource.clone(),
+                from: from.clone(),
+                to: to.clone(),
+                instruction: Arc::clone(instruction),
+            })
+        };
+        if self.lines < 2 * UPDATE_LOG_CAPACITY {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            writeln!(file, "{}", line(&key, &self.updates[&key])?)?;
+            self.lines += 1;
+        } else {
+            let mut contents = String::new();
+            for (key, instruction) in &self.updates {
+                contents.push_str(&line(key, instruction)?);
+                contents.push('\n');
+            }
+            let temp_path = path.with_extension("tmp");
+            fs::write(&temp_path, contents)?;
+            fs::rename(temp_path, path)?;
+            self.lines = self.updates.len();
+        }
+        Ok(())
+    }
+
+    /// Returns the instructions of the shortest chain of recorded updates of
+    /// `resource` from the version `from` to the version `to`.
+    fn replay(
+        &self,
+        resource: &RcStr,
+        from: &str,
+        to: &str,
+    ) -> Option<Vec<Arc<serde_json::Value>>> {
+        // The update that first reached each version, from which the chain is collected backwards.
+        let mut reached_by: FxIndexMap<&str, (&str, &Arc<serde_json::Value>)> =
+            FxIndexMap::default();
+        let mut queue = VecDeque::from([from]);
+        while let Some(version) = queue.pop_front() {
+            if version == to {
+                let mut instructions = Vec::new();
+                let mut version = to;
+                while version != from {
+                    let (previous, instruction) = reached_by[version];
+                    instructions.push(instruction.clone());
+                    version = previous;
+                }
+                instructions.reverse();
+                return Some(instructions);
+            }
+            for ((update_resource, update_from, update_to), instruction) in &self.updates {
+                if update_resource == resource
+                    && update_from == version
+                    && update_to != from
+                    && !reached_by.contains_key(update_to.as_str())
+                {
+                    reached_by.insert(update_to, (version, instruction));
+                    queue.push_back(update_to);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Inserts `value` as the most recent entry of `entries`, and drops the least
+/// recent entries beyond `capacity`.
+fn remember<K: Hash + Eq, V>(entries: &mut FxIndexMap<K, V>, key: K, value: V, capacity: usize) {
+    entries.shift_remove(&key);
+    entries.insert(key, value);
+    while entries.len() > capacity {
+        entries.shift_remove_index(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Once};
+
+    use anyhow::{bail, Result};
+    use serde_json::json;
+    use turbo_tasks::{FxIndexMap, IntoTraitRef, RcStr, TraitRef, TurboTasks, Vc};
+    use turbo_tasks_memory::MemoryBackend;
+
+    use super::{
+        remember, FileHashVersion, PartialUpdate, ResumedVersion, UpdateLog, Version,
+        VersionHistory, UPDATE_LOG_CAPACITY,
+    };
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    async fn version(hash: &str) -> Result<TraitRef<Box<dyn Version>>> {
+        Vc::upcast::<Box<dyn Version>>(FileHashVersion { hash: hash.into() }.cell())
+            .into_trait_ref()
+            .await
+    }
+
+    async fn update(to: &str, instruction: &str) -> Result<PartialUpdate> {
+        Ok(PartialUpdate {
+            to: version(to).await?,
+            instruction: Arc::new(json!(instruction)),
+        })
+    }
+
+    #[test]
+    fn remembers_the_most_recent_entries() {
+        let mut entries = FxIndexMap::default();
+        remember(&mut entries, "a", 1, 2);
+        remember(&mut entries, "b", 2, 2);
+        // Inserting a known entry again makes it the most recent one.
+        remember(&mut entries, "a", 3, 2);
+        remember(&mut entries, "c", 4, 2);
+        assert_eq!(
+            entries.into_iter().collect::<Vec<_>>(),
+            vec![("a", 3), ("c", 4)]
+        );
+    }
+
+    #[tokio::test]
+    async fn replays_the_missed_updates_after_a_restart() {
+        register();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hmr-updates");
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            let resource: RcStr = "chunk-list".into();
+            let history = VersionHistory::persistent(path.clone());
+            history
+                .insert(resource.clone(), version("a").await?)
+                .await?;
+            history
+                .insert_update(resource.clone(), "a".into(), &update("b", "a to b").await?)
+                .await?;
+            history
+                .insert_update(resource.clone(), "b".into(), &update("c", "b to c").await?)
+                .await?;
+
+            // The versions are gone after a restart, only the updates are read again.
+            let history = VersionHistory::persistent(path);
+            let ResumedVersion::Replay(instructions) = history
+                .resume(&resource, version("c").await?, Some("a"))
+                .await?
+            else {
+                bail!("expected the missed updates to be replayed");
+            };
+            assert_eq!(
+                instructions,
+                vec![Arc::new(json!("a to b")), Arc::new(json!("b to c"))]
+            );
+
+            // The content changed while the server wasn't running.
+            let ResumedVersion::Version(from) = history
+                .resume(&resource, version("d").await?, Some("a"))
+                .await?
+            else {
+                bail!("expected a version to update from");
+            };
+            assert_eq!(*TraitRef::cell(from).id().await?, "");
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn rewrites_the_update_log_when_it_has_grown_too_large() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hmr-updates");
+        let mut log = UpdateLog {
+            path: Some(path.clone()),
+            ..Default::default()
+        };
+        for i in 0..=2 * UPDATE_LOG_CAPACITY {
+            let key = (
+                "chunk-list".into(),
+                i.to_string().into(),
+                (i + 1).to_string().into(),
+            );
+            remember(
+                &mut log.updates,
+                key.clone(),
+                Arc::new(json!(i)),
+                UPDATE_LOG_CAPACITY,
+            );
+            log.persist(key).unwrap();
+        }
+        assert_eq!(log.lines, UPDATE_LOG_CAPACITY);
+
+        let log = VersionHistory::persistent(path).log.into_inner().unwrap();
+        assert_eq!(log.updates.len(), UPDATE_LOG_CAPACITY);
+        let instructions = log.replay(&"chunk-list".into(), "257", "513").unwrap();
+        assert_eq!(instructions.len(), UPDATE_LOG_CAPACITY);
+        assert_eq!(instructions[0], Arc::new(json!(257)));
+    }
+}
@@ -6,7 +6,7 @@ use turbo_tasks_fs::File;
 use turbopack_core::{
     asset::{Asset, AssetContent},
     chunk::{Chunk, ChunkItem, ChunkingContext},
-    code_builder::{Code, CodeBuilder},
+    code_builder::{apply_source_map_source_template, Code, CodeBuilder},
     ident::AssetIdent,
     introspect::Introspectable,
     output::{OutputAsset, OutputAssets},
@@ -144,8 +144,12 @@ impl Asset for SingleItemCssChunk {
 #[turbo_tasks::value_impl]
 impl GenerateSourceMap for SingleItemCssChunk {
     #[turbo_tasks::function]
-    fn generate_source_map(self: Vc<Self>) -> Vc<OptionSourceMap> {
-        self.code().generate_source_map()
+    async fn generate_source_map(self: Vc<Self>) -> Result<Vc<OptionSourceMap>> {
+        let this = self.await?;
+        Ok(apply_source_map_source_template(
+            self.code().generate_source_map(),
+            this.chunking_context,
+        ))
     }
 }
 
@@ -1,7 +1,7 @@
 use std::{fmt::Write, sync::Arc};
 
 use anyhow::{bail, Context, Result};
-use indoc::formatdoc;
+use indoc::{formatdoc, indoc};
 use lightningcss::css_modules::CssModuleReference;
 use swc_core::common::{BytePos, FileName, LineCol, SourceMap};
 use turbo_tasks::{FxIndexMap, RcStr, ResolvedVc, Value, ValueToString, Vc};
@@ -21,8 +21,8 @@ use turbopack_core::{
 };
 use turbopack_ecmascript::{
     chunk::{
-        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkPlaceable,
-        EcmascriptChunkType, EcmascriptExports,
+        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemOptions,
+        EcmascriptChunkPlaceable, EcmascriptChunkType, EcmascriptExports,
     },
     utils::StringifyJs,
     ParseResultSourceMap,
@@ -311,8 +311,16 @@ impl EcmascriptChunkItem for ModuleChunkItem {
     #[turbo_tasks::function]
     async fn content(&self) -> Result<Vc<EcmascriptChunkItemContent>> {
         let classes = self.module.classes().await?;
-
-        let mut code = "__turbopack_export_value__({\n".to_string();
+        let hot_module_replacement = *self
+            .chunking_context
+            .is_hot_module_replacement_enabled()
+            .await?;
+
+        let mut code = if hot_module_replacement {
+            "let classes = {\n".to_string()
+        } else {
+            "__turbopack_export_value__({\n".to_string()
+        };
         for (export_name, class_names) in &*classes {
             let mut exported_class_names = Vec::with_capacity(class_names.len());
 
@@ -385,7 +393,30 @@ impl EcmascriptChunkItem for ModuleChunkItem {
                 exported_class_names.join(" + \" \" + ")
             )?;
         }
-        code += "});\n";
+        if hot_module_replacement {
+            // Class names only depend on the path of the module, so a style-only edit
+            // keeps them. The module accepts its own updates and updates the mapping
+            // that importers already hold in place, so they don't need to re-render.
+            code += indoc! {r#"
+                };
+                if (module.hot) {
+                  const prevClasses = module.hot.data.classes;
+                  if (prevClasses) {
+                    for (const key of Object.keys(prevClasses)) {
+                      delete prevClasses[key];
+                    }
+                    classes = Object.assign(prevClasses, classes);
+                  }
+                  module.hot.dispose((data) => {
+                    data.classes = classes;
+                  });
+                  module.hot.accept();
+                }
+                __turbopack_export_value__(classes);
+            "#};
+        } else {
+            code += "});\n";
+        }
         Ok(EcmascriptChunkItemContent {
             inner_code: code.clone().into(),
             // We generate a minimal map for runtime code so that the filename is
@@ -394,6 +425,10 @@ impl EcmascriptChunkItem for ModuleChunkItem {
                 self.module.ident().to_string().await?.to_string(),
                 code,
             ))),
+            options: EcmascriptChunkItemOptions {
+                module: hot_module_replacement,
+                ..Default::default()
+            },
             ..Default::default()
         }
         .cell())
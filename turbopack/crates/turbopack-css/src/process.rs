@@ -447,8 +447,14 @@ pub async fn finalize_css(
                 FileContent::Content(v) => v.content().to_str()?,
                 _ => bail!("this case should be filtered out while parsing"),
             };
+            // Nesting is only kept when all targets support it natively.
+            let handle_nesting = !chunking_context
+                .environment()
+                .capabilities()
+                .await?
+                .css_nesting;
             let (result, srcmap) =
-                stylesheet.to_css(cm.clone(), &code, minify_type, true, true, true)?;
+                stylesheet.to_css(cm.clone(), &code, minify_type, true, true, handle_nesting)?;
 
             Ok(FinalCssResult::Ok {
                 output_code: result.code,
@@ -548,6 +554,8 @@ async fn process_content(
     let config = ParserOptions {
         css_modules: match ty {
             CssModuleAssetType::Module => Some(lightningcss::css_modules::Config {
+                // The hash is derived from the path of the module, not its content, so class
+                // names stay stable across edits and HMR can keep the mapping.
                 pattern: Pattern {
                     segments: smallvec![
                         Segment::Name,
@@ -24,7 +24,7 @@ use turbopack_core::{
 use crate::{
     references::{
         import::{ImportAssetReference, ImportAttributes},
-        url::UrlAssetReference,
+        url::{url_request, UrlAssetReference},
     },
     StyleSheetLike,
 };
@@ -130,14 +130,12 @@ impl VisitMut for ModuleReferencesVisitor<'_> {
             UrlValue::Raw(v) => v.value.clone(),
         };
 
-        // ignore internal urls like `url(#noiseFilter)`
-        // ignore server-relative urls like `url(/foo)`
-        if !matches!(src.bytes().next(), Some(b'#') | Some(b'/')) {
+        if let Some(request) = url_request(&src) {
             let issue_span = u.span;
 
             let vc = UrlAssetReference::new(
                 self.origin,
-                Request::parse(Value::new(RcStr::from(src.as_str()).into())),
+                request,
                 IssueSource::from_swc_offsets(
                     Vc::upcast(self.source),
                     issue_span.lo.0 as _,
@@ -197,14 +195,12 @@ impl Visitor<'_> for ModuleReferencesVisitor<'_> {
     fn visit_url(&mut self, u: &mut Url) -> std::result::Result<(), Self::Error> {
         let src = &*u.url;
 
-        // ignore internal urls like `url(#noiseFilter)`
-        // ignore server-relative urls like `url(/foo)`
-        if !matches!(src.bytes().next(), Some(b'#') | Some(b'/')) {
+        if let Some(request) = url_request(src) {
             let issue_span = u.loc;
 
             let vc = UrlAssetReference::new(
                 self.origin,
-                Request::parse(Value::new(RcStr::from(src).into())),
+                request,
                 IssueSource::from_line_col(
                     Vc::upcast(self.source),
                     SourcePos {
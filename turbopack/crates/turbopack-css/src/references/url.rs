@@ -117,21 +117,48 @@ impl ValueToString for UrlAssetReference {
     }
 }
 
+/// Returns the request of the `url()` reference `src`, or `None` when it
+/// doesn't refer to a module and is kept as it is.
+///
+/// Internal urls like `url(#noiseFilter)` and server-relative urls like
+/// `url(/foo)` don't refer to modules. A leading `~` explicitly marks a module
+/// request like `url(~some-package/image.png)`, as supported by css-loader.
+pub fn url_request(src: &str) -> Option<Vc<Request>> {
+    let request = module_request(src)?;
+    Some(Request::parse(Value::new(RcStr::from(request).into())))
+}
+
+fn module_request(src: &str) -> Option<&str> {
+    match src.bytes().next() {
+        None | Some(b'#') | Some(b'/') => None,
+        Some(b'~') => Some(&src[1..]),
+        _ => Some(src),
+    }
+}
+
 #[turbo_tasks::function]
 pub async fn resolve_url_reference(
     url: Vc<UrlAssetReference>,
     chunking_context: Vc<Box<dyn ChunkingContext>>,
 ) -> Result<Vc<Option<RcStr>>> {
     let this = url.await?;
-    // TODO(WEB-662) This is not the correct way to get the current chunk path. It
-    // currently works as all chunks are in the same directory.
-    let chunk_path = chunking_context.chunk_path(
-        AssetIdent::from_path(this.origin.origin_path()),
-        ".css".into(),
-    );
-    let context_path = chunk_path.parent().await?;
 
     if let ReferencedAsset::Some(asset) = &*url.get_referenced_asset(chunking_context).await? {
+        // The url of the asset, e.g. with the asset prefix, is correct no matter
+        // which chunk the CSS ends up in.
+        if *chunking_context.asset_urls_in_css().await? {
+            let url = chunking_context.asset_url(asset.ident()).await?;
+            return Ok(Vc::cell(Some((*url).clone())));
+        }
+
+        // TODO(WEB-662) This is not the correct way to get the current chunk path. It
+        // currently works as all chunks are in the same directory.
+        let chunk_path = chunking_context.chunk_path(
+            AssetIdent::from_path(this.origin.origin_path()),
+            ".css".into(),
+        );
+        let context_path = chunk_path.parent().await?;
+
         // TODO(WEB-662) This is not the correct way to get the path of the asset.
         // `asset` is on module-level, but we need the output-level asset instead.
         let path = asset.ident().path().await?;
@@ -202,3 +229,25 @@ impl Visitor<'_> for AssetReferenceReplacer<'_> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::module_request;
+
+    #[test]
+    fn parses_module_requests() {
+        assert_eq!(module_request("./image.png"), Some("./image.png"));
+        assert_eq!(module_request("image.png"), Some("image.png"));
+        assert_eq!(
+            module_request("~some-package/image.png"),
+            Some("some-package/image.png")
+        );
+    }
+
+    #[test]
+    fn keeps_internal_and_server_relative_urls() {
+        assert_eq!(module_request("#noiseFilter"), None);
+        assert_eq!(module_request("/image.png"), None);
+        assert_eq!(module_request(""), None);
+    }
+}
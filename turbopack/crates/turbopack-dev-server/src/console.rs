@@ -0,0 +1,256 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use hyper::{HeaderMap, Uri};
+use serde::Deserialize;
+use tracing::{event, Level};
+use turbo_tasks::{TransientInstance, Vc};
+use turbopack_core::{
+    asset::AssetContent,
+    source_map::{SourceMap, Token},
+};
+
+use crate::source::{
+    request::SourceRequest,
+    resolve::{resolve_source_request, ResolveSourceRequestResult},
+    Body, ContentSource,
+};
+
+/// A message of the browser console that the client runtime forwards to the
+/// console endpoint of a project, see
+/// [crate::DevServerBuilder::forward_browser_console].
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrowserConsoleMessage {
+    level: BrowserConsoleLevel,
+    message: String,
+    /// The stack of the logged error, in the format of the browser.
+    #[serde(default)]
+    stack: Option<String>,
+    /// The version of the last HMR update that the page applied, if any.
+    #[serde(default)]
+    update_id: Option<String>,
+    /// The URL of the page.
+    #[serde(default)]
+    page: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+enum BrowserConsoleLevel {
+    Error,
+    Warn,
+}
+
+impl BrowserConsoleLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            BrowserConsoleLevel::Error => "error",
+            BrowserConsoleLevel::Warn => "warn",
+        }
+    }
+}
+
+/// Prints a message of the browser console with the stack frames resolved to
+/// the original sources, and records it in the trace.
+pub(crate) async fn forward_browser_console_message(
+    source: Vc<Box<dyn ContentSource>>,
+    message: BrowserConsoleMessage,
+) -> Result<()> {
+    let mut frames = Vec::new();
+    for line in message.stack.iter().flat_map(|stack| stack.lines()) {
+        let Some(frame) = parse_stack_frame(line) else {
+            continue;
+        };
+        frames.push(match resolve_stack_frame(source, &frame).await? {
+            Some(original) => original,
+            None => frame.to_string(),
+        });
+    }
+
+    let mut output = format!("[browser {}] {}", message.level.as_str(), message.message);
+    for frame in &frames {
+        write!(output, "\n    at {frame}")?;
+    }
+    if let Some(page) = &message.page {
+        write!(output, "\n    on page {page}")?;
+    }
+    if let Some(update_id) = &message.update_id {
+        write!(output, "\n    after HMR update {update_id}")?;
+    }
+    println!("{output}");
+
+    event!(
+        Level::INFO,
+        level = message.level.as_str(),
+        console_message = message.message.as_str(),
+        stack = frames.join("\n").as_str(),
+        page = message.page.as_deref(),
+        update_id = message.update_id.as_deref(),
+        "browser console"
+    );
+    Ok(())
+}
+
+/// A frame of an error stack, e.g. `at render (http://localhost:3000/_chunks/app.js:10:5)`
+/// in Chrome or `render@http://localhost:3000/_chunks/app.js:10:5` in
+/// Firefox and Safari.
+#[derive(Debug, PartialEq, Eq)]
+struct StackFrame<'a> {
+    name: Option<&'a str>,
+    url: &'a str,
+    line: usize,
+    column: usize,
+}
+
+impl std::fmt::Display for StackFrame<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "{name} ({}:{}:{})", self.url, self.line, self.column),
+            None => write!(f, "{}:{}:{}", self.url, self.line, self.column),
+        }
+    }
+}
+
+fn parse_stack_frame(line: &str) -> Option<StackFrame<'_>> {
+    let line = line.trim();
+    let (name, location) = if let Some(frame) = line.strip_prefix("at ") {
+        match frame
+            .strip_suffix(')')
+            .and_then(|frame| frame.split_once(" ("))
+        {
+            Some((name, location)) => (Some(name), location),
+            None => (None, frame),
+        }
+    } else {
+        let (name, location) = line.rsplit_once('@')?;
+        (Some(name).filter(|name| !name.is_empty()), location)
+    };
+    let mut parts = location.rsplitn(3, ':');
+    let column = parts.next()?.parse().ok()?;
+    let line = parts.next()?.parse().ok()?;
+    let url = parts.next()?;
+    Some(StackFrame {
+        name,
+        url,
+        line,
+        column,
+    })
+}
+
+/// Resolves a frame of a chunk that is served by `source` to its original
+/// position, using the source map of the chunk.
+async fn resolve_stack_frame(
+    source: Vc<Box<dyn ContentSource>>,
+    frame: &StackFrame<'_>,
+) -> Result<Option<String>> {
+    let Ok(uri) = frame.url.parse::<Uri>() else {
+        return Ok(None);
+    };
+    let Ok(map_uri) = format!("{}.map", uri.path()).parse::<Uri>() else {
+        return Ok(None);
+    };
+    let request = SourceRequest {
+        uri: map_uri,
+        headers: HeaderMap::new(),
+        method: "GET".to_string(),
+        body: Body::new(vec![]),
+    };
+    let resolved = resolve_source_request(source, TransientInstance::new(request)).await?;
+    let ResolveSourceRequestResult::Static(content, _) = &*resolved else {
+        return Ok(None);
+    };
+    let AssetContent::File(file) = &*content.await?.content.content().await? else {
+        return Ok(None);
+    };
+    let Some(map) = SourceMap::new_from_file_content(**file).await? else {
+        return Ok(None);
+    };
+    let token = map
+        .cell()
+        .lookup_token(frame.line.saturating_sub(1), frame.column.saturating_sub(1))
+        .await?;
+    let Token::Original(token) = &*token else {
+        return Ok(None);
+    };
+    let location = format!(
+        "{}:{}:{}",
+        token.original_file,
+        token.original_line + 1,
+        token.original_column + 1
+    );
+    Ok(Some(match token.name.as_deref().or(frame.name) {
+        Some(name) => format!("{name} ({location})"),
+        None => location,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_stack_frame, StackFrame};
+
+    #[test]
+    fn parses_chrome_frames() {
+        assert_eq!(
+            parse_stack_frame("    at render (http://localhost:3000/_chunks/app.js:10:5)"),
+            Some(StackFrame {
+                name: Some("render"),
+                url: "http://localhost:3000/_chunks/app.js",
+                line: 10,
+                column: 5,
+            })
+        );
+        assert_eq!(
+            parse_stack_frame("    at http://localhost:3000/_chunks/app.js:1:20"),
+            Some(StackFrame {
+                name: None,
+                url: "http://localhost:3000/_chunks/app.js",
+                line: 1,
+                column: 20,
+            })
+        );
+        assert_eq!(
+            parse_stack_frame("    at async Promise.all (index 0)"),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_firefox_and_safari_frames() {
+        assert_eq!(
+            parse_stack_frame("render@http://localhost:3000/_chunks/app.js:10:5"),
+            Some(StackFrame {
+                name: Some("render"),
+                url: "http://localhost:3000/_chunks/app.js",
+                line: 10,
+                column: 5,
+            })
+        );
+        assert_eq!(
+            parse_stack_frame("@http://localhost:3000/_chunks/app.js:1:20"),
+            Some(StackFrame {
+                name: None,
+                url: "http://localhost:3000/_chunks/app.js",
+                line: 1,
+                column: 20,
+            })
+        );
+    }
+
+    #[test]
+    fn skips_other_lines() {
+        assert_eq!(parse_stack_frame("Error: something went wrong"), None);
+        assert_eq!(parse_stack_frame(""), None);
+    }
+
+    #[test]
+    fn formats_frames() {
+        let frame = parse_stack_frame("at render (http://localhost:3000/app.js:10:5)").unwrap();
+        assert_eq!(
+            frame.to_string(),
+            "render (http://localhost:3000/app.js:10:5)"
+        );
+        let frame = parse_stack_frame("at http://localhost:3000/app.js:10:5").unwrap();
+        assert_eq!(frame.to_string(), "http://localhost:3000/app.js:10:5");
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use anyhow::Result;
+use hyper::Response;
+use serde::Deserialize;
+use turbo_tasks::RcStr;
+use turbo_tasks_fs::glob::Glob;
+use turbo_tasks_hash::hash_xxh3_hash64;
+
+/// Configuration of faults that the dev server injects into its responses, to
+/// test loading states, error boundaries and the recovery from missed HMR
+/// updates.
+///
+/// Faults are injected when serving requests and sending HMR updates, so they
+/// apply to every request instead of being cached with the content.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultInjectionConfig {
+    /// The seed of the pseudo-random decisions. The same seed and the same
+    /// order of requests and updates inject the same faults.
+    #[serde(default)]
+    pub seed: u64,
+    /// The rules, of which the first one that matches a path applies.
+    #[serde(default)]
+    pub rules: Vec<FaultInjectionRule>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultInjectionRule {
+    /// A glob that is matched against the path of requests and HMR resources,
+    /// without the leading slash.
+    pub route: RcStr,
+    /// The latency added before responding to a request.
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// The ratio of requests, from 0 to 1, that respond with a 500 error.
+    #[serde(default)]
+    pub error_rate: f64,
+    /// The ratio of HMR updates, from 0 to 1, that are not sent to the client.
+    #[serde(default)]
+    pub drop_hmr_rate: f64,
+}
+
+/// Injects the faults of a [FaultInjectionConfig].
+#[derive(Default)]
+pub struct FaultInjection {
+    seed: u64,
+    rules: Vec<(Glob, FaultInjectionRule)>,
+    counter: AtomicU64,
+}
+
+impl FaultInjection {
+    pub fn new(config: FaultInjectionConfig) -> Result<Self> {
+        Ok(Self {
+            seed: config.seed,
+            rules: config
+                .rules
+                .into_iter()
+                .map(|rule| Ok((Glob::parse(&rule.route)?, rule)))
+                .collect::<Result<_>>()?,
+            counter: AtomicU64::new(0),
+        })
+    }
+
+    fn rule(&self, path: &str) -> Option<&FaultInjectionRule> {
+        let path = path.trim_start_matches('/');
+        self.rules
+            .iter()
+            .find(|(glob, _)| glob.execute(path))
+            .map(|(_, rule)| rule)
+    }
+
+    /// Decides whether a fault with the given `rate` occurs.
+    fn roll(&self, rate: f64) -> bool {
+        if rate <= 0.0 {
+            return false;
+        }
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        let hash = hash_xxh3_hash64((self.seed, n));
+        (hash as f64 / u64::MAX as f64) < rate
+    }
+
+    /// Delays a request to `path` and returns an error response instead of
+    /// its content when the matching rule says so.
+    pub(crate) async fn inject_into_request(
+        &self,
+        path: &str,
+    ) -> Result<Option<Response<hyper::Body>>> {
+        let Some(rule) = self.rule(path) else {
+            return Ok(None);
+        };
+        if rule.latency_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(rule.latency_ms)).await;
+        }
+        if !self.roll(rule.error_rate) {
+            return Ok(None);
+        }
+        println!("[500] {path} (injected fault)");
+        Ok(Some(Response::builder().status(500).body(
+            hyper::Body::from(format!("Injected fault for {path}")),
+        )?))
+    }
+
+    /// Returns true when an HMR update of the resource at `path` should not be
+    /// sent to the client.
+    pub(crate) fn drop_update(&self, path: &str) -> bool {
+        self.rule(path)
+            .is_some_and(|rule| self.roll(rule.drop_hmr_rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::{FaultInjection, FaultInjectionConfig};
+
+    fn fault_injection(seed: u64, rules: serde_json::Value) -> FaultInjection {
+        let config: FaultInjectionConfig =
+            serde_json::from_value(serde_json::json!({ "seed": seed, "rules": rules })).unwrap();
+        FaultInjection::new(config).unwrap()
+    }
+
+    #[test]
+    fn applies_the_first_matching_rule() {
+        let faults = fault_injection(
+            0,
+            serde_json::json!([
+                { "route": "api/**", "errorRate": 1 },
+                { "route": "**", "latencyMs": 10 },
+            ]),
+        );
+        assert_eq!(faults.rule("/api/users").unwrap().error_rate, 1.0);
+        assert_eq!(faults.rule("/about").unwrap().latency_ms, 10);
+        assert!(fault_injection(0, serde_json::json!([]))
+            .rule("/about")
+            .is_none());
+    }
+
+    #[test]
+    fn responds_with_errors() {
+        let faults = fault_injection(
+            0,
+            serde_json::json!([
+                { "route": "broken", "errorRate": 1 },
+                { "route": "working", "errorRate": 0 },
+            ]),
+        );
+        let response = block_on(faults.inject_into_request("/broken"))
+            .unwrap()
+            .expect("the request fails");
+        assert_eq!(response.status(), 500);
+        assert!(block_on(faults.inject_into_request("/working"))
+            .unwrap()
+            .is_none());
+        assert!(block_on(faults.inject_into_request("/other"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn drops_updates_deterministically() {
+        let rules = serde_json::json!([{ "route": "**", "dropHmrRate": 0.5 }]);
+        let decisions = |seed| {
+            let faults = fault_injection(seed, rules.clone());
+            (0..64)
+                .map(|_| faults.drop_update("_chunks/app.js"))
+                .collect::<Vec<_>>()
+        };
+        let dropped = decisions(1);
+        assert_eq!(decisions(1), dropped);
+        assert_ne!(decisions(2), dropped);
+        assert!(dropped.contains(&true) && dropped.contains(&false));
+    }
+}
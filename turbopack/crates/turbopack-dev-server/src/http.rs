@@ -10,7 +10,7 @@ use hyper::{
 };
 use mime::Mime;
 use tokio_util::io::{ReaderStream, StreamReader};
-use turbo_tasks::{util::SharedError, CollectiblesSource, ReadRef, TransientInstance, Vc};
+use turbo_tasks::{util::SharedError, CollectiblesSource, ReadRef, Vc};
 use turbo_tasks_bytes::Bytes;
 use turbo_tasks_fs::FileContent;
 use turbopack_core::{
@@ -20,8 +20,7 @@ use turbopack_core::{
 };
 
 use crate::source::{
-    request::SourceRequest,
-    resolve::{resolve_source_request, ResolveSourceRequestResult},
+    request::SourceRequest, resolve::ResolveSourceRequestResult, response_cache::ResponseCache,
     Body, ContentSource, ContentSourceSideEffect, HeaderList, ProxyResult,
 };
 
@@ -37,14 +36,13 @@ enum GetFromSourceResult {
     NotFound,
 }
 
-/// Resolves a [SourceRequest] within a [super::ContentSource], returning the
-/// corresponding content as a
+/// Reads the content of a resolved [SourceRequest] within a
+/// [super::ContentSource].
 #[turbo_tasks::function]
 async fn get_from_source(
-    source: Vc<Box<dyn ContentSource>>,
-    request: TransientInstance<SourceRequest>,
+    resolved: Vc<ResolveSourceRequestResult>,
 ) -> Result<Vc<GetFromSourceResult>> {
-    Ok(match &*resolve_source_request(source, request).await? {
+    Ok(match &*resolved.await? {
         ResolveSourceRequestResult::Static(static_content_vc, header_overwrites) => {
             let static_content = static_content_vc.await?;
             if let AssetContent::File(file) = &*static_content.content.content().await? {
@@ -72,13 +70,15 @@ pub async fn process_request_with_content_source(
     source: Vc<Box<dyn ContentSource>>,
     request: Request<hyper::Body>,
     issue_reporter: Vc<Box<dyn IssueReporter>>,
+    response_cache: &ResponseCache,
 ) -> Result<(
     Response<hyper::Body>,
     AutoSet<Vc<Box<dyn ContentSourceSideEffect>>>,
 )> {
     let original_path = request.uri().path().to_string();
     let request = http_request_to_source_request(request).await?;
-    let result = get_from_source(source, TransientInstance::new(request));
+    let resolved = response_cache.resolve(source, request).await?;
+    let result = get_from_source(resolved);
     let resolved_result = result.resolve_strongly_consistent().await?;
     let side_effects: AutoSet<Vc<Box<dyn ContentSourceSideEffect>>> = result.peek_collectibles();
     handle_issues(
@@ -6,27 +6,31 @@
 #![feature(arbitrary_self_types)]
 #![feature(arbitrary_self_types_pointers)]
 
+mod console;
+pub mod fault_injection;
 pub mod html;
 mod http;
 pub mod introspect;
 mod invalidation;
+pub mod port;
+mod project;
 pub mod source;
 pub mod update;
 
 use std::{
     collections::VecDeque,
     future::Future,
-    net::{SocketAddr, TcpListener},
+    net::{IpAddr, SocketAddr, TcpListener},
     pin::Pin,
     sync::Arc,
     time::{Duration, Instant},
 };
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use hyper::{
     server::{conn::AddrIncoming, Builder},
     service::{make_service_fn, service_fn},
-    Request, Response, Server,
+    Method, Request, Response, Server,
 };
 use parking_lot::Mutex;
 use socket2::{Domain, Protocol, Socket, Type};
@@ -38,12 +42,17 @@ use turbo_tasks::{
 use turbopack_core::{
     error::PrettyPrintError,
     issue::{handle_issues, IssueReporter, IssueSeverity},
+    version::VersionHistory,
 };
 
-use self::{source::ContentSource, update::UpdateServer};
+pub use self::project::{DevServerProject, ProjectStatus};
+use self::{fault_injection::FaultInjection, source::ContentSource, update::UpdateServer};
 use crate::{
+    console::{forward_browser_console_message, BrowserConsoleMessage},
     invalidation::{ServerRequest, ServerRequestSideEffects},
-    source::ContentSourceSideEffect,
+    port::{is_addr_in_use, port_range},
+    project::ProjectStatusCounter,
+    source::{response_cache::ResponseCache, ContentSourceSideEffect},
 };
 
 pub trait SourceProvider: Send + Clone + 'static {
@@ -66,6 +75,9 @@ pub struct DevServerBuilder {
     pub addr: SocketAddr,
     #[turbo_tasks(trace_ignore)]
     server: Builder<AddrIncoming>,
+    #[turbo_tasks(trace_ignore)]
+    fault_injection: Arc<FaultInjection>,
+    forward_browser_console: bool,
 }
 
 #[derive(TraceRawVcs)]
@@ -102,171 +114,165 @@ impl DevServer {
             .context("not able to bind address")?;
         socket.listen(128).context("not able to listen on socket")?;
 
-        let listener: TcpListener = socket.into();
+        Self::from_listener(socket.into())
+    }
+
+    /// Binds the first free port of `port..port + max_attempts`, so several
+    /// servers can be started at the same time, e.g. for the apps of a
+    /// monorepo. The bound port is available as [DevServerBuilder::addr].
+    pub fn listen_negotiated(
+        host: IpAddr,
+        port: u16,
+        max_attempts: u16,
+    ) -> Result<DevServerBuilder, anyhow::Error> {
+        for current_port in port_range(port, max_attempts) {
+            match Self::listen(SocketAddr::new(host, current_port)) {
+                Err(error) if is_addr_in_use(&error) => continue,
+                result => return result,
+            }
+        }
+        bail!(
+            "not able to bind address, ports {} to {} are in use",
+            port,
+            port.saturating_add(max_attempts.saturating_sub(1))
+        )
+    }
+
+    /// Serves on a listener that is already bound, e.g. the one passed by
+    /// socket activation, see [port::activated_listener].
+    pub fn from_listener(listener: TcpListener) -> Result<DevServerBuilder, anyhow::Error> {
         let addr = listener
             .local_addr()
             .context("not able to get bound address")?;
         let server = Server::from_tcp(listener).context("Not able to start server")?;
-        Ok(DevServerBuilder { addr, server })
+        Ok(DevServerBuilder {
+            addr,
+            server,
+            fault_injection: Default::default(),
+            forward_browser_console: false,
+        })
     }
 }
 
+/// A project of [DevServerBuilder::serve_projects] with the state of the
+/// server for it.
+struct ServedProject {
+    project: DevServerProject,
+    ongoing_side_effects: Mutex<VecDeque<Arc<tokio::sync::Mutex<Option<JoinHandle<Result<()>>>>>>>,
+    version_history: Arc<VersionHistory>,
+    status: ProjectStatusCounter,
+    response_cache: ResponseCache,
+}
+
 impl DevServerBuilder {
+    /// Injects faults into the responses and HMR updates of the server. This
+    /// is meant for testing how an app handles slow or failing requests during
+    /// development.
+    pub fn fault_injection(mut self, fault_injection: FaultInjection) -> Self {
+        self.fault_injection = Arc::new(fault_injection);
+        self
+    }
+
+    /// Prints the errors and warnings of the browser console of the pages of
+    /// the projects in the terminal, with their stack frames resolved to the
+    /// original sources, and records them in the trace.
+    ///
+    /// The client runtime sends them to [DevServerProject::console_path],
+    /// along with the HMR update the page applied last, so it's visible when
+    /// an update broke the page.
+    pub fn forward_browser_console(mut self, forward_browser_console: bool) -> Self {
+        self.forward_browser_console = forward_browser_console;
+        self
+    }
+
     pub fn serve(
         self,
         turbo_tasks: Arc<dyn TurboTasksApi>,
         source_provider: impl SourceProvider + Sync,
         get_issue_reporter: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync>,
     ) -> DevServer {
-        let ongoing_side_effects = Arc::new(Mutex::new(VecDeque::<
-            Arc<tokio::sync::Mutex<Option<JoinHandle<Result<()>>>>>,
-        >::with_capacity(16)));
+        self.serve_projects(
+            turbo_tasks,
+            vec![DevServerProject::new(
+                "default".into(),
+                source_provider,
+                get_issue_reporter,
+            )],
+        )
+    }
+
+    /// Serves several projects behind one listener. Requests are routed by
+    /// their `Host` header and path, see [DevServerProject].
+    ///
+    /// Each project has its own HMR endpoint at
+    /// [DevServerProject::hmr_path] and reports its [ProjectStatus] at
+    /// [DevServerProject::status_path].
+    pub fn serve_projects(
+        self,
+        turbo_tasks: Arc<dyn TurboTasksApi>,
+        projects: Vec<DevServerProject>,
+    ) -> DevServer {
+        let projects: Arc<Vec<Arc<ServedProject>>> = Arc::new(
+            projects
+                .into_iter()
+                .map(|project| {
+                    Arc::new(ServedProject {
+                        project,
+                        ongoing_side_effects: Mutex::new(VecDeque::with_capacity(16)),
+                        version_history: Arc::new(VersionHistory::default()),
+                        status: Default::default(),
+                        response_cache: Default::default(),
+                    })
+                })
+                .collect(),
+        );
+        let fault_injection = self.fault_injection;
+        let forward_browser_console = self.forward_browser_console;
         let make_svc = make_service_fn(move |_| {
             let tt = turbo_tasks.clone();
-            let source_provider = source_provider.clone();
-            let get_issue_reporter = get_issue_reporter.clone();
-            let ongoing_side_effects = ongoing_side_effects.clone();
+            let projects = projects.clone();
+            let fault_injection = fault_injection.clone();
             async move {
                 let handler = move |request: Request<hyper::Body>| {
                     let request_span = info_span!(parent: None, "request", name = ?request.uri());
                     let start = Instant::now();
+                    let project =
+                        project::route(&projects, |served| &served.project, &request).cloned();
                     let tt = tt.clone();
-                    let get_issue_reporter = get_issue_reporter.clone();
-                    let ongoing_side_effects = ongoing_side_effects.clone();
-                    let source_provider = source_provider.clone();
-                    let future = async move {
-                        event!(parent: Span::current(), Level::DEBUG, "request start");
-                        // Wait until all ongoing side effects are completed
-                        // We only need to wait for the ongoing side effects that were started
-                        // before this request. Later added side effects are not relevant for this.
-                        let current_ongoing_side_effects = {
-                            // Cleanup the ongoing_side_effects list
-                            let mut guard = ongoing_side_effects.lock();
-                            while let Some(front) = guard.front() {
-                                let Ok(front_guard) = front.try_lock() else {
-                                    break;
-                                };
-                                if front_guard.is_some() {
-                                    break;
-                                }
-                                drop(front_guard);
-                                guard.pop_front();
-                            }
-                            // Get a clone of the remaining list
-                            (*guard).clone()
-                        };
-                        // Wait for the side effects to complete
-                        for side_effect_mutex in current_ongoing_side_effects {
-                            let mut guard = side_effect_mutex.lock().await;
-                            if let Some(join_handle) = guard.take() {
-                                join_handle.await??;
-                            }
-                            drop(guard);
-                        }
-                        let reason = ServerRequest {
-                            method: request.method().clone(),
-                            uri: request.uri().clone(),
-                        };
-                        let side_effects_reason = ServerRequestSideEffects {
-                            method: request.method().clone(),
-                            uri: request.uri().clone(),
+                    let fault_injection = fault_injection.clone();
+                    async move {
+                        let Some(project) = project else {
+                            println!("[404] {} (no matching project)", request.uri().path());
+                            return Response::builder().status(404).body(hyper::Body::empty());
                         };
-                        run_once_with_reason(tt.clone(), reason, async move {
-                            let issue_reporter = get_issue_reporter();
-
-                            if hyper_tungstenite::is_upgrade_request(&request) {
-                                let uri = request.uri();
-                                let path = uri.path();
-
-                                if path == "/turbopack-hmr" {
-                                    let (response, websocket) =
-                                        hyper_tungstenite::upgrade(request, None)?;
-                                    let update_server =
-                                        UpdateServer::new(source_provider, issue_reporter);
-                                    update_server.run(&*tt, websocket);
-                                    return Ok(response);
-                                }
-
-                                println!("[404] {} (WebSocket)", path);
-                                if path == "/_next/webpack-hmr" {
-                                    // Special-case requests to webpack-hmr as these are made by
-                                    // Next.js clients built
-                                    // without turbopack, which may be making requests in
-                                    // development.
-                                    println!(
-                                        "A non-turbopack next.js client is trying to connect."
-                                    );
-                                    println!(
-                                        "Make sure to reload/close any browser window which has \
-                                         been opened without --turbo."
-                                    );
-                                }
-
-                                return Ok(Response::builder()
-                                    .status(404)
-                                    .body(hyper::Body::empty())?);
-                            }
-
-                            let uri = request.uri();
-                            let path = uri.path().to_string();
-                            let source = source_provider.get_source();
-                            let resolved_source = source.resolve_strongly_consistent().await?;
-                            handle_issues(
-                                source,
-                                issue_reporter,
-                                IssueSeverity::Fatal.cell(),
-                                Some(&path),
-                                Some("get source"),
+                        project.status.request();
+                        match project
+                            .clone()
+                            .handle_request(
+                                tt,
+                                fault_injection,
+                                forward_browser_console,
+                                request,
+                                start,
                             )
-                            .await?;
-                            let (response, side_effects) =
-                                http::process_request_with_content_source(
-                                    resolved_source,
-                                    request,
-                                    issue_reporter,
-                                )
-                                .await?;
-                            let status = response.status().as_u16();
-                            let is_error = response.status().is_client_error()
-                                || response.status().is_server_error();
-                            let elapsed = start.elapsed();
-                            if is_error
-                                || (cfg!(feature = "log_request_stats")
-                                    && elapsed > Duration::from_secs(1))
-                            {
-                                println!(
-                                    "[{status}] {path} ({duration})",
-                                    duration = FormatDuration(elapsed)
-                                );
-                            }
-                            if !side_effects.is_empty() {
-                                let join_handle = tokio::spawn(run_once_with_reason(
-                                    tt.clone(),
-                                    side_effects_reason,
-                                    async move {
-                                        for side_effect in side_effects {
-                                            side_effect.apply().await?;
-                                        }
-                                        Ok(())
-                                    },
-                                ));
-                                ongoing_side_effects.lock().push_back(Arc::new(
-                                    tokio::sync::Mutex::new(Some(join_handle)),
-                                ));
+                            .await
+                        {
+                            Ok(r) => {
+                                if r.status().is_server_error() {
+                                    project.status.error(format!(
+                                        "responded with status {}",
+                                        r.status().as_u16()
+                                    ));
+                                }
+                                Ok::<_, hyper::http::Error>(r)
                             }
-                            Ok(response)
-                        })
-                        .await
-                    };
-                    async move {
-                        match future.await {
-                            Ok(r) => Ok::<_, hyper::http::Error>(r),
                             Err(e) => {
                                 println!(
                                     "[500] error ({}): {}",
                                     FormatDuration(start.elapsed()),
                                     PrettyPrintError(&e),
                                 );
+                                project.status.error(format!("{}", PrettyPrintError(&e)));
                                 Ok(Response::builder()
                                     .status(500)
                                     .body(hyper::Body::from(format!("{}", PrettyPrintError(&e))))?)
@@ -290,6 +296,177 @@ impl DevServerBuilder {
     }
 }
 
+impl ServedProject {
+    async fn handle_request(
+        self: Arc<Self>,
+        tt: Arc<dyn TurboTasksApi>,
+        fault_injection: Arc<FaultInjection>,
+        forward_browser_console: bool,
+        request: Request<hyper::Body>,
+        start: Instant,
+    ) -> Result<Response<hyper::Body>> {
+        event!(parent: Span::current(), Level::DEBUG, "request start");
+        // Wait until all ongoing side effects are completed
+        // We only need to wait for the ongoing side effects that were started
+        // before this request. Later added side effects are not relevant for this.
+        let current_ongoing_side_effects = {
+            // Cleanup the ongoing_side_effects list
+            let mut guard = self.ongoing_side_effects.lock();
+            while let Some(front) = guard.front() {
+                let Ok(front_guard) = front.try_lock() else {
+                    break;
+                };
+                if front_guard.is_some() {
+                    break;
+                }
+                drop(front_guard);
+                guard.pop_front();
+            }
+            // Get a clone of the remaining list
+            (*guard).clone()
+        };
+        // Wait for the side effects to complete
+        for side_effect_mutex in current_ongoing_side_effects {
+            let mut guard = side_effect_mutex.lock().await;
+            if let Some(join_handle) = guard.take() {
+                join_handle.await??;
+            }
+            drop(guard);
+        }
+        let reason = ServerRequest {
+            method: request.method().clone(),
+            uri: request.uri().clone(),
+        };
+        let side_effects_reason = ServerRequestSideEffects {
+            method: request.method().clone(),
+            uri: request.uri().clone(),
+        };
+        run_once_with_reason(tt.clone(), reason, async move {
+            let issue_reporter = (self.project.get_issue_reporter)();
+
+            if hyper_tungstenite::is_upgrade_request(&request) {
+                let uri = request.uri();
+                let path = uri.path();
+
+                if path == self.project.hmr_path() {
+                    let (response, websocket) = hyper_tungstenite::upgrade(request, None)?;
+                    let source_provider = self.project.source_provider.clone();
+                    let update_server = UpdateServer::new(
+                        move || source_provider(),
+                        issue_reporter,
+                        self.version_history.clone(),
+                        fault_injection,
+                    );
+                    self.status.hmr_connection();
+                    update_server.run(&*tt, websocket);
+                    return Ok(response);
+                }
+
+                println!("[404] {} (WebSocket)", path);
+                if path == "/_next/webpack-hmr" {
+                    // Special-case requests to webpack-hmr as these are made by
+                    // Next.js clients built
+                    // without turbopack, which may be making requests in
+                    // development.
+                    println!("A non-turbopack next.js client is trying to connect.");
+                    println!(
+                        "Make sure to reload/close any browser window which has been opened \
+                         without --turbo."
+                    );
+                }
+
+                return Ok(Response::builder().status(404).body(hyper::Body::empty())?);
+            }
+
+            let uri = request.uri();
+            let path = uri.path().to_string();
+            if path == self.project.console_path() {
+                // The client runtime always forwards the console, and stops when the endpoint
+                // doesn't exist.
+                if !forward_browser_console || request.method() != Method::POST {
+                    return Ok(Response::builder().status(404).body(hyper::Body::empty())?);
+                }
+                let body = hyper::body::to_bytes(request.into_body()).await?;
+                let Ok(message) = serde_json::from_slice::<BrowserConsoleMessage>(&body) else {
+                    return Ok(Response::builder()
+                        .status(400)
+                        .body(hyper::Body::from("Invalid browser console message"))?);
+                };
+                forward_browser_console_message((self.project.source_provider)(), message).await?;
+                return Ok(Response::builder().status(204).body(hyper::Body::empty())?);
+            }
+            if path == self.project.status_path() {
+                let status = self.status.get(&self.project);
+                return Ok(Response::builder()
+                    .header("content-type", "application/json")
+                    .body(hyper::Body::from(serde_json::to_string(&status)?))?);
+            }
+            if path == self.project.profile_path() {
+                let Some(profile) = tt.sampling_profile() else {
+                    return Ok(Response::builder()
+                        .status(404)
+                        .body(hyper::Body::from("The sampling profiler is not running"))?);
+                };
+                return Ok(Response::builder()
+                    .header("content-type", "text/plain; charset=utf-8")
+                    .header(
+                        "content-disposition",
+                        "attachment; filename=\"turbopack.folded\"",
+                    )
+                    .body(hyper::Body::from(profile.to_folded()))?);
+            }
+            if let Some(response) = fault_injection.inject_into_request(&path).await? {
+                return Ok(response);
+            }
+            let source = (self.project.source_provider)();
+            let resolved_source = source.resolve_strongly_consistent().await?;
+            handle_issues(
+                source,
+                issue_reporter,
+                IssueSeverity::Fatal.cell(),
+                Some(&path),
+                Some("get source"),
+            )
+            .await?;
+            let (response, side_effects) = http::process_request_with_content_source(
+                resolved_source,
+                request,
+                issue_reporter,
+                &self.response_cache,
+            )
+            .await?;
+            let status = response.status().as_u16();
+            let is_error =
+                response.status().is_client_error() || response.status().is_server_error();
+            let elapsed = start.elapsed();
+            if is_error || (cfg!(feature = "log_request_stats") && elapsed > Duration::from_secs(1))
+            {
+                println!(
+                    "[{status}] {path} ({duration})",
+                    duration = FormatDuration(elapsed)
+                );
+            }
+            if !side_effects.is_empty() {
+                let join_handle = tokio::spawn(run_once_with_reason(
+                    tt.clone(),
+                    side_effects_reason,
+                    async move {
+                        for side_effect in side_effects {
+                            side_effect.apply().await?;
+                        }
+                        Ok(())
+                    },
+                ));
+                self.ongoing_side_effects
+                    .lock()
+                    .push_back(Arc::new(tokio::sync::Mutex::new(Some(join_handle))));
+            }
+            Ok(response)
+        })
+        .await
+    }
+}
+
 pub fn register() {
     turbo_tasks::register();
     turbo_tasks_bytes::register();
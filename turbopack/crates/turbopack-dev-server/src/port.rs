@@ -0,0 +1,121 @@
+use std::{
+    io,
+    net::{IpAddr, SocketAddr, TcpListener},
+};
+
+use anyhow::{bail, Context, Result};
+
+/// Returns true when `error` was caused by the address being in use.
+pub(crate) fn is_addr_in_use(error: &anyhow::Error) -> bool {
+    error.chain().any(|error| {
+        error
+            .downcast_ref::<io::Error>()
+            .is_some_and(|error| error.kind() == io::ErrorKind::AddrInUse)
+    })
+}
+
+/// Returns the first port of `port..port + max_attempts` that can be bound on
+/// `host`, for tools that start servers for several projects at the same
+/// time.
+///
+/// The port is only checked, so another process could bind it before it's
+/// used. Prefer [crate::DevServer::listen_negotiated], which keeps the bound
+/// socket.
+pub fn negotiate_port(host: IpAddr, port: u16, max_attempts: u16) -> Result<u16> {
+    for current_port in port_range(port, max_attempts) {
+        match TcpListener::bind(SocketAddr::new(host, current_port)) {
+            Ok(listener) => return Ok(listener.local_addr()?.port()),
+            Err(error) if error.kind() == io::ErrorKind::AddrInUse => continue,
+            Err(error) => {
+                return Err(error).with_context(|| format!("not able to bind port {current_port}"))
+            }
+        }
+    }
+    bail!(
+        "ports {} to {} are in use",
+        port,
+        port.saturating_add(max_attempts.saturating_sub(1))
+    )
+}
+
+pub(crate) fn port_range(port: u16, max_attempts: u16) -> impl Iterator<Item = u16> {
+    // Port 0 lets the OS choose a free port, so retrying is never needed.
+    let attempts = if port == 0 { 1 } else { max_attempts.max(1) };
+    (0..attempts).map_while(move |attempt| port.checked_add(attempt))
+}
+
+/// Returns the listener that was passed by systemd style socket activation,
+/// i.e. as file descriptor 3 with `LISTEN_PID` set to the current process and
+/// `LISTEN_FDS` set to at least 1.
+///
+/// Returns `None` when no listener was passed, when it was taken already, or on
+/// platforms without socket activation.
+pub fn activated_listener() -> Result<Option<TcpListener>> {
+    #[cfg(unix)]
+    {
+        use std::{
+            os::fd::FromRawFd,
+            sync::atomic::{AtomicBool, Ordering},
+        };
+
+        /// The first file descriptor passed by socket activation.
+        const LISTEN_FDS_START: i32 = 3;
+        /// Whether the listener was taken already.
+        static TAKEN: AtomicBool = AtomicBool::new(false);
+
+        let listen_pid = std::env::var("LISTEN_PID").ok();
+        if listen_pid.and_then(|pid| pid.parse::<u32>().ok()) != Some(std::process::id()) {
+            return Ok(None);
+        }
+        let listen_fds = std::env::var("LISTEN_FDS")
+            .ok()
+            .and_then(|fds| fds.parse::<u32>().ok())
+            .unwrap_or(0);
+        if listen_fds == 0 || TAKEN.swap(true, Ordering::AcqRel) {
+            return Ok(None);
+        }
+        // SAFETY: The service manager passes ownership of the socket, and it's
+        // only taken once.
+        let listener = unsafe { TcpListener::from_raw_fd(LISTEN_FDS_START) };
+        listener
+            .local_addr()
+            .context("the socket passed by socket activation is not a TCP listener")?;
+        Ok(Some(listener))
+    }
+    #[cfg(not(unix))]
+    {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, TcpListener};
+
+    use super::{negotiate_port, port_range};
+
+    #[test]
+    fn ranges_ports() {
+        assert_eq!(port_range(3000, 3).collect::<Vec<_>>(), [3000, 3001, 3002]);
+        // At least one port is tried.
+        assert_eq!(port_range(3000, 0).collect::<Vec<_>>(), [3000]);
+        // The OS chooses a free port for port 0.
+        assert_eq!(port_range(0, 10).collect::<Vec<_>>(), [0]);
+        assert_eq!(
+            port_range(u16::MAX - 1, 5).collect::<Vec<_>>(),
+            [u16::MAX - 1, u16::MAX]
+        );
+    }
+
+    #[test]
+    fn skips_ports_in_use() {
+        let host = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let listener = TcpListener::bind((host, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(negotiate_port(host, port, 1).is_err());
+        if port < u16::MAX {
+            assert_ne!(negotiate_port(host, port, 10).unwrap(), port);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use hyper::{header::HOST, Request};
+use parking_lot::Mutex;
+use serde::Serialize;
+use turbo_tasks::{RcStr, Vc};
+use turbopack_core::issue::IssueReporter;
+
+use crate::{source::ContentSource, SourceProvider};
+
+/// A project that is served by a [crate::DevServer], see
+/// [crate::DevServerBuilder::serve_projects].
+///
+/// Requests are routed to the matching project with the most specific route.
+/// Projects with a host are more specific than projects without one, and
+/// longer path prefixes are more specific than shorter ones. A project without
+/// a host and a path prefix receives all requests that no other project
+/// matches.
+pub struct DevServerProject {
+    pub(crate) name: RcStr,
+    pub(crate) host: Option<String>,
+    pub(crate) path_prefix: String,
+    pub(crate) source_provider: Arc<dyn Fn() -> Vc<Box<dyn ContentSource>> + Send + Sync>,
+    pub(crate) get_issue_reporter: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync>,
+}
+
+impl DevServerProject {
+    pub fn new(
+        name: RcStr,
+        source_provider: impl SourceProvider + Sync,
+        get_issue_reporter: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync>,
+    ) -> Self {
+        Self {
+            name,
+            host: None,
+            path_prefix: String::new(),
+            source_provider: Arc::new(move || source_provider.get_source()),
+            get_issue_reporter,
+        }
+    }
+
+    /// Only routes requests with this `Host` header to the project, e.g.
+    /// `docs.localhost`. The port of the header is ignored.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Only routes requests below this path to the project, e.g. `/docs`.
+    ///
+    /// Requests are passed to the project unchanged, so it needs to be built
+    /// with the same base path.
+    pub fn path_prefix(mut self, path_prefix: impl Into<String>) -> Self {
+        let path_prefix = path_prefix.into();
+        self.path_prefix = path_prefix.trim_end_matches('/').to_string();
+        if !self.path_prefix.is_empty() && !self.path_prefix.starts_with('/') {
+            self.path_prefix.insert(0, '/');
+        }
+        self
+    }
+
+    /// The path of the HMR endpoint of the project.
+    pub fn hmr_path(&self) -> String {
+        format!("{}/turbopack-hmr", self.path_prefix)
+    }
+
+    /// The path of the endpoint that reports the [ProjectStatus] of the
+    /// project. It's below `__turbopack__`, which is reserved for the
+    /// internals of turbopack, so it doesn't shadow a page of the project.
+    pub fn status_path(&self) -> String {
+        format!("{}/__turbopack__/status", self.path_prefix)
+    }
+
+    /// The path of the endpoint that serves the profile of the sampling
+    /// profiler of Turbo Engine as folded stacks, when it's running.
+    pub fn profile_path(&self) -> String {
+        format!("{}/turbopack-profile", self.path_prefix)
+    }
+
+    /// The path of the endpoint that receives the browser console messages of
+    /// the pages of the project, when forwarding them is enabled with
+    /// [crate::DevServerBuilder::forward_browser_console].
+    pub fn console_path(&self) -> String {
+        format!("{}/turbopack-console", self.path_prefix)
+    }
+
+    /// Returns how specific the route of the project is for the request, or
+    /// `None` when the project doesn't match it.
+    fn route_specificity<B>(&self, request: &Request<B>) -> Option<(bool, usize)> {
+        if let Some(host) = &self.host {
+            let request_host = request
+                .headers()
+                .get(HOST)
+                .and_then(|value| value.to_str().ok())
+                .or_else(|| request.uri().host())?;
+            let request_host = strip_port(request_host);
+            if !request_host.eq_ignore_ascii_case(host) {
+                return None;
+            }
+        }
+        let path = request.uri().path();
+        let rest = path.strip_prefix(&*self.path_prefix)?;
+        if !rest.is_empty() && !rest.starts_with('/') {
+            return None;
+        }
+        Some((self.host.is_some(), self.path_prefix.len()))
+    }
+}
+
+fn strip_port(host: &str) -> &str {
+    if host.starts_with('[') {
+        // IPv6 addresses are enclosed in brackets, e.g. `[::1]:3000`
+        return host.split_inclusive(']').next().unwrap_or(host);
+    }
+    host.split(':').next().unwrap_or(host)
+}
+
+/// Returns the project with the most specific route for the request.
+pub(crate) fn route<'a, T, B>(
+    projects: &'a [T],
+    get_project: impl Fn(&T) -> &DevServerProject,
+    request: &Request<B>,
+) -> Option<&'a T> {
+    projects
+        .iter()
+        .filter_map(|project| {
+            get_project(project)
+                .route_specificity(request)
+                .map(|specificity| (specificity, project))
+        })
+        // `max_by_key` returns the last maximum, but earlier projects should win
+        .rev()
+        .max_by_key(|(specificity, _)| *specificity)
+        .map(|(_, project)| project)
+}
+
+/// Counts the requests of a project, which are reported at its status
+/// endpoint.
+#[derive(Default)]
+pub(crate) struct ProjectStatusCounter {
+    requests: AtomicU64,
+    errors: AtomicU64,
+    hmr_connections: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+impl ProjectStatusCounter {
+    pub fn request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn hmr_connection(&self) {
+        self.hmr_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn error(&self, message: String) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+        *self.last_error.lock() = Some(message);
+    }
+
+    pub fn get(&self, project: &DevServerProject) -> ProjectStatus {
+        ProjectStatus {
+            name: project.name.clone(),
+            host: project.host.clone(),
+            path_prefix: project.path_prefix.clone(),
+            hmr_path: project.hmr_path(),
+            requests: self.requests.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            hmr_connections: self.hmr_connections.load(Ordering::Relaxed),
+            last_error: self.last_error.lock().clone(),
+        }
+    }
+}
+
+/// The status of a served project, as reported at
+/// [DevServerProject::status_path].
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectStatus {
+    pub name: RcStr,
+    pub host: Option<String>,
+    pub path_prefix: String,
+    pub hmr_path: String,
+    pub requests: u64,
+    pub errors: u64,
+    /// The number of HMR connections since the server started.
+    pub hmr_connections: u64,
+    pub last_error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use hyper::{header::HOST, Request};
+    use turbo_tasks::Vc;
+    use turbopack_core::issue::IssueReporter;
+
+    use super::{route, strip_port, DevServerProject};
+    use crate::source::ContentSource;
+
+    fn project(name: &str) -> DevServerProject {
+        DevServerProject::new(
+            name.into(),
+            || -> Vc<Box<dyn ContentSource>> { unreachable!() },
+            Arc::new(|| -> Vc<Box<dyn IssueReporter>> { unreachable!() }),
+        )
+    }
+
+    fn routed<'a>(projects: &'a [DevServerProject], host: &str, path: &str) -> Option<&'a str> {
+        let request = Request::builder()
+            .uri(path)
+            .header(HOST, host)
+            .body(())
+            .unwrap();
+        route(projects, |project| project, &request).map(|project| &*project.name)
+    }
+
+    #[test]
+    fn strips_ports() {
+        assert_eq!(strip_port("localhost:3000"), "localhost");
+        assert_eq!(strip_port("docs.localhost"), "docs.localhost");
+        assert_eq!(strip_port("[::1]:3000"), "[::1]");
+        assert_eq!(strip_port("[::1]"), "[::1]");
+    }
+
+    #[test]
+    fn routes_to_the_most_specific_project() {
+        let projects = [
+            project("default"),
+            project("docs").path_prefix("docs/"),
+            project("blog").host("Blog.localhost"),
+            project("blog-docs")
+                .host("blog.localhost")
+                .path_prefix("/docs"),
+        ];
+        assert_eq!(routed(&projects, "localhost:3000", "/"), Some("default"));
+        assert_eq!(
+            routed(&projects, "localhost:3000", "/docs/intro"),
+            Some("docs")
+        );
+        assert_eq!(routed(&projects, "localhost:3000", "/docs"), Some("docs"));
+        // Prefixes only match whole path segments.
+        assert_eq!(
+            routed(&projects, "localhost:3000", "/docsearch"),
+            Some("default")
+        );
+        assert_eq!(
+            routed(&projects, "BLOG.localhost:3000", "/post"),
+            Some("blog")
+        );
+        assert_eq!(
+            routed(&projects, "blog.localhost", "/docs/intro"),
+            Some("blog-docs")
+        );
+    }
+
+    #[test]
+    fn routes_to_the_first_of_equally_specific_projects() {
+        let projects = [project("a"), project("b")];
+        assert_eq!(routed(&projects, "localhost", "/"), Some("a"));
+
+        let projects = [project("docs").path_prefix("/docs")];
+        assert_eq!(routed(&projects, "localhost", "/blog"), None);
+    }
+
+    #[test]
+    fn namespaces_the_endpoints_of_a_project() {
+        let project = project("docs").path_prefix("/docs");
+        assert_eq!(project.hmr_path(), "/docs/turbopack-hmr");
+        assert_eq!(project.status_path(), "/docs/__turbopack__/status");
+    }
+}
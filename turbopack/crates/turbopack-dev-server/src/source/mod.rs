@@ -7,8 +7,10 @@ pub mod lazy_instantiated;
 pub mod query;
 pub mod request;
 pub(crate) mod resolve;
+pub(crate) mod response_cache;
 pub mod route_tree;
 pub mod router;
+pub mod spa_fallback;
 pub mod static_assets;
 pub mod wrapping_source;
 
@@ -404,6 +406,26 @@ impl ContentSourceDataVary {
         }
         true
     }
+
+    /// Returns true if the content only depends on the method and the URL of
+    /// the request, so requests with the same method and URL get the same
+    /// content.
+    pub fn only_method_and_url(&self) -> bool {
+        // All fields must be used!
+        let ContentSourceDataVary {
+            method: _,
+            url: _,
+            original_url: _,
+            query: _,
+            raw_query: _,
+            headers,
+            raw_headers,
+            body,
+            cache_buster,
+            placeholder_for_future_extensions: _,
+        } = self;
+        headers.is_none() && !raw_headers && !body && !cache_buster
+    }
 }
 
 /// A source of content that the dev server uses to respond to http requests.
@@ -36,15 +36,28 @@ pub enum ResolveSourceRequestResult {
 /// version of the content. We don't make resolve_source_request strongly
 /// consistent as we want get_routes and get to be independent consistent and
 /// any side effect in get should not wait for recomputing of get_routes.
-#[turbo_tasks::function]
+#[turbo_tasks::function(transient)]
 pub async fn resolve_source_request(
     source: Vc<Box<dyn ContentSource>>,
     request: TransientInstance<SourceRequest>,
 ) -> Result<Vc<ResolveSourceRequestResult>> {
+    let result = resolve_source_request_with(source, &request, false).await?;
+    Ok(result.expect("the content may depend on all of the request"))
+}
+
+/// Resolves a [SourceRequest] within a [super::ContentSource] like
+/// [resolve_source_request]. With `only_method_and_url`, returns `None` as soon
+/// as some content depends on more than the method and the URL of the request,
+/// without getting that content.
+pub(super) async fn resolve_source_request_with(
+    source: Vc<Box<dyn ContentSource>>,
+    request: &SourceRequest,
+    only_method_and_url: bool,
+) -> Result<Option<Vc<ResolveSourceRequestResult>>> {
     let original_path = request.uri.path().to_string();
     // Remove leading slash.
     let mut current_asset_path: RcStr = urlencoding::decode(&original_path[1..])?.into();
-    let mut request_overwrites = (*request).clone();
+    let mut request_overwrites = request.clone();
     let mut response_header_overwrites = Vec::new();
     let mut route_tree = source.get_routes().resolve_strongly_consistent().await?;
     'routes: loop {
@@ -52,8 +65,11 @@ pub async fn resolve_source_request(
         'sources: loop {
             for get_content in sources.strongly_consistent().await?.iter() {
                 let content_vary = get_content.vary().strongly_consistent().await?;
+                if only_method_and_url && !content_vary.only_method_and_url() {
+                    return Ok(None);
+                }
                 let content_data =
-                    request_to_data(&request_overwrites, &request, &content_vary).await?;
+                    request_to_data(&request_overwrites, request, &content_vary).await?;
                 let content = get_content.get(current_asset_path.clone(), Value::new(content_data));
                 match &*content.strongly_consistent().await? {
                     ContentSourceContent::Rewrite(rewrite) => {
@@ -103,19 +119,23 @@ pub async fn resolve_source_request(
                         }
                     }
                     ContentSourceContent::NotFound => {
-                        return Ok(ResolveSourceRequestResult::NotFound.cell());
+                        return Ok(Some(ResolveSourceRequestResult::NotFound.cell()));
                     }
                     ContentSourceContent::Static(static_content) => {
-                        return Ok(ResolveSourceRequestResult::Static(
-                            *static_content,
-                            HeaderList::new(response_header_overwrites)
-                                .to_resolved()
-                                .await?,
-                        )
-                        .cell());
+                        return Ok(Some(
+                            ResolveSourceRequestResult::Static(
+                                *static_content,
+                                HeaderList::new(response_header_overwrites)
+                                    .to_resolved()
+                                    .await?,
+                            )
+                            .cell(),
+                        ));
                     }
                     ContentSourceContent::HttpProxy(proxy_result) => {
-                        return Ok(ResolveSourceRequestResult::HttpProxy(**proxy_result).cell());
+                        return Ok(Some(
+                            ResolveSourceRequestResult::HttpProxy(**proxy_result).cell(),
+                        ));
                     }
                     ContentSourceContent::Next => continue,
                 }
@@ -124,7 +144,7 @@ pub async fn resolve_source_request(
         }
         break;
     }
-    Ok(ResolveSourceRequestResult::NotFound.cell())
+    Ok(Some(ResolveSourceRequestResult::NotFound.cell()))
 }
 
 static CACHE_BUSTER: AtomicU64 = AtomicU64::new(0);
@@ -0,0 +1,260 @@
+use anyhow::Result;
+use hyper::{HeaderMap, Uri};
+use indexmap::IndexSet;
+use parking_lot::Mutex;
+use turbo_tasks::{RcStr, ResolvedVc, TransientInstance, Vc};
+
+use super::{
+    request::SourceRequest,
+    resolve::{resolve_source_request, resolve_source_request_with, ResolveSourceRequestResult},
+    Body, ContentSource,
+};
+
+/// The maximum number of remembered method and URL pairs whose content
+/// depends on more of the request. The least recently requested one is
+/// forgotten first.
+const MAX_UNCACHEABLE_ENTRIES: usize = 1024;
+
+#[turbo_tasks::value(transparent, serialization = "none")]
+struct OptionResolveSourceRequestResult(Option<ResolvedVc<ResolveSourceRequestResult>>);
+
+/// Resolves a request with the method and the URL, but without headers or a
+/// body, within a [ContentSource]. Returns `None` when the content depends on
+/// more of the request, or isn't static.
+///
+/// Unlike [resolve_source_request], this task is keyed by stable inputs, so
+/// repeated requests read the same task, which is recomputed once anything it
+/// read is invalidated.
+#[turbo_tasks::function]
+async fn resolve_method_and_url(
+    source: Vc<Box<dyn ContentSource>>,
+    method: RcStr,
+    uri: RcStr,
+) -> Result<Vc<OptionResolveSourceRequestResult>> {
+    let request = SourceRequest {
+        method: method.to_string(),
+        uri: Uri::try_from(uri.as_str())?,
+        headers: HeaderMap::new(),
+        body: Body::default(),
+    };
+    let Some(result) = resolve_source_request_with(source, &request, true).await? else {
+        return Ok(Vc::cell(None));
+    };
+    Ok(Vc::cell(match *result.await? {
+        ResolveSourceRequestResult::Static(..) => Some(result.to_resolved().await?),
+        ResolveSourceRequestResult::HttpProxy(_) | ResolveSourceRequestResult::NotFound => None,
+    }))
+}
+
+/// Caches the resolved static content of requests by their method and URL.
+///
+/// The cached content is the [resolve_method_and_url] task, so it never
+/// serves stale content. The cache remembers the recently requested method
+/// and URL pairs whose content depends on more of the request, so those
+/// requests don't resolve their routes twice.
+#[derive(Default)]
+pub(crate) struct ResponseCache {
+    /// Least recently requested first.
+    uncacheable: Mutex<IndexSet<(String, String)>>,
+}
+
+impl ResponseCache {
+    /// Resolves a [SourceRequest] within a [ContentSource], like
+    /// [resolve_source_request], reading the cached content when it only
+    /// depends on the method and the URL of the request.
+    pub async fn resolve(
+        &self,
+        source: Vc<Box<dyn ContentSource>>,
+        request: SourceRequest,
+    ) -> Result<Vc<ResolveSourceRequestResult>> {
+        // Other methods may have side effects, which must not be skipped.
+        if request.method == "GET" || request.method == "HEAD" {
+            let key = (request.method.clone(), request.uri.to_string());
+            // Removed and inserted again to mark it as the most recent one.
+            let uncacheable = self.uncacheable.lock().shift_remove(&key);
+            if !uncacheable {
+                let cached =
+                    resolve_method_and_url(source, key.0.as_str().into(), key.1.as_str().into())
+                        .strongly_consistent()
+                        .await?;
+                if let Some(result) = *cached {
+                    return Ok(*result);
+                }
+            }
+            let mut entries = self.uncacheable.lock();
+            if entries.len() >= MAX_UNCACHEABLE_ENTRIES {
+                entries.shift_remove_index(0);
+            }
+            entries.insert(key);
+        }
+        Ok(resolve_source_request(
+            source,
+            TransientInstance::new(request),
+        ))
+    }
+
+    #[cfg(test)]
+    fn is_uncacheable(&self, method: &str, uri: &str) -> bool {
+        self.uncacheable
+            .lock()
+            .contains(&(method.to_string(), uri.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, sync::Once};
+
+    use anyhow::{bail, Result};
+    use hyper::{header::ACCEPT, HeaderMap};
+    use turbo_tasks::{TurboTasks, Vc};
+    use turbo_tasks_fs::{DiskFileSystem, FileContent, FileSystem};
+    use turbo_tasks_memory::MemoryBackend;
+    use turbopack_core::asset::AssetContent;
+
+    use super::ResponseCache;
+    use crate::source::{
+        request::SourceRequest,
+        resolve::ResolveSourceRequestResult,
+        spa_fallback::{SpaFallbackContentSource, SpaFallbackOptions},
+        static_assets::StaticAssetsContentSource,
+        Body, ContentSource,
+    };
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    fn request(path: &str) -> SourceRequest {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, "text/html".parse().unwrap());
+        SourceRequest {
+            method: "GET".to_string(),
+            uri: path.parse().unwrap(),
+            headers,
+            body: Body::default(),
+        }
+    }
+
+    fn source(root: &Path) -> Vc<Box<dyn ContentSource>> {
+        let fs = DiskFileSystem::new("test".into(), root.to_str().unwrap().into(), vec![]);
+        Vc::upcast(SpaFallbackContentSource::new(
+            Vc::upcast(StaticAssetsContentSource::new("".into(), fs.root())),
+            SpaFallbackOptions {
+                index: "/index.html".into(),
+                excluded_prefixes: vec![],
+            }
+            .cell(),
+        ))
+    }
+
+    async fn text(result: Vc<ResolveSourceRequestResult>) -> Result<String> {
+        let ResolveSourceRequestResult::Static(content, _) = &*result.await? else {
+            bail!("expected static content");
+        };
+        let AssetContent::File(file) = &*content.await?.content.content().await? else {
+            bail!("expected a file");
+        };
+        let FileContent::Content(file) = &*file.await? else {
+            bail!("expected file content");
+        };
+        Ok(file.content().to_str()?.to_string())
+    }
+
+    #[tokio::test]
+    async fn reads_the_cached_content_again() {
+        register();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let root = dir.path().to_path_buf();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            let source = source(&root);
+            let cache = ResponseCache::default();
+            let first = cache.resolve(source, request("/a.txt")).await?;
+            let second = cache.resolve(source, request("/a.txt")).await?;
+            assert_eq!(first, second);
+            assert_eq!(text(second).await?, "a");
+            assert!(!cache.is_uncacheable("GET", "/a.txt"));
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn invalidates_the_cached_content_when_the_source_changes() {
+        register();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let root = dir.path().to_path_buf();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            let disk = DiskFileSystem::new("test".into(), root.to_str().unwrap().into(), vec![]);
+            let source = Vc::upcast(StaticAssetsContentSource::new("".into(), disk.root()));
+            let cache = ResponseCache::default();
+            let first = cache.resolve(source, request("/a.txt")).await?;
+            assert_eq!(text(first).await?, "a");
+
+            fs::write(root.join("a.txt"), "b").unwrap();
+            disk.await?.invalidate();
+            let second = cache.resolve(source, request("/a.txt")).await?;
+            assert_eq!(first, second);
+            assert_eq!(text(second).await?, "b");
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn does_not_cache_content_that_varies_on_headers() {
+        register();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("index.html"), "<html></html>").unwrap();
+        let root = dir.path().to_path_buf();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            let source = source(&root);
+            let cache = ResponseCache::default();
+            // Served the document by the SPA fallback, which checks the
+            // accept header.
+            let first = cache.resolve(source, request("/dashboard")).await?;
+            assert_eq!(text(first).await?, "<html></html>");
+            assert!(cache.is_uncacheable("GET", "/dashboard"));
+
+            let second = cache.resolve(source, request("/dashboard")).await?;
+            assert_ne!(first, second);
+            assert_eq!(text(second).await?, "<html></html>");
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn forgets_the_least_recently_requested_uncacheable_content() {
+        register();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("index.html"), "<html></html>").unwrap();
+        let root = dir.path().to_path_buf();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            let source = source(&root);
+            let cache = ResponseCache::default();
+            for i in 0..super::MAX_UNCACHEABLE_ENTRIES {
+                cache.resolve(source, request(&format!("/{i}"))).await?;
+            }
+            // Requesting the first page again makes it the most recent one.
+            cache.resolve(source, request("/0")).await?;
+            cache.resolve(source, request("/new")).await?;
+            assert!(cache.is_uncacheable("GET", "/0"));
+            assert!(!cache.is_uncacheable("GET", "/1"));
+            assert!(cache.is_uncacheable("GET", "/new"));
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use turbo_tasks::{RcStr, Value, Vc};
+use turbopack_core::introspect::{Introspectable, IntrospectableChildren};
+
+use super::{
+    route_tree::{RouteTree, RouteTrees, RouteType},
+    ContentSource, ContentSourceContent, ContentSourceData, ContentSourceDataFilter,
+    ContentSourceDataVary, ContentSources, GetContentSourceContent, RewriteBuilder,
+};
+
+/// Options of the [SpaFallbackContentSource].
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+pub struct SpaFallbackOptions {
+    /// The path and query of the HTML document that is served instead, e.g.
+    /// `/index.html`. It's resolved like any other request.
+    pub index: RcStr,
+    /// Path prefixes without a leading slash, e.g. `api/`, that are never
+    /// served the document, so unknown API routes respond with a 404.
+    pub excluded_prefixes: Vec<RcStr>,
+}
+
+/// Serves an HTML document for page requests that no route of `source`
+/// matches, like the history API fallback of dev servers for single page
+/// applications, which route on the client.
+///
+/// Only `GET` requests that accept `text/html` and whose last path segment has
+/// no file extension are page requests. Unmatched requests of assets and of
+/// the excluded prefixes respond with a 404 as usual.
+#[turbo_tasks::value(shared)]
+pub struct SpaFallbackContentSource {
+    pub source: Vc<Box<dyn ContentSource>>,
+    pub options: Vc<SpaFallbackOptions>,
+}
+
+#[turbo_tasks::value_impl]
+impl SpaFallbackContentSource {
+    #[turbo_tasks::function]
+    pub fn new(
+        source: Vc<Box<dyn ContentSource>>,
+        options: Vc<SpaFallbackOptions>,
+    ) -> Vc<SpaFallbackContentSource> {
+        SpaFallbackContentSource { source, options }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ContentSource for SpaFallbackContentSource {
+    #[turbo_tasks::function]
+    fn get_routes(&self) -> Vc<RouteTree> {
+        Vc::<RouteTrees>::cell(vec![
+            self.source.get_routes(),
+            RouteTree::new_route(
+                Vec::new(),
+                RouteType::NotFound,
+                Vc::upcast(SpaFallbackGetContent::new(self.options)),
+            ),
+        ])
+        .merge()
+    }
+
+    #[turbo_tasks::function]
+    fn get_children(&self) -> Vc<ContentSources> {
+        Vc::cell(vec![self.source])
+    }
+}
+
+#[turbo_tasks::value]
+struct SpaFallbackGetContent {
+    options: Vc<SpaFallbackOptions>,
+}
+
+#[turbo_tasks::value_impl]
+impl SpaFallbackGetContent {
+    #[turbo_tasks::function]
+    fn new(options: Vc<SpaFallbackOptions>) -> Vc<Self> {
+        SpaFallbackGetContent { options }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl GetContentSourceContent for SpaFallbackGetContent {
+    #[turbo_tasks::function]
+    fn vary(&self) -> Vc<ContentSourceDataVary> {
+        ContentSourceDataVary {
+            method: true,
+            headers: Some(ContentSourceDataFilter::Subset(BTreeSet::from([
+                "accept".to_string()
+            ]))),
+            ..Default::default()
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn get(
+        &self,
+        path: RcStr,
+        data: Value<ContentSourceData>,
+    ) -> Result<Vc<ContentSourceContent>> {
+        let options = self.options.await?;
+        let accepts_html = data
+            .headers
+            .as_ref()
+            .and_then(|headers| headers.get("accept"))
+            .is_some_and(|accept| accept.contains("text/html"));
+        let is_asset = path
+            .rsplit('/')
+            .next()
+            .is_some_and(|name| name.contains('.'));
+        let is_excluded = options
+            .excluded_prefixes
+            .iter()
+            .any(|prefix| path.starts_with(prefix.as_str()));
+        // The document itself is not found either, e.g. when it's `/`.
+        let index_path = options.index.split(['?', '#']).next().unwrap_or_default();
+        let is_index = index_path.trim_start_matches('/') == path.as_str();
+        if data.method.as_deref() != Some("GET")
+            || !accepts_html
+            || is_asset
+            || is_excluded
+            || is_index
+        {
+            return Ok(ContentSourceContent::Next.cell());
+        }
+        Ok(ContentSourceContent::Rewrite(
+            RewriteBuilder::new(options.index.clone())
+                .build()
+                .to_resolved()
+                .await?,
+        )
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Introspectable for SpaFallbackContentSource {
+    #[turbo_tasks::function]
+    fn ty(&self) -> Vc<RcStr> {
+        Vc::cell("spa fallback content source".into())
+    }
+
+    #[turbo_tasks::function]
+    async fn title(&self) -> Result<Vc<RcStr>> {
+        Ok(Vc::cell(self.options.await?.index.clone()))
+    }
+
+    #[turbo_tasks::function]
+    async fn children(&self) -> Result<Vc<IntrospectableChildren>> {
+        Ok(Vc::cell(
+            Vc::try_resolve_sidecast::<Box<dyn Introspectable>>(self.source)
+                .await?
+                .map(|source| (Vc::cell("source".into()), source))
+                .into_iter()
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+
+    use anyhow::Result;
+    use turbo_tasks::{RcStr, TurboTasks, Value, Vc};
+    use turbo_tasks_memory::MemoryBackend;
+
+    use super::{SpaFallbackContentSource, SpaFallbackOptions};
+    use crate::source::{
+        headers::{HeaderValue, Headers},
+        ContentSource, ContentSourceContent, ContentSourceData, NoContentSource, RewriteType,
+    };
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    /// Returns the path and query that a request of `path` is rewritten to, or
+    /// `None` when it's not found.
+    async fn route(method: &str, path: &str, accept: &str) -> Result<Option<RcStr>> {
+        let source = SpaFallbackContentSource::new(
+            Vc::upcast(NoContentSource::new()),
+            SpaFallbackOptions {
+                index: "/".into(),
+                excluded_prefixes: vec!["api/".into()],
+            }
+            .cell(),
+        );
+        let mut headers = Headers::default();
+        headers.insert(
+            "accept".to_string(),
+            HeaderValue::SingleString(accept.to_string()),
+        );
+        let data = ContentSourceData {
+            method: Some(method.into()),
+            headers: Some(headers),
+            ..Default::default()
+        };
+        for get_content in source.get_routes().get(path.into()).await?.iter() {
+            match &*get_content
+                .get(path.into(), Value::new(data.clone()))
+                .await?
+            {
+                ContentSourceContent::Rewrite(rewrite) => {
+                    let RewriteType::Location { path_and_query } = &rewrite.await?.ty else {
+                        panic!("rewrites to a location");
+                    };
+                    return Ok(Some(path_and_query.clone()));
+                }
+                ContentSourceContent::Next => {}
+                _ => return Ok(None),
+            }
+        }
+        Ok(None)
+    }
+
+    #[tokio::test]
+    async fn serves_the_document_for_page_requests() {
+        register();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            assert_eq!(
+                route("GET", "dashboard/settings", "text/html,*/*;q=0.8").await?,
+                Some("/".into())
+            );
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn does_not_serve_the_document_for_other_requests() {
+        register();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async move {
+            // Not a page request
+            assert_eq!(route("POST", "dashboard", "text/html").await?, None);
+            assert_eq!(route("GET", "dashboard", "application/json").await?, None);
+            // Assets
+            assert_eq!(route("GET", "static/app.js", "text/html").await?, None);
+            // Excluded prefixes
+            assert_eq!(route("GET", "api/users", "text/html").await?, None);
+            // The document itself
+            assert_eq!(route("GET", "", "text/html").await?, None);
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
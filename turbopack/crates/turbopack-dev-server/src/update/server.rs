@@ -1,5 +1,6 @@
 use std::{
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
@@ -11,17 +12,22 @@ use pin_project_lite::pin_project;
 use tokio::select;
 use tokio_stream::StreamMap;
 use tracing::{instrument, Level};
-use turbo_tasks::{TransientInstance, TurboTasksApi, Vc};
+use turbo_tasks::{RcStr, TransientInstance, TurboTasksApi, Vc};
 use turbo_tasks_fs::json::parse_json_with_source_context;
-use turbopack_core::{error::PrettyPrintError, issue::IssueReporter, version::Update};
+use turbopack_core::{
+    error::PrettyPrintError,
+    issue::IssueReporter,
+    version::{Update, VersionHistory},
+};
 use turbopack_ecmascript_hmr_protocol::{
-    ClientMessage, ClientUpdateInstruction, Issue, ResourceIdentifier,
+    ClientMessage, ClientUpdateInstruction, Issue, ResourceIdentifier, EMPTY_ISSUES,
 };
 
 use super::stream::UpdateStream;
 use crate::{
+    fault_injection::FaultInjection,
     source::{request::SourceRequest, resolve::resolve_source_request, Body},
-    update::stream::UpdateStreamItem,
+    update::stream::{UpdateStreamItem, VersionedUpdateStreamItem},
     SourceProvider,
 };
 
@@ -30,14 +36,26 @@ pub(crate) struct UpdateServer<P: SourceProvider> {
     source_provider: P,
     #[allow(dead_code)]
     issue_reporter: Vc<Box<dyn IssueReporter>>,
+    version_history: Arc<VersionHistory>,
+    fault_injection: Arc<FaultInjection>,
 }
 
 impl<P: SourceProvider + Clone + Send + Sync> UpdateServer<P> {
     /// Create a new update server with the given websocket and content source.
-    pub fn new(source_provider: P, issue_reporter: Vc<Box<dyn IssueReporter>>) -> Self {
+    ///
+    /// The `version_history` is shared between all connections, so that
+    /// reconnecting clients can be updated from the versions they last applied.
+    pub fn new(
+        source_provider: P,
+        issue_reporter: Vc<Box<dyn IssueReporter>>,
+        version_history: Arc<VersionHistory>,
+        fault_injection: Arc<FaultInjection>,
+    ) -> Self {
         Self {
             source_provider,
             issue_reporter,
+            version_history,
+            fault_injection,
         }
     }
 
@@ -61,7 +79,7 @@ impl<P: SourceProvider + Clone + Send + Sync> UpdateServer<P> {
             select! {
                 message = client.try_next() => {
                     match message? {
-                        Some(ClientMessage::Subscribe { resource }) => {
+                        Some(ClientMessage::Subscribe { resource, version }) => {
                             let get_content = {
                                 let source_provider = self.source_provider.clone();
                                 let request = resource_to_request(&resource)?;
@@ -74,7 +92,12 @@ impl<P: SourceProvider + Clone + Send + Sync> UpdateServer<P> {
                                     )
                                 }
                             };
-                            match UpdateStream::new(resource.to_string().into(), TransientInstance::new(Box::new(get_content))).await {
+                            match UpdateStream::new(
+                                resource.to_string().into(),
+                                TransientInstance::new(Box::new(get_content)),
+                                version.map(RcStr::from),
+                                self.version_history.clone(),
+                            ).await {
                                 Ok(stream) => {
                                     streams.insert(resource, stream);
                                 }
@@ -97,6 +120,9 @@ impl<P: SourceProvider + Clone + Send + Sync> UpdateServer<P> {
                 }
                 Some((resource, update)) = streams.next() => {
                     match update {
+                        Ok(_) if self.fault_injection.drop_update(&resource.path) => {
+                            println!("[UpdateServer]: dropped update for {resource} (injected fault)");
+                        }
                         Ok(update) => {
                             Self::send_update(&mut client, &mut streams, resource, &update).await?;
                         }
@@ -116,9 +142,10 @@ impl<P: SourceProvider + Clone + Send + Sync> UpdateServer<P> {
         client: &mut UpdateClient,
         streams: &mut StreamMap<ResourceIdentifier, UpdateStream>,
         resource: ResourceIdentifier,
-        item: &UpdateStreamItem,
+        (item, version): &VersionedUpdateStreamItem,
     ) -> Result<()> {
-        match item {
+        let version = version.as_deref();
+        match &**item {
             UpdateStreamItem::NotFound => {
                 // If the resource was not found, we remove the stream and indicate that to the
                 // client.
@@ -127,6 +154,15 @@ impl<P: SourceProvider + Clone + Send + Sync> UpdateServer<P> {
                     .send(ClientUpdateInstruction::not_found(&resource))
                     .await?;
             }
+            UpdateStreamItem::Replay { instructions } => {
+                let instructions = instructions.iter().map(|i| &**i).collect();
+                client
+                    .send(
+                        ClientUpdateInstruction::replay(&resource, instructions, EMPTY_ISSUES)
+                            .with_version(version),
+                    )
+                    .await?;
+            }
             UpdateStreamItem::Found { update, issues } => {
                 let issues = issues
                     .iter()
@@ -136,21 +172,30 @@ impl<P: SourceProvider + Clone + Send + Sync> UpdateServer<P> {
                     Update::Partial(partial) => {
                         let partial_instruction = &partial.instruction;
                         client
-                            .send(ClientUpdateInstruction::partial(
-                                &resource,
-                                partial_instruction,
-                                &issues,
-                            ))
+                            .send(
+                                ClientUpdateInstruction::partial(
+                                    &resource,
+                                    partial_instruction,
+                                    &issues,
+                                )
+                                .with_version(version),
+                            )
                             .await?;
                     }
                     Update::Missing | Update::Total(_) => {
                         client
-                            .send(ClientUpdateInstruction::restart(&resource, &issues))
+                            .send(
+                                ClientUpdateInstruction::restart(&resource, &issues)
+                                    .with_version(version),
+                            )
                             .await?;
                     }
                     Update::None => {
                         client
-                            .send(ClientUpdateInstruction::issues(&resource, &issues))
+                            .send(
+                                ClientUpdateInstruction::issues(&resource, &issues)
+                                    .with_version(version),
+                            )
                             .await?;
                     }
                 }
@@ -1,11 +1,14 @@
-use std::pin::Pin;
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
 
 use anyhow::Result;
 use futures::prelude::*;
 use tokio::sync::mpsc::Sender;
 use tokio_stream::wrappers::ReceiverStream;
 use tracing::Instrument;
-use turbo_tasks::{IntoTraitRef, RcStr, ReadRef, TransientInstance, Vc};
+use turbo_tasks::{IntoTraitRef, RcStr, ReadRef, TraitRef, TransientInstance, Vc};
 use turbo_tasks_fs::{FileSystem, FileSystemPath};
 use turbopack_core::{
     error::PrettyPrintError,
@@ -15,8 +18,8 @@ use turbopack_core::{
     },
     server_fs::ServerFileSystem,
     version::{
-        NotFoundVersion, PartialUpdate, TotalUpdate, Update, Version, VersionState,
-        VersionedContent,
+        NotFoundVersion, ResumedVersion, TotalUpdate, Update, Version, VersionHistory,
+        VersionState, VersionedContent,
     },
 };
 
@@ -40,7 +43,7 @@ fn extend_issues(issues: &mut Vec<ReadRef<PlainIssue>>, new_issues: Vec<ReadRef<
     }
 }
 
-#[turbo_tasks::function]
+#[turbo_tasks::function(transient)]
 async fn get_update_stream_item(
     resource: RcStr,
     from: Vc<VersionState>,
@@ -158,7 +161,7 @@ async fn get_update_stream_item(
     }
 }
 
-#[turbo_tasks::function]
+#[turbo_tasks::function(transient)]
 async fn compute_update_stream(
     resource: RcStr,
     from: Vc<VersionState>,
@@ -175,15 +178,28 @@ async fn compute_update_stream(
     Default::default()
 }
 
+/// An item of an [`UpdateStream`], along with the id of the version the
+/// resource is at after applying it, if it changed.
+pub(super) type VersionedUpdateStreamItem = (ReadRef<UpdateStreamItem>, Option<RcStr>);
+
 pub(super) struct UpdateStream(
-    Pin<Box<dyn Stream<Item = Result<ReadRef<UpdateStreamItem>>> + Send + Sync>>,
+    Pin<Box<dyn Stream<Item = Result<VersionedUpdateStreamItem>> + Send + Sync>>,
 );
 
 impl UpdateStream {
-    #[tracing::instrument(skip(get_content), name = "UpdateStream::new")]
+    /// Creates a stream of updates for `resource`.
+    ///
+    /// When the client is resubscribing with the id of the last version it
+    /// applied, the first item brings it up to date from that version, or
+    /// replays the updates it missed when the version isn't known anymore.
+    /// Versions and updates sent to clients are recorded in `history` for that
+    /// purpose.
+    #[tracing::instrument(skip(get_content, history), name = "UpdateStream::new")]
     pub async fn new(
         resource: RcStr,
         get_content: TransientInstance<GetContentFn>,
+        client_version: Option<RcStr>,
+        history: Arc<VersionHistory>,
     ) -> Result<UpdateStream> {
         let (sx, rx) = tokio::sync::mpsc::channel(32);
 
@@ -197,10 +213,27 @@ impl UpdateStream {
             ResolveSourceRequestResult::HttpProxy(proxy_result) => Vc::upcast(proxy_result),
             _ => Vc::upcast(NotFoundVersion::new()),
         };
-        let version_state = VersionState::new(version.into_trait_ref().await?).await?;
+        let version = version.into_trait_ref().await?;
+        let (from, replayed) = match history
+            .resume(&resource, version.clone(), client_version.as_deref())
+            .await?
+        {
+            ResumedVersion::Version(from) => (from, None),
+            ResumedVersion::Replay(instructions) => (version.clone(), Some(instructions)),
+        };
+        // The id of the version the client is at, which the updates sent to it
+        // are recorded from.
+        let last_version = Arc::new(Mutex::new(
+            TraitRef::cell(from.clone()).id().await?.clone_value(),
+        ));
+        // The client always learns its version from the first item, even when
+        // it is already up to date.
+        let current_version = history.insert(resource.clone(), version).await?;
+        let mut initial_version = Some(current_version.clone());
+        let version_state = VersionState::new(from).await?;
 
         let _ = compute_update_stream(
-            resource,
+            resource.clone(),
             version_state,
             get_content,
             TransientInstance::new(sx),
@@ -219,24 +252,49 @@ impl UpdateStream {
                     } else {
                         (false, false)
                     };
+                let initial_version = initial_version.take();
+                let resource = resource.clone();
+                let history = history.clone();
+                let last_version = last_version.clone();
 
                 async move {
-                    match item.as_deref() {
-                        Ok(UpdateStreamItem::Found { update, .. }) => {
+                    let item = match item {
+                        Ok(item) => item,
+                        Err(err) => return Some(Err(err)),
+                    };
+                    match &*item {
+                        UpdateStreamItem::Found { update, .. } => {
                             match &**update {
-                                Update::Partial(PartialUpdate { to, .. })
-                                | Update::Total(TotalUpdate { to }) => {
+                                Update::Partial(partial) => {
+                                    version_state
+                                        .set(partial.to.clone())
+                                        .await
+                                        .expect("failed to update version");
+                                    let from = last_version.lock().unwrap().clone();
+                                    let version =
+                                        history.insert_update(resource, from, partial).await;
+                                    if let Ok(version) = &version {
+                                        *last_version.lock().unwrap() = version.clone();
+                                    }
+
+                                    Some(version.map(|version| (item, Some(version))))
+                                }
+                                Update::Total(TotalUpdate { to }) => {
                                     version_state
                                         .set(to.clone())
                                         .await
                                         .expect("failed to update version");
+                                    let version = history.insert(resource, to.clone()).await;
+                                    if let Ok(version) = &version {
+                                        *last_version.lock().unwrap() = version.clone();
+                                    }
 
-                                    Some(item)
+                                    Some(version.map(|version| (item, Some(version))))
                                 }
                                 // Do not propagate empty updates.
                                 Update::None | Update::Missing => {
-                                    if has_issues || issues_changed {
-                                        Some(item)
+                                    if initial_version.is_some() || has_issues || issues_changed {
+                                        Some(Ok((item, initial_version)))
                                     } else {
                                         None
                                     }
@@ -245,7 +303,7 @@ impl UpdateStream {
                         }
                         _ => {
                             // Propagate other updates
-                            Some(item)
+                            Some(Ok((item, None)))
                         }
                     }
                 }
@@ -254,12 +312,21 @@ impl UpdateStream {
             .in_current_span()
         });
 
+        // The replayed updates are sent before any other item.
+        let replayed = replayed.map(|instructions| {
+            Ok((
+                ReadRef::new_owned(UpdateStreamItem::Replay { instructions }),
+                Some(current_version),
+            ))
+        });
+        let stream = stream::iter(replayed).chain(stream);
+
         Ok(UpdateStream(Box::pin(stream)))
     }
 }
 
 impl Stream for UpdateStream {
-    type Item = Result<ReadRef<UpdateStreamItem>>;
+    type Item = Result<VersionedUpdateStreamItem>;
 
     fn poll_next(
         self: Pin<&mut Self>,
@@ -273,6 +340,11 @@ impl Stream for UpdateStream {
 #[derive(Debug)]
 pub enum UpdateStreamItem {
     NotFound,
+    /// Brings a client up to date whose version isn't known anymore, see
+    /// [`ResumedVersion::Replay`].
+    Replay {
+        instructions: Vec<Arc<serde_json::Value>>,
+    },
     Found {
         update: ReadRef<Update>,
         issues: Vec<ReadRef<PlainIssue>>,
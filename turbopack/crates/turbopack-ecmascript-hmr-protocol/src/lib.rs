@@ -33,6 +33,10 @@ pub enum ClientMessage {
     Subscribe {
         #[serde(flatten)]
         resource: ResourceIdentifier,
+        /// The id of the last version of the resource the client applied, if
+        /// it is resubscribing after a reconnect.
+        #[serde(default)]
+        version: Option<String>,
     },
     #[serde(rename = "turbopack-unsubscribe")]
     Unsubscribe {
@@ -48,6 +52,10 @@ pub struct ClientUpdateInstruction<'a> {
     #[serde(flatten)]
     pub ty: ClientUpdateInstructionType<'a>,
     pub issues: &'a [Issue<'a>],
+    /// The id of the version the resource is at after applying this
+    /// instruction. Sent back by the client when it resubscribes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<&'a str>,
 }
 
 pub const EMPTY_ISSUES: &[Issue<'static>] = &[];
@@ -62,6 +70,7 @@ impl<'a> ClientUpdateInstruction<'a> {
             resource,
             ty,
             issues,
+            version: None,
         }
     }
 
@@ -87,16 +96,44 @@ impl<'a> ClientUpdateInstruction<'a> {
         )
     }
 
+    /// Returns a [`ClientUpdateInstruction`] that brings the resource up to
+    /// date by applying the partial update `instructions` in order.
+    pub fn replay(
+        resource: &'a ResourceIdentifier,
+        instructions: Vec<&'a Value>,
+        issues: &'a [Issue<'a>],
+    ) -> Self {
+        Self::new(
+            resource,
+            ClientUpdateInstructionType::Replay { instructions },
+            issues,
+        )
+    }
+
     pub fn issues(resource: &'a ResourceIdentifier, issues: &'a [Issue<'a>]) -> Self {
         Self::new(resource, ClientUpdateInstructionType::Issues, issues)
     }
 
-    pub fn with_issues(self, issues: &'a [Issue<'a>]) -> Self {
-        Self {
-            resource: self.resource,
-            ty: self.ty,
+    /// Returns a [`ClientUpdateInstruction`] that replaces the resource with
+    /// `content`, for clients that can't load it themselves.
+    pub fn sync(
+        resource: &'a ResourceIdentifier,
+        content: &'a str,
+        issues: &'a [Issue<'a>],
+    ) -> Self {
+        Self::new(
+            resource,
+            ClientUpdateInstructionType::Sync { content },
             issues,
-        }
+        )
+    }
+
+    pub fn with_issues(self, issues: &'a [Issue<'a>]) -> Self {
+        Self { issues, ..self }
+    }
+
+    pub fn with_version(self, version: Option<&'a str>) -> Self {
+        Self { version, ..self }
     }
 }
 
@@ -106,7 +143,9 @@ pub enum ClientUpdateInstructionType<'a> {
     Restart,
     NotFound,
     Partial { instruction: &'a Value },
+    Replay { instructions: Vec<&'a Value> },
     Issues,
+    Sync { content: &'a str },
 }
 
 #[derive(Serialize)]
@@ -188,3 +227,97 @@ impl<'a> From<&'a PlainIssue> for Issue<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{ClientMessage, ClientUpdateInstruction, ResourceIdentifier, EMPTY_ISSUES};
+
+    #[test]
+    fn parses_the_version_of_a_subscription() {
+        let message: ClientMessage = serde_json::from_value(json!({
+            "type": "turbopack-subscribe",
+            "path": "chunk-list.json",
+            "version": "abc",
+        }))
+        .unwrap();
+        let ClientMessage::Subscribe { resource, version } = message else {
+            panic!("expected a subscription");
+        };
+        assert_eq!(resource.path, "chunk-list.json");
+        assert_eq!(version.as_deref(), Some("abc"));
+
+        // Clients subscribing for the first time don't send a version.
+        let message: ClientMessage = serde_json::from_value(json!({
+            "type": "turbopack-subscribe",
+            "path": "chunk-list.json",
+        }))
+        .unwrap();
+        assert!(matches!(
+            message,
+            ClientMessage::Subscribe { version: None, .. }
+        ));
+    }
+
+    #[test]
+    fn sends_the_version_of_an_instruction() {
+        let resource = ResourceIdentifier {
+            path: "chunk-list.json".to_string(),
+            headers: None,
+        };
+        let instruction = ClientUpdateInstruction::issues(&resource, EMPTY_ISSUES);
+        assert!(serde_json::to_value(&instruction)
+            .unwrap()
+            .get("version")
+            .is_none());
+
+        let instruction = instruction.with_version(Some("abc"));
+        assert_eq!(
+            serde_json::to_value(&instruction).unwrap()["version"],
+            json!("abc")
+        );
+    }
+
+    #[test]
+    fn sends_the_content_of_a_sync_instruction() {
+        let resource = ResourceIdentifier {
+            path: "chunk-list.json".to_string(),
+            headers: None,
+        };
+        let instruction = ClientUpdateInstruction::sync(&resource, "content", EMPTY_ISSUES)
+            .with_version(Some("abc"));
+        assert_eq!(
+            serde_json::to_value(&instruction).unwrap(),
+            json!({
+                "resource": { "path": "chunk-list.json", "headers": null },
+                "type": "sync",
+                "content": "content",
+                "issues": [],
+                "version": "abc",
+            })
+        );
+    }
+
+    #[test]
+    fn sends_the_instructions_of_a_replay_instruction() {
+        let resource = ResourceIdentifier {
+            path: "chunk-list.json".to_string(),
+            headers: None,
+        };
+        let (first, second) = (json!({ "type": "a" }), json!({ "type": "b" }));
+        let instruction =
+            ClientUpdateInstruction::replay(&resource, vec![&first, &second], EMPTY_ISSUES)
+                .with_version(Some("abc"));
+        assert_eq!(
+            serde_json::to_value(&instruction).unwrap(),
+            json!({
+                "resource": { "path": "chunk-list.json", "headers": null },
+                "type": "replay",
+                "instructions": [{ "type": "a" }, { "type": "b" }],
+                "issues": [],
+                "version": "abc",
+            })
+        );
+    }
+}
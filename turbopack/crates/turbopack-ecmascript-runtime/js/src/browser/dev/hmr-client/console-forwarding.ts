@@ -0,0 +1,73 @@
+import { getLastUpdateId } from "./hmr-client";
+
+export type ConsoleForwardingOptions = {
+  /** The path of the console endpoint of the dev server. */
+  path: string;
+};
+
+type ConsoleLevel = "error" | "warn";
+
+/**
+ * Forwards errors and warnings of the browser console, and uncaught errors, to
+ * the dev server, which prints them with source-mapped stack frames.
+ *
+ * Forwarding is opt-in on the server: when it responds that the endpoint
+ * doesn't exist, nothing is sent anymore.
+ */
+export function forwardConsole({ path }: ConsoleForwardingOptions) {
+  let enabled = true;
+
+  function send(level: ConsoleLevel, args: unknown[]) {
+    if (!enabled) return;
+    const error = args.find((arg): arg is Error => arg instanceof Error);
+    const body = JSON.stringify({
+      level,
+      message: args.map(formatArg).join(" "),
+      stack: error?.stack,
+      updateId: getLastUpdateId(),
+      page: location.href,
+    });
+    fetch(path, {
+      method: "POST",
+      headers: { "content-type": "application/json" },
+      body,
+      keepalive: true,
+    })
+      .then((response) => {
+        if (response.status === 404) {
+          enabled = false;
+        }
+      })
+      // Failing to forward must not log to the console again.
+      .catch(() => {});
+  }
+
+  for (const level of ["error", "warn"] as const) {
+    const original = console[level];
+    console[level] = (...args: unknown[]) => {
+      original.apply(console, args);
+      send(level, args);
+    };
+  }
+
+  window.addEventListener("error", (event) => {
+    send("error", [event.error ?? event.message]);
+  });
+  window.addEventListener("unhandledrejection", (event) => {
+    send("error", [event.reason]);
+  });
+}
+
+function formatArg(arg: unknown): string {
+  if (arg instanceof Error) {
+    return `${arg.name}: ${arg.message}`;
+  }
+  if (typeof arg === "string") {
+    return arg;
+  }
+  try {
+    return JSON.stringify(arg) ?? String(arg);
+  } catch {
+    return String(arg);
+  }
+}
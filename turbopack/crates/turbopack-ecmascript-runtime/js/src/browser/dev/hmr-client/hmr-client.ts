@@ -75,6 +75,12 @@ export function connect({
 type UpdateCallbackSet = {
   callbacks: Set<UpdateCallback>;
   unsubscribe: () => void;
+  /**
+   * The id of the last version of the resource that was applied. Sent when
+   * resubscribing, so the server can replay missed updates instead of
+   * requiring a full reload.
+   */
+  version?: string;
 };
 
 const updateCallbackSets: Map<ResourceKey, UpdateCallbackSet> = new Map();
@@ -94,11 +100,13 @@ function resourceKey(resource: ResourceIdentifier): ResourceKey {
 
 function subscribeToUpdates(
   sendMessage: SendMessage,
-  resource: ResourceIdentifier
+  resource: ResourceIdentifier,
+  version?: string
 ): () => void {
   sendJSON(sendMessage, {
     type: "turbopack-subscribe",
     ...resource,
+    version,
   });
 
   return () => {
@@ -110,8 +118,8 @@ function subscribeToUpdates(
 }
 
 function handleSocketConnected(sendMessage: SendMessage) {
-  for (const key of updateCallbackSets.keys()) {
-    subscribeToUpdates(sendMessage, JSON.parse(key));
+  for (const [key, callbackSet] of updateCallbackSets) {
+    subscribeToUpdates(sendMessage, JSON.parse(key), callbackSet.version);
   }
 }
 
@@ -492,11 +500,31 @@ export function setHooks(newHooks: typeof hooks) {
   Object.assign(hooks, newHooks);
 }
 
+/**
+ * The version of the last partial update that was received, which identifies
+ * the HMR update the page is running.
+ */
+let lastUpdateId: string | undefined;
+
+export function getLastUpdateId(): string | undefined {
+  return lastUpdateId;
+}
+
 function handleSocketMessage(msg: ServerMessage) {
   sortIssues(msg.issues);
 
   handleIssues(msg);
 
+  if (msg.version != null) {
+    const callbackSet = updateCallbackSets.get(resourceKey(msg.resource));
+    if (callbackSet) {
+      callbackSet.version = msg.version;
+    }
+    if (msg.type === "partial" || msg.type === "replay") {
+      lastUpdateId = msg.version;
+    }
+  }
+
   switch (msg.type) {
     case "issues":
       // issues are already handled
@@ -505,6 +533,17 @@ function handleSocketMessage(msg: ServerMessage) {
       // aggregate updates
       aggregateUpdates(msg);
       break;
+    case "replay":
+      for (const instruction of msg.instructions) {
+        aggregateUpdates({
+          resource: msg.resource,
+          issues: msg.issues,
+          version: msg.version,
+          type: "partial",
+          instruction,
+        });
+      }
+      break;
     default:
       // run single update
       const runHooks = chunkListsWithPendingUpdates.size === 0;
@@ -1,2 +1,3 @@
 export * from "./hmr-client";
 export * from "./websocket";
+export * from "./console-forwarding";
@@ -599,11 +599,17 @@ function applyChunkListUpdate(update: ChunkListUpdate) {
     for (const [chunkPath, chunkUpdate] of Object.entries(update.chunks)) {
       switch (chunkUpdate.type) {
         case "added":
-          BACKEND.loadChunk(chunkPath, { type: SourceType.Update });
+          trackUpdateChunkLoad(
+            loadChunkPath({ type: SourceType.Update }, chunkPath)
+          );
           break;
-        case "total":
-          DEV_BACKEND.reloadChunk?.(chunkPath);
+        case "total": {
+          const reload = DEV_BACKEND.reloadChunk?.(chunkPath);
+          if (reload != null) {
+            trackUpdateChunkLoad(reload);
+          }
           break;
+        }
         case "deleted":
           DEV_BACKEND.unloadChunk?.(chunkPath);
           break;
@@ -624,6 +630,34 @@ function applyChunkListUpdate(update: ChunkListUpdate) {
   }
 }
 
+/**
+ * The number of chunk loads of HMR updates in a row that may fail, after
+ * retries, before the page is reloaded. Until then, a later update may still
+ * bring the page up to date.
+ */
+const MAX_UPDATE_CHUNK_LOAD_FAILURES = 3;
+let updateChunkLoadFailures = 0;
+
+function trackUpdateChunkLoad(load: Promise<void>) {
+  load.then(
+    () => {
+      updateChunkLoadFailures = 0;
+    },
+    (error) => {
+      updateChunkLoadFailures++;
+      if (updateChunkLoadFailures >= MAX_UPDATE_CHUNK_LOAD_FAILURES) {
+        console.error(
+          `[turbopack] ${updateChunkLoadFailures} chunks of HMR updates failed to load, reloading the page`,
+          error
+        );
+        DEV_BACKEND.restart();
+      } else {
+        console.warn("[turbopack] A chunk of an HMR update failed to load", error);
+      }
+    }
+  );
+}
+
 function applyEcmascriptMergedUpdate(update: EcmascriptMergedUpdate) {
   const { entries = {}, chunks = {} } = update;
   const { added, modified, chunksAdded, chunksDeleted } = computeChangedModules(
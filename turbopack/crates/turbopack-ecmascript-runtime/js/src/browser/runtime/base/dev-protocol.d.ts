@@ -5,16 +5,31 @@
 type PartialServerMessage = {
   resource: ResourceIdentifier;
   issues: Issue[];
+  version?: string;
   type: "partial";
   instruction: PartialUpdate;
 };
 
+/**
+ * Brings a resource whose version the server doesn't know anymore up to date
+ * by applying the partial updates it missed in order.
+ */
+type ReplayServerMessage = {
+  resource: ResourceIdentifier;
+  issues: Issue[];
+  version?: string;
+  type: "replay";
+  instructions: PartialUpdate[];
+};
+
 // string encoding of a module factory (used in hmr updates)
 type ModuleFactoryString = string;
 
 type ServerMessage = {
   resource: ResourceIdentifier;
   issues: Issue[];
+  /** The id of the version the resource is at after applying this message. */
+  version?: string;
 } & (
   | {
       type: "restart";
@@ -23,6 +38,7 @@ type ServerMessage = {
       type: "notFound";
     }
   | PartialServerMessage
+  | ReplayServerMessage
   | {
       type: "issues";
     }
@@ -89,6 +105,11 @@ type EcmascriptModuleEntry = {
   code: ModuleFactoryString;
   url: string;
   map?: string;
+  /**
+   * The URL of the source map relative to the chunk base path, when it's
+   * too large to be inlined as `map`.
+   */
+  mapUrl?: string;
 };
 
 type ResourceIdentifier = {
@@ -98,6 +119,8 @@ type ResourceIdentifier = {
 
 type ClientMessageSubscribe = {
   type: "turbopack-subscribe";
+  /** The id of the last version of the resource applied by the client. */
+  version?: string;
 } & ResourceIdentifier;
 
 type ClientMessageUnsubscribe = {
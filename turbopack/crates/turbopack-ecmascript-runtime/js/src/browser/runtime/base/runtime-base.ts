@@ -13,6 +13,8 @@
 
 declare var TURBOPACK_WORKER_LOCATION: string;
 declare var CHUNK_BASE_PATH: string;
+/** The delay before each retry of a failed chunk load. Empty when disabled. */
+declare var CHUNK_LOAD_RETRY_DELAYS_MS: number[];
 declare function instantiateModule(id: ModuleId, source: SourceInfo): Module;
 
 type RuntimeParams = {
@@ -64,10 +66,82 @@ type SourceInfo =
     };
 
 interface RuntimeBackend {
-  registerChunk: (chunkPath: ChunkPath, params?: RuntimeParams) => void;
-  loadChunk: (chunkPath: ChunkPath, source: SourceInfo) => Promise<void>;
+  /**
+   * Returns the exports of the last runtime module of the chunk, if the
+   * backend supports ES module chunks.
+   */
+  registerChunk: (
+    chunkPath: ChunkPath,
+    params?: RuntimeParams
+  ) => void | Promise<Module["exports"] | undefined>;
+  /**
+   * Loads a chunk. `attempt` counts the previous failed attempts to load the
+   * chunk, retries must not be served from the cache.
+   */
+  loadChunk: (
+    chunkPath: ChunkPath,
+    source: SourceInfo,
+    attempt: number
+  ) => Promise<void>;
+}
+
+/**
+ * Why a chunk failed to load:
+ *
+ * * `network`: the chunk couldn't be fetched, e.g. because the connection
+ *   dropped or the server responded with an error status.
+ * * `cross-origin`: the chunk is served by another origin, which responded,
+ *   but the browser refused the response, e.g. because of missing CORS headers.
+ * * `integrity`: the chunk could be fetched, but its content doesn't match the
+ *   integrity metadata of its element.
+ */
+type ChunkLoadErrorKind = "network" | "cross-origin" | "integrity";
+
+/**
+ * The error that backends fail with when a chunk can't be loaded. Only these
+ * errors are retried, errors that are thrown while evaluating a chunk aren't.
+ */
+class ChunkLoadError extends Error {
+  name = "ChunkLoadError";
+
+  chunkPath: ChunkPath;
+  url: string;
+  kind: ChunkLoadErrorKind;
+
+  constructor(
+    chunkPath: ChunkPath,
+    url: string,
+    kind: ChunkLoadErrorKind,
+    cause?: unknown
+  ) {
+    super(
+      `Loading chunk ${chunkPath} from ${url} failed (${kind})`,
+      cause != null ? { cause } : undefined
+    );
+    this.chunkPath = chunkPath;
+    this.url = url;
+    this.kind = kind;
+  }
 }
 
+/**
+ * The `detail` of the `turbopack:chunk-load-error` event, which is dispatched
+ * on the global object whenever an attempt to load a chunk fails. Listeners
+ * can call `preventDefault()` to cancel the remaining retries.
+ */
+type ChunkLoadErrorEventDetail = {
+  chunkPath: ChunkPath;
+  url: string;
+  kind: ChunkLoadErrorKind;
+  /** The number of previous failed attempts. */
+  attempt: number;
+  /** Whether the chunk will be requested again, unless cancelled. */
+  willRetry: boolean;
+  error: ChunkLoadError;
+};
+
+const CHUNK_LOAD_ERROR_EVENT = "turbopack:chunk-load-error";
+
 interface DevRuntimeBackend {
   reloadChunk?: (chunkPath: ChunkPath) => Promise<void>;
   unloadChunk?: (chunkPath: ChunkPath) => void;
@@ -189,7 +263,7 @@ async function loadChunkPath(
   chunkPath: ChunkPath
 ): Promise<any> {
   try {
-    await BACKEND.loadChunk(chunkPath, source);
+    await loadChunkPathWithRetries(source, chunkPath);
   } catch (error) {
     let loadReason;
     switch (source.type) {
@@ -218,6 +292,59 @@ async function loadChunkPath(
   }
 }
 
+async function loadChunkPathWithRetries(
+  source: SourceInfo,
+  chunkPath: ChunkPath
+): Promise<void> {
+  for (let attempt = 0; ; attempt++) {
+    try {
+      return await BACKEND.loadChunk(chunkPath, source, attempt);
+    } catch (error) {
+      if (!(error instanceof ChunkLoadError)) {
+        throw error;
+      }
+
+      // Chunks of the runtime are part of the page already, requesting them
+      // again is up to the page.
+      const willRetry =
+        source.type !== SourceType.Runtime &&
+        attempt < CHUNK_LOAD_RETRY_DELAYS_MS.length;
+      const cancelled = dispatchChunkLoadError({
+        chunkPath,
+        url: error.url,
+        kind: error.kind,
+        attempt,
+        willRetry,
+        error,
+      });
+      if (!willRetry || cancelled) {
+        throw error;
+      }
+
+      await new Promise((resolve) =>
+        setTimeout(resolve, CHUNK_LOAD_RETRY_DELAYS_MS[attempt])
+      );
+    }
+  }
+}
+
+/**
+ * Dispatches the `turbopack:chunk-load-error` event and returns whether a
+ * listener cancelled the retries.
+ */
+function dispatchChunkLoadError(detail: ChunkLoadErrorEventDetail): boolean {
+  if (
+    typeof dispatchEvent !== "function" ||
+    typeof CustomEvent !== "function"
+  ) {
+    return false;
+  }
+
+  return !dispatchEvent(
+    new CustomEvent(CHUNK_LOAD_ERROR_EVENT, { detail, cancelable: true })
+  );
+}
+
 /**
  * Returns an absolute url to an asset.
  */
@@ -0,0 +1,77 @@
+/**
+ * This file contains the runtime code specific to the Turbopack development
+ * ECMAScript DOM runtime when the page enforces a strict Content Security
+ * Policy, which forbids `eval` and `new Function`.
+ *
+ * It will be appended to the DOM development backend code. Its `_eval`
+ * declaration comes last in the runtime and thus replaces the `eval`-based one.
+ */
+
+/* eslint-disable @typescript-eslint/no-unused-vars */
+
+/// <reference path="../base/runtime-base.ts" />
+/// <reference path="../base/dev-base.ts" />
+/// <reference path="../../../shared/require-type.d.ts" />
+
+/**
+ * The nonce of the script that loaded the runtime. Scripts inserted by the
+ * runtime need to carry it to be allowed by a nonce-based policy.
+ */
+const CSP_NONCE: string | undefined = (() => {
+  const currentScript = document.currentScript as HTMLScriptElement | null;
+  if (currentScript?.nonce) {
+    return currentScript.nonce;
+  }
+  return (
+    document.querySelector<HTMLScriptElement>("script[nonce]")?.nonce ||
+    undefined
+  );
+})();
+
+const CSP_EVAL_RESULT = "__turbopack_csp_eval_result__";
+
+function _eval({
+  code,
+  url,
+  map,
+  mapUrl,
+}: EcmascriptModuleEntry): ModuleFactory {
+  // Inline scripts are executed synchronously when they are inserted, so the
+  // module factory is available right after. The assignment is prepended to the
+  // first line to keep the lines of the source map intact.
+  code = `globalThis[${JSON.stringify(CSP_EVAL_RESULT)}] = ${code}`;
+  code += `\n\n//# sourceURL=${encodeURI(
+    location.origin + CHUNK_BASE_PATH + url
+  )}`;
+  if (map) {
+    code += `\n//# sourceMappingURL=data:application/json;charset=utf-8;base64,${btoa(
+      // btoa doesn't handle nonlatin characters, so escape them as \x sequences
+      // See https://stackoverflow.com/a/26603875
+      unescape(encodeURIComponent(map))
+    )}`;
+  } else if (mapUrl) {
+    code += `\n//# sourceMappingURL=${encodeURI(
+      location.origin + CHUNK_BASE_PATH + mapUrl
+    )}`;
+  }
+
+  const script = document.createElement("script");
+  if (CSP_NONCE) {
+    script.nonce = CSP_NONCE;
+  }
+  script.textContent = code;
+  document.head.appendChild(script);
+  script.remove();
+
+  const global = globalThis as Record<string, unknown>;
+  const factory = global[CSP_EVAL_RESULT] as ModuleFactory | undefined;
+  delete global[CSP_EVAL_RESULT];
+
+  if (factory == null) {
+    throw new Error(
+      `Failed to evaluate the update of ${url}. The Content Security Policy of the page needs to allow scripts with the nonce of the Turbopack runtime.`
+    );
+  }
+
+  return factory;
+}
@@ -113,7 +113,12 @@ let DEV_BACKEND: DevRuntimeBackend;
   }
 })();
 
-function _eval({ code, url, map }: EcmascriptModuleEntry): ModuleFactory {
+function _eval({
+  code,
+  url,
+  map,
+  mapUrl,
+}: EcmascriptModuleEntry): ModuleFactory {
   code += `\n\n//# sourceURL=${encodeURI(
     location.origin + CHUNK_BASE_PATH + url
   )}`;
@@ -123,6 +128,10 @@ function _eval({ code, url, map }: EcmascriptModuleEntry): ModuleFactory {
       // See https://stackoverflow.com/a/26603875
       unescape(encodeURIComponent(map))
     )}`;
+  } else if (mapUrl) {
+    code += `\n//# sourceMappingURL=${encodeURI(
+      location.origin + CHUNK_BASE_PATH + mapUrl
+    )}`;
   }
 
   // eslint-disable-next-line no-eval
@@ -0,0 +1,33 @@
+/**
+ * This file contains the runtime code for ESM externals in the browser. They
+ * are imported natively, so the browser resolves them with the import map of
+ * the page.
+ *
+ * It will be appended to the base runtime code when the environment supports
+ * ESM externals.
+ */
+
+/* eslint-disable @typescript-eslint/no-unused-vars */
+
+/// <reference path="../base/runtime-base.ts" />
+
+async function browserExternalImport(
+  id: ModuleId,
+  options?: ImportCallOptions
+): Promise<Exports | EsmNamespaceObject> {
+  let raw;
+  try {
+    // @ts-ignore The options argument needs ESM module settings, but the runtime is checked as CommonJS
+    raw = await import(/* turbopackIgnore: true */ id, options);
+  } catch (err) {
+    throw new Error(
+      `Failed to load external module ${id}, make sure that the import map of the page maps it: ${err}`
+    );
+  }
+
+  if (raw && raw.__esModule && raw.default && "default" in raw.default) {
+    return interopEsm(raw.default, createNS(raw), true);
+  }
+
+  return raw;
+}
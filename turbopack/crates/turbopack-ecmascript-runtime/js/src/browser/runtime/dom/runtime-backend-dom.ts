@@ -20,6 +20,11 @@ type ChunkResolver = {
 let BACKEND: RuntimeBackend;
 
 function augmentContext(context: unknown): unknown {
+  // ESM externals are only supported when the environment allows them, see
+  // `externals-dom.ts`.
+  if (typeof browserExternalImport === "function") {
+    (context as { y: typeof browserExternalImport }).y = browserExternalImport;
+  }
   return context;
 }
 
@@ -53,6 +58,15 @@ async function loadWebAssemblyModule(
  */
 const chunkResolvers: Map<ChunkPath, ChunkResolver> = new Map();
 
+/**
+ * The integrity metadata and CORS mode of chunk elements that failed to load,
+ * which the elements of retries keep.
+ */
+const chunkElementAttributes: Map<
+  ChunkPath,
+  { integrity: string; crossOrigin: string | null }
+> = new Map();
+
 (() => {
   BACKEND = {
     async registerChunk(chunkPath, params) {
@@ -83,8 +97,8 @@ const chunkResolvers: Map<ChunkPath, ChunkResolver> = new Map();
       }
     },
 
-    loadChunk(chunkPath, source) {
-      return doLoadChunk(chunkPath, source);
+    loadChunk(chunkPath, source, attempt) {
+      return doLoadChunk(chunkPath, source, attempt);
     },
   };
 
@@ -111,11 +125,109 @@ const chunkResolvers: Map<ChunkPath, ChunkResolver> = new Map();
     return resolver;
   }
 
+  /**
+   * Rejects the resolver of a chunk that failed to load and forgets it, so a
+   * retry requests the chunk again.
+   */
+  function rejectResolver(
+    chunkPath: ChunkPath,
+    resolver: ChunkResolver,
+    error: Error
+  ) {
+    if (chunkResolvers.get(chunkPath) === resolver) {
+      chunkResolvers.delete(chunkPath);
+    }
+    resolver.reject(error);
+  }
+
+  /**
+   * Handles the `error` event of the element of a chunk.
+   */
+  function onChunkElementError(
+    chunkPath: ChunkPath,
+    resolver: ChunkResolver,
+    element: HTMLScriptElement | HTMLLinkElement
+  ) {
+    const url =
+      element instanceof HTMLScriptElement ? element.src : element.href;
+    if (element.integrity || element.crossOrigin != null) {
+      chunkElementAttributes.set(chunkPath, {
+        integrity: element.integrity,
+        crossOrigin: element.crossOrigin,
+      });
+    }
+    // The element can't load anymore, a retry adds a new one.
+    element.remove();
+    rejectResolver(
+      chunkPath,
+      resolver,
+      new ChunkLoadError(chunkPath, url, getChunkLoadErrorKind(url, element))
+    );
+  }
+
+  /**
+   * The `error` event of an element doesn't tell why loading failed, so the
+   * resource timing entry of the request is consulted instead. It shows
+   * whether the server responded, and with which status, as far as the
+   * browser exposes it.
+   */
+  function getChunkLoadErrorKind(
+    url: string,
+    element: HTMLScriptElement | HTMLLinkElement
+  ): ChunkLoadErrorKind {
+    const absoluteUrl = new URL(url, location.href);
+    const crossOrigin = absoluteUrl.origin !== location.origin;
+    const timing =
+      typeof performance !== "undefined" &&
+      typeof performance.getEntriesByName === "function"
+        ? (performance
+            .getEntriesByName(absoluteUrl.href, "resource")
+            .pop() as PerformanceResourceTiming | undefined)
+        : undefined;
+    // Without an entry or a response, the request failed.
+    if (timing == null || timing.responseEnd === 0) {
+      return "network";
+    }
+    // The status is `0` for network errors and responses of other origins
+    // without CORS, and missing in older browsers.
+    const status =
+      "responseStatus" in timing
+        ? (timing as { responseStatus: number }).responseStatus
+        : undefined;
+    if (status != null && (status >= 400 || (status === 0 && !crossOrigin))) {
+      return "network";
+    }
+
+    if (element.integrity) {
+      return "integrity";
+    }
+    return crossOrigin ? "cross-origin" : "network";
+  }
+
+  /**
+   * Applies the integrity metadata and CORS mode of a previous element of the
+   * chunk that failed to load.
+   */
+  function applyChunkElementAttributes(
+    chunkPath: ChunkPath,
+    element: HTMLScriptElement | HTMLLinkElement
+  ) {
+    const attributes = chunkElementAttributes.get(chunkPath);
+    if (attributes != null) {
+      element.integrity = attributes.integrity;
+      element.crossOrigin = attributes.crossOrigin;
+    }
+  }
+
   /**
    * Loads the given chunk, and returns a promise that resolves once the chunk
    * has been loaded.
    */
-  async function doLoadChunk(chunkPath: ChunkPath, source: SourceInfo) {
+  async function doLoadChunk(
+    chunkPath: ChunkPath,
+    source: SourceInfo,
+    attempt: number
+  ) {
     const resolver = getOrCreateResolver(chunkPath);
     if (resolver.resolved) {
       return resolver.promise;
@@ -140,13 +252,22 @@ const chunkResolvers: Map<ChunkPath, ChunkResolver> = new Map();
 
     const chunkUrl = getChunkRelativeUrl(chunkPath);
     const decodedChunkUrl = decodeURI(chunkUrl);
+    // Retries bypass the cache, which may hold a broken response, e.g. one
+    // that was truncated and doesn't match the integrity metadata.
+    const requestUrl =
+      attempt > 0 ? `${chunkUrl}?turbopack-retry=${attempt}` : chunkUrl;
 
     if (typeof importScripts === "function") {
       // We're in a web worker
       if (chunkPath.endsWith(".css")) {
         // ignore
       } else if (chunkPath.endsWith(".js")) {
-        importScripts(TURBOPACK_WORKER_LOCATION + chunkUrl);
+        const url = TURBOPACK_WORKER_LOCATION + requestUrl;
+        try {
+          importScripts(url);
+        } catch (error) {
+          throw new ChunkLoadError(chunkPath, url, "network", error);
+        }
       } else {
         throw new Error(`can't infer type of chunk from path ${chunkPath} in worker`);
       }
@@ -162,9 +283,10 @@ const chunkResolvers: Map<ChunkPath, ChunkResolver> = new Map();
         } else {
           const link = document.createElement("link");
           link.rel = "stylesheet";
-          link.href = chunkUrl;
+          link.href = requestUrl;
+          applyChunkElementAttributes(chunkPath, link);
           link.onerror = () => {
-            resolver.reject();
+            onChunkElementError(chunkPath, resolver, link);
           };
           link.onload = () => {
             // CSS chunks do not register themselves, and as such must be marked as
@@ -182,17 +304,22 @@ const chunkResolvers: Map<ChunkPath, ChunkResolver> = new Map();
           // can't detect that. The Promise will never resolve in this case.
           for (const script of Array.from(previousScripts)) {
             script.addEventListener("error", () => {
-              resolver.reject();
+              onChunkElementError(
+                chunkPath,
+                resolver,
+                script as HTMLScriptElement
+              );
             });
           }
         } else {
           const script = document.createElement("script");
-          script.src = chunkUrl;
+          script.src = requestUrl;
+          applyChunkElementAttributes(chunkPath, script);
           // We'll only mark the chunk as loaded once the script has been executed,
           // which happens in `registerChunk`. Hence the absence of `resolve()` in
           // this branch.
           script.onerror = () => {
-            resolver.reject();
+            onChunkElementError(chunkPath, resolver, script);
           };
           document.body.appendChild(script);
         }
@@ -24,7 +24,10 @@ type ExternalRequire = (
   esm?: boolean
 ) => Exports | EsmNamespaceObject;
 
-type ExternalImport = (id: ModuleId) => Promise<Exports | EsmNamespaceObject>;
+type ExternalImport = (
+  id: ModuleId,
+  options?: ImportCallOptions
+) => Promise<Exports | EsmNamespaceObject>;
 
 interface TurbopackEdgeContext extends TurbopackBaseContext<Module> {
   x: ExternalRequire;
@@ -0,0 +1,99 @@
+/**
+ * This file contains the runtime code specific to the Turbopack development
+ * ECMAScript runtime for ES module chunks in the browser.
+ *
+ * It will be appended to the base development runtime code.
+ */
+
+/* eslint-disable @typescript-eslint/no-unused-vars */
+
+/// <reference path="../base/runtime-base.ts" />
+/// <reference path="../base/dev-base.ts" />
+/// <reference path="./runtime-backend-esm.ts" />
+/// <reference path="../../../shared/require-type.d.ts" />
+
+let DEV_BACKEND: DevRuntimeBackend;
+
+(() => {
+  DEV_BACKEND = {
+    unloadChunk(chunkPath) {
+      chunkResolvers.delete(chunkPath);
+
+      // ES modules can't be unloaded, so only the links of CSS chunks are
+      // removed.
+      if (chunkPath.endsWith(".css")) {
+        const chunkUrl = getChunkRelativeUrl(chunkPath);
+        const links = document.querySelectorAll(
+          `link[href="${chunkUrl}"],link[href^="${chunkUrl}?"]`
+        );
+        for (const link of Array.from(links)) {
+          link.remove();
+        }
+      }
+    },
+
+    reloadChunk(chunkPath) {
+      return new Promise<void>((resolve, reject) => {
+        if (!chunkPath.endsWith(".css")) {
+          reject(new Error("The ESM backend can only reload CSS chunks"));
+          return;
+        }
+
+        const chunkUrl = getChunkRelativeUrl(chunkPath);
+        const previousLinks = document.querySelectorAll(
+          `link[rel=stylesheet][href="${chunkUrl}"],link[rel=stylesheet][href^="${chunkUrl}?"]`
+        );
+        if (previousLinks.length === 0) {
+          reject(new Error(`No link element found for chunk ${chunkPath}`));
+          return;
+        }
+
+        const link = document.createElement("link");
+        link.rel = "stylesheet";
+        // The query makes sure the stylesheet is requested again.
+        link.href = `${chunkUrl}?ts=${Date.now()}`;
+        link.onerror = () => {
+          reject();
+        };
+        link.onload = () => {
+          // First load the new CSS, then remove the old ones, which prevents
+          // flickering.
+          for (const previousLink of Array.from(previousLinks))
+            previousLink.remove();
+          resolve();
+        };
+        previousLinks[0].parentElement!.insertBefore(
+          link,
+          previousLinks[0].nextSibling
+        );
+      });
+    },
+
+    restart: () => self.location.reload(),
+  };
+})();
+
+function _eval({
+  code,
+  url,
+  map,
+  mapUrl,
+}: EcmascriptModuleEntry): ModuleFactory {
+  code += `\n\n//# sourceURL=${encodeURI(
+    location.origin + CHUNK_BASE_PATH + url
+  )}`;
+  if (map) {
+    code += `\n//# sourceMappingURL=data:application/json;charset=utf-8;base64,${btoa(
+      // btoa doesn't handle nonlatin characters, so escape them as \x sequences
+      // See https://stackoverflow.com/a/26603875
+      unescape(encodeURIComponent(map))
+    )}`;
+  } else if (mapUrl) {
+    code += `\n//# sourceMappingURL=${encodeURI(
+      location.origin + CHUNK_BASE_PATH + mapUrl
+    )}`;
+  }
+
+  // eslint-disable-next-line no-eval
+  return eval(code);
+}
@@ -0,0 +1,197 @@
+/**
+ * This file contains the runtime code specific to the Turbopack ECMAScript
+ * runtime for ES module chunks in the browser.
+ *
+ * ES module chunks import the chunks they depend on, so this backend only
+ * loads the chunks of async imports: JS chunks with `import()` and CSS chunks
+ * with `<link>` elements.
+ *
+ * It will be appended to the base runtime code.
+ */
+
+/* eslint-disable @typescript-eslint/no-unused-vars */
+
+/// <reference path="../base/runtime-base.ts" />
+/// <reference path="../dom/externals-dom.ts" />
+/// <reference path="../../../shared/runtime-types.d.ts" />
+
+type ChunkResolver = {
+  resolved: boolean;
+  resolve: () => void;
+  reject: (error?: Error) => void;
+  promise: Promise<void>;
+};
+
+let BACKEND: RuntimeBackend;
+
+function augmentContext(context: unknown): unknown {
+  // ESM externals are only supported when the environment allows them, see
+  // `externals-dom.ts`.
+  if (typeof browserExternalImport === "function") {
+    (context as { y: typeof browserExternalImport }).y = browserExternalImport;
+  }
+  return context;
+}
+
+function fetchWebAssembly(wasmChunkPath: ChunkPath) {
+  return fetch(getChunkRelativeUrl(wasmChunkPath));
+}
+
+async function loadWebAssembly(
+  _source: unknown,
+  wasmChunkPath: ChunkPath,
+  importsObj: WebAssembly.Imports
+): Promise<Exports> {
+  const req = fetchWebAssembly(wasmChunkPath);
+
+  const { instance } = await WebAssembly.instantiateStreaming(req, importsObj);
+
+  return instance.exports;
+}
+
+async function loadWebAssemblyModule(
+  _source: unknown,
+  wasmChunkPath: ChunkPath
+): Promise<WebAssembly.Module> {
+  const req = fetchWebAssembly(wasmChunkPath);
+
+  return await WebAssembly.compileStreaming(req);
+}
+
+/**
+ * Maps chunk paths to the corresponding resolver.
+ */
+const chunkResolvers: Map<ChunkPath, ChunkResolver> = new Map();
+
+(() => {
+  BACKEND = {
+    async registerChunk(chunkPath, params) {
+      const resolver = getOrCreateResolver(chunkPath);
+      resolver.resolve();
+
+      if (params == null) {
+        return;
+      }
+
+      // The chunks of the chunk group were imported by the chunk itself, so
+      // they only need to be marked as available.
+      await Promise.all(
+        params.otherChunks.map((otherChunkData) =>
+          loadChunk({ type: SourceType.Runtime, chunkPath }, otherChunkData)
+        )
+      );
+
+      // The exports of the last runtime module are exported by the chunk.
+      let exports;
+      for (const moduleId of params.runtimeModuleIds) {
+        exports = getOrInstantiateRuntimeModule(moduleId, chunkPath).exports;
+      }
+      return exports;
+    },
+
+    loadChunk(chunkPath, source, attempt) {
+      return doLoadChunk(chunkPath, source, attempt);
+    },
+  };
+
+  function getOrCreateResolver(chunkPath: ChunkPath): ChunkResolver {
+    let resolver = chunkResolvers.get(chunkPath);
+    if (!resolver) {
+      let resolve: () => void;
+      let reject: (error?: Error) => void;
+      const promise = new Promise<void>((innerResolve, innerReject) => {
+        resolve = innerResolve;
+        reject = innerReject;
+      });
+      resolver = {
+        resolved: false,
+        promise,
+        resolve: () => {
+          resolver!.resolved = true;
+          resolve();
+        },
+        reject: reject!,
+      };
+      chunkResolvers.set(chunkPath, resolver);
+    }
+    return resolver;
+  }
+
+  /**
+   * Rejects the resolver of a chunk that failed to load and forgets it, so a
+   * retry requests the chunk again.
+   */
+  function rejectResolver(
+    chunkPath: ChunkPath,
+    resolver: ChunkResolver,
+    error: Error
+  ) {
+    if (chunkResolvers.get(chunkPath) === resolver) {
+      chunkResolvers.delete(chunkPath);
+    }
+    resolver.reject(error);
+  }
+
+  /**
+   * Loads the given chunk, and returns a promise that resolves once the chunk
+   * has been loaded.
+   */
+  async function doLoadChunk(
+    chunkPath: ChunkPath,
+    source: SourceInfo,
+    attempt: number
+  ) {
+    const resolver = getOrCreateResolver(chunkPath);
+    if (resolver.resolved) {
+      return resolver.promise;
+    }
+
+    if (source.type === SourceType.Runtime) {
+      // Chunks referenced from runtime code are imported by the chunk, or are
+      // CSS chunks that are already linked in the document.
+      if (chunkPath.endsWith(".css")) {
+        resolver.resolve();
+      }
+      return resolver.promise;
+    }
+
+    const chunkUrl = getChunkRelativeUrl(chunkPath);
+    // Retries bypass the cache, which may hold a broken response.
+    const requestUrl =
+      attempt > 0 ? `${chunkUrl}?turbopack-retry=${attempt}` : chunkUrl;
+
+    if (chunkPath.endsWith(".js")) {
+      // ES module chunks register themselves when they are evaluated.
+      import(/* turbopackIgnore: true */ requestUrl).catch((error) => {
+        rejectResolver(
+          chunkPath,
+          resolver,
+          new ChunkLoadError(chunkPath, requestUrl, "network", error)
+        );
+      });
+    } else if (chunkPath.endsWith(".css")) {
+      const link = document.createElement("link");
+      link.rel = "stylesheet";
+      link.href = requestUrl;
+      link.onerror = () => {
+        // The element can't load anymore, a retry adds a new one.
+        link.remove();
+        rejectResolver(
+          chunkPath,
+          resolver,
+          new ChunkLoadError(chunkPath, link.href, "network")
+        );
+      };
+      link.onload = () => {
+        // CSS chunks do not register themselves, and as such must be marked as
+        // loaded instantly.
+        resolver.resolve();
+      };
+      document.head.appendChild(link);
+    } else {
+      throw new Error(`can't infer type of chunk from path ${chunkPath}`);
+    }
+
+    return resolver.promise;
+  }
+})();
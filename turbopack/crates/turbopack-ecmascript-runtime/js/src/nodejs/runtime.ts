@@ -39,7 +39,10 @@ function stringifySourceInfo(source: SourceInfo): string {
 }
 
 type ExternalRequire = (id: ModuleId) => Exports | EsmNamespaceObject;
-type ExternalImport = (id: ModuleId) => Promise<Exports | EsmNamespaceObject>;
+type ExternalImport = (
+  id: ModuleId,
+  options?: ImportCallOptions
+) => Promise<Exports | EsmNamespaceObject>;
 
 interface TurbopackNodeBuildContext extends TurbopackBaseContext<Module> {
   R: ResolvePathFromModule;
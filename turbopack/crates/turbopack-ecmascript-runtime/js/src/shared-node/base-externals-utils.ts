@@ -6,10 +6,11 @@
 /// Currently this is for node.js / edge runtime both.
 /// If a fn requires node.js specific behavior, it should be placed in `node-external-utils` instead.
 
-async function externalImport(id: ModuleId) {
+async function externalImport(id: ModuleId, options?: ImportCallOptions) {
   let raw;
   try {
-    raw = await import(id);
+    // @ts-ignore The options argument needs ESM module settings, but the runtime is checked as CommonJS
+    raw = await import(id, options);
   } catch (err) {
     // TODO(alexkirsz) This can happen when a client-side module tries to load
     // an external module we don't provide a shim for (e.g. querystring, url).
@@ -10,7 +10,10 @@ use turbopack_core::{
 };
 use turbopack_ecmascript::utils::StringifyJs;
 
-use crate::{asset_context::get_runtime_asset_context, embed_js::embed_static_code, RuntimeType};
+use crate::{
+    asset_context::get_runtime_asset_context, embed_js::embed_static_code, ChunkLoadRetry,
+    OutputFormat, RuntimeType,
+};
 
 /// Returns the code for the ECMAScript runtime.
 #[turbo_tasks::function]
@@ -18,6 +21,8 @@ pub async fn get_browser_runtime_code(
     environment: Vc<Environment>,
     chunk_base_path: Vc<Option<RcStr>>,
     runtime_type: Value<RuntimeType>,
+    output_format: Value<OutputFormat>,
+    chunk_load_retry: Value<ChunkLoadRetry>,
     output_root: Vc<RcStr>,
 ) -> Result<Vc<Code>> {
     let asset_context = get_runtime_asset_context(environment);
@@ -45,6 +50,19 @@ pub async fn get_browser_runtime_code(
 
     let mut runtime_backend_code = vec![];
     match (chunk_loading, *runtime_type) {
+        // ES module chunks import the chunks they depend on, so they only need a reduced
+        // backend that loads the chunks of async imports.
+        (ChunkLoading::Dom, RuntimeType::Development)
+            if matches!(*output_format, OutputFormat::EsModule) =>
+        {
+            runtime_backend_code.push("browser/runtime/esm/runtime-backend-esm.ts");
+            runtime_backend_code.push("browser/runtime/esm/dev-backend-esm.ts");
+        }
+        (ChunkLoading::Dom, RuntimeType::Production)
+            if matches!(*output_format, OutputFormat::EsModule) =>
+        {
+            runtime_backend_code.push("browser/runtime/esm/runtime-backend-esm.ts");
+        }
         (ChunkLoading::Edge, RuntimeType::Development) => {
             runtime_backend_code.push("browser/runtime/edge/runtime-backend-edge.ts");
             runtime_backend_code.push("browser/runtime/edge/dev-backend-edge.ts");
@@ -59,6 +77,9 @@ pub async fn get_browser_runtime_code(
         (ChunkLoading::Dom, RuntimeType::Development) => {
             runtime_backend_code.push("browser/runtime/dom/runtime-backend-dom.ts");
             runtime_backend_code.push("browser/runtime/dom/dev-backend-dom.ts");
+            if *environment.strict_csp().await? {
+                runtime_backend_code.push("browser/runtime/dom-csp/dev-backend-dom-csp.ts");
+            }
         }
         (ChunkLoading::Dom, RuntimeType::Production) => {
             // TODO
@@ -87,10 +108,12 @@ pub async fn get_browser_runtime_code(
             const CHUNK_BASE_PATH = {};
             const RUNTIME_PUBLIC_PATH = {};
             const OUTPUT_ROOT = {};
+            const CHUNK_LOAD_RETRY_DELAYS_MS = {};
         "#,
         StringifyJs(chunk_base_path),
         StringifyJs(chunk_base_path),
         StringifyJs(output_root.as_str()),
+        StringifyJs(&chunk_load_retry.delays_ms()),
     )?;
 
     code.push_code(&*shared_runtime_utils_code.await?);
@@ -98,19 +121,26 @@ pub async fn get_browser_runtime_code(
         code.push_code(&*embed_static_code(asset_context, runtime_code.into()).await?);
     }
 
-    if *environment.supports_commonjs_externals().await? {
+    let capabilities = environment.capabilities().await?;
+    if capabilities.commonjs_externals {
         code.push_code(
             &*embed_static_code(asset_context, "shared-node/base-externals-utils.ts".into())
                 .await?,
         );
     }
-    if *environment.node_externals().await? {
+    if capabilities.node_externals {
         code.push_code(
             &*embed_static_code(asset_context, "shared-node/node-externals-utils.ts".into())
                 .await?,
         );
     }
-    if *environment.supports_wasm().await? {
+    if capabilities.esm_externals && matches!(chunk_loading, ChunkLoading::Dom) {
+        code.push_code(
+            &*embed_static_code(asset_context, "browser/runtime/dom/externals-dom.ts".into())
+                .await?,
+        );
+    }
+    if capabilities.wasm {
         code.push_code(
             &*embed_static_code(asset_context, "shared-node/node-wasm-utils.ts".into()).await?,
         );
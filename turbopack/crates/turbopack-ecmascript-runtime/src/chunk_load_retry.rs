@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use turbo_tasks::trace::TraceRawVcs;
+
+/// How the browser runtime retries loading a chunk after a failure, e.g. a
+/// network error or an integrity mismatch. Retries request the chunk with a
+/// cache-busting query, after an exponentially increasing delay.
+///
+/// Retries are disabled by default.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, PartialEq, Eq, TraceRawVcs)]
+pub struct ChunkLoadRetry {
+    /// The number of retries before loading the chunk fails. `0` disables
+    /// retries.
+    pub max_retries: u32,
+    /// The delay before the first retry. It doubles with every further retry.
+    pub initial_delay_ms: u32,
+    /// The upper bound of the delay before a retry.
+    pub max_delay_ms: u32,
+}
+
+impl ChunkLoadRetry {
+    /// Retries loading a chunk up to `max_retries` times with the default
+    /// delays.
+    pub fn with_max_retries(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    /// The delay before each retry, in the order of the retries.
+    pub fn delays_ms(&self) -> Vec<u32> {
+        (0..self.max_retries)
+            .map(|retry| {
+                self.initial_delay_ms
+                    .saturating_mul(2u32.saturating_pow(retry))
+                    .min(self.max_delay_ms)
+            })
+            .collect()
+    }
+}
+
+impl Default for ChunkLoadRetry {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            initial_delay_ms: 500,
+            max_delay_ms: 4000,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChunkLoadRetry;
+
+    #[test]
+    fn retries_are_disabled_by_default() {
+        assert!(ChunkLoadRetry::default().delays_ms().is_empty());
+    }
+
+    #[test]
+    fn doubles_the_delay_up_to_the_maximum() {
+        assert_eq!(
+            ChunkLoadRetry::with_max_retries(5).delays_ms(),
+            [500, 1000, 2000, 4000, 4000]
+        );
+        let retry = ChunkLoadRetry {
+            max_retries: 40,
+            initial_delay_ms: 1,
+            max_delay_ms: u32::MAX,
+        };
+        assert_eq!(retry.delays_ms().last(), Some(&u32::MAX));
+    }
+}
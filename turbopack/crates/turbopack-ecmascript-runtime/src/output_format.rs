@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use turbo_tasks::trace::TraceRawVcs;
+
+/// The format of emitted ECMAScript chunks.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Hash, PartialEq, Eq, TraceRawVcs)]
+pub enum OutputFormat {
+    /// Scripts that register their modules with the Turbopack runtime.
+    #[default]
+    Runtime,
+    /// ES modules that statically import the chunks they depend on and export
+    /// the bindings of their entry module, e.g. for `<script type="module">`
+    /// and libraries. Chunks are still registered with the runtime, whose
+    /// backend only loads the chunks of async imports, with `import()`.
+    EsModule,
+}
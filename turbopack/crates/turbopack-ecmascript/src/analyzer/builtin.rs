@@ -395,6 +395,7 @@ pub fn replace_builtin(value: &mut JsValue) -> bool {
                             // The Array.prototype.map method
                             "map" => {
                                 if let Some(func) = args.first() {
+                                    let mutable = *mutable;
                                     *value = JsValue::array(
                                         take(items)
                                             .into_iter()
@@ -412,9 +413,63 @@ pub fn replace_builtin(value: &mut JsValue) -> bool {
                                             })
                                             .collect(),
                                     );
+                                    // The items might be mutated before they are mapped
+                                    if mutable {
+                                        value.add_unknown_mutations(true);
+                                    }
+                                    return true;
+                                }
+                            }
+                            // The Array.prototype.join method
+                            "join" if !*mutable => {
+                                let separator = match &args[..] {
+                                    [] | [JsValue::Constant(ConstantValue::Undefined)] => {
+                                        Some(",".to_string())
+                                    }
+                                    // Unlike items, a `null` separator is converted to "null"
+                                    [JsValue::Constant(ConstantValue::Null)] => {
+                                        Some("null".to_string())
+                                    }
+                                    [JsValue::Constant(separator)] => join_item(separator),
+                                    _ => None,
+                                };
+                                let joined = separator.and_then(|separator| {
+                                    items
+                                        .iter()
+                                        .map(|item| match item {
+                                            JsValue::Constant(item) => join_item(item),
+                                            _ => None,
+                                        })
+                                        .collect::<Option<Vec<_>>>()
+                                        .map(|items| items.join(&separator))
+                                });
+                                if let Some(joined) = joined {
+                                    *value = joined.into();
                                     return true;
                                 }
                             }
+                            // The Array.prototype.includes method
+                            "includes" if !*mutable => {
+                                if let [JsValue::Constant(search)] = &args[..] {
+                                    let mut found = Some(false);
+                                    for item in items.iter() {
+                                        match item {
+                                            JsValue::Constant(item)
+                                                if same_value_zero(item, search) =>
+                                            {
+                                                found = Some(true);
+                                                break;
+                                            }
+                                            JsValue::Constant(_) => {}
+                                            _ => found = None,
+                                        }
+                                    }
+                                    if let Some(found) = found {
+                                        *value = JsValue::Constant(found.into());
+                                        return true;
+                                    }
+                                }
+                            }
                             _ => {}
                         }
                     }
@@ -665,3 +720,37 @@ pub fn replace_builtin(value: &mut JsValue) -> bool {
         _ => false,
     }
 }
+
+/// Converts an array item to a string like `Array.prototype.join` does.
+/// Returns `None` when the conversion isn't known at compile time.
+fn join_item(item: &ConstantValue) -> Option<String> {
+    match item {
+        ConstantValue::Undefined | ConstantValue::Null => Some(String::new()),
+        ConstantValue::Str(str) => Some(str.as_str().to_string()),
+        ConstantValue::True => Some("true".to_string()),
+        ConstantValue::False => Some("false".to_string()),
+        ConstantValue::BigInt(n) => Some(n.to_string()),
+        // Only integers are formatted the same way as in JavaScript
+        ConstantValue::Num(ConstantNumber(n)) if n.fract() == 0.0 && n.abs() < 1e21 => {
+            Some(if *n == 0.0 {
+                "0".to_string()
+            } else {
+                format!("{n}")
+            })
+        }
+        ConstantValue::Num(_) | ConstantValue::Regex(..) => None,
+    }
+}
+
+/// Compares values like `Array.prototype.includes` does, i.e. `NaN` is equal
+/// to itself and `0` is equal to `-0`.
+fn same_value_zero(a: &ConstantValue, b: &ConstantValue) -> bool {
+    match (a, b) {
+        (ConstantValue::Num(ConstantNumber(a)), ConstantValue::Num(ConstantNumber(b))) => {
+            a == b || (a.is_nan() && b.is_nan())
+        }
+        // Regexes are objects, which are only equal to themselves
+        (ConstantValue::Regex(..), _) | (_, ConstantValue::Regex(..)) => false,
+        _ => a == b,
+    }
+}
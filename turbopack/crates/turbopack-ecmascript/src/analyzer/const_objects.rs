@@ -0,0 +1,300 @@
+use rustc_hash::FxHashSet;
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+
+/// Finds `const` bindings of object literals with literal values, like
+/// `const X = { A: "a", B: "b" } as const`, whose properties are only ever
+/// read. These objects are never mutated and never passed by reference, so
+/// their properties can be evaluated like literals.
+pub(crate) fn find_unmutated_const_objects(m: &Program) -> FxHashSet<Id> {
+    let mut visitor = ConstObjectsVisitor::default();
+
+    m.visit_with(&mut visitor);
+
+    let ConstObjectsVisitor {
+        mut candidates,
+        escaped,
+    } = visitor;
+    candidates.retain(|id| !escaped.contains(id));
+    candidates
+}
+
+#[derive(Default)]
+struct ConstObjectsVisitor {
+    /// `const` bindings initialized with an object literal with literal values.
+    candidates: FxHashSet<Id>,
+    /// Bindings that are used other than by reading one of their properties.
+    escaped: FxHashSet<Id>,
+}
+
+impl ConstObjectsVisitor {
+    /// Marks the object of a member expression like `X.a` as escaped, as the
+    /// member expression is used in a place where `X` might be mutated.
+    fn escape_member_object(&mut self, e: &Expr) {
+        let member = match unwrap(e) {
+            Expr::Member(member) => member,
+            Expr::OptChain(OptChainExpr { base, .. }) => match &**base {
+                OptChainBase::Member(member) => member,
+                OptChainBase::Call(_) => return,
+            },
+            _ => return,
+        };
+        if let Expr::Ident(obj) = unwrap(&member.obj) {
+            self.escaped.insert(obj.to_id());
+        }
+    }
+}
+
+/// Unwraps expressions that evaluate to their inner expression at runtime.
+fn unwrap(e: &Expr) -> &Expr {
+    match e {
+        Expr::Paren(ParenExpr { expr, .. })
+        | Expr::TsConstAssertion(TsConstAssertion { expr, .. })
+        | Expr::TsAs(TsAsExpr { expr, .. })
+        | Expr::TsSatisfies(TsSatisfiesExpr { expr, .. }) => unwrap(expr),
+        _ => e,
+    }
+}
+
+/// Whether `e` is an object literal like `{ A: "a", "B": 1 }` with static keys
+/// and literal values.
+fn is_literal_object(e: &Expr) -> bool {
+    let Expr::Object(obj) = e else {
+        return false;
+    };
+    obj.props.iter().all(|prop| match prop {
+        PropOrSpread::Prop(prop) => match &**prop {
+            Prop::KeyValue(KeyValueProp { key, value }) => {
+                !matches!(key, PropName::Computed(_)) && matches!(unwrap(value), Expr::Lit(_))
+            }
+            _ => false,
+        },
+        PropOrSpread::Spread(_) => false,
+    })
+}
+
+impl Visit for ConstObjectsVisitor {
+    noop_visit_type!();
+
+    fn visit_var_decl(&mut self, n: &VarDecl) {
+        if n.kind != VarDeclKind::Const {
+            n.visit_children_with(self);
+            return;
+        }
+
+        for decl in &n.decls {
+            match (&decl.name, &decl.init) {
+                (Pat::Ident(name), Some(init)) if is_literal_object(unwrap(init)) => {
+                    self.candidates.insert(name.to_id());
+                    init.visit_with(self);
+                }
+                _ => decl.visit_with(self),
+            }
+        }
+    }
+
+    fn visit_export_decl(&mut self, n: &ExportDecl) {
+        // Exported bindings can be mutated by other modules.
+        if let Decl::Var(var) = &n.decl {
+            for decl in &var.decls {
+                if let Pat::Ident(name) = &decl.name {
+                    self.escaped.insert(name.to_id());
+                }
+            }
+        }
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_member_expr(&mut self, n: &MemberExpr) {
+        if let Expr::Ident(_) = unwrap(&n.obj) {
+            // Reading a property doesn't let the object escape.
+            n.prop.visit_with(self);
+        } else {
+            n.visit_children_with(self);
+        }
+    }
+
+    fn visit_ident(&mut self, n: &Ident) {
+        self.escaped.insert(n.to_id());
+    }
+
+    fn visit_assign_expr(&mut self, n: &AssignExpr) {
+        if let AssignTarget::Simple(SimpleAssignTarget::Member(member)) = &n.left {
+            if let Expr::Ident(obj) = unwrap(&member.obj) {
+                self.escaped.insert(obj.to_id());
+            }
+        }
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_pat(&mut self, n: &Pat) {
+        // Destructuring assignments and `for ... in` or `for ... of` heads.
+        if let Pat::Expr(e) = n {
+            self.escape_member_object(e);
+        }
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_update_expr(&mut self, n: &UpdateExpr) {
+        self.escape_member_object(&n.arg);
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_unary_expr(&mut self, n: &UnaryExpr) {
+        if n.op == UnaryOp::Delete {
+            self.escape_member_object(&n.arg);
+        }
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_callee(&mut self, n: &Callee) {
+        // Method calls pass the object as `this`.
+        if let Callee::Expr(e) = n {
+            self.escape_member_object(e);
+        }
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_opt_call(&mut self, n: &OptCall) {
+        self.escape_member_object(&n.callee);
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_tagged_tpl(&mut self, n: &TaggedTpl) {
+        self.escape_member_object(&n.tag);
+
+        n.visit_children_with(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::{
+        common::{FileName, Mark},
+        ecma::{
+            ast::*,
+            parser::{parse_file_as_module, Syntax, TsSyntax},
+            transforms::base::resolver,
+            visit::VisitMutWith,
+        },
+        testing::run_test,
+    };
+
+    use super::find_unmutated_const_objects;
+
+    /// Returns the names of the unmutated const objects in `code`, sorted.
+    fn unmutated(code: &str) -> Vec<String> {
+        run_test(false, |cm, _handler| {
+            let fm = cm.new_source_file(FileName::Anon.into(), code.to_string());
+            let mut m = parse_file_as_module(
+                &fm,
+                Syntax::Typescript(TsSyntax::default()),
+                EsVersion::latest(),
+                None,
+                &mut vec![],
+            )
+            .unwrap();
+            m.visit_mut_with(&mut resolver(Mark::new(), Mark::new(), true));
+
+            let mut names = find_unmutated_const_objects(&Program::Module(m))
+                .into_iter()
+                .map(|(sym, _)| sym.to_string())
+                .collect::<Vec<_>>();
+            names.sort();
+            Ok(names)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn literal_objects() {
+        assert_eq!(
+            unmutated(
+                r#"
+                const A = { X: "x", "Y": 1 } as const;
+                const B = ({ X: true }) satisfies object;
+                const C = { X: null };
+                if (A.X === "x" && A["Y"] === 1 && B.X && C.X === null) {}
+                "#
+            ),
+            vec!["A", "B", "C"]
+        );
+    }
+
+    #[test]
+    fn non_literal_objects() {
+        assert_eq!(
+            unmutated(
+                r#"
+                let A = { X: "x" };
+                var B = { X: "x" };
+                const C = { X: value };
+                const D = { [key]: "x" };
+                const E = { ...other };
+                const F = { x() {} };
+                const G = ["x"];
+                A.X; B.X; C.X; D.X; E.X; F.X; G[0];
+                "#
+            ),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn mutated_objects() {
+        assert_eq!(
+            unmutated(
+                r#"
+                const A = { X: "x" };
+                A.X = "y";
+                const B = { X: 1 };
+                B.X++;
+                const C = { X: "x" };
+                delete C.X;
+                const D = { X: "x" };
+                ({ y: D.X } = other);
+                const E = { X: "x" };
+                for (E.X of list) {}
+                const F = { X: "x" };
+                F.toString();
+                const G = { X: "x" };
+                G.X?.();
+                const H = { X: "x" };
+                H.X``;
+                const I = { X: "x" } as const;
+                (I as any).X = "y";
+                "#
+            ),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn escaped_objects() {
+        assert_eq!(
+            unmutated(
+                r#"
+                const A = { X: "x" };
+                mutate(A);
+                const B = { X: "x" };
+                const alias = B;
+                export const C = { X: "x" };
+                const D = { X: "x" };
+                export { D };
+                const E = { X: "x" };
+                E.X;
+                "#
+            ),
+            vec!["E"]
+        );
+    }
+}
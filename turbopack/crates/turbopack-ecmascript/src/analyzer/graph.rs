@@ -4,9 +4,13 @@ use std::{
     mem::{replace, take},
 };
 
+use rustc_hash::FxHashSet;
 use swc_core::{
     atoms::Atom,
-    common::{comments::Comments, pass::AstNodePath, Mark, Span, Spanned, SyntaxContext, GLOBALS},
+    common::{
+        comments::Comments, pass::AstNodePath, Mark, Span, Spanned, SyntaxContext, DUMMY_SP,
+        GLOBALS,
+    },
     ecma::{
         ast::*,
         atoms::js_word,
@@ -17,8 +21,8 @@ use turbo_tasks::{RcStr, Vc};
 use turbopack_core::source::Source;
 
 use super::{
-    is_unresolved_id, ConstantNumber, ConstantValue, ImportMap, JsValue, ObjectPart,
-    WellKnownFunctionKind,
+    const_objects::find_unmutated_const_objects, is_unresolved_id, ConstantNumber, ConstantValue,
+    ImportMap, JsValue, ObjectPart, WellKnownFunctionKind,
 };
 use crate::{
     analyzer::{is_unresolved, WellKnownObjectKind},
@@ -236,6 +240,21 @@ impl Effect {
             Effect::Unreachable { .. } => {}
         }
     }
+
+    /// The span of the code that caused this effect.
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            Effect::Conditional { span, .. }
+            | Effect::Call { span, .. }
+            | Effect::MemberCall { span, .. }
+            | Effect::Member { span, .. }
+            | Effect::ImportedBinding { span, .. }
+            | Effect::FreeVar { span, .. }
+            | Effect::TypeOf { span, .. }
+            | Effect::ImportMeta { span, .. } => Some(span),
+            Effect::Unreachable { .. } => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -278,6 +297,7 @@ pub fn create_graph(m: &Program, eval_context: &EvalContext) -> VarGraph {
             current_value: Default::default(),
             cur_fn_return_values: Default::default(),
             cur_fn_ident: Default::default(),
+            enclosing_span: DUMMY_SP,
         },
         &mut Default::default(),
     );
@@ -293,6 +313,9 @@ pub struct EvalContext {
     pub(crate) unresolved_mark: Mark,
     pub(crate) top_level_mark: Mark,
     pub(crate) imports: ImportMap,
+    /// `const` bindings of object literals that are never mutated, see
+    /// [find_unmutated_const_objects].
+    pub(crate) unmutated_const_objects: FxHashSet<Id>,
 }
 
 impl EvalContext {
@@ -310,6 +333,7 @@ impl EvalContext {
             unresolved_mark,
             top_level_mark,
             imports: ImportMap::analyze(module, source, comments),
+            unmutated_const_objects: find_unmutated_const_objects(module),
         }
     }
 
@@ -375,6 +399,35 @@ impl EvalContext {
         }
     }
 
+    /// Evaluates the object of a member expression.
+    ///
+    /// Optional chains, e.g. `process?.env` in `process?.env?.NODE_ENV`, are
+    /// evaluated like plain member expressions: the member is only accessed
+    /// when the chain doesn't short-circuit, and then it has the same value.
+    /// This allows matching defines and free variables through them.
+    fn eval_member_obj(&self, e: &Expr) -> JsValue {
+        match e {
+            Expr::Paren(e) => self.eval_member_obj(&e.expr),
+            Expr::OptChain(OptChainExpr {
+                base: box OptChainBase::Member(member),
+                ..
+            }) => self.eval_member(member),
+            _ => self.eval(e),
+        }
+    }
+
+    fn eval_member(&self, member: &MemberExpr) -> JsValue {
+        let obj = self.eval_member_obj(&member.obj);
+        let prop = match &member.prop {
+            MemberProp::Ident(prop) => prop.sym.clone().into(),
+            MemberProp::Computed(computed) => self.eval(&computed.expr),
+            MemberProp::PrivateName(_) => {
+                return JsValue::unknown_empty(true, "unsupported expression");
+            }
+        };
+        JsValue::member(Box::new(obj), Box::new(prop))
+    }
+
     pub fn eval(&self, e: &Expr) -> JsValue {
         debug_assert!(
             GLOBALS.is_set(),
@@ -382,6 +435,9 @@ impl EvalContext {
         );
         match e {
             Expr::Paren(e) => self.eval(&e.expr),
+            Expr::TsConstAssertion(e) => self.eval(&e.expr),
+            Expr::TsAs(e) => self.eval(&e.expr),
+            Expr::TsSatisfies(e) => self.eval(&e.expr),
             Expr::Lit(e) => JsValue::Constant(e.clone().into()),
             Expr::Ident(i) => self.eval_ident(i),
 
@@ -545,24 +601,7 @@ impl EvalContext {
                 last
             }
 
-            Expr::Member(MemberExpr {
-                obj,
-                prop: MemberProp::Ident(prop),
-                ..
-            }) => {
-                let obj = self.eval(obj);
-                JsValue::member(Box::new(obj), Box::new(prop.sym.clone().into()))
-            }
-
-            Expr::Member(MemberExpr {
-                obj,
-                prop: MemberProp::Computed(computed),
-                ..
-            }) => {
-                let obj = self.eval(obj);
-                let prop = self.eval(&computed.expr);
-                JsValue::member(Box::new(obj), Box::new(prop))
-            }
+            Expr::Member(member) => self.eval_member(member),
 
             Expr::New(NewExpr {
                 callee: box callee,
@@ -743,6 +782,10 @@ struct Analyzer<'a> {
     cur_fn_return_values: Option<Vec<JsValue>>,
 
     cur_fn_ident: u32,
+
+    /// The span of the innermost statement or expression that is being visited
+    /// and has a span in the original source code.
+    enclosing_span: Span,
 }
 
 pub fn as_parent_path(ast_path: &AstNodePath<AstParentNodeRef<'_>>) -> Vec<AstParentKind> {
@@ -814,7 +857,14 @@ impl Analyzer<'_> {
         self.add_value(id, value);
     }
 
-    fn add_effect(&mut self, effect: Effect) {
+    fn add_effect(&mut self, mut effect: Effect) {
+        // Code generated by transforms like JSX or TypeScript lowering has no span in
+        // the original source code. Report its effects at the enclosing code instead.
+        if let Some(span) = effect.span_mut() {
+            if span.is_dummy() {
+                *span = self.enclosing_span;
+            }
+        }
         self.effects.push(effect);
     }
 
@@ -1160,7 +1210,7 @@ impl Analyzer<'_> {
         member_expr: &'ast MemberExpr,
         ast_path: &AstNodePath<AstParentNodeRef<'r>>,
     ) {
-        let obj_value = self.eval_context.eval(&member_expr.obj);
+        let obj_value = self.eval_context.eval_member_obj(&member_expr.obj);
         let prop_value = match &member_expr.prop {
             // TODO avoid clone
             MemberProp::Ident(i) => i.sym.clone().into(),
@@ -1414,6 +1464,19 @@ impl VisitAstPath for Analyzer<'_> {
         member_expr.visit_children_with_ast_path(self, ast_path);
     }
 
+    fn visit_stmt<'ast: 'r, 'r>(
+        &mut self,
+        n: &'ast Stmt,
+        ast_path: &mut AstNodePath<AstParentNodeRef<'r>>,
+    ) {
+        let old_span = self.enclosing_span;
+        if !n.span().is_dummy() {
+            self.enclosing_span = n.span();
+        }
+        n.visit_children_with_ast_path(self, ast_path);
+        self.enclosing_span = old_span;
+    }
+
     fn visit_expr<'ast: 'r, 'r>(
         &mut self,
         n: &'ast Expr,
@@ -1421,7 +1484,12 @@ impl VisitAstPath for Analyzer<'_> {
     ) {
         let old = self.var_decl_kind;
         self.var_decl_kind = None;
+        let old_span = self.enclosing_span;
+        if !n.span().is_dummy() {
+            self.enclosing_span = n.span();
+        }
         n.visit_children_with_ast_path(self, ast_path);
+        self.enclosing_span = old_span;
         self.var_decl_kind = old;
     }
 
@@ -1607,7 +1675,19 @@ impl VisitAstPath for Analyzer<'_> {
     ) {
         if self.var_decl_kind.is_some() {
             if let Some(init) = &n.init {
-                self.current_value = Some(self.eval_context.eval(init));
+                let mut value = self.eval_context.eval(init);
+                // Property accesses on objects that are never mutated can be treated as
+                // literals, which is common for pseudo-enums like `const X = { ... } as const`.
+                if let (Pat::Ident(name), JsValue::Object { mutable, .. }) = (&n.name, &mut value) {
+                    if self
+                        .eval_context
+                        .unmutated_const_objects
+                        .contains(&name.to_id())
+                    {
+                        *mutable = false;
+                    }
+                }
+                self.current_value = Some(value);
             }
         }
         {
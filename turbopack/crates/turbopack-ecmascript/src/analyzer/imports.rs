@@ -15,7 +15,7 @@ use swc_core::{
 use turbo_tasks::{FxIndexMap, FxIndexSet, RcStr, Vc};
 use turbopack_core::{issue::IssueSource, source::Source};
 
-use super::{top_level_await::has_top_level_await, JsValue, ModuleValue};
+use super::{top_level_await::has_top_level_await, JsValue, ModuleValue, ObjectPart};
 use crate::{
     tree_shake::{find_turbopack_part_id_in_asserts, PartId},
     SpecifiedModuleType,
@@ -73,6 +73,71 @@ impl ImportAnnotations {
         ImportAnnotations { map }
     }
 
+    /// Parses the import attributes of the options bag of an `import()`
+    /// expression, e.g. `import("./data.json", { with: { type: "json" } })`.
+    /// The legacy `assert` key is accepted as well.
+    ///
+    /// Only attributes with constant string values are known at compile
+    /// time; the rest are ignored.
+    pub fn parse_dynamic_import_options(options: &JsValue) -> ImportAnnotations {
+        let JsValue::Object { parts, .. } = options else {
+            return ImportAnnotations::default();
+        };
+
+        let mut map = BTreeMap::new();
+        for part in parts {
+            let ObjectPart::KeyValue(
+                key,
+                JsValue::Object {
+                    parts: attributes, ..
+                },
+            ) = part
+            else {
+                continue;
+            };
+            if !matches!(key.as_str(), Some("with" | "assert")) {
+                continue;
+            }
+            for attribute in attributes {
+                if let ObjectPart::KeyValue(key, value) = attribute {
+                    if let (Some(key), Some(value)) = (key.as_str(), value.as_str()) {
+                        map.insert(key.into(), value.into());
+                    }
+                }
+            }
+        }
+
+        ImportAnnotations { map }
+    }
+
+    /// Returns true when the bundler applies all options of an `import()`
+    /// expression, which is the case for a `type: "json"` import attribute.
+    /// Other options are only passed on when the module is imported natively.
+    pub fn bundler_applies_dynamic_import_options(options: &JsValue) -> bool {
+        let JsValue::Object { parts, .. } = options else {
+            return false;
+        };
+        parts.iter().all(|part| {
+            let ObjectPart::KeyValue(
+                key,
+                JsValue::Object {
+                    parts: attributes, ..
+                },
+            ) = part
+            else {
+                return false;
+            };
+            matches!(key.as_str(), Some("with" | "assert"))
+                && attributes.iter().all(|attribute| {
+                    matches!(
+                        attribute,
+                        ObjectPart::KeyValue(key, value)
+                            if key.as_str() == Some("type") && value.as_str() == Some("json")
+                    )
+                })
+        })
+    }
+
     /// Returns the content on the transition annotation
     pub fn transition(&self) -> Option<&str> {
         self.get(&ANNOTATION_TRANSITION)
@@ -91,6 +156,10 @@ impl ImportAnnotations {
     pub fn get(&self, key: &JsWord) -> Option<&str> {
         self.map.get(key).map(|w| w.as_str())
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
 }
 
 impl Display for ImportAnnotations {
@@ -270,8 +339,17 @@ impl ImportMap {
         self.reexports.iter().map(|(i, r)| (*i, r))
     }
 
+    /// Returns the local bindings of default imports along with the index of
+    /// their reference.
+    pub fn default_imports(&self) -> impl Iterator<Item = (&Id, usize)> {
+        self.imports
+            .iter()
+            .filter(|(_, (_, export))| &**export == "default")
+            .map(|(id, (i, _))| (id, *i))
+    }
+
     /// Analyze ES import
-    pub(super) fn analyze(
+    pub(crate) fn analyze(
         m: &Program,
         source: Option<Vc<Box<dyn Source>>>,
         comments: Option<&dyn Comments>,
@@ -635,3 +713,70 @@ fn get_import_symbol_from_export(specifier: &ExportSpecifier) -> ImportedSymbol
         ExportSpecifier::Namespace(..) => ImportedSymbol::Exports,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ImportAnnotations;
+    use crate::analyzer::{JsValue, ObjectPart};
+
+    fn options(key: &str, attributes: Vec<(&str, JsValue)>) -> JsValue {
+        JsValue::object(vec![ObjectPart::KeyValue(
+            key.into(),
+            JsValue::object(
+                attributes
+                    .into_iter()
+                    .map(|(key, value)| ObjectPart::KeyValue(key.into(), value))
+                    .collect(),
+            ),
+        )])
+    }
+
+    #[test]
+    fn dynamic_import_options() {
+        let json = options("with", vec![("type", "json".into())]);
+        let annotations = ImportAnnotations::parse_dynamic_import_options(&json);
+        assert_eq!(annotations.module_type(), Some("json"));
+        assert!(ImportAnnotations::bundler_applies_dynamic_import_options(
+            &json
+        ));
+
+        let legacy = options("assert", vec![("type", "json".into())]);
+        let annotations = ImportAnnotations::parse_dynamic_import_options(&legacy);
+        assert_eq!(annotations.module_type(), Some("json"));
+        assert!(ImportAnnotations::bundler_applies_dynamic_import_options(
+            &legacy
+        ));
+
+        // Attributes that aren't known at compile time are ignored
+        let dynamic = options(
+            "with",
+            vec![
+                ("type", JsValue::unknown_empty(false, "")),
+                ("other", "value".into()),
+            ],
+        );
+        let annotations = ImportAnnotations::parse_dynamic_import_options(&dynamic);
+        assert_eq!(annotations.module_type(), None);
+        assert_eq!(annotations.get(&"other".into()), Some("value"));
+        assert!(!ImportAnnotations::bundler_applies_dynamic_import_options(
+            &dynamic
+        ));
+    }
+
+    #[test]
+    fn native_only_dynamic_import_options() {
+        let applied =
+            |options: &JsValue| ImportAnnotations::bundler_applies_dynamic_import_options(options);
+        assert!(applied(&JsValue::object(vec![])));
+        assert!(!applied(&options("with", vec![("type", "css".into())])));
+        assert!(!applied(&options(
+            "with",
+            vec![("type", "json".into()), ("integrity", "sha384-abc".into())]
+        )));
+        assert!(!applied(&options("other", vec![])));
+        assert!(!applied(&JsValue::object(vec![ObjectPart::Spread(
+            JsValue::unknown_empty(false, "")
+        )])));
+        assert!(!applied(&JsValue::unknown_empty(false, "")));
+    }
+}
@@ -35,9 +35,11 @@ pub(crate) use self::imports::ImportMap;
 use crate::{references::require_context::RequireContextMap, utils::StringifyJs};
 
 pub mod builtin;
+mod const_objects;
 pub mod graph;
 pub mod imports;
 pub mod linker;
+pub(crate) mod property_accesses;
 pub mod top_level_await;
 pub mod well_known;
 
@@ -1696,6 +1698,18 @@ impl JsValue {
                         "Object.assign".to_string(),
                         "Object.assign method: https://developer.mozilla.org/zh-CN/docs/Web/JavaScript/Reference/Global_Objects/Object/assign",
                     ),
+                    WellKnownFunctionKind::ObjectKeys => (
+                        "Object.keys".to_string(),
+                        "Object.keys method: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/keys",
+                    ),
+                    WellKnownFunctionKind::ObjectValues => (
+                        "Object.values".to_string(),
+                        "Object.values method: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/values",
+                    ),
+                    WellKnownFunctionKind::ObjectEntries => (
+                        "Object.entries".to_string(),
+                        "Object.entries method: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/entries",
+                    ),
                     WellKnownFunctionKind::PathJoin => (
                         "path.join".to_string(),
                         "The Node.js path.join method: https://nodejs.org/api/path.html#pathjoinpaths",
@@ -3780,6 +3794,9 @@ impl Hash for RequireContextValue {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum WellKnownFunctionKind {
     ObjectAssign,
+    ObjectKeys,
+    ObjectValues,
+    ObjectEntries,
     PathJoin,
     PathDirname,
     /// `0` is the current working directory.
@@ -3978,9 +3995,14 @@ mod tests {
     use std::{mem::take, path::PathBuf, time::Instant};
 
     use swc_core::{
-        common::{comments::SingleThreadedComments, Mark},
+        common::{comments::SingleThreadedComments, FileName, Mark},
         ecma::{
-            ast::EsVersion, parser::parse_file_as_program, transforms::base::resolver,
+            ast::EsVersion,
+            parser::{parse_file_as_program, EsSyntax, Syntax},
+            transforms::{
+                base::resolver,
+                react::{self, react},
+            },
             visit::VisitMutWith,
         },
         testing::{fixture, run_test, NormalizedOutput},
@@ -4311,6 +4333,166 @@ mod tests {
         .unwrap();
     }
 
+    #[fixture("tests/analyzer/spans/**/input.jsx")]
+    fn spans_fixture(input: PathBuf) {
+        let spans_snapshot_path = input.with_file_name("spans.snapshot");
+
+        run_test(false, |cm, handler| {
+            let fm = cm.load_file(&input).unwrap();
+
+            let comments = SingleThreadedComments::default();
+            let mut m = parse_file_as_program(
+                &fm,
+                Syntax::Es(EsSyntax {
+                    jsx: true,
+                    ..Default::default()
+                }),
+                EsVersion::latest(),
+                Some(&comments),
+                &mut vec![],
+            )
+            .map_err(|err| err.into_diagnostic(handler).emit())?;
+
+            let unresolved_mark = Mark::new();
+            let top_level_mark = Mark::new();
+            m.visit_mut_with(&mut resolver(unresolved_mark, top_level_mark, false));
+            // The JSX transform generates code with dummy spans, e.g. the `_jsx` import
+            m.mutate(react(
+                cm.clone(),
+                Some(&comments),
+                react::Options {
+                    runtime: Some(react::Runtime::Automatic),
+                    ..Default::default()
+                },
+                top_level_mark,
+                unresolved_mark,
+            ));
+
+            let eval_context =
+                EvalContext::new(&m, unresolved_mark, top_level_mark, Some(&comments), None);
+            let var_graph = create_graph(&m, &eval_context);
+
+            let spans = var_graph
+                .effects
+                .iter()
+                .map(|effect| {
+                    let (kind, span) = match effect {
+                        Effect::Conditional { span, .. } => ("conditional", span),
+                        Effect::Call { span, .. } => ("call", span),
+                        Effect::MemberCall { span, .. } => ("member call", span),
+                        Effect::Member { span, .. } => ("member", span),
+                        Effect::ImportedBinding { span, .. } => ("imported binding", span),
+                        Effect::FreeVar { span, .. } => ("free var", span),
+                        Effect::TypeOf { span, .. } => ("typeof", span),
+                        Effect::ImportMeta { span, .. } => ("import meta", span),
+                        Effect::Unreachable { .. } => return "unreachable".to_string(),
+                    };
+                    assert!(!span.is_dummy(), "{kind} effect has no span");
+                    format!("{kind}: {}", cm.span_to_snippet(*span).unwrap())
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            NormalizedOutput::from(spans)
+                .compare_to_file(&spans_snapshot_path)
+                .unwrap();
+
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    /// Resolves the value of the binding `name` in `code` and explains it.
+    fn resolve_binding(code: &str, name: &str) -> String {
+        let code = code.to_string();
+        let name = name.to_string();
+        run_test(false, |cm, handler| {
+            let r = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap();
+            r.block_on(async move {
+                let fm = cm.new_source_file(FileName::Anon.into(), code);
+                let mut m = parse_file_as_program(
+                    &fm,
+                    Default::default(),
+                    EsVersion::latest(),
+                    None,
+                    &mut vec![],
+                )
+                .map_err(|err| err.into_diagnostic(handler).emit())?;
+
+                let unresolved_mark = Mark::new();
+                let top_level_mark = Mark::new();
+                m.visit_mut_with(&mut resolver(unresolved_mark, top_level_mark, false));
+
+                let eval_context =
+                    EvalContext::new(&m, unresolved_mark, top_level_mark, None, None);
+                let var_graph = create_graph(&m, &eval_context);
+                let value = var_graph
+                    .values
+                    .iter()
+                    .find(|((id, _), _)| *id == *name)
+                    .map(|(_, value)| value.clone())
+                    .unwrap();
+                let value = resolve(&var_graph, value, ImportAttributes::empty_ref()).await;
+                let (explainer, hints) = value.explain(10, 5);
+                Ok(format!("{explainer}{hints}"))
+            })
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn evaluates_object_enumeration() {
+        crate::register();
+        assert_eq!(
+            resolve_binding("const x = Object.keys({ b: 1, 1: 2, a: 3, 0: 4 });", "x"),
+            r#"["0", "1", "b", "a"]"#
+        );
+        assert_eq!(
+            resolve_binding("const x = Object.values({ a: 'a', b: 'b', a: 'c' });", "x"),
+            r#"["c", "b"]"#
+        );
+        assert_eq!(
+            resolve_binding("const x = Object.entries({ a: 'b' });", "x"),
+            r#"[["a", "b"]]"#
+        );
+        // `__proto__` sets the prototype, which isn't an own property
+        assert!(
+            resolve_binding("const x = Object.keys({ __proto__: null, a: 1 });", "x")
+                .starts_with("???")
+        );
+    }
+
+    #[test]
+    fn evaluates_array_methods() {
+        crate::register();
+        assert_eq!(
+            resolve_binding(
+                "const CONFIG = { a: 1, b: 2 };
+                const x = Object.keys(CONFIG).map(function (key) { return key; });",
+                "x"
+            ),
+            r#"["a", "b"]"#
+        );
+        assert_eq!(
+            resolve_binding("const x = ['a', 1, null, true].join();", "x"),
+            r#""a,1,,true""#
+        );
+        assert_eq!(
+            resolve_binding("const x = ['a', 'b'].join(null);", "x"),
+            r#""anullb""#
+        );
+        assert_eq!(
+            resolve_binding("const x = [1, 2].includes(2);", "x"),
+            "true"
+        );
+        assert_eq!(
+            resolve_binding("const x = ['a', 'b'].includes('c');", "x"),
+            "false"
+        );
+    }
+
     async fn resolve(var_graph: &VarGraph, val: JsValue, attributes: &ImportAttributes) -> JsValue {
         turbo_tasks_testing::VcStorage::with(async {
             let compile_time_info = CompileTimeInfo::builder(Environment::new(Value::new(
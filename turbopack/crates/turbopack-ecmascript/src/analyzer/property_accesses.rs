@@ -0,0 +1,335 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use swc_core::ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+};
+use turbo_tasks::{FxIndexSet, RcStr};
+
+/// The properties that are read from a binding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PropertyAccesses {
+    /// Only the properties at these paths are read, like `["a", "b"]` for
+    /// `x.a.b`. Everything nested in them might be read too.
+    Paths(FxIndexSet<Vec<RcStr>>),
+    /// The binding is used in other ways, e.g. it's passed to a function,
+    /// mutated or read with a computed key, so all properties might be read.
+    All,
+}
+
+impl PropertyAccesses {
+    fn add(&mut self, path: Vec<RcStr>) {
+        if path.is_empty() {
+            *self = PropertyAccesses::All;
+        } else if let PropertyAccesses::Paths(paths) = self {
+            paths.insert(path);
+        }
+    }
+}
+
+/// Finds the properties that are read from each of `bindings`, e.g. the
+/// default import of a JSON module, which is never mutated by the module.
+pub(crate) fn find_property_accesses(
+    m: &Program,
+    bindings: FxHashSet<Id>,
+) -> FxHashMap<Id, PropertyAccesses> {
+    let mut visitor = PropertyAccessesVisitor {
+        accesses: bindings
+            .into_iter()
+            .map(|id| (id, PropertyAccesses::Paths(Default::default())))
+            .collect(),
+    };
+
+    m.visit_with(&mut visitor);
+
+    visitor.accesses
+}
+
+struct PropertyAccessesVisitor {
+    accesses: FxHashMap<Id, PropertyAccesses>,
+}
+
+impl PropertyAccessesVisitor {
+    /// Returns the binding and the static property path of an expression like
+    /// `x.a["b"][0]`. The path ends before the first computed property that
+    /// isn't a literal.
+    fn member_path<'a>(&self, e: &'a Expr) -> Option<(&'a Ident, Vec<RcStr>)> {
+        match unwrap(e) {
+            Expr::Ident(ident) => self
+                .accesses
+                .contains_key(&ident.to_id())
+                .then(|| (ident, Vec::new())),
+            Expr::Member(member) => self.member_expr_path(member),
+            Expr::OptChain(OptChainExpr { base, .. }) => match &**base {
+                OptChainBase::Member(member) => self.member_expr_path(member),
+                OptChainBase::Call(_) => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn member_expr_path<'a>(&self, member: &'a MemberExpr) -> Option<(&'a Ident, Vec<RcStr>)> {
+        let mut keys = vec![static_key(&member.prop)];
+        let mut obj = &*member.obj;
+        let ident = loop {
+            let member = match unwrap(obj) {
+                Expr::Ident(ident) => break ident,
+                Expr::Member(member) => member,
+                Expr::OptChain(OptChainExpr { base, .. }) => match &**base {
+                    OptChainBase::Member(member) => member,
+                    OptChainBase::Call(_) => return None,
+                },
+                _ => return None,
+            };
+            keys.push(static_key(&member.prop));
+            obj = &member.obj;
+        };
+        if !self.accesses.contains_key(&ident.to_id()) {
+            return None;
+        }
+        let path = keys.into_iter().rev().map_while(|key| key).collect();
+        Some((ident, path))
+    }
+
+    fn add(&mut self, ident: &Ident, path: Vec<RcStr>) {
+        if let Some(accesses) = self.accesses.get_mut(&ident.to_id()) {
+            accesses.add(path);
+        }
+    }
+
+    /// Marks the binding of a member expression like `x.a` as fully used, as
+    /// the member expression is used in a place where it might be mutated.
+    fn mutate(&mut self, e: &Expr) {
+        if let Some((ident, _)) = self.member_path(e) {
+            self.add(ident, Vec::new());
+        }
+    }
+}
+
+/// Unwraps expressions that evaluate to their inner expression at runtime.
+fn unwrap(e: &Expr) -> &Expr {
+    match e {
+        Expr::Paren(ParenExpr { expr, .. })
+        | Expr::TsNonNull(TsNonNullExpr { expr, .. })
+        | Expr::TsAs(TsAsExpr { expr, .. })
+        | Expr::TsSatisfies(TsSatisfiesExpr { expr, .. }) => unwrap(expr),
+        _ => e,
+    }
+}
+
+/// Returns the key of a property like `.a`, `["a"]` or `[0]`.
+fn static_key(prop: &MemberProp) -> Option<RcStr> {
+    match prop {
+        MemberProp::Ident(ident) => Some(ident.sym.as_str().into()),
+        MemberProp::Computed(ComputedPropName { expr, .. }) => match unwrap(expr) {
+            Expr::Lit(Lit::Str(str)) => Some(str.value.as_str().into()),
+            Expr::Lit(Lit::Num(num)) if num.value.fract() == 0.0 && num.value >= 0.0 => {
+                Some(format!("{}", num.value).into())
+            }
+            _ => None,
+        },
+        MemberProp::PrivateName(_) => None,
+    }
+}
+
+impl Visit for PropertyAccessesVisitor {
+    noop_visit_type!();
+
+    fn visit_module_decl(&mut self, n: &ModuleDecl) {
+        // The bindings are declared by imports, which don't read them.
+        if !matches!(n, ModuleDecl::Import(_)) {
+            n.visit_children_with(self);
+        }
+    }
+
+    fn visit_member_expr(&mut self, n: &MemberExpr) {
+        if let Some((ident, path)) = self.member_expr_path(n) {
+            self.add(ident, path);
+            // Only computed keys are visited, as the object is covered by the path.
+            let mut member = n;
+            loop {
+                if let MemberProp::Computed(prop) = &member.prop {
+                    prop.visit_with(self);
+                }
+                match unwrap(&member.obj) {
+                    Expr::Member(obj) => member = obj,
+                    Expr::OptChain(OptChainExpr { base, .. }) => match &**base {
+                        OptChainBase::Member(obj) => member = obj,
+                        OptChainBase::Call(_) => break,
+                    },
+                    _ => break,
+                }
+            }
+        } else {
+            n.visit_children_with(self);
+        }
+    }
+
+    fn visit_ident(&mut self, n: &Ident) {
+        self.add(n, Vec::new());
+    }
+
+    fn visit_assign_expr(&mut self, n: &AssignExpr) {
+        if let AssignTarget::Simple(SimpleAssignTarget::Member(member)) = &n.left {
+            if let Some((ident, _)) = self.member_expr_path(member) {
+                self.add(ident, Vec::new());
+            }
+        }
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_pat(&mut self, n: &Pat) {
+        // Destructuring assignments and `for ... in` or `for ... of` heads.
+        if let Pat::Expr(e) = n {
+            self.mutate(e);
+        }
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_update_expr(&mut self, n: &UpdateExpr) {
+        self.mutate(&n.arg);
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_unary_expr(&mut self, n: &UnaryExpr) {
+        if n.op == UnaryOp::Delete {
+            self.mutate(&n.arg);
+        }
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_callee(&mut self, n: &Callee) {
+        // Method calls pass the object as `this`, so all of its properties
+        // might be read.
+        if let Callee::Expr(e) = n {
+            if let Expr::Member(member) = unwrap(e) {
+                if let Some((ident, path)) = self.member_path(&member.obj) {
+                    self.add(ident, path);
+                    if let MemberProp::Computed(prop) = &member.prop {
+                        prop.visit_with(self);
+                    }
+                    return;
+                }
+            }
+        }
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_opt_call(&mut self, n: &OptCall) {
+        if let Expr::Member(member) = unwrap(&n.callee) {
+            if let Some((ident, path)) = self.member_path(&member.obj) {
+                self.add(ident, path);
+                n.args.visit_with(self);
+                return;
+            }
+        }
+
+        n.visit_children_with(self);
+    }
+
+    fn visit_tagged_tpl(&mut self, n: &TaggedTpl) {
+        self.mutate(&n.tag);
+
+        n.visit_children_with(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_hash::FxHashSet;
+    use swc_core::{
+        common::{FileName, Mark},
+        ecma::{
+            ast::*, parser::parse_file_as_module, transforms::base::resolver, visit::VisitMutWith,
+        },
+        testing::run_test,
+    };
+
+    use super::{find_property_accesses, PropertyAccesses};
+
+    /// Finds the properties that `code` reads from the default import `data`.
+    fn accesses(code: &str) -> PropertyAccesses {
+        run_test(false, |cm, _handler| {
+            let fm = cm.new_source_file(
+                FileName::Anon.into(),
+                format!("import data from './data.json';\n{code}"),
+            );
+            let mut m = parse_file_as_module(
+                &fm,
+                Default::default(),
+                EsVersion::latest(),
+                None,
+                &mut vec![],
+            )
+            .unwrap();
+            m.visit_mut_with(&mut resolver(Mark::new(), Mark::new(), false));
+
+            let Some(ModuleItem::ModuleDecl(ModuleDecl::Import(import))) = m.body.first() else {
+                unreachable!()
+            };
+            let ImportSpecifier::Default(default) = &import.specifiers[0] else {
+                unreachable!()
+            };
+            let id = default.local.to_id();
+            let mut accesses =
+                find_property_accesses(&Program::Module(m), FxHashSet::from_iter([id.clone()]));
+            Ok(accesses.remove(&id).unwrap())
+        })
+        .unwrap()
+    }
+
+    fn paths(paths: &[&[&str]]) -> PropertyAccesses {
+        PropertyAccesses::Paths(
+            paths
+                .iter()
+                .map(|path| path.iter().map(|key| (*key).into()).collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn static_member_expressions() {
+        assert_eq!(
+            accesses("data.a.b; data['c']; data.d[0]; data?.e.f;"),
+            paths(&[&["a", "b"], &["c"], &["d", "0"], &["e", "f"]])
+        );
+        assert_eq!(accesses("console.log(1);"), paths(&[]));
+    }
+
+    #[test]
+    fn computed_keys_end_the_path() {
+        assert_eq!(accesses("data.a[key].b;"), paths(&[&["a"]]));
+        assert_eq!(accesses("data[key];"), PropertyAccesses::All);
+    }
+
+    #[test]
+    fn values_passed_along() {
+        assert_eq!(
+            accesses("f(data.a); data.b.map(f);"),
+            paths(&[&["a"], &["b"]])
+        );
+        assert_eq!(accesses("f(data);"), PropertyAccesses::All);
+        assert_eq!(accesses("const { a } = data;"), PropertyAccesses::All);
+        assert_eq!(accesses("export default data;"), PropertyAccesses::All);
+    }
+
+    #[test]
+    fn mutations() {
+        assert_eq!(accesses("data.a.b = 1;"), PropertyAccesses::All);
+        assert_eq!(accesses("data.a++;"), PropertyAccesses::All);
+        assert_eq!(accesses("delete data.a;"), PropertyAccesses::All);
+        assert_eq!(accesses("[data.a] = [1];"), PropertyAccesses::All);
+    }
+
+    #[test]
+    fn shadowed_bindings() {
+        assert_eq!(
+            accesses("data.a; function f(data) { return data; }"),
+            paths(&[&["a"]])
+        );
+    }
+}
@@ -6,7 +6,7 @@ use turbopack_core::compile_time_info::CompileTimeInfo;
 use url::Url;
 
 use super::{
-    imports::ImportAnnotations, ConstantValue, JsValue, JsValueUrlKind, ModuleValue,
+    imports::ImportAnnotations, ConstantValue, JsValue, JsValueUrlKind, ModuleValue, ObjectPart,
     WellKnownFunctionKind, WellKnownObjectKind,
 };
 use crate::analyzer::RequireContextValue;
@@ -54,6 +54,9 @@ pub async fn well_known_function_call(
 ) -> Result<JsValue> {
     Ok(match kind {
         WellKnownFunctionKind::ObjectAssign => object_assign(args),
+        WellKnownFunctionKind::ObjectKeys
+        | WellKnownFunctionKind::ObjectValues
+        | WellKnownFunctionKind::ObjectEntries => object_enumerate(kind, args),
         WellKnownFunctionKind::PathJoin => path_join(args),
         WellKnownFunctionKind::PathDirname => path_dirname(args),
         WellKnownFunctionKind::PathResolve(cwd) => path_resolve(*cwd, args),
@@ -165,6 +168,82 @@ pub fn object_assign(args: Vec<JsValue>) -> JsValue {
     }
 }
 
+/// Evaluates `Object.keys`, `Object.values` and `Object.entries` on object
+/// literals with known keys, e.g. `Object.keys({ a: 1, b: 2 })` is `["a",
+/// "b"]`.
+pub fn object_enumerate(kind: WellKnownFunctionKind, args: Vec<JsValue>) -> JsValue {
+    let properties = match &args[..] {
+        [JsValue::Object { parts, mutable, .. }] => own_enumerable_properties(parts)
+            .map(|properties| (properties, *mutable))
+            .ok_or("only objects with known keys can be enumerated"),
+        _ => Err("only object literals can be enumerated"),
+    };
+    let (properties, mutable) = match properties {
+        Ok(result) => result,
+        Err(reason) => {
+            return JsValue::unknown(
+                JsValue::call(Box::new(JsValue::WellKnownFunction(kind)), args),
+                true,
+                reason,
+            )
+        }
+    };
+    let mut value = JsValue::array(
+        properties
+            .into_iter()
+            .map(|(key, value)| match kind {
+                WellKnownFunctionKind::ObjectKeys => key.into(),
+                WellKnownFunctionKind::ObjectValues => value,
+                _ => JsValue::array(vec![key.into(), value]),
+            })
+            .collect(),
+    );
+    if mutable {
+        value.add_unknown_mutations(true);
+    }
+    value
+}
+
+/// Returns the properties of an object literal in the order of
+/// `Object.keys`, which lists integer keys first in ascending order, followed
+/// by the other keys in the order they were added. Returns `None` when not all
+/// keys are known.
+fn own_enumerable_properties(parts: &[ObjectPart]) -> Option<Vec<(String, JsValue)>> {
+    let mut properties: Vec<(String, JsValue)> = Vec::with_capacity(parts.len());
+    for part in parts {
+        let ObjectPart::KeyValue(key, value) = part else {
+            return None;
+        };
+        let key = match key {
+            JsValue::Constant(ConstantValue::Str(str)) => str.as_str().to_string(),
+            JsValue::Constant(ConstantValue::Num(num)) => num.as_u32_index()?.to_string(),
+            _ => return None,
+        };
+        // `{ __proto__: value }` sets the prototype instead of an own property, but a
+        // computed `["__proto__"]` key can't be told apart from it here
+        if key == "__proto__" {
+            return None;
+        }
+        // A later property with the same key replaces the value, but keeps the position
+        if let Some(property) = properties.iter_mut().find(|(k, _)| *k == key) {
+            property.1 = value.clone();
+        } else {
+            properties.push((key, value.clone()));
+        }
+    }
+    let array_index = |key: &str| {
+        key.parse::<u32>()
+            .ok()
+            .filter(|index| *index != u32::MAX && index.to_string() == key)
+    };
+    // The sort is stable, so other keys stay in insertion order
+    properties.sort_by_key(|(key, _)| match array_index(key) {
+        Some(index) => (0, index),
+        None => (1, 0),
+    });
+    Some(properties)
+}
+
 pub fn path_join(args: Vec<JsValue>) -> JsValue {
     if args.is_empty() {
         return ".".into();
@@ -596,6 +675,9 @@ pub async fn well_known_object_member(
 fn global_object(prop: JsValue) -> JsValue {
     match prop.as_str() {
         Some("assign") => JsValue::WellKnownFunction(WellKnownFunctionKind::ObjectAssign),
+        Some("keys") => JsValue::WellKnownFunction(WellKnownFunctionKind::ObjectKeys),
+        Some("values") => JsValue::WellKnownFunction(WellKnownFunctionKind::ObjectValues),
+        Some("entries") => JsValue::WellKnownFunction(WellKnownFunctionKind::ObjectEntries),
         _ => JsValue::unknown(
             JsValue::member(
                 Box::new(JsValue::WellKnownObject(WellKnownObjectKind::GlobalObject)),
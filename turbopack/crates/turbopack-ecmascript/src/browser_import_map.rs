@@ -0,0 +1,311 @@
+use std::collections::VecDeque;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{FxIndexMap, FxIndexSet, RcStr, ResolvedVc, Vc};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::AssetContent,
+    chunk::ChunkableModuleReference,
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    module::{Module, Modules},
+    output::OutputAsset,
+    package_json::package_of_path,
+    resolve::{
+        options::{ImportMap, ImportMapping},
+        ExternalType,
+    },
+    virtual_output::VirtualOutputAsset,
+};
+
+/// An import map of the page, which maps specifiers to URLs. Imports that
+/// match one of the specifiers are not bundled, but left to the browser, which
+/// resolves them with the import map at runtime. This allows to load large
+/// dependencies from a CDN.
+///
+/// The environment of the chunking context needs to support ESM externals, and
+/// externals need to be imported instead of required. turbopack-cli reads it
+/// from the `importmap.json` of the project.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone, Default)]
+pub struct BrowserImportMap {
+    /// The URLs of the specifiers. Like in an import map, a specifier that ends
+    /// with `/` maps all specifiers that start with it.
+    pub imports: FxIndexMap<RcStr, RcStr>,
+}
+
+#[derive(Serialize)]
+struct BrowserImportMapJson<'a> {
+    imports: &'a FxIndexMap<RcStr, RcStr>,
+}
+
+#[derive(Deserialize)]
+struct BrowserImportMapFile {
+    #[serde(default)]
+    imports: FxIndexMap<RcStr, RcStr>,
+}
+
+impl BrowserImportMap {
+    /// Parses an import map file. Only `imports` is supported, `scopes` are
+    /// ignored.
+    pub fn parse(json: &str) -> Result<Self> {
+        let file: BrowserImportMapFile = serde_json::from_str(json)?;
+        Ok(BrowserImportMap {
+            imports: file.imports,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&BrowserImportMapJson {
+            imports: &self.imports,
+        })?)
+    }
+
+    /// The import map as a `<script type="importmap">`, which needs to come
+    /// before the scripts of the page.
+    pub fn script_tag(&self) -> Result<String> {
+        // `</script>` in a URL would end the script early.
+        let json = self.to_json()?.replace("</", "<\\/");
+        Ok(format!("<script type=\"importmap\">\n{json}\n</script>"))
+    }
+
+    /// Inserts aliases that resolve the specifiers of the import map to ESM
+    /// externals.
+    pub fn insert_externals(&self, import_map: &mut ImportMap) {
+        for specifier in self.imports.keys() {
+            if specifier.ends_with('/') {
+                import_map.insert_wildcard_alias(
+                    specifier.clone(),
+                    ImportMapping::External(
+                        Some(format!("{specifier}*").into()),
+                        ExternalType::EcmaScriptModule,
+                    )
+                    .resolved_cell(),
+                );
+            } else {
+                import_map.insert_exact_alias(
+                    specifier.clone(),
+                    ImportMapping::External(
+                        Some(specifier.clone()),
+                        ExternalType::EcmaScriptModule,
+                    )
+                    .resolved_cell(),
+                );
+            }
+        }
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl BrowserImportMap {
+    /// Reads the import map file at `path`, e.g. `importmap.json`. The import
+    /// map is empty when the file doesn't exist.
+    #[turbo_tasks::function]
+    pub async fn read(path: Vc<FileSystemPath>) -> Result<Vc<Self>> {
+        let FileContent::Content(file) = &*path.read().await? else {
+            return Ok(BrowserImportMap::default().cell());
+        };
+        let import_map = BrowserImportMap::parse(file.content().to_str()?.as_ref())
+            .with_context(|| format!("Unable to parse {}", path.to_string().await?))?;
+        Ok(import_map.cell())
+    }
+
+    #[turbo_tasks::function]
+    pub fn is_empty(&self) -> Vc<bool> {
+        Vc::cell(self.imports.is_empty())
+    }
+
+    /// The import map as JSON, to be embedded into the page as a
+    /// `<script type="importmap">`.
+    #[turbo_tasks::function]
+    pub fn json(&self) -> Result<Vc<AssetContent>> {
+        Ok(AssetContent::file(File::from(self.to_json()?).into()))
+    }
+
+    /// The import map as an output asset at `path`, e.g. `importmap.json`.
+    #[turbo_tasks::function]
+    pub fn output_asset(self: Vc<Self>, path: Vc<FileSystemPath>) -> Vc<Box<dyn OutputAsset>> {
+        Vc::upcast(VirtualOutputAsset::new(path, self.json()))
+    }
+
+    /// Emits an error for each package of the import map that is bundled
+    /// anyway, e.g. because a subpath of it is imported that is not mapped or
+    /// one of its files is imported by path. The package would be loaded twice
+    /// then.
+    #[turbo_tasks::function]
+    pub async fn validate(&self, entries: Vc<Modules>) -> Result<Vc<()>> {
+        let packages: FxIndexSet<&str> = self
+            .imports
+            .keys()
+            .filter_map(|specifier| package_of_specifier(specifier))
+            .collect();
+        if packages.is_empty() {
+            return Ok(Default::default());
+        }
+
+        let mut visited = FxIndexSet::default();
+        let mut queue = VecDeque::new();
+        for &entry in entries.await?.iter() {
+            if visited.insert(entry) {
+                queue.push_back(entry);
+            }
+        }
+        let mut reported = FxIndexSet::default();
+        while let Some(module) = queue.pop_front() {
+            let path = module.ident().path();
+            if let Some((name, _)) = package_of_path(&path.await?.path) {
+                if packages.contains(name) && reported.insert(RcStr::from(name)) {
+                    BundledImportMapPackageIssue {
+                        path: path.to_resolved().await?,
+                        package: name.into(),
+                    }
+                    .cell()
+                    .emit();
+                }
+            }
+            for &child in bundled_referenced_modules(*module).await?.iter() {
+                if visited.insert(child) {
+                    queue.push_back(child);
+                }
+            }
+        }
+        Ok(Default::default())
+    }
+}
+
+/// Returns the name of the package that a specifier of the import map
+/// belongs to, e.g. `react-dom` for `react-dom/client`. URLs and relative
+/// specifiers don't belong to a package.
+fn package_of_specifier(specifier: &str) -> Option<&str> {
+    if specifier.starts_with('.') || specifier.starts_with('/') || specifier.contains(':') {
+        return None;
+    }
+    let mut segments = specifier.splitn(3, '/');
+    let first = segments.next()?;
+    let name_len = if first.starts_with('@') {
+        first.len() + 1 + segments.next().filter(|s| !s.is_empty())?.len()
+    } else {
+        first.len()
+    };
+    Some(&specifier[..name_len]).filter(|name| !name.is_empty())
+}
+
+/// The modules that are bundled when the module is chunked, including the
+/// ones that are loaded asynchronously.
+#[turbo_tasks::function]
+async fn bundled_referenced_modules(module: Vc<Box<dyn Module>>) -> Result<Vc<Modules>> {
+    let mut modules = FxIndexSet::default();
+    for &reference in module.references().await?.iter() {
+        let Some(reference) =
+            Vc::try_resolve_downcast::<Box<dyn ChunkableModuleReference>>(reference).await?
+        else {
+            continue;
+        };
+        if reference.chunking_type().await?.is_none() {
+            continue;
+        }
+        modules.extend(
+            reference
+                .resolve_reference()
+                .resolve()
+                .await?
+                .primary_modules()
+                .await?
+                .iter()
+                .copied(),
+        );
+    }
+    Ok(Vc::cell(modules.into_iter().collect()))
+}
+
+#[turbo_tasks::value(shared)]
+struct BundledImportMapPackageIssue {
+    /// The first bundled module of the package.
+    path: ResolvedVc<FileSystemPath>,
+    package: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for BundledImportMapPackageIssue {
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Analysis.into()
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Line(vec![
+            StyledString::Code(self.package.clone()),
+            StyledString::Text(" is in the import map, but is bundled too".into()),
+        ])
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(
+                "The package would be loaded twice, once from the URL of the import map and once \
+                 from the bundle. Map all imported subpaths of the package, e.g. with a specifier \
+                 that ends with `/`, and don't import its files by path."
+                    .into(),
+            )
+            .cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{package_of_specifier, BrowserImportMap};
+
+    #[test]
+    fn parses_the_imports() {
+        let import_map = BrowserImportMap::parse(
+            r#"{
+                "imports": {
+                    "react": "https://esm.sh/react@18",
+                    "lodash/": "https://esm.sh/lodash/"
+                },
+                "scopes": {}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            import_map.imports.keys().collect::<Vec<_>>(),
+            ["react", "lodash/"]
+        );
+        assert!(BrowserImportMap::parse("{}").unwrap().imports.is_empty());
+    }
+
+    #[test]
+    fn escapes_the_script_tag() {
+        let import_map = BrowserImportMap::parse(
+            r#"{ "imports": { "evil": "https://example.com/</script>" } }"#,
+        )
+        .unwrap();
+        let script_tag = import_map.script_tag().unwrap();
+        assert!(script_tag.starts_with("<script type=\"importmap\">"));
+        assert_eq!(script_tag.matches("</script>").count(), 1);
+    }
+
+    #[test]
+    fn finds_the_package_of_a_specifier() {
+        assert_eq!(package_of_specifier("react"), Some("react"));
+        assert_eq!(package_of_specifier("react-dom/client"), Some("react-dom"));
+        assert_eq!(package_of_specifier("@scope/pkg/sub"), Some("@scope/pkg"));
+        assert_eq!(package_of_specifier("@scope/"), None);
+        assert_eq!(package_of_specifier("./local"), None);
+        assert_eq!(package_of_specifier("https://esm.sh/react"), None);
+    }
+}
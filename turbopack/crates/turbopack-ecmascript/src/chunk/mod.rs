@@ -30,7 +30,9 @@ pub use self::{
         EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemExt,
         EcmascriptChunkItemOptions,
     },
-    placeable::{EcmascriptChunkPlaceable, EcmascriptExports},
+    placeable::{
+        EcmascriptChunkPlaceable, EcmascriptExports, EcmascriptValueModule, PropertyPaths,
+    },
 };
 
 #[turbo_tasks::value]
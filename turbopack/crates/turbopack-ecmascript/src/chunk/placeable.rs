@@ -1,5 +1,5 @@
 use anyhow::Result;
-use turbo_tasks::{ResolvedVc, TryFlatJoinIterExt, Vc};
+use turbo_tasks::{RcStr, ResolvedVc, TryFlatJoinIterExt, Vc};
 use turbo_tasks_fs::{glob::Glob, FileJsonContent, FileSystemPath};
 use turbopack_core::{
     asset::Asset,
@@ -7,7 +7,10 @@ use turbopack_core::{
     error::PrettyPrintError,
     issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
     module::Module,
-    resolve::{find_context_file, package_json, FindContextFileResult},
+    resolve::{
+        find_context_file, origin::ResolveOrigin, package_json, parse::Request,
+        FindContextFileResult,
+    },
 };
 
 use crate::references::{
@@ -29,6 +32,26 @@ pub trait EcmascriptChunkPlaceable: ChunkableModule + Module + Asset {
     }
 }
 
+/// Paths of properties of a value, like `["a", "b"]` for `value.a.b`.
+#[turbo_tasks::value(transparent)]
+pub struct PropertyPaths(Vec<Vec<RcStr>>);
+
+/// A module that exports a plain value, like a JSON module. It can omit the
+/// properties of the value that importing modules don't read.
+#[turbo_tasks::value_trait]
+pub trait EcmascriptValueModule: Module {
+    /// Records that the import of `request` from `origin` only reads the
+    /// properties at `paths` of the value, and returns the module that is
+    /// shared by all such imports. It exports the properties that any of them
+    /// reads, with everything nested in them.
+    fn with_read_properties(
+        self: Vc<Self>,
+        origin: Vc<Box<dyn ResolveOrigin>>,
+        request: Vc<Request>,
+        paths: Vc<PropertyPaths>,
+    ) -> Vc<Box<dyn Module>>;
+}
+
 #[turbo_tasks::value]
 enum SideEffectsValue {
     None,
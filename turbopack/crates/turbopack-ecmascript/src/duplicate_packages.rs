@@ -0,0 +1,409 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{trace::TraceRawVcs, FxIndexMap, FxIndexSet, RcStr, ResolvedVc, Vc};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack_core::{
+    chunk::{ChunkableModuleReference, ChunkingType},
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    module::{Module, Modules},
+    package_json::{package_of_path, read_package_json},
+};
+
+/// A package that is included in more than one version in a chunk group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+pub struct DuplicatePackage {
+    pub name: RcStr,
+    pub versions: Vec<DuplicatePackageVersion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePackageVersion {
+    /// The version from the package.json of the package, if it has one.
+    pub version: Option<RcStr>,
+    /// The directory of the package.
+    pub path: RcStr,
+    /// The paths of the modules from an entry of the chunk group to the
+    /// first module of this version of the package.
+    pub import_chain: Vec<RcStr>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct DuplicatePackages(Vec<DuplicatePackage>);
+
+/// Finds the packages that are included in more than one version in the chunk
+/// group of the `entries`, and emits a warning for each of them.
+///
+/// Packages are identified by the `node_modules` directory that contains their
+/// modules, and their version is read from their package.json. Modules that
+/// are loaded asynchronously are placed in other chunk groups and are not
+/// visited.
+#[turbo_tasks::function]
+pub async fn find_duplicate_packages(entries: Vc<Modules>) -> Result<Vc<DuplicatePackages>> {
+    // The module from which each module was found first, so the shortest
+    // import chains can be reconstructed.
+    let mut parents: FxIndexMap<ResolvedVc<Box<dyn Module>>, Option<ResolvedVc<Box<dyn Module>>>> =
+        FxIndexMap::default();
+    let mut queue = VecDeque::new();
+    for &entry in entries.await?.iter() {
+        if parents.insert(entry, None).is_none() {
+            queue.push_back(entry);
+        }
+    }
+
+    // The first module of each package directory, by package name
+    let mut packages: FxIndexMap<RcStr, FxIndexMap<RcStr, ResolvedVc<Box<dyn Module>>>> =
+        FxIndexMap::default();
+    while let Some(module) = queue.pop_front() {
+        let path = module.ident().path().await?;
+        if let Some((name, dir)) = package_of_path(&path.path) {
+            packages
+                .entry(name.into())
+                .or_default()
+                .entry(dir.into())
+                .or_insert(module);
+        }
+        for &child in chunk_group_referenced_modules(*module).await?.iter() {
+            if !parents.contains_key(&child) {
+                parents.insert(child, Some(module));
+                queue.push_back(child);
+            }
+        }
+    }
+
+    let mut duplicates = Vec::new();
+    for (name, dirs) in packages {
+        if dirs.len() < 2 {
+            continue;
+        }
+        // Copies of the same version, e.g. from different pnpm peer dependency
+        // sets, are reported with the first copy that was found
+        let mut versions: FxIndexMap<Option<RcStr>, DuplicatePackageVersion> =
+            FxIndexMap::default();
+        for (dir, module) in dirs {
+            let version = package_version(*module.ident().path(), &dir).await?;
+            if versions.contains_key(&version) {
+                continue;
+            }
+            let import_chain = import_chain(&parents, module).await?;
+            versions.insert(
+                version.clone(),
+                DuplicatePackageVersion {
+                    version,
+                    path: dir,
+                    import_chain,
+                },
+            );
+        }
+        if versions.len() < 2 {
+            continue;
+        }
+        let duplicate = DuplicatePackage {
+            name,
+            versions: versions.into_values().collect(),
+        };
+        if let Some(&entry) = parents.keys().next() {
+            DuplicatePackageIssue {
+                path: entry.ident().path().to_resolved().await?,
+                package: duplicate.clone(),
+            }
+            .cell()
+            .emit();
+        }
+        duplicates.push(duplicate);
+    }
+
+    Ok(Vc::cell(duplicates))
+}
+
+/// The modules that are placed in the same chunk group as the module when it's
+/// chunked.
+#[turbo_tasks::function]
+pub(crate) async fn chunk_group_referenced_modules(
+    module: Vc<Box<dyn Module>>,
+) -> Result<Vc<Modules>> {
+    let mut modules = FxIndexSet::default();
+    for &reference in module.references().await?.iter() {
+        let Some(reference) =
+            Vc::try_resolve_downcast::<Box<dyn ChunkableModuleReference>>(reference).await?
+        else {
+            continue;
+        };
+        match *reference.chunking_type().await? {
+            Some(
+                ChunkingType::Parallel
+                | ChunkingType::ParallelInheritAsync
+                | ChunkingType::Passthrough,
+            ) => {}
+            Some(ChunkingType::Async) | None => continue,
+        }
+        modules.extend(
+            reference
+                .resolve_reference()
+                .resolve()
+                .await?
+                .primary_modules()
+                .await?
+                .iter()
+                .copied(),
+        );
+    }
+    Ok(Vc::cell(modules.into_iter().collect()))
+}
+
+async fn import_chain(
+    parents: &FxIndexMap<ResolvedVc<Box<dyn Module>>, Option<ResolvedVc<Box<dyn Module>>>>,
+    module: ResolvedVc<Box<dyn Module>>,
+) -> Result<Vec<RcStr>> {
+    let mut chain = Vec::new();
+    let mut current = Some(module);
+    while let Some(module) = current {
+        chain.push(module.ident().path().await?.path.clone());
+        current = parents.get(&module).copied().flatten();
+    }
+    chain.reverse();
+    Ok(chain)
+}
+
+/// Reads the version of the package in `dir` from its package.json. `path` is
+/// a file in the same file system.
+async fn package_version(path: Vc<FileSystemPath>, dir: &str) -> Result<Option<RcStr>> {
+    let package_json_path = path.root().join(format!("{dir}/package.json").into());
+    if let Some(package_json) = &*read_package_json(package_json_path).await? {
+        Ok(package_json["version"].as_str().map(RcStr::from))
+    } else {
+        Ok(None)
+    }
+}
+
+#[turbo_tasks::value(shared)]
+struct DuplicatePackageIssue {
+    /// The first entry of the chunk group.
+    path: ResolvedVc<FileSystemPath>,
+    package: DuplicatePackage,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for DuplicatePackageIssue {
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Analysis.into()
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Line(vec![
+            StyledString::Text("Multiple versions of ".into()),
+            StyledString::Code(self.package.name.clone()),
+            StyledString::Text(" are bundled".into()),
+        ])
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        let mut lines = vec![StyledString::Text(
+            "The package is included in each of these versions, which increases the bundle size:"
+                .into(),
+        )];
+        for version in &self.package.versions {
+            lines.push(StyledString::Line(vec![
+                StyledString::Code(
+                    version
+                        .version
+                        .clone()
+                        .unwrap_or_else(|| "unknown version".into()),
+                ),
+                StyledString::Text(format!(" in {}, imported by", version.path).into()),
+            ]));
+            for path in &version.import_chain {
+                lines.push(StyledString::Text(format!("  {path}").into()));
+            }
+        }
+        Vc::cell(Some(StyledString::Stack(lines).cell()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+
+    use anyhow::Result;
+    use turbo_tasks::{RcStr, ResolvedVc, TurboTasks, ValueToString, Vc};
+    use turbo_tasks_fs::{DiskFileSystem, FileContent, FileSystem, FileSystemPath};
+    use turbo_tasks_memory::MemoryBackend;
+    use turbopack_core::{
+        asset::{Asset, AssetContent},
+        chunk::{ChunkableModuleReference, ChunkingType, ChunkingTypeOption},
+        ident::AssetIdent,
+        module::Module,
+        reference::{ModuleReference, ModuleReferences},
+        resolve::ModuleResolveResult,
+    };
+
+    use super::{find_duplicate_packages, DuplicatePackageVersion};
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    /// A module that only references other modules.
+    #[turbo_tasks::value]
+    struct TestModule {
+        path: ResolvedVc<FileSystemPath>,
+        references: Vec<ResolvedVc<TestReference>>,
+    }
+
+    #[turbo_tasks::value_impl]
+    impl Module for TestModule {
+        #[turbo_tasks::function]
+        fn ident(&self) -> Vc<AssetIdent> {
+            AssetIdent::from_path(*self.path)
+        }
+
+        #[turbo_tasks::function]
+        fn references(&self) -> Vc<ModuleReferences> {
+            Vc::cell(
+                self.references
+                    .iter()
+                    .map(|&reference| Vc::upcast(*reference))
+                    .collect(),
+            )
+        }
+    }
+
+    #[turbo_tasks::value_impl]
+    impl Asset for TestModule {
+        #[turbo_tasks::function]
+        fn content(&self) -> Vc<AssetContent> {
+            AssetContent::file(FileContent::NotFound.cell())
+        }
+    }
+
+    #[turbo_tasks::value]
+    struct TestReference {
+        module: ResolvedVc<Box<dyn Module>>,
+        is_async: bool,
+    }
+
+    #[turbo_tasks::value_impl]
+    impl ModuleReference for TestReference {
+        #[turbo_tasks::function]
+        fn resolve_reference(&self) -> Vc<ModuleResolveResult> {
+            ModuleResolveResult::module(self.module).cell()
+        }
+    }
+
+    #[turbo_tasks::value_impl]
+    impl ValueToString for TestReference {
+        #[turbo_tasks::function]
+        fn to_string(&self) -> Vc<RcStr> {
+            Vc::cell("test reference".into())
+        }
+    }
+
+    #[turbo_tasks::value_impl]
+    impl ChunkableModuleReference for TestReference {
+        #[turbo_tasks::function]
+        fn chunking_type(&self) -> Vc<ChunkingTypeOption> {
+            Vc::cell(Some(if self.is_async {
+                ChunkingType::Async
+            } else {
+                ChunkingType::Parallel
+            }))
+        }
+    }
+
+    /// Creates a module at `path` that references the `children`, which are
+    /// loaded asynchronously when the flag is set.
+    async fn module(
+        root: Vc<FileSystemPath>,
+        path: &str,
+        children: Vec<(ResolvedVc<Box<dyn Module>>, bool)>,
+    ) -> Result<ResolvedVc<Box<dyn Module>>> {
+        let mut references = vec![];
+        for (module, is_async) in children {
+            references.push(TestReference { module, is_async }.resolved_cell());
+        }
+        Ok(ResolvedVc::upcast(
+            TestModule {
+                path: root.join(path.into()).to_resolved().await?,
+                references,
+            }
+            .resolved_cell(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn finds_packages_in_multiple_versions() {
+        register();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async {
+            let root = DiskFileSystem::new(
+                "test".into(),
+                concat!(env!("CARGO_MANIFEST_DIR"), "/tests/duplicate-packages").into(),
+                vec![],
+            )
+            .root();
+
+            let a = module(root, "node_modules/a/index.js", vec![]).await?;
+            let nested_a = module(root, "node_modules/b/node_modules/a/index.js", vec![]).await?;
+            let b = module(root, "node_modules/b/index.js", vec![(nested_a, false)]).await?;
+            // Another copy of the first version isn't reported again
+            let copied_a = module(root, "node_modules/e/node_modules/a/index.js", vec![]).await?;
+            let e = module(root, "node_modules/e/index.js", vec![(copied_a, false)]).await?;
+            // Modules loaded asynchronously are in another chunk group
+            let async_a = module(root, "node_modules/d/node_modules/a/index.js", vec![]).await?;
+            let d = module(root, "node_modules/d/index.js", vec![(async_a, false)]).await?;
+            let index = module(
+                root,
+                "src/index.js",
+                vec![(a, false), (b, false), (e, false), (d, true)],
+            )
+            .await?;
+
+            let duplicates = find_duplicate_packages(Vc::cell(vec![index])).await?;
+            assert_eq!(duplicates.len(), 1);
+            assert_eq!(duplicates[0].name, "a");
+            assert_eq!(
+                duplicates[0].versions,
+                vec![
+                    DuplicatePackageVersion {
+                        version: Some("1.0.0".into()),
+                        path: "node_modules/a".into(),
+                        import_chain: vec!["src/index.js".into(), "node_modules/a/index.js".into()],
+                    },
+                    DuplicatePackageVersion {
+                        version: Some("2.0.0".into()),
+                        path: "node_modules/b/node_modules/a".into(),
+                        import_chain: vec![
+                            "src/index.js".into(),
+                            "node_modules/b/index.js".into(),
+                            "node_modules/b/node_modules/a/index.js".into(),
+                        ],
+                    },
+                ]
+            );
+
+            // A single version isn't reported
+            let duplicates = find_duplicate_packages(Vc::cell(vec![b])).await?;
+            assert!(duplicates.is_empty());
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -11,9 +11,11 @@
 pub mod analyzer;
 pub mod annotations;
 pub mod async_chunk;
+pub mod browser_import_map;
 pub mod chunk;
 pub mod chunk_group_files_asset;
 pub mod code_gen;
+pub mod duplicate_packages;
 mod errors;
 pub mod global_module_id_strategy;
 pub mod magic_identifier;
@@ -21,6 +23,7 @@ pub mod manifest;
 pub mod minify;
 pub mod parse;
 mod path_visitor;
+pub mod polyfills;
 pub mod references;
 pub mod side_effect_optimization;
 pub(crate) mod special_cases;
@@ -71,7 +74,8 @@ use turbopack_core::{
     compile_time_info::CompileTimeInfo,
     context::AssetContext,
     ident::AssetIdent,
-    module::{Module, OptionModule},
+    module::Module,
+    module_metadata::ModuleMetadata,
     reference::ModuleReferences,
     reference_type::InnerAssets,
     resolve::{
@@ -146,6 +150,19 @@ pub struct EcmascriptOptions {
     /// If false, they will reference the whole directory. If true, they won't
     /// reference anything and lead to an runtime error instead.
     pub ignore_dynamic_requests: bool,
+    /// Packages that aren't bundled when they are required in a `try` block,
+    /// like optional dependencies are. The require throws a "Cannot find
+    /// module" error at runtime instead, which the `try` block is expected to
+    /// handle.
+    pub exclude_optional_requires: Option<ResolvedVc<Vec<RcStr>>>,
+    /// Moves styled-jsx styles without dynamic interpolations to CSS modules,
+    /// so they are served in CSS chunks instead of being injected at runtime.
+    /// Static `css` props of host elements are moved to CSS modules as well.
+    pub extract_styled_jsx: bool,
+    /// Reports modules that are very large or slow to parse and analyze as
+    /// hints. The report depends on timings, so it's meant for development
+    /// only.
+    pub report_slow_modules: bool,
 }
 
 #[turbo_tasks::value(serialization = "auto_for_input")]
@@ -546,6 +563,11 @@ impl Module for EcmascriptModuleAsset {
         let references = analyze.references.await?.iter().copied().collect();
         Ok(Vc::cell(references))
     }
+
+    #[turbo_tasks::function]
+    async fn metadata(self: Vc<Self>) -> Result<Vc<ModuleMetadata>> {
+        Ok(self.analyze().await?.metadata)
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -599,7 +621,10 @@ impl ResolveOrigin for EcmascriptModuleAsset {
     }
 
     #[turbo_tasks::function]
-    async fn get_inner_asset(&self, request: Vc<Request>) -> Result<Vc<OptionModule>> {
+    async fn get_inner_asset(
+        &self,
+        request: Vc<Request>,
+    ) -> Result<Vc<Option<ResolvedVc<Box<dyn Module>>>>> {
         Ok(Vc::cell(if let Some(inner_assets) = &self.inner_assets {
             if let Some(request) = request.await?.request() {
                 inner_assets.await?.get(&request).copied()
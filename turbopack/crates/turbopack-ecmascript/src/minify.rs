@@ -5,11 +5,15 @@ use swc_core::{
     base::{try_with_handler, Compiler},
     common::{
         comments::{Comments, SingleThreadedComments},
-        BytePos, FileName, FilePathMapping, LineCol, Mark, SourceMap as SwcSourceMap, GLOBALS,
+        BytePos, FileName, FilePathMapping, LineCol, Mark, SourceFile, SourceMap as SwcSourceMap,
+        Span, Spanned, GLOBALS,
     },
     ecma::{
         self,
-        ast::{EsVersion, Program},
+        ast::{
+            Class, ClassExpr, ClassMember, Decl, EsVersion, Expr, ExprStmt, FnExpr, Ident, Lit,
+            ModuleDecl, ModuleItem, Pat, Program, PropName, Stmt,
+        },
         codegen::{
             text_writer::{self, JsWriter, WriteJs},
             Emitter,
@@ -17,19 +21,52 @@ use swc_core::{
         minifier::option::{ExtraOptions, MangleOptions, MinifyOptions},
         parser::{lexer::Lexer, Parser, StringInput, Syntax},
         transforms::base::fixer::paren_remover,
+        visit::{VisitMut, VisitMutWith},
     },
+    quote,
 };
 use turbo_tasks::Vc;
 use turbo_tasks_fs::FileSystemPath;
 use turbopack_core::{
+    chunk::KeepNames,
     code_builder::{Code, CodeBuilder},
     source_map::GenerateSourceMap,
 };
 
 use crate::ParseResultSourceMap;
 
+/// Minifies `code` and references its source map, which is emitted next to
+/// the file at `path`.
+#[turbo_tasks::function]
+pub async fn minify(
+    path: Vc<FileSystemPath>,
+    code: Vc<Code>,
+    keep_names: KeepNames,
+) -> Result<Vc<Code>> {
+    let minified = minify_code(path, code, keep_names).await?;
+    let mut builder = CodeBuilder::default();
+    builder.push_code(&minified);
+    write!(builder, "{}", source_map_reference(path.await?.file_name()))?;
+    Ok(builder.build().cell())
+}
+
+/// The comment that references the source map of the file `file_name`, which
+/// is emitted next to it.
+pub fn source_map_reference(file_name: &str) -> String {
+    format!(
+        "\n\n//# sourceMappingURL={}.map",
+        urlencoding::encode(file_name)
+    )
+}
+
+/// Minifies `code` like [minify], but doesn't reference its source map, so
+/// code like a chunk footer can be added after it.
 #[turbo_tasks::function]
-pub async fn minify(path: Vc<FileSystemPath>, code: Vc<Code>) -> Result<Vc<Code>> {
+pub async fn minify_code(
+    path: Vc<FileSystemPath>,
+    code: Vc<Code>,
+    keep_names: KeepNames,
+) -> Result<Vc<Code>> {
     let path = path.await?;
     let original_map = code.generate_source_map();
     let code = code.await?;
@@ -41,11 +78,14 @@ pub async fn minify(path: Vc<FileSystemPath>, code: Vc<Code>) -> Result<Vc<Code>
         code.source_code().to_str()?.to_string(),
     );
 
+    // Only the annotations of kept names are read, other comments like
+    // `#__PURE__` are not passed to the minifier.
+    let annotations = SingleThreadedComments::default();
     let lexer = Lexer::new(
         Syntax::default(),
         EsVersion::latest(),
         StringInput::from(&*fm),
-        None,
+        (keep_names == KeepNames::AnnotatedOnly).then_some(&annotations as &dyn Comments),
     );
     let mut parser = Parser::new_from(lexer);
 
@@ -66,7 +106,14 @@ pub async fn minify(path: Vc<FileSystemPath>, code: Vc<Code>) -> Result<Vc<Code>
             let top_level_mark = Mark::new();
 
             Ok(compiler.run_transform(handler, false, || {
-                let program = program.apply(paren_remover(Some(&comments)));
+                let mut program = program.apply(paren_remover(Some(&comments)));
+
+                if keep_names != KeepNames::Off {
+                    program.visit_mut_with(&mut KeepNamesVisitor {
+                        keep_names,
+                        annotations: &annotations,
+                    });
+                }
 
                 let mut program = program.apply(swc_core::ecma::transforms::base::resolver(
                     unresolved_mark,
@@ -101,7 +148,7 @@ pub async fn minify(path: Vc<FileSystemPath>, code: Vc<Code>) -> Result<Vc<Code>
         })
     })?;
 
-    let (src, src_map_buf) = print_program(cm.clone(), program)?;
+    let (src, src_map_buf) = print_program(cm.clone(), program, None, true)?;
 
     let mut builder = CodeBuilder::default();
     builder.push_source(
@@ -111,34 +158,234 @@ pub async fn minify(path: Vc<FileSystemPath>, code: Vc<Code>) -> Result<Vc<Code>
         )),
     );
 
-    write!(
-        builder,
-        "\n\n//# sourceMappingURL={}.map",
-        urlencoding::encode(path.file_name())
-    )?;
     Ok(builder.build().cell())
 }
 
+/// Formats `code` for reading it while debugging: it's printed with consistent
+/// indentation and line breaks, and comments are kept. Like [minify_code], the
+/// result doesn't reference its source map. Code that can't be parsed is
+/// returned unchanged.
+#[turbo_tasks::function]
+pub async fn pretty_print(path: Vc<FileSystemPath>, code: Vc<Code>) -> Result<Vc<Code>> {
+    let path = path.await?;
+    let original_map = code.generate_source_map();
+    let original_code = code.await?;
+
+    let cm = Arc::new(SwcSourceMap::new(FilePathMapping::empty()));
+    let fm = cm.new_source_file(
+        FileName::Custom(path.path.to_string()).into(),
+        original_code.source_code().to_str()?.to_string(),
+    );
+    let Some((src, src_map_buf)) = pretty_print_source_file(cm.clone(), &fm)? else {
+        return Ok(code);
+    };
+
+    let mut builder = CodeBuilder::default();
+    builder.push_source(
+        &src.into(),
+        Some(Vc::upcast(
+            ParseResultSourceMap::new(cm, src_map_buf, original_map).cell(),
+        )),
+    );
+
+    Ok(builder.build().cell())
+}
+
+/// Parses and prints `fm` with its comments, or returns `None` when it can't
+/// be parsed.
+fn pretty_print_source_file(
+    cm: Arc<SwcSourceMap>,
+    fm: &SourceFile,
+) -> Result<Option<(String, Vec<(BytePos, LineCol)>)>> {
+    let comments = SingleThreadedComments::default();
+    let lexer = Lexer::new(
+        Syntax::default(),
+        EsVersion::latest(),
+        StringInput::from(fm),
+        Some(&comments as &dyn Comments),
+    );
+    let Ok(program) = GLOBALS.set(&Default::default(), || {
+        Parser::new_from(lexer).parse_program()
+    }) else {
+        return Ok(None);
+    };
+    print_program(cm, program, Some(&comments), false).map(Some)
+}
+
+/// Assigns the original names of functions and classes with
+/// `Object.defineProperty` next to their declarations, so they keep them when
+/// they are renamed by the mangler.
+struct KeepNamesVisitor<'a> {
+    keep_names: KeepNames,
+    annotations: &'a SingleThreadedComments,
+}
+
+impl KeepNamesVisitor<'_> {
+    fn keeps_functions(&self) -> bool {
+        matches!(self.keep_names, KeepNames::All | KeepNames::AnnotatedOnly)
+    }
+
+    fn is_annotated(&self, span: Span) -> bool {
+        self.annotations.with_leading(span.lo, |comments| {
+            comments
+                .iter()
+                .any(|comment| matches!(comment.text.trim(), "@__KEEP_NAME__" | "#__KEEP_NAME__"))
+        })
+    }
+
+    fn insert_kept_names<T: KeepNamesItem>(&self, items: &mut Vec<T>) {
+        // Function declarations are hoisted, so their names are assigned before
+        // any other statement.
+        let mut hoisted = Vec::new();
+        let mut result = Vec::with_capacity(items.len());
+        for item in items.drain(..) {
+            let mut kept = Vec::new();
+            if let Some(decl) = item.decl() {
+                if self.keep_names != KeepNames::AnnotatedOnly || self.is_annotated(item.span()) {
+                    match decl {
+                        Decl::Fn(decl) if self.keeps_functions() => {
+                            hoisted.push(decl.ident.clone());
+                        }
+                        Decl::Class(decl) if !has_static_name(&decl.class) => {
+                            kept.push(decl.ident.clone());
+                        }
+                        Decl::Var(decl) => {
+                            for declarator in &decl.decls {
+                                let (Pat::Ident(binding), Some(init)) =
+                                    (&declarator.name, &declarator.init)
+                                else {
+                                    continue;
+                                };
+                                // Anonymous functions and classes are named by
+                                // the variable.
+                                let named_by_binding = match &**init {
+                                    Expr::Fn(FnExpr { ident: None, .. }) | Expr::Arrow(_) => {
+                                        self.keeps_functions()
+                                    }
+                                    Expr::Class(ClassExpr { ident: None, class }) => {
+                                        !has_static_name(class)
+                                    }
+                                    _ => false,
+                                };
+                                if named_by_binding {
+                                    kept.push(binding.id.clone());
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            result.push(item);
+            result.extend(kept.iter().map(|ident| T::from(keep_name(ident))));
+        }
+        if !hoisted.is_empty() {
+            let directives = result.iter().take_while(|item| item.is_directive()).count();
+            result.splice(
+                directives..directives,
+                hoisted.iter().map(|ident| T::from(keep_name(ident))),
+            );
+        }
+        *items = result;
+    }
+}
+
+impl VisitMut for KeepNamesVisitor<'_> {
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        items.visit_mut_children_with(self);
+        self.insert_kept_names(items);
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        stmts.visit_mut_children_with(self);
+        self.insert_kept_names(stmts);
+    }
+}
+
+/// A statement of a list that the assignments of kept names are inserted
+/// into.
+trait KeepNamesItem: From<Stmt> + Spanned {
+    fn decl(&self) -> Option<&Decl>;
+
+    fn is_directive(&self) -> bool;
+}
+
+impl KeepNamesItem for Stmt {
+    fn decl(&self) -> Option<&Decl> {
+        match self {
+            Stmt::Decl(decl) => Some(decl),
+            _ => None,
+        }
+    }
+
+    fn is_directive(&self) -> bool {
+        matches!(self, Stmt::Expr(ExprStmt { expr, .. }) if matches!(&**expr, Expr::Lit(Lit::Str(_))))
+    }
+}
+
+impl KeepNamesItem for ModuleItem {
+    fn decl(&self) -> Option<&Decl> {
+        match self {
+            ModuleItem::Stmt(stmt) => stmt.decl(),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => Some(&export.decl),
+            _ => None,
+        }
+    }
+
+    fn is_directive(&self) -> bool {
+        matches!(self, ModuleItem::Stmt(stmt) if stmt.is_directive())
+    }
+}
+
+/// Classes with a static `name` member define the name themselves.
+fn has_static_name(class: &Class) -> bool {
+    let is_name = |key: &PropName| match key {
+        PropName::Ident(ident) => &*ident.sym == "name",
+        PropName::Str(str) => &*str.value == "name",
+        _ => false,
+    };
+    class.body.iter().any(|member| match member {
+        ClassMember::Method(method) => method.is_static && is_name(&method.key),
+        ClassMember::ClassProp(prop) => prop.is_static && is_name(&prop.key),
+        _ => false,
+    })
+}
+
+fn keep_name(ident: &Ident) -> Stmt {
+    quote!(
+        "Object.defineProperty($target, \"name\", { value: $name, configurable: true });" as Stmt,
+        target: Ident = ident.clone(),
+        name: Expr = Expr::Lit(Lit::Str(ident.sym.as_str().into()))
+    )
+}
+
 // From https://github.com/swc-project/swc/blob/11efd4e7c5e8081f8af141099d3459c3534c1e1d/crates/swc/src/lib.rs#L523-L560
 fn print_program(
     cm: Arc<SwcSourceMap>,
     program: Program,
+    comments: Option<&dyn Comments>,
+    minify: bool,
 ) -> Result<(String, Vec<(BytePos, LineCol)>)> {
     let mut src_map_buf = vec![];
 
     let src = {
         let mut buf = vec![];
         {
-            let wr = Box::new(text_writer::omit_trailing_semi(Box::new(JsWriter::new(
+            let wr = Box::new(JsWriter::new(
                 cm.clone(),
                 "\n",
                 &mut buf,
                 Some(&mut src_map_buf),
-            )))) as Box<dyn WriteJs>;
+            )) as Box<dyn WriteJs>;
+            let wr: Box<dyn WriteJs> = if minify {
+                Box::new(text_writer::omit_trailing_semi(wr))
+            } else {
+                wr
+            };
 
             let mut emitter = Emitter {
-                cfg: swc_core::ecma::codegen::Config::default().with_minify(true),
-                comments: None,
+                cfg: swc_core::ecma::codegen::Config::default().with_minify(minify),
+                comments,
                 cm: cm.clone(),
                 wr,
             };
@@ -153,3 +400,152 @@ fn print_program(
 
     Ok((src, src_map_buf))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use swc_core::{
+        common::{
+            comments::{Comments, SingleThreadedComments},
+            FileName, FilePathMapping, SourceMap as SwcSourceMap, GLOBALS,
+        },
+        ecma::{
+            ast::EsVersion,
+            parser::{lexer::Lexer, Parser, StringInput, Syntax},
+            visit::VisitMutWith,
+        },
+    };
+    use turbo_tasks::TurboTasks;
+    use turbo_tasks_fs::{FileSystem, VirtualFileSystem};
+    use turbo_tasks_memory::MemoryBackend;
+    use turbopack_core::{chunk::KeepNames, code_builder::CodeBuilder};
+
+    use super::{
+        minify, minify_code, pretty_print_source_file, print_program, source_map_reference,
+        KeepNamesVisitor,
+    };
+
+    fn keep_names(code: &str, keep_names: KeepNames) -> String {
+        let cm = Arc::new(SwcSourceMap::new(FilePathMapping::empty()));
+        let fm = cm.new_source_file(FileName::Anon.into(), code.to_string());
+        let annotations = SingleThreadedComments::default();
+        let lexer = Lexer::new(
+            Syntax::default(),
+            EsVersion::latest(),
+            StringInput::from(&*fm),
+            Some(&annotations as &dyn Comments),
+        );
+        GLOBALS.set(&Default::default(), || {
+            let mut program = Parser::new_from(lexer).parse_program().unwrap();
+            program.visit_mut_with(&mut KeepNamesVisitor {
+                keep_names,
+                annotations: &annotations,
+            });
+            print_program(cm.clone(), program, None, true).unwrap().0
+        })
+    }
+
+    /// The names that are kept in the printed code, in order.
+    fn kept(code: &str) -> Vec<&str> {
+        code.split("Object.defineProperty(")
+            .skip(1)
+            .map(|rest| rest.split(',').next().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn keeps_names_of_functions_and_classes() {
+        let code = r#""use strict";
+            a();
+            function a() {}
+            class B {}
+            const c = () => {}, d = class {}, e = 1;
+            class F { static name() {} }"#;
+
+        let all = keep_names(code, KeepNames::All);
+        assert_eq!(kept(&all), vec!["a", "B", "c", "d"]);
+        // Function declarations are hoisted, but not before directives.
+        assert!(all.starts_with("\"use strict\";Object.defineProperty(a,"));
+
+        assert_eq!(
+            kept(&keep_names(code, KeepNames::ClassesOnly)),
+            vec!["B", "d"]
+        );
+    }
+
+    #[test]
+    fn keeps_names_of_annotated_declarations() {
+        let code = "/* @__KEEP_NAME__ */ function a() {}
+            function b() {}
+            /*#__KEEP_NAME__*/ const c = function () {};
+            const d = function () {};";
+        assert_eq!(
+            kept(&keep_names(code, KeepNames::AnnotatedOnly)),
+            vec!["a", "c"]
+        );
+    }
+
+    #[test]
+    fn pretty_prints_chunks() {
+        let code = "(globalThis.TURBOPACK = globalThis.TURBOPACK || []).push([\"a.js\", {\n// \
+                    [project]/src/a.js\n\"[project]/src/a.js\": ((__turbopack_context__) => {let \
+                    x = 1;if (x) {x++}}),\n}]);";
+        let cm = Arc::new(SwcSourceMap::new(FilePathMapping::empty()));
+        let fm = cm.new_source_file(FileName::Anon.into(), code.to_string());
+        let (printed, _) = pretty_print_source_file(cm, &fm).unwrap().unwrap();
+
+        // The banner comments of the modules are kept.
+        assert!(printed.contains("// [project]/src/a.js\n"));
+        // Every statement is on its own line and indented by its nesting.
+        let indentation = |statement: &str| {
+            let line = printed
+                .lines()
+                .find(|line| line.trim() == statement)
+                .unwrap();
+            line.len() - line.trim_start().len()
+        };
+        assert!(indentation("let x = 1;") > 0);
+        assert!(indentation("x++;") > indentation("let x = 1;"));
+    }
+
+    #[test]
+    fn keeps_code_that_cannot_be_parsed() {
+        let cm = Arc::new(SwcSourceMap::new(FilePathMapping::empty()));
+        let fm = cm.new_source_file(FileName::Anon.into(), "let = ;".to_string());
+        assert!(pretty_print_source_file(cm, &fm).unwrap().is_none());
+    }
+
+    #[test]
+    fn references_source_maps_next_to_the_file() {
+        assert_eq!(
+            source_map_reference("chunk 1.js"),
+            "\n\n//# sourceMappingURL=chunk%201.js.map"
+        );
+    }
+
+    #[tokio::test]
+    async fn minify_references_the_source_map() {
+        crate::register();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async {
+            let path = VirtualFileSystem::new().root().join("chunk.js".into());
+            let mut code = CodeBuilder::default();
+            code += "const answer = 42;\nconsole.log(answer);\n";
+            let code = code.build().cell();
+
+            let minified = minify(path, code, KeepNames::Off).await?;
+            let minified = minified.source_code().to_str()?;
+            assert!(minified.ends_with("\n\n//# sourceMappingURL=chunk.js.map"));
+
+            // Chunks add their footer after the minified code, before the reference.
+            let minified = minify_code(path, code, KeepNames::Off).await?;
+            let minified = minified.source_code().to_str()?;
+            assert!(minified.contains("console.log("));
+            assert!(!minified.contains("sourceMappingURL"));
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
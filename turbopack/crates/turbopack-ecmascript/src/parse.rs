@@ -1,6 +1,13 @@
-use std::{future::Future, sync::Arc};
+use std::{
+    future::Future,
+    mem::take,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::{anyhow, Context, Result};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use swc_core::{
     base::SwcComments,
     common::{
@@ -21,13 +28,16 @@ use swc_core::{
     },
 };
 use tracing::Instrument;
-use turbo_tasks::{util::WrapFuture, RcStr, Value, ValueToString, Vc};
+use turbo_tasks::{
+    debug::ValueDebugFormat, trace::TraceRawVcs, util::WrapFuture, RcStr, Value, ValueToString, Vc,
+};
 use turbo_tasks_fs::{FileContent, FileSystemPath};
 use turbo_tasks_hash::hash_xxh3_hash64;
 use turbopack_core::{
     asset::{Asset, AssetContent},
     error::PrettyPrintError,
     issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    module_metadata::ModuleMetadata,
     source::Source,
     source_map::{GenerateSourceMap, OptionSourceMap, SourceMap},
     SOURCE_MAP_PREFIX,
@@ -57,6 +67,12 @@ pub enum ParseResult {
         globals: Arc<Globals>,
         #[turbo_tasks(debug_ignore, trace_ignore)]
         source_map: Arc<swc_core::common::SourceMap>,
+        /// Metadata that the transforms added.
+        metadata: ModuleMetadata,
+        /// How long parsing and transforming the module took, for
+        /// [ModuleProcessingIssue].
+        #[turbo_tasks(debug_ignore, trace_ignore)]
+        timings: ProcessingTimings,
     },
     Unparseable {
         messages: Option<Vec<RcStr>>,
@@ -275,6 +291,7 @@ async fn parse_file_content(
     let parser_handler = Handler::with_emitter(true, false, emitter.clone());
     let globals = Arc::new(Globals::new());
     let globals_ref = &globals;
+    let mut timings = ProcessingTimings::new(string.len());
 
     let mut result = WrapFuture::new(
         async {
@@ -324,7 +341,9 @@ async fn parse_file_content(
 
                 let mut parser = Parser::new_from(lexer);
                 let span = tracing::trace_span!("swc_parse").entered();
+                let start = Instant::now();
                 let program_result = parser.parse_program();
+                timings.record("parse", start.elapsed());
                 drop(span);
 
                 let mut has_errors = vec![];
@@ -364,6 +383,7 @@ async fn parse_file_content(
                     | EcmascriptModuleAssetType::TypescriptDeclaration
             );
             let span = tracing::trace_span!("swc_resolver").entered();
+            let start = Instant::now();
 
             parsed_program.visit_mut_with(&mut resolver(
                 unresolved_mark,
@@ -385,8 +405,10 @@ async fn parse_file_content(
             });
 
             parsed_program.mutate(swc_core::ecma::lints::rules::lint_to_fold(rules));
+            timings.record("resolve and lint", start.elapsed());
             drop(span);
 
+            let metadata = Mutex::new(ModuleMetadata::default());
             let transform_context = TransformContext {
                 comments: &comments,
                 source_map: &source_map,
@@ -396,13 +418,18 @@ async fn parse_file_content(
                 file_name_str: fs_path.file_name(),
                 file_name_hash: file_path_hash,
                 file_path: fs_path_vc,
+                metadata: &metadata,
             };
             let span = tracing::trace_span!("transforms");
             async {
                 for transform in transforms.iter() {
+                    let name = transform.name();
+                    let start = Instant::now();
                     transform
                         .apply(&mut parsed_program, &transform_context)
+                        .instrument(tracing::trace_span!("transform", name))
                         .await?;
+                    timings.record(format!("{name} transform"), start.elapsed());
                 }
                 anyhow::Ok(())
             }
@@ -445,6 +472,8 @@ async fn parse_file_content(
                 // borrowed
                 globals: Arc::new(Globals::new()),
                 source_map,
+                metadata: metadata.into_inner(),
+                timings: take(&mut timings),
             })
         },
         |f, cx| {
@@ -464,6 +493,133 @@ async fn parse_file_content(
     Ok(result.cell())
 }
 
+/// Modules that take longer than this to be processed are reported.
+const SLOW_MODULE_THRESHOLD: Duration = Duration::from_secs(1);
+/// Modules with source code larger than this (in bytes) are reported.
+const LARGE_MODULE_THRESHOLD: usize = 2 * 1024 * 1024;
+
+/// The size of a module and the time each step of processing it took. The
+/// steps are measured within their tracing spans.
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, ValueDebugFormat,
+)]
+pub struct ProcessingTimings {
+    /// The size of the source code in bytes.
+    pub size: usize,
+    /// The time spent in each step, in order.
+    pub steps: Vec<(RcStr, Duration)>,
+}
+
+impl ProcessingTimings {
+    pub fn new(size: usize) -> Self {
+        ProcessingTimings {
+            size,
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, step: impl Into<RcStr>, duration: Duration) {
+        self.steps.push((step.into(), duration));
+    }
+
+    pub fn total(&self) -> Duration {
+        self.steps.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    fn is_slow(&self) -> bool {
+        self.total() >= SLOW_MODULE_THRESHOLD
+    }
+
+    fn is_large(&self) -> bool {
+        self.size >= LARGE_MODULE_THRESHOLD
+    }
+
+    /// The step that took the longest.
+    fn slowest_step(&self) -> Option<&(RcStr, Duration)> {
+        self.steps.iter().max_by_key(|(_, duration)| *duration)
+    }
+}
+
+/// A hint about a single module that is disproportionately slow to process or
+/// very large, which usually slows down every rebuild that includes it.
+///
+/// As the timings vary between runs, this is only reported in development, see
+/// [crate::EcmascriptOptions::report_slow_modules].
+#[turbo_tasks::value(shared)]
+pub struct ModuleProcessingIssue {
+    pub source: Vc<Box<dyn Source>>,
+    pub timings: ProcessingTimings,
+}
+
+impl ModuleProcessingIssue {
+    /// Emits an issue when the module exceeds the time or size thresholds.
+    pub fn report(source: Vc<Box<dyn Source>>, timings: ProcessingTimings) {
+        if !timings.is_slow() && !timings.is_large() {
+            return;
+        }
+        ModuleProcessingIssue { source, timings }.cell().emit();
+    }
+}
+
+fn processing_title(timings: &ProcessingTimings) -> String {
+    if timings.is_slow() {
+        format!("Processing this module took {:.1?}", timings.total())
+    } else {
+        format!("This module is very large ({} KiB)", timings.size / 1024)
+    }
+}
+
+fn processing_description(timings: &ProcessingTimings) -> Vec<String> {
+    let mut lines = vec![format!("Source code size: {} KiB", timings.size / 1024)];
+    if let Some((step, duration)) = timings.slowest_step() {
+        lines.push(format!("Most time was spent in {step} ({duration:.1?}):"));
+    }
+    lines.extend(
+        timings
+            .steps
+            .iter()
+            .map(|(step, duration)| format!("  {step}: {duration:.1?}")),
+    );
+    lines.push(
+        "Large or generated files slow down every rebuild that includes them. Consider splitting \
+         them up or loading them at runtime instead."
+            .to_string(),
+    );
+    lines
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for ModuleProcessingIssue {
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.source.ident().path()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(processing_title(&self.timings).into()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        let lines = processing_description(&self.timings)
+            .into_iter()
+            .map(|line| StyledString::Text(line.into()))
+            .collect();
+        Vc::cell(Some(StyledString::Stack(lines).cell()))
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Hint.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::ProcessModule.cell()
+    }
+}
+
 #[turbo_tasks::value]
 struct ReadSourceIssue {
     source: Vc<Box<dyn Source>>,
@@ -507,3 +663,55 @@ impl Issue for ReadSourceIssue {
         IssueStage::Load.cell()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{processing_description, processing_title, ProcessingTimings};
+
+    fn timings(size: usize, steps: &[(&str, u64)]) -> ProcessingTimings {
+        let mut timings = ProcessingTimings::new(size);
+        for (step, millis) in steps {
+            timings.record(*step, Duration::from_millis(*millis));
+        }
+        timings
+    }
+
+    #[test]
+    fn reports_slow_or_large_modules() {
+        let small_and_fast = timings(1024, &[("parse", 300), ("analyze", 300)]);
+        assert!(!small_and_fast.is_slow() && !small_and_fast.is_large());
+
+        // The steps are summed up, none of them is slow on its own.
+        let slow = timings(1024, &[("parse", 600), ("analyze", 600)]);
+        assert!(slow.is_slow());
+        assert_eq!(processing_title(&slow), "Processing this module took 1.2s");
+
+        let large = timings(8 * 1024 * 1024, &[("parse", 10)]);
+        assert!(large.is_large() && !large.is_slow());
+        assert_eq!(
+            processing_title(&large),
+            "This module is very large (8192 KiB)"
+        );
+    }
+
+    #[test]
+    fn describes_the_slowest_step() {
+        let slow = timings(
+            2048,
+            &[("parse", 200), ("react transform", 900), ("analyze", 100)],
+        );
+        let description = processing_description(&slow);
+        assert_eq!(
+            &description[..5],
+            [
+                "Source code size: 2 KiB",
+                "Most time was spent in react transform (900.0ms):",
+                "  parse: 200.0ms",
+                "  react transform: 900.0ms",
+                "  analyze: 100.0ms",
+            ]
+        );
+    }
+}
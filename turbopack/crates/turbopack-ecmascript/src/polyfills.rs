@@ -0,0 +1,478 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use swc_core::{
+    common::Mark,
+    ecma::{
+        ast::{CallExpr, Callee, Expr, Ident, MemberExpr, MemberProp},
+        preset_env::Versions,
+        visit::{Visit, VisitWith},
+    },
+};
+use turbo_tasks::{FxIndexSet, RcStr, ResolvedVc, TryJoinIterExt, Value, Vc};
+use turbo_tasks_fs::{File, FileSystemPath};
+use turbopack_core::{
+    asset::AssetContent,
+    context::AssetContext,
+    environment::{Environment, MinVersions},
+    module::{Module, Modules},
+    reference_type::{EntryReferenceSubType, ReferenceType},
+    virtual_source::VirtualSource,
+};
+
+use crate::{
+    duplicate_packages::chunk_group_referenced_modules, parse::ParseResult, EcmascriptParsable,
+};
+
+/// A built-in API that core-js can polyfill.
+struct PolyfillableApi {
+    /// The name of the API as it is reported, e.g. `Object.hasOwn`.
+    name: &'static str,
+    usage: ApiUsage,
+    /// The core-js modules that polyfill the API, without the `.js` extension.
+    core_js_modules: &'static [&'static str],
+    support: MinVersions,
+}
+
+/// How code uses an API.
+enum ApiUsage {
+    /// A property of a global, e.g. `Object.hasOwn`.
+    Static(&'static str, &'static str),
+    /// A global, e.g. `structuredClone`.
+    Global(&'static str),
+    /// A method, e.g. `.at()`. The type of the value it is called on is not
+    /// known, so each call of a method with the name is counted as a usage.
+    Method(&'static str),
+}
+
+const POLYFILLABLE_APIS: &[PolyfillableApi] = &[
+    PolyfillableApi {
+        name: "Object.fromEntries",
+        usage: ApiUsage::Static("Object", "fromEntries"),
+        core_js_modules: &["es.object.from-entries"],
+        support: MinVersions {
+            chrome: Some((73, 0)),
+            edge: Some((79, 0)),
+            firefox: Some((63, 0)),
+            safari: Some((12, 1)),
+            ios: Some((12, 2)),
+            samsung: Some((11, 0)),
+            opera: Some((60, 0)),
+            node: Some((12, 0)),
+        },
+    },
+    PolyfillableApi {
+        name: "Object.hasOwn",
+        usage: ApiUsage::Static("Object", "hasOwn"),
+        core_js_modules: &["es.object.has-own"],
+        support: MinVersions {
+            chrome: Some((93, 0)),
+            edge: Some((93, 0)),
+            firefox: Some((92, 0)),
+            safari: Some((15, 4)),
+            ios: Some((15, 4)),
+            samsung: Some((17, 0)),
+            opera: Some((79, 0)),
+            node: Some((16, 9)),
+        },
+    },
+    PolyfillableApi {
+        name: "Promise.allSettled",
+        usage: ApiUsage::Static("Promise", "allSettled"),
+        core_js_modules: &["es.promise.all-settled"],
+        support: MinVersions {
+            chrome: Some((76, 0)),
+            edge: Some((79, 0)),
+            firefox: Some((71, 0)),
+            safari: Some((13, 0)),
+            ios: Some((13, 0)),
+            samsung: Some((12, 0)),
+            opera: Some((63, 0)),
+            node: Some((12, 9)),
+        },
+    },
+    PolyfillableApi {
+        name: "Promise.any",
+        usage: ApiUsage::Static("Promise", "any"),
+        core_js_modules: &["es.aggregate-error", "es.promise.any"],
+        support: MinVersions {
+            chrome: Some((85, 0)),
+            edge: Some((85, 0)),
+            firefox: Some((79, 0)),
+            safari: Some((14, 0)),
+            ios: Some((14, 0)),
+            samsung: Some((14, 0)),
+            opera: Some((71, 0)),
+            node: Some((15, 0)),
+        },
+    },
+    PolyfillableApi {
+        name: "structuredClone",
+        usage: ApiUsage::Global("structuredClone"),
+        core_js_modules: &["web.structured-clone"],
+        support: MinVersions {
+            chrome: Some((98, 0)),
+            edge: Some((98, 0)),
+            firefox: Some((94, 0)),
+            safari: Some((15, 4)),
+            ios: Some((15, 4)),
+            samsung: Some((18, 0)),
+            opera: Some((84, 0)),
+            node: Some((17, 0)),
+        },
+    },
+    PolyfillableApi {
+        name: "Array.prototype.at",
+        usage: ApiUsage::Method("at"),
+        core_js_modules: &["es.array.at", "es.string.at-alternative"],
+        support: MinVersions {
+            chrome: Some((92, 0)),
+            edge: Some((92, 0)),
+            firefox: Some((90, 0)),
+            safari: Some((15, 4)),
+            ios: Some((15, 4)),
+            samsung: Some((16, 0)),
+            opera: Some((78, 0)),
+            node: Some((16, 6)),
+        },
+    },
+    PolyfillableApi {
+        name: "Array.prototype.findLast",
+        usage: ApiUsage::Method("findLast"),
+        core_js_modules: &["es.array.find-last"],
+        support: MinVersions {
+            chrome: Some((97, 0)),
+            edge: Some((97, 0)),
+            firefox: Some((104, 0)),
+            safari: Some((15, 4)),
+            ios: Some((15, 4)),
+            samsung: Some((18, 0)),
+            opera: Some((83, 0)),
+            node: Some((18, 0)),
+        },
+    },
+    PolyfillableApi {
+        name: "Array.prototype.findLastIndex",
+        usage: ApiUsage::Method("findLastIndex"),
+        core_js_modules: &["es.array.find-last-index"],
+        support: MinVersions {
+            chrome: Some((97, 0)),
+            edge: Some((97, 0)),
+            firefox: Some((104, 0)),
+            safari: Some((15, 4)),
+            ios: Some((15, 4)),
+            samsung: Some((18, 0)),
+            opera: Some((83, 0)),
+            node: Some((18, 0)),
+        },
+    },
+    PolyfillableApi {
+        name: "Array.prototype.flat",
+        usage: ApiUsage::Method("flat"),
+        core_js_modules: &["es.array.flat", "es.array.unscopables.flat"],
+        support: MinVersions {
+            chrome: Some((69, 0)),
+            edge: Some((79, 0)),
+            firefox: Some((62, 0)),
+            safari: Some((12, 0)),
+            ios: Some((12, 0)),
+            samsung: Some((10, 0)),
+            opera: Some((56, 0)),
+            node: Some((11, 0)),
+        },
+    },
+    PolyfillableApi {
+        name: "Array.prototype.flatMap",
+        usage: ApiUsage::Method("flatMap"),
+        core_js_modules: &["es.array.flat-map", "es.array.unscopables.flat-map"],
+        support: MinVersions {
+            chrome: Some((69, 0)),
+            edge: Some((79, 0)),
+            firefox: Some((62, 0)),
+            safari: Some((12, 0)),
+            ios: Some((12, 0)),
+            samsung: Some((10, 0)),
+            opera: Some((56, 0)),
+            node: Some((11, 0)),
+        },
+    },
+    PolyfillableApi {
+        name: "String.prototype.replaceAll",
+        usage: ApiUsage::Method("replaceAll"),
+        core_js_modules: &["es.string.replace-all"],
+        support: MinVersions {
+            chrome: Some((85, 0)),
+            edge: Some((85, 0)),
+            firefox: Some((77, 0)),
+            safari: Some((13, 1)),
+            ios: Some((13, 4)),
+            samsung: Some((14, 0)),
+            opera: Some((71, 0)),
+            node: Some((15, 0)),
+        },
+    },
+];
+
+/// The names of polyfillable APIs, e.g. `Object.hasOwn`.
+#[turbo_tasks::value(transparent)]
+pub struct PolyfillableApis(Vec<RcStr>);
+
+/// The polyfillable APIs that the code of `module` uses.
+#[turbo_tasks::function]
+async fn used_polyfillable_apis(module: Vc<Box<dyn Module>>) -> Result<Vc<PolyfillableApis>> {
+    let Some(parsable) = Vc::try_resolve_sidecast::<Box<dyn EcmascriptParsable>>(module).await?
+    else {
+        return Ok(Vc::cell(vec![]));
+    };
+    let ParseResult::Ok {
+        program,
+        eval_context,
+        ..
+    } = &*parsable.failsafe_parse().await?
+    else {
+        return Ok(Vc::cell(vec![]));
+    };
+    let mut used = FxIndexSet::default();
+    program.visit_with(&mut UsedApisVisitor {
+        unresolved_mark: eval_context.unresolved_mark,
+        used: &mut used,
+    });
+    Ok(Vc::cell(used.into_iter().map(RcStr::from).collect()))
+}
+
+struct UsedApisVisitor<'a> {
+    unresolved_mark: Mark,
+    used: &'a mut FxIndexSet<&'static str>,
+}
+
+impl UsedApisVisitor<'_> {
+    fn add(&mut self, matches: impl Fn(&ApiUsage) -> bool) {
+        self.used.extend(
+            POLYFILLABLE_APIS
+                .iter()
+                .filter(|api| matches(&api.usage))
+                .map(|api| api.name),
+        );
+    }
+}
+
+impl Visit for UsedApisVisitor<'_> {
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        if let (Expr::Ident(obj), MemberProp::Ident(prop)) = (&*member.obj, &member.prop) {
+            // Locals that shadow the global are not the API.
+            if obj.ctxt.outer() == self.unresolved_mark {
+                self.add(|usage| {
+                    matches!(usage, ApiUsage::Static(global, name) if &*obj.sym == *global && &*prop.sym == *name)
+                });
+            }
+        }
+        member.visit_children_with(self);
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if let Expr::Member(MemberExpr {
+                prop: MemberProp::Ident(prop),
+                ..
+            }) = &**callee
+            {
+                self.add(|usage| matches!(usage, ApiUsage::Method(name) if &*prop.sym == *name));
+            }
+        }
+        call.visit_children_with(self);
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if ident.ctxt.outer() == self.unresolved_mark {
+            self.add(|usage| matches!(usage, ApiUsage::Global(name) if &*ident.sym == *name));
+        }
+    }
+}
+
+/// The APIs that the modules in the chunk group of `entries` use, but that
+/// some runtimes of `environment` don't support, in a stable order.
+///
+/// Modules that are loaded asynchronously are placed in other chunk groups and
+/// are not visited. Nothing is visited when all runtimes support all APIs.
+#[turbo_tasks::function]
+pub async fn required_polyfills(
+    entries: Vc<Modules>,
+    environment: Vc<Environment>,
+) -> Result<Vc<PolyfillableApis>> {
+    let versions = environment.runtime_versions().await?;
+    if POLYFILLABLE_APIS
+        .iter()
+        .all(|api| api.support.is_supported_by(&versions))
+    {
+        return Ok(Vc::cell(vec![]));
+    }
+    let used = entries
+        .await?
+        .iter()
+        .map(|&entry| async move { chunk_group_used_apis(*entry).await })
+        .try_join()
+        .await?;
+    let used = used
+        .iter()
+        .flat_map(|apis| apis.iter().map(|api| &**api))
+        .collect::<FxIndexSet<_>>();
+    Ok(Vc::cell(unsupported_used_apis(&versions, &used)))
+}
+
+/// The polyfillable APIs that the modules in the chunk group of `entry` use.
+///
+/// This is computed per entry, so the modules of entries that are shared
+/// between chunk groups, e.g. runtime entries, are only visited once.
+#[turbo_tasks::function]
+async fn chunk_group_used_apis(entry: Vc<Box<dyn Module>>) -> Result<Vc<PolyfillableApis>> {
+    let entry = entry.to_resolved().await?;
+    let mut visited = FxIndexSet::<ResolvedVc<Box<dyn Module>>>::default();
+    visited.insert(entry);
+    let mut queue = VecDeque::from([entry]);
+    let mut used = FxIndexSet::default();
+    while let Some(module) = queue.pop_front() {
+        used.extend(used_polyfillable_apis(*module).await?.iter().cloned());
+        for &child in chunk_group_referenced_modules(*module).await?.iter() {
+            if visited.insert(child) {
+                queue.push_back(child);
+            }
+        }
+    }
+    Ok(Vc::cell(used.into_iter().collect()))
+}
+
+/// The `used` APIs that some runtimes of `versions` don't support, in the
+/// order of [POLYFILLABLE_APIS].
+fn unsupported_used_apis(versions: &Versions, used: &FxIndexSet<&str>) -> Vec<RcStr> {
+    POLYFILLABLE_APIS
+        .iter()
+        .filter(|api| used.contains(api.name) && !api.support.is_supported_by(versions))
+        .map(|api| api.name.into())
+        .collect()
+}
+
+/// A module that imports the core-js polyfills of the `apis`. It needs to be
+/// evaluated at the entry of the chunk group, before any other module.
+///
+/// core-js is resolved from `path`, which is usually next to the entry.
+#[turbo_tasks::function]
+pub async fn polyfills_entry_module(
+    asset_context: Vc<Box<dyn AssetContext>>,
+    path: Vc<FileSystemPath>,
+    apis: Vc<PolyfillableApis>,
+) -> Result<Vc<Box<dyn Module>>> {
+    let apis = apis.await?;
+    let mut code = String::new();
+    for api in POLYFILLABLE_APIS
+        .iter()
+        .filter(|api| apis.iter().any(|name| name == api.name))
+    {
+        for module in api.core_js_modules {
+            code.push_str(&format!("import \"core-js/modules/{module}.js\";\n"));
+        }
+    }
+    let source = VirtualSource::new(path, AssetContent::file(File::from(code).into()));
+    asset_context
+        .process(
+            Vc::upcast(source),
+            Value::new(ReferenceType::Entry(EntryReferenceSubType::Runtime)),
+        )
+        .module()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::{
+        common::{FileName, Mark},
+        ecma::{
+            ast::EsVersion,
+            parser::parse_file_as_program,
+            preset_env::{Version, Versions},
+            transforms::base::resolver,
+            visit::{VisitMutWith, VisitWith},
+        },
+        testing::run_test,
+    };
+    use turbo_tasks::FxIndexSet;
+
+    use super::{unsupported_used_apis, UsedApisVisitor};
+
+    fn used_apis(code: &str) -> Vec<&'static str> {
+        run_test(false, |cm, _handler| {
+            let fm = cm.new_source_file(FileName::Anon.into(), code.into());
+            let mut program = parse_file_as_program(
+                &fm,
+                Default::default(),
+                EsVersion::latest(),
+                None,
+                &mut vec![],
+            )
+            .unwrap();
+            let unresolved_mark = Mark::new();
+            program.visit_mut_with(&mut resolver(unresolved_mark, Mark::new(), false));
+            let mut used = FxIndexSet::default();
+            program.visit_with(&mut UsedApisVisitor {
+                unresolved_mark,
+                used: &mut used,
+            });
+            Ok(used.into_iter().collect())
+        })
+        .unwrap()
+    }
+
+    fn version(major: u32, minor: u32) -> Option<Version> {
+        Some(Version {
+            major,
+            minor,
+            patch: 0,
+        })
+    }
+
+    #[test]
+    fn finds_used_apis() {
+        assert_eq!(
+            used_apis(
+                "Object.hasOwn(a, 'b'); const c = structuredClone(a); [1, [2]].flat().at(-1);"
+            ),
+            [
+                "Object.hasOwn",
+                "structuredClone",
+                "Array.prototype.at",
+                "Array.prototype.flat"
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_shadowed_globals() {
+        assert!(used_apis(
+            "const Object = { hasOwn() {} }; function f(structuredClone) { Object.hasOwn(); \
+             structuredClone(); }"
+        )
+        .is_empty());
+        // Other properties of the global are not the API.
+        assert!(used_apis("Object.keys(a); Promise.all([]);").is_empty());
+    }
+
+    #[test]
+    fn requires_apis_that_are_used_and_unsupported() {
+        let used =
+            FxIndexSet::from_iter(["structuredClone", "Object.hasOwn", "Object.fromEntries"]);
+        // Safari 15.0 supports Object.fromEntries, but not the others.
+        let versions = Versions {
+            chrome: version(100, 0),
+            safari: version(15, 0),
+            ..Default::default()
+        };
+        assert_eq!(
+            unsupported_used_apis(&versions, &used),
+            ["Object.hasOwn", "structuredClone"]
+        );
+        let versions = Versions {
+            chrome: version(120, 0),
+            ..Default::default()
+        };
+        assert!(unsupported_used_apis(&versions, &used).is_empty());
+    }
+}
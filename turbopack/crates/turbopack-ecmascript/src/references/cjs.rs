@@ -6,14 +6,17 @@ use swc_core::{
 };
 use turbo_tasks::{RcStr, Value, ValueToString, Vc};
 use turbopack_core::{
-    chunk::{ChunkableModuleReference, ChunkingContext},
+    chunk::{ChunkableModuleReference, ChunkingContext, ChunkingType, ChunkingTypeOption},
     issue::IssueSource,
     reference::ModuleReference,
     resolve::{origin::ResolveOrigin, parse::Request, ModuleResolveResult},
 };
 use turbopack_resolve::ecmascript::cjs_resolve;
 
-use super::pattern_mapping::{PatternMapping, ResolveType::ChunkItem};
+use super::{
+    pattern_mapping::{PatternMapping, ResolveType::ChunkItem},
+    util::{request_to_string, throw_module_not_found_expr},
+};
 use crate::{
     code_gen::{CodeGenerateable, CodeGeneration},
     create_visitor,
@@ -81,6 +84,10 @@ pub struct CjsRequireAssetReference {
     pub path: Vc<AstPath>,
     pub issue_source: Vc<IssueSource>,
     pub in_try: bool,
+    /// An optional dependency, which is required in a `try` block, that is
+    /// excluded from the bundle. The require throws a "Cannot find module"
+    /// error at runtime, like when the dependency isn't installed.
+    pub weak: bool,
 }
 
 #[turbo_tasks::value_impl]
@@ -92,6 +99,7 @@ impl CjsRequireAssetReference {
         path: Vc<AstPath>,
         issue_source: Vc<IssueSource>,
         in_try: bool,
+        weak: bool,
     ) -> Vc<Self> {
         Self::cell(CjsRequireAssetReference {
             origin,
@@ -99,6 +107,7 @@ impl CjsRequireAssetReference {
             path,
             issue_source,
             in_try,
+            weak,
         })
     }
 }
@@ -127,7 +136,16 @@ impl ValueToString for CjsRequireAssetReference {
 }
 
 #[turbo_tasks::value_impl]
-impl ChunkableModuleReference for CjsRequireAssetReference {}
+impl ChunkableModuleReference for CjsRequireAssetReference {
+    #[turbo_tasks::function]
+    fn chunking_type(&self) -> Vc<ChunkingTypeOption> {
+        if self.weak {
+            Vc::cell(None)
+        } else {
+            Vc::cell(Some(ChunkingType::default()))
+        }
+    }
+}
 
 #[turbo_tasks::value_impl]
 impl CodeGenerateable for CjsRequireAssetReference {
@@ -136,6 +154,15 @@ impl CodeGenerateable for CjsRequireAssetReference {
         &self,
         chunking_context: Vc<Box<dyn ChunkingContext>>,
     ) -> Result<Vc<CodeGeneration>> {
+        if self.weak {
+            let request = request_to_string(self.request).await?;
+            let path = &self.path.await?;
+            let visitor = create_visitor!(path, visit_mut_expr(expr: &mut Expr) {
+                *expr = throw_module_not_found_expr(&request);
+            });
+            return Ok(CodeGeneration::visitors(vec![visitor]));
+        }
+
         let pm = PatternMapping::resolve_request(
             self.request,
             self.origin,
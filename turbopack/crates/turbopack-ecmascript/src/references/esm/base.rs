@@ -30,7 +30,7 @@ use turbopack_resolve::ecmascript::esm_resolve;
 use super::export::{all_known_export_names, is_export_missing};
 use crate::{
     analyzer::imports::ImportAnnotations,
-    chunk::EcmascriptChunkPlaceable,
+    chunk::{EcmascriptChunkPlaceable, EcmascriptValueModule, PropertyPaths},
     code_gen::{CodeGenerateable, CodeGeneration},
     magic_identifier,
     references::util::{request_to_string, throw_module_not_found_expr},
@@ -42,6 +42,9 @@ use crate::{
 pub enum ReferencedAsset {
     Some(ResolvedVc<Box<dyn EcmascriptChunkPlaceable>>),
     External(RcStr, ExternalType),
+    /// The request was ignored, e.g. by a `false` entry of the `browser` field
+    /// of a package.json, and is imported as an empty module.
+    Ignored,
     None,
     Unresolvable,
 }
@@ -53,6 +56,7 @@ impl ReferencedAsset {
             ReferencedAsset::External(request, ty) => Some(magic_identifier::mangle(&format!(
                 "{ty} external {request}"
             ))),
+            ReferencedAsset::Ignored => Some(magic_identifier::mangle("ignored module")),
             ReferencedAsset::None | ReferencedAsset::Unresolvable => None,
         })
     }
@@ -90,7 +94,9 @@ impl ReferencedAsset {
                         return Ok(ReferencedAsset::Some(placeable).cell());
                     }
                 }
-                // TODO ignore should probably be handled differently
+                ModuleResolveResultItem::Ignore => {
+                    return Ok(ReferencedAsset::Ignored.cell());
+                }
                 _ => {}
             }
         }
@@ -107,6 +113,9 @@ pub struct EsmAssetReference {
     pub issue_source: Vc<IssueSource>,
     pub export_name: Option<ResolvedVc<ModulePart>>,
     pub import_externals: bool,
+    /// The properties that the importing module reads from the imported value,
+    /// when they are statically known.
+    pub read_properties: Option<ResolvedVc<PropertyPaths>>,
 }
 
 impl EsmAssetReference {
@@ -129,6 +138,7 @@ impl EsmAssetReference {
         annotations: Value<ImportAnnotations>,
         export_name: Option<ResolvedVc<ModulePart>>,
         import_externals: bool,
+        read_properties: Option<ResolvedVc<PropertyPaths>>,
     ) -> Vc<Self> {
         Self::cell(EsmAssetReference {
             origin,
@@ -137,6 +147,7 @@ impl EsmAssetReference {
             annotations: annotations.into_value(),
             export_name,
             import_externals,
+            read_properties,
         })
     }
 
@@ -206,6 +217,25 @@ impl ModuleReference for EsmAssetReference {
             }
         }
 
+        if let Some(read_properties) = self.read_properties {
+            // Value modules, like JSON modules, only need to export the properties
+            // that are read by any of their importers.
+            let mut result = result.await?.clone_value();
+            for item in result.primary.values_mut() {
+                if let ModuleResolveResultItem::Module(module) = item {
+                    if let Some(value_module) =
+                        ResolvedVc::try_sidecast::<Box<dyn EcmascriptValueModule>>(*module).await?
+                    {
+                        *module = value_module
+                            .with_read_properties(self.origin, self.request, *read_properties)
+                            .to_resolved()
+                            .await?;
+                    }
+                }
+            }
+            return Ok(result.cell());
+        }
+
         Ok(result)
     }
 }
@@ -356,6 +386,17 @@ impl CodeGenerateable for EsmAssetReference {
                             ),
                         ))
                     }
+                    ReferencedAsset::Ignored => Some((
+                        ident.clone().into(),
+                        var_decl_with_span(
+                            quote!(
+                                "var $name = {};" as Stmt,
+                                name =
+                                    Ident::new(ident.clone().into(), DUMMY_SP, Default::default()),
+                            ),
+                            span,
+                        ),
+                    )),
                     #[allow(unreachable_patterns)]
                     ReferencedAsset::External(request, ty) => {
                         bail!(
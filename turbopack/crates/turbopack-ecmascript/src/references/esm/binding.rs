@@ -8,7 +8,7 @@ use swc_core::{
             PropName, SeqExpr, SimpleAssignTarget, Str,
         },
         visit::{
-            fields::{CalleeField, PropField},
+            fields::{CalleeField, OptCallField, ParenExprField, PropField, TaggedTplField},
             AstParentKind,
         },
     },
@@ -90,12 +90,7 @@ impl EsmBinding {
                 // Any other expression can be replaced with the import accessor.
                 Some(swc_core::ecma::visit::AstParentKind::Expr(_)) => {
                     ast_path.pop();
-                    let in_call = matches!(
-                        ast_path.last(),
-                        Some(swc_core::ecma::visit::AstParentKind::Callee(
-                            CalleeField::Expr
-                        ))
-                    );
+                    let in_call = is_callee(&ast_path);
 
                     visitors.push(
                     create_visitor!(exact ast_path, visit_mut_expr(expr: &mut Expr) {
@@ -165,6 +160,29 @@ impl CodeGenerateable for EsmBindings {
     }
 }
 
+/// Returns true when the expression at `ast_path` is called, which includes
+/// optional calls, tagged templates and calls of parenthesized expressions.
+/// Replacing a binding with a member expression there would make the module
+/// namespace object the `this` of the call.
+fn is_callee(ast_path: &[AstParentKind]) -> bool {
+    let mut parents = ast_path.iter().rev();
+    loop {
+        match parents.next() {
+            Some(AstParentKind::ParenExpr(ParenExprField::Expr)) => {
+                if !matches!(parents.next(), Some(AstParentKind::Expr(_))) {
+                    return false;
+                }
+            }
+            Some(
+                AstParentKind::Callee(CalleeField::Expr)
+                | AstParentKind::OptCall(OptCallField::Callee)
+                | AstParentKind::TaggedTpl(TaggedTplField::Tag),
+            ) => return true,
+            _ => return false,
+        }
+    }
+}
+
 fn make_expr(imported_module: &str, export: Option<&str>, span: Span, in_call: bool) -> Expr {
     if let Some(export) = export {
         let mut expr = Expr::Member(MemberExpr {
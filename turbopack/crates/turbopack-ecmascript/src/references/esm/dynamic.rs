@@ -8,15 +8,17 @@ use turbo_tasks::{RcStr, Value, ValueToString, Vc};
 use turbopack_core::{
     chunk::{ChunkableModuleReference, ChunkingContext, ChunkingType, ChunkingTypeOption},
     environment::ChunkLoading,
-    issue::IssueSource,
+    ident::AssetIdent,
+    issue::{analyze::AnalyzeIssue, IssueExt, IssueSeverity, IssueSource, StyledString},
     reference::ModuleReference,
-    reference_type::EcmaScriptModulesReferenceSubType,
+    reference_type::{EcmaScriptModulesReferenceSubType, ImportWithType},
     resolve::{origin::ResolveOrigin, parse::Request, ModuleResolveResult},
 };
 use turbopack_resolve::ecmascript::esm_resolve;
 
 use super::super::pattern_mapping::{PatternMapping, ResolveType};
 use crate::{
+    analyzer::imports::ImportAnnotations,
     code_gen::{CodeGenerateable, CodeGeneration},
     create_visitor,
     references::AstPath,
@@ -29,6 +31,11 @@ pub struct EsmAsyncAssetReference {
     pub request: Vc<Request>,
     pub path: Vc<AstPath>,
     pub issue_source: Vc<IssueSource>,
+    /// The attributes of the `with` option of the `import()` expression.
+    pub annotations: ImportAnnotations,
+    /// The `import()` expression has options that the bundler doesn't apply,
+    /// which are only passed on when the module is imported natively.
+    pub native_only_options: bool,
     pub in_try: bool,
     pub import_externals: bool,
 }
@@ -41,6 +48,8 @@ impl EsmAsyncAssetReference {
         request: Vc<Request>,
         path: Vc<AstPath>,
         issue_source: Vc<IssueSource>,
+        annotations: Value<ImportAnnotations>,
+        native_only_options: bool,
         in_try: bool,
         import_externals: bool,
     ) -> Vc<Self> {
@@ -49,12 +58,26 @@ impl EsmAsyncAssetReference {
             request,
             path,
             issue_source,
+            annotations: annotations.into_value(),
+            native_only_options,
             in_try,
             import_externals,
         })
     }
 }
 
+impl EsmAsyncAssetReference {
+    /// Type attributes select the module type like they do for static
+    /// imports.
+    fn reference_sub_type(&self) -> EcmaScriptModulesReferenceSubType {
+        if matches!(self.annotations.module_type(), Some("json")) {
+            EcmaScriptModulesReferenceSubType::ImportWithType(ImportWithType::Json)
+        } else {
+            EcmaScriptModulesReferenceSubType::DynamicImport
+        }
+    }
+}
+
 #[turbo_tasks::value_impl]
 impl ModuleReference for EsmAsyncAssetReference {
     #[turbo_tasks::function]
@@ -62,7 +85,7 @@ impl ModuleReference for EsmAsyncAssetReference {
         esm_resolve(
             self.origin,
             self.request,
-            Value::new(EcmaScriptModulesReferenceSubType::DynamicImport),
+            Value::new(self.reference_sub_type()),
             self.in_try,
             Some(self.issue_source),
         )
@@ -73,9 +96,12 @@ impl ModuleReference for EsmAsyncAssetReference {
 impl ValueToString for EsmAsyncAssetReference {
     #[turbo_tasks::function]
     async fn to_string(&self) -> Result<Vc<RcStr>> {
-        Ok(Vc::cell(
-            format!("dynamic import {}", self.request.to_string().await?,).into(),
-        ))
+        let request = self.request.to_string().await?;
+        Ok(Vc::cell(if self.annotations.is_empty() {
+            format!("dynamic import {request}").into()
+        } else {
+            format!("dynamic import {request} with {}", self.annotations).into()
+        }))
     }
 }
 
@@ -101,7 +127,7 @@ impl CodeGenerateable for EsmAsyncAssetReference {
             esm_resolve(
                 self.origin,
                 self.request,
-                Value::new(EcmaScriptModulesReferenceSubType::DynamicImport),
+                Value::new(self.reference_sub_type()),
                 self.in_try,
                 Some(self.issue_source),
             ),
@@ -116,15 +142,40 @@ impl CodeGenerateable for EsmAsyncAssetReference {
         )
         .await?;
 
-        let path = &self.path.await?;
         let import_externals = self.import_externals;
+        if self.native_only_options && !pm.passes_import_options(import_externals) {
+            AnalyzeIssue {
+                code: None,
+                message: StyledString::Text(
+                    "The module is bundled, so only a `type: \"json\"` import attribute is \
+                     applied. The other options are only passed on when the module is imported \
+                     natively, e.g. when it's an ESM external."
+                        .into(),
+                )
+                .cell(),
+                source_ident: AssetIdent::from_path(self.origin.origin_path()),
+                severity: IssueSeverity::Warning.into(),
+                source: Some(self.issue_source),
+                title: Vc::cell("import() options are ignored".into()),
+            }
+            .cell()
+            .emit();
+        }
+
+        let path = &self.path.await?;
 
         let visitor = create_visitor!(path, visit_mut_expr(expr: &mut Expr) {
             let old_expr = expr.take();
             let message = if let Expr::Call(CallExpr { args, ..}) = old_expr {
-                match args.into_iter().next() {
+                let mut args = args.into_iter();
+                match args.next() {
                     Some(ExprOrSpread { spread: None, expr: key_expr }) => {
-                        *expr = pm.create_import(*key_expr, import_externals);
+                        // The options are only passed on when the module is imported natively.
+                        let options = args.next().and_then(|options| match options {
+                            ExprOrSpread { spread: None, expr } => Some(*expr),
+                            _ => None,
+                        });
+                        *expr = pm.create_import(*key_expr, options, import_externals);
                         return;
                     }
                     // These are SWC bugs: https://github.com/swc-project/swc/issues/5394
@@ -6,10 +6,12 @@ pub mod constant_value;
 pub mod dynamic_expression;
 pub mod esm;
 pub mod external_module;
+pub mod module_federation;
 pub mod node;
 pub mod pattern_mapping;
 pub mod raw;
 pub mod require_context;
+pub mod styled_jsx;
 pub mod type_issue;
 pub mod typescript;
 pub mod unreachable;
@@ -21,6 +23,7 @@ use std::{
     collections::{BTreeMap, HashMap},
     mem::take,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 use anyhow::{bail, Result};
@@ -31,6 +34,7 @@ use num_traits::Zero;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use regex::Regex;
+use rustc_hash::{FxHashMap, FxHashSet};
 use sourcemap::decode_data_url;
 use swc_core::{
     atoms::JsWord,
@@ -62,6 +66,7 @@ use turbopack_core::{
     error::PrettyPrintError,
     issue::{analyze::AnalyzeIssue, IssueExt, IssueSeverity, IssueSource, StyledString},
     module::Module,
+    module_metadata::ModuleMetadata,
     reference::{ModuleReference, ModuleReferences, SourceMapReference},
     reference_type::{CommonJsReferenceSubType, ReferenceType},
     resolve::{
@@ -94,6 +99,10 @@ use self::{
     },
     node::DirAssetReference,
     raw::FileSourceReference,
+    styled_jsx::{
+        match_static_css_prop, match_static_styled_jsx_style, CssPropReference,
+        StyledJsxStyleReference,
+    },
     typescript::{TsConfigReference, TsReferencePathAssetReference, TsReferenceTypeAssetReference},
 };
 use super::{
@@ -122,13 +131,14 @@ use crate::{
         graph::{ConditionalKind, EffectArg, EvalContext, VarGraph},
         imports::{ImportAnnotations, ImportAttributes, ImportedSymbol, Reexport},
         parse_require_context,
+        property_accesses::{find_property_accesses, PropertyAccesses},
         top_level_await::has_top_level_await,
-        ConstantNumber, ConstantString, JsValueUrlKind, RequireContextValue,
+        ConstantNumber, ConstantString, ImportMap, JsValueUrlKind, RequireContextValue,
     },
-    chunk::EcmascriptExports,
+    chunk::{EcmascriptExports, PropertyPaths},
     code_gen::{CodeGen, CodeGenerateable, CodeGenerateableWithAsyncModuleInfo, CodeGenerateables},
     magic_identifier,
-    parse::parse,
+    parse::{parse, ModuleProcessingIssue},
     references::{
         async_module::{AsyncModule, OptionAsyncModule},
         cjs::{CjsRequireAssetReference, CjsRequireCacheAccess, CjsRequireResolveAssetReference},
@@ -157,6 +167,7 @@ pub struct AnalyzeEcmascriptModuleResult {
     /// `true` when the analysis was successful.
     pub successful: bool,
     pub source_map: Vc<OptionSourceMap>,
+    pub metadata: Vc<ModuleMetadata>,
 }
 
 /// A temporary analysis result builder to pass around, to be turned into an
@@ -172,6 +183,7 @@ pub struct AnalyzeEcmascriptModuleResultBuilder {
     successful: bool,
     source_map: Option<ResolvedVc<OptionSourceMap>>,
     bindings: Vec<EsmBinding>,
+    metadata: ModuleMetadata,
 }
 
 impl AnalyzeEcmascriptModuleResultBuilder {
@@ -187,6 +199,7 @@ impl AnalyzeEcmascriptModuleResultBuilder {
             successful: false,
             source_map: None,
             bindings: Vec::new(),
+            metadata: ModuleMetadata::default(),
         }
     }
 
@@ -274,6 +287,12 @@ impl AnalyzeEcmascriptModuleResultBuilder {
         self.successful = successful;
     }
 
+    /// The metadata of the module, to which the analysis can add information
+    /// in addition to what the transforms added.
+    pub fn metadata_mut(&mut self) -> &mut ModuleMetadata {
+        &mut self.metadata
+    }
+
     /// Builds the final analysis result. Resolves internal Vcs for performance
     /// in using them.
     pub async fn build(
@@ -339,6 +358,7 @@ impl AnalyzeEcmascriptModuleResultBuilder {
                 async_module: self.async_module,
                 successful: self.successful,
                 source_map: *source_map,
+                metadata: self.metadata.cell(),
             },
         ))
     }
@@ -365,6 +385,7 @@ struct AnalysisState<'a> {
     tree_shaking_mode: Option<TreeShakingMode>,
     import_externals: bool,
     ignore_dynamic_requests: bool,
+    exclude_optional_requires: &'a [RcStr],
     url_rewrite_behavior: Option<UrlRewriteBehavior>,
 }
 
@@ -391,6 +412,16 @@ impl<'a> AnalysisState<'a> {
     }
 }
 
+/// Runs a synchronous step of the analysis in a tracing span, and adds the time
+/// it took to `elapsed`.
+fn timed_step<R>(name: &'static str, elapsed: &mut Duration, f: impl FnOnce() -> R) -> R {
+    let _span = tracing::trace_span!("analyse step", name).entered();
+    let start = Instant::now();
+    let result = f();
+    *elapsed += start.elapsed();
+    result
+}
+
 fn set_handler_and_globals<F, R>(handler: &Handler, globals: &Arc<Globals>, f: F) -> R
 where
     F: FnOnce() -> R,
@@ -482,11 +513,17 @@ pub(crate) async fn analyse_ecmascript_module_internal(
         eval_context,
         comments,
         source_map,
-        ..
+        metadata,
+        timings,
     } = &*parsed
     else {
         return analysis.build(false).await;
     };
+    // Only the synchronous steps of the analysis are measured, as the time spent
+    // waiting for other tasks, e.g. resolving, isn't caused by this module.
+    let mut analyze_time = Duration::ZERO;
+
+    analysis.metadata_mut().extend(metadata);
 
     let compile_time_info = compile_time_info_for_module_type(
         raw_module.compile_time_info,
@@ -587,11 +624,18 @@ pub(crate) async fn analyse_ecmascript_module_internal(
         Box::new(IssueEmitter::new(source, source_map.clone(), None)),
     );
 
-    let mut var_graph =
-        set_handler_and_globals(&handler, globals, || create_graph(program, eval_context));
+    let mut var_graph = timed_step("create graph", &mut analyze_time, || {
+        set_handler_and_globals(&handler, globals, || create_graph(program, eval_context))
+    });
 
     let mut evaluation_references = Vec::new();
 
+    let mut read_properties = if options.tree_shaking_mode.is_some() {
+        read_properties_of_default_imports(program, &eval_context.imports)
+    } else {
+        FxHashMap::default()
+    };
+
     for (i, r) in eval_context.imports.references().enumerate() {
         let r = EsmAssetReference::new(
             origin,
@@ -630,6 +674,7 @@ pub(crate) async fn analyse_ecmascript_module_internal(
                 }
             },
             import_externals,
+            read_properties.remove(&i),
         );
 
         import_references.push(r);
@@ -648,48 +693,54 @@ pub(crate) async fn analyse_ecmascript_module_internal(
     }
 
     let (webpack_runtime, webpack_entry, webpack_chunks, esm_exports, esm_star_exports) =
-        set_handler_and_globals(&handler, globals, || {
-            // TODO migrate to effects
-            let mut visitor =
-                ModuleReferencesVisitor::new(eval_context, &import_references, &mut analysis);
-
-            for (i, reexport) in eval_context.imports.reexports() {
-                let import_ref = import_references[i];
-                match reexport {
-                    Reexport::Star => {
-                        visitor.esm_star_exports.push(Vc::upcast(import_ref));
-                    }
-                    Reexport::Namespace { exported: n } => {
-                        visitor.esm_exports.insert(
-                            n.as_str().into(),
-                            EsmExport::ImportedNamespace(Vc::upcast(import_ref)),
-                        );
-                    }
-                    Reexport::Named {
-                        imported: i,
-                        exported: e,
-                    } => {
-                        visitor.esm_exports.insert(
-                            e.as_str().into(),
-                            EsmExport::ImportedBinding(
-                                Vc::upcast(import_ref),
-                                i.to_string().into(),
-                                false,
-                            ),
-                        );
+        timed_step("visit references", &mut analyze_time, || {
+            set_handler_and_globals(&handler, globals, || {
+                // TODO migrate to effects
+                let mut visitor = ModuleReferencesVisitor::new(
+                    eval_context,
+                    &import_references,
+                    &mut analysis,
+                    options.extract_styled_jsx.then_some(origin),
+                );
+
+                for (i, reexport) in eval_context.imports.reexports() {
+                    let import_ref = import_references[i];
+                    match reexport {
+                        Reexport::Star => {
+                            visitor.esm_star_exports.push(Vc::upcast(import_ref));
+                        }
+                        Reexport::Namespace { exported: n } => {
+                            visitor.esm_exports.insert(
+                                n.as_str().into(),
+                                EsmExport::ImportedNamespace(Vc::upcast(import_ref)),
+                            );
+                        }
+                        Reexport::Named {
+                            imported: i,
+                            exported: e,
+                        } => {
+                            visitor.esm_exports.insert(
+                                e.as_str().into(),
+                                EsmExport::ImportedBinding(
+                                    Vc::upcast(import_ref),
+                                    i.to_string().into(),
+                                    false,
+                                ),
+                            );
+                        }
                     }
                 }
-            }
 
-            program.visit_with_ast_path(&mut visitor, &mut Default::default());
+                program.visit_with_ast_path(&mut visitor, &mut Default::default());
 
-            (
-                visitor.webpack_runtime,
-                visitor.webpack_entry,
-                visitor.webpack_chunks,
-                visitor.esm_exports,
-                visitor.esm_star_exports,
-            )
+                (
+                    visitor.webpack_runtime,
+                    visitor.webpack_entry,
+                    visitor.webpack_chunks,
+                    visitor.esm_exports,
+                    visitor.esm_star_exports,
+                )
+            })
         });
 
     for export in esm_exports.values() {
@@ -815,8 +866,9 @@ pub(crate) async fn analyse_ecmascript_module_internal(
         }
     };
 
-    let top_level_await_span =
-        set_handler_and_globals(&handler, globals, || has_top_level_await(program));
+    let top_level_await_span = timed_step("find top level await", &mut analyze_time, || {
+        set_handler_and_globals(&handler, globals, || has_top_level_await(program))
+    });
     let has_top_level_await = top_level_await_span.is_some();
 
     if eval_context.is_esm(specified_type) {
@@ -844,6 +896,11 @@ pub(crate) async fn analyse_ecmascript_module_internal(
 
     let effects = take(&mut var_graph.effects);
 
+    let exclude_optional_requires = match options.exclude_optional_requires {
+        Some(packages) => packages.await?.clone_value(),
+        None => Vec::new(),
+    };
+
     let mut analysis_state = AnalysisState {
         handler: &handler,
         source,
@@ -855,6 +912,7 @@ pub(crate) async fn analyse_ecmascript_module_internal(
         tree_shaking_mode: options.tree_shaking_mode,
         import_externals: options.import_externals,
         ignore_dynamic_requests: options.ignore_dynamic_requests,
+        exclude_optional_requires: &exclude_optional_requires,
         url_rewrite_behavior: options.url_rewrite_behavior,
     };
 
@@ -1215,6 +1273,12 @@ pub(crate) async fn analyse_ecmascript_module_internal(
 
     analysis.set_successful(true);
 
+    if options.report_slow_modules && part.is_none() {
+        let mut timings = timings.clone();
+        timings.record("analyze", analyze_time);
+        ModuleProcessingIssue::report(source, timings);
+    }
+
     analysis
         .build(matches!(
             options.tree_shaking_mode,
@@ -1223,6 +1287,42 @@ pub(crate) async fn analyse_ecmascript_module_internal(
         .await
 }
 
+/// Finds the properties that are read from the default imports of each
+/// reference, when they are statically known. References that are reexported
+/// are skipped, as other modules might read any property.
+fn read_properties_of_default_imports(
+    program: &Program,
+    imports: &ImportMap,
+) -> FxHashMap<usize, ResolvedVc<PropertyPaths>> {
+    let reexported: FxHashSet<usize> = imports.reexports().map(|(i, _)| i).collect();
+    let mut bindings = FxHashMap::<usize, Vec<Id>>::default();
+    for (id, i) in imports.default_imports() {
+        if !reexported.contains(&i) {
+            bindings.entry(i).or_default().push(id.clone());
+        }
+    }
+    if bindings.is_empty() {
+        return FxHashMap::default();
+    }
+
+    let accesses = find_property_accesses(program, bindings.values().flatten().cloned().collect());
+    bindings
+        .into_iter()
+        .filter_map(|(i, ids)| {
+            let mut paths = FxIndexSet::default();
+            for id in ids {
+                match &accesses[&id] {
+                    PropertyAccesses::Paths(id_paths) => paths.extend(id_paths.iter().cloned()),
+                    PropertyAccesses::All => return None,
+                }
+            }
+            let mut paths: Vec<_> = paths.into_iter().collect();
+            paths.sort();
+            Some((i, ResolvedVc::cell(paths)))
+        })
+        .collect()
+}
+
 #[turbo_tasks::function]
 async fn compile_time_info_for_module_type(
     compile_time_info: Vc<CompileTimeInfo>,
@@ -1289,6 +1389,7 @@ async fn handle_call<G: Fn(Vec<Effect>) + Send + Sync>(
         source,
         compile_time_info,
         ignore_dynamic_requests,
+        exclude_optional_requires,
         url_rewrite_behavior,
         ..
     } = state;
@@ -1433,7 +1534,7 @@ async fn handle_call<G: Fn(Vec<Effect>) + Send + Sync>(
         }
         JsValue::WellKnownFunction(WellKnownFunctionKind::Import) => {
             let args = linked_args(args).await?;
-            if args.len() == 1 {
+            if args.len() == 1 || args.len() == 2 {
                 let pat = js_value_to_pattern(&args[0]);
                 if !pat.has_constant_parts() {
                     let (args, hints) = explain_args(&args);
@@ -1451,11 +1552,20 @@ async fn handle_call<G: Fn(Vec<Effect>) + Send + Sync>(
                         return Ok(());
                     }
                 }
+                let annotations = args
+                    .get(1)
+                    .map(ImportAnnotations::parse_dynamic_import_options)
+                    .unwrap_or_default();
+                let native_only_options = args.get(1).is_some_and(|options| {
+                    !ImportAnnotations::bundler_applies_dynamic_import_options(options)
+                });
                 analysis.add_reference(EsmAsyncAssetReference::new(
                     origin,
                     Request::parse(Value::new(pat)),
                     Vc::cell(ast_path.to_vec()),
                     issue_source(source, span),
+                    Value::new(annotations),
+                    native_only_options,
                     in_try,
                     state.import_externals,
                 ));
@@ -1488,12 +1598,14 @@ async fn handle_call<G: Fn(Vec<Effect>) + Send + Sync>(
                         return Ok(());
                     }
                 }
+                let weak = in_try && requires_package(&pat, exclude_optional_requires);
                 analysis.add_reference(CjsRequireAssetReference::new(
                     origin,
                     Request::parse(Value::new(pat)),
                     Vc::cell(ast_path.to_vec()),
                     issue_source(source, span),
                     in_try,
+                    weak,
                 ));
                 return Ok(());
             }
@@ -2213,6 +2325,7 @@ async fn handle_free_var_reference(
                     None => None,
                 },
                 state.import_externals,
+                None,
             )
             .resolve()
             .await?;
@@ -2231,6 +2344,18 @@ fn issue_source(source: Vc<Box<dyn Source>>, span: Span) -> Vc<IssueSource> {
     IssueSource::from_swc_offsets(source, span.lo.to_usize(), span.hi.to_usize())
 }
 
+/// Whether `pattern` requires one of `packages` or a file inside of it.
+fn requires_package(pattern: &Pattern, packages: &[RcStr]) -> bool {
+    let Pattern::Constant(request) = pattern else {
+        return false;
+    };
+    packages.iter().any(|package| {
+        request
+            .strip_prefix(package.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
+}
+
 fn analyze_amd_define(
     source: Vc<Box<dyn Source>>,
     analysis: &mut AnalyzeEcmascriptModuleResultBuilder,
@@ -2721,6 +2846,9 @@ struct ModuleReferencesVisitor<'a> {
     webpack_runtime: Option<(RcStr, Span)>,
     webpack_entry: bool,
     webpack_chunks: Vec<Lit>,
+    /// The origin of the CSS modules that static styled-jsx styles are moved
+    /// to. `None` when styled-jsx styles are not extracted.
+    styled_jsx_origin: Option<Vc<Box<dyn ResolveOrigin>>>,
 }
 
 impl<'a> ModuleReferencesVisitor<'a> {
@@ -2728,6 +2856,7 @@ impl<'a> ModuleReferencesVisitor<'a> {
         eval_context: &'a EvalContext,
         import_references: &'a [Vc<EsmAssetReference>],
         analysis: &'a mut AnalyzeEcmascriptModuleResultBuilder,
+        styled_jsx_origin: Option<Vc<Box<dyn ResolveOrigin>>>,
     ) -> Self {
         Self {
             eval_context,
@@ -2739,6 +2868,7 @@ impl<'a> ModuleReferencesVisitor<'a> {
             webpack_runtime: None,
             webpack_entry: false,
             webpack_chunks: Vec::new(),
+            styled_jsx_origin,
         }
     }
 }
@@ -3044,6 +3174,29 @@ impl VisitAstPath for ModuleReferencesVisitor<'_> {
                 }
             }
         }
+        if let Some(origin) = self.styled_jsx_origin {
+            if let Some(style) = match_static_styled_jsx_style(call, &self.eval_context.imports) {
+                let reference = StyledJsxStyleReference::new(
+                    origin,
+                    style.id,
+                    style.css,
+                    Vc::cell(as_parent_path(ast_path)),
+                );
+                self.analysis.add_reference(reference);
+                self.analysis.add_code_gen(reference);
+                return;
+            }
+            if let Some(css_prop) = match_static_css_prop(call, &self.eval_context.imports) {
+                let reference = CssPropReference::new(
+                    origin,
+                    css_prop.class_name,
+                    css_prop.css,
+                    Vc::cell(as_parent_path(ast_path)),
+                );
+                self.analysis.add_reference(reference);
+                self.analysis.add_code_gen(reference);
+            }
+        }
         call.visit_children_with_ast_path(self, ast_path);
     }
 }
@@ -0,0 +1,886 @@
+use std::io::Write;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{
+    trace::TraceRawVcs, FxIndexMap, RcStr, ReadRef, ResolvedVc, TryJoinIterExt, Value,
+    ValueToString, Vc,
+};
+use turbo_tasks_fs::{glob::Glob, rope::RopeBuilder, FileContent, FileSystem, VirtualFileSystem};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    chunk::{
+        AsyncModuleInfo, ChunkItem, ChunkItemExt, ChunkType, ChunkableModule,
+        ChunkableModuleReference, ChunkingContext, ModuleId,
+    },
+    ident::AssetIdent,
+    module::Module,
+    reference::{ModuleReference, ModuleReferences},
+    reference_type::ReferenceType,
+    resolve::{
+        origin::{ResolveOrigin, ResolveOriginExt},
+        parse::Request,
+        ModuleResolveResult,
+    },
+};
+use turbopack_resolve::ecmascript::apply_cjs_specific_options;
+
+use crate::{
+    chunk::{
+        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkPlaceable,
+        EcmascriptChunkType, EcmascriptExports,
+    },
+    references::async_module::{AsyncModule, OptionAsyncModule},
+    utils::StringifyJs,
+    EcmascriptModuleContent, EcmascriptOptions,
+};
+
+/// Configuration for consuming Module Federation remotes.
+///
+/// Requests whose first segment matches the name of a remote (e.g.
+/// `app1/Button` for the remote `app1`) are resolved to the exposed module
+/// (`./Button`) of that remote container. Imports of shared packages are
+/// resolved to the best matching version in the share scope.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug, Default)]
+pub struct ModuleFederationOptions {
+    /// The name of the share scope passed to `container.init()`. Defaults to
+    /// `"default"`.
+    pub share_scope: Option<RcStr>,
+    pub remotes: FxIndexMap<RcStr, ModuleFederationRemote>,
+    /// Packages that are provided to the share scope so that remotes can reuse
+    /// them instead of loading their own copy, and that are consumed from the
+    /// share scope when a remote or the host provides a compatible version.
+    pub shared: FxIndexMap<RcStr, ModuleFederationShared>,
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleFederationOptions {
+    #[turbo_tasks::function]
+    pub fn share_scope(&self) -> Vc<RcStr> {
+        Vc::cell(self.share_scope.clone().unwrap_or_else(|| "default".into()))
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionModuleFederationOptions(Option<Vc<ModuleFederationOptions>>);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs)]
+pub struct ModuleFederationRemote {
+    /// The url of the `remoteEntry` script.
+    pub entry: RcStr,
+    pub remote_type: ModuleFederationRemoteType,
+}
+
+impl ModuleFederationRemote {
+    /// Parses the `remoteEntry` of a remote. `name@url` loads a script that
+    /// assigns the container to the global `name`, a plain url is imported as
+    /// an ES module.
+    pub fn parse(entry: &str) -> Self {
+        match entry.split_once('@') {
+            Some((global, url)) if !global.contains(':') && !global.contains('/') => {
+                ModuleFederationRemote {
+                    entry: url.into(),
+                    remote_type: ModuleFederationRemoteType::Script {
+                        global: global.into(),
+                    },
+                }
+            }
+            _ => ModuleFederationRemote {
+                entry: entry.into(),
+                remote_type: ModuleFederationRemoteType::Module,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs)]
+pub enum ModuleFederationRemoteType {
+    /// A classic script that assigns the container to the given global
+    /// variable.
+    Script { global: RcStr },
+    /// An ES module that exports `init` and `get`.
+    Module,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs)]
+pub struct ModuleFederationShared {
+    /// The version that is registered in the share scope. Defaults to `"0"`
+    /// when the version of the local package is unknown.
+    pub version: Option<RcStr>,
+    /// The versions that may be consumed instead of the local package, as an
+    /// exact version, a `^` or `~` range or `*`. Defaults to the `^` range of
+    /// `version`, or `*` when the version is unknown.
+    pub required_version: Option<RcStr>,
+}
+
+impl ModuleFederationShared {
+    fn version(&self) -> RcStr {
+        self.version.clone().unwrap_or_else(|| "0".into())
+    }
+
+    fn required_version(&self) -> RcStr {
+        match (&self.required_version, &self.version) {
+            (Some(required_version), _) => required_version.clone(),
+            (None, Some(version)) => format!("^{version}").into(),
+            (None, None) => "*".into(),
+        }
+    }
+}
+
+#[turbo_tasks::function]
+fn layer() -> Vc<RcStr> {
+    Vc::cell("module federation".into())
+}
+
+/// Returns the remote and the exposed module that is requested by `request`,
+/// if the request refers to one of the configured remotes.
+pub async fn module_federation_remote_request(
+    request: Vc<Request>,
+    options: Vc<ModuleFederationOptions>,
+) -> Result<Option<(RcStr, RcStr)>> {
+    let Request::Module { module, path, .. } = &*request.await? else {
+        return Ok(None);
+    };
+    if !options.await?.remotes.contains_key(module) {
+        return Ok(None);
+    }
+    let Some(path) = path.as_string() else {
+        return Ok(None);
+    };
+    Ok(Some((module.clone(), exposed_module(path))))
+}
+
+/// Returns the shared package that is requested by `request`, if it's imported
+/// by its name.
+pub async fn module_federation_shared_request(
+    request: Vc<Request>,
+    options: Vc<ModuleFederationOptions>,
+) -> Result<Option<RcStr>> {
+    let Request::Module { module, path, .. } = &*request.await? else {
+        return Ok(None);
+    };
+    if path.as_string() != Some("") || !options.await?.shared.contains_key(module) {
+        return Ok(None);
+    }
+    Ok(Some(module.clone()))
+}
+
+/// Maps the path of a request within a remote (e.g. `/Button` of
+/// `app1/Button`) to the name of the module exposed by the container.
+fn exposed_module(path: &str) -> RcStr {
+    if path.is_empty() {
+        ".".into()
+    } else {
+        format!(".{path}").into()
+    }
+}
+
+/// A module that loads an exposed module from a Module Federation remote
+/// container at runtime.
+///
+/// The container is initialized with the share scope before the exposed
+/// module is requested. All packages configured as `shared` are registered in
+/// the share scope first, unless a version has already been provided by
+/// someone else.
+#[turbo_tasks::value]
+pub struct ModuleFederationRemoteModule {
+    origin: Vc<Box<dyn ResolveOrigin>>,
+    remote_name: RcStr,
+    exposed: RcStr,
+    options: Vc<ModuleFederationOptions>,
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleFederationRemoteModule {
+    #[turbo_tasks::function]
+    pub fn new(
+        origin: Vc<Box<dyn ResolveOrigin>>,
+        remote_name: RcStr,
+        exposed: RcStr,
+        options: Vc<ModuleFederationOptions>,
+    ) -> Vc<Self> {
+        Self::cell(ModuleFederationRemoteModule {
+            origin,
+            remote_name,
+            exposed,
+            options,
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn shared_references(&self) -> Result<Vc<SharedModuleReferences>> {
+        Ok(Vc::cell(
+            self.options
+                .await?
+                .shared
+                .iter()
+                .map(|(request, shared)| {
+                    ModuleFederationSharedReference::new(
+                        self.origin,
+                        request.clone(),
+                        shared.version(),
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    async fn content(
+        self: Vc<Self>,
+        chunking_context: Vc<Box<dyn ChunkingContext>>,
+    ) -> Result<Vc<EcmascriptModuleContent>> {
+        let this = self.await?;
+        let options = this.options.await?;
+        let Some(remote) = options.remotes.get(&this.remote_name) else {
+            anyhow::bail!("unknown module federation remote {}", this.remote_name);
+        };
+
+        let shared = self
+            .shared_references()
+            .await?
+            .iter()
+            .map(|&reference| async move {
+                let Some(id) = local_module_id(reference, chunking_context).await? else {
+                    return Ok(None);
+                };
+                let reference = reference.await?;
+                Ok(Some((
+                    reference.request.clone(),
+                    reference.version.clone(),
+                    id,
+                )))
+            })
+            .try_join()
+            .await?;
+
+        let mut code = RopeBuilder::default();
+
+        write_share_scope(&mut code, this.options).await?;
+        for (request, version, id) in shared.into_iter().flatten() {
+            write_provide_shared(&mut code, &request, &version, &id)?;
+        }
+        writeln!(code)?;
+
+        match &remote.remote_type {
+            ModuleFederationRemoteType::Script { global } => {
+                writeln!(
+                    code,
+                    r#"if (!globalThis[{global}]) {{
+    await new Promise((resolve, reject) => {{
+        const script = document.createElement("script");
+        script.src = {entry};
+        script.onload = resolve;
+        script.onerror = () => reject(new Error("Failed to load remote entry " + script.src));
+        document.head.appendChild(script);
+    }});
+}}
+const container = globalThis[{global}];"#,
+                    global = StringifyJs(global),
+                    entry = StringifyJs(&remote.entry),
+                )?;
+            }
+            ModuleFederationRemoteType::Module => {
+                writeln!(
+                    code,
+                    "const container = await import(/* webpackIgnore: true */ {});",
+                    StringifyJs(&remote.entry)
+                )?;
+            }
+        }
+
+        writeln!(
+            code,
+            r#"const initialized = globalThis.__turbopack_initialized_containers__ ??= new WeakMap();
+if (!initialized.has(container)) {{
+    initialized.set(container, Promise.resolve(container.init(shareScope)));
+}}
+await initialized.get(container);
+const factory = await container.get({});
+__turbopack_export_namespace__(factory());"#,
+            StringifyJs(&this.exposed)
+        )?;
+
+        Ok(EcmascriptModuleContent {
+            inner_code: code.build(),
+            source_map: None,
+            is_esm: true,
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Module for ModuleFederationRemoteModule {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        let fs = VirtualFileSystem::new_with_name("module-federation".into());
+
+        // The shared packages are resolved from the origin.
+        AssetIdent::from_path(fs.root())
+            .with_layer(layer())
+            .with_modifier(self.origin.origin_path().to_string())
+            .with_modifier(Vc::cell(self.remote_name.clone()))
+            .with_modifier(Vc::cell(self.exposed.clone()))
+    }
+
+    #[turbo_tasks::function]
+    async fn references(self: Vc<Self>) -> Result<Vc<ModuleReferences>> {
+        Ok(Vc::cell(
+            self.shared_references()
+                .await?
+                .iter()
+                .map(|reference| Vc::upcast(*reference))
+                .collect(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for ModuleFederationRemoteModule {
+    #[turbo_tasks::function]
+    fn content(self: Vc<Self>) -> Vc<AssetContent> {
+        // should be `NotFound` as this function gets called to detect source changes
+        AssetContent::file(FileContent::NotFound.cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModule for ModuleFederationRemoteModule {
+    #[turbo_tasks::function]
+    fn as_chunk_item(
+        self: Vc<Self>,
+        chunking_context: Vc<Box<dyn ChunkingContext>>,
+    ) -> Vc<Box<dyn ChunkItem>> {
+        Vc::upcast(
+            ModuleFederationRemoteChunkItem {
+                module: self,
+                chunking_context,
+            }
+            .cell(),
+        )
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkPlaceable for ModuleFederationRemoteModule {
+    #[turbo_tasks::function]
+    fn get_exports(&self) -> Vc<EcmascriptExports> {
+        EcmascriptExports::DynamicNamespace.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn get_async_module(&self) -> Vc<OptionAsyncModule> {
+        Vc::cell(Some(
+            AsyncModule {
+                has_top_level_await: true,
+                import_externals: false,
+            }
+            .cell(),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn is_marked_as_side_effect_free(
+        self: Vc<Self>,
+        _side_effect_free_packages: Vc<Glob>,
+    ) -> Vc<bool> {
+        Vc::cell(false)
+    }
+}
+
+#[turbo_tasks::value]
+pub struct ModuleFederationRemoteChunkItem {
+    module: Vc<ModuleFederationRemoteModule>,
+    chunking_context: Vc<Box<dyn ChunkingContext>>,
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkItem for ModuleFederationRemoteChunkItem {
+    #[turbo_tasks::function]
+    fn asset_ident(&self) -> Vc<AssetIdent> {
+        self.module.ident()
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> Vc<ModuleReferences> {
+        self.module.references()
+    }
+
+    #[turbo_tasks::function]
+    fn ty(self: Vc<Self>) -> Vc<Box<dyn ChunkType>> {
+        Vc::upcast(Vc::<EcmascriptChunkType>::default())
+    }
+
+    #[turbo_tasks::function]
+    fn module(&self) -> Vc<Box<dyn Module>> {
+        Vc::upcast(self.module)
+    }
+
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> Vc<Box<dyn ChunkingContext>> {
+        self.chunking_context
+    }
+
+    #[turbo_tasks::function]
+    fn is_self_async(self: Vc<Self>) -> Vc<bool> {
+        Vc::cell(true)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkItem for ModuleFederationRemoteChunkItem {
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> Vc<Box<dyn ChunkingContext>> {
+        self.chunking_context
+    }
+
+    #[turbo_tasks::function]
+    fn content(self: Vc<Self>) -> Vc<EcmascriptChunkItemContent> {
+        panic!("content() should not be called");
+    }
+
+    #[turbo_tasks::function]
+    fn content_with_async_module_info(
+        &self,
+        async_module_info: Option<Vc<AsyncModuleInfo>>,
+    ) -> Vc<EcmascriptChunkItemContent> {
+        let async_module_options = self
+            .module
+            .get_async_module()
+            .module_options(async_module_info);
+
+        EcmascriptChunkItemContent::new(
+            self.module.content(self.chunking_context),
+            self.chunking_context,
+            EcmascriptOptions::default().cell(),
+            async_module_options,
+        )
+    }
+}
+
+/// A module that loads a shared package from the share scope at runtime.
+///
+/// The local package is provided to the share scope first. The highest
+/// version in the share scope that matches the required version is loaded,
+/// which is the local package unless a remote or the host provides a newer
+/// compatible version.
+#[turbo_tasks::value]
+pub struct ModuleFederationConsumeSharedModule {
+    origin: Vc<Box<dyn ResolveOrigin>>,
+    request: RcStr,
+    options: Vc<ModuleFederationOptions>,
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleFederationConsumeSharedModule {
+    #[turbo_tasks::function]
+    pub fn new(
+        origin: Vc<Box<dyn ResolveOrigin>>,
+        request: RcStr,
+        options: Vc<ModuleFederationOptions>,
+    ) -> Vc<Self> {
+        Self::cell(ModuleFederationConsumeSharedModule {
+            origin,
+            request,
+            options,
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn shared_reference(&self) -> Result<Vc<ModuleFederationSharedReference>> {
+        let options = self.options.await?;
+        let Some(shared) = options.shared.get(&self.request) else {
+            anyhow::bail!("unknown module federation shared package {}", self.request);
+        };
+        Ok(ModuleFederationSharedReference::new(
+            self.origin,
+            self.request.clone(),
+            shared.version(),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    async fn content(
+        self: Vc<Self>,
+        chunking_context: Vc<Box<dyn ChunkingContext>>,
+    ) -> Result<Vc<EcmascriptModuleContent>> {
+        let this = self.await?;
+        let options = this.options.await?;
+        let Some(shared) = options.shared.get(&this.request) else {
+            anyhow::bail!("unknown module federation shared package {}", this.request);
+        };
+
+        let mut code = RopeBuilder::default();
+
+        write_share_scope(&mut code, this.options).await?;
+        let reference = self.shared_reference();
+        if let Some(id) = local_module_id(reference, chunking_context).await? {
+            write_provide_shared(&mut code, &this.request, &reference.await?.version, &id)?;
+        }
+        writeln!(code)?;
+
+        writeln!(
+            code,
+            r#"{SELECT_SHARED_VERSION}
+const versions = shareScope[{request}] ?? {{}};
+const version = selectSharedVersion(Object.keys(versions), {required_version});
+if (version === undefined) {{
+    throw new Error("No version of the shared package " + {request} + " matches " + {required_version});
+}}
+const factory = await versions[version].get();
+__turbopack_export_namespace__(factory());"#,
+            request = StringifyJs(&this.request),
+            required_version = StringifyJs(&shared.required_version()),
+        )?;
+
+        Ok(EcmascriptModuleContent {
+            inner_code: code.build(),
+            source_map: None,
+            is_esm: true,
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Module for ModuleFederationConsumeSharedModule {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        let fs = VirtualFileSystem::new_with_name("module-federation".into());
+
+        // The local package is resolved from the origin.
+        AssetIdent::from_path(fs.root())
+            .with_layer(layer())
+            .with_modifier(self.origin.origin_path().to_string())
+            .with_modifier(Vc::cell(format!("shared {}", self.request).into()))
+    }
+
+    #[turbo_tasks::function]
+    fn references(self: Vc<Self>) -> Vc<ModuleReferences> {
+        Vc::cell(vec![Vc::upcast(self.shared_reference())])
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for ModuleFederationConsumeSharedModule {
+    #[turbo_tasks::function]
+    fn content(self: Vc<Self>) -> Vc<AssetContent> {
+        // should be `NotFound` as this function gets called to detect source changes
+        AssetContent::file(FileContent::NotFound.cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModule for ModuleFederationConsumeSharedModule {
+    #[turbo_tasks::function]
+    fn as_chunk_item(
+        self: Vc<Self>,
+        chunking_context: Vc<Box<dyn ChunkingContext>>,
+    ) -> Vc<Box<dyn ChunkItem>> {
+        Vc::upcast(
+            ModuleFederationConsumeSharedChunkItem {
+                module: self,
+                chunking_context,
+            }
+            .cell(),
+        )
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkPlaceable for ModuleFederationConsumeSharedModule {
+    #[turbo_tasks::function]
+    fn get_exports(&self) -> Vc<EcmascriptExports> {
+        EcmascriptExports::DynamicNamespace.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn get_async_module(&self) -> Vc<OptionAsyncModule> {
+        Vc::cell(Some(
+            AsyncModule {
+                has_top_level_await: true,
+                import_externals: false,
+            }
+            .cell(),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn is_marked_as_side_effect_free(
+        self: Vc<Self>,
+        _side_effect_free_packages: Vc<Glob>,
+    ) -> Vc<bool> {
+        Vc::cell(false)
+    }
+}
+
+#[turbo_tasks::value]
+pub struct ModuleFederationConsumeSharedChunkItem {
+    module: Vc<ModuleFederationConsumeSharedModule>,
+    chunking_context: Vc<Box<dyn ChunkingContext>>,
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkItem for ModuleFederationConsumeSharedChunkItem {
+    #[turbo_tasks::function]
+    fn asset_ident(&self) -> Vc<AssetIdent> {
+        self.module.ident()
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> Vc<ModuleReferences> {
+        self.module.references()
+    }
+
+    #[turbo_tasks::function]
+    fn ty(self: Vc<Self>) -> Vc<Box<dyn ChunkType>> {
+        Vc::upcast(Vc::<EcmascriptChunkType>::default())
+    }
+
+    #[turbo_tasks::function]
+    fn module(&self) -> Vc<Box<dyn Module>> {
+        Vc::upcast(self.module)
+    }
+
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> Vc<Box<dyn ChunkingContext>> {
+        self.chunking_context
+    }
+
+    #[turbo_tasks::function]
+    fn is_self_async(self: Vc<Self>) -> Vc<bool> {
+        Vc::cell(true)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkItem for ModuleFederationConsumeSharedChunkItem {
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> Vc<Box<dyn ChunkingContext>> {
+        self.chunking_context
+    }
+
+    #[turbo_tasks::function]
+    fn content(self: Vc<Self>) -> Vc<EcmascriptChunkItemContent> {
+        panic!("content() should not be called");
+    }
+
+    #[turbo_tasks::function]
+    fn content_with_async_module_info(
+        &self,
+        async_module_info: Option<Vc<AsyncModuleInfo>>,
+    ) -> Vc<EcmascriptChunkItemContent> {
+        let async_module_options = self
+            .module
+            .get_async_module()
+            .module_options(async_module_info);
+
+        EcmascriptChunkItemContent::new(
+            self.module.content(self.chunking_context),
+            self.chunking_context,
+            EcmascriptOptions::default().cell(),
+            async_module_options,
+        )
+    }
+}
+
+/// Declares `shareScope`, the configured share scope.
+async fn write_share_scope(
+    code: &mut RopeBuilder,
+    options: Vc<ModuleFederationOptions>,
+) -> Result<()> {
+    writeln!(
+        code,
+        "const scopes = globalThis.__turbopack_share_scopes__ ??= {{}};"
+    )?;
+    writeln!(
+        code,
+        "const shareScope = scopes[{}] ??= {{}};",
+        StringifyJs(&*options.share_scope().await?)
+    )?;
+    Ok(())
+}
+
+/// Registers the local module `id` as `version` of the shared package
+/// `request`, unless someone else already provided that version.
+fn write_provide_shared(
+    code: &mut RopeBuilder,
+    request: &str,
+    version: &str,
+    id: &ModuleId,
+) -> Result<()> {
+    writeln!(
+        code,
+        "(shareScope[{request}] ??= {{}})[{version}] ??= {{ from: \"turbopack\", get: () => \
+         Promise.resolve(() => __turbopack_require__({id})) }};",
+        request = StringifyJs(request),
+        version = StringifyJs(version),
+        id = StringifyJs(id),
+    )?;
+    Ok(())
+}
+
+/// Selects the highest version in the share scope that matches a range, like
+/// the version negotiation of webpack's `ConsumeSharedPlugin`. Pre-release and
+/// build suffixes are ignored.
+const SELECT_SHARED_VERSION: &str = r#"function parseVersion(version) {
+    return version.split(/[-+]/)[0].split(".").map((part) => parseInt(part, 10) || 0);
+}
+function compareVersions(a, b) {
+    for (let i = 0; i < 3; i++) {
+        const difference = (a[i] ?? 0) - (b[i] ?? 0);
+        if (difference !== 0) return difference;
+    }
+    return 0;
+}
+function satisfies(version, range) {
+    if (range === "*") return true;
+    const operator = range[0] === "^" || range[0] === "~" ? range[0] : "";
+    const required = parseVersion(range.slice(operator.length));
+    const actual = parseVersion(version);
+    if (!operator) return compareVersions(actual, required) === 0;
+    if (compareVersions(actual, required) < 0) return false;
+    // `~` allows patch updates, `^` allows updates that keep the first
+    // non-zero part.
+    const fixedParts = operator === "~" ? 2 : required.findIndex((part) => part !== 0) + 1 || 1;
+    return required.slice(0, fixedParts).every((part, i) => (actual[i] ?? 0) === part);
+}
+function selectSharedVersion(versions, range) {
+    return versions
+        .filter((version) => satisfies(version, range))
+        .sort((a, b) => compareVersions(parseVersion(b), parseVersion(a)))[0];
+}"#;
+
+/// Returns the id of the local package of a shared reference, if it's
+/// installed.
+async fn local_module_id(
+    reference: Vc<ModuleFederationSharedReference>,
+    chunking_context: Vc<Box<dyn ChunkingContext>>,
+) -> Result<Option<ReadRef<ModuleId>>> {
+    let Some(module) = *reference.resolve_reference().first_module().await? else {
+        return Ok(None);
+    };
+    let Some(placeable) =
+        ResolvedVc::try_sidecast::<Box<dyn EcmascriptChunkPlaceable>>(module).await?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(placeable.as_chunk_item(chunking_context).id().await?))
+}
+
+#[turbo_tasks::value(transparent)]
+struct SharedModuleReferences(Vec<Vc<ModuleFederationSharedReference>>);
+
+/// A reference from a remote module or a consumed shared package to the local
+/// package that is provided to the share scope.
+#[turbo_tasks::value]
+#[derive(Hash, Debug)]
+pub struct ModuleFederationSharedReference {
+    origin: Vc<Box<dyn ResolveOrigin>>,
+    request: RcStr,
+    version: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleFederationSharedReference {
+    #[turbo_tasks::function]
+    pub fn new(origin: Vc<Box<dyn ResolveOrigin>>, request: RcStr, version: RcStr) -> Vc<Self> {
+        Self::cell(ModuleFederationSharedReference {
+            origin,
+            request,
+            version,
+        })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleReference for ModuleFederationSharedReference {
+    #[turbo_tasks::function]
+    async fn resolve_reference(&self) -> Result<Vc<ModuleResolveResult>> {
+        // Not an import or require, which would be resolved to the consumed
+        // package again.
+        let ty = Value::new(ReferenceType::Undefined);
+        let options = apply_cjs_specific_options(self.origin.resolve_options(ty.clone()));
+        Ok(self
+            .origin
+            .resolve_asset(Request::parse_string(self.request.clone()), options, ty))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for ModuleFederationSharedReference {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> Vc<RcStr> {
+        Vc::cell(format!("module federation shared {}@{}", self.request, self.version).into())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModuleReference for ModuleFederationSharedReference {}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        exposed_module, ModuleFederationRemote, ModuleFederationRemoteType, ModuleFederationShared,
+    };
+
+    #[test]
+    fn parse_remote() {
+        assert_eq!(
+            ModuleFederationRemote::parse("app1@https://example.com/remoteEntry.js"),
+            ModuleFederationRemote {
+                entry: "https://example.com/remoteEntry.js".into(),
+                remote_type: ModuleFederationRemoteType::Script {
+                    global: "app1".into()
+                },
+            }
+        );
+        assert_eq!(
+            ModuleFederationRemote::parse("https://example.com/remoteEntry.mjs"),
+            ModuleFederationRemote {
+                entry: "https://example.com/remoteEntry.mjs".into(),
+                remote_type: ModuleFederationRemoteType::Module,
+            }
+        );
+        // An `@` in the url is not the name of a global.
+        assert_eq!(
+            ModuleFederationRemote::parse("https://user@example.com/remoteEntry.mjs"),
+            ModuleFederationRemote {
+                entry: "https://user@example.com/remoteEntry.mjs".into(),
+                remote_type: ModuleFederationRemoteType::Module,
+            }
+        );
+        assert_eq!(
+            ModuleFederationRemote::parse("/remotes/@scope/remoteEntry.mjs"),
+            ModuleFederationRemote {
+                entry: "/remotes/@scope/remoteEntry.mjs".into(),
+                remote_type: ModuleFederationRemoteType::Module,
+            }
+        );
+    }
+
+    #[test]
+    fn required_version_of_shared_package() {
+        let shared =
+            |version: Option<&str>, required_version: Option<&str>| ModuleFederationShared {
+                version: version.map(Into::into),
+                required_version: required_version.map(Into::into),
+            };
+        assert_eq!(shared(Some("1.2.3"), None).required_version(), "^1.2.3");
+        assert_eq!(
+            shared(Some("1.2.3"), Some("~1.2.0")).required_version(),
+            "~1.2.0"
+        );
+        assert_eq!(shared(None, None).required_version(), "*");
+        assert_eq!(shared(None, None).version(), "0");
+    }
+
+    #[test]
+    fn exposed_module_of_request() {
+        assert_eq!(exposed_module(""), ".");
+        assert_eq!(exposed_module("/Button"), "./Button");
+        assert_eq!(exposed_module("/components/Button"), "./components/Button");
+    }
+}
@@ -135,7 +135,12 @@ impl SinglePatternMapping {
         }
     }
 
-    pub fn create_import(&self, key_expr: Cow<'_, Expr>, import_externals: bool) -> Expr {
+    pub fn create_import(
+        &self,
+        key_expr: Cow<'_, Expr>,
+        options: Option<Expr>,
+        import_externals: bool,
+    ) -> Expr {
         match self {
             Self::Invalid => {
                 let error = quote_expr!(
@@ -157,10 +162,13 @@ impl SinglePatternMapping {
                 if import_externals {
                     Expr::Call(CallExpr {
                         callee: Callee::Expr(quote_expr!("__turbopack_external_import__")),
-                        args: vec![ExprOrSpread {
-                            spread: None,
-                            expr: Box::new(key_expr.into_owned()),
-                        }],
+                        args: std::iter::once(key_expr.into_owned())
+                            .chain(options)
+                            .map(|expr| ExprOrSpread {
+                                spread: None,
+                                expr: Box::new(expr),
+                            })
+                            .collect(),
                         span: DUMMY_SP,
                         ..Default::default()
                     })
@@ -250,7 +258,7 @@ fn create_context_map(
                         id: Expr = v.create_id(Cow::Borrowed(key_expr)),
                         module: Expr = match import_mode {
                             ImportMode::Require => v.create_require(Cow::Borrowed(key_expr)),
-                            ImportMode::Import { import_externals } => v.create_import(Cow::Borrowed(key_expr), import_externals),
+                            ImportMode::Import { import_externals } => v.create_import(Cow::Borrowed(key_expr), None, import_externals),
                         },
                     ),
             })))
@@ -292,9 +300,31 @@ impl PatternMapping {
         }
     }
 
-    pub fn create_import(&self, key_expr: Expr, import_externals: bool) -> Expr {
+    /// Returns true when the options of an `import()` expression are passed
+    /// on, which is only the case when the module is imported natively.
+    pub fn passes_import_options(&self, import_externals: bool) -> bool {
+        import_externals
+            && matches!(
+                self,
+                PatternMapping::Single(SinglePatternMapping::External(
+                    _,
+                    ExternalType::EcmaScriptModule
+                ))
+            )
+    }
+
+    /// `options` are the options of the `import()` expression, which are
+    /// passed on when the module is imported natively.
+    pub fn create_import(
+        &self,
+        key_expr: Expr,
+        options: Option<Expr>,
+        import_externals: bool,
+    ) -> Expr {
         match self {
-            PatternMapping::Single(pm) => pm.create_import(Cow::Owned(key_expr), import_externals),
+            PatternMapping::Single(pm) => {
+                pm.create_import(Cow::Owned(key_expr), options, import_externals)
+            }
             PatternMapping::Map(map) => {
                 let map =
                     create_context_map(map, &key_expr, ImportMode::Import { import_externals });
@@ -0,0 +1,613 @@
+use anyhow::Result;
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{
+        CallExpr, Callee, Expr, ExprOrSpread, IdentName, KeyValueProp, Lit, MemberProp, ObjectLit,
+        Prop, PropName, PropOrSpread, Str,
+    },
+    quote,
+};
+use turbo_tasks::{RcStr, Value, ValueToString, Vc};
+use turbo_tasks_fs::{File, FileSystemPath};
+use turbo_tasks_hash::hash_xxh3_hash64;
+use turbopack_core::{
+    asset::AssetContent,
+    chunk::{ChunkableModuleReference, ChunkingContext},
+    context::AssetContext,
+    reference::ModuleReference,
+    reference_type::{EcmaScriptModulesReferenceSubType, ReferenceType},
+    resolve::{origin::ResolveOrigin, ModuleResolveResult},
+    virtual_source::VirtualSource,
+};
+
+use super::AstPath;
+use crate::{
+    analyzer::imports::ImportMap,
+    code_gen::{CodeGenerateable, CodeGeneration},
+    create_visitor,
+};
+
+/// The module that styled-jsx imports to inject styles at runtime.
+const STYLED_JSX_STYLE_MODULE: &str = "styled-jsx/style";
+
+/// The modules and exports of the functions that compiled JSX calls to create
+/// elements.
+const JSX_FACTORIES: &[(&str, &str)] = &[
+    ("react/jsx-runtime", "jsx"),
+    ("react/jsx-runtime", "jsxs"),
+    ("react/jsx-dev-runtime", "jsxDEV"),
+    ("react", "createElement"),
+];
+
+/// A style of styled-jsx that is fully known at compile time.
+pub(crate) struct StaticStyledJsxStyle {
+    /// The id of the scope, from which the scoped class names are derived.
+    pub id: RcStr,
+    /// The scoped CSS.
+    pub css: RcStr,
+}
+
+/// Matches the element that the styled-jsx transform creates for a `<style
+/// jsx>` block, after JSX has been compiled, e.g. `_jsx(_JSXStyle, { id:
+/// "1a2b3c", children: "p.jsx-1a2b3c{color:red}" })` or
+/// `React.createElement(_JSXStyle, { id: "1a2b3c" }, "...")`.
+///
+/// Returns `None` for styles with dynamic interpolations, which need to be
+/// injected at runtime.
+pub(crate) fn match_static_styled_jsx_style(
+    call: &CallExpr,
+    imports: &ImportMap,
+) -> Option<StaticStyledJsxStyle> {
+    if !matches!(call.callee, Callee::Expr(_)) {
+        return None;
+    }
+    let [component, props, rest @ ..] = &call.args[..] else {
+        return None;
+    };
+    let ExprOrSpread {
+        spread: None,
+        expr: component,
+    } = component
+    else {
+        return None;
+    };
+    let Expr::Ident(component) = &**component else {
+        return None;
+    };
+    let (reference, Some(export)) = imports.get_binding(&component.to_id())? else {
+        return None;
+    };
+    if &*export != "default"
+        || imports.references().nth(reference)?.module_path != STYLED_JSX_STYLE_MODULE
+    {
+        return None;
+    }
+
+    let ExprOrSpread { spread: None, expr } = props else {
+        return None;
+    };
+    let Expr::Object(props) = &**expr else {
+        return None;
+    };
+    let mut id = None;
+    let mut css = None;
+    for prop in &props.props {
+        let PropOrSpread::Prop(prop) = prop else {
+            return None;
+        };
+        let Prop::KeyValue(prop) = &**prop else {
+            return None;
+        };
+        match prop_name(&prop.key)? {
+            "id" => id = Some(static_string(&prop.value)?),
+            "children" => css = Some(static_string(&prop.value)?),
+            // e.g. `dynamic` for styles with interpolations
+            _ => return None,
+        }
+    }
+    if css.is_none() {
+        // The classic runtime passes children as additional arguments
+        if let [ExprOrSpread { spread: None, expr }, ..] = rest {
+            css = Some(static_string(expr)?);
+        }
+    }
+
+    Some(StaticStyledJsxStyle { id: id?, css: css? })
+}
+
+/// A `css` prop of a host element that is fully known at compile time.
+pub(crate) struct StaticCssProp {
+    /// The class name that replaces the `css` prop.
+    pub class_name: RcStr,
+    /// The CSS, scoped to the class name.
+    pub css: RcStr,
+}
+
+/// Matches a host element with a static `css` prop, after JSX has been
+/// compiled, e.g. `_jsx("div", { css: "color: red", className: "title" })`.
+///
+/// Returns `None` for dynamic styles, for components, which could handle the
+/// prop themselves, and when the `className` isn't known at compile time.
+pub(crate) fn match_static_css_prop(call: &CallExpr, imports: &ImportMap) -> Option<StaticCssProp> {
+    if !is_jsx_factory(&call.callee, imports) {
+        return None;
+    }
+    let [ExprOrSpread {
+        spread: None,
+        expr: element,
+    }, ExprOrSpread {
+        spread: None,
+        expr: props,
+    }, ..] = &call.args[..]
+    else {
+        return None;
+    };
+    if !matches!(&**element, Expr::Lit(Lit::Str(_))) {
+        return None;
+    }
+    let Expr::Object(props) = &**props else {
+        return None;
+    };
+    let mut css = None;
+    for prop in &props.props {
+        // A spread could override the `className`
+        let PropOrSpread::Prop(prop) = prop else {
+            return None;
+        };
+        let prop = match &**prop {
+            Prop::KeyValue(prop) => prop,
+            Prop::Shorthand(ident) if matches!(&*ident.sym, "css" | "className") => {
+                return None;
+            }
+            _ => continue,
+        };
+        match prop_name(&prop.key) {
+            Some("css") => css = Some(static_string(&prop.value)?),
+            Some("className") => {
+                static_string(&prop.value)?;
+            }
+            _ => {}
+        }
+    }
+    let css = css?;
+    let class_name: RcStr = format!("css-{:x}", hash_xxh3_hash64(css.as_str())).into();
+    Some(StaticCssProp {
+        css: format!(".{class_name}{{{css}}}").into(),
+        class_name,
+    })
+}
+
+fn is_jsx_factory(callee: &Callee, imports: &ImportMap) -> bool {
+    let Callee::Expr(callee) = callee else {
+        return false;
+    };
+    let (reference, export) = match &**callee {
+        Expr::Ident(ident) => {
+            let Some((reference, Some(export))) = imports.get_binding(&ident.to_id()) else {
+                return false;
+            };
+            (reference, export)
+        }
+        // e.g. `React.createElement`
+        Expr::Member(member) => {
+            let (Expr::Ident(object), MemberProp::Ident(prop)) = (&*member.obj, &member.prop)
+            else {
+                return false;
+            };
+            match imports.get_binding(&object.to_id()) {
+                Some((reference, None)) => (reference, prop.sym.as_str().into()),
+                Some((reference, Some(export))) if &*export == "default" => {
+                    (reference, prop.sym.as_str().into())
+                }
+                _ => return false,
+            }
+        }
+        _ => return false,
+    };
+    let Some(reference) = imports.references().nth(reference) else {
+        return false;
+    };
+    JSX_FACTORIES
+        .iter()
+        .any(|(module, name)| reference.module_path == *module && &*export == *name)
+}
+
+fn prop_name(key: &PropName) -> Option<&str> {
+    match key {
+        PropName::Ident(key) => Some(key.sym.as_str()),
+        PropName::Str(key) => Some(key.value.as_str()),
+        _ => None,
+    }
+}
+
+fn static_string(expr: &Expr) -> Option<RcStr> {
+    match expr {
+        Expr::Lit(Lit::Str(str)) => Some(str.value.as_str().into()),
+        Expr::Tpl(tpl) if tpl.exprs.is_empty() => {
+            let quasi = tpl.quasis.first()?;
+            Some(quasi.cooked.as_ref()?.as_str().into())
+        }
+        _ => None,
+    }
+}
+
+/// Moves a static styled-jsx style to a CSS module, so it's served as part of
+/// a CSS chunk instead of being injected by the styled-jsx runtime. The
+/// element that would inject it is removed, while the scoped class names on
+/// the elements stay the same.
+#[turbo_tasks::value]
+#[derive(Hash, Debug)]
+pub struct StyledJsxStyleReference {
+    pub origin: Vc<Box<dyn ResolveOrigin>>,
+    pub id: RcStr,
+    pub css: RcStr,
+    pub path: Vc<AstPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl StyledJsxStyleReference {
+    #[turbo_tasks::function]
+    pub fn new(
+        origin: Vc<Box<dyn ResolveOrigin>>,
+        id: RcStr,
+        css: RcStr,
+        path: Vc<AstPath>,
+    ) -> Vc<Self> {
+        Self::cell(StyledJsxStyleReference {
+            origin,
+            id,
+            css,
+            path,
+        })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleReference for StyledJsxStyleReference {
+    #[turbo_tasks::function]
+    async fn resolve_reference(&self) -> Result<Vc<ModuleResolveResult>> {
+        let path = self
+            .origin
+            .origin_path()
+            .append(format!(".styled-jsx-{}.css", self.id).into());
+        css_module(self.origin, path, self.css.clone()).await
+    }
+}
+
+/// Processes `css` like a CSS file at `path` that is imported for its side
+/// effects.
+async fn css_module(
+    origin: Vc<Box<dyn ResolveOrigin>>,
+    path: Vc<FileSystemPath>,
+    css: RcStr,
+) -> Result<Vc<ModuleResolveResult>> {
+    let source = VirtualSource::new(path, AssetContent::file(File::from(css).into()));
+    let module = origin
+        .asset_context()
+        .process(
+            Vc::upcast(source),
+            Value::new(ReferenceType::EcmaScriptModules(
+                EcmaScriptModulesReferenceSubType::Undefined,
+            )),
+        )
+        .try_into_module()
+        .await?;
+    Ok(match *module {
+        Some(module) => ModuleResolveResult::module(module.to_resolved().await?).cell(),
+        None => ModuleResolveResult::unresolvable().cell(),
+    })
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for StyledJsxStyleReference {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> Vc<RcStr> {
+        Vc::cell(format!("styled-jsx style {}", self.id).into())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModuleReference for StyledJsxStyleReference {}
+
+#[turbo_tasks::value_impl]
+impl CodeGenerateable for StyledJsxStyleReference {
+    #[turbo_tasks::function]
+    async fn code_generation(
+        &self,
+        _context: Vc<Box<dyn ChunkingContext>>,
+    ) -> Result<Vc<CodeGeneration>> {
+        let path = &self.path.await?;
+
+        let visitor = create_visitor!(path, visit_mut_expr(expr: &mut Expr) {
+            *expr = quote!("null" as Expr);
+        });
+
+        Ok(CodeGeneration::visitors(vec![visitor]))
+    }
+}
+
+/// Moves a static `css` prop of a host element to a CSS module. The prop is
+/// replaced by a class name that the CSS is scoped to.
+#[turbo_tasks::value]
+#[derive(Hash, Debug)]
+pub struct CssPropReference {
+    pub origin: Vc<Box<dyn ResolveOrigin>>,
+    pub class_name: RcStr,
+    pub css: RcStr,
+    pub path: Vc<AstPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl CssPropReference {
+    #[turbo_tasks::function]
+    pub fn new(
+        origin: Vc<Box<dyn ResolveOrigin>>,
+        class_name: RcStr,
+        css: RcStr,
+        path: Vc<AstPath>,
+    ) -> Vc<Self> {
+        Self::cell(CssPropReference {
+            origin,
+            class_name,
+            css,
+            path,
+        })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleReference for CssPropReference {
+    #[turbo_tasks::function]
+    async fn resolve_reference(&self) -> Result<Vc<ModuleResolveResult>> {
+        let path = self
+            .origin
+            .origin_path()
+            .append(format!(".{}.css", self.class_name).into());
+        css_module(self.origin, path, self.css.clone()).await
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for CssPropReference {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> Vc<RcStr> {
+        Vc::cell(format!("css prop {}", self.class_name).into())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModuleReference for CssPropReference {}
+
+#[turbo_tasks::value_impl]
+impl CodeGenerateable for CssPropReference {
+    #[turbo_tasks::function]
+    async fn code_generation(
+        &self,
+        _context: Vc<Box<dyn ChunkingContext>>,
+    ) -> Result<Vc<CodeGeneration>> {
+        let path = &self.path.await?;
+        let class_name = self.class_name.clone();
+
+        let visitor = create_visitor!(path, visit_mut_expr(expr: &mut Expr) {
+            let Expr::Call(call) = expr else {
+                return;
+            };
+            let Some(Expr::Object(props)) = call.args.get_mut(1).map(|arg| &mut *arg.expr) else {
+                return;
+            };
+            replace_css_prop(props, &class_name);
+        });
+
+        Ok(CodeGeneration::visitors(vec![visitor]))
+    }
+}
+
+/// Removes the `css` prop and adds `class_name` to the `className` prop.
+fn replace_css_prop(props: &mut ObjectLit, class_name: &str) {
+    let mut class_names = vec![];
+    props.props.retain(|prop| {
+        let PropOrSpread::Prop(prop) = prop else {
+            return true;
+        };
+        let Prop::KeyValue(prop) = &**prop else {
+            return true;
+        };
+        match prop_name(&prop.key) {
+            Some("css") => false,
+            Some("className") => {
+                class_names.extend(static_string(&prop.value));
+                false
+            }
+            _ => true,
+        }
+    });
+    class_names.push(class_name.into());
+    props
+        .props
+        .push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+            key: PropName::Ident(IdentName::new("className".into(), DUMMY_SP)),
+            value: Box::new(Expr::Lit(Lit::Str(Str {
+                span: DUMMY_SP,
+                value: class_names.join(" ").into(),
+                raw: None,
+            }))),
+        }))));
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::{
+        common::{FileName, Mark},
+        ecma::{
+            ast::*, parser::parse_file_as_module, transforms::base::resolver, visit::VisitMutWith,
+        },
+        testing::run_test,
+    };
+
+    use super::{
+        match_static_css_prop, match_static_styled_jsx_style, prop_name, replace_css_prop,
+        static_string,
+    };
+    use crate::analyzer::imports::ImportMap;
+
+    /// Parses `code` and passes the call of the last expression statement to
+    /// `f`.
+    fn with_last_call<T>(code: &str, f: impl FnOnce(&mut CallExpr, &ImportMap) -> T) -> T {
+        run_test(false, |cm, _handler| {
+            let fm = cm.new_source_file(FileName::Anon.into(), code.to_string());
+            let mut m = parse_file_as_module(
+                &fm,
+                Default::default(),
+                EsVersion::latest(),
+                None,
+                &mut vec![],
+            )
+            .unwrap();
+            m.visit_mut_with(&mut resolver(Mark::new(), Mark::new(), false));
+            let mut program = Program::Module(m);
+            let imports = ImportMap::analyze(&program, None, None);
+
+            let Program::Module(m) = &mut program else {
+                unreachable!()
+            };
+            let Some(ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. }))) = m.body.last_mut()
+            else {
+                unreachable!()
+            };
+            let Expr::Call(call) = &mut **expr else {
+                unreachable!()
+            };
+            Ok(f(call, &imports))
+        })
+        .unwrap()
+    }
+
+    fn styled_jsx_style(code: &str) -> Option<(String, String)> {
+        with_last_call(code, |call, imports| {
+            match_static_styled_jsx_style(call, imports)
+                .map(|style| (style.id.to_string(), style.css.to_string()))
+        })
+    }
+
+    #[test]
+    fn static_styled_jsx_styles() {
+        assert_eq!(
+            styled_jsx_style(
+                r#"import _JSXStyle from "styled-jsx/style";
+                _jsx(_JSXStyle, { id: "1a2b3c", children: "p.jsx-1a2b3c{color:red}" });"#
+            ),
+            Some(("1a2b3c".into(), "p.jsx-1a2b3c{color:red}".into()))
+        );
+        assert_eq!(
+            styled_jsx_style(
+                r#"import _JSXStyle from "styled-jsx/style";
+                React.createElement(_JSXStyle, { id: "1a2b3c" }, `p.jsx-1a2b3c{color:red}`);"#
+            ),
+            Some(("1a2b3c".into(), "p.jsx-1a2b3c{color:red}".into()))
+        );
+    }
+
+    #[test]
+    fn dynamic_styled_jsx_styles() {
+        // Interpolations are injected at runtime
+        assert_eq!(
+            styled_jsx_style(
+                r#"import _JSXStyle from "styled-jsx/style";
+                _jsx(_JSXStyle, { id: "1a2b3c", dynamic: [color], children: `p{color:${color}}` });"#
+            ),
+            None
+        );
+        // Not the element of styled-jsx
+        assert_eq!(
+            styled_jsx_style(
+                r#"import _JSXStyle from "other/style";
+                _jsx(_JSXStyle, { id: "1a2b3c", children: "p{color:red}" });"#
+            ),
+            None
+        );
+    }
+
+    /// Returns the class name and CSS of the `css` prop, and the props after
+    /// the prop has been replaced.
+    fn css_prop(code: &str) -> Option<(String, String, String)> {
+        with_last_call(code, |call, imports| {
+            let css_prop = match_static_css_prop(call, imports)?;
+            let Expr::Object(props) = &mut *call.args[1].expr else {
+                unreachable!()
+            };
+            replace_css_prop(props, &css_prop.class_name);
+
+            let props = props
+                .props
+                .iter()
+                .map(|prop| {
+                    let PropOrSpread::Prop(prop) = prop else {
+                        unreachable!()
+                    };
+                    let Prop::KeyValue(prop) = &**prop else {
+                        unreachable!()
+                    };
+                    format!(
+                        "{}={}",
+                        prop_name(&prop.key).unwrap(),
+                        static_string(&prop.value).unwrap()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            Some((
+                css_prop.class_name.to_string(),
+                css_prop.css.to_string(),
+                props,
+            ))
+        })
+    }
+
+    #[test]
+    fn static_css_props() {
+        let (class_name, css, props) = css_prop(
+            r#"import { jsx as _jsx } from "react/jsx-runtime";
+            _jsx("div", { css: "color: red", id: "title" });"#,
+        )
+        .unwrap();
+        assert!(class_name.starts_with("css-"));
+        assert_eq!(css, format!(".{class_name}{{color: red}}"));
+        assert_eq!(props, format!("id=title,className={class_name}"));
+
+        let (class_name, _, props) = css_prop(
+            r#"import React from "react";
+            React.createElement("div", { className: "title", css: `color: red` }, "text");"#,
+        )
+        .unwrap();
+        assert_eq!(props, format!("className=title {class_name}"));
+    }
+
+    #[test]
+    fn dynamic_css_props() {
+        // Components could handle the prop themselves
+        assert!(css_prop(
+            r#"import { jsx as _jsx } from "react/jsx-runtime";
+            _jsx(Component, { css: "color: red" });"#
+        )
+        .is_none());
+        // The CSS isn't known at compile time
+        assert!(css_prop(
+            r#"import { jsx as _jsx } from "react/jsx-runtime";
+            _jsx("div", { css: `color: ${color}` });"#
+        )
+        .is_none());
+        // The class name isn't known at compile time
+        assert!(css_prop(
+            r#"import { jsx as _jsx } from "react/jsx-runtime";
+            _jsx("div", { css: "color: red", className });"#
+        )
+        .is_none());
+        assert!(css_prop(
+            r#"import { jsx as _jsx } from "react/jsx-runtime";
+            _jsx("div", { css: "color: red", ...props });"#
+        )
+        .is_none());
+        // Not a JSX factory
+        assert!(css_prop(r#"element("div", { css: "color: red" });"#).is_none());
+    }
+}
@@ -8,6 +8,7 @@ use turbopack_core::{
     chunk::{ChunkableModule, ChunkingContext},
     ident::AssetIdent,
     module::Module,
+    module_metadata::ModuleMetadata,
     reference::ModuleReferences,
     resolve::ModulePart,
 };
@@ -52,6 +53,11 @@ impl Module for EcmascriptModuleLocalsModule {
         let result = self.module.analyze().await?;
         Ok(result.local_references)
     }
+
+    #[turbo_tasks::function]
+    fn metadata(&self) -> Vc<ModuleMetadata> {
+        self.module.metadata()
+    }
 }
 
 #[turbo_tasks::value_impl]
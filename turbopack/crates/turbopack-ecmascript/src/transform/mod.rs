@@ -2,13 +2,14 @@ use std::{fmt::Debug, hash::Hash, sync::Arc};
 
 use anyhow::Result;
 use async_trait::async_trait;
+use parking_lot::Mutex;
 use swc_core::{
     atoms::Atom,
     base::SwcComments,
     common::{collections::AHashMap, comments::Comments, util::take::Take, Mark, SourceMap},
     ecma::{
         ast::{Module, ModuleItem, Program, Script},
-        preset_env::{self, Targets},
+        preset_env::{self, Feature, FeatureOrModule, Targets},
         transforms::{
             base::{assumptions::Assumptions, feature::FeatureFlag, helpers::inject_helpers},
             optimization::inline_globals2,
@@ -22,6 +23,7 @@ use turbo_tasks_fs::FileSystemPath;
 use turbopack_core::{
     environment::Environment,
     issue::{Issue, IssueSeverity, IssueStage, StyledString},
+    module_metadata::ModuleMetadata,
 };
 
 #[turbo_tasks::value(serialization = "auto_for_input")]
@@ -48,6 +50,13 @@ pub enum EcmascriptInputTransform {
     TypeScript {
         #[serde(default)]
         use_define_for_class_fields: bool,
+        // tsconfig.compilerOptions.verbatimModuleSyntax, or the legacy
+        // `preserveValueImports` + `isolatedModules` combination
+        #[serde(default)]
+        verbatim_module_syntax: bool,
+        // tsconfig.compilerOptions.importsNotUsedAsValues set to `preserve` or `error`
+        #[serde(default)]
+        preserve_unused_imports: bool,
     },
     Decorators {
         #[serde(default)]
@@ -115,9 +124,25 @@ pub struct TransformContext<'a> {
     pub file_name_str: &'a str,
     pub file_name_hash: u128,
     pub file_path: Vc<FileSystemPath>,
+    /// Metadata about the module, to pass information that transforms
+    /// discover to chunking and manifests.
+    pub metadata: &'a Mutex<ModuleMetadata>,
 }
 
 impl EcmascriptInputTransform {
+    /// A short name of the transform for diagnostics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EcmascriptInputTransform::CommonJs => "commonjs",
+            EcmascriptInputTransform::Plugin(_) => "plugin",
+            EcmascriptInputTransform::PresetEnv(_) => "preset-env",
+            EcmascriptInputTransform::React { .. } => "react",
+            EcmascriptInputTransform::GlobalTypeofs { .. } => "global typeofs",
+            EcmascriptInputTransform::TypeScript { .. } => "typescript",
+            EcmascriptInputTransform::Decorators { .. } => "decorators",
+        }
+    }
+
     pub async fn apply(&self, program: &mut Program, ctx: &TransformContext<'_>) -> Result<()> {
         let &TransformContext {
             comments,
@@ -223,9 +248,29 @@ impl EcmascriptInputTransform {
             }
             EcmascriptInputTransform::PresetEnv(env) => {
                 let versions = env.runtime_versions().await?;
+                // Class features are lowered based on the capabilities of the environment
+                // instead of the per-feature data of preset-env, so all modules of a layer
+                // agree on the shape of classes, including subclasses of classes in other
+                // modules.
+                let class_features = [
+                    Feature::ClassProperties,
+                    Feature::PrivateMethods,
+                    Feature::PrivatePropertyInObject,
+                    Feature::ClassStaticBlock,
+                ]
+                .into_iter()
+                .map(FeatureOrModule::Feature)
+                .collect::<Vec<_>>();
+                let (include, exclude) = if env.capabilities().await?.native_class_features() {
+                    (vec![], class_features)
+                } else {
+                    (class_features, vec![])
+                };
                 let config = swc_core::ecma::preset_env::Config {
                     targets: Some(Targets::Versions(*versions)),
                     mode: None, // Don't insert core-js polyfills
+                    include,
+                    exclude,
                     ..Default::default()
                 };
 
@@ -260,25 +305,42 @@ impl EcmascriptInputTransform {
                 ));
             }
             EcmascriptInputTransform::TypeScript {
-                // TODO(WEB-1213)
-                use_define_for_class_fields: _use_define_for_class_fields,
+                use_define_for_class_fields,
+                verbatim_module_syntax,
+                preserve_unused_imports,
             } => {
-                use swc_core::ecma::transforms::typescript::typescript;
-                let config = Default::default();
+                use swc_core::ecma::transforms::typescript::{
+                    typescript, Config, ImportsNotUsedAsValues,
+                };
+                // With `verbatimModuleSyntax` only imports/exports explicitly marked with
+                // `type` are elided, everything else is kept as written. Without it, imports
+                // whose bindings are only used in type positions are elided, unless
+                // `importsNotUsedAsValues` asks to keep them as side effect imports.
+                // Without `useDefineForClassFields`, fields are assigned in the constructor like
+                // tsc does, before preset-env lowers the remaining class features.
+                let config = Config {
+                    verbatim_module_syntax: *verbatim_module_syntax,
+                    native_class_properties: *use_define_for_class_fields,
+                    import_not_used_as_values: if *preserve_unused_imports {
+                        ImportsNotUsedAsValues::Preserve
+                    } else {
+                        ImportsNotUsedAsValues::Remove
+                    },
+                    ..Default::default()
+                };
                 program.mutate(typescript(config, unresolved_mark, top_level_mark));
             }
             EcmascriptInputTransform::Decorators {
                 is_legacy,
                 is_ecma: _,
                 emit_decorators_metadata,
-                // TODO(WEB-1213)
-                use_define_for_class_fields: _use_define_for_class_fields,
+                use_define_for_class_fields,
             } => {
                 use swc_core::ecma::transforms::proposal::decorators::{decorators, Config};
                 let config = Config {
                     legacy: *is_legacy,
                     emit_metadata: *emit_decorators_metadata,
-                    ..Default::default()
+                    use_define_for_class_fields: *use_define_for_class_fields,
                 };
 
                 program.mutate((decorators(config), inject_helpers(unresolved_mark)));
@@ -332,3 +394,152 @@ impl Issue for UnsupportedServerActionIssue {
         IssueStage::Transform.cell()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+
+    use anyhow::{bail, Result};
+    use async_trait::async_trait;
+    use swc_core::ecma::ast::Program;
+    use turbo_tasks::{RcStr, TurboTasks, Value, Vc};
+    use turbo_tasks_fs::{glob::Glob, File, FileSystem, FileSystemPath, VirtualFileSystem};
+    use turbo_tasks_memory::MemoryBackend;
+    use turbopack_core::{
+        asset::AssetContent,
+        compile_time_info::CompileTimeInfo,
+        context::{AssetContext, ProcessResult},
+        environment::{Environment, ExecutionEnvironment, NodeJsEnvironment},
+        module::Module,
+        module_metadata::ModuleMetadataKey,
+        reference_type::ReferenceType,
+        resolve::{options::ResolveOptions, parse::Request, ModuleResolveResult, ResolveResult},
+        source::Source,
+        virtual_source::VirtualSource,
+    };
+
+    use super::{CustomTransformer, EcmascriptInputTransform, TransformContext};
+    use crate::{EcmascriptModuleAsset, EcmascriptOptions};
+
+    const TRANSFORMED: ModuleMetadataKey<RcStr> = ModuleMetadataKey::new("test/transformed");
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    #[derive(Debug)]
+    struct WriteMetadata;
+
+    #[async_trait]
+    impl CustomTransformer for WriteMetadata {
+        async fn transform(
+            &self,
+            _program: &mut Program,
+            ctx: &TransformContext<'_>,
+        ) -> Result<()> {
+            ctx.metadata
+                .lock()
+                .insert(&TRANSFORMED, &ctx.file_name_str.into())
+        }
+    }
+
+    /// Only provides what the analysis of a module without references needs.
+    #[turbo_tasks::value]
+    struct TestAssetContext;
+
+    #[turbo_tasks::value_impl]
+    impl AssetContext for TestAssetContext {
+        #[turbo_tasks::function]
+        fn compile_time_info(&self) -> Vc<CompileTimeInfo> {
+            CompileTimeInfo::new(Environment::new(Value::new(
+                ExecutionEnvironment::NodeJsLambda(NodeJsEnvironment::default().into()),
+            )))
+        }
+
+        #[turbo_tasks::function]
+        fn layer(&self) -> Vc<RcStr> {
+            Vc::cell("test".into())
+        }
+
+        #[turbo_tasks::function]
+        fn resolve_options(
+            &self,
+            _origin_path: Vc<FileSystemPath>,
+            _reference_type: Value<ReferenceType>,
+        ) -> Result<Vc<ResolveOptions>> {
+            bail!("not supported")
+        }
+
+        #[turbo_tasks::function]
+        fn resolve_asset(
+            &self,
+            _origin_path: Vc<FileSystemPath>,
+            _request: Vc<Request>,
+            _resolve_options: Vc<ResolveOptions>,
+            _reference_type: Value<ReferenceType>,
+        ) -> Result<Vc<ModuleResolveResult>> {
+            bail!("not supported")
+        }
+
+        #[turbo_tasks::function]
+        fn process(
+            &self,
+            _asset: Vc<Box<dyn Source>>,
+            _reference_type: Value<ReferenceType>,
+        ) -> Result<Vc<ProcessResult>> {
+            bail!("not supported")
+        }
+
+        #[turbo_tasks::function]
+        fn process_resolve_result(
+            &self,
+            _result: Vc<ResolveResult>,
+            _reference_type: Value<ReferenceType>,
+        ) -> Result<Vc<ModuleResolveResult>> {
+            bail!("not supported")
+        }
+
+        #[turbo_tasks::function]
+        fn with_transition(&self, _transition: RcStr) -> Result<Vc<Box<dyn AssetContext>>> {
+            bail!("not supported")
+        }
+
+        #[turbo_tasks::function]
+        fn side_effect_free_packages(&self) -> Result<Vc<Glob>> {
+            bail!("not supported")
+        }
+    }
+
+    #[tokio::test]
+    async fn exposes_metadata_of_transforms_on_the_module() {
+        register();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async {
+            let path = Vc::upcast::<Box<dyn FileSystem>>(VirtualFileSystem::new())
+                .root()
+                .join("index.js".into());
+            let source = VirtualSource::new(
+                path,
+                AssetContent::file(File::from("export const a = 1;").into()),
+            );
+            let asset_context: Vc<Box<dyn AssetContext>> = Vc::upcast(TestAssetContext.cell());
+            let module = EcmascriptModuleAsset::builder(
+                Vc::upcast(source),
+                asset_context,
+                Vc::cell(vec![EcmascriptInputTransform::Plugin(Vc::cell(Box::new(
+                    WriteMetadata,
+                )))]),
+                EcmascriptOptions::default().cell(),
+                asset_context.compile_time_info(),
+            )
+            .build();
+
+            let metadata = module.metadata().await?;
+            assert_eq!(metadata.get(&TRANSFORMED)?, Some(RcStr::from("index.js")));
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -5,6 +5,7 @@ use turbopack_core::{
     chunk::{AsyncModuleInfo, ChunkableModule, ChunkingContext, EvaluatableAsset},
     ident::AssetIdent,
     module::Module,
+    module_metadata::ModuleMetadata,
     reference::{ModuleReference, ModuleReferences, SingleModuleReference},
     resolve::ModulePart,
 };
@@ -121,6 +122,12 @@ impl Module for EcmascriptModulePartAsset {
         self.full_module.ident().with_part(self.part)
     }
 
+    /// Parts share the metadata of the whole module.
+    #[turbo_tasks::function]
+    fn metadata(&self) -> Vc<ModuleMetadata> {
+        self.full_module.metadata()
+    }
+
     #[turbo_tasks::function]
     async fn references(&self) -> Result<Vc<ModuleReferences>> {
         let split_data = split_module(self.full_module).await?;
@@ -488,6 +488,7 @@ pub(super) async fn split(
             eval_context,
             source_map,
             globals,
+            metadata,
             ..
         } => {
             // If the script file is a common js file, we cannot split the module
@@ -565,6 +566,9 @@ pub(super) async fn split(
                         comments: comments.clone(),
                         source_map: source_map.clone(),
                         eval_context,
+                        metadata: metadata.clone(),
+                        // Fragments aren't reported as slow modules.
+                        timings: Default::default(),
                     })
                 })
                 .collect();
@@ -610,6 +614,7 @@ pub(crate) async fn part_of_module(
                     eval_context,
                     globals,
                     source_map,
+                    metadata,
                     ..
                 } = &*modules[0].await?
                 {
@@ -689,6 +694,8 @@ pub(crate) async fn part_of_module(
                         eval_context,
                         globals: globals.clone(),
                         source_map: source_map.clone(),
+                        metadata: metadata.clone(),
+                        timings: Default::default(),
                     }
                     .cell());
                 } else {
@@ -78,6 +78,17 @@ pub fn module_id_to_lit(module_id: &ModuleId) -> Expr {
     })
 }
 
+/// Whether `name` can be used as an import or export name without quotes, e.g.
+/// in `export { a as name }`. Keywords are allowed there. Other names need to
+/// be written as string literals.
+pub fn is_identifier_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
 pub struct StringifyJs<'a, T>(pub &'a T)
 where
     T: ?Sized;
@@ -184,3 +195,18 @@ pub fn module_value_to_well_known_object(module_value: &ModuleValue) -> Option<J
         _ => return None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::is_identifier_name;
+
+    #[test]
+    fn identifier_names() {
+        for name in ["a", "_private", "$", "camelCase2", "default", "class"] {
+            assert!(is_identifier_name(name), "{name}");
+        }
+        for name in ["", "2d", "a-b", "a b", "🦀"] {
+            assert!(!is_identifier_name(name), "{name}");
+        }
+    }
+}
@@ -0,0 +1,66 @@
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+#![cfg(test)]
+
+use turbo_tasks::Vc;
+use turbo_tasks_fs::{FileSystem, FileSystemPath, VirtualFileSystem};
+use turbo_tasks_testing::{register, run, Registration};
+use turbopack_core::resolve::{
+    options::{ImportMap, ImportMapResult},
+    parse::Request,
+    ExternalType, ResolveResultItem,
+};
+use turbopack_ecmascript::browser_import_map::BrowserImportMap;
+
+static REGISTRATION: Registration = register!(turbopack_ecmascript::register);
+
+async fn lookup(
+    import_map: &ImportMap,
+    root: Vc<FileSystemPath>,
+    request: &str,
+) -> anyhow::Result<Option<ResolveResultItem>> {
+    let ImportMapResult::Result(result) = import_map
+        .lookup(root, Request::parse_string(request.into()))
+        .await?
+    else {
+        return Ok(None);
+    };
+    Ok(result.await?.primary.values().next().cloned())
+}
+
+#[tokio::test]
+async fn maps_specifiers_to_esm_externals() {
+    run(&REGISTRATION, || async {
+        let root = VirtualFileSystem::new().root();
+        let browser_import_map = BrowserImportMap::parse(
+            r#"{
+                "imports": {
+                    "react": "https://esm.sh/react@18",
+                    "lodash/": "https://esm.sh/lodash/"
+                }
+            }"#,
+        )?;
+        let mut import_map = ImportMap::empty();
+        browser_import_map.insert_externals(&mut import_map);
+
+        assert_eq!(
+            lookup(&import_map, root, "react").await?,
+            Some(ResolveResultItem::External(
+                "react".into(),
+                ExternalType::EcmaScriptModule
+            ))
+        );
+        assert_eq!(
+            lookup(&import_map, root, "lodash/fp").await?,
+            Some(ResolveResultItem::External(
+                "lodash/fp".into(),
+                ExternalType::EcmaScriptModule
+            ))
+        );
+        // only the specifiers of the import map are externals
+        assert_eq!(lookup(&import_map, root, "react-dom").await?, None);
+        assert_eq!(lookup(&import_map, root, "react/jsx-runtime").await?, None);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
@@ -3,7 +3,8 @@
 //! JSON assets are parsed to ensure they contain valid JSON.
 //!
 //! When imported from ES modules, they produce a module that exports the
-//! JSON value as an object.
+//! JSON value as an object. When the importing modules only read statically
+//! known properties, they share a module that only includes those.
 
 #![feature(min_specialization)]
 #![feature(arbitrary_self_types)]
@@ -11,8 +12,9 @@
 
 use std::fmt::Write;
 
-use anyhow::{bail, Error, Result};
-use turbo_tasks::{RcStr, ValueToString, Vc};
+use anyhow::{bail, Context, Error, Result};
+use serde_json::Value as JsonValue;
+use turbo_tasks::{Completion, FxIndexMap, RcStr, ResolvedVc, State, ValueToString, Vc};
 use turbo_tasks_fs::{FileContent, FileJsonContent};
 use turbopack_core::{
     asset::{Asset, AssetContent},
@@ -20,11 +22,12 @@ use turbopack_core::{
     ident::AssetIdent,
     module::Module,
     reference::ModuleReferences,
+    resolve::{origin::ResolveOrigin, parse::Request},
     source::Source,
 };
 use turbopack_ecmascript::chunk::{
     EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkPlaceable, EcmascriptChunkType,
-    EcmascriptExports,
+    EcmascriptExports, EcmascriptValueModule, PropertyPaths,
 };
 
 #[turbo_tasks::function]
@@ -32,16 +35,74 @@ fn modifier() -> Vc<RcStr> {
     Vc::cell("json".into())
 }
 
+#[turbo_tasks::function]
+fn read_properties_modifier() -> Vc<RcStr> {
+    Vc::cell("read properties".into())
+}
+
+type PropertyPathsByImport =
+    FxIndexMap<(ResolvedVc<Box<dyn ResolveOrigin>>, ResolvedVc<Request>), Vec<Vec<RcStr>>>;
+
+/// The paths of the properties of a JSON value that each import of a JSON
+/// module reads.
+///
+/// Imports only ever add or replace their paths, so the module never misses a
+/// property that an import reads. The paths of removed imports are kept
+/// until the next session.
+#[turbo_tasks::value(eq = "manual", cell = "new")]
+struct ReadProperties {
+    by_import: State<PropertyPathsByImport>,
+}
+
+#[turbo_tasks::value_impl]
+impl ReadProperties {
+    #[turbo_tasks::function]
+    async fn record(
+        &self,
+        origin: ResolvedVc<Box<dyn ResolveOrigin>>,
+        request: ResolvedVc<Request>,
+        paths: Vc<PropertyPaths>,
+    ) -> Result<Vc<Completion>> {
+        let paths = paths.await?.clone_value();
+        self.by_import.update_conditionally(|by_import| {
+            by_import.insert((origin, request), paths.clone()).as_ref() != Some(&paths)
+        });
+        Ok(Completion::new())
+    }
+}
+
 #[turbo_tasks::value]
 pub struct JsonModuleAsset {
     source: Vc<Box<dyn Source>>,
+    /// Only the properties that the imports of the module read are exported,
+    /// when set.
+    read_properties: Option<ResolvedVc<ReadProperties>>,
 }
 
 #[turbo_tasks::value_impl]
 impl JsonModuleAsset {
     #[turbo_tasks::function]
     pub fn new(source: Vc<Box<dyn Source>>) -> Vc<Self> {
-        Self::cell(JsonModuleAsset { source })
+        Self::cell(JsonModuleAsset {
+            source,
+            read_properties: None,
+        })
+    }
+
+    /// Creates the module that is shared by all imports of `source` that only
+    /// read statically known properties. It exports the properties that any of
+    /// them reads.
+    #[turbo_tasks::function]
+    fn new_tree_shaken(source: Vc<Box<dyn Source>>) -> Vc<Self> {
+        Self::cell(JsonModuleAsset {
+            source,
+            read_properties: Some(
+                ReadProperties {
+                    by_import: State::new(Default::default()),
+                }
+                .resolved_cell(),
+            ),
+        })
     }
 }
 
@@ -49,7 +110,30 @@ impl JsonModuleAsset {
 impl Module for JsonModuleAsset {
     #[turbo_tasks::function]
     fn ident(&self) -> Vc<AssetIdent> {
-        self.source.ident().with_modifier(modifier())
+        let ident = self.source.ident().with_modifier(modifier());
+        if self.read_properties.is_some() {
+            return ident.with_modifier(read_properties_modifier());
+        }
+        ident
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptValueModule for JsonModuleAsset {
+    #[turbo_tasks::function]
+    async fn with_read_properties(
+        &self,
+        origin: ResolvedVc<Box<dyn ResolveOrigin>>,
+        request: ResolvedVc<Request>,
+        paths: Vc<PropertyPaths>,
+    ) -> Result<Vc<Box<dyn Module>>> {
+        let module = JsonModuleAsset::new_tree_shaken(self.source);
+        let read_properties = module
+            .await?
+            .read_properties
+            .context("a tree shaken JSON module has read properties")?;
+        read_properties.record(*origin, *request, paths).await?;
+        Ok(Vc::upcast(module))
     }
 }
 
@@ -134,6 +218,23 @@ impl EcmascriptChunkItem for JsonChunkItem {
         let data = content.parse_json().await?;
         match &*data {
             FileJsonContent::Content(data) => {
+                let selected;
+                let module = self.module.await?;
+                let data = match module.read_properties {
+                    Some(read_properties) => {
+                        let read_properties = read_properties.await?;
+                        let paths = read_properties
+                            .by_import
+                            .get()
+                            .values()
+                            .flatten()
+                            .cloned()
+                            .collect::<Vec<_>>();
+                        selected = select_properties(data, &paths);
+                        &selected
+                    }
+                    None => data,
+                };
                 let js_str_content = serde_json::to_string(&data.to_string())?;
                 let inner_code =
                     format!("__turbopack_export_value__(JSON.parse({js_str_content}));");
@@ -165,6 +266,55 @@ impl EcmascriptChunkItem for JsonChunkItem {
     }
 }
 
+/// Returns `value` with only the properties at `paths`, and everything nested
+/// in them. Values that aren't objects, like arrays, are kept as a whole.
+fn select_properties(value: &JsonValue, paths: &[Vec<RcStr>]) -> JsonValue {
+    let JsonValue::Object(object) = value else {
+        return value.clone();
+    };
+    if paths.iter().any(|path| path.is_empty()) {
+        return value.clone();
+    }
+    let mut selected = serde_json::Map::new();
+    for (key, value) in object {
+        let nested: Vec<_> = paths
+            .iter()
+            .filter(|path| *path[0] == **key)
+            .map(|path| path[1..].to_vec())
+            .collect();
+        if !nested.is_empty() {
+            selected.insert(key.clone(), select_properties(value, &nested));
+        }
+    }
+    JsonValue::Object(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn selects_properties() {
+        let value = json!({
+            "a": { "b": 1, "c": [1, 2], "d": { "e": true } },
+            "f": "f",
+            "g": null
+        });
+        let paths = vec![
+            vec!["a".into(), "c".into(), "length".into()],
+            vec!["a".into(), "d".into()],
+            vec!["missing".into()],
+        ];
+        assert_eq!(
+            select_properties(&value, &paths),
+            json!({ "a": { "c": [1, 2], "d": { "e": true } } })
+        );
+        assert_eq!(select_properties(&value, &[vec![]]), value);
+    }
+}
+
 pub fn register() {
     turbo_tasks::register();
     turbo_tasks_fs::register();
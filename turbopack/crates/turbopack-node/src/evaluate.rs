@@ -12,8 +12,8 @@ use parking_lot::Mutex;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use turbo_tasks::{
-    duration_span, fxindexmap, mark_finished, prevent_gc, util::SharedError, Completion, RawVc,
-    ResolvedVc, TaskInput, TryJoinIterExt, Value, Vc,
+    duration_span, fxindexmap, mark_finished, prevent_gc, util::SharedError, Completion, CowVec,
+    RawVc, ResolvedVc, TaskInput, TryJoinIterExt, Value, Vc,
 };
 use turbo_tasks_bytes::{Bytes, Stream};
 use turbo_tasks_env::ProcessEnv;
@@ -142,11 +142,10 @@ pub async fn get_evaluate_pool(
             bail!("Internal module is not evaluatable");
         };
 
-        let mut entries = vec![globals_module];
+        let mut entries = CowVec::new();
+        entries.push(globals_module);
         if let Some(runtime_entries) = runtime_entries {
-            for &entry in &*runtime_entries.await? {
-                entries.push(entry)
-            }
+            entries.extend(&*runtime_entries.await?);
         }
 
         Vc::<EvaluatableAssets>::cell(entries)
@@ -91,6 +91,9 @@ pub struct WebpackLoaders {
     execution_context: ResolvedVc<ExecutionContext>,
     loaders: ResolvedVc<WebpackLoaderItems>,
     rename_as: Option<RcStr>,
+    /// Distinguishes the processed sources from those of other rules for the
+    /// same files, e.g. rules that only apply to some importers.
+    ident_modifier: Option<RcStr>,
     resolve_options_context: ResolvedVc<ResolveOptionsContext>,
 }
 
@@ -102,6 +105,7 @@ impl WebpackLoaders {
         execution_context: ResolvedVc<ExecutionContext>,
         loaders: ResolvedVc<WebpackLoaderItems>,
         rename_as: Option<RcStr>,
+        ident_modifier: Option<RcStr>,
         resolve_options_context: ResolvedVc<ResolveOptionsContext>,
     ) -> Vc<Self> {
         WebpackLoaders {
@@ -109,6 +113,7 @@ impl WebpackLoaders {
             execution_context,
             loaders,
             rename_as,
+            ident_modifier,
             resolve_options_context,
         }
         .cell()
@@ -142,13 +147,16 @@ struct WebpackLoadersProcessedAsset {
 impl Source for WebpackLoadersProcessedAsset {
     #[turbo_tasks::function]
     async fn ident(&self) -> Result<Vc<AssetIdent>> {
-        Ok(
-            if let Some(rename_as) = self.transform.await?.rename_as.as_deref() {
-                self.source.ident().rename_as(rename_as.into())
-            } else {
-                self.source.ident()
-            },
-        )
+        let transform = self.transform.await?;
+        let mut ident = if let Some(rename_as) = transform.rename_as.as_deref() {
+            self.source.ident().rename_as(rename_as.into())
+        } else {
+            self.source.ident()
+        };
+        if let Some(modifier) = &transform.ident_modifier {
+            ident = ident.with_modifier(Vc::cell(modifier.clone()));
+        }
+        Ok(ident)
     }
 }
 
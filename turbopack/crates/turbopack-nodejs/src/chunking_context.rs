@@ -9,8 +9,8 @@ use turbopack_core::{
         availability_info::AvailabilityInfo,
         chunk_group::{make_chunk_group, MakeChunkGroupResult},
         module_id_strategies::{DevModuleIdStrategy, ModuleIdStrategy},
-        Chunk, ChunkGroupResult, ChunkItem, ChunkableModule, ChunkingContext,
-        EntryChunkGroupResult, EvaluatableAssets, MinifyType, ModuleId,
+        Chunk, ChunkCodeWrapping, ChunkGroupResult, ChunkItem, ChunkableModule, ChunkingContext,
+        EntryChunkGroupResult, EvaluatableAssets, KeepNames, MinifyType, ModuleId,
     },
     environment::Environment,
     ident::AssetIdent,
@@ -39,11 +39,26 @@ impl NodeJsChunkingContextBuilder {
         self
     }
 
+    /// Makes `url()` references in CSS refer to static assets by their url,
+    /// which starts with the `asset_prefix`, instead of relative to the CSS
+    /// chunk, so they stay correct no matter where the CSS is emitted.
+    pub fn asset_urls_in_css(mut self) -> Self {
+        self.chunking_context.asset_urls_in_css = true;
+        self
+    }
+
     pub fn minify_type(mut self, minify_type: MinifyType) -> Self {
         self.chunking_context.minify_type = minify_type;
         self
     }
 
+    /// Keeps the names of functions and classes in minified chunks, see
+    /// [KeepNames].
+    pub fn keep_names(mut self, keep_names: KeepNames) -> Self {
+        self.chunking_context.keep_names = keep_names;
+        self
+    }
+
     pub fn runtime_type(mut self, runtime_type: RuntimeType) -> Self {
         self.chunking_context.runtime_type = runtime_type;
         self
@@ -59,6 +74,17 @@ impl NodeJsChunkingContextBuilder {
         self
     }
 
+    pub fn source_map_source_template(mut self, template: RcStr) -> Self {
+        self.chunking_context.source_map_source_template = Some(template);
+        self
+    }
+
+    /// Adds a banner, a footer or a runtime injection to every emitted chunk.
+    pub fn chunk_code_wrapping(mut self, chunk_code_wrapping: ChunkCodeWrapping) -> Self {
+        self.chunking_context.chunk_code_wrapping = chunk_code_wrapping;
+        self
+    }
+
     pub fn module_id_strategy(mut self, module_id_strategy: Vc<Box<dyn ModuleIdStrategy>>) -> Self {
         self.chunking_context.module_id_strategy = module_id_strategy;
         self
@@ -87,18 +113,27 @@ pub struct NodeJsChunkingContext {
     asset_root_path: Vc<FileSystemPath>,
     /// Static assets requested from this url base
     asset_prefix: Vc<Option<RcStr>>,
+    /// Whether `url()` references in CSS refer to static assets by their url
+    /// instead of relative to the CSS chunk
+    asset_urls_in_css: bool,
     /// The environment chunks will be evaluated in.
     environment: Vc<Environment>,
     /// The kind of runtime to include in the output.
     runtime_type: RuntimeType,
     /// Whether to minify resulting chunks
     minify_type: MinifyType,
+    /// Which functions and classes keep their names in minified chunks
+    keep_names: KeepNames,
     /// Whether to use manifest chunks for lazy compilation
     manifest_chunks: bool,
     /// The strategy to use for generating module ids
     module_id_strategy: Vc<Box<dyn ModuleIdStrategy>>,
     /// Whether to use file:// uris for source map sources
     should_use_file_source_map_uris: bool,
+    /// Template to rewrite `turbopack://` sources in source maps with
+    source_map_source_template: Option<RcStr>,
+    /// Code to add to every emitted chunk
+    chunk_code_wrapping: ChunkCodeWrapping,
 }
 
 impl NodeJsChunkingContext {
@@ -120,11 +155,15 @@ impl NodeJsChunkingContext {
                 chunk_root_path,
                 asset_root_path,
                 asset_prefix: Default::default(),
+                asset_urls_in_css: false,
                 environment,
                 runtime_type,
                 minify_type: MinifyType::NoMinify,
+                keep_names: Default::default(),
                 manifest_chunks: false,
                 should_use_file_source_map_uris: false,
+                source_map_source_template: None,
+                chunk_code_wrapping: Default::default(),
                 module_id_strategy: Vc::upcast(DevModuleIdStrategy::new()),
             },
         }
@@ -144,6 +183,12 @@ impl NodeJsChunkingContext {
     pub fn minify_type(&self) -> MinifyType {
         self.minify_type
     }
+
+    /// Returns which functions and classes keep their names in minified
+    /// chunks.
+    pub fn keep_names(&self) -> KeepNames {
+        self.keep_names
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -223,6 +268,11 @@ impl ChunkingContext for NodeJsChunkingContext {
         ))
     }
 
+    #[turbo_tasks::function]
+    fn asset_urls_in_css(&self) -> Vc<bool> {
+        Vc::cell(self.asset_urls_in_css)
+    }
+
     #[turbo_tasks::function]
     async fn chunk_path(
         &self,
@@ -244,6 +294,16 @@ impl ChunkingContext for NodeJsChunkingContext {
         Vc::cell(self.should_use_file_source_map_uris)
     }
 
+    #[turbo_tasks::function]
+    fn source_map_source_template(&self) -> Vc<Option<RcStr>> {
+        Vc::cell(self.source_map_source_template.clone())
+    }
+
+    #[turbo_tasks::function]
+    fn chunk_code_wrapping(&self) -> Vc<ChunkCodeWrapping> {
+        self.chunk_code_wrapping.clone().cell()
+    }
+
     #[turbo_tasks::function]
     async fn asset_path(
         &self,
@@ -417,3 +477,69 @@ impl ChunkingContext for NodeJsChunkingContext {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+
+    use turbo_tasks::{TurboTasks, Value, Vc};
+    use turbo_tasks_fs::{FileSystem, VirtualFileSystem};
+    use turbo_tasks_memory::MemoryBackend;
+    use turbopack_core::{
+        chunk::ChunkingContext,
+        environment::{Environment, ExecutionEnvironment, NodeJsEnvironment},
+        ident::AssetIdent,
+    };
+    use turbopack_ecmascript_runtime::RuntimeType;
+
+    use super::NodeJsChunkingContext;
+
+    fn register() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(crate::register);
+    }
+
+    #[tokio::test]
+    async fn css_refers_to_assets_by_their_url() {
+        register();
+        let tt = TurboTasks::new(MemoryBackend::new(usize::MAX));
+        tt.run_once(async {
+            let root = VirtualFileSystem::new().root();
+            let client_root = root.join(".next".into());
+            let environment = Environment::new(Value::new(ExecutionEnvironment::NodeJsLambda(
+                NodeJsEnvironment::default().into(),
+            )));
+            let chunking_context = |asset_urls_in_css: bool| {
+                let builder = NodeJsChunkingContext::builder(
+                    root,
+                    root.join(".next/server".into()),
+                    client_root,
+                    root.join(".next/server/chunks".into()),
+                    client_root.join("static/media".into()),
+                    environment,
+                    RuntimeType::Development,
+                )
+                .asset_prefix(Vc::cell(Some("https://cdn.example.com/_next/".into())));
+                let builder = if asset_urls_in_css {
+                    builder.asset_urls_in_css()
+                } else {
+                    builder
+                };
+                Vc::upcast::<Box<dyn ChunkingContext>>(builder.build())
+            };
+
+            assert!(!*chunking_context(false).asset_urls_in_css().await?);
+
+            let chunking_context = chunking_context(true);
+            assert!(*chunking_context.asset_urls_in_css().await?);
+            let asset = AssetIdent::from_path(client_root.join("static/media/logo.123.png".into()));
+            assert_eq!(
+                &**chunking_context.asset_url(asset).await?,
+                "https://cdn.example.com/_next/static/media/logo.123.png"
+            );
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -16,7 +16,7 @@ use crate::NodeJsChunkingContext;
 
 /// Production Ecmascript chunk targeting Node.js.
 #[turbo_tasks::value(shared)]
-pub(crate) struct EcmascriptBuildNodeChunk {
+pub struct EcmascriptBuildNodeChunk {
     chunking_context: Vc<NodeJsChunkingContext>,
     chunk: Vc<EcmascriptChunk>,
 }
@@ -61,6 +61,11 @@ impl EcmascriptBuildNodeChunk {
             this.chunk.chunk_content(),
         ))
     }
+
+    #[turbo_tasks::function]
+    pub fn chunk(&self) -> Vc<Box<dyn Chunk>> {
+        Vc::upcast(self.chunk)
+    }
 }
 
 #[turbo_tasks::value_impl]
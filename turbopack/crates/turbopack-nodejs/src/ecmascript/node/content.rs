@@ -7,14 +7,14 @@ use turbo_tasks_fs::File;
 use turbopack_core::{
     asset::AssetContent,
     chunk::{ChunkItemExt, ChunkingContext, MinifyType, ModuleId},
-    code_builder::{Code, CodeBuilder},
+    code_builder::{apply_source_map_source_template, Code, CodeBuilder},
     output::OutputAsset,
     source_map::{GenerateSourceMap, OptionSourceMap},
     version::{Version, VersionedContent},
 };
 use turbopack_ecmascript::{
     chunk::{EcmascriptChunkContent, EcmascriptChunkItemExt},
-    minify::minify,
+    minify::{minify_code, source_map_reference},
     utils::StringifyJs,
 };
 
@@ -88,24 +88,25 @@ impl EcmascriptBuildNodeChunkContent {
 
         write!(code, "\n}};")?;
 
-        if code.has_source_map() {
-            let filename = chunk_path.file_name();
-            write!(
-                code,
-                "\n\n//# sourceMappingURL={}.map",
-                urlencoding::encode(filename)
-            )?;
-        }
+        let content = code.build().cell();
+        let chunking_context = this.chunking_context.await?;
+        let content = if matches!(chunking_context.minify_type(), MinifyType::Minify) {
+            minify_code(chunk_path_vc, content, chunking_context.keep_names())
+        } else {
+            content
+        };
+
+        let chunk_code_wrapping = this.chunking_context.chunk_code_wrapping().await?;
+        let mut code = CodeBuilder::default();
+        chunk_code_wrapping.write_banner(&mut code)?;
+        code.push_code(&*content.await?);
+        chunk_code_wrapping.write_footer(&mut code)?;
 
-        let code = code.build().cell();
-        if matches!(
-            this.chunking_context.await?.minify_type(),
-            MinifyType::Minify
-        ) {
-            return Ok(minify(chunk_path_vc, code));
+        if code.has_source_map() {
+            write!(code, "{}", source_map_reference(chunk_path.file_name()))?;
         }
 
-        Ok(code)
+        Ok(code.build().cell())
     }
 
     #[turbo_tasks::function]
@@ -122,8 +123,12 @@ impl EcmascriptBuildNodeChunkContent {
 #[turbo_tasks::value_impl]
 impl GenerateSourceMap for EcmascriptBuildNodeChunkContent {
     #[turbo_tasks::function]
-    fn generate_source_map(self: Vc<Self>) -> Vc<OptionSourceMap> {
-        self.code().generate_source_map()
+    async fn generate_source_map(self: Vc<Self>) -> Result<Vc<OptionSourceMap>> {
+        let this = self.await?;
+        Ok(apply_source_map_source_template(
+            self.code().generate_source_map(),
+            Vc::upcast(this.chunking_context),
+        ))
     }
 }
 
@@ -7,7 +7,7 @@ use turbo_tasks_fs::{File, FileSystemPath};
 use turbopack_core::{
     asset::{Asset, AssetContent},
     chunk::{ChunkItemExt, ChunkableModule, ChunkingContext, EvaluatableAssets},
-    code_builder::{Code, CodeBuilder},
+    code_builder::{apply_source_map_source_template, Code, CodeBuilder},
     ident::AssetIdent,
     output::{OutputAsset, OutputAssets},
     source_map::{GenerateSourceMap, OptionSourceMap, SourceMapAsset},
@@ -77,7 +77,9 @@ impl EcmascriptBuildNodeEntryChunk {
             );
         };
 
+        let chunk_code_wrapping = this.chunking_context.chunk_code_wrapping().await?;
         let mut code = CodeBuilder::default();
+        chunk_code_wrapping.write_banner(&mut code)?;
 
         writedoc!(
             code,
@@ -140,6 +142,8 @@ impl EcmascriptBuildNodeEntryChunk {
             StringifyJs(&*runtime_module_id),
         )?;
 
+        chunk_code_wrapping.write_footer(&mut code)?;
+
         Ok(Code::cell(code.build()))
     }
 
@@ -214,7 +218,11 @@ impl Asset for EcmascriptBuildNodeEntryChunk {
 #[turbo_tasks::value_impl]
 impl GenerateSourceMap for EcmascriptBuildNodeEntryChunk {
     #[turbo_tasks::function]
-    fn generate_source_map(self: Vc<Self>) -> Vc<OptionSourceMap> {
-        self.code().generate_source_map()
+    async fn generate_source_map(self: Vc<Self>) -> Result<Vc<OptionSourceMap>> {
+        let this = self.await?;
+        Ok(apply_source_map_source_template(
+            self.code().generate_source_map(),
+            Vc::upcast(this.chunking_context),
+        ))
     }
 }
@@ -7,7 +7,7 @@ use turbo_tasks_fs::{File, FileSystem};
 use turbopack_core::{
     asset::{Asset, AssetContent},
     chunk::ChunkingContext,
-    code_builder::{Code, CodeBuilder},
+    code_builder::{apply_source_map_source_template, Code, CodeBuilder},
     ident::AssetIdent,
     output::{OutputAsset, OutputAssets},
     source_map::{GenerateSourceMap, OptionSourceMap, SourceMapAsset},
@@ -47,7 +47,9 @@ impl EcmascriptBuildNodeRuntimeChunk {
             );
         };
 
+        let chunk_code_wrapping = this.chunking_context.chunk_code_wrapping().await?;
         let mut code = CodeBuilder::default();
+        chunk_code_wrapping.write_banner(&mut code)?;
         let output_root = output_root.to_string();
         let asset_prefix = this.chunking_context.asset_prefix().await?;
         let asset_prefix = asset_prefix.as_deref().unwrap_or("/");
@@ -84,6 +86,9 @@ impl EcmascriptBuildNodeRuntimeChunk {
             }
         }
 
+        chunk_code_wrapping.write_runtime_injection(&mut code)?;
+        chunk_code_wrapping.write_footer(&mut code)?;
+
         Ok(Code::cell(code.build()))
     }
 }
@@ -142,7 +147,11 @@ impl Asset for EcmascriptBuildNodeRuntimeChunk {
 #[turbo_tasks::value_impl]
 impl GenerateSourceMap for EcmascriptBuildNodeRuntimeChunk {
     #[turbo_tasks::function]
-    fn generate_source_map(self: Vc<Self>) -> Vc<OptionSourceMap> {
-        self.code().generate_source_map()
+    async fn generate_source_map(self: Vc<Self>) -> Result<Vc<OptionSourceMap>> {
+        let this = self.await?;
+        Ok(apply_source_map_source_template(
+            self.code().generate_source_map(),
+            Vc::upcast(this.chunking_context),
+        ))
     }
 }
@@ -12,7 +12,9 @@ use turbopack_core::resolve::{
 
 use crate::{
     resolve_options_context::ResolveOptionsContext,
-    typescript::{apply_tsconfig_resolve_options, tsconfig, tsconfig_resolve_options},
+    typescript::{
+        apply_tsconfig_resolve_options, tsconfig, tsconfig_for_directory, tsconfig_resolve_options,
+    },
 };
 
 const NODE_EXTERNALS: [&str; 63] = [
@@ -291,9 +293,10 @@ pub async fn resolve_options(
     let resolve_options = if options_context_value.enable_typescript {
         let tsconfig = find_context_file(resolve_path, tsconfig()).await?;
         match *tsconfig {
-            FindContextFileResult::Found(path, _) => {
-                apply_tsconfig_resolve_options(resolve_options, tsconfig_resolve_options(*path))
-            }
+            FindContextFileResult::Found(path, _) => apply_tsconfig_resolve_options(
+                resolve_options,
+                tsconfig_resolve_options(tsconfig_for_directory(*path, resolve_path)),
+            ),
             FindContextFileResult::NotFound(_) => resolve_options,
         }
     } else {
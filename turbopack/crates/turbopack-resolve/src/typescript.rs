@@ -3,7 +3,7 @@ use std::{collections::HashMap, fmt::Write, mem::take};
 use anyhow::Result;
 use serde_json::Value as JsonValue;
 use turbo_tasks::{fxindexset, RcStr, ResolvedVc, Value, ValueDefault, Vc};
-use turbo_tasks_fs::{FileContent, FileJsonContent, FileSystemPath};
+use turbo_tasks_fs::{FileContent, FileJsonContent, FileSystemEntryType, FileSystemPath};
 use turbopack_core::{
     asset::Asset,
     context::AssetContext,
@@ -23,7 +23,7 @@ use turbopack_core::{
         pattern::Pattern,
         resolve, AliasPattern, ModuleResolveResult,
     },
-    source::{OptionSource, Source},
+    source::Source,
 };
 
 use crate::ecmascript::get_condition_maps;
@@ -44,19 +44,23 @@ async fn json_only(resolve_options: Vc<ResolveOptions>) -> Result<Vc<ResolveOpti
 
 type TsConfig = (Vc<FileJsonContent>, ResolvedVc<Box<dyn Source>>);
 
+/// Reads `tsconfig` and all configs it `extends`, ordered by precedence. When
+/// a config extends multiple configs, the later ones take precedence over the
+/// earlier ones, like in tsc.
 #[tracing::instrument(skip_all)]
 pub async fn read_tsconfigs(
-    mut data: Vc<FileContent>,
-    mut tsconfig: ResolvedVc<Box<dyn Source>>,
+    data: Vc<FileContent>,
+    tsconfig: ResolvedVc<Box<dyn Source>>,
     resolve_options: Vc<ResolveOptions>,
 ) -> Result<Vec<TsConfig>> {
     let mut configs = Vec::new();
     let resolve_options = json_only(resolve_options);
-    loop {
+    let mut stack = vec![(data, tsconfig)];
+    while let Some((data, tsconfig)) = stack.pop() {
         // tsc ignores empty config files.
         if let FileContent::Content(file) = &*data.await? {
             if file.content().is_empty() {
-                break;
+                continue;
             }
         }
 
@@ -89,12 +93,19 @@ pub async fn read_tsconfigs(
             }
             FileJsonContent::Content(json) => {
                 configs.push((parsed_data, tsconfig));
-                if let Some(extends) = json["extends"].as_str() {
+                let extends = match &json["extends"] {
+                    JsonValue::String(extends) => vec![extends.as_str()],
+                    JsonValue::Array(extends) => {
+                        extends.iter().filter_map(JsonValue::as_str).collect()
+                    }
+                    _ => Vec::new(),
+                };
+                // The stack is processed from the end, so the last extended config
+                // and its own extended configs come first.
+                for extends in extends {
                     let resolved = resolve_extends(*tsconfig, extends, resolve_options).await?;
                     if let Some(source) = *resolved.await? {
-                        data = source.content().file_content();
-                        tsconfig = source;
-                        continue;
+                        stack.push((source.content().file_content(), source));
                     } else {
                         TsConfigIssue {
                             severity: IssueSeverity::Error.resolved_cell(),
@@ -108,7 +119,6 @@ pub async fn read_tsconfigs(
                 }
             }
         }
-        break;
     }
     Ok(configs)
 }
@@ -120,7 +130,7 @@ async fn resolve_extends(
     tsconfig: Vc<Box<dyn Source>>,
     extends: &str,
     resolve_options: Vc<ResolveOptions>,
-) -> Result<Vc<OptionSource>> {
+) -> Result<Vc<Option<ResolvedVc<Box<dyn Source>>>>> {
     let parent_dir = tsconfig.ident().path().parent();
     let request = Request::parse_string(extends.into());
 
@@ -171,7 +181,7 @@ async fn resolve_extends_rooted_or_relative(
     request: Vc<Request>,
     resolve_options: Vc<ResolveOptions>,
     path: &str,
-) -> Result<Vc<OptionSource>> {
+) -> Result<Vc<Option<ResolvedVc<Box<dyn Source>>>>> {
     let mut result = resolve(
         lookup_path,
         Value::new(ReferenceType::TypeScript(
@@ -341,6 +351,60 @@ pub async fn tsconfig_resolve_options(
     .cell())
 }
 
+/// Returns the tsconfig that applies to files in `lookup_path`, starting with
+/// the nearest `tsconfig`. When `lookup_path` is inside a project that the
+/// tsconfig lists in `references`, e.g. a package of a monorepo, the config of
+/// that project applies instead, following its own `references`.
+#[turbo_tasks::function]
+pub async fn tsconfig_for_directory(
+    tsconfig: Vc<FileSystemPath>,
+    lookup_path: Vc<FileSystemPath>,
+) -> Result<Vc<FileSystemPath>> {
+    let content = tsconfig.read().parse_json_with_comments().await?;
+    let FileJsonContent::Content(json) = &*content else {
+        return Ok(tsconfig);
+    };
+    let JsonValue::Array(references) = &json["references"] else {
+        return Ok(tsconfig);
+    };
+
+    let lookup_path_value = lookup_path.await?;
+    let dir = tsconfig.parent();
+    let mut depth = dir.await?.path.len();
+    let mut project = None;
+    for reference in references {
+        let Some(path) = reference["path"].as_str() else {
+            continue;
+        };
+        let Some(path) = *dir.try_join(path.into()).await? else {
+            continue;
+        };
+        // A reference points to a config file, which can have any name, or to a
+        // directory that contains a `tsconfig.json`.
+        let config = if path.await?.path.ends_with(".json") {
+            *path
+        } else {
+            path.join("tsconfig.json".into())
+        };
+        if !matches!(&*config.get_type().await?, FileSystemEntryType::File) {
+            continue;
+        }
+        // Only projects nested deeper than the current one are followed, which
+        // also prevents cycles of references.
+        let project_dir = config.parent().await?;
+        if project_dir.path.len() > depth && lookup_path_value.is_inside_or_equal_ref(&project_dir)
+        {
+            depth = project_dir.path.len();
+            project = Some(config);
+        }
+    }
+
+    Ok(match project {
+        Some(project) => tsconfig_for_directory(project, lookup_path),
+        None => tsconfig,
+    })
+}
+
 #[turbo_tasks::function]
 pub fn tsconfig() -> Vc<Vec<RcStr>> {
     Vc::cell(vec!["tsconfig.json".into(), "jsconfig.json".into()])
@@ -11,8 +11,8 @@ use anyhow::{Context, Result};
 use dunce::canonicalize;
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{
-    debug::ValueDebugFormat, fxindexmap, trace::TraceRawVcs, Completion, RcStr, ResolvedVc,
-    TryJoinIterExt, TurboTasks, Value, Vc,
+    debug::ValueDebugFormat, fxindexmap, trace::TraceRawVcs, Completion, FxIndexMap, RcStr,
+    ResolvedVc, TryJoinIterExt, TurboTasks, Value, Vc,
 };
 use turbo_tasks_bytes::stream::SingleValue;
 use turbo_tasks_env::CommandLineProcessEnv;
@@ -22,8 +22,13 @@ use turbo_tasks_fs::{
 };
 use turbo_tasks_memory::MemoryBackend;
 use turbopack::{
-    ecmascript::TreeShakingMode,
-    module_options::{EcmascriptOptionsContext, ModuleOptionsContext},
+    ecmascript::{
+        references::module_federation::{
+            ModuleFederationOptions, ModuleFederationRemote, ModuleFederationShared,
+        },
+        TreeShakingMode,
+    },
+    module_options::{EcmascriptOptionsContext, ModuleOptionsContext, TypescriptTransformOptions},
     ModuleAssetContext,
 };
 use turbopack_core::{
@@ -31,7 +36,7 @@ use turbopack_core::{
     compile_time_info::CompileTimeInfo,
     condition::ContextCondition,
     context::AssetContext,
-    environment::{Environment, ExecutionEnvironment, NodeJsEnvironment},
+    environment::{BrowserEnvironment, Environment, ExecutionEnvironment, NodeJsEnvironment},
     file_source::FileSource,
     issue::{Issue, IssueDescriptionExt},
     reference_type::{InnerAssets, ReferenceType},
@@ -183,6 +188,32 @@ async fn run(resource: PathBuf, snapshot_mode: IssueSnapshotMode) -> Result<JsRe
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 struct TestOptions {
     tree_shaking_mode: Option<TreeShakingMode>,
+    /// Transforms the code for the browsers of the query instead of the
+    /// current Node.js version, to test how syntax is lowered.
+    browserslist: Option<RcStr>,
+    /// Packages that aren't bundled when they are required in a `try` block.
+    exclude_optional_requires: Option<Vec<RcStr>>,
+    /// The `verbatimModuleSyntax` option of the typescript transform.
+    verbatim_module_syntax: Option<bool>,
+    /// Whether the typescript transform keeps imports only used as types, like
+    /// `importsNotUsedAsValues: "preserve"`.
+    preserve_unused_imports: Option<bool>,
+    /// Module Federation remotes that are consumed.
+    module_federation: Option<TestModuleFederationOptions>,
+    /// Moves static styled-jsx styles and `css` props to CSS modules.
+    extract_styled_jsx: Option<bool>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Serialize, Deserialize, TraceRawVcs, ValueDebugFormat)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TestModuleFederationOptions {
+    /// Maps the name of a remote to its `remoteEntry`, see
+    /// [ModuleFederationRemote::parse].
+    #[serde(default)]
+    remotes: FxIndexMap<RcStr, RcStr>,
+    /// Maps the shared packages to the version that is provided.
+    #[serde(default)]
+    shared: FxIndexMap<RcStr, RcStr>,
 }
 
 #[turbo_tasks::value]
@@ -260,6 +291,20 @@ async fn run_test(prepared_test: Vc<PreparedTest>) -> Result<Vc<RunTestResult>>
         NodeJsEnvironment::default().into(),
     )));
 
+    let preset_env = match &options.browserslist {
+        Some(browserslist) => Environment::new(Value::new(ExecutionEnvironment::Browser(
+            BrowserEnvironment {
+                dom: false,
+                web_worker: false,
+                service_worker: false,
+                browserslist_query: browserslist.clone(),
+                strict_csp: false,
+            }
+            .into(),
+        ))),
+        None => env,
+    };
+
     let compile_time_info = CompileTimeInfo::builder(env)
         .defines(
             compile_time_defines!(
@@ -282,11 +327,51 @@ async fn run_test(prepared_test: Vc<PreparedTest>) -> Result<Vc<RunTestResult>>
         compile_time_info,
         ModuleOptionsContext {
             ecmascript: EcmascriptOptionsContext {
-                enable_typescript_transform: Some(Default::default()),
+                enable_typescript_transform: Some(
+                    TypescriptTransformOptions {
+                        verbatim_module_syntax: options.verbatim_module_syntax.unwrap_or(false),
+                        preserve_unused_imports: options.preserve_unused_imports.unwrap_or(false),
+                        ..Default::default()
+                    }
+                    .cell(),
+                ),
                 import_externals: true,
+                exclude_optional_requires: options
+                    .exclude_optional_requires
+                    .clone()
+                    .map(ResolvedVc::cell),
+                extract_styled_jsx: options.extract_styled_jsx.unwrap_or(false),
+                enable_module_federation: options.module_federation.as_ref().map(
+                    |module_federation| {
+                        ModuleFederationOptions {
+                            share_scope: None,
+                            remotes: module_federation
+                                .remotes
+                                .iter()
+                                .map(|(name, entry)| {
+                                    (name.clone(), ModuleFederationRemote::parse(entry))
+                                })
+                                .collect(),
+                            shared: module_federation
+                                .shared
+                                .iter()
+                                .map(|(name, version)| {
+                                    (
+                                        name.clone(),
+                                        ModuleFederationShared {
+                                            version: Some(version.clone()),
+                                            required_version: None,
+                                        },
+                                    )
+                                })
+                                .collect(),
+                        }
+                        .cell()
+                    },
+                ),
                 ..Default::default()
             },
-            preset_env_versions: Some(env.to_resolved().await?),
+            preset_env_versions: Some(preset_env.to_resolved().await?),
             tree_shaking_mode: options.tree_shaking_mode,
             rules: vec![(
                 ContextCondition::InDirectory("node_modules".into()),
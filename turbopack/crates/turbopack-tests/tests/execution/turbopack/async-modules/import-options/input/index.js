@@ -0,0 +1,17 @@
+it("should import JSON with a type attribute", async () => {
+  const data = await import("./data.json", { with: { type: "json" } });
+  expect(data.default).toEqual({ bundled: true });
+});
+
+it("should accept the legacy assert key", async () => {
+  const data = await import("./data.json", { assert: { type: "json" } });
+  expect(data.default).toEqual({ bundled: true });
+});
+
+it("should pass the options on to natively imported externals", async () => {
+  // Node.js only imports JSON modules with a `type: "json"` attribute.
+  const data = await import("esm-external/json-package/data.json", {
+    with: { type: "json" },
+  });
+  expect(data.default).toEqual({ external: true });
+});
@@ -128,6 +128,22 @@ it("should evaluate NODE_ENV", () => {
   }
 });
 
+function optionalNodeEnv() {
+  return [
+    process?.env?.NODE_ENV,
+    process.env?.NODE_ENV,
+    process?.env.NODE_ENV,
+    process?.env?.["NODE_ENV"],
+    (process?.env)?.["NODE_ENV"],
+    (process.env)["NODE_ENV"],
+  ];
+}
+
+it("should evaluate optional-chained NODE_ENV", () => {
+  expect(optionalNodeEnv()).toEqual(Array(6).fill("development"));
+  expect(optionalNodeEnv.toString()).not.toContain("process");
+});
+
 it("should keep side-effects in if statements", () => {
   {
     let ok = false;
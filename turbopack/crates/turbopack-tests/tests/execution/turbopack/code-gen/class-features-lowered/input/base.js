@@ -0,0 +1,36 @@
+export class Base {
+  static instances = 0;
+  static #registry = [];
+
+  static {
+    this.initialized = true;
+  }
+
+  static register(instance) {
+    Base.#registry.push(instance);
+    this.instances++;
+    return Base.#registry.length;
+  }
+
+  #secret;
+
+  constructor(secret) {
+    this.#secret = secret;
+  }
+
+  get #masked() {
+    return "*".repeat(this.#secret.length);
+  }
+
+  reveal() {
+    return this.#secret;
+  }
+
+  mask() {
+    return this.#masked;
+  }
+
+  static isBase(value) {
+    return #secret in value;
+  }
+}
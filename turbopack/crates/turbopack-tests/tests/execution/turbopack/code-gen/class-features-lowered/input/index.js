@@ -0,0 +1,78 @@
+import { Base } from "./base.js";
+
+class Derived extends Base {
+  static label = `derived of ${super.instances}`;
+  field = this.reveal().toUpperCase();
+
+  #checked() {
+    return Derived.isBase(this);
+  }
+
+  isChecked() {
+    return this.#checked();
+  }
+}
+
+it("should keep brand checks of private fields", () => {
+  expect(Base.isBase(new Base("a"))).toBe(true);
+  expect(Base.isBase(new Derived("b"))).toBe(true);
+  expect(Base.isBase({})).toBe(false);
+  expect(new Derived("c").isChecked()).toBe(true);
+});
+
+it("should throw when private members are used on other objects", () => {
+  expect(() => Base.prototype.reveal.call({})).toThrow(TypeError);
+  expect(() => Base.prototype.mask.call({})).toThrow(TypeError);
+  expect(new Base("abc").mask()).toBe("***");
+});
+
+it("should initialize fields after the super constructor", () => {
+  expect(new Derived("value").field).toBe("VALUE");
+});
+
+it("should throw when fields are read before they are initialized", () => {
+  class Early {
+    first = this.second;
+    second = 1;
+    #late = 2;
+    peek = this.#read();
+
+    #read() {
+      return this.#late;
+    }
+  }
+  expect(new Early().first).toBe(undefined);
+  expect(new Early().peek).toBe(2);
+
+  class TooEarly {
+    peek = this.#read();
+    #late = 2;
+
+    #read() {
+      return this.#late;
+    }
+  }
+  expect(() => new TooEarly()).toThrow(TypeError);
+});
+
+it("should inherit static members", () => {
+  expect(Derived.label).toBe("derived of 0");
+  expect(Derived.initialized).toBe(true);
+  expect(Base.register(new Base("x"))).toBe(1);
+  expect(Derived.register(new Derived("y"))).toBe(2);
+  expect(Derived.instances).toBe(2);
+  expect(Base.instances).toBe(1);
+  expect(Object.prototype.hasOwnProperty.call(Derived, "instances")).toBe(true);
+});
+
+it("should define fields instead of assigning them", () => {
+  class WithSetter {
+    set value(v) {
+      throw new Error("setter should not be called");
+    }
+  }
+  class Defined extends WithSetter {
+    value = 1;
+  }
+  expect(new Defined().value).toBe(1);
+});
@@ -0,0 +1,45 @@
+// The output of the styled-jsx transform and the automatic JSX runtime.
+import _JSXStyle from "styled-jsx/style";
+import { jsx as _jsx } from "react/jsx-runtime";
+import React from "react";
+
+it("should remove static styled-jsx styles", () => {
+  const style = _jsx(_JSXStyle, {
+    id: "1a2b3c",
+    children: "p.jsx-1a2b3c{color:red}",
+  });
+  expect(style).toBe(null);
+});
+
+it("should keep styled-jsx styles with interpolations", () => {
+  const color = "red";
+  const style = _jsx(_JSXStyle, {
+    id: "4d5e6f",
+    dynamic: [color],
+    children: `p.__jsx-style-dynamic-selector{color:${color}}`,
+  });
+  expect(style.type).toBe(_JSXStyle);
+});
+
+it("should replace static css props by a class name", () => {
+  const element = _jsx("div", { css: "color: red", id: "title" });
+  expect(element.props).not.toHaveProperty("css");
+  expect(element.props.id).toBe("title");
+  expect(element.props.className).toMatch(/^css-[0-9a-f]+$/);
+
+  const other = React.createElement(
+    "div",
+    { className: "title", css: "color: red" },
+    "text"
+  );
+  expect(other.props.className).toBe(`title ${element.props.className}`);
+});
+
+it("should keep dynamic css props", () => {
+  const color = "red";
+  const element = _jsx("div", { css: `color: ${color}` });
+  expect(element.props.css).toBe("color: red");
+
+  const Component = (props) => props;
+  expect(_jsx(Component, { css: "color: red" }).props.css).toBe("color: red");
+});
@@ -17,3 +17,16 @@ it("should still be possible to call the function with a different context", ()
   expect(module.getThis.call(obj)).toBe(obj);
   expect((0, module.getThis)()).toBe(undefined);
 });
+
+it("should not have this context when calling a binding in parentheses", () => {
+  expect((getThis)()).toBe(undefined);
+  expect(((getThis))()).toBe(undefined);
+});
+
+it("should not have this context when calling a binding optionally", () => {
+  expect(getThis?.()).toBe(undefined);
+});
+
+it("should not have this context when calling a binding as a template tag", () => {
+  expect(getThis`template`).toBe(undefined);
+});
@@ -0,0 +1,6 @@
+import { local } from "./module.ts";
+
+it("should keep imports only used as types as side effect imports", () => {
+  expect(local).toBe(1);
+  expect(globalThis.usedAsTypeEvaluated).toBe(true);
+});
@@ -0,0 +1,3 @@
+import { UsedAsType } from "./used-as-type";
+
+export const local: UsedAsType = 1;
@@ -0,0 +1,3 @@
+globalThis.usedAsTypeEvaluated = true;
+
+export type UsedAsType = number;
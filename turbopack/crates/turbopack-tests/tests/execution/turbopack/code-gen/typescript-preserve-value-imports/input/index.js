@@ -0,0 +1,11 @@
+import { shape, size } from "./module.ts";
+
+it("should preserve value imports that are only used as types", () => {
+  expect(shape).toBe(undefined);
+  expect(globalThis.shapeModuleEvaluated).toBe(true);
+});
+
+it("should elide type-only imports", () => {
+  expect(size).toBe(1);
+  expect(globalThis.sizeModuleEvaluated).toBe(undefined);
+});
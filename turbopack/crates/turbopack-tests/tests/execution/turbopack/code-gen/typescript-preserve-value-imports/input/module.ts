@@ -0,0 +1,8 @@
+// next-core maps `preserveValueImports` with `isolatedModules` from the
+// tsconfig.json to `verbatimModuleSyntax`, which keeps value imports that are
+// only used as types.
+import { Shape } from "./shape";
+import type { Size } from "./size";
+
+export const shape: Shape | undefined = undefined;
+export const size: Size = 1;
@@ -0,0 +1,3 @@
+globalThis.shapeModuleEvaluated = true;
+
+export class Shape {}
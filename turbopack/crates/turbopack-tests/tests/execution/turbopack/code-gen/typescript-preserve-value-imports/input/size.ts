@@ -0,0 +1,3 @@
+globalThis.sizeModuleEvaluated = true;
+
+export type Size = number;
@@ -0,0 +1,21 @@
+import * as reexports from "./reexports.ts";
+
+it("should keep value re-exports", () => {
+  expect(reexports.value).toBe(42);
+  expect(reexports.renamed).toBe(42);
+  expect(reexports.fromStar).toBe("star");
+});
+
+it("should elide type-only re-exports", () => {
+  expect(Object.keys(reexports).sort()).toEqual([
+    "fromStar",
+    "local",
+    "renamed",
+    "value",
+  ]);
+});
+
+it("should not evaluate modules only imported for types", () => {
+  expect(globalThis.typeOnlyModuleEvaluated).toBe(undefined);
+  expect(reexports.local).toBe(1);
+});
@@ -0,0 +1,11 @@
+import { value, type ValueType } from "./values";
+import type { OnlyType } from "./types";
+import { UsedAsType } from "./used-as-type";
+
+export { value };
+export type { ValueType, OnlyType };
+export { value as renamed, type ValueType as RenamedType } from "./values";
+export type { OtherType } from "./types";
+export * from "./star";
+
+export const local: UsedAsType = 1;
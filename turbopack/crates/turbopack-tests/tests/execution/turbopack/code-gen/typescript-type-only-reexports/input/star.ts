@@ -0,0 +1,3 @@
+export type StarType = string;
+
+export const fromStar: StarType = "star";
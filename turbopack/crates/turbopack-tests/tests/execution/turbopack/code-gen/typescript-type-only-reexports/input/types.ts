@@ -0,0 +1,4 @@
+globalThis.typeOnlyModuleEvaluated = true;
+
+export type OnlyType = string;
+export type OtherType = boolean;
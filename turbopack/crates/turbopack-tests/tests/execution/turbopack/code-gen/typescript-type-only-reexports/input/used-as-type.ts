@@ -0,0 +1,3 @@
+globalThis.typeOnlyModuleEvaluated = true;
+
+export type UsedAsType = number;
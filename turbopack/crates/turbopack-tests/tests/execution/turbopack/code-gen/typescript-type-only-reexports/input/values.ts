@@ -0,0 +1,3 @@
+export type ValueType = number;
+
+export const value: ValueType = 42;
@@ -0,0 +1,11 @@
+import { label, local } from "./module.ts";
+
+it("should keep unused imports that are not marked as type-only", () => {
+  expect(local).toBe(42);
+  expect(globalThis.unusedModuleEvaluated).toBe(true);
+});
+
+it("should elide type-only imports", () => {
+  expect(label).toBe("label");
+  expect(globalThis.typeOnlyModuleEvaluated).toBe(undefined);
+});
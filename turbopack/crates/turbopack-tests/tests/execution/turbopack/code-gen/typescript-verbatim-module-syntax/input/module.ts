@@ -0,0 +1,6 @@
+import { unused } from "./unused";
+import type { OnlyType } from "./types";
+import { value, type ValueType } from "./values";
+
+export const local: ValueType = value;
+export const label: OnlyType = "label";
@@ -0,0 +1,3 @@
+globalThis.unusedModuleEvaluated = true;
+
+export const unused = "unused";
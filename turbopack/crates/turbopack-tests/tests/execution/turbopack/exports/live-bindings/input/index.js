@@ -0,0 +1,43 @@
+import replaced, {
+  arrow,
+  context,
+  counter,
+  handler,
+  increment,
+  renamed,
+  replaceDefault,
+  update,
+} from "./module.js";
+import * as ns from "./module.js";
+import * as reexport from "./reexport.js";
+
+it("should keep the identity of bound functions", () => {
+  expect(ns.handler).toBe(handler);
+  expect(reexport.handler).toBe(handler);
+  expect(require("./module.js").handler).toBe(handler);
+  expect(require("./reexport.js").handler).toBe(handler);
+  expect(arrow()).toBe(handler);
+  expect(handler()).toBe(context);
+});
+
+it("should update live bindings", () => {
+  expect(counter).toBe(0);
+  increment();
+  expect(counter).toBe(1);
+  expect(ns.counter).toBe(1);
+  expect(reexport.counter).toBe(1);
+});
+
+it("should update renamed live bindings", () => {
+  expect(renamed).toBe("before");
+  update();
+  expect(renamed).toBe("after");
+  expect(reexport.renamed).toBe("after");
+});
+
+it("should update reassigned default function declarations", () => {
+  expect(replaced()).toBe("original");
+  replaceDefault();
+  expect(replaced()).toBe("replaced");
+  expect(ns.default()).toBe("replaced");
+});
@@ -0,0 +1,25 @@
+function handle() {
+  return this;
+}
+
+export const context = {};
+export const handler = handle.bind(context);
+export const arrow = () => handler;
+
+export let counter = 0;
+export function increment() {
+  counter++;
+}
+
+export default function replaced() {
+  return "original";
+}
+export function replaceDefault() {
+  replaced = () => "replaced";
+}
+
+let value = "before";
+export { value as renamed };
+export function update() {
+  value = "after";
+}
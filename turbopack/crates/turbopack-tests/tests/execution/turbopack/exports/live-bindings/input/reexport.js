@@ -0,0 +1,2 @@
+export { handler, arrow } from "./module.js";
+export * from "./module.js";
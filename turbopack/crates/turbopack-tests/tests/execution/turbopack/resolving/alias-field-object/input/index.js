@@ -0,0 +1,30 @@
+import * as pkg from "package";
+import feature from "package/feature";
+
+it("should follow the alias field for the file that the exports field resolves to with a key without extension", () => {
+  expect(pkg.env).toBe("browser");
+});
+
+it("should follow the alias field for keys that are not normalized", () => {
+  expect(pkg.logger).toBe("browser logger");
+});
+
+it("should follow the alias field for the directory of an index file", () => {
+  expect(feature).toBe("browser feature");
+  expect(pkg.feature).toBe("browser feature");
+});
+
+it("should import modules that are aliased to false as empty modules", () => {
+  expect(pkg.ignoredModule).toEqual({});
+  expect(pkg.ignoredModuleDefault).toBeUndefined();
+  expect(pkg.requiredIgnoredModule).toEqual({});
+});
+
+it("should import files that are aliased to false as empty modules", () => {
+  expect(pkg.ignoredFile).toEqual({});
+  expect(pkg.ignoredFileExport).toBeUndefined();
+});
+
+it("should import modules that are aliased to false as empty modules with import()", async () => {
+  await expect(pkg.importIgnoredModule()).resolves.toEqual({});
+});
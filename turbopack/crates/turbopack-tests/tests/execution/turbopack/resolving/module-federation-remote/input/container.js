@@ -0,0 +1,12 @@
+// The container is already available, so the remote entry script isn't loaded.
+globalThis.app1 = {
+  calls: [],
+  init(shareScope) {
+    this.calls.push("init");
+    this.shareScope = shareScope;
+  },
+  async get(exposed) {
+    this.calls.push(`get ${exposed}`);
+    return () => ({ exposed, default: "remote" });
+  },
+};
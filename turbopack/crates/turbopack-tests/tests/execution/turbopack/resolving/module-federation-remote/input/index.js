@@ -0,0 +1,28 @@
+import "./container.js";
+
+it("should load exposed modules from the remote container", async () => {
+  const button = await import("app1/Button");
+  expect(button.exposed).toBe("./Button");
+  expect(button.default).toBe("remote");
+
+  const root = await import("app1");
+  expect(root.exposed).toBe(".");
+});
+
+it("should initialize the container once with the share scope", async () => {
+  await import("app1/Button");
+  await import("app1/Other");
+  const { calls, shareScope } = globalThis.app1;
+  expect(calls.filter((call) => call === "init")).toEqual(["init"]);
+  expect(calls.indexOf("init")).toBe(0);
+  expect(calls).toContain("get ./Other");
+  expect(shareScope).toBe(globalThis.__turbopack_share_scopes__.default);
+});
+
+it("should provide shared packages to the share scope", async () => {
+  const { shareScope } = globalThis.app1;
+  const shared = shareScope["shared-dep"]["1.2.3"];
+  expect(shared.from).toBe("turbopack");
+  const factory = await shared.get();
+  expect(factory()).toEqual({ name: "shared-dep" });
+});
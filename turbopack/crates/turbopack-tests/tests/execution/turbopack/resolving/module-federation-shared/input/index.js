@@ -0,0 +1,19 @@
+import "./scope.js";
+
+it("should consume the highest compatible version from the share scope", async () => {
+  const sharedDep = await import("shared-dep");
+  expect(sharedDep.version).toBe("1.9.0");
+});
+
+it("should consume the local package without a compatible version", async () => {
+  const otherDep = await import("other-dep");
+  expect(otherDep.version).toBe("1.0.0");
+});
+
+it("should provide the local packages to the share scope", async () => {
+  await import("shared-dep");
+  const shareScope = globalThis.__turbopack_share_scopes__.default;
+  expect(shareScope["shared-dep"]["1.2.3"].from).toBe("turbopack");
+  const factory = await shareScope["shared-dep"]["1.2.3"].get();
+  expect(factory()).toEqual({ name: "shared-dep", version: "1.2.3" });
+});
@@ -0,0 +1,15 @@
+// The versions that the host provides before the shared packages are consumed.
+const scopes = (globalThis.__turbopack_share_scopes__ ??= {});
+const shareScope = (scopes.default ??= {});
+
+function provide(name, version) {
+  (shareScope[name] ??= {})[version] = {
+    from: "host",
+    get: async () => () => ({ name, version }),
+  };
+}
+
+provide("shared-dep", "1.1.0");
+provide("shared-dep", "1.9.0");
+provide("shared-dep", "2.0.0");
+provide("other-dep", "2.0.0");
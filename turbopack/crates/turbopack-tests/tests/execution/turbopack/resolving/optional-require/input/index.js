@@ -0,0 +1,35 @@
+it("should not bundle excluded packages that are required in a try block", () => {
+  let error;
+  try {
+    require("optional-dep");
+  } catch (e) {
+    error = e;
+  }
+  expect(error).toBeInstanceOf(Error);
+  expect(error.code).toBe("MODULE_NOT_FOUND");
+  expect(error.message).toBe("Cannot find module 'optional-dep'");
+});
+
+it("should not bundle files of excluded packages that are required in a try block", () => {
+  let error;
+  try {
+    require("optional-dep/feature");
+  } catch (e) {
+    error = e;
+  }
+  expect(error.code).toBe("MODULE_NOT_FOUND");
+});
+
+it("should bundle packages that are not excluded", () => {
+  let value;
+  try {
+    value = require("other-dep");
+  } catch (e) {
+    value = e;
+  }
+  expect(value).toBe("other");
+});
+
+it("should bundle excluded packages that are required outside of a try block", () => {
+  expect(require("optional-dep")).toBe("optional");
+});
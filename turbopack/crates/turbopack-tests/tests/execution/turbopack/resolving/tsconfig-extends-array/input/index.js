@@ -0,0 +1,10 @@
+import a from "@a";
+import b from "@b";
+
+it("should read paths of all extended configs", () => {
+  expect(a).toBe("a");
+});
+
+it("should prefer paths of later extended configs", () => {
+  expect(b).toBe("b");
+});
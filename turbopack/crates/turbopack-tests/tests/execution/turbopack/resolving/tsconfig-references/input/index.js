@@ -0,0 +1,10 @@
+import value from "#value";
+import libValue from "./packages/lib/src/index";
+
+it("should resolve paths of the nearest tsconfig", () => {
+  expect(value).toBe("root");
+});
+
+it("should resolve paths of referenced projects", () => {
+  expect(libValue).toBe("lib");
+});
@@ -96,6 +96,8 @@ struct SnapshotOptions {
     use_swc_css: bool,
     #[serde(default)]
     tree_shaking_mode: Option<TreeShakingMode>,
+    #[serde(default)]
+    strict_csp: bool,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -123,6 +125,7 @@ impl Default for SnapshotOptions {
             environment: Default::default(),
             use_swc_css: Default::default(),
             tree_shaking_mode: Default::default(),
+            strict_csp: Default::default(),
         }
     }
 }
@@ -215,6 +218,7 @@ async fn run_test(resource: RcStr) -> Result<Vc<FileSystemPath>> {
                     web_worker: false,
                     service_worker: false,
                     browserslist_query: options.browserslist.into(),
+                    strict_csp: options.strict_csp,
                 }
                 .into(),
             )
@@ -1,4 +1,5 @@
 use std::{
+    cmp::Reverse,
     net::{SocketAddr, SocketAddrV4, TcpListener, TcpStream},
     sync::{Arc, Mutex},
     thread::spawn,
@@ -9,7 +10,7 @@ use serde::{Deserialize, Serialize};
 use tungstenite::{accept, Message};
 
 use crate::{
-    store::SpanId,
+    store::{SpanId, Store},
     store_container::StoreContainer,
     u64_string,
     viewer::{Update, ViewLineUpdate, ViewMode, Viewer},
@@ -43,6 +44,21 @@ pub enum ServerToClientMessage {
         args: Vec<(String, String)>,
         path: Vec<String>,
     },
+    TopSelfTime {
+        entries: Vec<TopSelfTimeEntry>,
+    },
+}
+
+/// The spans of a name, aggregated over the whole trace.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TopSelfTimeEntry {
+    pub name: String,
+    pub count: usize,
+    pub self_time: u64,
+    /// The time turbo-tasks functions spent waiting for other tasks during
+    /// their executions, in microseconds. It's zero for other spans.
+    pub wait_time: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -67,6 +83,9 @@ pub enum ClientToServerMessage {
         #[serde(with = "u64_string")]
         id: SpanId,
     },
+    TopSelfTime {
+        count: usize,
+    },
     Ack,
     CheckForMoreData,
 }
@@ -337,6 +356,17 @@ fn handle_connection(
 
                         continue;
                     }
+                    ClientToServerMessage::TopSelfTime { count } => {
+                        let message = {
+                            let store = state.store.read();
+                            ServerToClientMessage::TopSelfTime {
+                                entries: top_self_time(&store, count),
+                            }
+                        };
+                        let message = serde_json::to_string(&message).unwrap();
+                        websocket.send(Message::Text(message))?;
+                        continue;
+                    }
                     ClientToServerMessage::Ack => {
                         ready_for_update = true;
                         if update_skipped {
@@ -367,3 +397,31 @@ fn handle_connection(
         }
     }
 }
+
+/// Aggregates all spans by name and returns the `count` names with the highest
+/// self time, i.e. the time spent in the spans but not in their children.
+fn top_self_time(store: &Store, count: usize) -> Vec<TopSelfTimeEntry> {
+    let mut entries = store
+        .root_span()
+        .bottom_up()
+        .map(|bottom_up| {
+            let wait_time = bottom_up
+                .spans()
+                .filter_map(|span| {
+                    span.args()
+                        .find(|&(key, _)| key == "wait_time")
+                        .and_then(|(_, value)| value.parse::<u64>().ok())
+                })
+                .sum();
+            TopSelfTimeEntry {
+                name: bottom_up.nice_name().1.to_string(),
+                count: bottom_up.count(),
+                self_time: bottom_up.self_time(),
+                wait_time,
+            }
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| Reverse(entry.self_time));
+    entries.truncate(count);
+    entries
+}
@@ -7,6 +7,8 @@
 
 pub mod exit;
 mod flavor;
+#[cfg(feature = "otlp")]
+pub mod otlp;
 pub mod raw_trace;
 pub mod trace_writer;
 pub mod tracing;
@@ -0,0 +1,532 @@
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError};
+use serde_json::{json, Value as JsonValue};
+use tracing::{span, Subscriber};
+use tracing_subscriber::{registry::LookupSpan, Layer};
+
+use crate::{raw_trace::ValuesVisitor, tracing::TraceValue};
+
+/// The maximum number of spans that are sent in a single export request.
+const MAX_BATCH_SIZE: usize = 512;
+
+/// How long finished spans are buffered before they are exported.
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The maximum number of finished spans that wait for the export. Further
+/// spans are dropped, so a slow or unreachable collector can't grow the memory
+/// without bounds.
+const MAX_QUEUE_SIZE: usize = 65_536;
+
+/// The configuration of the OTLP exporter. It follows the environment
+/// variables of the OpenTelemetry SDKs, see [OtlpConfig::from_env].
+#[derive(Clone, Debug)]
+pub struct OtlpConfig {
+    /// The base URL of the OTLP/HTTP endpoint of the collector. Spans are sent
+    /// to `{endpoint}/v1/traces`.
+    pub endpoint: String,
+    /// Additional headers of the export requests, e.g. for authentication.
+    pub headers: Vec<(String, String)>,
+    /// The attributes of the resource that produces the spans, including
+    /// `service.name`.
+    pub resource_attributes: Vec<(String, String)>,
+}
+
+impl OtlpConfig {
+    /// Reads the configuration from `OTEL_EXPORTER_OTLP_ENDPOINT`,
+    /// `OTEL_EXPORTER_OTLP_HEADERS`, `OTEL_SERVICE_NAME` and
+    /// `OTEL_RESOURCE_ATTRIBUTES`. The `service.name` defaults to
+    /// `default_service_name`.
+    ///
+    /// Returns `None` when no endpoint is configured.
+    pub fn from_env(default_service_name: &str) -> Option<Self> {
+        let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok()?;
+        let headers = std::env::var("OTEL_EXPORTER_OTLP_HEADERS")
+            .map(|headers| parse_key_value_list(&headers))
+            .unwrap_or_default();
+        let mut resource_attributes = std::env::var("OTEL_RESOURCE_ATTRIBUTES")
+            .map(|attributes| parse_key_value_list(&attributes))
+            .unwrap_or_default();
+        let service_name = std::env::var("OTEL_SERVICE_NAME").ok();
+        if service_name.is_some() || !resource_attributes.iter().any(|(k, _)| k == "service.name") {
+            resource_attributes.retain(|(key, _)| key != "service.name");
+            resource_attributes.push((
+                "service.name".to_string(),
+                service_name.unwrap_or_else(|| default_service_name.to_string()),
+            ));
+        }
+        Some(Self {
+            endpoint,
+            headers,
+            resource_attributes,
+        })
+    }
+}
+
+/// Parses a `key1=value1,key2=value2` list, as used by the OpenTelemetry
+/// environment variables.
+fn parse_key_value_list(list: &str) -> Vec<(String, String)> {
+    list.split(',')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+enum ExportMessage {
+    Span(FinishedSpan),
+    /// Exports the remaining spans and stops the exporter.
+    Shutdown,
+}
+
+/// A span that has finished and is ready to be exported.
+struct FinishedSpan {
+    trace_id: u128,
+    span_id: u64,
+    parent_span_id: Option<u64>,
+    name: Cow<'static, str>,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(Cow<'static, str>, TraceValue<'static>)>,
+}
+
+/// The data of an open span, stored in the extensions of the span.
+struct OpenSpan {
+    trace_id: u128,
+    span_id: u64,
+    parent_span_id: Option<u64>,
+    name: &'static str,
+    start: SystemTime,
+    values: Vec<(Cow<'static, str>, TraceValue<'static>)>,
+}
+
+/// A tracing layer that exports spans to an OpenTelemetry collector via
+/// OTLP/HTTP.
+///
+/// Spans of turbo-tasks functions, route compilations and HMR updates are
+/// mapped to spans with descriptive names and semantic attributes, see
+/// [map_span]. Other spans are exported as they are.
+pub struct OtlpLayer<S: Subscriber + for<'a> LookupSpan<'a>> {
+    span_tx: Sender<ExportMessage>,
+    /// The number of spans that were dropped because the queue was full.
+    dropped_spans: Arc<AtomicU64>,
+    _phantom: PhantomData<fn(S)>,
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> OtlpLayer<S> {
+    /// Creates the layer and starts the exporter in a background thread. The
+    /// remaining spans are exported when the returned guard is dropped.
+    pub fn new(config: OtlpConfig) -> (Self, OtlpExporterGuard) {
+        let (span_tx, span_rx) = bounded(MAX_QUEUE_SIZE);
+        let dropped_spans = Arc::new(AtomicU64::new(0));
+        let handle = {
+            let dropped_spans = dropped_spans.clone();
+            std::thread::spawn(move || export_loop(config, span_rx, &dropped_spans))
+        };
+        (
+            Self {
+                span_tx: span_tx.clone(),
+                dropped_spans,
+                _phantom: PhantomData,
+            },
+            OtlpExporterGuard {
+                span_tx: Some(span_tx),
+                handle: Some(handle),
+            },
+        )
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for OtlpLayer<S> {
+    fn on_new_span(
+        &self,
+        attrs: &span::Attributes<'_>,
+        id: &span::Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<OpenSpan>()
+                .map(|parent| (parent.trace_id, parent.span_id))
+        });
+        let mut values = ValuesVisitor::new();
+        attrs.values().record(&mut values);
+        span.extensions_mut().insert(OpenSpan {
+            trace_id: parent.map_or_else(rand::random, |(trace_id, _)| trace_id),
+            span_id: random_span_id(),
+            parent_span_id: parent.map(|(_, span_id)| span_id),
+            name: attrs.metadata().name(),
+            start: SystemTime::now(),
+            values: values.values,
+        });
+    }
+
+    fn on_record(
+        &self,
+        id: &span::Id,
+        record: &span::Record<'_>,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        if let Some(open) = span.extensions_mut().get_mut::<OpenSpan>() {
+            let mut values = ValuesVisitor::new();
+            record.record(&mut values);
+            open.values.extend(values.values);
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(open) = span.extensions_mut().remove::<OpenSpan>() else {
+            return;
+        };
+        let (name, attributes) = map_span(open.name, open.values);
+        let result = self.span_tx.try_send(ExportMessage::Span(FinishedSpan {
+            trace_id: open.trace_id,
+            span_id: open.span_id,
+            parent_span_id: open.parent_span_id,
+            name,
+            start: open.start,
+            end: SystemTime::now(),
+            attributes,
+        }));
+        if let Err(TrySendError::Full(_)) = result {
+            self.dropped_spans.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+fn random_span_id() -> u64 {
+    // A span id of zero is invalid.
+    loop {
+        let id = rand::random();
+        if id != 0 {
+            return id;
+        }
+    }
+}
+
+/// Maps the well-known spans of Turbopack and Next.js to OpenTelemetry span
+/// names and attributes:
+///
+/// * `turbo_tasks::function` spans are named after the function, with the function in
+///   `turbo_tasks.function`.
+/// * Endpoint spans, e.g. `app endpoint HTML`, become `compile route` spans with the route in
+///   `next.route` and the kind of endpoint in `next.route.kind`.
+/// * `HMR subscription` spans, which cover the computation of an update, become `hmr update` spans
+///   with the subscription in `turbopack.hmr.identifier`.
+fn map_span(
+    name: &'static str,
+    mut values: Vec<(Cow<'static, str>, TraceValue<'static>)>,
+) -> (
+    Cow<'static, str>,
+    Vec<(Cow<'static, str>, TraceValue<'static>)>,
+) {
+    fn rename(
+        values: &mut [(Cow<'static, str>, TraceValue<'static>)],
+        from: &str,
+        to: &'static str,
+    ) -> Option<String> {
+        let (key, value) = values.iter_mut().find(|(key, _)| *key == *from)?;
+        *key = to.into();
+        match value {
+            TraceValue::String(value) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+
+    match name {
+        "turbo_tasks::function" | "turbo_tasks::resolve_call" => {
+            let function = rename(&mut values, "name", "turbo_tasks.function");
+            let name = match (name, function) {
+                ("turbo_tasks::function", Some(function)) => function.into(),
+                (_, Some(function)) => format!("resolve {function}").into(),
+                (name, None) => name.into(),
+            };
+            (name, values)
+        }
+        _ if name.ends_with("endpoint") || name.contains(" endpoint ") => {
+            rename(&mut values, "name", "next.route");
+            values.push(("next.route.kind".into(), TraceValue::String(name.into())));
+            ("compile route".into(), values)
+        }
+        "HMR subscription" => {
+            rename(&mut values, "identifier", "turbopack.hmr.identifier");
+            ("hmr update".into(), values)
+        }
+        _ => (name.into(), values),
+    }
+}
+
+/// Exports the spans in batches until the exporter is shut down.
+///
+/// Only the first failed export is reported, as the collector is likely
+/// unreachable for the following ones too.
+fn export_loop(config: OtlpConfig, span_rx: Receiver<ExportMessage>, dropped_spans: &AtomicU64) {
+    let client = reqwest::blocking::Client::new();
+    let url = format!("{}/v1/traces", config.endpoint.trim_end_matches('/'));
+    let resource = json!({
+        "attributes": config
+            .resource_attributes
+            .iter()
+            .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+            .collect::<Vec<_>>(),
+    });
+
+    let mut batch = Vec::with_capacity(MAX_BATCH_SIZE);
+    let mut closed = false;
+    let mut reported_failure = false;
+    while !closed {
+        let deadline = std::time::Instant::now() + EXPORT_INTERVAL;
+        while batch.len() < MAX_BATCH_SIZE {
+            match span_rx.recv_deadline(deadline) {
+                Ok(ExportMessage::Span(span)) => batch.push(span),
+                Err(RecvTimeoutError::Timeout) => break,
+                Ok(ExportMessage::Shutdown) | Err(RecvTimeoutError::Disconnected) => {
+                    closed = true;
+                    break;
+                }
+            }
+        }
+        if batch.is_empty() {
+            continue;
+        }
+        let body = json!({
+            "resourceSpans": [{
+                "resource": resource,
+                "scopeSpans": [{
+                    "scope": { "name": "turbopack" },
+                    "spans": batch.drain(..).map(span_to_json).collect::<Vec<_>>(),
+                }],
+            }],
+        });
+        let mut request = client.post(&url).json(&body);
+        for (key, value) in &config.headers {
+            request = request.header(key, value);
+        }
+        // Failing to export must not affect the build.
+        if let Err(err) = request
+            .send()
+            .and_then(|response| response.error_for_status())
+        {
+            if !reported_failure {
+                reported_failure = true;
+                eprintln!(
+                    "Failed to export spans to {url}, further failures aren't reported: {err}"
+                );
+            }
+        }
+    }
+
+    let dropped_spans = dropped_spans.load(Ordering::Relaxed);
+    if dropped_spans > 0 {
+        eprintln!(
+            "Dropped {dropped_spans} spans, as they were finished faster than they could be \
+             exported to {url}"
+        );
+    }
+}
+
+fn span_to_json(span: FinishedSpan) -> JsonValue {
+    fn unix_nanos(time: SystemTime) -> String {
+        time.duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+            .to_string()
+    }
+
+    let mut json = json!({
+        "traceId": format!("{:032x}", span.trace_id),
+        "spanId": format!("{:016x}", span.span_id),
+        "name": span.name,
+        // SPAN_KIND_INTERNAL
+        "kind": 1,
+        "startTimeUnixNano": unix_nanos(span.start),
+        "endTimeUnixNano": unix_nanos(span.end),
+        "attributes": span
+            .attributes
+            .iter()
+            .map(|(key, value)| json!({ "key": key, "value": attribute_value(value) }))
+            .collect::<Vec<_>>(),
+    });
+    if let Some(parent_span_id) = span.parent_span_id {
+        json["parentSpanId"] = format!("{:016x}", parent_span_id).into();
+    }
+    json
+}
+
+fn attribute_value(value: &TraceValue<'_>) -> JsonValue {
+    match value {
+        TraceValue::String(value) => json!({ "stringValue": value }),
+        // 64 bit integers are encoded as strings in the JSON encoding of OTLP.
+        TraceValue::Int(value) => json!({ "intValue": value.to_string() }),
+        TraceValue::UInt(value) => json!({ "intValue": value.to_string() }),
+        TraceValue::Float(value) => json!({ "doubleValue": value }),
+        TraceValue::Bool(value) => json!({ "boolValue": value }),
+    }
+}
+
+/// Exports the remaining spans and stops the exporter when dropped.
+pub struct OtlpExporterGuard {
+    span_tx: Option<Sender<ExportMessage>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for OtlpExporterGuard {
+    fn drop(&mut self) {
+        let _ = self.span_tx.take().unwrap().send(ExportMessage::Shutdown);
+        let _ = self.handle.take().unwrap().join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use serde_json::json;
+
+    use super::{map_span, parse_key_value_list, span_to_json, FinishedSpan};
+    use crate::tracing::TraceValue;
+
+    fn string(key: &'static str, value: &'static str) -> (Cow<'static, str>, TraceValue<'static>) {
+        (key.into(), TraceValue::String(value.into()))
+    }
+
+    /// Maps a span and formats its attributes as `key=value`.
+    fn mapped(
+        name: &'static str,
+        values: Vec<(Cow<'static, str>, TraceValue<'static>)>,
+    ) -> (String, Vec<String>) {
+        let (name, values) = map_span(name, values);
+        (
+            name.into_owned(),
+            values
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parses_key_value_lists() {
+        assert_eq!(
+            parse_key_value_list("a=1, b = 2,invalid,c=x=y,"),
+            vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "2".to_string()),
+                ("c".to_string(), "x=y".to_string()),
+            ]
+        );
+        assert_eq!(parse_key_value_list(""), vec![]);
+    }
+
+    #[test]
+    fn maps_turbo_tasks_functions() {
+        assert_eq!(
+            mapped(
+                "turbo_tasks::function",
+                vec![string("name", "Project::entrypoints")]
+            ),
+            (
+                "Project::entrypoints".to_string(),
+                vec!["turbo_tasks.function=Project::entrypoints".to_string()]
+            )
+        );
+        assert_eq!(
+            mapped(
+                "turbo_tasks::resolve_call",
+                vec![string("name", "Project::entrypoints")]
+            ),
+            (
+                "resolve Project::entrypoints".to_string(),
+                vec!["turbo_tasks.function=Project::entrypoints".to_string()]
+            )
+        );
+        assert_eq!(
+            mapped("turbo_tasks::function", vec![]),
+            ("turbo_tasks::function".to_string(), vec![])
+        );
+    }
+
+    #[test]
+    fn maps_routes_and_hmr_updates() {
+        assert_eq!(
+            mapped("app endpoint HTML", vec![string("name", "/blog/[slug]")]),
+            (
+                "compile route".to_string(),
+                vec![
+                    "next.route=/blog/[slug]".to_string(),
+                    "next.route.kind=app endpoint HTML".to_string(),
+                ]
+            )
+        );
+        assert_eq!(
+            mapped("HMR subscription", vec![string("identifier", "app/page")]),
+            (
+                "hmr update".to_string(),
+                vec!["turbopack.hmr.identifier=app/page".to_string()]
+            )
+        );
+        assert_eq!(
+            mapped("other", vec![("count".into(), TraceValue::UInt(1))]),
+            ("other".to_string(), vec!["count=1".to_string()])
+        );
+    }
+
+    #[test]
+    fn encodes_spans_as_otlp_json() {
+        let span = FinishedSpan {
+            trace_id: 1,
+            span_id: 2,
+            parent_span_id: Some(3),
+            name: "compile route".into(),
+            start: UNIX_EPOCH + Duration::from_nanos(10),
+            end: UNIX_EPOCH + Duration::from_nanos(20),
+            attributes: vec![
+                string("next.route", "/"),
+                ("size".into(), TraceValue::UInt(u64::MAX)),
+                ("ratio".into(), TraceValue::Float(0.5)),
+                ("cached".into(), TraceValue::Bool(true)),
+            ],
+        };
+        assert_eq!(
+            span_to_json(span),
+            json!({
+                "traceId": "00000000000000000000000000000001",
+                "spanId": "0000000000000002",
+                "parentSpanId": "0000000000000003",
+                "name": "compile route",
+                "kind": 1,
+                "startTimeUnixNano": "10",
+                "endTimeUnixNano": "20",
+                "attributes": [
+                    { "key": "next.route", "value": { "stringValue": "/" } },
+                    { "key": "size", "value": { "intValue": "18446744073709551615" } },
+                    { "key": "ratio", "value": { "doubleValue": 0.5 } },
+                    { "key": "cached", "value": { "boolValue": true } },
+                ],
+            })
+        );
+    }
+}
@@ -6,7 +6,7 @@ use turbopack_core::{
     chunk::{AsyncModuleInfo, ChunkItem, ChunkType, ChunkableModule, ChunkingContext},
     context::AssetContext,
     ident::AssetIdent,
-    module::{Module, OptionModule},
+    module::Module,
     reference::ModuleReferences,
     reference_type::ReferenceType,
     resolve::{origin::ResolveOrigin, parse::Request},
@@ -172,7 +172,10 @@ impl ResolveOrigin for WebAssemblyModuleAsset {
     }
 
     #[turbo_tasks::function]
-    fn get_inner_asset(self: Vc<Self>, request: Vc<Request>) -> Vc<OptionModule> {
+    fn get_inner_asset(
+        self: Vc<Self>,
+        request: Vc<Request>,
+    ) -> Vc<Option<ResolvedVc<Box<dyn Module>>>> {
         self.loader_as_resolve_origin().get_inner_asset(request)
     }
 }
@@ -28,7 +28,10 @@ use ecmascript::{
     EcmascriptModuleAsset, EcmascriptModuleAssetType, TreeShakingMode,
 };
 use graph::{aggregate, AggregatedGraph, AggregatedGraphNodeContent};
-use module_options::{ModuleOptions, ModuleOptionsContext, ModuleRuleEffect, ModuleType};
+use module_options::{
+    loader_issuer_matches, loader_rule_condition, ModuleOptions, ModuleOptionsContext,
+    ModuleRuleEffect, ModuleType, WebpackLoadersOptions,
+};
 use tracing::Instrument;
 use turbo_tasks::{Completion, RcStr, ResolvedVc, Value, ValueToString, Vc};
 use turbo_tasks_fs::{glob::Glob, FileSystemPath};
@@ -54,7 +57,13 @@ use turbopack_core::{
 pub use turbopack_css as css;
 pub use turbopack_ecmascript as ecmascript;
 use turbopack_ecmascript::{
-    references::external_module::{CachedExternalModule, CachedExternalType},
+    references::{
+        external_module::{CachedExternalModule, CachedExternalType},
+        module_federation::{
+            module_federation_remote_request, module_federation_shared_request,
+            ModuleFederationConsumeSharedModule, ModuleFederationRemoteModule,
+        },
+    },
     tree_shake::asset::EcmascriptModulePartAsset,
 };
 use turbopack_json::JsonModuleAsset;
@@ -319,6 +328,9 @@ pub struct ModuleAssetContext {
     pub resolve_options_context: Vc<ResolveOptionsContext>,
     pub layer: Vc<RcStr>,
     transition: Option<ResolvedVc<Box<dyn Transition>>>,
+    /// The context without the webpack loader rules that only apply for some
+    /// issuers, when this context applies them.
+    issuer_base: Option<ResolvedVc<ModuleAssetContext>>,
 }
 
 #[turbo_tasks::value_impl]
@@ -338,6 +350,7 @@ impl ModuleAssetContext {
             resolve_options_context,
             transition: None,
             layer,
+            issuer_base: None,
         })
     }
 
@@ -357,6 +370,7 @@ impl ModuleAssetContext {
             resolve_options_context,
             layer,
             transition: Some(transition),
+            issuer_base: None,
         })
     }
 
@@ -419,6 +433,125 @@ impl ModuleAssetContext {
             },
         )
     }
+
+    /// Returns true when one of the webpack loader rules only applies for some
+    /// issuers.
+    #[turbo_tasks::function]
+    async fn has_issuer_rules(&self) -> Result<Vc<bool>> {
+        let Some(webpack_loaders_options) =
+            self.module_options_context.await?.enable_webpack_loaders
+        else {
+            return Ok(Vc::cell(false));
+        };
+        Ok(Vc::cell(
+            webpack_loaders_options
+                .await?
+                .rules
+                .await?
+                .values()
+                .flatten()
+                .any(|rule| rule.issuer.is_some()),
+        ))
+    }
+
+    /// Returns the context that applies the webpack loader rules whose
+    /// `issuer` matches `origin_path`, i.e. the rules for modules imported by
+    /// `origin_path`.
+    #[turbo_tasks::function]
+    async fn issuer_context(self: Vc<Self>, origin_path: Vc<FileSystemPath>) -> Result<Vc<Self>> {
+        let base = match self.await?.issuer_base {
+            Some(base) => *base,
+            None => self,
+        };
+        let module_options_context = base.await?.module_options_context.await?;
+        let (Some(webpack_loaders_options), Some(execution_context)) = (
+            module_options_context.enable_webpack_loaders,
+            module_options_context.execution_context,
+        ) else {
+            return Ok(base);
+        };
+        let project_path = execution_context.project_path();
+        let mut active_issuers = Vec::new();
+        for rule in webpack_loaders_options
+            .await?
+            .rules
+            .await?
+            .values()
+            .flatten()
+        {
+            let Some(issuer) = &rule.issuer else {
+                continue;
+            };
+            if !active_issuers.contains(issuer)
+                && loader_issuer_matches(issuer, origin_path, project_path).await?
+            {
+                active_issuers.push(issuer.clone());
+            }
+        }
+        Ok(if active_issuers.is_empty() {
+            base
+        } else {
+            base.with_active_issuers(active_issuers)
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn with_active_issuers(self: Vc<Self>, active_issuers: Vec<RcStr>) -> Result<Vc<Self>> {
+        let this = self.await?;
+        let module_options_context = this.module_options_context.await?;
+        let Some(webpack_loaders_options) = module_options_context.enable_webpack_loaders else {
+            return Ok(self);
+        };
+        let webpack_loaders_options = WebpackLoadersOptions {
+            active_issuers,
+            ..(*webpack_loaders_options.await?).clone()
+        };
+        let module_options_context = ModuleOptionsContext {
+            enable_webpack_loaders: Some(webpack_loaders_options.cell()),
+            ..(*module_options_context).clone()
+        };
+        Ok(Self::cell(ModuleAssetContext {
+            transitions: this.transitions,
+            compile_time_info: this.compile_time_info,
+            module_options_context: module_options_context.cell(),
+            resolve_options_context: this.resolve_options_context,
+            layer: this.layer,
+            transition: this.transition,
+            issuer_base: Some(self.to_resolved().await?),
+        }))
+    }
+
+    /// Returns true when one of the active webpack loader rules with an
+    /// `issuer` applies to `source`.
+    #[turbo_tasks::function]
+    async fn applies_issuer_rules(&self, source: Vc<Box<dyn Source>>) -> Result<Vc<bool>> {
+        let module_options_context = self.module_options_context.await?;
+        let (Some(webpack_loaders_options), Some(execution_context)) = (
+            module_options_context.enable_webpack_loaders,
+            module_options_context.execution_context,
+        ) else {
+            return Ok(Vc::cell(false));
+        };
+        let webpack_loaders_options = webpack_loaders_options.await?;
+        let project_path = execution_context.project_path();
+        let path = source.ident().path().await?;
+        for (glob, items) in webpack_loaders_options.rules.await?.iter() {
+            for rule in items {
+                let Some(issuer) = &rule.issuer else {
+                    continue;
+                };
+                if webpack_loaders_options.active_issuers.contains(issuer)
+                    && loader_rule_condition(glob, rule, project_path)
+                        .await?
+                        .matches(source, &path, &ReferenceType::Undefined)
+                        .await?
+                {
+                    return Ok(Vc::cell(true));
+                }
+            }
+        }
+        Ok(Vc::cell(false))
+    }
 }
 
 impl ModuleAssetContext {
@@ -647,6 +780,48 @@ impl AssetContext for ModuleAssetContext {
     ) -> Result<Vc<ModuleResolveResult>> {
         let context_path = origin_path.parent().resolve().await?;
 
+        if let Some(module_federation) = self
+            .await?
+            .module_options_context
+            .await?
+            .ecmascript
+            .enable_module_federation
+        {
+            if let Some((remote_name, exposed)) =
+                module_federation_remote_request(request, module_federation).await?
+            {
+                let module = ModuleFederationRemoteModule::new(
+                    Vc::upcast(PlainResolveOrigin::new(Vc::upcast(self), origin_path)),
+                    remote_name,
+                    exposed,
+                    module_federation,
+                )
+                .to_resolved()
+                .await?;
+                return Ok(ModuleResolveResult::module(ResolvedVc::upcast(module)).cell());
+            }
+            // Only imports and requires consume shared packages. The local
+            // package is provided to the share scope with another reference
+            // type.
+            if matches!(
+                &*reference_type,
+                ReferenceType::EcmaScriptModules(_) | ReferenceType::CommonJs(_)
+            ) {
+                if let Some(shared) =
+                    module_federation_shared_request(request, module_federation).await?
+                {
+                    let module = ModuleFederationConsumeSharedModule::new(
+                        Vc::upcast(PlainResolveOrigin::new(Vc::upcast(self), origin_path)),
+                        shared,
+                        module_federation,
+                    )
+                    .to_resolved()
+                    .await?;
+                    return Ok(ModuleResolveResult::module(ResolvedVc::upcast(module)).cell());
+                }
+            }
+        }
+
         let result = resolve(
             context_path,
             reference_type.clone(),
@@ -654,7 +829,12 @@ impl AssetContext for ModuleAssetContext {
             resolve_options,
         );
 
-        let mut result = self.process_resolve_result(result.resolve().await?, reference_type);
+        let context = if *self.has_issuer_rules().await? {
+            self.issuer_context(origin_path)
+        } else {
+            self
+        };
+        let mut result = context.process_resolve_result(result.resolve().await?, reference_type);
 
         if *self.is_types_resolving_enabled().await? {
             let types_result = type_resolve(
@@ -712,6 +892,13 @@ impl AssetContext for ModuleAssetContext {
         reference_type: Value<ReferenceType>,
     ) -> Result<Vc<ProcessResult>> {
         let this = self.await?;
+        if let Some(issuer_base) = this.issuer_base {
+            // Modules that no issuer rule applies to are shared with the base
+            // context, so they aren't duplicated for each issuer.
+            if !*self.applies_issuer_rules(asset).await? {
+                return Ok(issuer_base.process(asset, reference_type));
+            }
+        }
         if let Some(transition) = this.transition {
             Ok(transition.process(asset, self, reference_type))
         } else {
@@ -13,6 +13,7 @@ pub use rule_condition::*;
 use turbo_tasks::{RcStr, ResolvedVc, Vc};
 use turbo_tasks_fs::{glob::Glob, FileSystemPath};
 use turbopack_core::{
+    file_source::FileSource,
     reference_type::{CssReferenceSubType, ReferenceType, UrlReferenceSubType},
     resolve::options::{ImportMap, ImportMapping},
 };
@@ -52,6 +53,57 @@ async fn package_import_map_from_context(
     import_map.cell()
 }
 
+/// The condition of a glob of a webpack loader rule. Globs without a `/` match
+/// the file name, others the path relative to the project.
+async fn loader_glob_condition(
+    glob: &RcStr,
+    project_path: Vc<FileSystemPath>,
+) -> Result<RuleCondition> {
+    Ok(if !glob.contains('/') {
+        RuleCondition::ResourceBasePathGlob(Glob::new(glob.clone()).await?)
+    } else {
+        RuleCondition::ResourcePathGlob {
+            base: project_path.await?,
+            glob: Glob::new(glob.clone()).await?,
+        }
+    })
+}
+
+/// The condition of the files that a webpack loader rule for `glob` applies
+/// to, without the condition on the issuer.
+pub(crate) async fn loader_rule_condition(
+    glob: &RcStr,
+    rule: &LoaderRuleItem,
+    project_path: Vc<FileSystemPath>,
+) -> Result<RuleCondition> {
+    let condition = loader_glob_condition(glob, project_path).await?;
+    Ok(if let Some(exclude) = &rule.exclude {
+        RuleCondition::All(vec![
+            condition,
+            RuleCondition::not(loader_glob_condition(exclude, project_path).await?),
+        ])
+    } else {
+        condition
+    })
+}
+
+/// Returns true when `path` matches the `issuer` glob of a webpack loader
+/// rule.
+pub(crate) async fn loader_issuer_matches(
+    issuer: &RcStr,
+    path: Vc<FileSystemPath>,
+    project_path: Vc<FileSystemPath>,
+) -> Result<bool> {
+    let condition = loader_glob_condition(issuer, project_path).await?;
+    condition
+        .matches(
+            Vc::upcast(FileSource::new(path)),
+            &*path.await?,
+            &ReferenceType::Undefined,
+        )
+        .await
+}
+
 #[turbo_tasks::value(cell = "new", eq = "manual")]
 pub struct ModuleOptions {
     pub rules: Vec<ModuleRule>,
@@ -73,7 +125,10 @@ impl ModuleOptions {
                     ref enable_typescript_transform,
                     ref enable_decorators,
                     ignore_dynamic_requests,
+                    exclude_optional_requires,
                     import_externals,
+                    extract_styled_jsx,
+                    report_slow_modules,
                     esm_url_rewrite_behavior,
                     ref enable_typeof_window_inlining,
                     ..
@@ -134,6 +189,9 @@ impl ModuleOptions {
             url_rewrite_behavior: esm_url_rewrite_behavior,
             import_externals,
             ignore_dynamic_requests,
+            exclude_optional_requires,
+            extract_styled_jsx,
+            report_slow_modules,
             refresh,
             ..Default::default()
         };
@@ -158,6 +216,8 @@ impl ModuleOptions {
             let options = options.await?;
             Some(EcmascriptInputTransform::TypeScript {
                 use_define_for_class_fields: options.use_define_for_class_fields,
+                verbatim_module_syntax: options.verbatim_module_syntax,
+                preserve_unused_imports: options.preserve_unused_imports,
             })
         } else {
             None
@@ -548,17 +608,24 @@ impl ModuleOptions {
             } else {
                 package_import_map_from_context("loader-runner".into(), path)
             };
-            for (glob, rule) in webpack_loaders_options.rules.await?.iter() {
+            let project_path = execution_context.project_path();
+            let webpack_rules = webpack_loaders_options.rules.await?;
+            let mut loader_rules = webpack_rules
+                .iter()
+                .flat_map(|(glob, items)| items.iter().map(move |rule| (glob, rule)))
+                .filter(|(_, rule)| {
+                    rule.issuer.as_ref().map_or(true, |issuer| {
+                        webpack_loaders_options.active_issuers.contains(issuer)
+                    })
+                })
+                .collect::<Vec<_>>();
+            // The sort is stable, so rules of the same stage keep the order of the
+            // configuration.
+            loader_rules.sort_by_key(|(_, rule)| rule.enforce);
+            for (glob, rule) in loader_rules {
                 rules.push(ModuleRule::new(
                     RuleCondition::All(vec![
-                        if !glob.contains('/') {
-                            RuleCondition::ResourceBasePathGlob(Glob::new(glob.clone()).await?)
-                        } else {
-                            RuleCondition::ResourcePathGlob {
-                                base: execution_context.project_path().await?,
-                                glob: Glob::new(glob.clone()).await?,
-                            }
-                        },
+                        loader_rule_condition(glob, rule, project_path).await?,
                         RuleCondition::not(RuleCondition::ResourceIsVirtualSource),
                     ]),
                     vec![ModuleRuleEffect::SourceTransforms(Vc::cell(vec![
@@ -573,6 +640,9 @@ impl ModuleOptions {
                             *execution_context,
                             rule.loaders,
                             rule.rename_as.clone(),
+                            rule.issuer
+                                .as_ref()
+                                .map(|issuer| format!("issuer {issuer}").into()),
                             resolve_options_context,
                         )),
                     ]))],
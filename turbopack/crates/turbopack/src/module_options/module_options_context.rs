@@ -4,7 +4,10 @@ use turbopack_core::{
     chunk::MinifyType, condition::ContextCondition, environment::Environment,
     resolve::options::ImportMapping,
 };
-use turbopack_ecmascript::{references::esm::UrlRewriteBehavior, TreeShakingMode};
+use turbopack_ecmascript::{
+    references::{esm::UrlRewriteBehavior, module_federation::ModuleFederationOptions},
+    TreeShakingMode,
+};
 pub use turbopack_mdx::MdxTransformOptions;
 use turbopack_node::{
     execution_context::ExecutionContext,
@@ -13,15 +16,63 @@ use turbopack_node::{
 
 use super::ModuleRule;
 
+/// When a loader rule runs relative to the other rules that apply to the same
+/// file.
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Debug,
+    TraceRawVcs,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum LoaderEnforce {
+    /// Runs before all other rules.
+    Pre,
+    /// Runs in the order of the configuration.
+    #[default]
+    Normal,
+    /// Runs after all other rules.
+    Post,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, TraceRawVcs, Serialize, Deserialize)]
 pub struct LoaderRuleItem {
     pub loaders: Vc<WebpackLoaderItems>,
     pub rename_as: Option<RcStr>,
+    pub enforce: LoaderEnforce,
+    /// A glob of files that the rule doesn't apply to, matched like the glob
+    /// of the rule.
+    pub exclude: Option<RcStr>,
+    /// A glob that the module importing the file has to match for the rule to
+    /// apply, matched like the glob of the rule.
+    pub issuer: Option<RcStr>,
+}
+
+impl LoaderRuleItem {
+    pub fn new(loaders: Vc<WebpackLoaderItems>, rename_as: Option<RcStr>) -> Self {
+        LoaderRuleItem {
+            loaders,
+            rename_as,
+            enforce: LoaderEnforce::Normal,
+            exclude: None,
+            issuer: None,
+        }
+    }
 }
 
+/// The loader rules for each glob. The rules of a glob form a pipeline that
+/// runs in order.
 #[derive(Default)]
 #[turbo_tasks::value(transparent)]
-pub struct WebpackRules(FxIndexMap<RcStr, LoaderRuleItem>);
+pub struct WebpackRules(FxIndexMap<RcStr, Vec<LoaderRuleItem>>);
 
 #[derive(Default)]
 #[turbo_tasks::value(transparent)]
@@ -32,6 +83,9 @@ pub struct OptionWebpackRules(Option<Vc<WebpackRules>>);
 pub struct WebpackLoadersOptions {
     pub rules: Vc<WebpackRules>,
     pub loader_runner_package: Option<Vc<ImportMapping>>,
+    /// The `issuer` globs that match the importing module. Rules with an
+    /// `issuer` only apply when it's included.
+    pub active_issuers: Vec<RcStr>,
 }
 
 /// The kind of decorators transform to use.
@@ -85,6 +139,13 @@ impl ValueDefault for DecoratorsOptions {
 #[derive(Default, Clone, Debug)]
 pub struct TypescriptTransformOptions {
     pub use_define_for_class_fields: bool,
+    /// Only elide imports and exports explicitly marked with `type`.
+    /// (https://www.typescriptlang.org/tsconfig#verbatimModuleSyntax)
+    pub verbatim_module_syntax: bool,
+    /// Keep imports whose bindings are only used as types as side effect
+    /// imports instead of removing them.
+    /// (https://www.typescriptlang.org/tsconfig#importsNotUsedAsValues)
+    pub preserve_unused_imports: bool,
 }
 
 #[turbo_tasks::value_impl]
@@ -151,6 +212,18 @@ pub struct EcmascriptOptionsContext {
     /// If false, they will reference the whole directory. If true, they won't
     /// reference anything and lead to an runtime error instead.
     pub ignore_dynamic_requests: bool,
+    /// Packages that aren't bundled when they are required in a `try` block,
+    /// see [turbopack_ecmascript::EcmascriptOptions::exclude_optional_requires].
+    pub exclude_optional_requires: Option<ResolvedVc<Vec<RcStr>>>,
+    /// Resolve requests to Module Federation remotes to modules that load the
+    /// remote container at runtime.
+    pub enable_module_federation: Option<Vc<ModuleFederationOptions>>,
+    /// Moves styled-jsx styles without dynamic interpolations and static `css`
+    /// props to CSS chunks. Requires the styled-jsx transform to run before.
+    pub extract_styled_jsx: bool,
+    /// Reports very large or slow modules as hints, see
+    /// [turbopack_ecmascript::EcmascriptOptions::report_slow_modules].
+    pub report_slow_modules: bool,
 
     pub placeholder_for_future_extensions: (),
 }